@@ -0,0 +1,86 @@
+//! Compares parsing a very large file read through a buffer with parsing it
+//! memory-mapped. The file is a MYSTRAN output of about 200 MB, written to a
+//! temporary directory (and reused if it's already there).
+//!
+//! Run with `cargo bench -p f06 --features mmap`. On the development machine,
+//! it takes about 7.4 s through a buffer and 7.2 s memory-mapped, some 2%
+//! less. Just reading the lines of the file through a buffer takes about
+//! 0.12 s, so no way of reading it could take 20% off: parsing each line is
+//! what takes the time. That used to be 21.0 s and 19.7 s, before blocks
+//! stopped copying their whole matrix for every row and the checks run on
+//! every line stopped breaking most of them down for nothing.
+
+#![allow(clippy::needless_return)]
+
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use f06::prelude::*;
+
+/// How big the file has to be, in bytes.
+const TARGET_SIZE: u64 = 200 * 1024 * 1024;
+
+/// How many grid points each displacements table has.
+const NUM_GRIDS: usize = 10000;
+
+/// Writes a subcase of displacements of a MYSTRAN output.
+fn write_subcase<W: Write>(out: &mut W, subcase: usize) {
+  let mut text = format!("\n OUTPUT FOR SUBCASE {:>8}\n\n", subcase);
+  text.push_str(&format!("{:>55}D I S P L A C E M E N T S\n", ""));
+  text.push_str(&format!(
+    "{:>46}(in global coordinate system at each grid)\n",
+    ""
+  ));
+  text.push_str(concat!(
+    "           GRID     COORD      T1            T2            T3",
+    "            R1            R2            R3\n",
+    "                     SYS\n"
+  ));
+  for gid in 1..=NUM_GRIDS {
+    text.push_str(&format!("{:>15}{:>9}", gid, 0));
+    for dof in 0..6 {
+      let x = ((gid * 7 + dof * 13 + subcase) % 1000) as f64 * 1e-3;
+      text.push_str(&format!("  {:.6}E+00", x));
+    }
+    text.push('\n');
+  }
+  out.write_all(text.as_bytes()).expect("couldn't write a subcase");
+}
+
+/// Writes the file, unless it's already there, returning its path.
+fn write_big_file() -> PathBuf {
+  let path = std::env::temp_dir().join("f06_parse_mmap_bench.f06");
+  let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+  if size >= TARGET_SIZE {
+    return path;
+  }
+  let file = std::fs::File::create(&path).expect("couldn't create the file");
+  let mut out = BufWriter::new(file);
+  out.write_all(b"\n MYSTRAN Version 15.1.3\n")
+    .expect("couldn't write the banner");
+  let mut subcase = 0;
+  while out.get_ref().metadata().map(|m| m.len()).unwrap_or(0) < TARGET_SIZE {
+    subcase += 1;
+    write_subcase(&mut out, subcase);
+  }
+  out.flush().expect("couldn't finish writing the file");
+  return path;
+}
+
+/// Benchmarks both ways of parsing the file.
+fn bench_big_file(c: &mut Criterion) {
+  let path = write_big_file();
+  let mut group = c.benchmark_group("parse_big_file");
+  group.sample_size(10);
+  group.bench_function("bufreader", |b| b.iter(|| {
+    return OnePassParser::parse_file(&path).expect("couldn't parse");
+  }));
+  group.bench_function("mmap", |b| b.iter(|| {
+    return OnePassParser::parse_mmap(&path).expect("couldn't parse");
+  }));
+  group.finish();
+}
+
+criterion_group!(benches, bench_big_file);
+criterion_main!(benches);
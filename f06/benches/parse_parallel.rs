@@ -0,0 +1,82 @@
+//! Compares parsing a batch of files one after the other with parsing them in
+//! parallel. The batch is eight MYSTRAN outputs of about 10k lines each,
+//! written to a temporary directory.
+//!
+//! Run with `cargo bench -p f06 --features rayon`.
+
+#![allow(clippy::needless_return)]
+
+use std::path::PathBuf;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use f06::prelude::*;
+
+/// How many files are in the batch.
+const NUM_FILES: usize = 8;
+
+/// How many grid points each displacements table has.
+const NUM_GRIDS: usize = 2500;
+
+/// How many subcases each file has, each with a displacements table.
+const NUM_SUBCASES: usize = 4;
+
+/// Writes a MYSTRAN output with some subcases of displacements, about
+/// `NUM_GRIDS * NUM_SUBCASES` lines long.
+fn mystran_output(seed: usize) -> String {
+  let mut out = String::from("\n MYSTRAN Version 15.1.3\n");
+  for subcase in 1..=NUM_SUBCASES {
+    out.push_str(&format!("\n OUTPUT FOR SUBCASE {:>8}\n\n", subcase));
+    out.push_str(&format!("{:>55}D I S P L A C E M E N T S\n", ""));
+    out.push_str(&format!(
+      "{:>46}(in global coordinate system at each grid)\n",
+      ""
+    ));
+    out.push_str(concat!(
+      "           GRID     COORD      T1            T2            T3",
+      "            R1            R2            R3\n",
+      "                     SYS\n"
+    ));
+    for gid in 1..=NUM_GRIDS {
+      out.push_str(&format!("{:>15}{:>9}", gid, 0));
+      for dof in 0..6 {
+        let x = ((seed + gid * 7 + dof * 13 + subcase) % 1000) as f64 * 1e-3;
+        out.push_str(&format!("  {:.6}E+00", x));
+      }
+      out.push('\n');
+    }
+  }
+  return out;
+}
+
+/// Writes the batch of files, returning their paths.
+fn write_batch() -> Vec<PathBuf> {
+  let dir = std::env::temp_dir().join("f06_parse_parallel_bench");
+  std::fs::create_dir_all(&dir).expect("couldn't create the batch directory");
+  return (0..NUM_FILES)
+    .map(|i| {
+      let path = dir.join(format!("run{}.f06", i));
+      std::fs::write(&path, mystran_output(i))
+        .expect("couldn't write a file of the batch");
+      return path;
+    })
+    .collect();
+}
+
+/// Benchmarks both ways of parsing the batch.
+fn bench_batch(c: &mut Criterion) {
+  let paths = write_batch();
+  let mut group = c.benchmark_group("parse_batch");
+  group.sample_size(10);
+  group.bench_function("sequential", |b| b.iter(|| {
+    return paths.iter()
+      .map(OnePassParser::parse_file)
+      .collect::<Vec<_>>();
+  }));
+  group.bench_function("parallel", |b| b.iter(|| {
+    return parse_files_parallel(&paths);
+  }));
+  group.finish();
+}
+
+criterion_group!(benches, bench_batch);
+criterion_main!(benches);
@@ -0,0 +1,136 @@
+//! This module implements parsing from tokio's async readers, e.g. network
+//! streams, as a stream of blocks. Lines go through the same parser as the
+//! synchronous path, via `OnePassParser::feed_sized`.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::AsyncBufRead;
+use tokio_stream::Stream;
+
+use crate::prelude::*;
+use crate::util::decode_raw_line;
+
+impl OnePassParser {
+  /// Parses from an async reader, yielding the blocks as they finish.
+  pub fn parse_async<R: AsyncBufRead + Unpin>(
+    reader: R
+  ) -> AsyncBlockStream<R> {
+    return Self::new().read_async(reader);
+  }
+
+  /// Parses from an async reader with this parser, which might have been set
+  /// up beforehand, yielding the blocks as they finish.
+  pub fn read_async<R: AsyncBufRead + Unpin>(
+    self,
+    reader: R
+  ) -> AsyncBlockStream<R> {
+    return AsyncBlockStream {
+      parser: self,
+      reader,
+      line: Vec::new(),
+      finished: VecDeque::new(),
+      done: false,
+      sniffed: false
+    };
+  }
+}
+
+/// Parses an F06 from an async reader one line at a time, yielding each block
+/// as soon as it's finished. Everything but the blocks accumulates in the
+/// file as usual, and is returned by `finish`.
+pub struct AsyncBlockStream<R: AsyncBufRead + Unpin> {
+  /// The parser, which hands the finished blocks out.
+  parser: OnePassParser,
+  /// Where the lines come from.
+  reader: R,
+  /// The bytes of the line being read.
+  line: Vec<u8>,
+  /// The finished blocks not yielded yet.
+  finished: VecDeque<FinalBlock>,
+  /// Whether the reader ran out or failed.
+  done: bool,
+  /// Whether the beginning of the input was checked for the wrong kind of
+  /// file.
+  sniffed: bool
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncBlockStream<R> {
+  /// Returns the file parsed so far, without the blocks yielded.
+  pub fn file(&self) -> &F06File {
+    return self.parser.file();
+  }
+
+  /// Finishes up and returns the file, with the blocks not yielded yet in it.
+  /// Lines not read yet are left alone.
+  pub fn finish(self) -> F06File {
+    return self.parser.finish_with(self.finished);
+  }
+
+  /// Feeds the line read into the parser.
+  fn feed_line(&mut self) -> Result<(), ParseError> {
+    let line = decode_raw_line(&self.line)?;
+    let size = self.line.len() as u64;
+    self.line.clear();
+    self.finished.extend(self.parser.feed_sized(&line, size));
+    return Ok(());
+  }
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for AsyncBlockStream<R> {
+  type Item = Result<FinalBlock, ParseError>;
+
+  fn poll_next(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>
+  ) -> Poll<Option<Self::Item>> {
+    let this = self.get_mut();
+    loop {
+      if let Some(block) = this.finished.pop_front() {
+        return Poll::Ready(Some(Ok(block)));
+      } else if this.done {
+        return Poll::Ready(None);
+      }
+      let available = match Pin::new(&mut this.reader).poll_fill_buf(cx) {
+        Poll::Ready(Ok(bytes)) => bytes,
+        Poll::Ready(Err(e)) => {
+          this.done = true;
+          return Poll::Ready(Some(Err(ParseError::Io(e))));
+        },
+        Poll::Pending => return Poll::Pending
+      };
+      if !this.sniffed {
+        this.sniffed = true;
+        if let Some(wrong) = sniff(available) {
+          this.done = true;
+          return Poll::Ready(Some(Err(ParseError::WrongFile(wrong))));
+        }
+      }
+      if available.is_empty() {
+        // the last line might not have had a line break
+        this.done = true;
+        if !this.line.is_empty() {
+          if let Err(e) = this.feed_line() {
+            return Poll::Ready(Some(Err(e)));
+          }
+        }
+        this.finished.extend(this.parser.end_of_input());
+        continue;
+      }
+      let newline = available.iter().position(|b| *b == b'\n');
+      let (taken, complete) = match newline {
+        Some(i) => (i + 1, true),
+        None => (available.len(), false)
+      };
+      this.line.extend_from_slice(&available[..taken]);
+      Pin::new(&mut this.reader).consume(taken);
+      if complete {
+        if let Err(e) = this.feed_line() {
+          this.done = true;
+          return Poll::Ready(Some(Err(e)));
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,209 @@
+//! This module implements parsing batches of files (e.g. for regression
+//! tests, or archives of runs). Each file gets its own parser, so nothing is
+//! shared between them, not even the flavour: a batch can mix solvers.
+//!
+//! With the `rayon` feature, batches can be parsed in parallel.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::prelude::*;
+
+/// A file parsed as part of a batch, along with how its flavour was resolved.
+#[derive(Debug)]
+pub struct BatchFile {
+  /// The path to the file.
+  pub path: PathBuf,
+  /// How the flavour of the file was resolved, if it was parsed (even if it
+  /// was then rejected for it).
+  pub resolution: Option<FlavourResolution>,
+  /// The parsed file, or why it couldn't be. Files rejected for their
+  /// flavour err with invalid data, wrapping a `FlavourMismatch`.
+  pub result: io::Result<F06File>
+}
+
+impl BatchFile {
+  /// Returns why the file was rejected for its flavour, if it was.
+  pub fn flavour_mismatch(&self) -> Option<&FlavourMismatch> {
+    return self.result.as_ref().err()?.get_ref()?.downcast_ref();
+  }
+
+  /// Describes the file and how its flavour was resolved, for a manifest.
+  pub fn manifest_input(&self) -> ManifestInput {
+    return ManifestInput {
+      path: self.path.display().to_string(),
+      flavour: self.resolution.as_ref().map(|res| res.flavour().spec()),
+      resolution: self.resolution.clone(),
+      error: self.result.as_ref().err().map(|e| e.to_string()),
+      fingerprint: None
+    };
+  }
+}
+
+/// Parses a file of a batch from a reader with a parser (which might have
+/// been set up beforehand), resolving its flavour according to a policy. The
+/// path is what the file is known as ("-" for standard input), and it's named
+/// after it.
+pub fn parse_flavoured<R: BufRead>(
+  mut parser: OnePassParser,
+  path: &Path,
+  reader: R,
+  policy: &FlavourPolicy
+) -> BatchFile {
+  parser.flavour_policy(policy);
+  let result = parser.read_bufread(reader).map(|mut file| {
+    file.filename = path.file_name()
+      .and_then(|s| s.to_str())
+      .map(String::from);
+    return file;
+  });
+  let resolution = result.as_ref()
+    .ok()
+    .map(|file| file.flavour_resolution.clone());
+  let result = result.and_then(|file| {
+    return match policy.check(&file.flavour_resolution) {
+      Ok(()) => Ok(file),
+      Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e))
+    };
+  });
+  return BatchFile { path: path.to_owned(), resolution, result };
+}
+
+/// Opens a file of a batch and parses it (see `parse_flavoured`).
+pub fn parse_flavoured_file(
+  parser: OnePassParser,
+  path: &Path,
+  policy: &FlavourPolicy
+) -> BatchFile {
+  return match File::open(path) {
+    Ok(file) => parse_flavoured(parser, path, BufReader::new(file), policy),
+    Err(e) => BatchFile {
+      path: path.to_owned(),
+      resolution: None,
+      result: Err(e)
+    }
+  };
+}
+
+/// Parses several files one after the other, each with a parser made by a
+/// function, resolving their flavours according to a policy. The results
+/// are in the same order as the paths.
+pub fn parse_files_flavoured<F>(
+  paths: &[PathBuf],
+  policy: &FlavourPolicy,
+  make_parser: F
+) -> Vec<BatchFile>
+where
+  F: Fn() -> OnePassParser
+{
+  return paths.iter()
+    .map(|path| parse_flavoured_file(make_parser(), path, policy))
+    .collect();
+}
+
+/// Merges files parsed on their own into one, merging their blocks where
+/// they can be (e.g. a subcase split across runs). Everything but the blocks
+/// and the messages comes from the first file that parsed, since the line
+/// numbers of the others don't mean anything in it. For the same reason, the
+/// others' blocks lose their line and byte ranges, are numbered anew after
+/// the first file's, and their warnings, fatal errors and diagnostics go in
+/// `merged_warnings`, `merged_fatal_errors` and `merged_diagnostics`, under
+/// the name of their file (or its position, if it has none). Fails only if
+/// none of them parsed; then, with the first error.
+pub fn merge_parsed<I>(results: I) -> io::Result<F06File>
+where
+  I: IntoIterator<Item = io::Result<F06File>>
+{
+  let mut merged: Option<F06File> = None;
+  let mut first_error: Option<io::Error> = None;
+  for (i, result) in results.into_iter().enumerate() {
+    match (result, merged.as_mut()) {
+      (Ok(file), None) => merged = Some(file),
+      (Ok(file), Some(into)) => {
+        let name = file.filename.clone()
+          .unwrap_or_else(|| format!("#{}", i + 1));
+        if !file.warnings.is_empty() {
+          into.merged_warnings.entry(name.clone())
+            .or_default()
+            .extend(file.warnings);
+        }
+        for (other, warnings) in file.merged_warnings {
+          into.merged_warnings.entry(other).or_default().extend(warnings);
+        }
+        if !file.fatal_errors.is_empty() {
+          into.merged_fatal_errors.entry(name.clone())
+            .or_default()
+            .extend(file.fatal_errors);
+        }
+        for (other, fatals) in file.merged_fatal_errors {
+          into.merged_fatal_errors.entry(other).or_default().extend(fatals);
+        }
+        if !file.diagnostics.is_empty() {
+          into.merged_diagnostics.insert(name, file.diagnostics);
+        }
+        into.merged_diagnostics.extend(file.merged_diagnostics);
+        let first_uid = into.all_blocks(false)
+          .filter_map(|b| b.uid)
+          .max()
+          .map_or(0, |uid| uid + 1);
+        into.filename = None;
+        let blocks = file.blocks.into_values().flatten();
+        for (uid, mut block) in (first_uid..).zip(blocks) {
+          block.uid = Some(uid);
+          block.line_range = None;
+          block.byte_range = None;
+          block.row_lines.clear();
+          into.insert_block(block);
+        }
+      },
+      (Err(e), _) => {
+        first_error.get_or_insert(e);
+      }
+    }
+  }
+  return match (merged, first_error) {
+    (Some(mut file), _) => {
+      file.merge_blocks(true);
+      Ok(file)
+    },
+    (None, Some(e)) => Err(e),
+    (None, None) => Ok(F06File::new())
+  };
+}
+
+/// Parses several files in parallel, each on its own. The results are in the
+/// same order as the paths.
+#[cfg(feature = "rayon")]
+pub fn parse_files_parallel(paths: &[PathBuf]) -> Vec<io::Result<F06File>> {
+  return paths.par_iter()
+    .map(OnePassParser::parse_file)
+    .collect();
+}
+
+/// Parses several files in parallel, each with a parser made by a function,
+/// resolving their flavours according to a policy. The results are in the
+/// same order as the paths.
+#[cfg(feature = "rayon")]
+pub fn parse_files_parallel_flavoured<F>(
+  paths: &[PathBuf],
+  policy: &FlavourPolicy,
+  make_parser: F
+) -> Vec<BatchFile>
+where
+  F: Fn() -> OnePassParser + Sync
+{
+  return paths.par_iter()
+    .map(|path| parse_flavoured_file(make_parser(), path, policy))
+    .collect();
+}
+
+/// Parses several files in parallel and merges the ones that parsed into one
+/// (see `merge_parsed`).
+#[cfg(feature = "rayon")]
+pub fn parse_files_parallel_merge(paths: &[PathBuf]) -> io::Result<F06File> {
+  return merge_parsed(parse_files_parallel(paths));
+}
@@ -1,10 +1,15 @@
 //! This submodule defines the blocks that make up an F06 file.
 
+#[cfg(feature = "parser")]
 pub(crate) mod decoders;
 pub mod compare;
+pub mod dimensions;
 pub mod indexing;
+pub mod table;
 pub mod types;
+pub mod visitor;
 
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Display;
 use std::mem::discriminant;
@@ -16,6 +21,8 @@ use serde::{Serialize, Deserialize};
 
 use indexing::{IndexType, NasIndex};
 use crate::blocks::types::BlockType;
+use crate::geometry::SIXDOF;
+#[cfg(feature = "parser")]
 use crate::flavour::Flavour;
 use crate::prelude::BlockRef;
 
@@ -33,7 +40,7 @@ pub type DynMatx<S, const W: usize> = Matrix<
 >;
 
 /// Full-dynamic matrix used in finalised blocks.
-#[derive(Clone, Debug, Serialize, Deserialize, derive_more::From)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, derive_more::From)]
 pub enum FinalDMat {
   /// Matrix with real values.
   Reals(DMatrix<f64>),
@@ -62,6 +69,34 @@ impl FinalDMat {
     };
   }
 
+  /// Reorders the columns, so that the column at each position is the one
+  /// that was at the position `order` holds for it. Panics if `order` isn't a
+  /// permutation of the columns.
+  pub fn permute_columns(&mut self, order: &[usize]) {
+    let is_permutation = order.len() == self.ncols()
+      && order.iter().copied().collect::<BTreeSet<_>>().len() == order.len()
+      && order.iter().all(|c| *c < order.len());
+    assert!(is_permutation, "not a column permutation: {:?}", order);
+    /// Makes a matrix with the columns of another, reordered.
+    fn permuted<S: NasScalar>(m: &DMatrix<S>, order: &[usize]) -> DMatrix<S> {
+      return DMatrix::from_fn(m.nrows(), m.ncols(), |r, c| m[(r, order[c])]);
+    }
+    match self {
+      FinalDMat::Reals(m) => *m = permuted(m, order),
+      FinalDMat::Integers(m) => *m = permuted(m, order),
+      FinalDMat::Naturals(m) => *m = permuted(m, order)
+    };
+  }
+
+  /// Removes some columns, given by position, shifting the ones after them.
+  pub(crate) fn remove_columns(&mut self, at: &[usize]) {
+    match self {
+      FinalDMat::Reals(m) => *m = m.clone().remove_columns_at(at),
+      FinalDMat::Integers(m) => *m = m.clone().remove_columns_at(at),
+      FinalDMat::Naturals(m) => *m = m.clone().remove_columns_at(at)
+    };
+  }
+
   /// Number of rows.
   pub fn nrows(&self) -> usize {
     return match self {
@@ -129,8 +164,12 @@ pub(crate) struct RowBlock<
   row_indexes: BTreeMap<R, usize>,
   /// The column indexes.
   col_indexes: BTreeMap<C, usize>,
-  /// The data within.
-  data: Option<DynMatx<S, W>>
+  /// The data within, a row at a time. They only go in a matrix when the
+  /// block is finalised, since growing a matrix a row at a time copies all
+  /// of it each time.
+  rows: Vec<[S; W]>,
+  /// The line each row was first inserted from, where known.
+  row_lines: BTreeMap<R, usize>
 }
 
 impl<S, R, C, const W: usize> RowBlock<S, R, C, W>
@@ -138,31 +177,38 @@ impl<S, R, C, const W: usize> RowBlock<S, R, C, W>
   /// Creates a new RowBlock with a set width and a pre-allocated size.
   pub(crate) fn new(col_indexes: BTreeMap<C, usize>) -> Self {
     let row_indexes: BTreeMap<R, usize> = BTreeMap::new();
-    return Self { row_indexes, col_indexes, data: None }
+    return Self {
+      row_indexes,
+      col_indexes,
+      rows: Vec::new(),
+      row_lines: BTreeMap::new()
+    };
   }
 
-  /// Inserts a line raw into the data matrix, without fixing indexes. Returns
-  /// the row within the underlying matrixes this was put in.
-  pub(crate) fn insert_raw(&mut self, row_index: R, row: &[S; W]) -> usize {
+  /// Inserts a line raw into the data matrix, without fixing indexes, as read
+  /// from a line. Returns the row within the underlying matrixes this was put
+  /// in.
+  pub(crate) fn insert_raw(
+    &mut self,
+    row_index: R,
+    row: &[S; W],
+    line_no: usize
+  ) -> usize {
     if self.row_indexes.contains_key(&row_index) {
       warn!("tried to insert the same line twice! index: {:?}", row_index);
     }
-    let irow: usize;
-    if let Some(mut mat) = self.data.take() {
-      if let Some(fnd) = self.row_indexes.get(&row_index) {
-        irow = *fnd;
-      } else {
-        irow = mat.nrows();
-        mat = mat.insert_row(irow, S::zero());
+    let irow = match self.row_indexes.get(&row_index) {
+      Some(fnd) => {
+        self.rows[*fnd] = *row;
+        *fnd
+      },
+      None => {
+        self.rows.push(*row);
+        self.rows.len() - 1
       }
-      mat.row_mut(irow).copy_from_slice(row);
-      self.data = Some(mat);
-    } else {
-      irow = 0;
-      let mat = DynMatx::<S, W>::from_row_slice(row);
-      self.data = Some(mat);
-    }
+    };
     self.row_indexes.insert(row_index, irow);
+    self.row_lines.entry(row_index).or_insert(line_no);
     return irow;
   }
 
@@ -183,14 +229,32 @@ impl<S, R, C, const W: usize> RowBlock<S, R, C, W>
   pub(crate) fn insert_row(
     &mut self,
     row_index: R,
-    data: &BTreeMap<C, S>
+    data: &BTreeMap<C, S>,
+    line_no: usize
   ) -> usize {
     let mut raw_data = [S::zero(); W];
     data.iter().for_each(|(c, s)| {
       let ri = self.col_indexes.get(c).expect("bad col index");
       raw_data[*ri] = *s;
     });
-    return self.insert_raw(row_index, &raw_data);
+    return self.insert_raw(row_index, &raw_data, line_no);
+  }
+
+  /// Overwrites some values of a row inserted earlier, by column. Returns
+  /// false, changing nothing, if there's no such row.
+  pub(crate) fn update_row(
+    &mut self,
+    row_index: R,
+    data: &BTreeMap<C, S>
+  ) -> bool {
+    let Some(irow) = self.row_indexes.get(&row_index) else {
+      return false;
+    };
+    for (c, s) in data {
+      let ci = self.col_indexes.get(c).expect("bad col index");
+      self.rows[*irow][*ci] = *s;
+    }
+    return true;
   }
 }
 
@@ -210,24 +274,36 @@ impl<S, R, C, const W: usize> RowBlock<S, R, C, W>
     let col_indexes: BTreeMap<NasIndex, usize> = self.col_indexes.into_iter()
       .map(|(k, v)| (k.into(), v))
       .collect();
-    let data: Option<FinalDMat> = self.data.map(|m| {
-      let nr = m.nrows();
-      let nc = m.ncols();
-      return FinalDMat::from(m.reshape_generic(Dyn(nr), Dyn(nc)));
+    let row_lines: BTreeMap<NasIndex, usize> = self.row_lines.into_iter()
+      .map(|(k, v)| (k.into(), v))
+      .collect();
+    let data: Option<FinalDMat> = (!self.rows.is_empty()).then(|| {
+      let nr = self.rows.len();
+      let values = self.rows.into_iter().flatten();
+      return FinalDMat::from(DMatrix::from_row_iterator(nr, W, values));
     });
     return FinalBlock {
       block_type,
       line_range,
+      byte_range: None,
       subcase,
       row_indexes,
       col_indexes,
-      data
+      data,
+      print_precision: None,
+      metadata: BTreeMap::new(),
+      row_lines,
+      uid: None,
+      provenance: MergeProvenance::default(),
+      line_counts: LineCounts::default(),
+      step: None
     };
   }
 }
 
 /// Contains the result of an attempt to merge two blocks.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
 pub enum MergeResult {
   /// Merge was successful.
   Success {
@@ -261,14 +337,196 @@ pub enum MergeIncompatible {
   /// Matrices did not have the same type of scalar.
   ScalarMismatch,
   /// Subcases don't match.
-  SubcaseMismatch
+  SubcaseMismatch,
+  /// The blocks are tagged with different superelements.
+  SuperelementMismatch,
+  /// The blocks are for different modes.
+  ModeMismatch,
+  /// The blocks are for different time steps or frequencies.
+  StepMismatch,
+  /// A block's column indexes don't map one-to-one onto its data's columns.
+  BadColumnPositions {
+    /// Whether it's the secondary block (otherwise, it's the primary).
+    secondary: bool,
+    /// Positions more than one column index maps to.
+    duplicated: BTreeSet<usize>,
+    /// Positions past the last column of the data.
+    out_of_range: BTreeSet<usize>
+  },
+  /// The blocks have the same column indexes, but their data don't have the
+  /// same number of columns, so one can't be laid out like the other.
+  WidthMismatch {
+    /// The number of columns in the primary's data.
+    primary: usize,
+    /// The number of columns in the secondary's data.
+    secondary: usize
+  }
+}
+
+/// A block's data having its columns moved around to be laid out like
+/// another's, so that the two could be merged.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnReconciliation {
+  /// The identifier of the block that was moved around, if it had one.
+  pub uid: Option<usize>,
+  /// The columns that moved, with their positions before and after.
+  pub moved: BTreeMap<NasIndex, (usize, usize)>
+}
+
+/// Where the data in a block came from, as far as merging goes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeProvenance {
+  /// The identifiers of the blocks merged into this one, in order (`None`
+  /// for blocks that had none).
+  pub merged: Vec<Option<usize>>,
+  /// The blocks whose columns had to be moved around to be merged.
+  pub reconciliations: Vec<ColumnReconciliation>
+}
+
+/// How the lines within a block were classified while decoding it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineCounts {
+  /// How many lines got each response.
+  pub responses: BTreeMap<LineResponse, usize>,
+  /// How many of the metadata lines were solver debug output, which never
+  /// reaches the decoder (see `Solver::debug_line_prefixes`).
+  pub debug: usize
+}
+
+impl LineCounts {
+  /// Counts a line that got a response.
+  pub fn count(&mut self, response: LineResponse) {
+    *self.responses.entry(response).or_default() += 1;
+  }
+
+  /// Counts a line of solver debug output, as metadata.
+  pub fn count_debug(&mut self) {
+    self.count(LineResponse::Metadata);
+    self.debug += 1;
+  }
+
+  /// Returns how many lines got a response.
+  pub fn of(&self, response: LineResponse) -> usize {
+    return self.responses.get(&response).copied().unwrap_or(0);
+  }
+
+  /// Adds the counts of another block to these.
+  pub fn absorb(&mut self, other: &Self) {
+    for (response, n) in other.responses.iter() {
+      *self.responses.entry(*response).or_default() += n;
+    }
+    self.debug += other.debug;
+  }
+}
+
+/// Metadata key for the failure theory of a composite failure indices table.
+pub const META_FAILURE_THEORY: &str = "FAILURE THEORY";
+
+/// Metadata key for the superelement a block was printed for.
+pub const META_SUPERELEMENT: &str = "SUPERELEMENT";
+
+/// Metadata key for the mode a block was printed for (e.g. eigenvectors).
+pub const META_MODE: &str = "MODE";
+
+/// Metadata key for the total strain energy of all elements in the model, as
+/// printed above (or below) an element strain energy table.
+pub const META_TOTAL_ENERGY: &str = "TOTAL ENERGY";
+
+/// Metadata key for the totals printed below a load vector (e.g. MYSTRAN's
+/// "APPLIED FORCE TOTALS"), one per degree of freedom, separated by spaces.
+pub const META_LOAD_TOTALS: &str = "LOAD TOTALS";
+
+/// Metadata key for how many lines of a block decoded with a custom layout
+/// didn't fit it, and were skipped.
+pub const META_LAYOUT_MISMATCHES: &str = "LAYOUT MISMATCHES";
+
+/// The point of a dynamic analysis a block was printed for. Transient and
+/// frequency response output repeats every block once per time step or
+/// excitation frequency, with the value printed above its header.
+///
+/// Steps are ordered times first, then by value (in the total order of
+/// `f64::total_cmp`), so they can key maps.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Step {
+  /// A time step of a transient analysis (e.g. `TIME = 1.0E-02`).
+  Time(f64),
+  /// An excitation frequency of a frequency response analysis (e.g.
+  /// `FREQUENCY = 2.0E+01`).
+  Frequency(f64)
+}
+
+impl Display for Step {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(f, "{} = {:E}", self.name(), self.value());
+  }
+}
+
+impl PartialEq for Step {
+  fn eq(&self, other: &Self) -> bool {
+    return self.cmp(other) == Ordering::Equal;
+  }
+}
+
+impl Eq for Step {}
+
+impl PartialOrd for Step {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    return Some(self.cmp(other));
+  }
+}
+
+impl Ord for Step {
+  fn cmp(&self, other: &Self) -> Ordering {
+    return match (self, other) {
+      (Self::Time(_), Self::Frequency(_)) => Ordering::Less,
+      (Self::Frequency(_), Self::Time(_)) => Ordering::Greater,
+      (a, b) => a.value().total_cmp(&b.value())
+    };
+  }
+}
+
+impl Step {
+  /// Returns the name the solver prints before the value, all caps.
+  pub const fn name(&self) -> &'static str {
+    return match self {
+      Self::Time(_) => "TIME",
+      Self::Frequency(_) => "FREQUENCY"
+    };
+  }
+
+  /// Returns the time or frequency itself.
+  pub const fn value(&self) -> f64 {
+    return match self {
+      Self::Time(x) | Self::Frequency(x) => *x
+    };
+  }
+
+  /// Builds a step from its name, as printed, and its value.
+  pub fn from_name(name: &str, value: f64) -> Option<Self> {
+    return match name {
+      "TIME" => Some(Self::Time(value)),
+      "FREQUENCY" => Some(Self::Frequency(value)),
+      _ => None
+    };
+  }
+}
+
+/// Returns the worse of two print precisions, if any is known.
+pub(crate) fn worst_precision(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+  return match (a, b) {
+    (Some(a), Some(b)) => Some(a.max(b)),
+    (a, b) => a.or(b)
+  };
 }
 
 /// Immutable view into a result block once it's finalised.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct FinalBlock {
   /// The line range, if at all known.
   pub line_range: Option<(usize, usize)>,
+  /// The byte range (half-open) of the lines in the line range, if known.
+  #[serde(default)]
+  pub byte_range: Option<(u64, u64)>,
   /// The block type that originated the data.
   pub block_type: BlockType,
   /// The subcase where this block appears.
@@ -278,10 +536,105 @@ pub struct FinalBlock {
   /// The column indexes.
   pub col_indexes: BTreeMap<NasIndex, usize>,
   /// The data within.
-  pub data: Option<FinalDMat>
+  pub data: Option<FinalDMat>,
+  /// The relative precision the values were printed with, i.e. one unit in
+  /// the last place of a mantissa, for the least precise of them. Only known
+  /// for blocks with reals printed in exponent form.
+  #[serde(default)]
+  pub print_precision: Option<f64>,
+  /// Details printed along with the data that apply to the whole block, by
+  /// name (e.g. `META_FAILURE_THEORY`).
+  #[serde(default)]
+  pub metadata: BTreeMap<String, String>,
+  /// The line of the F06 file each row was read from, where known.
+  #[serde(default)]
+  pub row_lines: BTreeMap<NasIndex, usize>,
+  /// An identifier for this block, unique within its file, if assigned (the
+  /// parser numbers blocks in the order they end).
+  #[serde(default)]
+  pub uid: Option<usize>,
+  /// The blocks merged into this one, and what was done to merge them.
+  #[serde(default)]
+  pub provenance: MergeProvenance,
+  /// How the lines within this block (and those merged into it) were
+  /// classified, if it was decoded from a file.
+  #[serde(default)]
+  pub line_counts: LineCounts,
+  /// The time step or frequency this block was printed for, in dynamic
+  /// analyses.
+  #[serde(default)]
+  pub step: Option<Step>
 }
 
 impl FinalBlock {
+  /// Returns the line of the F06 file a row was read from, if known.
+  pub fn source_line(&self, row: NasIndex) -> Option<usize> {
+    return self.row_lines.get(&row).copied();
+  }
+
+  /// Returns the superelement this block was printed for, if it was tagged
+  /// with one.
+  pub fn superelement(&self) -> Option<usize> {
+    return self.metadata.get(META_SUPERELEMENT).and_then(|s| s.parse().ok());
+  }
+
+  /// Returns the mode this block was printed for, if it was tagged with one.
+  pub fn mode(&self) -> Option<usize> {
+    return self.metadata.get(META_MODE).and_then(|s| s.parse().ok());
+  }
+
+  /// Returns the total strain energy of all elements in the model, if the
+  /// block was tagged with it.
+  pub fn total_energy(&self) -> Option<f64> {
+    return self.metadata.get(META_TOTAL_ENERGY).and_then(|s| s.parse().ok());
+  }
+
+  /// Returns the totals of a load vector, one per degree of freedom, if the
+  /// block was tagged with them.
+  pub fn load_totals(&self) -> Option<[f64; SIXDOF]> {
+    let totals = self.metadata.get(META_LOAD_TOTALS)?
+      .split_whitespace()
+      .map(|s| s.parse::<f64>().ok())
+      .collect::<Option<Vec<_>>>()?;
+    return totals.try_into().ok();
+  }
+
+  /// Returns the largest finite absolute value in the block, if it has any
+  /// data.
+  pub fn max_abs(&self) -> Option<f64> {
+    return match self.data {
+      Some(FinalDMat::Reals(ref m)) => m.iter()
+        .filter(|x| x.is_finite())
+        .map(|x| x.abs())
+        .reduce(f64::max),
+      Some(FinalDMat::Integers(ref m)) => m.iter()
+        .map(|x| x.unsigned_abs() as f64)
+        .reduce(f64::max),
+      Some(FinalDMat::Naturals(ref m)) => m.iter()
+        .map(|x| *x as f64)
+        .reduce(f64::max),
+      None => None
+    };
+  }
+
+  /// Returns where the NaNs and infinities in the block are, as row and
+  /// column indexes in order, along with the values.
+  pub fn non_finite(&self) -> Vec<(NasIndex, NasIndex, f64)> {
+    let m = match self.data {
+      Some(FinalDMat::Reals(ref m)) => m,
+      _ => return Vec::new()
+    };
+    let mut found = Vec::new();
+    for (row, ri) in self.row_indexes.iter() {
+      for (col, ci) in self.col_indexes.iter() {
+        if let Some(x) = m.get((*ri, *ci)).filter(|x| !x.is_finite()) {
+          found.push((*row, *col, *x));
+        }
+      }
+    }
+    return found;
+  }
+
   /// Returns the data at a certain location.
   pub fn get<R: Into<NasIndex>, C: Into<NasIndex>>(
     &self, row: R,
@@ -346,7 +699,7 @@ impl FinalBlock {
     let nixes: Vec<NasIndex> = self.col_indexes.keys().copied().collect();
     let mut ns: Vec<usize> = self.col_indexes.values().copied().collect();
     ns.sort();
-    for (nix, i) in nixes.into_iter().zip(ns.into_iter()) {
+    for (nix, i) in nixes.into_iter().zip(ns) {
       let nswap = self.col_indexes.iter()
         .find(|p| p.1 == &i)
         .map(|p| *(p.0))
@@ -362,7 +715,7 @@ impl FinalBlock {
     let nixes: Vec<NasIndex> = self.row_indexes.keys().copied().collect();
     let mut ns: Vec<usize> = self.row_indexes.values().copied().collect();
     ns.sort();
-    for (nix, i) in nixes.into_iter().zip(ns.into_iter()) {
+    for (nix, i) in nixes.into_iter().zip(ns) {
       let nswap = self.row_indexes.iter()
         .find(|p| p.1 == &i)
         .map(|p| *(p.0))
@@ -371,6 +724,85 @@ impl FinalBlock {
     }
   }
 
+  /// Returns the positions more than one column index maps to, and the ones
+  /// past the last column of the data (if there's any data).
+  fn bad_column_positions(&self) -> (BTreeSet<usize>, BTreeSet<usize>) {
+    let mut seen: BTreeSet<usize> = BTreeSet::new();
+    let duplicated = self.col_indexes.values()
+      .copied()
+      .filter(|c| !seen.insert(*c))
+      .collect();
+    let out_of_range = match self.data {
+      Some(ref fdm) => seen.into_iter().filter(|c| *c >= fdm.ncols()).collect(),
+      None => BTreeSet::new()
+    };
+    return (duplicated, out_of_range);
+  }
+
+  /// Reorders the columns in the underlying matrix, so that the column at
+  /// each position is the one that was at the position `order` holds for it,
+  /// and updates the column indexes accordingly. Panics if `order` isn't a
+  /// permutation of the columns.
+  pub fn permute_columns(&mut self, order: &[usize]) {
+    if let Some(ref mut fdm) = self.data {
+      fdm.permute_columns(order);
+    }
+    for pos in self.col_indexes.values_mut() {
+      *pos = order.iter()
+        .position(|c| c == pos)
+        .expect("column permutation misses an indexed column");
+    }
+  }
+
+  /// Removes some columns, along with their data, e.g. ones the solver
+  /// didn't print at all. Columns not in the block are ignored.
+  pub(crate) fn remove_columns(&mut self, cols: &BTreeSet<NasIndex>) {
+    let at = cols.iter()
+      .filter_map(|c| self.col_indexes.remove(c))
+      .collect::<BTreeSet<_>>();
+    if let Some(ref mut fdm) = self.data {
+      fdm.remove_columns(&at.iter().copied().collect::<Vec<_>>());
+    }
+    for pos in self.col_indexes.values_mut() {
+      *pos -= at.range(..*pos).count();
+    }
+  }
+
+  /// Moves the columns in the underlying matrix around so that they're laid
+  /// out like in another block, whose column indexes are given, returning
+  /// the columns that moved with their positions before and after. Columns
+  /// without an index keep their order, in the positions left over. Both
+  /// blocks must have the same column indexes, mapping one-to-one onto data
+  /// of the same width (see `can_merge`).
+  pub fn reconcile_columns(
+    &mut self,
+    layout: &BTreeMap<NasIndex, usize>
+  ) -> BTreeMap<NasIndex, (usize, usize)> {
+    let width = match self.data {
+      Some(ref fdm) => fdm.ncols(),
+      None => {
+        self.col_indexes = layout.clone();
+        return BTreeMap::new();
+      }
+    };
+    let before = self.col_indexes.clone();
+    let mut order: Vec<Option<usize>> = vec![None; width];
+    for (col, pos) in layout.iter() {
+      order[*pos] = before.get(col).copied();
+    }
+    let indexed = before.values().copied().collect::<BTreeSet<_>>();
+    let mut spare = (0..width).filter(|c| !indexed.contains(c));
+    let order = order.into_iter()
+      .map(|c| c.or_else(|| spare.next()))
+      .collect::<Option<Vec<usize>>>()
+      .expect("column layouts don't match");
+    self.permute_columns(&order);
+    return before.into_iter()
+      .map(|(col, pos)| (col, (pos, self.col_indexes[&col])))
+      .filter(|(_, (a, b))| a != b)
+      .collect();
+  }
+
   /// Checks for merge compatibility.
   pub fn can_merge(&self, other: &Self) -> Result<(), MergeIncompatible> {
     // check for same type
@@ -381,6 +813,18 @@ impl FinalBlock {
     if self.subcase != other.subcase {
       return Err(MergeIncompatible::SubcaseMismatch);
     }
+    // check for same superelement
+    if self.superelement() != other.superelement() {
+      return Err(MergeIncompatible::SuperelementMismatch);
+    }
+    // check for same mode
+    if self.mode() != other.mode() {
+      return Err(MergeIncompatible::ModeMismatch);
+    }
+    // check for same time step or frequency
+    if self.step != other.step {
+      return Err(MergeIncompatible::StepMismatch);
+    }
     // check for same columns
     let primary_col_set: BTreeSet<NasIndex> = self.col_indexes.keys()
       .copied()
@@ -396,10 +840,27 @@ impl FinalBlock {
         missing_in_secondary
       });
     }
+    // check that the columns map one-to-one onto the data
+    for (secondary, block) in [(false, self), (true, other)] {
+      let (duplicated, out_of_range) = block.bad_column_positions();
+      if !duplicated.is_empty() || !out_of_range.is_empty() {
+        return Err(MergeIncompatible::BadColumnPositions {
+          secondary,
+          duplicated,
+          out_of_range
+        });
+      }
+    }
     match (&self.data, &other.data) {
       (Some(ms), Some(mo)) if discriminant(ms) != discriminant(mo) => {
         return Err(MergeIncompatible::ScalarMismatch);
-      }
+      },
+      (Some(ms), Some(mo)) if ms.ncols() != mo.ncols() => {
+        return Err(MergeIncompatible::WidthMismatch {
+          primary: ms.ncols(),
+          secondary: mo.ncols()
+        });
+      },
       _ => return Ok(())
     };
   }
@@ -423,9 +884,36 @@ impl FinalBlock {
   ) -> Result<MergeResult, MergeIncompatible> {
     // check for compatibility
     self.can_merge(&other)?;
-    // sort columns in both so we can just move stuff
+    // the merged data is only as precise as the least precise of the two
+    let precision = worst_precision(
+      self.print_precision,
+      other.print_precision
+    );
+    self.print_precision = precision;
+    other.print_precision = precision;
+    // metadata only found in one of them still applies to both
+    for (k, v) in other.metadata.iter() {
+      self.metadata.entry(k.clone()).or_insert_with(|| v.clone());
+    }
+    other.metadata = self.metadata.clone();
+    // lay the secondary's columns out like the primary's so we can just move
+    // stuff, even if they were decoded from different header variants
     self.sort_columns();
-    other.sort_columns();
+    if other.col_indexes != self.col_indexes {
+      let moved = other.reconcile_columns(&self.col_indexes);
+      if !moved.is_empty() {
+        self.provenance.reconciliations.push(ColumnReconciliation {
+          uid: other.uid,
+          moved
+        });
+      }
+    }
+    self.provenance.merged.push(other.uid);
+    self.provenance.merged.extend(other.provenance.merged.iter().copied());
+    self.provenance.reconciliations.extend(
+      other.provenance.reconciliations.iter().cloned()
+    );
+    self.line_counts.absorb(&other.line_counts);
     /// Copies rows from one matrix to another
     fn row_copy<S: NasScalar>(
       mut p: DMatrix<S>,
@@ -446,6 +934,8 @@ impl FinalBlock {
       (None, Some(od)) => {
         // only secondary is nonempty. return secondary.
         other.data = Some(od);
+        other.provenance = self.provenance;
+        other.line_counts = self.line_counts;
         return Ok(MergeResult::Success { merged: other });
       },
       (Some(sd), None) => {
@@ -464,6 +954,11 @@ impl FinalBlock {
           .collect();
         let copied = &secondary_row_set - &primary_row_set;
         let skipped = &secondary_row_set - &copied;
+        for ci in copied.iter() {
+          if let Some(line) = other.row_lines.get(ci) {
+            self.row_lines.insert(*ci, *line);
+          }
+        }
         let to_copy = copied.iter()
           .map(|ci| other.row_indexes.get(ci).unwrap());
         // copy data
@@ -494,8 +989,9 @@ impl FinalBlock {
         // un-move stuff (this is stupid)
         self.data = Some(ndp);
         other.data = Some(nds);
-        // delete line range
+        // delete line and byte ranges
         self.line_range = None;
+        self.byte_range = None;
         // return accordingly
         if skipped.is_empty() {
           return Ok(MergeResult::Success { merged: self });
@@ -512,7 +1008,9 @@ impl FinalBlock {
 }
 
 /// Response of a block parser upon receiving a line.
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord
+)]
 pub enum LineResponse {
   /// The supplied line contained no useful information.
   Useless,
@@ -549,6 +1047,7 @@ impl LineResponse {
 
 /// This trait is implemented by all known output block decoders. It aids with
 /// code uniformity.
+#[cfg(feature = "parser")]
 pub(crate) trait BlockDecoder {
   /// The type of scalar in the data matrix.
   type MatScalar: NasScalar;
@@ -581,17 +1080,19 @@ pub(crate) trait BlockDecoder {
   /// Returns the last inserted index. Useful for stateful decoders.
   fn last_row_index(&self) -> Option<NasIndex> { return None; }
 
-  /// Consumes a line into the underlying data.
-  fn consume(&mut self, line: &str) -> LineResponse;
+  /// Consumes a line, with its number, into the underlying data.
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse;
 }
 
 /// This trait is used to hide implementation details of a block decoder.
+#[cfg(feature = "parser")]
 pub trait OpaqueDecoder {
   /// Returns the block type this decoder is for.
   fn block_type(&self) -> BlockType;
 
-  /// This function takes in a line and loads it into the decoder.
-  fn consume(&mut self, line: &str) -> LineResponse;
+  /// This function takes in a line, with its number, and loads it into the
+  /// decoder. Rows read from it are recorded as coming from that line.
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse;
 
   /// Called to hint to you what the header is, maybe it'll be useful.
   fn good_header(&mut self, header: &str) -> bool;
@@ -610,6 +1111,7 @@ pub trait OpaqueDecoder {
   ) -> FinalBlock;
 }
 
+#[cfg(feature = "parser")]
 impl<T> OpaqueDecoder for T
   where T: BlockDecoder, FinalDMat: From<DMatrix<T::MatScalar>> {
   fn block_type(&self) -> BlockType {
@@ -638,8 +1140,9 @@ impl<T> OpaqueDecoder for T
 
   fn consume(
     &mut self,
-    line: &str
+    line: &str,
+    line_no: usize
   ) -> LineResponse {
-    return BlockDecoder::consume(self, line);
+    return BlockDecoder::consume(self, line, line_no);
   }
 }
@@ -144,10 +144,12 @@ pub struct Criteria {
   /// Test a big-to-small ratio?
   #[arg(long, short = 'r')]
   pub ratio: Option<f64>,
-  /// Check for NaNs?
+  /// Flag NaNs even where both files have them? A NaN against anything else
+  /// is always flagged.
   #[arg(long)]
   pub nan: bool,
-  /// Check for infinities?
+  /// Flag infinities even where both files have the same one? Same as for
+  /// NaNs.
   #[arg(long)]
   pub inf: bool,
   /// Check for differing signs?
@@ -158,13 +160,8 @@ pub struct Criteria {
 impl Criteria {
   /// Checks a pair of values against this set of criteria.
   pub fn check(&self, a: f64, b: f64) -> Option<FlagReason> {
-    // check for NaNs
-    if self.nan && (a.is_nan() || b.is_nan()) {
-      return Some(FlagReason::NaN);
-    }
-    // check for infinities
-    if self.inf && (a.is_infinite() || b.is_infinite()) {
-      return Some(FlagReason::Infinity);
+    if !a.is_finite() || !b.is_finite() {
+      return self.check_non_finite(a, b);
     }
     // check signs
     if self.sig && (a.signum() != b.signum()) {
@@ -191,6 +188,128 @@ impl Criteria {
     // nothing? no flag
     return None;
   }
+
+  /// Checks a pair of values, at least one of them a NaN or an infinity.
+  /// They're never within tolerance, so they're flagged unless they're the
+  /// same on both sides, which is only flagged when checking for that kind
+  /// of value (`nan`, `inf`). This goes for tolerance models too.
+  pub fn check_non_finite(&self, a: f64, b: f64) -> Option<FlagReason> {
+    let nan = a.is_nan() || b.is_nan();
+    let same = (a.is_nan() && b.is_nan()) || a == b;
+    let checked = if nan { self.nan } else { self.inf };
+    if same && !checked {
+      return None;
+    }
+    return Some(if nan { FlagReason::NaN } else { FlagReason::Infinity });
+  }
+
+  /// Checks the tolerances against the relative precision some data was
+  /// printed with (see `FinalBlock::print_precision`) and its largest
+  /// absolute value. Print rounding alone can make values differ by that
+  /// much, so tighter tolerances just flag noise.
+  pub fn check_precision(
+    &self,
+    precision: f64,
+    magnitude: f64
+  ) -> Result<(), ToleranceTooTight> {
+    if let Some(max_ratio) = self.ratio {
+      if max_ratio < 1.0 + precision {
+        return Err(ToleranceTooTight::Ratio {
+          max_ratio,
+          print_precision: precision
+        });
+      }
+    }
+    if let Some(max_difference) = self.difference {
+      let resolution = precision * magnitude;
+      if max_difference < resolution {
+        return Err(ToleranceTooTight::Difference {
+          max_difference,
+          resolution
+        });
+      }
+    }
+    return Ok(());
+  }
+
+  /// Returns these criteria with the tolerances derived from the precision
+  /// some data was printed with and its largest absolute value: the max
+  /// ratio is set to twice the print precision (unless it was looser), and a
+  /// max difference is raised to twice the printed resolution if under it.
+  pub fn with_auto_tolerance(&self, precision: f64, magnitude: f64) -> Self {
+    let ratio = 1.0 + 2.0 * precision;
+    let difference = 2.0 * precision * magnitude;
+    return Self {
+      ratio: Some(self.ratio.map_or(ratio, |r| r.max(ratio))),
+      difference: self.difference.map(|d| d.max(difference)),
+      ..*self
+    };
+  }
+}
+
+/// A tolerance that asks for more precision than the data was printed with.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum ToleranceTooTight {
+  /// The max ratio is closer to one than the relative print precision.
+  Ratio {
+    /// The max ratio asked for.
+    max_ratio: f64,
+    /// The relative precision the values were printed with.
+    print_precision: f64
+  },
+  /// The max difference is smaller than the printed resolution of the
+  /// largest values.
+  Difference {
+    /// The max difference asked for.
+    max_difference: f64,
+    /// The printed resolution of the largest values.
+    resolution: f64
+  },
+  /// The relative tolerance is smaller than the relative print precision.
+  Relative {
+    /// The relative tolerance asked for.
+    max_relative: f64,
+    /// The relative precision the values were printed with.
+    print_precision: f64
+  },
+  /// The max ULP distance is smaller than the print precision, in ULPs.
+  Ulps {
+    /// The max ULP distance asked for.
+    max_ulps: u64,
+    /// The relative print precision, in ULPs.
+    resolution: u64
+  }
+}
+
+impl Display for ToleranceTooTight {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::Ratio { max_ratio, print_precision } => write!(
+        f,
+        "max ratio {} is tighter than the print precision ({:E})",
+        max_ratio,
+        print_precision
+      ),
+      Self::Difference { max_difference, resolution } => write!(
+        f,
+        "max difference {:E} is under the printed resolution ({:E})",
+        max_difference,
+        resolution
+      ),
+      Self::Relative { max_relative, print_precision } => write!(
+        f,
+        "relative tolerance {:E} is tighter than the print precision ({:E})",
+        max_relative,
+        print_precision
+      ),
+      Self::Ulps { max_ulps, resolution } => write!(
+        f,
+        "max ULP distance {} is under the print precision ({} ULPs)",
+        max_ulps,
+        resolution
+      ),
+    };
+  }
 }
 
 /// Holds a found value in two data blocks.
@@ -230,7 +349,16 @@ pub enum FlagReason {
   /// Signs differ!
   Signs,
   /// Row is misisng in one of the blocks.
-  Disjunction
+  Disjunction,
+  /// Outside of the tolerance given by a `ToleranceModel`.
+  OutOfTolerance {
+    /// The absolute-value difference between the numbers.
+    abs_difference: f64,
+    /// The difference relative to the larger absolute value.
+    rel_difference: f64,
+    /// The distance between the numbers in ULPs.
+    ulp_distance: u64
+  }
 }
 
 impl Display for FlagReason {
@@ -242,6 +370,7 @@ impl Display for FlagReason {
       FlagReason::Infinity => "infinity detected",
       FlagReason::Signs => "signs differ",
       FlagReason::Disjunction => "value absent in one of the files",
+      FlagReason::OutOfTolerance { .. } => "outside of tolerance",
     });
   }
 }
@@ -283,6 +412,23 @@ impl DataDiffer {
     impl Iterator<Item = FlaggedPosition> + 'a,
     IncompatibilityReason
   > {
+    return self.compare_with(a, b, |_, x, y| self.criteria.check(x, y));
+  }
+
+  /// Diff two data blocks, checking each pair of values found with a
+  /// function of their column instead of the criteria, and return flagged
+  /// positions.
+  pub fn compare_with<'a, F>(
+    &'a self,
+    a: &'a FinalBlock,
+    b: &'a FinalBlock,
+    check: F
+  ) -> Result<
+    impl Iterator<Item = FlaggedPosition> + 'a,
+    IncompatibilityReason
+  >
+  where F: Fn(NasIndex, f64, f64) -> Option<FlagReason> + 'a
+  {
     let comp = BlockCompatibility::from((a, b));
     if let BlockCompatibility::Incompatible(reason) = comp {
       return Err(reason);
@@ -320,7 +466,7 @@ impl DataDiffer {
             (Ok(Some(x)), Ok(Some(y))) => {
               fv.val_a = x.into();
               fv.val_b = y.into();
-              return self.criteria.check(x, y)
+              return check(c, x, y)
                 .map(|fr| FlaggedPosition { values: fv, reason: fr });
             },
             (Ok(_), Ok(None)) | (Ok(None), Ok(_)) => {
@@ -1,6 +1,7 @@
 //! This module implements the specific decoders for known data block types.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::marker::PhantomData;
 
 use log::*;
 
@@ -17,6 +18,20 @@ fn dof_cols() -> BTreeMap<Dof, usize> {
     .collect();
 }
 
+/// Splits the reals and the naturals out of the fields of a line.
+fn split_numbers(fields: &[LineField]) -> (Vec<f64>, Vec<usize>) {
+  let reals = fields.iter()
+    .filter_map(|f| if let LineField::Real(x) = f { Some(*x) } else { None })
+    .collect();
+  let ints = fields.iter()
+    .filter_map(|f| match f {
+      LineField::Integer(i) if *i >= 0 => Some(*i as usize),
+      _ => None
+    })
+    .collect();
+  return (reals, ints);
+}
+
 /// Creates a decoder that performs pure conversions from an inner decoder.
 macro_rules! converting_decoder {
   (
@@ -93,36 +108,89 @@ macro_rules! converting_decoder {
             }
           })
           .collect();
+        fb.row_lines = fb.row_lines.into_iter()
+          .filter_map(|(ri, line)| match ri {
+            NasIndex::$inner_row_type(row) => {
+              Some(($row_type::from(row).into(), line))
+            },
+            _ => None
+          })
+          .collect();
         fb.block_type = Self::BLOCK_TYPE;
         return fb;
       }
 
-      fn consume(&mut self, line: &str) -> LineResponse {
-        return BlockDecoder::consume(&mut self.inner, line);
+      fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+        return BlockDecoder::consume(&mut self.inner, line, line_no);
       }
     }
   };
 }
 
-/// This decodes a displacements block.
-pub(crate) struct DisplacementsDecoder {
-  /// The flavour of F06 file we're decoding displacements for.
+/// The kinds of six-DOF grid point vectors, which are printed alike under
+/// different headers, for `SixDofGridDecoder`.
+pub(crate) trait SixDofGridKind {
+  /// The block type they're decoded to.
+  const BLOCK_TYPE: BlockType;
+}
+
+/// Grid point displacements.
+pub(crate) struct Displacement;
+
+impl SixDofGridKind for Displacement {
+  const BLOCK_TYPE: BlockType = BlockType::Displacements;
+}
+
+/// Grid point velocities.
+pub(crate) struct Velocity;
+
+impl SixDofGridKind for Velocity {
+  const BLOCK_TYPE: BlockType = BlockType::Velocities;
+}
+
+/// Grid point accelerations.
+pub(crate) struct Acceleration;
+
+impl SixDofGridKind for Acceleration {
+  const BLOCK_TYPE: BlockType = BlockType::Accelerations;
+}
+
+/// Forces of single-point constraint.
+pub(crate) struct SpcForce;
+
+impl SixDofGridKind for SpcForce {
+  const BLOCK_TYPE: BlockType = BlockType::SpcForces;
+}
+
+/// Forces of multi-point constraint.
+pub(crate) struct MpcForce;
+
+impl SixDofGridKind for MpcForce {
+  const BLOCK_TYPE: BlockType = BlockType::MpcForces;
+}
+
+/// This decodes a block with six DOF values per grid point, a line each.
+pub(crate) struct SixDofGridDecoder<K: SixDofGridKind> {
+  /// The flavour of F06 file we're decoding for.
   flavour: Flavour,
-  /// The displacement data.
-  data: RowBlock<f64, GridPointRef, Dof, { Self::MATWIDTH }>
+  /// The grid point data.
+  data: RowBlock<f64, GridPointRef, Dof, SIXDOF>,
+  /// The kind of vector.
+  kind: PhantomData<K>
 }
 
-impl BlockDecoder for DisplacementsDecoder {
+impl<K: SixDofGridKind> BlockDecoder for SixDofGridDecoder<K> {
   type MatScalar = f64;
   type RowIndex = GridPointRef;
   type ColumnIndex = Dof;
   const MATWIDTH: usize = SIXDOF;
-  const BLOCK_TYPE: BlockType = BlockType::Displacements;
+  const BLOCK_TYPE: BlockType = K::BLOCK_TYPE;
 
   fn new(flavour: Flavour) -> Self {
     return Self {
       flavour,
-      data: RowBlock::new(dof_cols())
+      data: RowBlock::new(dof_cols()),
+      kind: PhantomData
     };
   }
 
@@ -134,14 +202,69 @@ impl BlockDecoder for DisplacementsDecoder {
     return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
     let dofs: [f64; SIXDOF] = if let Some(arr) = extract_reals(line) {
       arr
     } else {
       return LineResponse::Useless;
     };
     if let Some(gid) = nth_integer(line, 0) {
-      self.data.insert_raw((gid as usize).into(), &dofs);
+      self.data.insert_raw((gid as usize).into(), &dofs, line_no);
+      return LineResponse::Data;
+    }
+    return LineResponse::Useless;
+  }
+}
+
+/// This decodes a displacements block.
+pub(crate) type DisplacementsDecoder = SixDofGridDecoder<Displacement>;
+
+/// This decodes a velocities block.
+pub(crate) type VelocitiesDecoder = SixDofGridDecoder<Velocity>;
+
+/// This decodes an accelerations block.
+pub(crate) type AccelerationsDecoder = SixDofGridDecoder<Acceleration>;
+
+/// Decoder for the SPC forces block type.
+pub(crate) type SpcForcesDecoder = SixDofGridDecoder<SpcForce>;
+
+/// Decoder for the MPC forces block type.
+pub(crate) type MpcForcesDecoder = SixDofGridDecoder<MpcForce>;
+
+/// This decodes a solution-set displacements block. The row numbers are not
+/// grid point IDs, so they're kept as sequence numbers.
+pub(crate) struct SolutionSetDisplacementsDecoder {
+  /// The displacement data.
+  data: RowBlock<f64, SeqRef, Dof, { Self::MATWIDTH }>
+}
+
+impl BlockDecoder for SolutionSetDisplacementsDecoder {
+  type MatScalar = f64;
+  type RowIndex = SeqRef;
+  type ColumnIndex = Dof;
+  const MATWIDTH: usize = SIXDOF;
+  const BLOCK_TYPE: BlockType = BlockType::SolutionSetDisplacements;
+
+  fn new(_flavour: Flavour) -> Self {
+    return Self { data: RowBlock::new(dof_cols()) };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let dofs: [f64; SIXDOF] = if let Some(arr) = extract_reals(line) {
+      arr
+    } else {
+      return LineResponse::Useless;
+    };
+    if let Some(seq) = nth_natural(line, 0) {
+      self.data.insert_raw(SeqRef(seq), &dofs, line_no);
       return LineResponse::Data;
     }
     return LineResponse::Useless;
@@ -181,7 +304,7 @@ impl BlockDecoder for GridPointForceBalanceDecoder {
     return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
     if line.contains("FORCE BALANCE FOR GRID POINT") {
       self.gpref = nth_integer(line, 0).map(|x| (x as usize).into());
       return LineResponse::Metadata;
@@ -254,7 +377,7 @@ impl BlockDecoder for GridPointForceBalanceDecoder {
         force_origin: fo,
       };
       if let Some(arr) = extract_reals::<SIXDOF>(line) {
-        self.data.insert_raw(ri, &arr);
+        self.data.insert_raw(ri, &arr, line_no);
         return LineResponse::Data;
       } else {
         return LineResponse::BadFlavour;
@@ -264,72 +387,128 @@ impl BlockDecoder for GridPointForceBalanceDecoder {
   }
 }
 
-/// Decoder for the SPC forces block type.
-pub(crate) struct SpcForcesDecoder {
-  /// The flavour of F06 file we're decoding SPC forces for.
+/// The kinds of load vectors, which share the "LOAD VECTOR" bit of their
+/// headers, for `LoadVectorDecoder`.
+pub(crate) trait LoadVectorKind {
+  /// The block type they're decoded to.
+  const BLOCK_TYPE: BlockType;
+  /// Whether these are the equivalent nodal forces of thermal loads.
+  const THERMAL: bool;
+}
+
+/// Applied forces (the mechanical load vector).
+pub(crate) struct AppliedLoad;
+
+impl LoadVectorKind for AppliedLoad {
+  const BLOCK_TYPE: BlockType = BlockType::AppliedForces;
+  const THERMAL: bool = false;
+}
+
+/// Equivalent nodal forces of thermal loads.
+pub(crate) struct ThermalLoad;
+
+impl LoadVectorKind for ThermalLoad {
+  const BLOCK_TYPE: BlockType = BlockType::ThermalLoads;
+  const THERMAL: bool = true;
+}
+
+/// This decodes a load vector block. The totals some solvers print below it
+/// aren't a grid point, so they're kept in the block's metadata instead.
+pub(crate) struct LoadVectorDecoder<K: LoadVectorKind> {
+  /// The flavour of F06 file we're decoding displacements for.
   flavour: Flavour,
-  /// The displacement data.
-  data: RowBlock<f64, GridPointRef, Dof, { Self::MATWIDTH }>
+  /// The load data.
+  data: RowBlock<f64, GridPointRef, Dof, SIXDOF>,
+  /// The totals, if they were printed.
+  totals: Option<[f64; SIXDOF]>,
+  /// The kind of load vector.
+  kind: PhantomData<K>
 }
 
-impl BlockDecoder for SpcForcesDecoder {
+impl<K: LoadVectorKind> BlockDecoder for LoadVectorDecoder<K> {
   type MatScalar = f64;
   type RowIndex = GridPointRef;
   type ColumnIndex = Dof;
   const MATWIDTH: usize = SIXDOF;
-  const BLOCK_TYPE: BlockType = BlockType::SpcForces;
+  const BLOCK_TYPE: BlockType = K::BLOCK_TYPE;
 
   fn new(flavour: Flavour) -> Self {
     return Self {
       flavour,
-      data: RowBlock::new(dof_cols())
+      data: RowBlock::new(dof_cols()),
+      totals: None,
+      kind: PhantomData
     };
   }
 
+  fn good_header(&mut self, header: &str) -> bool {
+    // only thermal load vectors mention it, in all solvers we know.
+    let thermal = header.contains("THERMAL") || header.contains("TEMPERATURE");
+    return thermal == K::THERMAL;
+  }
+
   fn unwrap(
     self,
     subcase: usize,
     line_range: Option<(usize, usize)>
   ) -> FinalBlock {
-    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+    let mut block = self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+    if let Some(totals) = self.totals {
+      let totals = totals.map(|x| x.to_string()).join(" ");
+      block.metadata.insert(META_LOAD_TOTALS.to_owned(), totals);
+    }
+    return block;
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
     let dofs: [f64; SIXDOF] = if let Some(arr) = extract_reals(line) {
       arr
     } else {
       return LineResponse::Useless;
     };
+    if line.contains("TOTALS") {
+      self.totals = Some(dofs);
+      return LineResponse::Metadata;
+    }
     if let Some(gid) = nth_integer(line, 0) {
-      self.data.insert_raw((gid as usize).into(), &dofs);
+      self.data.insert_raw((gid as usize).into(), &dofs, line_no);
       return LineResponse::Data;
     }
     return LineResponse::Useless;
   }
 }
 
-/// This decodes an applied forces (load vector) block.
-pub(crate) struct AppliedForcesDecoder {
-  /// The flavour of F06 file we're decoding displacements for.
-  flavour: Flavour,
-  /// The displacement data.
-  data: RowBlock<f64, GridPointRef, Dof, { Self::MATWIDTH }>
+/// Decoder for applied forces (the load vector).
+pub(crate) type AppliedForcesDecoder = LoadVectorDecoder<AppliedLoad>;
+
+/// Decoder for the equivalent nodal forces of thermal loads.
+pub(crate) type ThermalLoadsDecoder = LoadVectorDecoder<ThermalLoad>;
+
+/// Decoder for temperature vectors. MYSTRAN prints a grid point per line,
+/// while Simcenter packs several grid/temperature pairs in each line.
+pub(crate) struct TemperaturesDecoder {
+  /// The temperature data.
+  data: RowBlock<f64, GridPointRef, SingleTemperature, { Self::MATWIDTH }>
 }
 
-impl BlockDecoder for AppliedForcesDecoder {
+impl BlockDecoder for TemperaturesDecoder {
   type MatScalar = f64;
   type RowIndex = GridPointRef;
-  type ColumnIndex = Dof;
-  const MATWIDTH: usize = SIXDOF;
-  const BLOCK_TYPE: BlockType = BlockType::AppliedForces;
+  type ColumnIndex = SingleTemperature;
+  const MATWIDTH: usize = 1;
+  const BLOCK_TYPE: BlockType = BlockType::Temperatures;
 
-  fn new(flavour: Flavour) -> Self {
+  fn new(_flavour: Flavour) -> Self {
     return Self {
-      flavour,
-      data: RowBlock::new(dof_cols())
+      data: RowBlock::new(SingleTemperature::canonical_cols())
     };
   }
 
+  fn good_header(&mut self, header: &str) -> bool {
+    // these are loads, not temperatures.
+    return !header.contains("LOAD");
+  }
+
   fn unwrap(
     self,
     subcase: usize,
@@ -338,17 +517,16 @@ impl BlockDecoder for AppliedForcesDecoder {
     return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
-    let dofs: [f64; Self::MATWIDTH] = if let Some(arr) = extract_reals(line) {
-      arr
-    } else {
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let pairs = packed_pairs(line);
+    for (gid, t) in pairs.iter().copied() {
+      self.data.insert_raw((gid as usize).into(), &[t], line_no);
+    }
+    if pairs.is_empty() {
       return LineResponse::Useless;
-    };
-    if let Some(gid) = nth_integer(line, 0) {
-      self.data.insert_raw((gid as usize).into(), &dofs);
+    } else {
       return LineResponse::Data;
     }
-    return LineResponse::Useless;
   }
 }
 
@@ -360,6 +538,9 @@ pub(crate) struct QuadStressesDecoder {
   data: RowBlock<f64, ElementSidedPoint, PlateStressField, { Self::MATWIDTH }>,
   /// Current row reference.
   cur_row: Option<<Self as BlockDecoder>::RowIndex>,
+  /// The row whose first side was seen, but not its second, if any. Kept
+  /// across page breaks through the hint, for orphan continuation lines.
+  open_row: Option<<Self as BlockDecoder>::RowIndex>,
   /// Element type, hinted by the header.
   etype: Option<ElementType>
 }
@@ -376,6 +557,7 @@ impl BlockDecoder for QuadStressesDecoder {
       flavour,
       data: RowBlock::new(PlateStressField::canonical_cols()),
       cur_row: None,
+      open_row: None,
       etype: None
     };
   }
@@ -399,6 +581,10 @@ impl BlockDecoder for QuadStressesDecoder {
   fn hint_last(&mut self, last: NasIndex) {
     if let NasIndex::ElementSidedPoint(esp) = last {
       self.cur_row = Some(esp);
+      // rows start at the bottom, so that one is still missing its top
+      if esp.side == ElementSide::Bottom {
+        self.open_row = Some(esp);
+      }
     }
   }
 
@@ -406,7 +592,7 @@ impl BlockDecoder for QuadStressesDecoder {
     return self.cur_row.map(|q| q.into());
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
     // first, take eight floats. if there aren't any, we're toast.
     let cols: [f64; Self::MATWIDTH] = if let Some(arr) = lax_reals(line) {
       arr
@@ -456,13 +642,20 @@ impl BlockDecoder for QuadStressesDecoder {
         }
       },
       Some(Solver::Simcenter) => {
-        if ints.is_empty() {
-          // cont. line
-          if let Some(ref mut ri) = self.cur_row {
-            ri.flip_side();
-          } else {
+        // after a page break, a continuation line may come with a carriage
+        // control zero in front of it.
+        let carriage_only = ints == [0] && line.starts_with('0');
+        if ints.is_empty() || carriage_only {
+          // cont. line, which goes with the row still missing its top
+          if self.cur_row.is_none() {
             warn!("cont line without row index at {}", line);
             return LineResponse::Abort;
+          } else if let Some(mut ri) = self.open_row.take() {
+            ri.flip_side();
+            self.cur_row = Some(ri);
+          } else {
+            warn!("ambiguous cont line, all rows have both sides, at {}", line);
+            return LineResponse::MissingMetadata;
           }
         } else {
           // line has row info
@@ -483,17 +676,19 @@ impl BlockDecoder for QuadStressesDecoder {
             warn!("no eid at {}", line);
             return LineResponse::Abort;
           };
-          self.cur_row.replace(ElementSidedPoint {
+          let row = ElementSidedPoint {
             element: ElementRef { eid, etype: self.etype },
             point,
             side
-          });
+          };
+          self.cur_row = Some(row);
+          self.open_row = Some(row);
         }
       },
       None => return LineResponse::BadFlavour,
     }
     if let Some(rid) = self.cur_row {
-      self.data.insert_raw(rid, &cols);
+      self.data.insert_raw(rid, &cols, line_no);
       return LineResponse::Data;
     } else {
       warn!("found data but couldn't construct row index at {}", line);
@@ -567,7 +762,7 @@ impl BlockDecoder for QuadForcesDecoder {
     return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
     if line.contains("GRID-ID") {
       self.has_grid_id = true;
       return LineResponse::Metadata;
@@ -636,7 +831,7 @@ impl BlockDecoder for QuadForcesDecoder {
     };
     // if we got a row ID, insert.
     if let Some(rid) = self.cur_row {
-      self.data.insert_raw(rid, &cols);
+      self.data.insert_raw(rid, &cols, line_no);
       return LineResponse::Data;
     } else {
       warn!("found data but couldn't construct row index at {}", line);
@@ -683,7 +878,7 @@ impl BlockDecoder for TriaForcesDecoder {
     return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
     let cols: [f64; Self::MATWIDTH] = if let Some(arr) = extract_reals(line) {
       arr
     } else {
@@ -691,8 +886,8 @@ impl BlockDecoder for TriaForcesDecoder {
     };
     if let Some(eid) = nth_integer(line, 0) {
       let ri = ElementRef { eid: eid as usize, etype: self.etype };
-      self.data.insert_raw(ri, &cols);
-      return LineResponse::Useless;
+      self.data.insert_raw(ri, &cols, line_no);
+      return LineResponse::Data;
     } else {
       warn!("line had data but no eid");
       return LineResponse::Abort;
@@ -700,21 +895,75 @@ impl BlockDecoder for TriaForcesDecoder {
   }
 }
 
-/// Decoder for ROD element engineering forces.
-pub(crate) struct RodForcesDecoder {
+/// The kinds of elements that print their axial force and torque (or
+/// torsional moment) side by side, several to a line, for
+/// `RodForcesDecoder`.
+pub(crate) trait AxialForceKind {
+  /// The element type.
+  const ELEMENT_TYPE: ElementType;
+  /// The block type of their engineering forces.
+  const FORCES: BlockType;
+}
+
+/// The kinds of rod elements, which print the same tables under different
+/// headers, for the rod decoders.
+pub(crate) trait RodKind: AxialForceKind {
+  /// The block type of their stresses.
+  const STRESSES: BlockType;
+}
+
+/// Plain rods, defined with CROD cards.
+pub(crate) struct Crod;
+
+impl AxialForceKind for Crod {
+  const ELEMENT_TYPE: ElementType = ElementType::Rod;
+  const FORCES: BlockType = BlockType::RodForces;
+}
+
+impl RodKind for Crod {
+  const STRESSES: BlockType = BlockType::RodStresses;
+}
+
+/// Rods defined along with their properties, with CONROD cards.
+pub(crate) struct Conrod;
+
+impl AxialForceKind for Conrod {
+  const ELEMENT_TYPE: ElementType = ElementType::Conrod;
+  const FORCES: BlockType = BlockType::ConrodForces;
+}
+
+impl RodKind for Conrod {
+  const STRESSES: BlockType = BlockType::ConrodStresses;
+}
+
+/// Viscous dampers, defined with CVISC cards. They print no stresses.
+pub(crate) struct Cvisc;
+
+impl AxialForceKind for Cvisc {
+  const ELEMENT_TYPE: ElementType = ElementType::Visc;
+  const FORCES: BlockType = BlockType::ViscForces;
+}
+
+/// Decoder for the engineering forces of rods and viscous dampers.
+pub(crate) struct RodForcesDecoder<K: AxialForceKind> {
   /// The inner block of data.
-  data: RowBlock<f64, ElementRef, RodForceField, 2>
+  data: RowBlock<f64, ElementRef, RodForceField, 2>,
+  /// The kind of element.
+  kind: PhantomData<K>
 }
 
-impl BlockDecoder for RodForcesDecoder {
+impl<K: AxialForceKind> BlockDecoder for RodForcesDecoder<K> {
   type MatScalar = f64;
   type RowIndex = ElementRef;
   type ColumnIndex = RodForceField;
   const MATWIDTH: usize = 2;
-  const BLOCK_TYPE: BlockType = BlockType::RodForces;
+  const BLOCK_TYPE: BlockType = K::FORCES;
 
   fn new(_flavour: Flavour) -> Self {
-    return Self { data: RowBlock::new(RodForceField::canonical_cols()) };
+    return Self {
+      data: RowBlock::new(RodForceField::canonical_cols()),
+      kind: PhantomData
+    };
   }
 
   fn unwrap(
@@ -725,27 +974,23 @@ impl BlockDecoder for RodForcesDecoder {
     return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
-    let mut fields = line_breakdown(line);
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    // packed like the scalar springs, but with two values per element
     let mut found = 0;
-    loop {
-      let (a, b, c) = (fields.next(), fields.next(), fields.next());
-      match (a, b, c) {
-        (
-          Some(LineField::Integer(eid)),
-          Some(LineField::Real(x)),
-          Some(LineField::Real(y))
-        ) => {
-          let ri = ElementRef {
-            eid: eid as usize,
-            etype: Some(ElementType::Rod)
-          };
-          self.data.insert_raw(ri, &[x, y]);
+    for (eid, floats) in int_pattern(line) {
+      match floats.len() {
+        0 => continue,
+        2 => {
+          let ri = ElementRef { eid, etype: Some(K::ELEMENT_TYPE) };
+          self.data.insert_raw(ri, &[floats[0], floats[1]], line_no);
           found += 1;
         },
-        _ => { break; }
+        n => {
+          warn!("got {} f64s for eid {} on line {}", n, eid, line);
+          return LineResponse::Abort;
+        }
       };
-    };
+    }
     if found > 0 {
       return LineResponse::Data;
     } else {
@@ -754,7 +999,9 @@ impl BlockDecoder for RodForcesDecoder {
   }
 }
 
-/// Decoder for bar engineering forces table.
+/// Decoder for bar engineering forces table. MYSTRAN and Simcenter print the
+/// columns in the canonical order: the moments at end A, then at end B, the
+/// shears, the axial force and the torque.
 pub(crate) struct BarForcesDecoder {
   /// The inner block of data.
   data: RowBlock<f64, ElementRef, BarForceField, 8>
@@ -781,7 +1028,7 @@ impl BlockDecoder for BarForcesDecoder {
     return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
     let cols: [f64; 8] = if let Some(arr) = extract_reals(line) {
       arr
     } else {
@@ -792,7 +1039,7 @@ impl BlockDecoder for BarForcesDecoder {
         eid: eid as usize,
         etype: Some(ElementType::Bar),
       };
-      self.data.insert_raw(ri, &cols);
+      self.data.insert_raw(ri, &cols, line_no);
       return LineResponse::Data;
     } else {
       warn!("no eid on bar force data line!");
@@ -801,122 +1048,709 @@ impl BlockDecoder for BarForcesDecoder {
   }
 }
 
-/// Decoder for ELAS1 engineering force blocks.
-pub(crate) struct Elas1ForcesDecoder {
-  /// The inner data block.
-  data: RowBlock<f64, ElementRef, SingleForce, { Self::MATWIDTH }>
+/// What a line of a Simcenter beam table holds.
+enum BeamLine {
+  /// The ID of the element the next stations belong to.
+  Element(usize),
+  /// The reals of a station: its distance over the length, then the values.
+  Station(Vec<f64>),
+  /// The reals at an end named by an "END A" or "END B" token, with its
+  /// distance put first as for any other station, and the element ID if the
+  /// line starts with it.
+  End(Option<usize>, Vec<f64>),
+  /// Anything else.
+  Other
 }
 
-impl BlockDecoder for Elas1ForcesDecoder {
-  type MatScalar = f64;
-  type RowIndex = ElementRef;
-  type ColumnIndex = SingleForce;
-  const MATWIDTH: usize = 1;
-  const BLOCK_TYPE: BlockType = BlockType::Elas1Forces;
+/// Finds the end of a beam named in a line, as "END A" or "END-A", which some
+/// tables print instead of the grid ID and the distance over the length.
+fn beam_end_token(line: &str) -> Option<BarEnd> {
+  let tokens = [
+    (BarEnd::EndA, ["END A", "END-A"]),
+    (BarEnd::EndB, ["END B", "END-B"])
+  ];
+  return tokens.into_iter()
+    .find(|(_, tokens)| tokens.iter().any(|token| line.contains(token)))
+    .map(|(end, _)| end);
+}
 
-  fn new(_flavour: Flavour) -> Self {
-    return Self { data: RowBlock::new(SingleForce::canonical_cols()) };
+/// Breaks down a line of a Simcenter beam table. Each element starts with a
+/// line with only its ID, then each station has a line with its grid ID (zero
+/// if it's not at an end), its distance over the length and the values. The
+/// ends can be named by tokens instead, with the element ID on the first one.
+fn simcenter_beam_line(line: &str) -> BeamLine {
+  let fields = line_breakdown(line).collect::<Vec<_>>();
+  let (mut reals, mut ints) = split_numbers(&fields);
+  if line.starts_with('0') && ints.len() > 1 && ints[0] == 0 {
+    // carriage control
+    ints.remove(0);
+  }
+  if let Some(end) = beam_end_token(line).filter(|_| !reals.is_empty()) {
+    reals.insert(0, end.fraction());
+    let eid = ints.first().copied().filter(|eid| *eid > 0);
+    return BeamLine::End(eid, reals);
+  }
+  if !reals.is_empty() {
+    return BeamLine::Station(reals);
+  }
+  let only_ints = fields.iter().all(|f| matches!(f, LineField::Integer(_)));
+  return match ints.as_slice() {
+    [eid] if only_ints && *eid > 0 => BeamLine::Element(*eid),
+    _ => BeamLine::Other
+  };
+}
+
+/// Decoder for beam engineering forces, at the ends and at intermediate
+/// stations, which are keyed by their distance from end A over the length.
+pub(crate) struct BeamForcesDecoder {
+  /// The flavour of solver we're decoding for.
+  flavour: Flavour,
+  /// The inner block of data.
+  data: RowBlock<f64, ElementStation, BeamForceField, { Self::MATWIDTH }>,
+  /// The current element ID.
+  eid: Option<usize>,
+  /// The last row inserted.
+  cur_row: Option<ElementStation>
+}
+
+impl BeamForcesDecoder {
+  /// Makes the row for a station of the current element.
+  fn station(&self, fraction: f64) -> Option<ElementStation> {
+    let element = ElementRef { eid: self.eid?, etype: Some(ElementType::Beam) };
+    return Some(ElementStation::at_fraction(element, fraction));
   }
 
-  fn unwrap(
-    self,
-    subcase: usize,
-    line_range: Option<(usize, usize)>
-  ) -> FinalBlock {
-    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+  /// Inserts a row.
+  fn insert(
+    &mut self,
+    row: ElementStation,
+    values: &[f64; Self::MATWIDTH],
+    line_no: usize
+  ) {
+    self.data.insert_raw(row, values, line_no);
+    self.cur_row = Some(row);
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
-    let mut fields = line_breakdown(line);
-    let mut found = 0;
-    loop {
-      let (a, b) = (fields.next(), fields.next());
-      match (a, b) {
-        (Some(LineField::Integer(eid)), Some(LineField::Real(x))) => {
-          let ri = ElementRef {
-            eid: eid as usize,
-            etype: Some(ElementType::Elas1)
-          };
-          self.data.insert_raw(ri, &[x]);
-          found += 1;
-        },
-        _ => { break; }
-      };
-    };
-    if found > 0 {
-      return LineResponse::Data;
+  /// Consumes a MYSTRAN line: the element ID, then the bending moments at end
+  /// A and at end B, the shears, the axial force and the torque. The warping
+  /// torque isn't printed, so its column is left out of the block.
+  fn consume_mystran(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let cols: [f64; 8] = if let Some(arr) = extract_reals(line) {
+      arr
     } else {
       return LineResponse::Useless;
+    };
+    if let Some(eid) = nth_natural(line, 0) {
+      self.eid = Some(eid);
+    } else {
+      warn!("no eid on beam force data line!");
+      return LineResponse::MissingMetadata;
     }
+    let [ma1, ma2, mb1, mb2, s1, s2, axial, torque] = cols;
+    for (fraction, m1, m2) in [(0.0, ma1, ma2), (1.0, mb1, mb2)] {
+      if let Some(row) = self.station(fraction) {
+        let values = [m1, m2, s1, s2, axial, torque, f64::NAN];
+        self.insert(row, &values, line_no);
+      }
+    }
+    return LineResponse::Data;
   }
-}
 
-/// A decoder for triangular elements' stresses.
-pub(crate) struct TriaStressesDecoder {
-  /// The flavour of solver we're doing.
-  flavour: Flavour,
-  /// The data within.
-  data: RowBlock<f64, ElementSidedPoint, PlateStressField, { Self::MATWIDTH }>,
-  /// The current element ID.
-  eid: Option<usize>,
-  /// The element type (gleaned from the header).
-  etype: Option<ElementType>
+  /// Consumes a Simcenter line, with the seven values at a station.
+  fn consume_simcenter(
+    &mut self,
+    line: &str,
+    line_no: usize
+  ) -> LineResponse {
+    let reals = match simcenter_beam_line(line) {
+      BeamLine::Element(eid) => {
+        self.eid = Some(eid);
+        return LineResponse::Metadata;
+      },
+      BeamLine::Station(reals) => reals,
+      BeamLine::End(eid, reals) => {
+        self.eid = eid.or(self.eid);
+        reals
+      },
+      BeamLine::Other => return LineResponse::Useless
+    };
+    if reals.len() != Self::MATWIDTH + 1 {
+      return LineResponse::Useless;
+    }
+    if !(0.0..=1.0).contains(&reals[0]) {
+      // no station there, that's mystran
+      return LineResponse::WrongSolver;
+    }
+    let row = if let Some(row) = self.station(reals[0]) {
+      row
+    } else {
+      warn!("no element ID for beam forces at {}", line);
+      return LineResponse::MissingMetadata;
+    };
+    let mut values = [0.0; Self::MATWIDTH];
+    values.copy_from_slice(&reals[1..]);
+    self.insert(row, &values, line_no);
+    return LineResponse::Data;
+  }
 }
 
-impl BlockDecoder for TriaStressesDecoder {
+impl BlockDecoder for BeamForcesDecoder {
   type MatScalar = f64;
-  type RowIndex = ElementSidedPoint;
-  type ColumnIndex = PlateStressField;
-  const MATWIDTH: usize = 8;
-  const BLOCK_TYPE: BlockType = BlockType::TriaStresses;
+  type RowIndex = ElementStation;
+  type ColumnIndex = BeamForceField;
+  const MATWIDTH: usize = 7;
+  const BLOCK_TYPE: BlockType = BlockType::BeamForces;
 
   fn new(flavour: Flavour) -> Self {
     return Self {
       flavour,
-      data: RowBlock::new(PlateStressField::canonical_cols()),
+      data: RowBlock::new(BeamForceField::canonical_cols()),
       eid: None,
-      etype: None,
-    }
+      cur_row: None
+    };
   }
 
-  fn good_header(&mut self, header: &str) -> bool {
-    self.etype = nth_etype(header, 0);
-    return true;
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    let mut block = self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+    if self.flavour.solver == Some(Solver::Mystran) {
+      let warping = NasIndex::from(BeamForceField::WarpingTorque);
+      block.remove_columns(&BTreeSet::from([warping]));
+    }
+    return block;
   }
 
   fn hint_last(&mut self, last: NasIndex) {
-    if let NasIndex::ElementSidedPoint(esp) = last {
-      self.etype = esp.element.etype;
-      self.eid = Some(esp.element.eid);
-    } else {
-      panic!("bad header passed to hint_last");
+    if let NasIndex::ElementStation(es) = last {
+      self.eid = Some(es.element.eid);
+      self.cur_row = Some(es);
     }
   }
 
-  fn unwrap(
-    self,
-    subcase: usize,
-    line_range: Option<(usize, usize)>
-  ) -> FinalBlock {
-    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+  fn last_row_index(&self) -> Option<NasIndex> {
+    return self.cur_row.map(|r| r.into());
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
-    let vals: [f64; 8] = if let Some(arr) = lax_reals(line) {
-      arr
-    } else {
-      return LineResponse::Useless;
-    };
-    let i0 = nth_natural(line, 0);
-    let i1 = nth_natural(line, 1);
-    self.eid = match (self.flavour.solver, self.eid) {
-      (Some(Solver::Mystran), None) => i0,
-      (Some(Solver::Mystran), Some(_)) => i0.or(self.eid),
-      (Some(Solver::Simcenter), None) => i1,
-      (Some(Solver::Simcenter), Some(_)) => i1.or(self.eid),
-      (None, _) => return LineResponse::BadFlavour,
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    return match self.flavour.solver {
+      Some(Solver::Mystran) => self.consume_mystran(line, line_no),
+      Some(Solver::Simcenter) => self.consume_simcenter(line, line_no),
+      None => LineResponse::BadFlavour
     };
-    let esp = if let Some(eid) = self.eid {
+  }
+}
+
+/// Decoder for beam stresses, at the ends and at intermediate stations. The
+/// margins of safety are often left blank, in which case they're NaN.
+pub(crate) struct BeamStressesDecoder {
+  /// The flavour of solver we're decoding for.
+  flavour: Flavour,
+  /// The inner block of data.
+  data: RowBlock<f64, ElementStation, BeamStressField, { Self::MATWIDTH }>,
+  /// The current element ID.
+  eid: Option<usize>,
+  /// For MYSTRAN, the line for end A, while we wait for the one for end B.
+  end_a: Option<[f64; 8]>,
+  /// The last row inserted.
+  cur_row: Option<ElementStation>
+}
+
+impl BeamStressesDecoder {
+  /// Makes the row for a station of the current element.
+  fn station(&self, fraction: f64) -> Option<ElementStation> {
+    let element = ElementRef { eid: self.eid?, etype: Some(ElementType::Beam) };
+    return Some(ElementStation::at_fraction(element, fraction));
+  }
+
+  /// Inserts a row.
+  fn insert(
+    &mut self,
+    row: ElementStation,
+    values: &[f64; Self::MATWIDTH],
+    line_no: usize
+  ) {
+    self.data.insert_raw(row, values, line_no);
+    self.cur_row = Some(row);
+  }
+
+  /// Consumes a MYSTRAN line. Like for bars, each element has two lines: the
+  /// ID, the bending stresses at the four points of end A, the axial stress,
+  /// the maximum, the minimum and the margin in tension; then the same for
+  /// end B, without the axial stress and with the margin in compression. The
+  /// longitudinal stresses are the bending ones plus the axial one.
+  fn consume_mystran(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let fields = line_breakdown(line).collect::<Vec<_>>();
+    let (reals, ints) = split_numbers(&fields);
+    if let ([eid], 7..=8) = (ints.as_slice(), reals.len()) {
+      self.eid = Some(*eid);
+      let mut end_a = [f64::NAN; 8];
+      end_a[..reals.len()].copy_from_slice(&reals);
+      self.end_a = Some(end_a);
+      return LineResponse::Data;
+    }
+    if !ints.is_empty() || !(6..=7).contains(&reals.len()) {
+      return LineResponse::Useless;
+    }
+    let [a1, a2, a3, a4, axial, max_a, min_a, ms_t] = match self.end_a.take() {
+      Some(end_a) => end_a,
+      None => {
+        warn!("found end B of a beam without end A, at {}", line);
+        return LineResponse::Abort;
+      }
+    };
+    let mut end_b = [f64::NAN; 7];
+    end_b[..reals.len()].copy_from_slice(&reals);
+    let [b1, b2, b3, b4, max_b, min_b, ms_c] = end_b;
+    let ends = [
+      (0.0, [a1, a2, a3, a4], max_a, min_a),
+      (1.0, [b1, b2, b3, b4], max_b, min_b)
+    ];
+    for (fraction, [c, d, e, f], max, min) in ends {
+      let values = [
+        c + axial,
+        d + axial,
+        e + axial,
+        f + axial,
+        max,
+        min,
+        ms_t,
+        ms_c
+      ];
+      if let Some(row) = self.station(fraction) {
+        self.insert(row, &values, line_no);
+      }
+    }
+    return LineResponse::Data;
+  }
+
+  /// Consumes a Simcenter line, with the stresses at the four points, the
+  /// maximum, the minimum and, if they weren't left blank, the margins.
+  fn consume_simcenter(
+    &mut self,
+    line: &str,
+    line_no: usize
+  ) -> LineResponse {
+    let reals = match simcenter_beam_line(line) {
+      BeamLine::Element(eid) => {
+        self.eid = Some(eid);
+        return LineResponse::Metadata;
+      },
+      BeamLine::Station(reals) => reals,
+      BeamLine::End(eid, reals) => {
+        self.eid = eid.or(self.eid);
+        reals
+      },
+      BeamLine::Other => return LineResponse::Useless
+    };
+    if !(Self::MATWIDTH-1..=Self::MATWIDTH+1).contains(&reals.len()) {
+      return LineResponse::Useless;
+    }
+    if !(0.0..=1.0).contains(&reals[0]) {
+      // no station there, that's mystran
+      return LineResponse::WrongSolver;
+    }
+    let row = if let Some(row) = self.station(reals[0]) {
+      row
+    } else {
+      warn!("no element ID for beam stresses at {}", line);
+      return LineResponse::MissingMetadata;
+    };
+    let mut values = [f64::NAN; Self::MATWIDTH];
+    values[..reals.len()-1].copy_from_slice(&reals[1..]);
+    self.insert(row, &values, line_no);
+    return LineResponse::Data;
+  }
+}
+
+impl BlockDecoder for BeamStressesDecoder {
+  type MatScalar = f64;
+  type RowIndex = ElementStation;
+  type ColumnIndex = BeamStressField;
+  const MATWIDTH: usize = 8;
+  const BLOCK_TYPE: BlockType = BlockType::BeamStresses;
+
+  fn new(flavour: Flavour) -> Self {
+    return Self {
+      flavour,
+      data: RowBlock::new(BeamStressField::canonical_cols()),
+      eid: None,
+      end_a: None,
+      cur_row: None
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+  }
+
+  fn hint_last(&mut self, last: NasIndex) {
+    if let NasIndex::ElementStation(es) = last {
+      self.eid = Some(es.element.eid);
+      self.cur_row = Some(es);
+    }
+  }
+
+  fn last_row_index(&self) -> Option<NasIndex> {
+    return self.cur_row.map(|r| r.into());
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    return match self.flavour.solver {
+      Some(Solver::Mystran) => self.consume_mystran(line, line_no),
+      Some(Solver::Simcenter) => self.consume_simcenter(line, line_no),
+      None => LineResponse::BadFlavour
+    };
+  }
+}
+
+converting_decoder!(
+  "Decoder for beam strains, laid out just like the stresses.",
+  BeamStrainsDecoder,
+  BeamStressesDecoder,
+  f64,
+  (ElementStation, ElementStation),
+  (BeamStrainField, BeamStressField),
+  BlockType::BeamStrains,
+  8
+);
+
+/// The parameters of a table of scalars that packs several (element ID,
+/// value) pairs side by side in each line, for `PackedScalarDecoder`.
+pub(crate) trait PackedScalarTable {
+  /// The type of the table's only column.
+  type Column: IndexType;
+  /// The table's only column.
+  const COLUMN: Self::Column;
+  /// The block type of the table.
+  const BLOCK_TYPE: BlockType;
+  /// The type of the elements in the table, unless the header names another
+  /// one of the same category.
+  const ELEMENT_TYPE: ElementType;
+  /// The most pairs a line of the table can have.
+  const MAX_PAIRS: usize;
+}
+
+/// Decoder for tables of scalars packed as (element ID, value) pairs, several
+/// to a line (how many depends on the solver), the last line usually only
+/// partly filled.
+///
+/// This is the decoder the scalar springs (ELAS1 to ELAS4) share for their
+/// forces, stresses and strains, one table per block type, which only differ
+/// in the header and the element type of their rows.
+pub(crate) struct PackedScalarDecoder<T: PackedScalarTable> {
+  /// The data within.
+  data: RowBlock<f64, ElementRef, T::Column, 1>,
+  /// The element type (gleaned from the header).
+  etype: ElementType
+}
+
+impl<T: PackedScalarTable> BlockDecoder for PackedScalarDecoder<T> {
+  type MatScalar = f64;
+  type RowIndex = ElementRef;
+  type ColumnIndex = T::Column;
+  const MATWIDTH: usize = 1;
+  const BLOCK_TYPE: BlockType = T::BLOCK_TYPE;
+
+  fn new(_flavour: Flavour) -> Self {
+    return Self {
+      data: RowBlock::new(BTreeMap::from([(T::COLUMN, 0)])),
+      etype: T::ELEMENT_TYPE
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+  }
+
+  fn good_header(&mut self, header: &str) -> bool {
+    // tables shared by a family of elements only differ in the subtype named
+    if let Some(etype) = nth_etype(header, 0) {
+      if etype.category() == T::ELEMENT_TYPE.category() {
+        self.etype = etype;
+      }
+    }
+    return true;
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let etype = Some(self.etype);
+    let mut pairs: Vec<(ElementRef, f64)> = Vec::new();
+    for (eid, floats) in int_pattern(line) {
+      match floats.len() {
+        0 => continue,
+        1 => pairs.push((ElementRef { eid, etype }, floats[0])),
+        _ => {
+          warn!("more than one float per ID in {} line {}", self.etype, line);
+          return LineResponse::Abort;
+        }
+      };
+    }
+    if pairs.len() > T::MAX_PAIRS {
+      warn!(
+        "{} pairs in a {} line, at most {} expected",
+        pairs.len(),
+        Self::BLOCK_TYPE,
+        T::MAX_PAIRS
+      );
+      return LineResponse::Abort;
+    }
+    for (eref, x) in pairs.iter() {
+      self.data.insert_raw(*eref, &[*x], line_no);
+    }
+    if pairs.is_empty() {
+      return LineResponse::Useless;
+    } else {
+      return LineResponse::Data;
+    }
+  }
+}
+
+/// Declares a table of packed scalars and the decoder for it.
+macro_rules! packed_scalar_decoder {
+  (
+    // description of the decoder
+    $desc:literal,
+    // name of the decoder
+    $dec:ident,
+    // name of the table parameters
+    $table:ident,
+    // column type and the column
+    ($col:ty, $column:expr),
+    // element type
+    $etype:expr,
+    // block type
+    $block_type:expr,
+    // most pairs per line
+    $max_pairs:literal
+  ) => {
+    #[doc = concat!("Parameters of the table read by `", stringify!($dec), "`.")]
+    pub(crate) struct $table;
+
+    impl PackedScalarTable for $table {
+      type Column = $col;
+      const COLUMN: Self::Column = $column;
+      const BLOCK_TYPE: BlockType = $block_type;
+      const ELEMENT_TYPE: ElementType = $etype;
+      const MAX_PAIRS: usize = $max_pairs;
+    }
+
+    #[doc = $desc]
+    pub(crate) type $dec = PackedScalarDecoder<$table>;
+  };
+}
+
+// MYSTRAN packs five pairs to a line, Simcenter four.
+
+packed_scalar_decoder!(
+  "Decoder for ELAS1 engineering force blocks.",
+  Elas1ForcesDecoder,
+  Elas1ForcesTable,
+  (SingleForce, SingleForce::Force),
+  ElementType::Elas1,
+  BlockType::Elas1Forces,
+  5
+);
+
+packed_scalar_decoder!(
+  "Decoder for ELAS1 element stresses.",
+  Elas1StressesDecoder,
+  Elas1StressesTable,
+  (SingleStress, SingleStress::Stress),
+  ElementType::Elas1,
+  BlockType::Elas1Stresses,
+  5
+);
+
+packed_scalar_decoder!(
+  "Decoder for ELAS1 element strains.",
+  Elas1StrainsDecoder,
+  Elas1StrainsTable,
+  (SingleStrain, SingleStrain::Strain),
+  ElementType::Elas1,
+  BlockType::Elas1Strains,
+  5
+);
+
+packed_scalar_decoder!(
+  "Decoder for ELAS2 engineering force blocks.",
+  Elas2ForcesDecoder,
+  Elas2ForcesTable,
+  (SingleForce, SingleForce::Force),
+  ElementType::Elas2,
+  BlockType::Elas2Forces,
+  5
+);
+
+packed_scalar_decoder!(
+  "Decoder for ELAS2 element stresses.",
+  Elas2StressesDecoder,
+  Elas2StressesTable,
+  (SingleStress, SingleStress::Stress),
+  ElementType::Elas2,
+  BlockType::Elas2Stresses,
+  5
+);
+
+packed_scalar_decoder!(
+  "Decoder for ELAS2 element strains.",
+  Elas2StrainsDecoder,
+  Elas2StrainsTable,
+  (SingleStrain, SingleStrain::Strain),
+  ElementType::Elas2,
+  BlockType::Elas2Strains,
+  5
+);
+
+packed_scalar_decoder!(
+  "Decoder for ELAS3 engineering force blocks.",
+  Elas3ForcesDecoder,
+  Elas3ForcesTable,
+  (SingleForce, SingleForce::Force),
+  ElementType::Elas3,
+  BlockType::Elas3Forces,
+  5
+);
+
+packed_scalar_decoder!(
+  "Decoder for ELAS3 element stresses.",
+  Elas3StressesDecoder,
+  Elas3StressesTable,
+  (SingleStress, SingleStress::Stress),
+  ElementType::Elas3,
+  BlockType::Elas3Stresses,
+  5
+);
+
+packed_scalar_decoder!(
+  "Decoder for ELAS3 element strains.",
+  Elas3StrainsDecoder,
+  Elas3StrainsTable,
+  (SingleStrain, SingleStrain::Strain),
+  ElementType::Elas3,
+  BlockType::Elas3Strains,
+  5
+);
+
+packed_scalar_decoder!(
+  "Decoder for ELAS4 engineering force blocks.",
+  Elas4ForcesDecoder,
+  Elas4ForcesTable,
+  (SingleForce, SingleForce::Force),
+  ElementType::Elas4,
+  BlockType::Elas4Forces,
+  5
+);
+
+packed_scalar_decoder!(
+  "Decoder for ELAS4 element stresses.",
+  Elas4StressesDecoder,
+  Elas4StressesTable,
+  (SingleStress, SingleStress::Stress),
+  ElementType::Elas4,
+  BlockType::Elas4Stresses,
+  5
+);
+
+packed_scalar_decoder!(
+  "Decoder for ELAS4 element strains.",
+  Elas4StrainsDecoder,
+  Elas4StrainsTable,
+  (SingleStrain, SingleStrain::Strain),
+  ElementType::Elas4,
+  BlockType::Elas4Strains,
+  5
+);
+
+// all four kinds of scalar damper share a block, telling the subtype apart by
+// the header.
+
+packed_scalar_decoder!(
+  "Decoder for scalar damper (DAMP1 to DAMP4) engineering force blocks.",
+  CdampForcesDecoder,
+  CdampForcesTable,
+  (SingleForce, SingleForce::Force),
+  ElementType::Damp1,
+  BlockType::CdampForces,
+  5
+);
+
+/// A decoder for triangular elements' stresses.
+pub(crate) struct TriaStressesDecoder {
+  /// The flavour of solver we're doing.
+  flavour: Flavour,
+  /// The data within.
+  data: RowBlock<f64, ElementSidedPoint, PlateStressField, { Self::MATWIDTH }>,
+  /// The current element ID.
+  eid: Option<usize>,
+  /// The element type (gleaned from the header).
+  etype: Option<ElementType>
+}
+
+impl BlockDecoder for TriaStressesDecoder {
+  type MatScalar = f64;
+  type RowIndex = ElementSidedPoint;
+  type ColumnIndex = PlateStressField;
+  const MATWIDTH: usize = 8;
+  const BLOCK_TYPE: BlockType = BlockType::TriaStresses;
+
+  fn new(flavour: Flavour) -> Self {
+    return Self {
+      flavour,
+      data: RowBlock::new(PlateStressField::canonical_cols()),
+      eid: None,
+      etype: None,
+    }
+  }
+
+  fn good_header(&mut self, header: &str) -> bool {
+    self.etype = nth_etype(header, 0);
+    return true;
+  }
+
+  fn hint_last(&mut self, last: NasIndex) {
+    if let NasIndex::ElementSidedPoint(esp) = last {
+      self.etype = esp.element.etype;
+      self.eid = Some(esp.element.eid);
+    } else {
+      panic!("bad header passed to hint_last");
+    }
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let vals: [f64; 8] = if let Some(arr) = lax_reals(line) {
+      arr
+    } else {
+      return LineResponse::Useless;
+    };
+    let i0 = nth_natural(line, 0);
+    let i1 = nth_natural(line, 1);
+    self.eid = match (self.flavour.solver, self.eid) {
+      (Some(Solver::Mystran), None) => i0,
+      (Some(Solver::Mystran), Some(_)) => i0.or(self.eid),
+      (Some(Solver::Simcenter), None) => i1,
+      (Some(Solver::Simcenter), Some(_)) => i1.or(self.eid),
+      (None, _) => return LineResponse::BadFlavour,
+    };
+    let esp = if let Some(eid) = self.eid {
       let element = ElementRef { eid, etype: self.etype };
       let side = if nth_natural(line, 0).is_none() {
         ElementSide::Top
@@ -929,7 +1763,7 @@ impl BlockDecoder for TriaStressesDecoder {
       warn!("no eid on data line on {}", line);
       return LineResponse::Abort;
     };
-    self.data.insert_raw(esp, &vals);
+    self.data.insert_raw(esp, &vals, line_no);
     return LineResponse::Data;
   }
 }
@@ -946,24 +1780,27 @@ converting_decoder!(
 );
 
 /// Decoder for "stresses in rod elements" tables.
-pub(crate) struct RodStressesDecoder {
+pub(crate) struct RodStressesDecoder<K: RodKind> {
   /// The flavour of type we're decoding in.
   flavour: Flavour,
   /// The data within.
-  data: RowBlock<f64, ElementRef, RodStressField, { Self::MATWIDTH }>
+  data: RowBlock<f64, ElementRef, RodStressField, 4>,
+  /// The kind of rod.
+  kind: PhantomData<K>
 }
 
-impl BlockDecoder for RodStressesDecoder {
+impl<K: RodKind> BlockDecoder for RodStressesDecoder<K> {
   type MatScalar = f64;
   type RowIndex = ElementRef;
   type ColumnIndex = RodStressField;
   const MATWIDTH: usize = 4;
-  const BLOCK_TYPE: BlockType = BlockType::RodStresses;
+  const BLOCK_TYPE: BlockType = K::STRESSES;
 
   fn new(flavour: Flavour) -> Self {
     return Self {
       flavour,
-      data: RowBlock::new(RodStressField::canonical_cols())
+      data: RowBlock::new(RodStressField::canonical_cols()),
+      kind: PhantomData
     };
   }
 
@@ -975,7 +1812,7 @@ impl BlockDecoder for RodStressesDecoder {
     return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
     let mut added = 0;
     for (eid, floats) in int_pattern(line) {
       let arr: [f64; 4] = match floats.len() {
@@ -992,8 +1829,8 @@ impl BlockDecoder for RodStressesDecoder {
           return LineResponse::Abort;
         }
       };
-      let eref = ElementRef { eid, etype: Some(ElementType::Rod) };
-      self.data.insert_raw(eref, &arr);
+      let eref = ElementRef { eid, etype: Some(K::ELEMENT_TYPE) };
+      self.data.insert_raw(eref, &arr, line_no);
       added += 1;
     }
     if added > 0 {
@@ -1007,7 +1844,7 @@ impl BlockDecoder for RodStressesDecoder {
 converting_decoder!(
   "Decoder for \"strains in rod elements\" tables.",
   RodStrainsDecoder,
-  RodStressesDecoder,
+  RodStressesDecoder<Crod>,
   f64,
   (ElementRef, ElementRef),
   (RodStrainField, RodStressField),
@@ -1015,6 +1852,17 @@ converting_decoder!(
   4
 );
 
+converting_decoder!(
+  "Decoder for \"strains in rod elements\" tables of CONRODs.",
+  ConrodStrainsDecoder,
+  RodStressesDecoder<Conrod>,
+  f64,
+  (ElementRef, ElementRef),
+  (RodStrainField, RodStressField),
+  BlockType::ConrodStrains,
+  4
+);
+
 /// Decoder for "stresses in bar elements" tables.
 pub(crate) struct BarStressesDecoder {
   /// The flavour of file we're decoding for.
@@ -1048,7 +1896,7 @@ impl BlockDecoder for BarStressesDecoder {
     return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
     /// Order of columns in the first row.
     const ORDER_L1: &[BarStressField] = &[
       BarStressField::AtRecoveryPoint { end: BarEnd::EndA, point: 1 },
@@ -1129,7 +1977,7 @@ impl BlockDecoder for BarStressesDecoder {
         .for_each(|(k, v)| { cols.insert(k, v); });
       if cols.len() == Self::MATWIDTH {
         let eref = ElementRef { eid, etype: Some(ElementType::Bar) };
-        self.data.insert_row(eref, &cols);
+        self.data.insert_row(eref, &cols, line_no);
         return LineResponse::Data;
       } else {
         warn!("bad number of items in val map ({})", cols.len());
@@ -1157,22 +2005,88 @@ converting_decoder!(
   15
 );
 
-/// Decoder for ELAS1 element stresses.
-pub(crate) struct Elas1StressesDecoder {
-  /// The data within.
-  data: RowBlock<f64, ElementRef, SingleStress, { Self::MATWIDTH }>
+/// Returns the signs a polarity column stands for, with a letter per
+/// component: "T" for tension, "C" for compression and "-" for a component
+/// printed with its own sign.
+fn polarity_signs(field: &LineField) -> Option<[Option<f64>; SIXDOF]> {
+  let word = match field {
+    LineField::NoIdea(word) if word.len() == SIXDOF => word,
+    _ => return None
+  };
+  let mut signs = [None; SIXDOF];
+  for (sign, c) in signs.iter_mut().zip(word.chars()) {
+    *sign = match c {
+      'T' => Some(1.0),
+      'C' => Some(-1.0),
+      '-' => None,
+      _ => return None
+    };
+  }
+  return Some(signs);
+}
+
+/// Joins the lines of an element whose values wrap: floats with no element ID
+/// continue the pending element, and an ID starts a new one, dropping (with a
+/// warning) whatever was pending. Returns the element ID and its values so
+/// far, or `None` if there's no ID to go by. `what` names the element in the
+/// warnings.
+fn join_wrapped(
+  pending: &mut Option<(usize, Vec<f64>)>,
+  eid: Option<usize>,
+  reals: Vec<f64>,
+  what: &str
+) -> Option<(usize, Vec<f64>)> {
+  return match (eid, pending.take()) {
+    (None, Some((eid, mut values))) => {
+      // the rest of a wrapped line
+      values.extend(reals);
+      Some((eid, values))
+    },
+    (Some(eid), pending) => {
+      if let Some((old, _)) = pending {
+        warn!("{} {} is missing the end of its line!", what, old);
+      }
+      Some((eid, reals))
+    },
+    (None, None) => {
+      warn!("{} line has floats but no EID!", what);
+      None
+    }
+  };
+}
+
+/// This decodes a bush forces block. Lines that wrap are joined with the
+/// next one. The element ID isn't necessarily the first field, as the element
+/// type can come before it.
+///
+/// When `POLAR`, as for stresses, some solvers print the values as magnitudes,
+/// with a polarity column (say, "TTCT-T") at the end of the line; each
+/// component gets the sign of its letter, so both conventions decode to the
+/// same data.
+pub(crate) struct BushForcesDecoder<const POLAR: bool = false> {
+  /// The flavour of F06 file we're decoding displacements for.
+  flavour: Flavour,
+  /// The displacement data.
+  data: RowBlock<f64, ElementRef, Dof, SIXDOF>,
+  /// An element whose line wrapped, and the values read so far.
+  pending: Option<(usize, Vec<f64>)>,
+  /// The signs from the polarity column of the current element, if seen.
+  polarity: Option<[Option<f64>; SIXDOF]>
 }
 
-impl BlockDecoder for Elas1StressesDecoder {
+impl<const POLAR: bool> BlockDecoder for BushForcesDecoder<POLAR> {
   type MatScalar = f64;
   type RowIndex = ElementRef;
-  type ColumnIndex = SingleStress;
-  const MATWIDTH: usize = 1;
-  const BLOCK_TYPE: BlockType = BlockType::Elas1Stresses;
+  type ColumnIndex = Dof;
+  const MATWIDTH: usize = SIXDOF;
+  const BLOCK_TYPE: BlockType = BlockType::BushForces;
 
-  fn new(_flavour: Flavour) -> Self {
+  fn new(flavour: Flavour) -> Self {
     return Self {
-      data: RowBlock::new(SingleStress::canonical_cols())
+      flavour,
+      data: RowBlock::new(dof_cols()),
+      pending: None,
+      polarity: None
     };
   }
 
@@ -1181,65 +2095,577 @@ impl BlockDecoder for Elas1StressesDecoder {
     subcase: usize,
     line_range: Option<(usize, usize)>
   ) -> FinalBlock {
+    if let Some((eid, _)) = self.pending {
+      warn!("bush element {} is missing the end of its line!", eid);
+    }
     return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
-    let etype = Some(ElementType::Elas1);
-    let mut added: usize = 0;
-    for (eid, floats) in int_pattern(line) {
-      match floats.len() {
-        0 => continue,
-        1 => {
-          let eref = ElementRef { eid, etype };
-          let vals = [floats[0]];
-          self.data.insert_raw(eref, &vals);
-          added += 1;
-        },
-        _ => {
-          warn!("more than one float in elas1 stress/strain line {}", line);
-          return LineResponse::Abort;
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let fields = line_breakdown(line).collect::<Vec<_>>();
+    let (reals, ints) = split_numbers(&fields);
+    if reals.is_empty() {
+      return LineResponse::Useless;
+    }
+    let eid = ints.into_iter().rfind(|i| *i > 0);
+    if eid.is_some() {
+      self.polarity = None;
+    }
+    let joined = join_wrapped(&mut self.pending, eid, reals, "bush element");
+    let Some((eid, values)) = joined else {
+      return LineResponse::Abort;
+    };
+    if POLAR {
+      if let Some(signs) = fields.iter().rev().find_map(polarity_signs) {
+        self.polarity = Some(signs);
+      }
+    }
+    let eref = ElementRef { eid, etype: Some(ElementType::Bush) };
+    match values.len() {
+      SIXDOF => {
+        let mut dofs = [0.0; SIXDOF];
+        dofs.copy_from_slice(&values);
+        let signs = self.polarity.take().unwrap_or([None; SIXDOF]);
+        for (x, sign) in dofs.iter_mut().zip(signs) {
+          if let Some(sign) = sign.filter(|_| *x != 0.0) {
+            if x.is_sign_negative() {
+              warn!("bush element {} has a polarity and signed values", eid);
+            }
+            *x = sign * x.abs();
+          }
+        }
+        self.data.insert_raw(eref, &dofs, line_no);
+      },
+      n if n < SIXDOF => self.pending = Some((eid, values)),
+      n => {
+        warn!("got {} floats for bush element {}", n, eid);
+        return LineResponse::Useless;
+      }
+    };
+    return LineResponse::Data;
+  }
+}
+
+converting_decoder!(
+  "Decoder for BUSH stresses, laid out like the forces",
+  BushStressesDecoder,
+  BushForcesDecoder<true>,
+  f64,
+  (ElementRef, ElementRef),
+  (BushStressField, Dof),
+  BlockType::BushStresses,
+  6
+);
+
+converting_decoder!(
+  "Decoder for BUSH strains",
+  BushStrainsDecoder,
+  BushStressesDecoder,
+  f64,
+  (ElementRef, ElementRef),
+  (BushStrainField, BushStressField),
+  BlockType::BushStrains,
+  6
+);
+
+/// A row of a solid element stress table: where, and the six components, von
+/// Mises and octahedral shear stress, the three principal stresses and the
+/// mean pressure, in the order of the CHEXA columns.
+type SolidStressRow = (ElementSidedPoint, [f64; 12]);
+
+/// Reads the stress tables of solid elements, which are laid out alike
+/// whatever the shape, at the centroid and (optionally) at the corners. The
+/// corners are the printed GRID-IDs: the first face's (the first half of
+/// those printed for an element, for shapes with opposite faces) are put on
+/// the bottom side, and those of the opposite face on the top side. Nastran prints either the von Mises or the
+/// octahedral shear stress; the other one is left out of the block, as are the
+/// principal stresses and mean pressure where they aren't printed.
+struct SolidStressReader {
+  /// The flavour of solver we're decoding for.
+  flavour: Flavour,
+  /// The type of the elements.
+  etype: ElementType,
+  /// How many of the corners printed for an element go on the bottom side;
+  /// the ones after them go on the top side.
+  bottom_corners: usize,
+  /// The current element ID.
+  eid: Option<usize>,
+  /// The corner GRID-IDs printed so far for the current element, in order.
+  grids: Vec<usize>,
+  /// Number of corners of the current element printed before those in
+  /// `grids`, when picking up where the last block left off.
+  skipped: usize,
+  /// The row being read, for Simcenter, where each point spans three lines.
+  pending: Option<ElementSidedPoint>,
+  /// The values of the row being read, in the order of the CHEXA columns.
+  values: [f64; 12],
+  /// Whether the last column is the octahedral shear stress instead of von
+  /// Mises.
+  octahedral: bool
+}
+
+impl SolidStressReader {
+  /// Instantiates a reader for a shape of element.
+  fn new(flavour: Flavour, etype: ElementType, bottom_corners: usize) -> Self {
+    return Self {
+      flavour,
+      etype,
+      bottom_corners,
+      eid: None,
+      grids: Vec::new(),
+      skipped: 0,
+      pending: None,
+      values: [0.0; 12],
+      octahedral: false
+    };
+  }
+
+  /// Returns whether the principal stresses and the mean pressure are
+  /// printed, which MYSTRAN doesn't.
+  fn principals_printed(&self) -> bool {
+    return self.flavour.solver != Some(Solver::Mystran);
+  }
+
+  /// Forgets the corners of the last element.
+  fn new_element(&mut self) {
+    self.grids.clear();
+    self.skipped = 0;
+  }
+
+  /// Finds the point a line is for, from the GRID-ID printed in it. The
+  /// corners whose GRID-ID comes after the first face's go on the top side.
+  fn locate(
+    &mut self,
+    line: &str,
+    gid: Option<usize>
+  ) -> Option<ElementSidedPoint> {
+    let eid = self.eid?;
+    let (point, side) = if line.contains("CENTER") {
+      self.new_element();
+      (ElementPoint::Centroid, ElementSide::Bottom)
+    } else {
+      let gid = gid?;
+      let nth = match self.grids.iter().position(|g| *g == gid) {
+        Some(nth) => nth,
+        None => {
+          self.grids.push(gid);
+          self.grids.len() - 1
         }
       };
+      let side = if self.skipped + nth >= self.bottom_corners {
+        ElementSide::Top
+      } else {
+        ElementSide::Bottom
+      };
+      (ElementPoint::Corner(gid.into()), side)
+    };
+    let element = ElementRef { eid, etype: Some(self.etype) };
+    return Some(ElementSidedPoint { element, point, side });
+  }
+
+  /// Finishes a row with the values read, the stress that isn't printed
+  /// being NaN until its column is left out of the block.
+  fn row(&mut self, at: ElementSidedPoint) -> SolidStressRow {
+    self.values[if self.octahedral { 6 } else { 7 }] = f64::NAN;
+    return (at, self.values);
+  }
+
+  /// Picks up where the last block left off.
+  fn hint_last(&mut self, esp: ElementSidedPoint) {
+    self.eid = Some(esp.element.eid);
+    self.new_element();
+    // the side only depends on whether the first face was passed; a bottom
+    // corner is taken to be the first one
+    if let ElementPoint::Corner(grid) = esp.point {
+      self.grids.push(grid.gid);
+      if esp.side == ElementSide::Top {
+        self.skipped = self.bottom_corners;
+      }
     }
-    if added > 0 {
-      return LineResponse::Data;
+  }
+
+  /// Consumes a MYSTRAN line: one per point, with the element ID (if it's a
+  /// new element), the grid ID or "CENTER", the six components and von Mises.
+  /// There are no principal stresses nor mean pressure.
+  fn consume_mystran(
+    &mut self,
+    line: &str
+  ) -> (LineResponse, Option<SolidStressRow>) {
+    let fields = line_breakdown(line).collect::<Vec<_>>();
+    if fields.iter().any(|f| matches!(f, LineField::NoIdea("XY"))) {
+      // three lines per point, that's simcenter
+      return (LineResponse::WrongSolver, None);
+    }
+    let (reals, ints) = split_numbers(&fields);
+    if reals.len() < 7 {
+      return (LineResponse::Useless, None);
+    }
+    let gid = if line.contains("CENTER") {
+      self.eid = ints.first().copied().or(self.eid);
+      None
+    } else if let [eid, gid, ..] = ints[..] {
+      if self.eid != Some(eid) {
+        self.new_element();
+      }
+      self.eid = Some(eid);
+      Some(gid)
     } else {
-      return LineResponse::Useless;
+      ints.first().copied()
+    };
+    let at = if let Some(at) = self.locate(line, gid) {
+      at
+    } else {
+      warn!("no element or grid ID for solid stresses at {}", line);
+      return (LineResponse::MissingMetadata, None);
+    };
+    self.values = [f64::NAN; 12];
+    self.values[..7].copy_from_slice(&reals[..7]);
+    return (LineResponse::Data, Some(self.row(at)));
+  }
+
+  /// Consumes a Simcenter line. Each element starts with a line with its ID,
+  /// then each point has three lines, for the X, Y and Z directions: normal
+  /// stress, shear stress (XY, YZ, ZX), principal stress (A, B, C) and
+  /// direction cosines; the first one also has the mean pressure and von Mises
+  /// (or octahedral shear) stress.
+  fn consume_simcenter(
+    &mut self,
+    line: &str
+  ) -> (LineResponse, Option<SolidStressRow>) {
+    if line.contains("OCTAHEDRAL") {
+      self.octahedral = true;
+      return (LineResponse::Metadata, None);
+    }
+    let fields = line_breakdown(line).collect::<Vec<_>>();
+    let (reals, mut ints) = split_numbers(&fields);
+    if line.starts_with('0') && ints.first() == Some(&0) {
+      // carriage control
+      ints.remove(0);
+    }
+    if line.contains("GRID CS") {
+      self.eid = ints.first().copied();
+      self.new_element();
+      return (LineResponse::Metadata, None);
+    }
+    let direction = fields.iter().find_map(|f| match f {
+      LineField::Character(c @ ('X' | 'Y' | 'Z')) => Some(*c),
+      _ => None
+    });
+    let mut row = None;
+    match direction {
+      Some('X') if reals.len() >= 5 => {
+        self.pending = self.locate(line, ints.last().copied());
+        if self.pending.is_none() {
+          warn!("no element or grid ID for solid stresses at {}", line);
+          return (LineResponse::MissingMetadata, None);
+        }
+        self.values = [0.0; 12];
+        self.values[0] = reals[0];
+        self.values[3] = reals[1];
+        self.values[8] = reals[2];
+        // the last ones, since direction cosines may be glued to their labels
+        let last = reals[reals.len() - 1];
+        self.values[if self.octahedral { 7 } else { 6 }] = last;
+        self.values[11] = reals[reals.len() - 2];
+      },
+      Some('Y') if reals.len() >= 3 && self.pending.is_some() => {
+        self.values[1] = reals[0];
+        self.values[4] = reals[1];
+        self.values[9] = reals[2];
+      },
+      Some('Z') if reals.len() >= 3 && self.pending.is_some() => {
+        self.values[2] = reals[0];
+        self.values[5] = reals[1];
+        self.values[10] = reals[2];
+        row = self.pending.take().map(|at| self.row(at));
+      },
+      Some(_) => {
+        warn!("solid stress line out of place at {}", line);
+        return (LineResponse::MissingMetadata, None);
+      },
+      None if reals.len() >= 7 => {
+        // one line per point, that's mystran
+        return (LineResponse::WrongSolver, None);
+      },
+      None => return (LineResponse::Useless, None)
+    }
+    return (LineResponse::Data, row);
+  }
+
+  /// Consumes a line, returning the row it finished, if any.
+  fn consume(&mut self, line: &str) -> (LineResponse, Option<SolidStressRow>) {
+    return match self.flavour.solver {
+      Some(Solver::Mystran) => self.consume_mystran(line),
+      Some(Solver::Simcenter) => self.consume_simcenter(line),
+      None => (LineResponse::BadFlavour, None)
+    };
+  }
+}
+
+/// Decoder for the stresses in hexahedral solid elements, at the centroid and
+/// (optionally) at the eight corners, four on each side.
+pub(crate) struct ChexaStressesDecoder {
+  /// Reads the lines.
+  reader: SolidStressReader,
+  /// The inner block of data.
+  data: RowBlock<f64, ElementSidedPoint, ChexaStressField, { Self::MATWIDTH }>,
+  /// The last row inserted.
+  cur_row: Option<ElementSidedPoint>
+}
+
+impl BlockDecoder for ChexaStressesDecoder {
+  type MatScalar = f64;
+  type RowIndex = ElementSidedPoint;
+  type ColumnIndex = ChexaStressField;
+  const MATWIDTH: usize = 12;
+  const BLOCK_TYPE: BlockType = BlockType::ChexaStresses;
+
+  fn new(flavour: Flavour) -> Self {
+    return Self {
+      reader: SolidStressReader::new(flavour, ElementType::Hexa, 4),
+      data: RowBlock::new(ChexaStressField::canonical_cols()),
+      cur_row: None
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    let mut block = self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+    // only one of the two is printed
+    let unprinted = if self.reader.octahedral {
+      ChexaStressField::VonMises
+    } else {
+      ChexaStressField::Octahedral
+    };
+    block.remove_columns(&BTreeSet::from([unprinted.into()]));
+    if !self.reader.principals_printed() {
+      let principals = [
+        ChexaStressField::PrincipalA,
+        ChexaStressField::PrincipalB,
+        ChexaStressField::PrincipalC,
+        ChexaStressField::MeanPressure
+      ];
+      block.remove_columns(&principals.map(Into::into).into());
+    }
+    return block;
+  }
+
+  fn hint_last(&mut self, last: NasIndex) {
+    if let NasIndex::ElementSidedPoint(esp) = last {
+      self.reader.hint_last(esp);
+      self.cur_row = Some(esp);
+    }
+  }
+
+  fn last_row_index(&self) -> Option<NasIndex> {
+    return self.cur_row.map(|r| r.into());
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let (response, row) = self.reader.consume(line);
+    if let Some((at, values)) = row {
+      self.data.insert_raw(at, &values, line_no);
+      self.cur_row = Some(at);
     }
+    return response;
   }
 }
 
 converting_decoder!(
-  "Decoder for ELAS1 element strains",
-  Elas1StrainsDecoder,
-  Elas1StressesDecoder,
+  "Block decoder for strains in hexahedral solid elements.",
+  ChexaStrainsDecoder,
+  ChexaStressesDecoder,
   f64,
-  (ElementRef, ElementRef),
-  (SingleStrain, SingleStress),
-  BlockType::Elas1Strains,
-  1
+  (ElementSidedPoint, ElementSidedPoint),
+  (ChexaStrainField, ChexaStressField),
+  BlockType::ChexaStrains,
+  12
 );
 
-/// This decodes a bush forces block.
-pub(crate) struct BushForcesDecoder {
-  /// The flavour of F06 file we're decoding displacements for.
+/// The solid elements whose stress tables keep von Mises but not the
+/// octahedral shear stress, for `SolidStressesDecoder`.
+pub(crate) trait SolidStressKind {
+  /// The columns of their stress tables, a subset of the CHEXA's.
+  type Field: IndexType + Into<ChexaStressField>;
+  /// The block type they're decoded to.
+  const BLOCK_TYPE: BlockType;
+  /// The element type.
+  const ETYPE: ElementType;
+  /// How many of the corners printed for an element go on the bottom side:
+  /// those of the first face, or all of them if there's no opposite face.
+  const BOTTOM_CORNERS: usize;
+
+  /// Returns the columns, in canonical order.
+  fn canonical_cols() -> BTreeMap<Self::Field, usize>;
+}
+
+/// Pentahedral solid elements, with the centroid and (optionally) the six
+/// corners, three on each side.
+pub(crate) struct Cpenta;
+
+impl SolidStressKind for Cpenta {
+  type Field = CpentaStressField;
+  const BLOCK_TYPE: BlockType = BlockType::CpentaStresses;
+  const ETYPE: ElementType = ElementType::Penta;
+  const BOTTOM_CORNERS: usize = 3;
+
+  fn canonical_cols() -> BTreeMap<Self::Field, usize> {
+    return CpentaStressField::canonical_cols();
+  }
+}
+
+/// Tetrahedral solid elements, with the centroid and (optionally) the
+/// corners. There are no opposite faces, so every point goes on the bottom
+/// side, even the mid-side ones of a CTETRA10 if they're printed.
+pub(crate) struct Ctetra;
+
+impl SolidStressKind for Ctetra {
+  type Field = CtetraStressField;
+  const BLOCK_TYPE: BlockType = BlockType::CtetraStresses;
+  const ETYPE: ElementType = ElementType::Tetra;
+  const BOTTOM_CORNERS: usize = 10;
+
+  fn canonical_cols() -> BTreeMap<Self::Field, usize> {
+    return CtetraStressField::canonical_cols();
+  }
+}
+
+/// Decoder for the stresses in solid elements other than the CHEXA. Only von
+/// Mises is kept, and it's left out where the octahedral shear stress is
+/// printed instead.
+pub(crate) struct SolidStressesDecoder<K: SolidStressKind> {
+  /// Reads the lines.
+  reader: SolidStressReader,
+  /// The inner block of data.
+  data: RowBlock<f64, ElementSidedPoint, K::Field, SOLID_STRESS_WIDTH>,
+  /// The last row inserted.
+  cur_row: Option<ElementSidedPoint>
+}
+
+/// The number of columns in the stress tables of `SolidStressesDecoder`.
+const SOLID_STRESS_WIDTH: usize = 11;
+
+/// Decoder for the stresses in pentahedral solid elements.
+pub(crate) type CpentaStressesDecoder = SolidStressesDecoder<Cpenta>;
+
+/// Decoder for the stresses in tetrahedral solid elements.
+pub(crate) type CtetraStressesDecoder = SolidStressesDecoder<Ctetra>;
+
+impl<K: SolidStressKind> BlockDecoder for SolidStressesDecoder<K> {
+  type MatScalar = f64;
+  type RowIndex = ElementSidedPoint;
+  type ColumnIndex = K::Field;
+  const MATWIDTH: usize = SOLID_STRESS_WIDTH;
+  const BLOCK_TYPE: BlockType = K::BLOCK_TYPE;
+
+  fn new(flavour: Flavour) -> Self {
+    return Self {
+      reader: SolidStressReader::new(flavour, K::ETYPE, K::BOTTOM_CORNERS),
+      data: RowBlock::new(K::canonical_cols()),
+      cur_row: None
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    let principals = [
+      ChexaStressField::PrincipalA,
+      ChexaStressField::PrincipalB,
+      ChexaStressField::PrincipalC,
+      ChexaStressField::MeanPressure
+    ];
+    let unprinted = self.data.col_indexes()
+      .keys()
+      .copied()
+      .filter(|col| {
+        let col: ChexaStressField = (*col).into();
+        return (self.reader.octahedral && col == ChexaStressField::VonMises)
+          || (!self.reader.principals_printed() && principals.contains(&col));
+      })
+      .map(Into::<NasIndex>::into)
+      .collect::<BTreeSet<_>>();
+    let mut block = self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+    block.remove_columns(&unprinted);
+    return block;
+  }
+
+  fn hint_last(&mut self, last: NasIndex) {
+    if let NasIndex::ElementSidedPoint(esp) = last {
+      self.reader.hint_last(esp);
+      self.cur_row = Some(esp);
+    }
+  }
+
+  fn last_row_index(&self) -> Option<NasIndex> {
+    return self.cur_row.map(|r| r.into());
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let (response, row) = self.reader.consume(line);
+    if let Some((at, values)) = row {
+      // all but the octahedral shear stress
+      let mut kept = [0.0; SOLID_STRESS_WIDTH];
+      kept[..7].copy_from_slice(&values[..7]);
+      kept[7..].copy_from_slice(&values[8..]);
+      self.data.insert_raw(at, &kept, line_no);
+      self.cur_row = Some(at);
+    }
+    return response;
+  }
+}
+
+converting_decoder!(
+  "Block decoder for strains in pentahedral solid elements.",
+  CpentaStrainsDecoder,
+  CpentaStressesDecoder,
+  f64,
+  (ElementSidedPoint, ElementSidedPoint),
+  (CpentaStrainField, CpentaStressField),
+  BlockType::CpentaStrains,
+  11
+);
+
+converting_decoder!(
+  "Block decoder for strains in tetrahedral solid elements.",
+  CtetraStrainsDecoder,
+  CtetraStressesDecoder,
+  f64,
+  (ElementSidedPoint, ElementSidedPoint),
+  (CtetraStrainField, CtetraStressField),
+  BlockType::CtetraStrains,
+  11
+);
+
+/// Decoder for "forces in shear panels" tables. Each element takes two lines:
+/// the first has its ID and the forces on the corners, the second the kick
+/// forces and the shear flows. They're joined into a single row.
+pub(crate) struct CshearForcesDecoder {
+  /// The flavour of file we're decoding for.
   flavour: Flavour,
-  /// The displacement data.
-  data: RowBlock<f64, ElementRef, Dof, { Self::MATWIDTH }>
+  /// The data within.
+  data: RowBlock<f64, ElementRef, CshearForceField, { Self::MATWIDTH }>,
+  /// An element whose second line hasn't been seen yet, and its values.
+  pending: Option<(usize, Vec<f64>)>
 }
 
-impl BlockDecoder for BushForcesDecoder {
+impl BlockDecoder for CshearForcesDecoder {
   type MatScalar = f64;
   type RowIndex = ElementRef;
-  type ColumnIndex = Dof;
-  const MATWIDTH: usize = SIXDOF;
-  const BLOCK_TYPE: BlockType = BlockType::BushForces;
+  type ColumnIndex = CshearForceField;
+  const MATWIDTH: usize = 16;
+  const BLOCK_TYPE: BlockType = BlockType::CshearForces;
 
   fn new(flavour: Flavour) -> Self {
     return Self {
       flavour,
-      data: RowBlock::new(dof_cols())
+      data: RowBlock::new(CshearForceField::canonical_cols()),
+      pending: None
     };
   }
 
@@ -1248,44 +2674,1283 @@ impl BlockDecoder for BushForcesDecoder {
     subcase: usize,
     line_range: Option<(usize, usize)>
   ) -> FinalBlock {
+    if let Some((eid, _)) = self.pending {
+      warn!("shear panel {} is missing its second line!", eid);
+    }
     return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
   }
 
-  fn consume(&mut self, line: &str) -> LineResponse {
-    let dofs: [f64; SIXDOF] = if let Some(arr) = extract_reals(line) {
-      arr
-    } else {
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let fields = line_breakdown(line).collect::<Vec<_>>();
+    let (reals, ints) = split_numbers(&fields);
+    if reals.is_empty() {
       return LineResponse::Useless;
+    }
+    let eid = ints.into_iter().rfind(|i| *i > 0);
+    let joined = join_wrapped(&mut self.pending, eid, reals, "shear panel");
+    let Some((eid, values)) = joined else {
+      return LineResponse::Abort;
+    };
+    match values.len() {
+      Self::MATWIDTH => {
+        let mut arr = [0.0; Self::MATWIDTH];
+        arr.copy_from_slice(&values);
+        let eref = ElementRef { eid, etype: Some(ElementType::Shear) };
+        self.data.insert_raw(eref, &arr, line_no);
+      },
+      n if n < Self::MATWIDTH => self.pending = Some((eid, values)),
+      n => {
+        warn!("got {} floats for shear panel {}", n, eid);
+        return LineResponse::Useless;
+      }
+    };
+    return LineResponse::Data;
+  }
+}
+
+/// Decoder for "stresses in shear panels" tables. These print two elements
+/// side by side, each with its maximum shear, average shear and margin of
+/// safety; margins left blank or printed as "N/A" become NaN.
+pub(crate) struct CshearStressesDecoder {
+  /// The flavour of file we're decoding for.
+  flavour: Flavour,
+  /// The data within.
+  data: RowBlock<f64, ElementRef, CshearStressField, { Self::MATWIDTH }>
+}
+
+impl CshearStressesDecoder {
+  /// Stores an element's values, if there are enough of them.
+  fn store(&mut self, eid: usize, vals: &[f64], line_no: usize) -> bool {
+    let arr: [f64; 3] = match vals {
+      [max, avg] => [*avg, *max, f64::NAN],
+      [max, avg, ms] => [*avg, *max, *ms],
+      _ => return false
+    };
+    let eref = ElementRef { eid, etype: Some(ElementType::Shear) };
+    self.data.insert_raw(eref, &arr, line_no);
+    return true;
+  }
+}
+
+impl BlockDecoder for CshearStressesDecoder {
+  type MatScalar = f64;
+  type RowIndex = ElementRef;
+  type ColumnIndex = CshearStressField;
+  const MATWIDTH: usize = 3;
+  const BLOCK_TYPE: BlockType = BlockType::CshearStresses;
+
+  fn new(flavour: Flavour) -> Self {
+    return Self {
+      flavour,
+      data: RowBlock::new(CshearStressField::canonical_cols())
     };
-    if let Some(eid) = last_natural(line) {
-      let eref = ElementRef { eid, etype: Some(ElementType::Bush) };
-      self.data.insert_raw(eref, &dofs);
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let mut added = 0;
+    let mut current: Option<(usize, Vec<f64>)> = None;
+    for field in line_breakdown(line) {
+      let x = match field {
+        LineField::Integer(i) if i > 0 => {
+          if let Some((eid, vals)) = current.take() {
+            added += self.store(eid, &vals, line_no) as usize;
+          }
+          current = Some((i as usize, Vec::new()));
+          continue;
+        },
+        // carriage control
+        LineField::Integer(0) if current.is_none() => continue,
+        LineField::Real(x) => x,
+        LineField::NoIdea("N/A") => f64::NAN,
+        _ => return LineResponse::Useless
+      };
+      match current.as_mut() {
+        Some((_, vals)) => vals.push(x),
+        None => return LineResponse::Useless
+      };
+    }
+    if let Some((eid, vals)) = current.take() {
+      added += self.store(eid, &vals, line_no) as usize;
+    }
+    if added > 0 {
       return LineResponse::Data;
     } else {
-      warn!("bush line has six floats but no EID!");
-      return LineResponse::Abort;
+      return LineResponse::Useless;
     }
   }
 }
 
-converting_decoder!(
-  "Decoder for BUSH stresses",
-  BushStressesDecoder,
-  BushForcesDecoder,
-  f64,
-  (ElementRef, ElementRef),
-  (Dof, Dof),
-  BlockType::BushStresses,
-  6
-);
+/// Returns the value a gap status is stored as: its index in `GAP_STATUSES`,
+/// so 0 for an open gap, 1 for a closed one and 2 for one that slides. Some
+/// solvers say a gap that's closed and not sliding sticks.
+fn gap_status(field: &LineField) -> Option<f64> {
+  let name = match field {
+    LineField::NoIdea("CLOSE" | "STICK") => "CLOSED",
+    LineField::NoIdea(name) => name,
+    _ => return None
+  };
+  return GAP_STATUSES.iter().position(|s| *s == name).map(|i| i as f64);
+}
 
-converting_decoder!(
-  "Decoder for BUSH strains",
-  BushStrainsDecoder,
-  BushStressesDecoder,
-  f64,
-  (ElementRef, ElementRef),
-  (Dof, Dof),
-  BlockType::BushStrains,
-  6
-);
+/// Decoder for "forces in gap elements" tables. Each element takes a line
+/// with its ID, the forces and the displacements in the element system and
+/// its status.
+pub(crate) struct CgapForcesDecoder {
+  /// The data within.
+  data: RowBlock<f64, ElementRef, CgapForceField, { Self::MATWIDTH }>
+}
+
+impl BlockDecoder for CgapForcesDecoder {
+  type MatScalar = f64;
+  type RowIndex = ElementRef;
+  type ColumnIndex = CgapForceField;
+  const MATWIDTH: usize = 9;
+  const BLOCK_TYPE: BlockType = BlockType::CgapForces;
+
+  fn new(_flavour: Flavour) -> Self {
+    return Self {
+      data: RowBlock::new(CgapForceField::canonical_cols())
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let fields = line_breakdown(line).collect::<Vec<_>>();
+    let eid = match fields.first() {
+      Some(LineField::Integer(eid)) if *eid > 0 => *eid as usize,
+      _ => return LineResponse::Useless
+    };
+    let (reals, _) = split_numbers(&fields);
+    if reals.len() < Self::MATWIDTH - 1 {
+      return LineResponse::Useless;
+    }
+    let status = match fields.iter().rev().find_map(gap_status) {
+      Some(status) => status,
+      None => {
+        warn!("gap element {} has no status!", eid);
+        f64::NAN
+      }
+    };
+    let eref = ElementRef { eid, etype: Some(ElementType::Gap) };
+    let mut row = [status; Self::MATWIDTH];
+    row[..Self::MATWIDTH-1].copy_from_slice(&reals[..Self::MATWIDTH-1]);
+    self.data.insert_raw(eref, &row, line_no);
+    return LineResponse::Data;
+  }
+}
+
+/// Returns the side of a ply printed among the fields of a line of a table
+/// for layered composite elements, or its middle if none is.
+fn ply_side(fields: &[LineField]) -> ElementSide {
+  return fields.iter()
+    .find_map(|lf| match lf {
+      LineField::NoIdea(s) => decode_side(s),
+      _ => None
+    })
+    .unwrap_or(ElementSide::Middle);
+}
+
+/// Decoder for the failure indices tables of layered composite elements. They
+/// come in two layouts:
+///   - by the failure theory of each element: each ply gets a row, and so do
+///     the bonds between plies (the inter-laminar failure indices) and the
+///     maximum over all plies of each element. Strength ratios are NaN where
+///     they're not printed.
+///   - by criterion (Tsai-Wu, Tsai-Hill, Hoffman, maximum stress and maximum
+///     strain): each ply gets a row, with the indices and whether the ply
+///     fails ("FAIL" or "NO FAIL"), stored as its index in `PLY_STATUSES`.
+///     It's NaN if the line doesn't say.
+///
+/// Blocks only have the columns of their layout. Plies are at their middle,
+/// unless a side is printed after the ply ID.
+pub(crate) struct CompositeFailureIndicesDecoder {
+  /// The inner block of data, for indices by theory.
+  data: RowBlock<
+    f64, ElementPlyRef, CompositeFailureField, { Self::MATWIDTH }
+  >,
+  /// The inner block of data, for indices by criterion, if the header says
+  /// that's the layout.
+  criteria: Option<
+    RowBlock<f64, ElementPlyRef, CompositeFailureField, { Self::CRITERIA }>
+  >,
+  /// Current row reference.
+  cur_row: Option<<Self as BlockDecoder>::RowIndex>,
+  /// Element type, hinted by the header.
+  etype: Option<ElementType>,
+  /// Where the column with the maxima over all plies begins, if known.
+  max_col: Option<usize>,
+  /// The failure theories found, in order.
+  theories: Vec<String>
+}
+
+impl CompositeFailureIndicesDecoder {
+  /// The number of columns of the indices by criterion.
+  const CRITERIA: usize = 6;
+
+  /// Makes column indexes for some columns, in order.
+  fn cols(
+    cols: &[CompositeFailureField]
+  ) -> BTreeMap<CompositeFailureField, usize> {
+    return cols.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+  }
+
+  /// Consumes a line of a table of indices by criterion.
+  fn consume_criteria(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let Some(criteria) = self.criteria.as_mut() else {
+      return LineResponse::Abort;
+    };
+    let (indices, status) = match extract_reals_with_status::<5>(line) {
+      Some(found) => found,
+      None => return LineResponse::Useless
+    };
+    let (eid, ply) = match (nth_natural(line, 0), nth_natural(line, 1)) {
+      (Some(eid), Some(ply)) => (eid, ply),
+      _ => return LineResponse::Useless
+    };
+    let failed = match status.as_deref() {
+      Some(status) => match PLY_STATUSES.iter().position(|s| *s == status) {
+        Some(code) => code as f64,
+        None => return LineResponse::Useless
+      },
+      None => {
+        warn!("ply {} of element {} has no failure status!", ply, eid);
+        f64::NAN
+      }
+    };
+    let fields = line_breakdown(line).collect::<Vec<_>>();
+    let row = ElementPlyRef {
+      element: ElementRef { eid, etype: self.etype },
+      layer: CompositeLayer::Ply(ply),
+      side: ply_side(&fields)
+    };
+    let mut vals = [failed; Self::CRITERIA];
+    vals[..indices.len()].copy_from_slice(&indices);
+    self.cur_row = Some(row);
+    criteria.insert_raw(row, &vals, line_no);
+    return LineResponse::Data;
+  }
+}
+
+impl BlockDecoder for CompositeFailureIndicesDecoder {
+  type MatScalar = f64;
+  type RowIndex = ElementPlyRef;
+  type ColumnIndex = CompositeFailureField;
+  const MATWIDTH: usize = 2;
+  const BLOCK_TYPE: BlockType = BlockType::CompositeFailureIndices;
+
+  fn new(_flavour: Flavour) -> Self {
+    return Self {
+      data: RowBlock::new(Self::cols(&[
+        CompositeFailureField::FailureIndex,
+        CompositeFailureField::StrengthRatio
+      ])),
+      criteria: None,
+      cur_row: None,
+      etype: None,
+      max_col: None,
+      theories: Vec::new()
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    if let Some(criteria) = self.criteria {
+      return criteria.finalise(Self::BLOCK_TYPE, subcase, line_range);
+    }
+    let mut block = self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+    if !self.theories.is_empty() {
+      block.metadata.insert(
+        META_FAILURE_THEORY.to_owned(),
+        self.theories.join(", ")
+      );
+    }
+    return block;
+  }
+
+  fn good_header(&mut self, header: &str) -> bool {
+    self.etype = nth_etype(header, 0);
+    if header.contains("FAILURE INDICES IN") {
+      self.criteria = Some(RowBlock::new(Self::cols(&[
+        CompositeFailureField::TsaiWu,
+        CompositeFailureField::TsaiHill,
+        CompositeFailureField::Hoffman,
+        CompositeFailureField::MaxStress,
+        CompositeFailureField::MaxStrain,
+        CompositeFailureField::Failed
+      ])));
+    }
+    return true;
+  }
+
+  fn hint_last(&mut self, last: NasIndex) {
+    if let NasIndex::ElementPlyRef(epr) = last {
+      self.cur_row = Some(epr);
+    }
+  }
+
+  fn last_row_index(&self) -> Option<NasIndex> {
+    return self.cur_row.map(|epr| epr.into());
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    if self.criteria.is_some() {
+      return self.consume_criteria(line, line_no);
+    }
+    // the column headers tell us where the maxima are printed.
+    if let Some(col) = line.find("MAX OF") {
+      self.max_col = Some(col);
+      return LineResponse::Metadata;
+    }
+    let fields = line_breakdown(line).collect::<Vec<_>>();
+    let ints = fields.iter()
+      .filter_map(|lf| {
+        if let LineField::Integer(i) = lf { Some(*i as usize) } else { None }
+      }).collect::<Vec<_>>();
+    let reals = fields.iter()
+      .filter_map(|lf| {
+        if let LineField::Real(x) = lf { Some(*x) } else { None }
+      }).collect::<Vec<_>>();
+    if reals.is_empty() || reals.len() > Self::MATWIDTH {
+      return LineResponse::Useless;
+    }
+    let side = ply_side(&fields);
+    let row = match (ints.as_slice(), self.cur_row) {
+      // first ply of an element, with its failure theory.
+      ([eid, ply], _) => {
+        let theory = fields.iter().find_map(|lf| match lf {
+          LineField::NoIdea(s) if decode_side(s).is_none() => {
+            Some(*s).filter(|s| s.starts_with(char::is_alphabetic))
+          },
+          _ => None
+        });
+        if let Some(theory) = theory {
+          if !self.theories.iter().any(|t| t == theory) {
+            self.theories.push(theory.to_owned());
+          }
+        }
+        ElementPlyRef {
+          element: ElementRef { eid: *eid, etype: self.etype },
+          layer: CompositeLayer::Ply(*ply),
+          side
+        }
+      },
+      // another ply of the same element.
+      ([ply], Some(cur)) => ElementPlyRef {
+        element: cur.element,
+        layer: CompositeLayer::Ply(*ply),
+        side
+      },
+      // the bond after a ply, or the maximum for the element.
+      ([], Some(cur)) => {
+        let indent = line.len() - line.trim_start().len();
+        let is_max = match (self.max_col, cur.layer) {
+          (Some(col), _) => indent >= col,
+          (None, layer) => matches!(layer, CompositeLayer::Bond(_))
+        };
+        let layer = match (is_max, cur.layer.ply()) {
+          (true, _) => CompositeLayer::AllPlies,
+          (false, Some(ply)) => CompositeLayer::Bond(ply),
+          (false, None) => {
+            warn!("bond line after the maximum at {}", line);
+            return LineResponse::Abort;
+          }
+        };
+        ElementPlyRef { element: cur.element, layer, side }
+      },
+      _ => {
+        warn!("couldn't construct a ply reference at {}", line);
+        return LineResponse::Abort;
+      }
+    };
+    let vals = [reals[0], reals.get(1).copied().unwrap_or(f64::NAN)];
+    self.cur_row = Some(row);
+    self.data.insert_raw(row, &vals, line_no);
+    return LineResponse::Data;
+  }
+}
+
+/// Decoder for the stresses in the plies of layered composite elements. Every
+/// line has the element ID and the ply ID, then the stresses in the ply: at
+/// its middle, unless a side is printed after the ply ID. MYSTRAN prints the
+/// von Mises stress last; it's zero where it's not printed.
+pub(crate) struct CompositePlyStressDecoder {
+  /// The inner block of data.
+  data: RowBlock<
+    f64, ElementPlyRef, CompositeStressField, { Self::MATWIDTH }
+  >,
+  /// Current row reference.
+  cur_row: Option<<Self as BlockDecoder>::RowIndex>,
+  /// Element type, hinted by the header.
+  etype: Option<ElementType>,
+  /// Were von Mises stresses printed?
+  von_mises: bool
+}
+
+impl BlockDecoder for CompositePlyStressDecoder {
+  type MatScalar = f64;
+  type RowIndex = ElementPlyRef;
+  type ColumnIndex = CompositeStressField;
+  const MATWIDTH: usize = 10;
+  const BLOCK_TYPE: BlockType = BlockType::CompositePlystresses;
+
+  fn new(_flavour: Flavour) -> Self {
+    return Self {
+      data: RowBlock::new(CompositeStressField::canonical_cols()),
+      cur_row: None,
+      etype: None,
+      von_mises: false
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    let mut block = self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+    if !self.von_mises {
+      let von_mises = NasIndex::from(CompositeStressField::VonMises);
+      block.remove_columns(&BTreeSet::from([von_mises]));
+    }
+    return block;
+  }
+
+  fn good_header(&mut self, header: &str) -> bool {
+    self.etype = nth_etype(header, 0);
+    return true;
+  }
+
+  fn hint_last(&mut self, last: NasIndex) {
+    if let NasIndex::ElementPlyRef(epr) = last {
+      self.cur_row = Some(epr);
+    }
+  }
+
+  fn last_row_index(&self) -> Option<NasIndex> {
+    return self.cur_row.map(|epr| epr.into());
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let fields = line_breakdown(line).collect::<Vec<_>>();
+    // the IDs (and the side) come before the first stress
+    let first_real = fields.iter()
+      .position(|lf| matches!(lf, LineField::Real(_)))
+      .unwrap_or(fields.len());
+    let (ids, values) = fields.split_at(first_real);
+    let ints = ids.iter()
+      .filter_map(|lf| {
+        if let LineField::Integer(i) = lf { Some(*i) } else { None }
+      }).collect::<Vec<_>>();
+    let reals = values.iter()
+      .filter_map(|lf| {
+        if let LineField::Real(x) = lf { Some(*x) } else { None }
+      }).collect::<Vec<_>>();
+    // without von Mises, there's one stress fewer
+    let widths = Self::MATWIDTH-1..=Self::MATWIDTH;
+    if reals.len() != values.len() || !widths.contains(&reals.len()) {
+      return LineResponse::Useless;
+    }
+    let (eid, ply) = match ints.as_slice() {
+      [eid, ply] => match (usize::try_from(*eid), usize::try_from(*ply)) {
+        (Ok(eid), Ok(ply)) => (eid, ply),
+        _ => return LineResponse::Useless
+      },
+      _ => return LineResponse::Useless
+    };
+    let side = ply_side(ids);
+    let row = ElementPlyRef {
+      element: ElementRef { eid, etype: self.etype },
+      layer: CompositeLayer::Ply(ply),
+      side
+    };
+    let mut vals = [f64::NAN; Self::MATWIDTH];
+    vals[..reals.len()].copy_from_slice(&reals);
+    self.von_mises |= reals.len() == Self::MATWIDTH;
+    self.cur_row = Some(row);
+    self.data.insert_raw(row, &vals, line_no);
+    return LineResponse::Data;
+  }
+}
+
+/// Decoder for the stresses at grid points on a surface (GPSTRESS output).
+/// Each grid point takes a line per fibre: the first one with its ID and the
+/// ID of the element the stresses are for (zero for the average of all of
+/// them), and the others with just the fibre. The surface ID comes from the
+/// header; it's zero if the header doesn't say.
+pub(crate) struct GridPointStressDecoder {
+  /// The inner block of data.
+  data: RowBlock<
+    f64, GridSurfacePoint, GridStressField, { Self::MATWIDTH }
+  >,
+  /// Current row reference.
+  cur_row: Option<<Self as BlockDecoder>::RowIndex>,
+  /// The surface ID, from the header.
+  surface: usize
+}
+
+impl BlockDecoder for GridPointStressDecoder {
+  type MatScalar = f64;
+  type RowIndex = GridSurfacePoint;
+  type ColumnIndex = GridStressField;
+  const MATWIDTH: usize = 8;
+  const BLOCK_TYPE: BlockType = BlockType::GridPointStresses;
+
+  fn new(_flavour: Flavour) -> Self {
+    return Self {
+      data: RowBlock::new(GridStressField::canonical_cols()),
+      cur_row: None,
+      surface: 0
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+  }
+
+  fn good_header(&mut self, header: &str) -> bool {
+    self.surface = header.split_once("SURFACE")
+      .and_then(|(_, after)| after.split_whitespace().next())
+      .and_then(|n| n.parse().ok())
+      .unwrap_or(0);
+    return true;
+  }
+
+  fn hint_last(&mut self, last: NasIndex) {
+    if let NasIndex::GridSurfacePoint(gsp) = last {
+      self.cur_row = Some(gsp);
+    }
+  }
+
+  fn last_row_index(&self) -> Option<NasIndex> {
+    return self.cur_row.map(|gsp| gsp.into());
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let fields = line_breakdown(line).collect::<Vec<_>>();
+    // the IDs and the fibre come before the first stress
+    let first_real = fields.iter()
+      .position(|lf| matches!(lf, LineField::Real(_)))
+      .unwrap_or(fields.len());
+    let (ids, values) = fields.split_at(first_real);
+    let mut ints = ids.iter()
+      .filter_map(|lf| {
+        if let LineField::Integer(i) = lf { Some(*i) } else { None }
+      }).collect::<Vec<_>>();
+    if line.starts_with('0') && ints.first() == Some(&0) {
+      // carriage control
+      ints.remove(0);
+    }
+    let reals = values.iter()
+      .filter_map(|lf| {
+        if let LineField::Real(x) = lf { Some(*x) } else { None }
+      }).collect::<Vec<_>>();
+    let vals: [f64; Self::MATWIDTH] = match reals.try_into() {
+      Ok(vals) if values.len() == Self::MATWIDTH => vals,
+      _ => return LineResponse::Useless
+    };
+    let side = ids.iter().find_map(|lf| match lf {
+      LineField::NoIdea(s) => decode_side(s),
+      _ => None
+    });
+    let side = match side {
+      Some(side) => side,
+      None => return LineResponse::Useless
+    };
+    let (grid_point, element) = match (ints.as_slice(), self.cur_row) {
+      // first fibre of a grid point, with the element ID.
+      ([gid, eid], _) => match (usize::try_from(*gid), usize::try_from(*eid)) {
+        (Ok(gid), Ok(eid)) => {
+          let element = Some(ElementRef { eid, etype: None })
+            .filter(|_| eid > 0);
+          (GridPointRef { gid }, element)
+        },
+        _ => return LineResponse::Useless
+      },
+      // another fibre of the same grid point and element.
+      ([], Some(gsp)) => (gsp.grid_point, gsp.element),
+      _ => return LineResponse::Useless
+    };
+    let row = GridSurfacePoint {
+      grid_point,
+      surface: self.surface,
+      element,
+      side
+    };
+    self.cur_row = Some(row);
+    self.data.insert_raw(row, &vals, line_no);
+    return LineResponse::Data;
+  }
+}
+
+/// Decoder for the strain energy of each element (ESE output). The element
+/// type comes from the `ELEMENT-TYPE = ...` line above each table, or from a
+/// field of the line itself after the element ID. Some solvers don't print
+/// the percentage of the total or the energy density, which are then NaN.
+/// The subtotals per element type are skipped, but the total energy of all
+/// elements is kept as metadata.
+pub(crate) struct StrainEnergyDecoder {
+  /// The inner block of data.
+  data: RowBlock<f64, ElementRef, StrainEnergyField, { Self::MATWIDTH }>,
+  /// Current row reference.
+  cur_row: Option<<Self as BlockDecoder>::RowIndex>,
+  /// Element type of the current table.
+  etype: Option<ElementType>,
+  /// Total energy of all elements, as printed, if it was.
+  total: Option<String>
+}
+
+impl BlockDecoder for StrainEnergyDecoder {
+  type MatScalar = f64;
+  type RowIndex = ElementRef;
+  type ColumnIndex = StrainEnergyField;
+  const MATWIDTH: usize = 3;
+  const BLOCK_TYPE: BlockType = BlockType::StrainEnergy;
+
+  fn new(_flavour: Flavour) -> Self {
+    return Self {
+      data: RowBlock::new(StrainEnergyField::canonical_cols()),
+      cur_row: None,
+      etype: None,
+      total: None
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    let mut block = self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+    if let Some(total) = self.total {
+      block.metadata.insert(META_TOTAL_ENERGY.to_owned(), total);
+    }
+    return block;
+  }
+
+  fn hint_last(&mut self, last: NasIndex) {
+    if let NasIndex::ElementRef(er) = last {
+      self.cur_row = Some(er);
+    }
+  }
+
+  fn last_row_index(&self) -> Option<NasIndex> {
+    return self.cur_row.map(|er| er.into());
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let etype_line = line.contains("ELEMENT-TYPE");
+    if etype_line {
+      self.etype = nth_etype(line, 0);
+    }
+    // the total of all elements, next to the element type or in its own row,
+    // kept as printed.
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let total = match (line.split_once("IN PROBLEM"), words.as_slice()) {
+      (Some((_, after)), _) => after.split_whitespace()
+        .find(|w| decode_nasfloat(w).is_some()),
+      (None, ["TOTAL", value]) => Some(*value)
+        .filter(|w| decode_nasfloat(w).is_some()),
+      _ => None
+    };
+    if let Some(total) = total {
+      self.total = Some(total.to_owned());
+    }
+    if etype_line || total.is_some() {
+      return LineResponse::Metadata;
+    }
+    let mut fields = line_breakdown(line);
+    let eid = match fields.next() {
+      Some(LineField::Integer(eid)) if eid > 0 => eid as usize,
+      _ => return LineResponse::Useless
+    };
+    let mut etype = self.etype;
+    let mut reals: Vec<f64> = Vec::new();
+    for field in fields {
+      match field {
+        LineField::Real(x) => reals.push(x),
+        LineField::ElementType(et) if reals.is_empty() => etype = Some(et),
+        _ => return LineResponse::Useless
+      };
+    }
+    if reals.is_empty() || reals.len() > Self::MATWIDTH {
+      return LineResponse::Useless;
+    }
+    let mut vals = [f64::NAN; Self::MATWIDTH];
+    for (val, x) in vals.iter_mut().zip(reals) {
+      *val = x;
+    }
+    let row = ElementRef { eid, etype };
+    self.cur_row = Some(row);
+    self.data.insert_raw(row, &vals, line_no);
+    return LineResponse::Data;
+  }
+}
+
+/// Breaks down a line of an eigenvalue table: the mode (or root) number, the
+/// extraction order and the reals after them. None if the line doesn't start
+/// like that.
+fn mode_line(line: &str) -> Option<(usize, usize, Vec<f64>)> {
+  let mut fields = line_breakdown(line);
+  let (mode, order) = match (fields.next(), fields.next()) {
+    (Some(LineField::Integer(m)), Some(LineField::Integer(o))) => (m, o),
+    _ => return None
+  };
+  let mut reals = Vec::new();
+  for field in fields {
+    match field {
+      LineField::Real(x) => reals.push(x),
+      _ => return None
+    };
+  }
+  if mode < 1 || order < 0 {
+    return None;
+  }
+  return Some((mode as usize, order as usize, reals));
+}
+
+/// The number of columns in a real eigenvalue table, past the mode number.
+const EIGENVALUE_WIDTH: usize = 6;
+
+/// The analyses that print real eigenvalue tables, which are laid out alike,
+/// for `EigenvalueDecoder`.
+pub(crate) trait EigenvalueKind {
+  /// The block type of their eigenvalue tables.
+  const BLOCK_TYPE: BlockType;
+}
+
+/// Normal modes analyses (SOL 103), whose eigenvalues give the natural
+/// frequencies.
+pub(crate) struct NormalModes;
+
+impl EigenvalueKind for NormalModes {
+  const BLOCK_TYPE: BlockType = BlockType::RealEigenvalues;
+}
+
+/// Linear buckling analyses (SOL 105), whose eigenvalues are load factors.
+pub(crate) struct Buckling;
+
+impl EigenvalueKind for Buckling {
+  const BLOCK_TYPE: BlockType = BlockType::BucklingEigenvalues;
+}
+
+/// Decoder for the real eigenvalues table of an analysis, one row per mode.
+pub(crate) struct EigenvalueDecoder<K: EigenvalueKind> {
+  /// The inner block of data.
+  data: RowBlock<f64, ModeRef, EigenvalueField, EIGENVALUE_WIDTH>,
+  /// The kind of analysis.
+  kind: PhantomData<K>
+}
+
+/// Decoder for the real eigenvalues of a normal modes analysis.
+pub(crate) type RealEigenvalueDecoder = EigenvalueDecoder<NormalModes>;
+
+/// Decoder for the real eigenvalues of a linear buckling analysis.
+pub(crate) type BucklingEigenvalueDecoder = EigenvalueDecoder<Buckling>;
+
+impl<K: EigenvalueKind> BlockDecoder for EigenvalueDecoder<K> {
+  type MatScalar = f64;
+  type RowIndex = ModeRef;
+  type ColumnIndex = EigenvalueField;
+  const MATWIDTH: usize = EIGENVALUE_WIDTH;
+  const BLOCK_TYPE: BlockType = K::BLOCK_TYPE;
+
+  fn new(_flavour: Flavour) -> Self {
+    return Self {
+      data: RowBlock::new(EigenvalueField::canonical_cols()),
+      kind: PhantomData
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let (mode_number, order, reals) = match mode_line(line) {
+      Some(parts) => parts,
+      None => return LineResponse::Useless
+    };
+    if reals.len() != Self::MATWIDTH - 1 {
+      warn!(
+        "got {} f64s for mode {} on line {}",
+        reals.len(),
+        mode_number,
+        line
+      );
+      return LineResponse::Abort;
+    }
+    let mut row = [order as f64; EIGENVALUE_WIDTH];
+    row[1..].copy_from_slice(&reals);
+    self.data.insert_raw(ModeRef { mode_number }, &row, line_no);
+    return LineResponse::Data;
+  }
+}
+
+/// Decoder for the complex eigenvalue summary of a complex modal analysis
+/// (SOL 107), one row per root.
+pub(crate) struct ComplexEigenvalueDecoder {
+  /// The inner block of data.
+  data: RowBlock<f64, ModeRef, ComplexEigenvalueField, { Self::MATWIDTH }>
+}
+
+impl BlockDecoder for ComplexEigenvalueDecoder {
+  type MatScalar = f64;
+  type RowIndex = ModeRef;
+  type ColumnIndex = ComplexEigenvalueField;
+  const MATWIDTH: usize = 5;
+  const BLOCK_TYPE: BlockType = BlockType::ComplexEigenvalues;
+
+  fn new(_flavour: Flavour) -> Self {
+    return Self {
+      data: RowBlock::new(ComplexEigenvalueField::canonical_cols())
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let (mode_number, order, reals) = match mode_line(line) {
+      Some(parts) => parts,
+      None => return LineResponse::Useless
+    };
+    if reals.len() != Self::MATWIDTH - 1 {
+      warn!(
+        "got {} f64s for root {} on line {}",
+        reals.len(),
+        mode_number,
+        line
+      );
+      return LineResponse::Abort;
+    }
+    let mut row = [order as f64; Self::MATWIDTH];
+    row[1..].copy_from_slice(&reals);
+    self.data.insert_raw(ModeRef { mode_number }, &row, line_no);
+    return LineResponse::Data;
+  }
+}
+
+/// Decoder for the modal effective mass table of a modal analysis, one row
+/// per mode with its effective masses in the six directions. The cumulative
+/// percentages after them aren't always printed; the block only has their
+/// columns when they are. The totals row isn't kept.
+pub(crate) struct ModalEffectiveMassDecoder {
+  /// The inner block of data.
+  data: RowBlock<f64, ModeRef, ModalMassField, { Self::MATWIDTH }>,
+  /// Were the cumulative percentages printed?
+  cumulative: bool
+}
+
+impl BlockDecoder for ModalEffectiveMassDecoder {
+  type MatScalar = f64;
+  type RowIndex = ModeRef;
+  type ColumnIndex = ModalMassField;
+  const MATWIDTH: usize = 2 * SIXDOF;
+  const BLOCK_TYPE: BlockType = BlockType::ModalEffectiveMass;
+
+  fn new(_flavour: Flavour) -> Self {
+    return Self {
+      data: RowBlock::new(ModalMassField::canonical_cols()),
+      cumulative: false
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    let mut block = self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+    if !self.cumulative {
+      let cumulative = ModalMassField::all()
+        .iter()
+        .skip(SIXDOF)
+        .map(|f| NasIndex::from(*f))
+        .collect();
+      block.remove_columns(&cumulative);
+    }
+    return block;
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let mut fields = line_breakdown(line);
+    let mode_number = match fields.next() {
+      Some(LineField::Integer(m)) if m > 0 => m as usize,
+      _ => return LineResponse::Useless
+    };
+    let mut reals = Vec::new();
+    for field in fields {
+      match field {
+        LineField::Real(x) => reals.push(x),
+        _ => return LineResponse::Useless
+      };
+    }
+    let mut row = [f64::NAN; Self::MATWIDTH];
+    match reals.len() {
+      SIXDOF => row[..SIXDOF].copy_from_slice(&reals),
+      Self::MATWIDTH => {
+        row.copy_from_slice(&reals);
+        self.cumulative = true;
+      },
+      n => {
+        warn!("got {} f64s for mode {} on line {}", n, mode_number, line);
+        return LineResponse::Abort;
+      }
+    };
+    self.data.insert_raw(ModeRef { mode_number }, &row, line_no);
+    return LineResponse::Data;
+  }
+}
+
+/// The parts of the output of the grid point weight generator, each starting
+/// with a line of its own.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum GpwgSection {
+  /// Before the first part.
+  Start,
+  /// The rigid body mass matrix about the reference point, "M O".
+  RigidBodyMass,
+  /// The transformation to the principal mass axes, "S".
+  MassAxes,
+  /// The masses and centres of gravity along each principal mass axis.
+  CentresOfGravity,
+  /// The inertia matrices after "I(S)" and "I(Q)", and the transformation
+  /// after "Q".
+  Other
+}
+
+/// Decoder for the output of the grid point weight generator: the total mass,
+/// centre of gravity and inertia tensor about the reference point. These come
+/// from matrices printed a row per line between asterisks, so the decoder
+/// keeps track of which part it's in and of the rows read so far.
+///
+/// The mass and inertia come from the rigid body mass matrix, whose lower
+/// right 3x3 part is the inertia tensor. That tensor holds the products of
+/// inertia negated, so they're negated back to be stored with the usual sign
+/// (Ixy being the integral of xy dm). Each coordinate of the centre of
+/// gravity is taken from the row of the next direction (X from Y, Y from Z
+/// and Z from X), since it's not determined along its own.
+pub(crate) struct GpwgDecoder {
+  /// The inner block of data.
+  data: RowBlock<f64, GpwgRef, GpwgField, { Self::MATWIDTH }>,
+  /// The part being read.
+  section: GpwgSection,
+  /// The rows of the current matrix read so far.
+  matrix_rows: usize,
+  /// The lower right part of the rigid body mass matrix, as it's read.
+  inertia: [[f64; 3]; 3]
+}
+
+impl GpwgDecoder {
+  /// Stores a quantity.
+  fn store(&mut self, quantity: GpwgRef, value: f64, line_no: usize) {
+    self.data.insert_raw(quantity, &[value], line_no);
+  }
+
+  /// Reads a row of the rigid body mass matrix.
+  fn rigid_body_row(&mut self, row: &[f64], line_no: usize) -> LineResponse {
+    if row.len() != SIXDOF {
+      return LineResponse::Abort;
+    }
+    let n = self.matrix_rows;
+    self.matrix_rows += 1;
+    match n {
+      0 => self.store(GpwgRef::Mass, row[0], line_no),
+      3..=5 => self.inertia[n - 3].copy_from_slice(&row[3..]),
+      6.. => return LineResponse::Abort,
+      _ => {}
+    };
+    if n == 5 {
+      let i = self.inertia;
+      self.store(GpwgRef::Ixx, i[0][0], line_no);
+      self.store(GpwgRef::Iyy, i[1][1], line_no);
+      self.store(GpwgRef::Izz, i[2][2], line_no);
+      self.store(GpwgRef::Ixy, -i[0][1], line_no);
+      self.store(GpwgRef::Iyz, -i[1][2], line_no);
+      self.store(GpwgRef::Ixz, -i[0][2], line_no);
+    }
+    return LineResponse::Data;
+  }
+}
+
+impl BlockDecoder for GpwgDecoder {
+  type MatScalar = f64;
+  type RowIndex = GpwgRef;
+  type ColumnIndex = GpwgField;
+  const MATWIDTH: usize = 1;
+  const BLOCK_TYPE: BlockType = BlockType::GridPointWeight;
+
+  fn new(_flavour: Flavour) -> Self {
+    return Self {
+      data: RowBlock::new(GpwgField::canonical_cols()),
+      section: GpwgSection::Start,
+      matrix_rows: 0,
+      inertia: [[0.0; 3]; 3]
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    return self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let section = match line.trim() {
+      "M O" => Some(GpwgSection::RigidBodyMass),
+      "S" => Some(GpwgSection::MassAxes),
+      "I(S)" | "I(Q)" | "Q" => Some(GpwgSection::Other),
+      l if l.starts_with("MASS AXIS SYSTEM") => {
+        Some(GpwgSection::CentresOfGravity)
+      },
+      _ => None
+    };
+    if let Some(section) = section {
+      self.section = section;
+      self.matrix_rows = 0;
+      return LineResponse::Metadata;
+    }
+    let fields = line_breakdown(line).collect::<Vec<_>>();
+    let reals = fields.iter()
+      .filter_map(|f| if let LineField::Real(x) = f { Some(*x) } else { None })
+      .collect::<Vec<_>>();
+    let in_matrix = line.trim_start().starts_with('*');
+    return match self.section {
+      GpwgSection::RigidBodyMass if in_matrix => {
+        self.rigid_body_row(&reals, line_no)
+      },
+      GpwgSection::CentresOfGravity => {
+        let (dir, cg) = match (fields.first(), reals.as_slice()) {
+          (Some(LineField::Character(d)), [_, x, y, z]) => (*d, [*x, *y, *z]),
+          _ => return LineResponse::Useless
+        };
+        match dir {
+          'X' => self.store(GpwgRef::CgZ, cg[2], line_no),
+          'Y' => self.store(GpwgRef::CgX, cg[0], line_no),
+          'Z' => self.store(GpwgRef::CgY, cg[1], line_no),
+          _ => return LineResponse::Useless
+        };
+        LineResponse::Data
+      },
+      GpwgSection::MassAxes | GpwgSection::Other if in_matrix => {
+        LineResponse::Metadata
+      },
+      _ => LineResponse::Useless
+    };
+  }
+}
+
+/// Decoder for real eigenvectors, printed like displacements, one block per
+/// mode. The mode number comes from the header and is kept as metadata, so
+/// that the eigenvectors of different modes never merge.
+pub(crate) struct EigenvectorDecoder {
+  /// The inner displacements decoder.
+  inner: DisplacementsDecoder,
+  /// The mode number, from the header.
+  mode: Option<usize>
+}
+
+impl BlockDecoder for EigenvectorDecoder {
+  type MatScalar = f64;
+  type RowIndex = GridPointRef;
+  type ColumnIndex = Dof;
+  const MATWIDTH: usize = SIXDOF;
+  const BLOCK_TYPE: BlockType = BlockType::Eigenvectors;
+
+  fn new(flavour: Flavour) -> Self {
+    return Self { inner: DisplacementsDecoder::new(flavour), mode: None };
+  }
+
+  fn good_header(&mut self, header: &str) -> bool {
+    self.mode = header.split_once("NO.")
+      .and_then(|(_, after)| after.split_whitespace().next())
+      .and_then(|n| n.parse().ok());
+    return true;
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    let mut block = self.inner.unwrap(subcase, line_range);
+    block.block_type = Self::BLOCK_TYPE;
+    if let Some(mode) = self.mode {
+      block.metadata.insert(META_MODE.to_owned(), mode.to_string());
+    }
+    return block;
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    return BlockDecoder::consume(&mut self.inner, line, line_no);
+  }
+}
+
+/// The kinds of vectors whose largest magnitudes per DOF are summarised in
+/// tables of their own, for `MaxSummaryDecoder`.
+pub(crate) trait MaxSummaryKind {
+  /// The block type the summaries are decoded to.
+  const BLOCK_TYPE: BlockType;
+}
+
+impl MaxSummaryKind for Displacement {
+  const BLOCK_TYPE: BlockType = BlockType::MaxDisplacements;
+}
+
+impl MaxSummaryKind for AppliedLoad {
+  const BLOCK_TYPE: BlockType = BlockType::MaxAppliedLoads;
+}
+
+impl MaxSummaryKind for SpcForce {
+  const BLOCK_TYPE: BlockType = BlockType::MaxSpcForces;
+}
+
+/// Decoder for the tables of maximums per DOF Simcenter prints after a
+/// subcase's vectors (e.g. "MAXIMUM  DISPLACEMENTS"). Each row is for a
+/// subcase or load set (printed as "SUBCASE/DAREA ID"), with the largest
+/// magnitude in each DOF, and can be followed by a line with the grid points
+/// where they occur. The grid point IDs are kept in columns of their own,
+/// holding whole numbers, which are left out of the block when the table
+/// doesn't print that line.
+pub(crate) struct MaxSummaryDecoder<K: MaxSummaryKind> {
+  /// The inner block of data.
+  data: RowBlock<f64, CaseRef, MaxSummaryField, { 2 * SIXDOF }>,
+  /// The row read last, which the grid points are for.
+  last: Option<CaseRef>,
+  /// Were the grid points printed?
+  grids: bool,
+  /// The kind of vector summarised.
+  kind: PhantomData<K>
+}
+
+impl<K: MaxSummaryKind> MaxSummaryDecoder<K> {
+  /// Reads a line with the magnitudes for a subcase or load set, after the
+  /// carriage control character, if any. Returns None for any other line.
+  fn magnitudes(fields: &[LineField]) -> Option<(CaseRef, [f64; SIXDOF])> {
+    let first_real = fields.iter()
+      .position(|f| matches!(f, LineField::Real(_)))?;
+    let case = match fields[..first_real].last() {
+      Some(LineField::Integer(c)) if *c > 0 => CaseRef(*c as usize),
+      _ => return None
+    };
+    let reals = fields[first_real..].iter()
+      .map(|f| if let LineField::Real(x) = f { Some(*x) } else { None })
+      .collect::<Option<Vec<_>>>()?;
+    return Some((case, reals.try_into().ok()?));
+  }
+
+  /// Reads a line with the grid points where the maximums occur, after the
+  /// carriage control character, if any. Returns None for any other line.
+  fn grids(fields: &[LineField]) -> Option<[usize; SIXDOF]> {
+    let fields = match fields {
+      [LineField::Integer(0), rest @ ..] if rest.len() == SIXDOF => rest,
+      _ => fields
+    };
+    let gids = fields.iter()
+      .map(|f| match f {
+        LineField::Integer(i) if *i >= 0 => Some(*i as usize),
+        _ => None
+      })
+      .collect::<Option<Vec<_>>>()?;
+    return gids.try_into().ok();
+  }
+}
+
+impl<K: MaxSummaryKind> BlockDecoder for MaxSummaryDecoder<K> {
+  type MatScalar = f64;
+  type RowIndex = CaseRef;
+  type ColumnIndex = MaxSummaryField;
+  const MATWIDTH: usize = 2 * SIXDOF;
+  const BLOCK_TYPE: BlockType = K::BLOCK_TYPE;
+
+  fn new(_flavour: Flavour) -> Self {
+    return Self {
+      data: RowBlock::new(MaxSummaryField::canonical_cols()),
+      last: None,
+      grids: false,
+      kind: PhantomData
+    };
+  }
+
+  fn unwrap(
+    self,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    let mut block = self.data.finalise(Self::BLOCK_TYPE, subcase, line_range);
+    if !self.grids {
+      let grids = MaxSummaryField::all()
+        .iter()
+        .filter(|f| f.is_grid())
+        .map(|f| NasIndex::from(*f))
+        .collect();
+      block.remove_columns(&grids);
+    }
+    return block;
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let fields = line_breakdown(line).collect::<Vec<_>>();
+    if let Some((case, magnitudes)) = Self::magnitudes(&fields) {
+      let mut row = [f64::NAN; 2 * SIXDOF];
+      row[..SIXDOF].copy_from_slice(&magnitudes);
+      self.data.insert_raw(case, &row, line_no);
+      self.last = Some(case);
+      return LineResponse::Data;
+    }
+    if let (Some(gids), Some(case)) = (Self::grids(&fields), self.last) {
+      let cols = Dof::all().iter()
+        .zip(gids)
+        .map(|(dof, gid)| (MaxSummaryField::of_dof(*dof, true), gid as f64))
+        .collect();
+      self.data.update_row(case, &cols);
+      self.grids = true;
+      // the grid points are printed once per row
+      self.last = None;
+      return LineResponse::Data;
+    }
+    return LineResponse::Useless;
+  }
+}
+
+/// Decoder for the maximum displacements per DOF.
+pub(crate) type MaxDisplacementsDecoder = MaxSummaryDecoder<Displacement>;
+
+/// Decoder for the maximum applied loads per DOF.
+pub(crate) type MaxAppliedLoadsDecoder = MaxSummaryDecoder<AppliedLoad>;
+
+/// Decoder for the maximum SPC forces per DOF.
+pub(crate) type MaxSpcForcesDecoder = MaxSummaryDecoder<SpcForce>;
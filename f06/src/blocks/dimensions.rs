@@ -0,0 +1,613 @@
+//! This submodule implements the physical dimensions of the columns of the
+//! supported block types. It's the one place that knows whether a column is a
+//! length, a force, a stress, etc., so that everything that cares about units
+//! (scaling values, putting units in headers) agrees on them.
+//!
+//! Dimensions depend on the block type as well as the column: a translational
+//! DOF is a length in a displacements block, but a force in an SPC forces
+//! block.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// The exponents of the SI base units that make up a physical dimension.
+/// Angles and strains are ratios, so they have none.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Default
+)]
+pub struct SiExponents {
+  /// Exponent of mass (kg).
+  pub mass: i8,
+  /// Exponent of length (m).
+  pub length: i8,
+  /// Exponent of time (s).
+  pub time: i8,
+  /// Exponent of temperature (K).
+  pub temperature: i8
+}
+
+impl SiExponents {
+  /// Makes a set of exponents.
+  pub const fn new(mass: i8, length: i8, time: i8, temperature: i8) -> Self {
+    return Self { mass, length, time, temperature };
+  }
+
+  /// Returns true if all exponents are zero.
+  pub const fn is_dimensionless(&self) -> bool {
+    return self.mass == 0
+      && self.length == 0
+      && self.time == 0
+      && self.temperature == 0;
+  }
+}
+
+/// The physical dimension of the values in a column. Several dimensions share
+/// the same SI exponents (e.g. moments and energies), but are kept apart since
+/// they're labelled differently.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq,
+  Hash
+)]
+pub enum PhysicalDimension {
+  /// A pure number, like a margin of safety, a ratio or a status code.
+  Dimensionless,
+  /// A length, like a translation.
+  Length,
+  /// An angle, like a rotation or a principal angle.
+  Angle,
+  /// A translational velocity.
+  Velocity,
+  /// A rotational velocity.
+  AngularVelocity,
+  /// A translational acceleration.
+  Acceleration,
+  /// A rotational acceleration.
+  AngularAcceleration,
+  /// A force.
+  Force,
+  /// A moment or a torque.
+  Moment,
+  /// A force per unit length, like a membrane force or a shear flow.
+  ForcePerLength,
+  /// A moment per unit length, like a plate bending moment.
+  MomentPerLength,
+  /// A stress.
+  Stress,
+  /// A strain.
+  Strain,
+  /// An energy.
+  Energy,
+  /// An energy per unit volume.
+  EnergyDensity,
+  /// A temperature.
+  Temperature,
+  /// A mass.
+  Mass,
+  /// A mass moment of inertia.
+  MomentOfInertia,
+  /// A frequency, in cycles per unit time.
+  Frequency,
+  /// An angular frequency, in radians per unit time.
+  AngularFrequency,
+  /// The square of an angular frequency, like a real eigenvalue.
+  AngularFrequencySquared,
+  /// Not known from the column alone, e.g. the values in the output of the
+  /// grid point weight generator, whose dimension depends on the row.
+  Unknown
+}
+
+impl Display for PhysicalDimension {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(f, "{}", match self {
+      Self::Dimensionless => "dimensionless",
+      Self::Length => "length",
+      Self::Angle => "angle",
+      Self::Velocity => "velocity",
+      Self::AngularVelocity => "angular velocity",
+      Self::Acceleration => "acceleration",
+      Self::AngularAcceleration => "angular acceleration",
+      Self::Force => "force",
+      Self::Moment => "moment",
+      Self::ForcePerLength => "force per length",
+      Self::MomentPerLength => "moment per length",
+      Self::Stress => "stress",
+      Self::Strain => "strain",
+      Self::Energy => "energy",
+      Self::EnergyDensity => "energy density",
+      Self::Temperature => "temperature",
+      Self::Mass => "mass",
+      Self::MomentOfInertia => "moment of inertia",
+      Self::Frequency => "frequency",
+      Self::AngularFrequency => "angular frequency",
+      Self::AngularFrequencySquared => "angular frequency squared",
+      Self::Unknown => "unknown",
+    });
+  }
+}
+
+impl PhysicalDimension {
+  /// Returns the exponents of the SI base units for this dimension. None if
+  /// it's unknown.
+  pub const fn exponents(&self) -> Option<SiExponents> {
+    let (mass, length, time, temperature) = match self {
+      Self::Dimensionless | Self::Angle | Self::Strain => (0, 0, 0, 0),
+      Self::Length => (0, 1, 0, 0),
+      Self::Velocity => (0, 1, -1, 0),
+      Self::AngularVelocity
+        | Self::Frequency
+        | Self::AngularFrequency => (0, 0, -1, 0),
+      Self::Acceleration => (0, 1, -2, 0),
+      Self::AngularAcceleration | Self::AngularFrequencySquared => {
+        (0, 0, -2, 0)
+      },
+      Self::Force | Self::MomentPerLength => (1, 1, -2, 0),
+      Self::Moment | Self::Energy => (1, 2, -2, 0),
+      Self::ForcePerLength => (1, 0, -2, 0),
+      Self::Stress | Self::EnergyDensity => (1, -1, -2, 0),
+      Self::Temperature => (0, 0, 0, 1),
+      Self::Mass => (1, 0, 0, 0),
+      Self::MomentOfInertia => (1, 2, 0, 0),
+      Self::Unknown => return None,
+    };
+    return Some(SiExponents::new(mass, length, time, temperature));
+  }
+}
+
+/// Returns the dimension of a DOF column in a block type.
+const fn dof_dimension(
+  context: BlockType,
+  dof_type: DofType
+) -> PhysicalDimension {
+  let rotational = matches!(dof_type, DofType::Rotational);
+  let (translation, rotation) = match context {
+    BlockType::Displacements
+      | BlockType::SolutionSetDisplacements
+      | BlockType::Eigenvectors => {
+      (PhysicalDimension::Length, PhysicalDimension::Angle)
+    },
+    BlockType::Velocities => {
+      (PhysicalDimension::Velocity, PhysicalDimension::AngularVelocity)
+    },
+    BlockType::Accelerations => (
+      PhysicalDimension::Acceleration,
+      PhysicalDimension::AngularAcceleration
+    ),
+    BlockType::GridPointForceBalance
+      | BlockType::SpcForces
+      | BlockType::MpcForces
+      | BlockType::AppliedForces
+      | BlockType::ThermalLoads
+      | BlockType::BushForces => {
+      (PhysicalDimension::Force, PhysicalDimension::Moment)
+    },
+    _ => return PhysicalDimension::Unknown
+  };
+  return if rotational { rotation } else { translation };
+}
+
+/// Returns the dimension of a column that holds stresses, or the strains
+/// that wrap the same field.
+const fn stress_or_strain(strain: bool) -> PhysicalDimension {
+  return if strain {
+    PhysicalDimension::Strain
+  } else {
+    PhysicalDimension::Stress
+  };
+}
+
+/// Returns the dimension of a plate stress or strain column.
+const fn plate_stress_dimension(
+  field: PlateStressField,
+  strain: bool
+) -> PhysicalDimension {
+  return match field {
+    PlateStressField::FibreDistance => PhysicalDimension::Length,
+    PlateStressField::Angle => PhysicalDimension::Angle,
+    PlateStressField::NormalX
+      | PlateStressField::NormalY
+      | PlateStressField::ShearXY
+      | PlateStressField::Major
+      | PlateStressField::Minor
+      | PlateStressField::VonMises => stress_or_strain(strain),
+  };
+}
+
+/// Returns the dimension of a rod stress or strain column.
+const fn rod_stress_dimension(
+  field: RodStressField,
+  strain: bool
+) -> PhysicalDimension {
+  return match field {
+    RodStressField::Axial | RodStressField::Torsional => {
+      stress_or_strain(strain)
+    },
+    RodStressField::AxialSafetyMargin
+      | RodStressField::TorsionalSafetyMargin => {
+      PhysicalDimension::Dimensionless
+    },
+  };
+}
+
+/// Returns the dimension of a bar stress or strain column.
+const fn bar_stress_dimension(
+  field: BarStressField,
+  strain: bool
+) -> PhysicalDimension {
+  return match field {
+    BarStressField::AtRecoveryPoint { .. }
+      | BarStressField::Axial
+      | BarStressField::MaxAt(_)
+      | BarStressField::MinAt(_) => stress_or_strain(strain),
+    BarStressField::SafetyMargin(_) => PhysicalDimension::Dimensionless,
+  };
+}
+
+/// Returns the dimension of a beam stress or strain column.
+const fn beam_stress_dimension(
+  field: BeamStressField,
+  strain: bool
+) -> PhysicalDimension {
+  return match field {
+    BeamStressField::MarginTension | BeamStressField::MarginCompression => {
+      PhysicalDimension::Dimensionless
+    },
+    BeamStressField::LongStressC
+      | BeamStressField::LongStressD
+      | BeamStressField::LongStressE
+      | BeamStressField::LongStressF
+      | BeamStressField::MaxStress
+      | BeamStressField::MinStress => stress_or_strain(strain),
+  };
+}
+
+/// Returns the dimension of a bar engineering force column.
+const fn bar_force_dimension(field: BarForceField) -> PhysicalDimension {
+  return match field {
+    BarForceField::BendMomentAPlane1
+      | BarForceField::BendMomentAPlane2
+      | BarForceField::BendMomentBPlane1
+      | BarForceField::BendMomentBPlane2
+      | BarForceField::Torque => PhysicalDimension::Moment,
+    BarForceField::ShearPlane1
+      | BarForceField::ShearPlane2
+      | BarForceField::AxialForce => PhysicalDimension::Force,
+  };
+}
+
+/// Returns the dimension of a beam engineering force column.
+const fn beam_force_dimension(field: BeamForceField) -> PhysicalDimension {
+  return match field {
+    BeamForceField::MomentPlane1
+      | BeamForceField::MomentPlane2
+      | BeamForceField::TotalTorque
+      | BeamForceField::WarpingTorque => PhysicalDimension::Moment,
+    BeamForceField::ShearPlane1
+      | BeamForceField::ShearPlane2
+      | BeamForceField::AxialForce => PhysicalDimension::Force,
+  };
+}
+
+/// Returns the dimension of a plate engineering force column. Those are all
+/// per unit length of the edge they act on.
+const fn plate_force_dimension(field: PlateForceField) -> PhysicalDimension {
+  return match field {
+    PlateForceField::MomentX
+      | PlateForceField::MomentY
+      | PlateForceField::MomentXY => PhysicalDimension::MomentPerLength,
+    PlateForceField::NormalX
+      | PlateForceField::NormalY
+      | PlateForceField::NormalXY
+      | PlateForceField::TransverseShearX
+      | PlateForceField::TransverseShearY => {
+      PhysicalDimension::ForcePerLength
+    },
+  };
+}
+
+/// Returns the dimension of a shear panel force column. The corner and kick
+/// forces are forces, but the edge shears are shear flows.
+const fn cshear_force_dimension(field: CshearForceField) -> PhysicalDimension {
+  return match field {
+    CshearForceField::Shear12
+      | CshearForceField::Shear23
+      | CshearForceField::Shear34
+      | CshearForceField::Shear41 => PhysicalDimension::ForcePerLength,
+    CshearForceField::F4ToF1
+      | CshearForceField::F2ToF1
+      | CshearForceField::F1ToF2
+      | CshearForceField::F3ToF2
+      | CshearForceField::F2ToF3
+      | CshearForceField::F4ToF3
+      | CshearForceField::F3ToF4
+      | CshearForceField::F1ToF4
+      | CshearForceField::Kick1
+      | CshearForceField::Kick2
+      | CshearForceField::Kick3
+      | CshearForceField::Kick4 => PhysicalDimension::Force,
+  };
+}
+
+/// Returns the dimension of a gap force column.
+const fn cgap_force_dimension(field: CgapForceField) -> PhysicalDimension {
+  return match field {
+    CgapForceField::CompressiveForce
+      | CgapForceField::ShearPlane1
+      | CgapForceField::ShearPlane2 => PhysicalDimension::Force,
+    CgapForceField::AxialDisplacement
+      | CgapForceField::TotalDisplacement1
+      | CgapForceField::TotalDisplacement2
+      | CgapForceField::Slip1
+      | CgapForceField::Slip2 => PhysicalDimension::Length,
+    CgapForceField::Status => PhysicalDimension::Dimensionless,
+  };
+}
+
+/// Returns the dimension of a composite ply stress column.
+const fn composite_stress_dimension(
+  field: CompositeStressField
+) -> PhysicalDimension {
+  return match field {
+    CompositeStressField::Angle => PhysicalDimension::Angle,
+    CompositeStressField::Normal1
+      | CompositeStressField::Normal2
+      | CompositeStressField::Shear12
+      | CompositeStressField::Shear1Z
+      | CompositeStressField::Shear2Z
+      | CompositeStressField::Major
+      | CompositeStressField::Minor
+      | CompositeStressField::MaxShear
+      | CompositeStressField::VonMises => PhysicalDimension::Stress,
+  };
+}
+
+/// Returns the dimension of a grid point stress column.
+const fn grid_stress_dimension(field: GridStressField) -> PhysicalDimension {
+  return match field {
+    GridStressField::Angle => PhysicalDimension::Angle,
+    GridStressField::NormalX
+      | GridStressField::NormalY
+      | GridStressField::ShearXY
+      | GridStressField::Major
+      | GridStressField::Minor
+      | GridStressField::MaxShear
+      | GridStressField::VonMises => PhysicalDimension::Stress,
+  };
+}
+
+/// Returns the dimension of a strain energy column.
+const fn strain_energy_dimension(
+  field: StrainEnergyField
+) -> PhysicalDimension {
+  return match field {
+    StrainEnergyField::Energy => PhysicalDimension::Energy,
+    StrainEnergyField::PercentTotal => PhysicalDimension::Dimensionless,
+    StrainEnergyField::Density => PhysicalDimension::EnergyDensity,
+  };
+}
+
+/// Returns the dimension of a real eigenvalue column. Buckling eigenvalues
+/// are load factors, but modal ones are squared angular frequencies. The
+/// generalized masses and stiffnesses depend on how the eigenvectors were
+/// normalised.
+const fn eigenvalue_dimension(
+  context: BlockType,
+  field: EigenvalueField
+) -> PhysicalDimension {
+  return match field {
+    EigenvalueField::ExtractionOrder => PhysicalDimension::Dimensionless,
+    EigenvalueField::Eigenvalue => match context {
+      BlockType::BucklingEigenvalues => PhysicalDimension::Dimensionless,
+      _ => PhysicalDimension::AngularFrequencySquared
+    },
+    EigenvalueField::Radians => PhysicalDimension::AngularFrequency,
+    EigenvalueField::Cycles => PhysicalDimension::Frequency,
+    EigenvalueField::GeneralizedMass
+      | EigenvalueField::GeneralizedStiffness => PhysicalDimension::Unknown,
+  };
+}
+
+/// Returns the dimension of a complex eigenvalue column. The parts of the
+/// eigenvalue are in radians per unit time.
+const fn complex_eigenvalue_dimension(
+  field: ComplexEigenvalueField
+) -> PhysicalDimension {
+  return match field {
+    ComplexEigenvalueField::ExtractionOrder
+      | ComplexEigenvalueField::Damping => PhysicalDimension::Dimensionless,
+    ComplexEigenvalueField::RealPart
+      | ComplexEigenvalueField::ImaginaryPart => {
+      PhysicalDimension::AngularFrequency
+    },
+    ComplexEigenvalueField::Frequency => PhysicalDimension::Frequency,
+  };
+}
+
+/// Returns the dimension of a modal effective mass column. Rotational
+/// effective masses are moments of inertia, and cumulative ones are
+/// percentages.
+const fn modal_mass_dimension(field: ModalMassField) -> PhysicalDimension {
+  return match field {
+    ModalMassField::Tx | ModalMassField::Ty | ModalMassField::Tz => {
+      PhysicalDimension::Mass
+    },
+    ModalMassField::Rx | ModalMassField::Ry | ModalMassField::Rz => {
+      PhysicalDimension::MomentOfInertia
+    },
+    ModalMassField::CumTx
+      | ModalMassField::CumTy
+      | ModalMassField::CumTz
+      | ModalMassField::CumRx
+      | ModalMassField::CumRy
+      | ModalMassField::CumRz => PhysicalDimension::Dimensionless,
+  };
+}
+
+/// Returns the dimension of a column of a table of maximums per DOF: that of
+/// the DOF in the vectors summarised, or none for the grid point IDs.
+const fn max_summary_dimension(
+  context: BlockType,
+  field: MaxSummaryField
+) -> PhysicalDimension {
+  if field.is_grid() {
+    return PhysicalDimension::Dimensionless;
+  }
+  let summarised = match context {
+    BlockType::MaxDisplacements => BlockType::Displacements,
+    BlockType::MaxAppliedLoads => BlockType::AppliedForces,
+    BlockType::MaxSpcForces => BlockType::SpcForces,
+    _ => return PhysicalDimension::Unknown
+  };
+  return dof_dimension(summarised, field.dof().dof_type);
+}
+
+impl NasIndex {
+  /// Returns the physical dimension of the values in this column, in a block
+  /// of a type. Unknown for indexes that aren't columns of that block type,
+  /// and for columns whose dimension depends on the row.
+  pub const fn dimension(&self, context: BlockType) -> PhysicalDimension {
+    return match self {
+      Self::Dof(dof) => dof_dimension(context, dof.dof_type),
+      Self::SingleForce(_) => PhysicalDimension::Force,
+      Self::SingleStress(_) => PhysicalDimension::Stress,
+      Self::SingleStrain(_) => PhysicalDimension::Strain,
+      Self::SingleTemperature(_) => PhysicalDimension::Temperature,
+      Self::BarForceField(f) => bar_force_dimension(*f),
+      Self::BarStressField(f) => bar_stress_dimension(*f, false),
+      Self::BarStrainField(f) => bar_stress_dimension(f.0, true),
+      Self::BeamForceField(f) => beam_force_dimension(*f),
+      Self::BeamStressField(f) => beam_stress_dimension(*f, false),
+      Self::BeamStrainField(f) => beam_stress_dimension(f.0, true),
+      Self::RodForceField(RodForceField::AxialForce) => {
+        PhysicalDimension::Force
+      },
+      Self::RodForceField(RodForceField::Torque) => PhysicalDimension::Moment,
+      Self::RodStressField(f) => rod_stress_dimension(*f, false),
+      Self::RodStrainField(f) => rod_stress_dimension(f.0, true),
+      Self::PlateForceField(f) => plate_force_dimension(*f),
+      Self::PlateStressField(f) => plate_stress_dimension(*f, false),
+      Self::PlateStrainField(f) => plate_stress_dimension(f.0, true),
+      Self::ChexaStressField(_)
+        | Self::CpentaStressField(_)
+        | Self::CtetraStressField(_)
+        | Self::BushStressField(_) => PhysicalDimension::Stress,
+      Self::ChexaStrainField(_)
+        | Self::CpentaStrainField(_)
+        | Self::CtetraStrainField(_)
+        | Self::BushStrainField(_) => PhysicalDimension::Strain,
+      Self::CshearForceField(f) => cshear_force_dimension(*f),
+      Self::CshearStressField(CshearStressField::MarginOfSafety) => {
+        PhysicalDimension::Dimensionless
+      },
+      Self::CshearStressField(_) => PhysicalDimension::Stress,
+      Self::CgapForceField(f) => cgap_force_dimension(*f),
+      Self::CompositeFailureField(_) => PhysicalDimension::Dimensionless,
+      Self::CompositeStressField(f) => composite_stress_dimension(*f),
+      Self::GridStressField(f) => grid_stress_dimension(*f),
+      Self::StrainEnergyField(f) => strain_energy_dimension(*f),
+      Self::EigenvalueField(f) => eigenvalue_dimension(context, *f),
+      Self::ComplexEigenvalueField(f) => complex_eigenvalue_dimension(*f),
+      Self::ModalMassField(f) => modal_mass_dimension(*f),
+      Self::GpwgField(_) => PhysicalDimension::Unknown,
+      Self::MaxSummaryField(f) => max_summary_dimension(context, *f),
+      Self::GridPointRef(_)
+        | Self::ElementRef(_)
+        | Self::PointInElement(_)
+        | Self::GridPointForceOrigin(_)
+        | Self::ElementSidedPoint(_)
+        | Self::ElementStation(_)
+        | Self::ElementPlyRef(_)
+        | Self::GridSurfacePoint(_)
+        | Self::SeqRef(_)
+        | Self::ModeRef(_)
+        | Self::GpwgRef(_)
+        | Self::CaseRef(_) => PhysicalDimension::Unknown,
+    };
+  }
+}
+
+/// How much to multiply values by to change the units of each SI base
+/// quantity, e.g. a length scale of 1000 goes from metres to millimetres.
+/// Temperatures are only scaled, so converting between scales with different
+/// zeroes (like Celsius and Kelvin) isn't possible.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct UnitScales {
+  /// Scale for masses.
+  pub mass: f64,
+  /// Scale for lengths.
+  pub length: f64,
+  /// Scale for times.
+  pub time: f64,
+  /// Scale for temperatures.
+  pub temperature: f64
+}
+
+impl Default for UnitScales {
+  fn default() -> Self {
+    return Self::identity();
+  }
+}
+
+impl UnitScales {
+  /// Scales that change nothing.
+  pub const fn identity() -> Self {
+    return Self { mass: 1.0, length: 1.0, time: 1.0, temperature: 1.0 };
+  }
+
+  /// Returns the factor to multiply values of a dimension by. None if the
+  /// dimension is unknown.
+  pub fn factor(&self, dimension: PhysicalDimension) -> Option<f64> {
+    let exps = dimension.exponents()?;
+    return Some(
+      self.mass.powi(exps.mass.into())
+        * self.length.powi(exps.length.into())
+        * self.time.powi(exps.time.into())
+        * self.temperature.powi(exps.temperature.into())
+    );
+  }
+}
+
+impl FinalBlock {
+  /// Returns the physical dimension of a column of this block.
+  pub fn column_dimension(&self, col: NasIndex) -> PhysicalDimension {
+    return col.dimension(self.block_type);
+  }
+
+  /// Changes the units of the real values in this block. Columns whose
+  /// dimension is unknown, and integer columns, are left alone. Returns the
+  /// columns left alone despite having real values.
+  pub fn scale_units(&mut self, scales: &UnitScales) -> Vec<NasIndex> {
+    let mut skipped = Vec::new();
+    let Some(FinalDMat::Reals(data)) = self.data.as_mut() else {
+      return skipped;
+    };
+    for (col, idx) in self.col_indexes.iter() {
+      match scales.factor(col.dimension(self.block_type)) {
+        Some(factor) => data.column_mut(*idx).scale_mut(factor),
+        None => skipped.push(*col),
+      }
+    }
+    return skipped;
+  }
+}
+
+impl F06File {
+  /// Changes the units of the real values in all blocks. Returns, for each
+  /// block type, the columns left alone for their dimension being unknown.
+  pub fn scale_units(
+    &mut self,
+    scales: &UnitScales
+  ) -> BTreeMap<BlockType, BTreeSet<NasIndex>> {
+    let mut skipped: BTreeMap<BlockType, BTreeSet<NasIndex>> = BTreeMap::new();
+    for block in self.all_blocks_mut(false) {
+      let cols = block.scale_units(scales);
+      if !cols.is_empty() {
+        skipped.entry(block.block_type).or_default().extend(cols);
+      }
+    }
+    return skipped;
+  }
+}
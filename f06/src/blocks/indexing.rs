@@ -86,6 +86,14 @@ macro_rules! gen_with_inner(
 
     impl IndexType for $outer_type {
       const INDEX_NAME: &'static str = $name;
+      const ROW_DIMENSION: RowDimension =
+        <$inner_type as IndexType>::ROW_DIMENSION;
+
+      fn fixed_values() -> Vec<NasIndex> {
+        return $inner_type::all().iter()
+          .map(|x| NasIndex::from($outer_type(*x)))
+          .collect();
+      }
     }
   }
 );
@@ -120,6 +128,49 @@ macro_rules! gen_nasindex {
         };
       }
     }
+
+    impl NasIndex {
+      /// Returns the name of the type of index within.
+      pub const fn index_name(&self) -> &'static str {
+        return match self {
+          $(Self::$tn(_) => <$tn as IndexType>::INDEX_NAME,)*
+        };
+      }
+
+      /// Feeds the parts of this index to a visitor, breaking compound
+      /// indexes down (see `NasIndexVisitor`).
+      pub fn visit(&self, v: &mut impl NasIndexVisitor) {
+        match self {
+          $(Self::$tn(x) => x.visit_parts(v),)*
+        };
+      }
+
+      /// Returns this index with its grid point IDs mapped by one function
+      /// and its element IDs by another, wherever they are within it.
+      pub fn map_ids<G, E>(&self, grid_fn: G, elem_fn: E) -> Self
+      where
+        G: Fn(usize) -> usize,
+        E: Fn(usize) -> usize
+      {
+        return match self {
+          $(Self::$tn(x) => Self::$tn(x.map_ids(&grid_fn, &elem_fn)),)*
+        };
+      }
+
+      /// The names of all the variants, for checking they're all covered.
+      #[cfg(test)]
+      pub(crate) const VARIANTS: &'static [&'static str] = &[
+        $(stringify!($tn),)*
+      ];
+
+      /// Returns the name of the variant.
+      #[cfg(test)]
+      pub(crate) const fn variant(&self) -> &'static str {
+        return match self {
+          $(Self::$tn(_) => stringify!($tn),)*
+        };
+      }
+    }
   };
 }
 
@@ -130,28 +181,179 @@ gen_nasindex!(
   PointInElement,
   GridPointForceOrigin,
   ElementSidedPoint,
+  ElementStation,
+  ElementPlyRef,
+  GridSurfacePoint,
   SingleForce,
   SingleStress,
   SingleStrain,
+  SingleTemperature,
   BarForceField,
   BarStressField,
   BarStrainField,
+  BeamForceField,
+  BeamStressField,
+  BeamStrainField,
   RodForceField,
   RodStressField,
   RodStrainField,
   PlateForceField,
   PlateStressField,
   PlateStrainField,
+  ChexaStressField,
+  ChexaStrainField,
+  CpentaStressField,
+  CpentaStrainField,
+  CtetraStressField,
+  CtetraStrainField,
+  CshearForceField,
+  CshearStressField,
+  CgapForceField,
+  BushStressField,
+  BushStrainField,
+  CompositeFailureField,
+  CompositeStressField,
+  GridStressField,
+  StrainEnergyField,
+  SeqRef,
+  ModeRef,
+  EigenvalueField,
+  ComplexEigenvalueField,
+  ModalMassField,
+  GpwgRef,
+  GpwgField,
+  CaseRef,
+  MaxSummaryField,
 );
 
+/// The "extra" dimension along which an element's results are broken into
+/// several rows, beyond the element (or grid point) itself.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq,
+  Hash
+)]
+pub enum RowDimension {
+  /// One row per element or grid point; nothing extra.
+  None,
+  /// Rows for each fibre side (top/bottom) of a plate.
+  Side,
+  /// Rows for each ply of a composite.
+  Ply,
+  /// Rows for each end of a bar or beam.
+  End,
+  /// Rows for each station along a beam.
+  Station,
+  /// Rows for each component (real/imaginary, magnitude/phase).
+  Component,
+  /// Rows for each time step or frequency of a dynamic analysis. No index
+  /// has this one; blocks are printed once per step instead (see `Step`).
+  Step,
+  /// Whether the step is a time or a frequency (`Step::name`), alongside
+  /// `Step`.
+  StepKind
+}
+
+impl Display for RowDimension {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(f, "{}", self.name());
+  }
+}
+
+impl RowDimension {
+  /// Returns a short, capitalised name for this dimension.
+  pub const fn name(&self) -> &'static str {
+    return match self {
+      Self::None => "None",
+      Self::Side => "Side",
+      Self::Ply => "Ply",
+      Self::End => "End",
+      Self::Station => "Station",
+      Self::Component => "Component",
+      Self::Step => "Step",
+      Self::StepKind => "StepKind",
+    };
+  }
+
+  /// Returns all the actual extra dimensions, i.e. all but `None`.
+  pub const fn extras() -> &'static [Self] {
+    return &[
+      Self::Side,
+      Self::Ply,
+      Self::End,
+      Self::Station,
+      Self::Component,
+      Self::Step,
+      Self::StepKind
+    ];
+  }
+
+  /// Returns true if this is an actual extra dimension.
+  pub const fn is_extra(&self) -> bool {
+    return !matches!(self, Self::None);
+  }
+}
+
 /// All field indexing types must implement this trait.
 pub trait IndexType: Copy + Ord + Eq + Into<NasIndex> + Display + DebugTrait {
   /// The name of this type of index, all caps.
   const INDEX_NAME: &'static str;
+  /// The extra row dimension this index type implies, if any.
+  const ROW_DIMENSION: RowDimension = RowDimension::None;
+
+  /// All the values this index can take, in canonical order, if they're a
+  /// fixed set (i.e. it's a column index). Empty otherwise.
+  fn fixed_values() -> Vec<NasIndex> {
+    return Vec::new();
+  }
+}
+
+/// Turns a list of index values into NasIndexes.
+fn into_nasindexes<T: IndexType>(values: &[T]) -> Vec<NasIndex> {
+  return values.iter().copied().map(Into::<NasIndex>::into).collect();
 }
 
 impl IndexType for Dof {
   const INDEX_NAME: &'static str = "DOF";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+/// A sequence number in a reduced (solution) set -- NOT a grid point ID.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq,
+  derive_more::From
+)]
+pub struct SeqRef(pub usize);
+
+impl Display for SeqRef {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(f, "SEQ {}", self.0);
+  }
+}
+
+impl IndexType for SeqRef {
+  const INDEX_NAME: &'static str = "SEQUENCE NUMBER";
+}
+
+/// A subcase or load set (DAREA) ID, as printed in the rows of the tables
+/// that summarise maximums across them -- NOT necessarily the subcase the
+/// block was found in.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq,
+  derive_more::From
+)]
+pub struct CaseRef(pub usize);
+
+impl Display for CaseRef {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(f, "CASE {}", self.0);
+  }
+}
+
+impl IndexType for CaseRef {
+  const INDEX_NAME: &'static str = "SUBCASE OR DAREA ID";
 }
 
 /// The possible origins for a force.
@@ -305,15 +507,23 @@ impl Display for ElementPoint {
 pub enum ElementSide {
   /// The bottom (Z1) side of the element.
   Bottom,
+  /// The middle of the element, between its sides (e.g. of a ply, when its
+  /// stresses are printed once).
+  ///
+  /// This was added after 0.3.7, for composite ply stresses. It's a breaking
+  /// change for code that matches on sides exhaustively, which needs an arm
+  /// for it now.
+  Middle,
   /// The top (Z2) side of the element.
   Top
 }
 
 impl Display for ElementSide {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    return write!(f, "{} SIDE", match self {
-      Self::Bottom => "BOTTOM",
-      Self::Top => "TOP",
+    return write!(f, "{}", match self {
+      Self::Bottom => "BOTTOM SIDE",
+      Self::Middle => "MIDDLE",
+      Self::Top => "TOP SIDE",
     });
   }
 }
@@ -323,6 +533,7 @@ impl ElementSide {
   pub const fn opposite(&self) -> Self {
     return match self {
       Self::Bottom => Self::Top,
+      Self::Middle => Self::Middle,
       Self::Top => Self::Bottom,
     };
   }
@@ -372,6 +583,7 @@ impl Display for ElementSidedPoint {
 
 impl IndexType for ElementSidedPoint {
   const INDEX_NAME: &'static str = "ELEMENT, POINT AND SIDE";
+  const ROW_DIMENSION: RowDimension = RowDimension::Side;
 }
 
 impl ElementSidedPoint {
@@ -381,6 +593,160 @@ impl ElementSidedPoint {
   }
 }
 
+/// An element and a station along it, as beams have results at their ends and
+/// at intermediate stations.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq
+)]
+pub struct ElementStation {
+  /// A reference to the element.
+  pub element: ElementRef,
+  /// The distance from end A over the length, in thousandths (that's as many
+  /// digits as get printed), so it can be compared and sorted.
+  pub station: u32
+}
+
+impl Display for ElementStation {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(f, "{}, STATION {:.3}", self.element, self.fraction());
+  }
+}
+
+impl IndexType for ElementStation {
+  const INDEX_NAME: &'static str = "ELEMENT AND STATION";
+  const ROW_DIMENSION: RowDimension = RowDimension::Station;
+}
+
+impl ElementStation {
+  /// How many units of the station make up the whole length.
+  const SCALE: f64 = 1000.0;
+
+  /// Makes a station from a fraction of the length (from end A).
+  pub fn at_fraction(element: ElementRef, fraction: f64) -> Self {
+    let station = (fraction.clamp(0.0, 1.0) * Self::SCALE).round() as u32;
+    return Self { element, station };
+  }
+
+  /// Returns the station as a fraction of the length (from end A).
+  pub fn fraction(&self) -> f64 {
+    return f64::from(self.station) / Self::SCALE;
+  }
+
+  /// Makes the station at an end.
+  pub fn at_end(element: ElementRef, end: BarEnd) -> Self {
+    return Self::at_fraction(element, end.fraction());
+  }
+
+  /// Returns the end this station is at, if it's at one.
+  pub fn end(&self) -> Option<BarEnd> {
+    return match self.station {
+      0 => Some(BarEnd::EndA),
+      s if f64::from(s) == Self::SCALE => Some(BarEnd::EndB),
+      _ => None
+    };
+  }
+}
+
+/// A layer in the laminate of a composite element.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq
+)]
+pub enum CompositeLayer {
+  /// A ply, by its ID.
+  Ply(usize),
+  /// The bond between a ply (by its ID) and the next one.
+  Bond(usize),
+  /// All plies at once, as in per-element maxima.
+  AllPlies
+}
+
+impl Display for CompositeLayer {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::Ply(ply) => write!(f, "PLY {}", ply),
+      Self::Bond(ply) => write!(f, "BOND AFTER PLY {}", ply),
+      Self::AllPlies => write!(f, "ALL PLIES")
+    };
+  }
+}
+
+impl CompositeLayer {
+  /// Returns the ply ID, if this is about one ply (or the bond after it).
+  pub const fn ply(&self) -> Option<usize> {
+    return match self {
+      Self::Ply(ply) | Self::Bond(ply) => Some(*ply),
+      Self::AllPlies => None
+    };
+  }
+
+  /// Returns a short, uppercase name for the kind of layer this is.
+  pub const fn kind(&self) -> &'static str {
+    return match self {
+      Self::Ply(_) => "PLY",
+      Self::Bond(_) => "BOND",
+      Self::AllPlies => "ALL"
+    };
+  }
+}
+
+/// An element and a layer of its laminate, at a side of the layer (its
+/// middle, unless the file prints a side).
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq,
+  derive_more::From
+)]
+pub struct ElementPlyRef {
+  /// A reference to the element.
+  pub element: ElementRef,
+  /// The layer within the element.
+  pub layer: CompositeLayer,
+  /// The side of the layer.
+  pub side: ElementSide
+}
+
+impl Display for ElementPlyRef {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(f, "{}, {}, {}", self.element, self.layer, self.side);
+  }
+}
+
+impl IndexType for ElementPlyRef {
+  const INDEX_NAME: &'static str = "ELEMENT AND PLY";
+  const ROW_DIMENSION: RowDimension = RowDimension::Ply;
+}
+
+/// A fibre of a grid point on a surface of grid point stress output, for one
+/// of the elements around it or for the average of all of them.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq
+)]
+pub struct GridSurfacePoint {
+  /// A reference to the grid point.
+  pub grid_point: GridPointRef,
+  /// The surface ID.
+  pub surface: usize,
+  /// The element the stresses are for, or none for the average of all the
+  /// elements around the grid point.
+  pub element: Option<ElementRef>,
+  /// The fibre.
+  pub side: ElementSide
+}
+
+impl Display for GridSurfacePoint {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}, SURFACE {}, ", self.grid_point, self.surface)?;
+    if let Some(element) = self.element {
+      write!(f, "{}, ", element)?;
+    }
+    return write!(f, "{}", self.side);
+  }
+}
+
+impl IndexType for GridSurfacePoint {
+  const INDEX_NAME: &'static str = "GRID POINT, SURFACE AND FIBRE";
+  const ROW_DIMENSION: RowDimension = RowDimension::Side;
+}
+
 from_enum!(
   "The columns for the stresses table for plate elements.",
   PlateStressField,
@@ -398,8 +764,285 @@ from_enum!(
 
 impl IndexType for PlateStressField {
   const INDEX_NAME: &'static str = "PLATE STRESS FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The columns for the stresses table for hexahedral solid elements.",
+  ChexaStressField,
+  [
+    (NormalX, "NORMAL-X"),
+    (NormalY, "NORMAL-Y"),
+    (NormalZ, "NORMAL-Z"),
+    (ShearXY, "SHEAR-XY"),
+    (ShearYZ, "SHEAR-YZ"),
+    (ShearZX, "SHEAR-ZX"),
+    (VonMises, "VON MISES"),
+    (Octahedral, "OCTAHEDRAL SHEAR"),
+    (PrincipalA, "PRINCIPAL A"),
+    (PrincipalB, "PRINCIPAL B"),
+    (PrincipalC, "PRINCIPAL C"),
+    (MeanPressure, "MEAN PRESSURE"),
+  ]
+);
+
+impl IndexType for ChexaStressField {
+  const INDEX_NAME: &'static str = "CHEXA STRESS FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+gen_with_inner!(
+  "The columns for the strains table for hexahedral solid elements.",
+  "CHEXA STRAIN FIELD",
+  ChexaStrainField,
+  ChexaStressField
+);
+
+from_enum!(
+  "The columns for the stresses table for pentahedral solid elements.",
+  CpentaStressField,
+  [
+    (NormalX, "NORMAL-X"),
+    (NormalY, "NORMAL-Y"),
+    (NormalZ, "NORMAL-Z"),
+    (ShearXY, "SHEAR-XY"),
+    (ShearYZ, "SHEAR-YZ"),
+    (ShearZX, "SHEAR-ZX"),
+    (VonMises, "VON MISES"),
+    (PrincipalA, "PRINCIPAL A"),
+    (PrincipalB, "PRINCIPAL B"),
+    (PrincipalC, "PRINCIPAL C"),
+    (MeanPressure, "MEAN PRESSURE"),
+  ]
+);
+
+impl IndexType for CpentaStressField {
+  const INDEX_NAME: &'static str = "CPENTA STRESS FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+impl From<CpentaStressField> for ChexaStressField {
+  fn from(value: CpentaStressField) -> Self {
+    return match value {
+      CpentaStressField::NormalX => Self::NormalX,
+      CpentaStressField::NormalY => Self::NormalY,
+      CpentaStressField::NormalZ => Self::NormalZ,
+      CpentaStressField::ShearXY => Self::ShearXY,
+      CpentaStressField::ShearYZ => Self::ShearYZ,
+      CpentaStressField::ShearZX => Self::ShearZX,
+      CpentaStressField::VonMises => Self::VonMises,
+      CpentaStressField::PrincipalA => Self::PrincipalA,
+      CpentaStressField::PrincipalB => Self::PrincipalB,
+      CpentaStressField::PrincipalC => Self::PrincipalC,
+      CpentaStressField::MeanPressure => Self::MeanPressure,
+    };
+  }
+}
+
+gen_with_inner!(
+  "The columns for the strains table for pentahedral solid elements.",
+  "CPENTA STRAIN FIELD",
+  CpentaStrainField,
+  CpentaStressField
+);
+
+from_enum!(
+  "The columns for the stresses table for tetrahedral solid elements.",
+  CtetraStressField,
+  [
+    (NormalX, "NORMAL-X"),
+    (NormalY, "NORMAL-Y"),
+    (NormalZ, "NORMAL-Z"),
+    (ShearXY, "SHEAR-XY"),
+    (ShearYZ, "SHEAR-YZ"),
+    (ShearZX, "SHEAR-ZX"),
+    (VonMises, "VON MISES"),
+    (PrincipalA, "PRINCIPAL A"),
+    (PrincipalB, "PRINCIPAL B"),
+    (PrincipalC, "PRINCIPAL C"),
+    (MeanPressure, "MEAN PRESSURE"),
+  ]
+);
+
+impl IndexType for CtetraStressField {
+  const INDEX_NAME: &'static str = "CTETRA STRESS FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+impl From<CtetraStressField> for ChexaStressField {
+  fn from(value: CtetraStressField) -> Self {
+    return match value {
+      CtetraStressField::NormalX => Self::NormalX,
+      CtetraStressField::NormalY => Self::NormalY,
+      CtetraStressField::NormalZ => Self::NormalZ,
+      CtetraStressField::ShearXY => Self::ShearXY,
+      CtetraStressField::ShearYZ => Self::ShearYZ,
+      CtetraStressField::ShearZX => Self::ShearZX,
+      CtetraStressField::VonMises => Self::VonMises,
+      CtetraStressField::PrincipalA => Self::PrincipalA,
+      CtetraStressField::PrincipalB => Self::PrincipalB,
+      CtetraStressField::PrincipalC => Self::PrincipalC,
+      CtetraStressField::MeanPressure => Self::MeanPressure,
+    };
+  }
 }
 
+gen_with_inner!(
+  "The columns for the strains table for tetrahedral solid elements.",
+  "CTETRA STRAIN FIELD",
+  CtetraStrainField,
+  CtetraStressField
+);
+
+from_enum!(
+  "The columns for the forces table for shear panels: the forces on the \
+  corners, each from one corner to the next, then the kick forces at the \
+  corners and the shear flows along the edges.",
+  CshearForceField,
+  [
+    (F4ToF1, "F4 TO F1"),
+    (F2ToF1, "F2 TO F1"),
+    (F1ToF2, "F1 TO F2"),
+    (F3ToF2, "F3 TO F2"),
+    (F2ToF3, "F2 TO F3"),
+    (F4ToF3, "F4 TO F3"),
+    (F3ToF4, "F3 TO F4"),
+    (F1ToF4, "F1 TO F4"),
+    (Kick1, "KICK-1"),
+    (Shear12, "SHEAR-12"),
+    (Kick2, "KICK-2"),
+    (Shear23, "SHEAR-23"),
+    (Kick3, "KICK-3"),
+    (Shear34, "SHEAR-34"),
+    (Kick4, "KICK-4"),
+    (Shear41, "SHEAR-41"),
+  ]
+);
+
+impl IndexType for CshearForceField {
+  const INDEX_NAME: &'static str = "CSHEAR FORCE FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The columns for the stresses table for shear panels.",
+  CshearStressField,
+  [
+    (AverageShear, "AVG SHEAR"),
+    (MaxShearStress, "MAX SHEAR"),
+    (MarginOfSafety, "SAFETY MARGIN"),
+  ]
+);
+
+impl IndexType for CshearStressField {
+  const INDEX_NAME: &'static str = "CSHEAR STRESS FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The columns for the forces table for gap elements: the compressive force, \
+  the shear forces in both planes, the displacements in the element system \
+  and the status of the gap, coded as an index into `GAP_STATUSES`.",
+  CgapForceField,
+  [
+    (CompressiveForce, "COMP-X"),
+    (ShearPlane1, "SHEAR-Y"),
+    (ShearPlane2, "SHEAR-Z"),
+    (AxialDisplacement, "AXIAL-U"),
+    (TotalDisplacement1, "TOTAL-V"),
+    (TotalDisplacement2, "TOTAL-W"),
+    (Slip1, "SLIP-V"),
+    (Slip2, "SLIP-W"),
+    (Status, "STATUS"),
+  ]
+);
+
+/// The statuses of a gap, by the code they're stored as in the status column
+/// of its forces: open, closed (and sticking), or closed and sliding.
+pub const GAP_STATUSES: [&str; 3] = ["OPEN", "CLOSED", "SLIDE"];
+
+impl IndexType for CgapForceField {
+  const INDEX_NAME: &'static str = "CGAP FORCE FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The columns for the stresses table for bush elements.",
+  BushStressField,
+  [
+    (TranslationX, "TRANSLATION-X"),
+    (TranslationY, "TRANSLATION-Y"),
+    (TranslationZ, "TRANSLATION-Z"),
+    (RotationX, "ROTATION-X"),
+    (RotationY, "ROTATION-Y"),
+    (RotationZ, "ROTATION-Z"),
+  ]
+);
+
+impl IndexType for BushStressField {
+  const INDEX_NAME: &'static str = "BUSH STRESS FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+impl BushStressField {
+  /// Returns the degree of freedom this component is along.
+  pub const fn dof(&self) -> Dof {
+    let (dof_type, axis) = match self {
+      Self::TranslationX => (DofType::Translational, Axis::X),
+      Self::TranslationY => (DofType::Translational, Axis::Y),
+      Self::TranslationZ => (DofType::Translational, Axis::Z),
+      Self::RotationX => (DofType::Rotational, Axis::X),
+      Self::RotationY => (DofType::Rotational, Axis::Y),
+      Self::RotationZ => (DofType::Rotational, Axis::Z),
+    };
+    return Dof { dof_type, axis };
+  }
+}
+
+impl From<Dof> for BushStressField {
+  fn from(dof: Dof) -> Self {
+    return match (dof.dof_type, dof.axis) {
+      (DofType::Translational, Axis::X) => Self::TranslationX,
+      (DofType::Translational, Axis::Y) => Self::TranslationY,
+      (DofType::Translational, Axis::Z) => Self::TranslationZ,
+      (DofType::Rotational, Axis::X) => Self::RotationX,
+      (DofType::Rotational, Axis::Y) => Self::RotationY,
+      (DofType::Rotational, Axis::Z) => Self::RotationZ,
+    };
+  }
+}
+
+gen_with_inner!(
+  "The columns for the strains table for bush elements.",
+  "BUSH STRAIN FIELD",
+  BushStrainField,
+  BushStressField
+);
+
 gen_with_inner!(
   "The columns for the strains table for plate elements.",
   "PLATE STRAIN FIELD",
@@ -424,6 +1067,10 @@ from_enum!(
 
 impl IndexType for PlateForceField {
   const INDEX_NAME: &'static str = "2D ELEM FORCE FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
 }
 
 from_enum!(
@@ -437,6 +1084,10 @@ from_enum!(
 
 impl IndexType for RodForceField {
   const INDEX_NAME: &'static str = "ROD FORCE FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
 }
 
 from_enum!(
@@ -456,6 +1107,14 @@ impl BarEnd {
       Self::EndB => Self::EndA,
     };
   }
+
+  /// Returns where this end is, as a fraction of the length from end A.
+  pub const fn fraction(&self) -> f64 {
+    return match self {
+      Self::EndA => 0.0,
+      Self::EndB => 1.0,
+    };
+  }
 }
 
 from_enum!(
@@ -467,73 +1126,116 @@ from_enum!(
   ]
 );
 
-/// A column of a BAR engineering force table.
-#[derive(
-  Copy, Clone, Debug, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq,
-  derive_more::From
-)]
-pub enum BarForceField {
-  /// Bend moments.
-  BendMoment {
-    /// The end of the bar.
-    end: BarEnd,
-    /// The plane.
-    plane: BarPlane
-  },
-  /// Shear forces.
-  Shear {
-    /// The plane.
-    plane: BarPlane
-  },
-  /// Axial force.
-  AxialForce,
-  /// Torque.
-  Torque
+from_enum!(
+  "A column of a BAR engineering force table, named the same for all solvers.",
+  BarForceField,
+  [
+    (BendMomentAPlane1, "BEND-MOMENT END-A, PLANE 1"),
+    (BendMomentAPlane2, "BEND-MOMENT END-A, PLANE 2"),
+    (BendMomentBPlane1, "BEND-MOMENT END-B, PLANE 1"),
+    (BendMomentBPlane2, "BEND-MOMENT END-B, PLANE 2"),
+    (ShearPlane1, "SHEAR PLANE 1"),
+    (ShearPlane2, "SHEAR PLANE 2"),
+    (AxialForce, "AXIAL FORCE"),
+    (Torque, "TORQUE"),
+  ]
+);
+
+impl IndexType for BarForceField {
+  const INDEX_NAME: &'static str = "BAR FORCE FIELD";
+  const ROW_DIMENSION: RowDimension = RowDimension::End;
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
 }
 
-impl Display for BarForceField {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl BarForceField {
+  /// Returns the bend moment at an end, in a plane.
+  pub const fn bend_moment(end: BarEnd, plane: BarPlane) -> Self {
+    return match (end, plane) {
+      (BarEnd::EndA, BarPlane::Plane1) => Self::BendMomentAPlane1,
+      (BarEnd::EndA, BarPlane::Plane2) => Self::BendMomentAPlane2,
+      (BarEnd::EndB, BarPlane::Plane1) => Self::BendMomentBPlane1,
+      (BarEnd::EndB, BarPlane::Plane2) => Self::BendMomentBPlane2,
+    };
+  }
+
+  /// Returns the end this field is at, if it's a bend moment.
+  pub const fn end(&self) -> Option<BarEnd> {
     return match self {
-      BarForceField::BendMoment { end, plane } => {
-        write!(f, "BEND-MOMENT {}, {}", end, plane)
+      Self::BendMomentAPlane1 | Self::BendMomentAPlane2 => Some(BarEnd::EndA),
+      Self::BendMomentBPlane1 | Self::BendMomentBPlane2 => Some(BarEnd::EndB),
+      _ => None
+    };
+  }
+
+  /// Returns the plane this field is in, if it's a bend moment or a shear.
+  pub const fn plane(&self) -> Option<BarPlane> {
+    return match self {
+      Self::BendMomentAPlane1 | Self::BendMomentBPlane1 | Self::ShearPlane1 => {
+        Some(BarPlane::Plane1)
+      },
+      Self::BendMomentAPlane2 | Self::BendMomentBPlane2 | Self::ShearPlane2 => {
+        Some(BarPlane::Plane2)
       },
-      BarForceField::Shear { plane } => write!(f, "SHEAR {}", plane),
-      BarForceField::AxialForce => write!(f, "AXIAL FORCE"),
-      BarForceField::Torque => write!(f, "TORQUE")
+      Self::AxialForce | Self::Torque => None
     };
   }
 }
 
-impl IndexType for BarForceField {
-  const INDEX_NAME: &'static str = "BAR FORCE FIELD";
-}
+from_enum!(
+  "A column of a BEAM engineering force table, at a station.",
+  BeamForceField,
+  [
+    (MomentPlane1, "BEND-MOMENT PLANE 1"),
+    (MomentPlane2, "BEND-MOMENT PLANE 2"),
+    (ShearPlane1, "WEB SHEAR PLANE 1"),
+    (ShearPlane2, "WEB SHEAR PLANE 2"),
+    (AxialForce, "AXIAL FORCE"),
+    (TotalTorque, "TOTAL TORQUE"),
+    (WarpingTorque, "WARPING TORQUE"),
+  ]
+);
 
-impl BarForceField {
-  /// Returns the fields in the most commonly seen order.
-  pub const fn all() -> &'static [Self] {
-    return &[
-      Self::BendMoment { end: BarEnd::EndA, plane: BarPlane::Plane1 },
-      Self::BendMoment { end: BarEnd::EndA, plane: BarPlane::Plane2 },
-      Self::BendMoment { end: BarEnd::EndB, plane: BarPlane::Plane1 },
-      Self::BendMoment { end: BarEnd::EndB, plane: BarPlane::Plane2 },
-      Self::Shear { plane: BarPlane::Plane1 },
-      Self::Shear { plane: BarPlane::Plane2 },
-      Self::AxialForce,
-      Self::Torque
-    ];
+impl IndexType for BeamForceField {
+  const INDEX_NAME: &'static str = "BEAM FORCE FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
   }
+}
 
-  /// Returns a col index map for ease of use in decoders.
-  pub fn canonical_cols() -> BTreeMap<Self, usize> {
-    return Self::all()
-      .iter()
-      .copied()
-      .enumerate()
-      .map(|(a, b)| (b, a))
-      .collect();
+from_enum!(
+  "A column of a BEAM stress table, at a station.",
+  BeamStressField,
+  [
+    (LongStressC, "LONG. STRESS AT C"),
+    (LongStressD, "LONG. STRESS AT D"),
+    (LongStressE, "LONG. STRESS AT E"),
+    (LongStressF, "LONG. STRESS AT F"),
+    (MaxStress, "MAXIMUM STRESS"),
+    (MinStress, "MINIMUM STRESS"),
+    (MarginTension, "MARGIN OF SAFETY IN TENSION"),
+    (MarginCompression, "MARGIN OF SAFETY IN COMPRESSION"),
+  ]
+);
+
+impl IndexType for BeamStressField {
+  const INDEX_NAME: &'static str = "BEAM STRESS FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
   }
 }
 
+gen_with_inner!(
+  "A column of a BEAM strain table, at a station.",
+  "BEAM STRAIN FIELD",
+  BeamStrainField,
+  BeamStressField
+);
+
 from_enum!(
   "Generic single-force field.",
   SingleForce,
@@ -558,17 +1260,319 @@ from_enum!(
   ]
 );
 
+from_enum!(
+  "Generic single-temperature field.",
+  SingleTemperature,
+  [
+    (Temperature, "TEMPERATURE"),
+  ]
+);
+
 impl IndexType for SingleForce {
   const INDEX_NAME: &'static str = "FORCE";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
 }
 
 impl IndexType for SingleStress {
   const INDEX_NAME: &'static str = "STRESS";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
 }
 
 
 impl IndexType for SingleStrain {
   const INDEX_NAME: &'static str = "STRAIN";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+impl IndexType for SingleTemperature {
+  const INDEX_NAME: &'static str = "TEMPERATURE";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The columns for the failure indices table for composite elements: the \
+  failure index by the theory of the element, with its strength ratio, or \
+  the index by each criterion, with whether the ply fails, coded as an index \
+  into `PLY_STATUSES`.",
+  CompositeFailureField,
+  [
+    (FailureIndex, "FAILURE INDEX"),
+    (StrengthRatio, "STRENGTH RATIO"),
+    (TsaiWu, "TSAI-WU"),
+    (TsaiHill, "TSAI-HILL"),
+    (Hoffman, "HOFFMAN"),
+    (MaxStress, "MAX STRESS"),
+    (MaxStrain, "MAX STRAIN"),
+    (Failed, "FAILED"),
+  ]
+);
+
+/// The statuses of a ply, by the code they're stored as in the failed column
+/// of its failure indices by criterion.
+pub const PLY_STATUSES: [&str; 2] = ["NO FAIL", "FAIL"];
+
+impl IndexType for CompositeFailureField {
+  const INDEX_NAME: &'static str = "COMPOSITE FAILURE FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The columns for the stresses table for the plies of composite elements.",
+  CompositeStressField,
+  [
+    (Normal1, "NORMAL-1"),
+    (Normal2, "NORMAL-2"),
+    (Shear12, "SHEAR-12"),
+    (Shear1Z, "SHEAR-1Z"),
+    (Shear2Z, "SHEAR-2Z"),
+    (Angle, "ANGLE"),
+    (Major, "MAJOR"),
+    (Minor, "MINOR"),
+    (MaxShear, "MAX SHEAR"),
+    (VonMises, "VON MISES"),
+  ]
+);
+
+impl IndexType for CompositeStressField {
+  const INDEX_NAME: &'static str = "COMPOSITE STRESS FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The columns for the stresses table for grid points on a surface.",
+  GridStressField,
+  [
+    (NormalX, "NORMAL-X"),
+    (NormalY, "NORMAL-Y"),
+    (ShearXY, "SHEAR-XY"),
+    (Angle, "ANGLE"),
+    (Major, "MAJOR"),
+    (Minor, "MINOR"),
+    (MaxShear, "MAX SHEAR"),
+    (VonMises, "VON MISES"),
+  ]
+);
+
+impl IndexType for GridStressField {
+  const INDEX_NAME: &'static str = "GRID STRESS FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The columns for the strain energy of each element.",
+  StrainEnergyField,
+  [
+    (Energy, "STRAIN ENERGY"),
+    (PercentTotal, "PERCENT OF TOTAL"),
+    (Density, "STRAIN ENERGY DENSITY"),
+  ]
+);
+
+impl IndexType for StrainEnergyField {
+  const INDEX_NAME: &'static str = "STRAIN ENERGY FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The columns for the real eigenvalues table of a modal analysis.",
+  EigenvalueField,
+  [
+    (ExtractionOrder, "EXTRACTION ORDER"),
+    (Eigenvalue, "EIGENVALUE"),
+    (Radians, "RADIANS"),
+    (Cycles, "CYCLES"),
+    (GeneralizedMass, "GENERALIZED MASS"),
+    (GeneralizedStiffness, "GENERALIZED STIFFNESS"),
+  ]
+);
+
+impl IndexType for EigenvalueField {
+  const INDEX_NAME: &'static str = "EIGENVALUE FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The columns for the complex eigenvalue summary of a modal analysis.",
+  ComplexEigenvalueField,
+  [
+    (ExtractionOrder, "EXTRACTION ORDER"),
+    (RealPart, "EIGENVALUE (REAL)"),
+    (ImaginaryPart, "EIGENVALUE (IMAG)"),
+    (Frequency, "FREQUENCY"),
+    (Damping, "DAMPING COEFFICIENT"),
+  ]
+);
+
+impl IndexType for ComplexEigenvalueField {
+  const INDEX_NAME: &'static str = "COMPLEX EIGENVALUE FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The columns for the modal effective mass table of a modal analysis.",
+  ModalMassField,
+  [
+    (Tx, "T1"),
+    (Ty, "T2"),
+    (Tz, "T3"),
+    (Rx, "R1"),
+    (Ry, "R2"),
+    (Rz, "R3"),
+    (CumTx, "T1 CUMULATIVE"),
+    (CumTy, "T2 CUMULATIVE"),
+    (CumTz, "T3 CUMULATIVE"),
+    (CumRx, "R1 CUMULATIVE"),
+    (CumRy, "R2 CUMULATIVE"),
+    (CumRz, "R3 CUMULATIVE"),
+  ]
+);
+
+impl IndexType for ModalMassField {
+  const INDEX_NAME: &'static str = "MODAL MASS FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The quantities in the output of the grid point weight generator.",
+  GpwgRef,
+  [
+    (Mass, "MASS"),
+    (CgX, "CG X"),
+    (CgY, "CG Y"),
+    (CgZ, "CG Z"),
+    (Ixx, "IXX"),
+    (Iyy, "IYY"),
+    (Izz, "IZZ"),
+    (Ixy, "IXY"),
+    (Iyz, "IYZ"),
+    (Ixz, "IXZ"),
+  ]
+);
+
+impl IndexType for GpwgRef {
+  const INDEX_NAME: &'static str = "GPWG QUANTITY";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The column for the output of the grid point weight generator.",
+  GpwgField,
+  [
+    (Value, "VALUE"),
+  ]
+);
+
+impl IndexType for GpwgField {
+  const INDEX_NAME: &'static str = "GPWG FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+from_enum!(
+  "The columns for the tables of maximums per DOF: the largest magnitude in \
+  each DOF, then the grid point where each of them occurs.",
+  MaxSummaryField,
+  [
+    (Tx, "T1"),
+    (Ty, "T2"),
+    (Tz, "T3"),
+    (Rx, "R1"),
+    (Ry, "R2"),
+    (Rz, "R3"),
+    (GridTx, "T1 GRID"),
+    (GridTy, "T2 GRID"),
+    (GridTz, "T3 GRID"),
+    (GridRx, "R1 GRID"),
+    (GridRy, "R2 GRID"),
+    (GridRz, "R3 GRID"),
+  ]
+);
+
+impl IndexType for MaxSummaryField {
+  const INDEX_NAME: &'static str = "MAXIMUM SUMMARY FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
+}
+
+impl MaxSummaryField {
+  /// Returns the DOF this column is about.
+  pub const fn dof(&self) -> Dof {
+    return match self {
+      Self::Tx | Self::GridTx => DOF_TX,
+      Self::Ty | Self::GridTy => DOF_TY,
+      Self::Tz | Self::GridTz => DOF_TZ,
+      Self::Rx | Self::GridRx => DOF_RX,
+      Self::Ry | Self::GridRy => DOF_RY,
+      Self::Rz | Self::GridRz => DOF_RZ,
+    };
+  }
+
+  /// Returns true if this column holds grid point IDs, not magnitudes.
+  pub const fn is_grid(&self) -> bool {
+    return matches!(
+      self,
+      Self::GridTx
+        | Self::GridTy
+        | Self::GridTz
+        | Self::GridRx
+        | Self::GridRy
+        | Self::GridRz
+    );
+  }
+
+  /// Returns the column with the grid points for a DOF, or the one with the
+  /// magnitudes.
+  pub const fn of_dof(dof: Dof, grid: bool) -> Self {
+    let i = match (dof.dof_type, dof.axis) {
+      (DofType::Translational, Axis::X) => 0,
+      (DofType::Translational, Axis::Y) => 1,
+      (DofType::Translational, Axis::Z) => 2,
+      (DofType::Rotational, Axis::X) => 3,
+      (DofType::Rotational, Axis::Y) => 4,
+      (DofType::Rotational, Axis::Z) => 5,
+    };
+    return Self::all()[if grid { i + SIXDOF } else { i }];
+  }
 }
 
 impl From<SingleStress> for SingleStrain {
@@ -590,6 +1594,10 @@ from_enum!(
 
 impl IndexType for RodStressField {
   const INDEX_NAME: &'static str = "ROD STRESS FIELD";
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
 }
 
 gen_with_inner!(
@@ -658,6 +1666,11 @@ impl Display for BarStressField {
 
 impl IndexType for BarStressField {
   const INDEX_NAME: &'static str = "BAR STRESS FIELD";
+  const ROW_DIMENSION: RowDimension = RowDimension::End;
+
+  fn fixed_values() -> Vec<NasIndex> {
+    return into_nasindexes(Self::all());
+  }
 }
 
 impl BarStressField {
@@ -0,0 +1,294 @@
+//! This submodule implements a pretty-printer that writes blocks out as text
+//! tables, in a few different styles (plain, markdown, org-mode).
+
+use clap::ValueEnum;
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+use crate::util::{fmt_f64, non_finite_name};
+
+/// Separator between columns in plain tables.
+const PLAIN_SEP: &str = "  ";
+
+/// The output mode for a table.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, ValueEnum, PartialEq, Eq
+)]
+#[clap(rename_all = "snake_case")]
+pub enum TableMode {
+  /// Space-separated columns with a dashed line under the header.
+  Plain,
+  /// A markdown (GitHub-flavoured) table.
+  Markdown,
+  /// An org-mode table.
+  Org
+}
+
+impl Default for TableMode {
+  fn default() -> Self {
+    return Self::Plain;
+  }
+}
+
+/// How values are aligned inside a column.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, ValueEnum, PartialEq, Eq
+)]
+#[clap(rename_all = "snake_case")]
+pub enum TableAlignment {
+  /// Pad to the right.
+  Left,
+  /// Pad to the left.
+  Right,
+  /// Line up the decimal points and exponents of real values.
+  Decimal
+}
+
+impl Default for TableAlignment {
+  fn default() -> Self {
+    return Self::Right;
+  }
+}
+
+/// Display settings for a single column.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct ColumnStyle {
+  /// Minimum width of the column.
+  pub min_width: usize,
+  /// Maximum width of the column. Headers and units are truncated to fit it,
+  /// but values never are.
+  pub max_width: Option<usize>,
+  /// Alignment of the values.
+  pub alignment: TableAlignment,
+  /// Number of decimal places for reals; free-form if absent.
+  pub precision: Option<usize>,
+  /// Units string shown under the header.
+  pub units: Option<String>
+}
+
+/// Display settings for a whole table.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct TableStyle {
+  /// The output mode.
+  pub mode: TableMode,
+  /// Style for the row index column.
+  pub index: ColumnStyle,
+  /// Styles for the data columns, in order.
+  pub columns: Vec<ColumnStyle>,
+  /// Style for data columns not in the vector above.
+  pub default_column: ColumnStyle
+}
+
+/// A rendered cell, before padding.
+#[derive(Clone, Debug)]
+struct TableCell {
+  /// The text in the cell.
+  text: String,
+  /// Whether the text is a formatted real number.
+  real: bool
+}
+
+/// Splits a number into its integer, fraction and exponent parts.
+fn split_number(s: &str) -> (&str, &str, &str) {
+  let (mantissa, exp) = match s.find(['E', 'e']) {
+    Some(i) => s.split_at(i),
+    None => (s, "")
+  };
+  return match mantissa.find('.') {
+    Some(i) => (&mantissa[..i], &mantissa[i..], exp),
+    None => (mantissa, "", exp)
+  };
+}
+
+/// Lines up the decimal points and exponents of the real cells in a column.
+/// Only the digits that were printed are kept: shorter fractions are padded
+/// with spaces, never with zeroes, so no precision is made up.
+fn decimal_align(cells: &mut [&mut TableCell]) {
+  let (mut iw, mut fw, mut ew) = (0, 0, 0);
+  for cell in cells.iter().filter(|c| c.real) {
+    let (i, f, e) = split_number(&cell.text);
+    iw = iw.max(i.len());
+    fw = fw.max(f.len());
+    ew = ew.max(e.len());
+  }
+  for cell in cells.iter_mut().filter(|c| c.real) {
+    let (i, f, e) = split_number(&cell.text);
+    cell.text = format!(
+      "{:>iw$}{:<fw$}{:<ew$}",
+      i,
+      f,
+      e,
+      iw = iw,
+      fw = fw,
+      ew = ew
+    );
+  }
+}
+
+/// Truncates a string to a maximum number of characters.
+fn truncate(s: &str, max: Option<usize>) -> String {
+  return match max {
+    Some(m) => s.chars().take(m).collect(),
+    None => s.to_owned()
+  };
+}
+
+/// Pads a string to a width according to an alignment.
+fn pad(s: &str, width: usize, alignment: TableAlignment) -> String {
+  return match alignment {
+    TableAlignment::Left => format!("{:<w$}", s, w = width),
+    TableAlignment::Right | TableAlignment::Decimal => {
+      format!("{:>w$}", s, w = width)
+    },
+  };
+}
+
+impl TableStyle {
+  /// Instantiates a default style with a certain mode.
+  pub fn new(mode: TableMode) -> Self {
+    return Self { mode, ..Default::default() };
+  }
+
+  /// Returns the style for the n-th data column.
+  pub fn column(&self, n: usize) -> &ColumnStyle {
+    return self.columns.get(n).unwrap_or(&self.default_column);
+  }
+
+  /// Formats a number according to a column style.
+  fn format_number(style: &ColumnStyle, num: F06Number) -> TableCell {
+    return match num {
+      F06Number::Real(x) => {
+        let mut text = String::new();
+        match style.precision {
+          Some(p) => {
+            fmt_f64(&mut text, x, 0, p, 2, true, true)
+              .expect("writing to a string shouldn't fail");
+          },
+          None => text = match non_finite_name(x) {
+            Some(name) => name.to_owned(),
+            None => format!("{:E}", x)
+          }
+        };
+        TableCell { text, real: true }
+      },
+      _ => TableCell { text: num.to_string(), real: false }
+    };
+  }
+
+  /// Renders a block as a table.
+  pub fn render(&self, block: &FinalBlock) -> String {
+    let mut rows: Vec<(&NasIndex, &usize)> = block.row_indexes.iter()
+      .collect();
+    rows.sort_by_key(|(_, i)| **i);
+    let mut cols: Vec<(&NasIndex, &usize)> = block.col_indexes.iter()
+      .collect();
+    cols.sort_by_key(|(_, i)| **i);
+    // the header, with the row index name first
+    let mut header: Vec<String> = vec![
+      rows.first().map(|(ri, _)| ri.index_name()).unwrap_or("").to_owned()
+    ];
+    header.extend(cols.iter().map(|(ci, _)| ci.to_string()));
+    // then the cells
+    let body: Vec<Vec<TableCell>> = rows.iter()
+      .map(|(ri, _)| {
+        let mut row = vec![TableCell { text: ri.to_string(), real: false }];
+        row.extend(cols.iter().enumerate().map(|(n, (ci, _))| {
+          return match block.get(**ri, **ci) {
+            Some(num) => Self::format_number(self.column(n), num),
+            None => TableCell { text: String::new(), real: false }
+          };
+        }));
+        return row;
+      }).collect();
+    return self.render_cells(header, body);
+  }
+
+  /// Renders a header and some pre-formatted cells as a table.
+  fn render_cells(
+    &self,
+    header: Vec<String>,
+    mut body: Vec<Vec<TableCell>>
+  ) -> String {
+    let ncols = header.len();
+    let styles: Vec<&ColumnStyle> = std::iter::once(&self.index)
+      .chain((0..ncols.saturating_sub(1)).map(|n| self.column(n)))
+      .collect();
+    let has_units = styles.iter().any(|s| s.units.is_some());
+    // markdown only has one header row, so units go in there
+    let header: Vec<String> = header.iter()
+      .zip(styles.iter())
+      .map(|(h, s)| match (self.mode, &s.units) {
+        (TableMode::Markdown, Some(u)) => format!("{} [{}]", h, u),
+        _ => h.clone()
+      })
+      .zip(styles.iter())
+      .map(|(h, s)| truncate(&h, s.max_width))
+      .collect();
+    let units: Vec<String> = styles.iter()
+      .map(|s| truncate(s.units.as_deref().unwrap_or(""), s.max_width))
+      .collect();
+    let units_row = has_units && self.mode != TableMode::Markdown;
+    // line up the decimal points where requested
+    for (n, style) in styles.iter().enumerate() {
+      if style.alignment == TableAlignment::Decimal {
+        let mut col: Vec<&mut TableCell> = body.iter_mut()
+          .filter_map(|r| r.get_mut(n))
+          .collect();
+        decimal_align(&mut col);
+      }
+    }
+    // compute widths
+    let widths: Vec<usize> = (0..ncols).map(|n| {
+      let mut w = styles[n].min_width.max(header[n].len());
+      if units_row {
+        w = w.max(units[n].len());
+      }
+      return body.iter()
+        .filter_map(|r| r.get(n))
+        .map(|c| c.text.len())
+        .fold(w, usize::max);
+    }).collect();
+    // and write it all out
+    let fmt_row = |cells: Vec<String>| -> String {
+      let padded = cells.iter()
+        .enumerate()
+        .map(|(n, c)| pad(c, widths[n], styles[n].alignment));
+      return match self.mode {
+        TableMode::Plain => padded.collect::<Vec<_>>().join(PLAIN_SEP),
+        TableMode::Markdown | TableMode::Org => format!(
+          "| {} |",
+          padded.collect::<Vec<_>>().join(" | ")
+        ),
+      };
+    };
+    let rule = match self.mode {
+      TableMode::Plain => widths.iter()
+        .map(|w| "-".repeat(*w))
+        .collect::<Vec<_>>()
+        .join(PLAIN_SEP),
+      TableMode::Markdown => format!("|{}|", widths.iter()
+        .zip(styles.iter())
+        .map(|(w, s)| match s.alignment {
+          TableAlignment::Left => format!(":{}", "-".repeat(w + 1)),
+          _ => format!("{}:", "-".repeat(w + 1))
+        })
+        .collect::<Vec<_>>()
+        .join("|")),
+      TableMode::Org => format!("|{}|", widths.iter()
+        .map(|w| "-".repeat(w + 2))
+        .collect::<Vec<_>>()
+        .join("+")),
+    };
+    let mut lines: Vec<String> = vec![fmt_row(header)];
+    if units_row {
+      lines.push(fmt_row(units));
+    }
+    lines.push(rule);
+    for row in body {
+      lines.push(fmt_row(row.into_iter().map(|c| c.text).collect()));
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    return out;
+  }
+}
@@ -7,9 +7,41 @@ use serde::{Serialize, Deserialize};
 use convert_case::{Case, Casing};
 
 use crate::prelude::*;
+#[cfg(feature = "parser")]
 use crate::blocks::decoders::*;
 
-/// Generates the BlockType enum and calls the init functions for them.
+/// Describes the shape of the data in a type of block.
+#[derive(Copy, Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct BlockSchema {
+  /// The name of the row index type.
+  pub row_index: &'static str,
+  /// The name of the column index type.
+  pub col_index: &'static str,
+  /// The extra dimension rows are broken into, beyond the element or grid
+  /// point. Comes from the row index, or failing that, the column index
+  /// (e.g. bar ends are columns, but are usually written as separate rows).
+  pub row_dimension: RowDimension
+}
+
+impl BlockSchema {
+  /// Computes the schema from the index types.
+  const fn of<R: IndexType, C: IndexType>() -> Self {
+    let row_dimension = if R::ROW_DIMENSION.is_extra() {
+      R::ROW_DIMENSION
+    } else {
+      C::ROW_DIMENSION
+    };
+    return Self {
+      row_index: R::INDEX_NAME,
+      col_index: C::INDEX_NAME,
+      row_dimension
+    };
+  }
+}
+
+/// Generates the BlockType enum and calls the init functions for them. Each
+/// block gets a description, a name, a decoder, its row and column index
+/// types, an element type and the headers that start it.
 macro_rules! gen_block_types {
   (
     $(
@@ -17,6 +49,7 @@ macro_rules! gen_block_types {
         $desc:literal,
         $bname:ident,
         $dec:ty,
+        ($row:ty, $col:ty),
         $etype:expr,
         $spaceds:expr
       },
@@ -42,6 +75,7 @@ macro_rules! gen_block_types {
       }
 
       /// Instantiates the decoder for this data block type.
+      #[cfg(feature = "parser")]
       pub fn init_decoder(&self, flavour: Flavour) -> Box<dyn OpaqueDecoder> {
         return match self {
           $(
@@ -86,7 +120,35 @@ macro_rules! gen_block_types {
         };
       }
 
+      /// Returns the schema for this block type.
+      pub const fn schema(&self) -> BlockSchema {
+        return match self {
+          $(
+            Self::$bname => BlockSchema::of::<$row, $col>(),
+          )*
+        };
+      }
+
+      /// Returns all the columns a block of this type can have, in canonical
+      /// order.
+      pub fn columns(&self) -> Vec<NasIndex> {
+        return match self {
+          $(
+            Self::$bname => <$col as IndexType>::fixed_values(),
+          )*
+        };
+      }
     }
+
+    // the index types are spelled out so the schema is known without the
+    // parser, so make sure they're the ones the decoders actually use.
+    $(
+      #[cfg(feature = "parser")]
+      const _: (
+        fn(<$dec as BlockDecoder>::RowIndex) -> $row,
+        fn(<$dec as BlockDecoder>::ColumnIndex) -> $col
+      ) = (|ri| ri, |ci| ci);
+    )*
   }
 }
 
@@ -96,14 +158,47 @@ gen_block_types!(
     "Grid point displacements",
     Displacements,
     DisplacementsDecoder,
+    (GridPointRef, Dof),
     None,
     ["DISPLACEMENTS", "DISPLACEMENT VECTOR"]
   },
+  // velocities
+  {
+    "Grid point velocities",
+    Velocities,
+    VelocitiesDecoder,
+    (GridPointRef, Dof),
+    None,
+    ["VELOCITIES", "VELOCITY VECTOR"]
+  },
+  // accelerations
+  {
+    "Grid point accelerations",
+    Accelerations,
+    AccelerationsDecoder,
+    (GridPointRef, Dof),
+    None,
+    ["ACCELERATIONS", "ACCELERATION VECTOR"]
+  },
+  // solution set displacements
+  {
+    "Solution set displacements",
+    SolutionSetDisplacements,
+    SolutionSetDisplacementsDecoder,
+    (SeqRef, Dof),
+    None,
+    [
+      "DISPLACEMENT VECTOR (SOLUTION SET)",
+      "DISPLACEMENTS (SOLUTION SET)",
+      "A-SET DISPLACEMENT"
+    ]
+  },
   // grid point force balance
   {
     "Grid point force balance",
     GridPointForceBalance,
     GridPointForceBalanceDecoder,
+    (GridPointForceOrigin, Dof),
     None,
     ["GRID POINT FORCE BALANCE"]
   },
@@ -112,22 +207,52 @@ gen_block_types!(
     "Forces of single-point constraint",
     SpcForces,
     SpcForcesDecoder,
+    (GridPointRef, Dof),
     None,
     ["SPC FORCES", "FORCES OF SINGLE-POINT CONSTRAINT"]
   },
+  // mpc forces
+  {
+    "Forces of multi-point constraint",
+    MpcForces,
+    MpcForcesDecoder,
+    (GridPointRef, Dof),
+    None,
+    ["MPC FORCES", "FORCES OF MULTI-POINT CONSTRAINT"]
+  },
   // applied forces
   {
     "Applied forces",
     AppliedForces,
     AppliedForcesDecoder,
+    (GridPointRef, Dof),
     None,
     ["APPLIED FORCES", "LOAD VECTOR"]
   },
+  // equivalent thermal loads
+  {
+    "Equivalent nodal forces of thermal loads",
+    ThermalLoads,
+    ThermalLoadsDecoder,
+    (GridPointRef, Dof),
+    None,
+    ["THERMAL LOAD VECTOR", "EQUIVALENT NODAL FORCES DUE TO THERMAL LOAD"]
+  },
+  // temperatures
+  {
+    "Grid point temperatures",
+    Temperatures,
+    TemperaturesDecoder,
+    (GridPointRef, SingleTemperature),
+    None,
+    ["TEMPERATURE VECTOR", "TEMPERATURES"]
+  },
   // elas1 forces
   {
     "Engineering forces in ELAS1 elements",
     Elas1Forces,
     Elas1ForcesDecoder,
+    (ElementRef, SingleForce),
     Some(ElementType::Elas1),
     [
       "FORCES IN SCALAR SPRINGS (CELAS1)",
@@ -139,6 +264,7 @@ gen_block_types!(
     "Stresses in ELAS1 elements",
     Elas1Stresses,
     Elas1StressesDecoder,
+    (ElementRef, SingleStress),
     Some(ElementType::Elas1),
     [
       "STRESSES IN SCALAR SPRINGS (CELAS1)",
@@ -153,6 +279,7 @@ gen_block_types!(
     "Strains in ELAS1 elements",
     Elas1Strains,
     Elas1StrainsDecoder,
+    (ElementRef, SingleStrain),
     Some(ElementType::Elas1),
     [
       "STRAINS IN SCALAR SPRINGS (CELAS1)",
@@ -162,11 +289,168 @@ gen_block_types!(
       )
     ]
   },
+  // elas2 forces
+  {
+    "Engineering forces in ELAS2 elements",
+    Elas2Forces,
+    Elas2ForcesDecoder,
+    (ElementRef, SingleForce),
+    Some(ElementType::Elas2),
+    [
+      "FORCES IN SCALAR SPRINGS (CELAS2)",
+      "ELEMENT ENGINEERING FORCES FOR ELEMENT TYPE ELAS2"
+    ]
+  },
+  // elas2 stresses
+  {
+    "Stresses in ELAS2 elements",
+    Elas2Stresses,
+    Elas2StressesDecoder,
+    (ElementRef, SingleStress),
+    Some(ElementType::Elas2),
+    [
+      "STRESSES IN SCALAR SPRINGS (CELAS2)",
+      concat!(
+        "ELEMENT STRESSES IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE ELAS2"
+      )
+    ]
+  },
+  // elas2 strains
+  {
+    "Strains in ELAS2 elements",
+    Elas2Strains,
+    Elas2StrainsDecoder,
+    (ElementRef, SingleStrain),
+    Some(ElementType::Elas2),
+    [
+      "STRAINS IN SCALAR SPRINGS (CELAS2)",
+      concat!(
+        "ELEMENT STRAINS IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE ELAS2"
+      )
+    ]
+  },
+  // elas3 forces
+  {
+    "Engineering forces in ELAS3 elements",
+    Elas3Forces,
+    Elas3ForcesDecoder,
+    (ElementRef, SingleForce),
+    Some(ElementType::Elas3),
+    [
+      "FORCES IN SCALAR SPRINGS (CELAS3)",
+      "ELEMENT ENGINEERING FORCES FOR ELEMENT TYPE ELAS3"
+    ]
+  },
+  // elas3 stresses
+  {
+    "Stresses in ELAS3 elements",
+    Elas3Stresses,
+    Elas3StressesDecoder,
+    (ElementRef, SingleStress),
+    Some(ElementType::Elas3),
+    [
+      "STRESSES IN SCALAR SPRINGS (CELAS3)",
+      concat!(
+        "ELEMENT STRESSES IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE ELAS3"
+      )
+    ]
+  },
+  // elas3 strains
+  {
+    "Strains in ELAS3 elements",
+    Elas3Strains,
+    Elas3StrainsDecoder,
+    (ElementRef, SingleStrain),
+    Some(ElementType::Elas3),
+    [
+      "STRAINS IN SCALAR SPRINGS (CELAS3)",
+      concat!(
+        "ELEMENT STRAINS IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE ELAS3"
+      )
+    ]
+  },
+  // elas4 forces
+  {
+    "Engineering forces in ELAS4 elements",
+    Elas4Forces,
+    Elas4ForcesDecoder,
+    (ElementRef, SingleForce),
+    Some(ElementType::Elas4),
+    [
+      "FORCES IN SCALAR SPRINGS (CELAS4)",
+      "ELEMENT ENGINEERING FORCES FOR ELEMENT TYPE ELAS4"
+    ]
+  },
+  // elas4 stresses
+  {
+    "Stresses in ELAS4 elements",
+    Elas4Stresses,
+    Elas4StressesDecoder,
+    (ElementRef, SingleStress),
+    Some(ElementType::Elas4),
+    [
+      "STRESSES IN SCALAR SPRINGS (CELAS4)",
+      concat!(
+        "ELEMENT STRESSES IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE ELAS4"
+      )
+    ]
+  },
+  // elas4 strains
+  {
+    "Strains in ELAS4 elements",
+    Elas4Strains,
+    Elas4StrainsDecoder,
+    (ElementRef, SingleStrain),
+    Some(ElementType::Elas4),
+    [
+      "STRAINS IN SCALAR SPRINGS (CELAS4)",
+      concat!(
+        "ELEMENT STRAINS IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE ELAS4"
+      )
+    ]
+  },
+  // scalar damper forces
+  {
+    "Engineering forces in scalar dampers",
+    CdampForces,
+    CdampForcesDecoder,
+    (ElementRef, SingleForce),
+    None,
+    [
+      "FORCES IN SCALAR DAMPERS (CDAMP1)",
+      "FORCES IN SCALAR DAMPERS (CDAMP2)",
+      "FORCES IN SCALAR DAMPERS (CDAMP3)",
+      "FORCES IN SCALAR DAMPERS (CDAMP4)",
+      "ELEMENT ENGINEERING FORCES FOR ELEMENT TYPE DAMP1",
+      "ELEMENT ENGINEERING FORCES FOR ELEMENT TYPE DAMP2",
+      "ELEMENT ENGINEERING FORCES FOR ELEMENT TYPE DAMP3",
+      "ELEMENT ENGINEERING FORCES FOR ELEMENT TYPE DAMP4"
+    ]
+  },
+  // viscous damper forces
+  {
+    "Engineering forces in viscous dampers",
+    ViscForces,
+    RodForcesDecoder<Cvisc>,
+    (ElementRef, RodForceField),
+    Some(ElementType::Visc),
+    [
+      "FORCES IN VISC ELEMENTS (CVISC)",
+      "ELEMENT ENGINEERING FORCES FOR ELEMENT TYPE VISC"
+    ]
+  },
   // rod forces
   {
     "Engineering forces in rod elements",
     RodForces,
-    RodForcesDecoder,
+    RodForcesDecoder<Crod>,
+    (ElementRef, RodForceField),
     Some(ElementType::Rod),
     [
       "FORCES IN ROD ELEMENTS (CROD)",
@@ -177,7 +461,8 @@ gen_block_types!(
   {
     "Stresses in rod elements",
     RodStresses,
-    RodStressesDecoder,
+    RodStressesDecoder<Crod>,
+    (ElementRef, RodStressField),
     Some(ElementType::Rod),
     [
       "STRESSES IN ROD ELEMENTS (CROD)",
@@ -192,6 +477,7 @@ gen_block_types!(
     "Strains in rod elements",
     RodStrains,
     RodStrainsDecoder,
+    (ElementRef, RodStrainField),
     Some(ElementType::Rod),
     [
       "STRAINS IN ROD ELEMENTS (CROD)",
@@ -201,11 +487,54 @@ gen_block_types!(
       )
     ]
   },
+  // conrod forces
+  {
+    "Engineering forces in CONROD elements",
+    ConrodForces,
+    RodForcesDecoder<Conrod>,
+    (ElementRef, RodForceField),
+    Some(ElementType::Conrod),
+    [
+      "FORCES IN ROD ELEMENTS (CONROD)",
+      "ELEMENT ENGINEERING FORCES FOR ELEMENT TYPE CONROD"
+    ]
+  },
+  // conrod stresses
+  {
+    "Stresses in CONROD elements",
+    ConrodStresses,
+    RodStressesDecoder<Conrod>,
+    (ElementRef, RodStressField),
+    Some(ElementType::Conrod),
+    [
+      "STRESSES IN ROD ELEMENTS (CONROD)",
+      concat!(
+        "ELEMENT STRESSES IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE CONROD"
+      )
+    ]
+  },
+  // conrod strains
+  {
+    "Strains in CONROD elements",
+    ConrodStrains,
+    ConrodStrainsDecoder,
+    (ElementRef, RodStrainField),
+    Some(ElementType::Conrod),
+    [
+      "STRAINS IN ROD ELEMENTS (CONROD)",
+      concat!(
+        "ELEMENT STRAINS IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE CONROD"
+      )
+    ]
+  },
   // bar forces
   {
     "Engineering forces in bar elements",
     BarForces,
     BarForcesDecoder,
+    (ElementRef, BarForceField),
     Some(ElementType::Bar),
     [
       "FORCES IN BAR ELEMENTS (CBAR)",
@@ -217,6 +546,7 @@ gen_block_types!(
     "Stresses in bar elements",
     BarStresses,
     BarStressesDecoder,
+    (ElementRef, BarStressField),
     Some(ElementType::Bar),
     [
       "STRESSES IN BAR ELEMENTS (CBAR)",
@@ -231,6 +561,7 @@ gen_block_types!(
     "Strains in bar elements",
     BarStrains,
     BarStrainsDecoder,
+    (ElementRef, BarStrainField),
     Some(ElementType::Bar),
     [
       "STRAINS IN BAR ELEMENTS (CBAR)",
@@ -240,11 +571,54 @@ gen_block_types!(
       )
     ]
   },
+  // beam forces
+  {
+    "Engineering forces in beam elements",
+    BeamForces,
+    BeamForcesDecoder,
+    (ElementStation, BeamForceField),
+    Some(ElementType::Beam),
+    [
+      "FORCES IN BEAM ELEMENTS (CBEAM)",
+      "ELEMENT ENGINEERING FORCES FOR ELEMENT TYPE BEAM"
+    ]
+  },
+  // beam stresses
+  {
+    "Stresses in beam elements",
+    BeamStresses,
+    BeamStressesDecoder,
+    (ElementStation, BeamStressField),
+    Some(ElementType::Beam),
+    [
+      "STRESSES IN BEAM ELEMENTS (CBEAM)",
+      concat!(
+        "ELEMENT STRESSES IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE BEAM"
+      )
+    ]
+  },
+  // beam strains
+  {
+    "Strains in beam elements",
+    BeamStrains,
+    BeamStrainsDecoder,
+    (ElementStation, BeamStrainField),
+    Some(ElementType::Beam),
+    [
+      "STRAINS IN BEAM ELEMENTS (CBEAM)",
+      concat!(
+        "ELEMENT STRAINS IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE BEAM"
+      )
+    ]
+  },
   // tria forces
   {
     "Engineering forces in triangular elements",
     TriaForces,
     TriaForcesDecoder,
+    (ElementRef, PlateForceField),
     Some(ElementType::Tria3),
     [
       "FORCES IN TRIANGULAR ELEMENTS (CTRIA3)",
@@ -256,6 +630,7 @@ gen_block_types!(
     "Stresses in triangular elements",
     TriaStresses,
     TriaStressesDecoder,
+    (ElementSidedPoint, PlateStressField),
     Some(ElementType::Tria3),
     [
       "STRESSES IN TRIANGULAR ELEMENTS (CTRIA3)",
@@ -270,6 +645,7 @@ gen_block_types!(
     "Strains in triangular elements",
     TriaStrains,
     TriaStrainsDecoder,
+    (ElementSidedPoint, PlateStrainField),
     Some(ElementType::Tria3),
     [
       "STRAINS IN TRIANGULAR ELEMENTS (CTRIA3)",
@@ -284,6 +660,7 @@ gen_block_types!(
     "Engineering forces in quadrilateral elements",
     QuadForces,
     QuadForcesDecoder,
+    (PointInElement, PlateForceField),
     Some(ElementType::Quad4),
     [
       "FORCES IN QUADRILATERAL ELEMENTS (QUAD4)",
@@ -295,6 +672,7 @@ gen_block_types!(
     "Stresses in quadrilateral elements",
     QuadStresses,
     QuadStressesDecoder,
+    (ElementSidedPoint, PlateStressField),
     Some(ElementType::Quad4),
     [
       "STRESSES IN QUADRILATERAL ELEMENTS (QUAD4)",
@@ -309,6 +687,7 @@ gen_block_types!(
     "Strains in quadrilateral elements",
     QuadStrains,
     QuadStrainsDecoder,
+    (ElementSidedPoint, PlateStrainField),
     Some(ElementType::Quad4),
     [
       "STRAINS IN QUADRILATERAL ELEMENTS (QUAD4)",
@@ -318,11 +697,178 @@ gen_block_types!(
       )
     ]
   },
+  // chexa stresses
+  {
+    "Stresses in hexahedral solid elements",
+    ChexaStresses,
+    ChexaStressesDecoder,
+    (ElementSidedPoint, ChexaStressField),
+    Some(ElementType::Hexa),
+    [
+      "STRESSES IN HEXAHEDRON SOLID ELEMENTS (HEXA)",
+      concat!(
+        "ELEMENT STRESSES IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE HEXA"
+      )
+    ]
+  },
+  // chexa strains
+  {
+    "Strains in hexahedral solid elements",
+    ChexaStrains,
+    ChexaStrainsDecoder,
+    (ElementSidedPoint, ChexaStrainField),
+    Some(ElementType::Hexa),
+    [
+      "STRAINS IN HEXAHEDRON SOLID ELEMENTS (HEXA)",
+      "STRAINS IN HEXAHEDRAL SOLID ELEMENTS",
+      concat!(
+        "ELEMENT STRAINS IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE HEXA"
+      )
+    ]
+  },
+  // cpenta stresses
+  {
+    "Stresses in pentahedral solid elements",
+    CpentaStresses,
+    CpentaStressesDecoder,
+    (ElementSidedPoint, CpentaStressField),
+    Some(ElementType::Penta),
+    [
+      "STRESSES IN PENTAHEDRON SOLID ELEMENTS (PENTA)",
+      concat!(
+        "ELEMENT STRESSES IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE PENTA"
+      )
+    ]
+  },
+  // cpenta strains
+  {
+    "Strains in pentahedral solid elements",
+    CpentaStrains,
+    CpentaStrainsDecoder,
+    (ElementSidedPoint, CpentaStrainField),
+    Some(ElementType::Penta),
+    [
+      "STRAINS IN PENTAHEDRON SOLID ELEMENTS (PENTA)",
+      concat!(
+        "ELEMENT STRAINS IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE PENTA"
+      )
+    ]
+  },
+  // ctetra stresses
+  {
+    "Stresses in tetrahedral solid elements",
+    CtetraStresses,
+    CtetraStressesDecoder,
+    (ElementSidedPoint, CtetraStressField),
+    Some(ElementType::Tetra),
+    [
+      "STRESSES IN TETRAHEDRON SOLID ELEMENTS",
+      "STRESSES IN TETRAHEDRAL SOLID ELEMENTS",
+      concat!(
+        "ELEMENT STRESSES IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE TETRA"
+      )
+    ]
+  },
+  // ctetra strains
+  {
+    "Strains in tetrahedral solid elements",
+    CtetraStrains,
+    CtetraStrainsDecoder,
+    (ElementSidedPoint, CtetraStrainField),
+    Some(ElementType::Tetra),
+    [
+      "STRAINS IN TETRAHEDRON SOLID ELEMENTS",
+      "STRAINS IN TETRAHEDRAL SOLID ELEMENTS",
+      concat!(
+        "ELEMENT STRAINS IN LOCAL ELEMENT COORDINATE SYSTEM ",
+        "FOR ELEMENT TYPE TETRA"
+      )
+    ]
+  },
+  // cshear forces
+  {
+    "Forces in shear panels",
+    CshearForces,
+    CshearForcesDecoder,
+    (ElementRef, CshearForceField),
+    Some(ElementType::Shear),
+    [
+      "FORCES IN SHEAR PANELS (CSHEAR)"
+    ]
+  },
+  // cshear stresses
+  {
+    "Stresses in shear panels",
+    CshearStresses,
+    CshearStressesDecoder,
+    (ElementRef, CshearStressField),
+    Some(ElementType::Shear),
+    [
+      "STRESSES IN SHEAR PANELS (CSHEAR)"
+    ]
+  },
+  // cgap forces
+  {
+    "Forces in gap elements",
+    CgapForces,
+    CgapForcesDecoder,
+    (ElementRef, CgapForceField),
+    Some(ElementType::Gap),
+    [
+      "FORCES IN GAP ELEMENTS",
+      "ELEMENT ENGINEERING FORCES FOR ELEMENT TYPE GAP"
+    ]
+  },
+  // composite failure indices
+  {
+    "Failure indices in layered composite elements",
+    CompositeFailureIndices,
+    CompositeFailureIndicesDecoder,
+    (ElementPlyRef, CompositeFailureField),
+    None,
+    [
+      "FAILURE INDICES FOR LAYERED COMPOSITE ELEMENTS",
+      "FAILURE INDICES IN LAYERED COMPOSITE ELEMENTS"
+    ]
+  },
+  // grid point stresses
+  {
+    "Stresses at grid points on a surface",
+    GridPointStresses,
+    GridPointStressDecoder,
+    (GridSurfacePoint, GridStressField),
+    None,
+    ["STRESSES AT GRID POINTS"]
+  },
+  // element strain energies
+  {
+    "Strain energy of each element",
+    StrainEnergy,
+    StrainEnergyDecoder,
+    (ElementRef, StrainEnergyField),
+    None,
+    ["ELEMENT STRAIN ENERGIES"]
+  },
+  // composite ply stresses
+  {
+    "Stresses in the plies of layered composite elements",
+    CompositePlystresses,
+    CompositePlyStressDecoder,
+    (ElementPlyRef, CompositeStressField),
+    None,
+    ["STRESSES IN LAYERED COMPOSITE ELEMENTS"]
+  },
   // bush forces
   {
     "Engineering forces in BUSH elements",
     BushForces,
     BushForcesDecoder,
+    (ElementRef, Dof),
     Some(ElementType::Bush),
     [
       "FORCES IN BUSH ELEMENTS (CBUSH)",
@@ -334,6 +880,7 @@ gen_block_types!(
     "Stresses in BUSH elements",
     BushStresses,
     BushStressesDecoder,
+    (ElementRef, BushStressField),
     Some(ElementType::Bush),
     [
       "STRESSES IN BUSH ELEMENTS (CBUSH)",
@@ -348,6 +895,7 @@ gen_block_types!(
     "Strains in BUSH elements",
     BushStrains,
     BushStrainsDecoder,
+    (ElementRef, BushStrainField),
     Some(ElementType::Bush),
     [
       "STRAINS IN BUSH ELEMENTS (CBUSH)",
@@ -357,6 +905,89 @@ gen_block_types!(
       )
     ]
   },
+  // real eigenvalues
+  {
+    "Real eigenvalues",
+    RealEigenvalues,
+    RealEigenvalueDecoder,
+    (ModeRef, EigenvalueField),
+    None,
+    ["REAL EIGENVALUES"]
+  },
+  // buckling eigenvalues; MYSTRAN names them in the header, but Simcenter
+  // prints them just like the real eigenvalues, so those headers are told
+  // apart by the solution (see `in_solution`)
+  {
+    "Buckling eigenvalues",
+    BucklingEigenvalues,
+    BucklingEigenvalueDecoder,
+    (ModeRef, EigenvalueField),
+    None,
+    ["BUCKLING EIGENVALUES"]
+  },
+  // complex eigenvalues
+  {
+    "Complex eigenvalues",
+    ComplexEigenvalues,
+    ComplexEigenvalueDecoder,
+    (ModeRef, ComplexEigenvalueField),
+    None,
+    ["COMPLEX EIGENVALUE SUMMARY"]
+  },
+  // real eigenvectors
+  {
+    "Real eigenvectors",
+    Eigenvectors,
+    EigenvectorDecoder,
+    (GridPointRef, Dof),
+    None,
+    ["REAL EIGENVECTOR"]
+  },
+  // modal effective masses
+  {
+    "Modal effective masses",
+    ModalEffectiveMass,
+    ModalEffectiveMassDecoder,
+    (ModeRef, ModalMassField),
+    None,
+    ["MODAL EFFECTIVE MASS"]
+  },
+  // grid point weight generator
+  {
+    "Grid point weight generator output",
+    GridPointWeight,
+    GpwgDecoder,
+    (GpwgRef, GpwgField),
+    None,
+    ["GRID POINT WEIGHT GENERATOR"]
+  },
+  // maximum displacements
+  {
+    "Maximum displacements per DOF",
+    MaxDisplacements,
+    MaxDisplacementsDecoder,
+    (CaseRef, MaxSummaryField),
+    None,
+    ["MAXIMUM DISPLACEMENTS"]
+  },
+  // maximum applied loads
+  {
+    "Maximum applied loads per DOF",
+    MaxAppliedLoads,
+    MaxAppliedLoadsDecoder,
+    (CaseRef, MaxSummaryField),
+    None,
+    ["MAXIMUM APPLIED LOADS"]
+  },
+  // maximum spc forces
+  {
+    "Maximum SPC forces per DOF",
+    MaxSpcForces,
+    MaxSpcForcesDecoder,
+    (CaseRef, MaxSummaryField),
+    None,
+    ["MAXIMUM SPCFORCES"]
+  },
 );
 
 impl Display for BlockType {
@@ -364,3 +995,18 @@ impl Display for BlockType {
     return write!(f, "{}", self.desc());
   }
 }
+
+impl BlockType {
+  /// Returns the block type a header of this one actually starts in a
+  /// solution. Some tables are printed alike by different solutions but mean
+  /// different things in each (e.g. the real eigenvalues of a linear buckling
+  /// analysis are its load factors).
+  pub const fn in_solution(&self, soltype: Option<SolType>) -> Self {
+    return match (self, soltype) {
+      (Self::RealEigenvalues, Some(SolType::LinearBuckling)) => {
+        Self::BucklingEigenvalues
+      },
+      _ => *self
+    };
+  }
+}
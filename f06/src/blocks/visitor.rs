@@ -0,0 +1,373 @@
+//! This submodule implements walking over the parts of a `NasIndex` (grid
+//! points, elements, DOFs and so on) without matching on all of its variants,
+//! and mapping the IDs within one.
+//!
+//! Every index type implements `VisitableIndex`, so a new variant in
+//! `NasIndex` won't compile until it says how it breaks down; code built on
+//! `NasIndexVisitor` then handles it without changes.
+
+use crate::prelude::*;
+
+/// Something that's fed the parts of indexes by `NasIndex::visit`. Compound
+/// indexes (e.g. an element and a point within it) are broken down into their
+/// parts, in order, between calls to `compound_start` and `compound_end`.
+/// Everything is ignored unless overridden.
+pub trait NasIndexVisitor {
+  /// Called before the parts of a compound index, with its index name.
+  fn compound_start(&mut self, _index_name: &'static str) {}
+
+  /// Called after the parts of a compound index, with its index name.
+  fn compound_end(&mut self, _index_name: &'static str) {}
+
+  /// A grid point.
+  fn grid(&mut self, _grid_point: GridPointRef) {}
+
+  /// An element. Elements applying a force to a grid point are visited too,
+  /// after their force origin.
+  fn element(&mut self, _element: ElementRef) {}
+
+  /// A degree of freedom.
+  fn dof(&mut self, _dof: Dof) {}
+
+  /// A point within an element.
+  fn point(&mut self, _point: ElementPoint) {}
+
+  /// Where a force applied to a grid point comes from.
+  fn force_origin(&mut self, _force_origin: ForceOrigin) {}
+
+  /// A side of an element.
+  fn side(&mut self, _side: ElementSide) {}
+
+  /// A station along an element, as a fraction of its length from end A.
+  fn station(&mut self, _fraction: f64) {}
+
+  /// A layer in the laminate of a composite element.
+  fn layer(&mut self, _layer: CompositeLayer) {}
+
+  /// A surface of grid point stress output, by ID.
+  fn surface(&mut self, _surface: usize) {}
+
+  /// A sequence number in a solution set.
+  fn seq(&mut self, _seq: SeqRef) {}
+
+  /// A mode (or root).
+  fn mode(&mut self, _mode: ModeRef) {}
+
+  /// A subcase or load set, in a summary across them.
+  fn case(&mut self, _case: CaseRef) {}
+
+  /// A column of a table, which has no parts (e.g. a stress field).
+  fn field(&mut self, _field: NasIndex) {}
+}
+
+/// The index types that make up `NasIndex`, which know how to feed their
+/// parts to a visitor and to map the IDs within them.
+pub trait VisitableIndex: IndexType {
+  /// Feeds the parts of this index to a visitor.
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor);
+
+  /// Returns this index with its grid point and element IDs mapped.
+  fn map_ids(
+    &self,
+    grid_fn: &dyn Fn(usize) -> usize,
+    elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self;
+}
+
+/// Implements `VisitableIndex` for column indexes (and the few row indexes
+/// without parts), which are fed as they are and have no IDs to map.
+macro_rules! visitable_fields {
+  ($($tn:ident,)*) => {
+    $(
+      impl VisitableIndex for $tn {
+        fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+          v.field((*self).into());
+        }
+
+        fn map_ids(
+          &self,
+          _grid_fn: &dyn Fn(usize) -> usize,
+          _elem_fn: &dyn Fn(usize) -> usize
+        ) -> Self {
+          return *self;
+        }
+      }
+    )*
+  };
+}
+
+visitable_fields!(
+  SingleForce,
+  SingleStress,
+  SingleStrain,
+  SingleTemperature,
+  BarForceField,
+  BarStressField,
+  BarStrainField,
+  BeamForceField,
+  BeamStressField,
+  BeamStrainField,
+  RodForceField,
+  RodStressField,
+  RodStrainField,
+  PlateForceField,
+  PlateStressField,
+  PlateStrainField,
+  ChexaStressField,
+  ChexaStrainField,
+  CpentaStressField,
+  CpentaStrainField,
+  CtetraStressField,
+  CtetraStrainField,
+  CshearForceField,
+  CshearStressField,
+  CgapForceField,
+  BushStressField,
+  BushStrainField,
+  CompositeFailureField,
+  CompositeStressField,
+  GridStressField,
+  StrainEnergyField,
+  EigenvalueField,
+  ComplexEigenvalueField,
+  ModalMassField,
+  GpwgRef,
+  GpwgField,
+  MaxSummaryField,
+);
+
+/// Maps the ID of an element.
+fn map_element(
+  element: ElementRef,
+  elem_fn: &dyn Fn(usize) -> usize
+) -> ElementRef {
+  return ElementRef { eid: elem_fn(element.eid), ..element };
+}
+
+/// Maps the grid point IDs in a point within an element.
+fn map_point(
+  point: ElementPoint,
+  grid_fn: &dyn Fn(usize) -> usize
+) -> ElementPoint {
+  let map_grid = |g: GridPointRef| GridPointRef { gid: grid_fn(g.gid) };
+  return match point {
+    ElementPoint::Corner(g) => ElementPoint::Corner(map_grid(g)),
+    ElementPoint::Midpoint(g) => ElementPoint::Midpoint(map_grid(g)),
+    ElementPoint::Centroid | ElementPoint::Anywhere => point
+  };
+}
+
+impl VisitableIndex for Dof {
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+    v.dof(*self);
+  }
+
+  fn map_ids(
+    &self,
+    _grid_fn: &dyn Fn(usize) -> usize,
+    _elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self {
+    return *self;
+  }
+}
+
+impl VisitableIndex for GridPointRef {
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+    v.grid(*self);
+  }
+
+  fn map_ids(
+    &self,
+    grid_fn: &dyn Fn(usize) -> usize,
+    _elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self {
+    return Self { gid: grid_fn(self.gid) };
+  }
+}
+
+impl VisitableIndex for ElementRef {
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+    v.element(*self);
+  }
+
+  fn map_ids(
+    &self,
+    _grid_fn: &dyn Fn(usize) -> usize,
+    elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self {
+    return map_element(*self, elem_fn);
+  }
+}
+
+impl VisitableIndex for PointInElement {
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+    v.compound_start(Self::INDEX_NAME);
+    v.element(self.element);
+    v.point(self.point);
+    v.compound_end(Self::INDEX_NAME);
+  }
+
+  fn map_ids(
+    &self,
+    grid_fn: &dyn Fn(usize) -> usize,
+    elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self {
+    return Self {
+      element: map_element(self.element, elem_fn),
+      point: map_point(self.point, grid_fn)
+    };
+  }
+}
+
+impl VisitableIndex for GridPointForceOrigin {
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+    v.compound_start(Self::INDEX_NAME);
+    v.grid(self.grid_point);
+    v.force_origin(self.force_origin);
+    if let ForceOrigin::Element { elem } = self.force_origin {
+      v.element(elem);
+    }
+    v.compound_end(Self::INDEX_NAME);
+  }
+
+  fn map_ids(
+    &self,
+    grid_fn: &dyn Fn(usize) -> usize,
+    elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self {
+    let force_origin = match self.force_origin {
+      ForceOrigin::Element { elem } => {
+        ForceOrigin::Element { elem: map_element(elem, elem_fn) }
+      },
+      other => other
+    };
+    return Self {
+      grid_point: self.grid_point.map_ids(grid_fn, elem_fn),
+      force_origin
+    };
+  }
+}
+
+impl VisitableIndex for ElementSidedPoint {
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+    v.compound_start(Self::INDEX_NAME);
+    v.element(self.element);
+    v.point(self.point);
+    v.side(self.side);
+    v.compound_end(Self::INDEX_NAME);
+  }
+
+  fn map_ids(
+    &self,
+    grid_fn: &dyn Fn(usize) -> usize,
+    elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self {
+    return Self {
+      element: map_element(self.element, elem_fn),
+      point: map_point(self.point, grid_fn),
+      side: self.side
+    };
+  }
+}
+
+impl VisitableIndex for ElementStation {
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+    v.compound_start(Self::INDEX_NAME);
+    v.element(self.element);
+    v.station(self.fraction());
+    v.compound_end(Self::INDEX_NAME);
+  }
+
+  fn map_ids(
+    &self,
+    _grid_fn: &dyn Fn(usize) -> usize,
+    elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self {
+    return Self { element: map_element(self.element, elem_fn), ..*self };
+  }
+}
+
+impl VisitableIndex for ElementPlyRef {
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+    v.compound_start(Self::INDEX_NAME);
+    v.element(self.element);
+    v.layer(self.layer);
+    v.side(self.side);
+    v.compound_end(Self::INDEX_NAME);
+  }
+
+  fn map_ids(
+    &self,
+    _grid_fn: &dyn Fn(usize) -> usize,
+    elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self {
+    return Self { element: map_element(self.element, elem_fn), ..*self };
+  }
+}
+
+impl VisitableIndex for GridSurfacePoint {
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+    v.compound_start(Self::INDEX_NAME);
+    v.grid(self.grid_point);
+    v.surface(self.surface);
+    if let Some(element) = self.element {
+      v.element(element);
+    }
+    v.side(self.side);
+    v.compound_end(Self::INDEX_NAME);
+  }
+
+  fn map_ids(
+    &self,
+    grid_fn: &dyn Fn(usize) -> usize,
+    elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self {
+    return Self {
+      grid_point: self.grid_point.map_ids(grid_fn, elem_fn),
+      element: self.element.map(|element| map_element(element, elem_fn)),
+      ..*self
+    };
+  }
+}
+
+impl VisitableIndex for SeqRef {
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+    v.seq(*self);
+  }
+
+  fn map_ids(
+    &self,
+    _grid_fn: &dyn Fn(usize) -> usize,
+    _elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self {
+    return *self;
+  }
+}
+
+impl VisitableIndex for ModeRef {
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+    v.mode(*self);
+  }
+
+  fn map_ids(
+    &self,
+    _grid_fn: &dyn Fn(usize) -> usize,
+    _elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self {
+    return *self;
+  }
+}
+
+impl VisitableIndex for CaseRef {
+  fn visit_parts(&self, v: &mut impl NasIndexVisitor) {
+    v.case(*self);
+  }
+
+  fn map_ids(
+    &self,
+    _grid_fn: &dyn Fn(usize) -> usize,
+    _elem_fn: &dyn Fn(usize) -> usize
+  ) -> Self {
+    return *self;
+  }
+}
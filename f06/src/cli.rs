@@ -0,0 +1,98 @@
+//! This module implements the options the command-line tools share, so that
+//! each is defined (and documented) once and flattened into every tool, and
+//! the exit codes they share.
+
+use std::error::Error;
+use std::io;
+
+use clap::Args;
+use clap::builder::RangedU64ValueParser;
+use log::error;
+
+use crate::prelude::*;
+use crate::util::{HEADER_WINDOW, MAX_HEADER_WINDOW};
+
+/// The exit code the tools exit with when an input isn't an F06 at all (e.g.
+/// an OP2), as opposed to 1 for any other failure.
+pub const EXIT_WRONG_FILE: i32 = 3;
+
+/// Exits with `EXIT_WRONG_FILE` if an error is the parser's, about the input
+/// not being an F06 at all, saying what it looks like instead.
+pub fn exit_if_wrong_file(error: &(dyn Error + 'static)) {
+  let wrong = error.downcast_ref::<io::Error>()
+    .and_then(ParseError::wrong_file_in);
+  if let Some(wrong) = wrong {
+    error!("Not an F06 file: {}.", wrong);
+    std::process::exit(EXIT_WRONG_FILE);
+  }
+}
+
+/// The options the tools take on how to parse their inputs, and on checking
+/// what they parsed.
+#[derive(Clone, Debug, Args)]
+pub struct ParsingArgs {
+  /// Read reals with decimal commas (e.g. "1,5E+03"), as written by some
+  /// localised solvers. Detected anyway once a data line clearly uses them.
+  #[arg(long = "decimal-comma")]
+  pub decimal_comma: bool,
+  /// How many lines apart the parts of a wrapped block header can be, with
+  /// only blank lines between them (1 to 3).
+  #[arg(
+    long = "header-window",
+    value_name = "LINES",
+    default_value_t = HEADER_WINDOW,
+    value_parser = RangedU64ValueParser::<usize>::new()
+      .range(1..=MAX_HEADER_WINDOW as u64)
+  )]
+  pub header_window: usize,
+  /// Skip lines starting with this text, as debug output printed in the
+  /// middle of blocks (besides the solver's known ones). Can be repeated.
+  #[arg(long = "debug-prefix", value_name = "TEXT")]
+  pub debug_prefixes: Vec<String>,
+  /// Check the parsed file for internal inconsistencies (e.g. two blocks
+  /// claiming the same lines) and warn about them. Always done in debug
+  /// builds.
+  #[arg(long = "validate-internal")]
+  pub validate_internal: bool,
+  /// Treat internal inconsistencies as errors, exiting with an error code
+  /// (when converting, NaN or infinite values too, before anything is
+  /// written). Implies --validate-internal.
+  #[arg(long = "strict")]
+  pub strict: bool
+}
+
+impl Default for ParsingArgs {
+  fn default() -> Self {
+    return Self {
+      decimal_comma: false,
+      header_window: HEADER_WINDOW,
+      debug_prefixes: Vec::new(),
+      validate_internal: false,
+      strict: false
+    };
+  }
+}
+
+impl ParsingArgs {
+  /// Sets a parser up with these options. It doesn't validate what it
+  /// parses, since `validate` reports that.
+  pub fn configure(&self, parser: &mut OnePassParser) {
+    parser.decimal_comma(self.decimal_comma);
+    parser.header_window(self.header_window);
+    parser.debug_line_prefixes(&self.debug_prefixes);
+    parser.validate_internal(false);
+  }
+
+  /// Checks a file for internal inconsistencies, logging them: always if
+  /// strict, if asked to or in debug builds otherwise. Returns how many there
+  /// are if strict, so the tool can stop on any, or zero otherwise.
+  pub fn validate(&self, f06: &F06File) -> usize {
+    if self.validate_internal || self.strict || cfg!(debug_assertions) {
+      let count = f06.report_violations(self.strict);
+      if self.strict {
+        return count;
+      }
+    }
+    return 0;
+  }
+}
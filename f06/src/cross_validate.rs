@@ -0,0 +1,286 @@
+//! This module implements physics-based sanity checks on parsed F06 data.
+//!
+//! Diffing against earlier output from the same solver can't catch errors that
+//! are internally consistent, so these checks compare different blocks of the
+//! same file against each other (and against some model geometry) instead.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::Read;
+
+use nalgebra::Vector3;
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// Default threshold under which elongations and forces are considered zero.
+pub const DEFAULT_SIGN_THRESHOLD: f64 = 1.0e-9;
+
+/// Minimal model geometry needed by the cross-checks: grid point locations
+/// and element connectivity.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ModelGeometry {
+  /// Grid point locations in the basic coordinate system.
+  pub grids: BTreeMap<usize, Vector3<f64>>,
+  /// Rod element connectivity (end A and end B grid IDs).
+  pub rods: BTreeMap<usize, (usize, usize)>
+}
+
+/// An error while reading model geometry.
+#[derive(Debug, derive_more::From)]
+#[non_exhaustive]
+pub enum GeometryError {
+  /// The CSV reader failed.
+  Csv(csv::Error),
+  /// A record had an unknown card name.
+  UnknownCard {
+    /// The 1-based record number.
+    record: usize,
+    /// The card name.
+    card: String
+  },
+  /// A record had a missing or unparseable field.
+  BadField {
+    /// The 1-based record number.
+    record: usize,
+    /// The 0-based field index.
+    field: usize
+  }
+}
+
+impl Display for GeometryError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::Csv(e) => write!(f, "CSV error: {}", e),
+      Self::UnknownCard { record, card } => {
+        write!(f, "unknown card \"{}\" in record {}", card, record)
+      },
+      Self::BadField { record, field } => {
+        write!(f, "bad field {} in record {}", field, record)
+      },
+    };
+  }
+}
+
+impl std::error::Error for GeometryError {}
+
+impl ModelGeometry {
+  /// Instantiates an empty geometry.
+  pub fn new() -> Self {
+    return Self::default();
+  }
+
+  /// Adds a grid point.
+  pub fn insert_grid(&mut self, gid: usize, x: f64, y: f64, z: f64) {
+    self.grids.insert(gid, Vector3::new(x, y, z));
+  }
+
+  /// Adds a rod element.
+  pub fn insert_rod(&mut self, eid: usize, ga: usize, gb: usize) {
+    self.rods.insert(eid, (ga, gb));
+  }
+
+  /// Reads geometry from a connectivity CSV. Each record is either
+  /// `GRID,gid,x,y,z` or `CROD,eid,ga,gb` (`CONROD` is accepted as an alias).
+  /// Blank records and records starting with `#` are ignored.
+  pub fn from_csv<R: Read>(reader: R) -> Result<Self, GeometryError> {
+    let mut geom = Self::new();
+    let mut rdr = csv::ReaderBuilder::new()
+      .has_headers(false)
+      .flexible(true)
+      .trim(csv::Trim::All)
+      .comment(Some(b'#'))
+      .from_reader(reader);
+    for (i, rec) in rdr.records().enumerate() {
+      let rec = rec?;
+      let record = i + 1;
+      let card = match rec.get(0) {
+        Some(s) if !s.is_empty() => s.to_uppercase(),
+        _ => continue
+      };
+      let int = |field: usize| -> Result<usize, GeometryError> {
+        return rec.get(field)
+          .and_then(|s| s.parse().ok())
+          .ok_or(GeometryError::BadField { record, field });
+      };
+      let real = |field: usize| -> Result<f64, GeometryError> {
+        return rec.get(field)
+          .and_then(|s| s.parse().ok())
+          .ok_or(GeometryError::BadField { record, field });
+      };
+      match card.as_str() {
+        "GRID" => geom.insert_grid(int(1)?, real(2)?, real(3)?, real(4)?),
+        "CROD" | "CONROD" => geom.insert_rod(int(1)?, int(2)?, int(3)?),
+        _ => return Err(GeometryError::UnknownCard { record, card })
+      };
+    }
+    return Ok(geom);
+  }
+}
+
+/// Something suspicious found by a cross-check.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
+pub enum CrossValidationFinding {
+  /// The sign of a rod's elongation disagrees with the sign of its axial
+  /// force (tension should be positive).
+  RodForceSignMismatch {
+    /// The subcase.
+    subcase: usize,
+    /// The rod element ID.
+    eid: usize,
+    /// The elongation computed from the displacements.
+    elongation: f64,
+    /// The axial force reported in the file.
+    axial_force: f64
+  },
+  /// An output was requested in the case control, to be printed, but none of
+  /// the blocks that would hold it were found in the subcase.
+  RequestedButMissing {
+    /// The subcase.
+    subcase: usize,
+    /// The request.
+    request: OutputRequest
+  }
+}
+
+impl Display for CrossValidationFinding {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::RodForceSignMismatch {
+        subcase,
+        eid,
+        elongation,
+        axial_force
+      } => write!(
+        f,
+        "subcase {}, rod {}: elongation {:E} but axial force {:E}",
+        subcase,
+        eid,
+        elongation,
+        axial_force
+      ),
+      Self::RequestedButMissing { subcase, request } => write!(
+        f,
+        "subcase {}: requested {} but found no such output",
+        subcase,
+        request
+      ),
+    };
+  }
+}
+
+/// Gets the translation of a grid point from a displacements block.
+fn translation(block: &FinalBlock, gid: usize) -> Option<Vector3<f64>> {
+  let gpref = GridPointRef { gid };
+  let mut t = Vector3::zeros();
+  for (i, dof) in [DOF_TX, DOF_TY, DOF_TZ].into_iter().enumerate() {
+    t[i] = f64::from(block.get(gpref, dof)?);
+  }
+  return Some(t);
+}
+
+/// Computes a rod's elongation in a subcase, if the data is all there.
+fn rod_elongation(
+  file: &F06File,
+  geometry: &ModelGeometry,
+  subcase: usize,
+  ends: (usize, usize)
+) -> Option<f64> {
+  let (ga, gb) = ends;
+  let axis = geometry.grids.get(&gb)? - geometry.grids.get(&ga)?;
+  let length = axis.norm();
+  if length == 0.0 {
+    return None;
+  }
+  let disps = file.block_search(
+    Some(BlockType::Displacements),
+    Some(subcase),
+    false
+  );
+  for block in disps {
+    let ua = translation(block, ga);
+    let ub = translation(block, gb);
+    if let (Some(ua), Some(ub)) = (ua, ub) {
+      return Some((ub - ua).dot(&axis) / length);
+    }
+  }
+  return None;
+}
+
+/// Checks that the sign of each rod's axial force matches the sign of its
+/// elongation. Elongations and forces smaller than the threshold in absolute
+/// value are not checked.
+pub fn check_rod_force_signs(
+  file: &F06File,
+  geometry: &ModelGeometry,
+  threshold: f64
+) -> Vec<CrossValidationFinding> {
+  let mut findings = Vec::new();
+  let blocks = [BlockType::RodForces, BlockType::ConrodForces]
+    .into_iter()
+    .flat_map(|bt| file.block_search(Some(bt), None, false));
+  for block in blocks {
+    for (eid, ends) in geometry.rods.iter() {
+      let eref = ElementRef { eid: *eid, etype: block.block_type.elem_type() };
+      let axial_force = match block.get(eref, RodForceField::AxialForce) {
+        Some(x) => f64::from(x),
+        None => continue
+      };
+      let elongation = match rod_elongation(
+        file, geometry, block.subcase, *ends
+      ) {
+        Some(x) => x,
+        None => continue
+      };
+      if elongation.abs() < threshold || axial_force.abs() < threshold {
+        continue;
+      }
+      if elongation.signum() != axial_force.signum() {
+        findings.push(CrossValidationFinding::RodForceSignMismatch {
+          subcase: block.subcase,
+          eid: *eid,
+          elongation,
+          axial_force
+        });
+      }
+    }
+  }
+  return findings;
+}
+
+/// Checks that each printed output requested in the case control echo has at
+/// least one block in its subcase. Requests for outputs we don't decode are
+/// not checked.
+pub fn check_requested_outputs(file: &F06File) -> Vec<CrossValidationFinding> {
+  let mut findings = Vec::new();
+  for (subcase, requests) in file.requests.iter() {
+    for request in requests.iter().filter(|r| r.expects_blocks()) {
+      let types = request.keyword.block_types();
+      let found = types.iter().any(|bt| {
+        return file.block_search(Some(*bt), Some(*subcase), false)
+          .next()
+          .is_some();
+      });
+      if !types.is_empty() && !found {
+        findings.push(CrossValidationFinding::RequestedButMissing {
+          subcase: *subcase,
+          request: request.clone()
+        });
+      }
+    }
+  }
+  return findings;
+}
+
+/// Runs all cross-checks on a file.
+pub fn cross_validate(
+  file: &F06File,
+  geometry: &ModelGeometry,
+  threshold: f64
+) -> Vec<CrossValidationFinding> {
+  let mut findings = Vec::new();
+  findings.extend(check_rod_force_signs(file, geometry, threshold));
+  findings.extend(check_requested_outputs(file));
+  return findings;
+}
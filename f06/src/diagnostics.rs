@@ -0,0 +1,352 @@
+//! This module implements structured diagnostics emitted while parsing, and
+//! their aggregation so that systematic issues don't bloat the output.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// Default maximum number of line numbers kept per aggregated diagnostic.
+pub const DEFAULT_MAX_LINES: usize = 10;
+
+/// Default maximum number of sample texts kept per aggregated diagnostic.
+pub const DEFAULT_MAX_SAMPLES: usize = 3;
+
+/// Default maximum number of distinct diagnostics kept.
+pub const DEFAULT_MAX_ENTRIES: usize = 1000;
+
+/// The kind of a diagnostic. Diagnostics of the same kind are aggregated.
+#[derive(
+  Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord
+)]
+#[non_exhaustive]
+pub enum DiagnosticKind {
+  /// A warning printed by the solver, with numbers blanked out.
+  SolverWarning {
+    /// The text of the warning with numbers replaced by '#'.
+    template: String
+  },
+  /// A fatal error printed by the solver, with numbers blanked out.
+  SolverFatal {
+    /// The text of the error with numbers replaced by '#'.
+    template: String
+  },
+  /// A field in a block was overflowed (printed as asterisks).
+  OverflowedField {
+    /// The type of block.
+    block_type: BlockType
+  },
+  /// A value in a block was NaN or infinite (or overflowed).
+  NonFiniteValue {
+    /// The type of block.
+    block_type: BlockType
+  },
+  /// A decoder gave an abnormal response to a line.
+  AbnormalLine {
+    /// The type of block.
+    block_type: BlockType,
+    /// The response the decoder gave.
+    response: LineResponse
+  },
+  /// A known block header was found before the solver was known.
+  BlockWithoutSolver,
+  /// A header matched more than one block type.
+  AmbiguousHeader,
+  /// A block's page header and a mid-page subcase marker disagreed.
+  SubcaseDisagreement {
+    /// The subcase in the page header.
+    page_header: usize,
+    /// The subcase in the mid-page marker.
+    marker: usize,
+    /// The subcase the block was assigned to.
+    chosen: usize
+  },
+  /// Something found by a post-processor.
+  PostProcessing {
+    /// The name of the post-processor.
+    processor: String,
+    /// The text of the diagnostic with numbers replaced by '#'.
+    template: String
+  }
+}
+
+impl Display for DiagnosticKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::SolverWarning { template } => {
+        write!(f, "Solver warning \"{}\"", template)
+      },
+      Self::SolverFatal { template } => {
+        write!(f, "Solver fatal error \"{}\"", template)
+      },
+      Self::OverflowedField { block_type } => write!(
+        f,
+        "Overflowed field in {} block stored as NaN",
+        block_type
+      ),
+      Self::NonFiniteValue { block_type } => write!(
+        f,
+        "NaN or infinite value in {} block",
+        block_type
+      ),
+      Self::AbnormalLine { block_type, response } => write!(
+        f,
+        "Abnormal response {:?} from {} decoder",
+        response,
+        block_type
+      ),
+      Self::BlockWithoutSolver => {
+        write!(f, "Block found before detecting the solver")
+      },
+      Self::AmbiguousHeader => {
+        write!(f, "Header matches more than one block type")
+      },
+      Self::SubcaseDisagreement { page_header, marker, chosen } => write!(
+        f,
+        "Page header says subcase {} but marker says {}, chose {}",
+        page_header,
+        marker,
+        chosen
+      ),
+      Self::PostProcessing { processor, template } => {
+        write!(f, "Post-processor {} found \"{}\"", processor, template)
+      },
+    };
+  }
+}
+
+impl DiagnosticKind {
+  /// Returns whether this is a fatal error.
+  pub const fn is_fatal(&self) -> bool {
+    return matches!(self, Self::SolverFatal { .. });
+  }
+}
+
+/// Replaces every number in a message with a '#', so that messages differing
+/// only in IDs and values get the same template.
+pub fn message_template(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  let mut in_number = false;
+  for c in text.trim().chars() {
+    let numeric = c.is_ascii_digit()
+      || (in_number && matches!(c, '.' | 'E' | 'e' | '+' | '-'));
+    if numeric {
+      if !in_number {
+        out.push('#');
+      }
+      in_number = true;
+    } else {
+      out.push(c);
+      in_number = false;
+    }
+  }
+  return out;
+}
+
+/// A single diagnostic, before aggregation.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Diagnostic {
+  /// The kind of diagnostic.
+  pub kind: DiagnosticKind,
+  /// The line it refers to, if any.
+  pub line: Option<usize>,
+  /// The text that generated it.
+  pub text: String
+}
+
+impl Display for Diagnostic {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.kind)?;
+    if let Some(line) = self.line {
+      write!(f, ", at line {}", line)?;
+    }
+    return Ok(());
+  }
+}
+
+/// Limits on how much diagnostic information is kept.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DiagnosticLimits {
+  /// Maximum number of line numbers kept per aggregated diagnostic.
+  pub max_lines: usize,
+  /// Maximum number of sample texts kept per aggregated diagnostic.
+  pub max_samples: usize,
+  /// Maximum number of distinct diagnostics kept.
+  pub max_entries: usize
+}
+
+impl Default for DiagnosticLimits {
+  fn default() -> Self {
+    return Self {
+      max_lines: DEFAULT_MAX_LINES,
+      max_samples: DEFAULT_MAX_SAMPLES,
+      max_entries: DEFAULT_MAX_ENTRIES
+    };
+  }
+}
+
+/// All occurrences of a kind of diagnostic, collapsed into one.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AggregatedDiagnostic {
+  /// The kind of diagnostic.
+  pub kind: DiagnosticKind,
+  /// Total number of occurrences.
+  pub count: usize,
+  /// The first few line numbers where it occurred.
+  pub lines: Vec<usize>,
+  /// The byte offsets of those lines, when known.
+  #[serde(default)]
+  pub offsets: Vec<u64>,
+  /// The first few distinct texts that generated it.
+  pub samples: Vec<String>
+}
+
+impl Display for AggregatedDiagnostic {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.kind)?;
+    if self.count == 1 {
+      write!(f, " -- 1 occurrence")?;
+    } else {
+      write!(f, " -- {} occurrences", self.count)?;
+    }
+    if let Some((first, rest)) = self.lines.split_first() {
+      if rest.is_empty() {
+        write!(f, ", at line {}", first)?;
+      } else {
+        write!(f, ", first at lines {}", first)?;
+        for line in rest {
+          write!(f, ", {}", line)?;
+        }
+        if self.count > self.lines.len() {
+          write!(f, ", ... ({} more)", self.count - self.lines.len())?;
+        }
+      }
+    }
+    return Ok(());
+  }
+}
+
+/// Order-preserving, size-capped collection of aggregated diagnostics.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct Diagnostics {
+  /// The limits in use.
+  pub limits: DiagnosticLimits,
+  /// The aggregated diagnostics, in order of first occurrence.
+  pub entries: Vec<AggregatedDiagnostic>,
+  /// Number of occurrences dropped because the entry cap was reached.
+  pub overflow: usize,
+  /// Maps each kind to its position in the entries.
+  #[serde(skip)]
+  index: BTreeMap<DiagnosticKind, usize>
+}
+
+impl Diagnostics {
+  /// Creates an empty collection with the default limits.
+  pub fn new() -> Self {
+    return Self::default();
+  }
+
+  /// Creates an empty collection with certain limits.
+  pub fn with_limits(limits: DiagnosticLimits) -> Self {
+    return Self { limits, ..Default::default() };
+  }
+
+  /// Records a diagnostic. Returns true if its line number was kept.
+  pub fn push(
+    &mut self,
+    kind: DiagnosticKind,
+    line: usize,
+    text: &str
+  ) -> bool {
+    return self.push_at(kind, line, None, text);
+  }
+
+  /// Records a diagnostic, along with the byte offset of its line, if known.
+  /// Returns true if its line number was kept.
+  pub fn push_at(
+    &mut self,
+    kind: DiagnosticKind,
+    line: usize,
+    offset: Option<u64>,
+    text: &str
+  ) -> bool {
+    return self.aggregate(kind, Some((line, offset)), text);
+  }
+
+  /// Records a single diagnostic. Returns true if its line number was kept,
+  /// or false if it was dropped or had none.
+  pub fn record(&mut self, diagnostic: &Diagnostic) -> bool {
+    return self.aggregate(
+      diagnostic.kind.clone(),
+      diagnostic.line.map(|l| (l, None)),
+      &diagnostic.text
+    );
+  }
+
+  /// Aggregates an occurrence of a diagnostic, at a line and byte offset if
+  /// known. Returns true if its line number was kept.
+  fn aggregate(
+    &mut self,
+    kind: DiagnosticKind,
+    at: Option<(usize, Option<u64>)>,
+    text: &str
+  ) -> bool {
+    // the index isn't serialised, so it might need rebuilding
+    if self.index.len() != self.entries.len() {
+      self.index = self.entries.iter()
+        .enumerate()
+        .map(|(i, e)| (e.kind.clone(), i))
+        .collect();
+    }
+    let ix = match self.index.get(&kind).copied() {
+      Some(ix) => ix,
+      None if self.entries.len() < self.limits.max_entries => {
+        self.entries.push(AggregatedDiagnostic {
+          kind: kind.clone(),
+          count: 0,
+          lines: Vec::new(),
+          offsets: Vec::new(),
+          samples: Vec::new()
+        });
+        self.index.insert(kind, self.entries.len() - 1);
+        self.entries.len() - 1
+      },
+      None => {
+        self.overflow += 1;
+        return false;
+      }
+    };
+    let entry = &mut self.entries[ix];
+    entry.count += 1;
+    let text = text.trim();
+    let new_sample = !entry.samples.iter().any(|s| s == text);
+    if new_sample && entry.samples.len() < self.limits.max_samples {
+      entry.samples.push(text.to_owned());
+    }
+    if let Some((line, offset)) = at {
+      if entry.lines.len() < self.limits.max_lines {
+        entry.lines.push(line);
+        entry.offsets.extend(offset);
+        return true;
+      }
+    }
+    return false;
+  }
+
+  /// Total number of occurrences, including dropped ones.
+  pub fn total(&self) -> usize {
+    return self.entries.iter().map(|e| e.count).sum::<usize>() + self.overflow;
+  }
+
+  /// Returns true if nothing was recorded.
+  pub fn is_empty(&self) -> bool {
+    return self.entries.is_empty() && self.overflow == 0;
+  }
+
+  /// Iterates over the aggregated diagnostics.
+  pub fn iter(&self) -> impl Iterator<Item = &AggregatedDiagnostic> {
+    return self.entries.iter();
+  }
+}
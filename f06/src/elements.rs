@@ -17,8 +17,14 @@ pub enum ElementCategory {
   ScalarMass,
   /// Scalar spring elements, like ELAS1.
   ScalarSpring,
+  /// Scalar damper elements, like DAMP1.
+  ScalarDamper,
+  /// Viscous damper elements, like VISC.
+  ViscousDamper,
   /// Bushing elements, like BUSH.
   Bushing,
+  /// Gap (contact) elements, like GAP.
+  Gap,
   /// One-dimensional elastic elements, like ROD.
   OneDimensionalElastic,
   /// Two-dimensional elastic elements, like QUAD4.
@@ -96,10 +102,21 @@ gen_elems!(
   (Elas2, "ELAS2", ScalarSpring),
   (Elas3, "ELAS3", ScalarSpring),
   (Elas4, "ELAS4", ScalarSpring),
+  // scalar damper
+  (Damp1, "DAMP1", ScalarDamper),
+  (Damp2, "DAMP2", ScalarDamper),
+  (Damp3, "DAMP3", ScalarDamper),
+  (Damp4, "DAMP4", ScalarDamper),
+  // viscous damper
+  (Visc, "VISC", ViscousDamper),
   // bushing
   (Bush, "BUSH", Bushing),
+  // gap
+  (Gap, "GAP", Gap),
   // 1D elastic
   (Bar, "BAR", OneDimensionalElastic),
+  // before rods, or their names would match them too
+  (Conrod, "CONROD", OneDimensionalElastic),
   (Rod, "ROD", OneDimensionalElastic),
   (Beam, "BEAM", OneDimensionalElastic),
   // 2D elastic
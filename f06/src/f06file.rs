@@ -1,7 +1,15 @@
 //! This module implements the general structure of an F06 file as we interpret
 //! it, and its submodules are responsible for specific parsing subroutines.
 
+pub mod accessors;
+pub mod axis;
 pub mod diff;
+pub mod fingerprint;
+pub mod invariants;
+pub mod pairing;
+pub mod requests;
+pub mod runstats;
+pub mod soldiags;
 
 use std::collections::{BTreeSet, BTreeMap};
 
@@ -25,6 +33,25 @@ pub struct BlockRef {
   pub block_type: BlockType
 }
 
+/// A reference to the blocks of a subcase and type at one step of a dynamic
+/// analysis, or to all of them if they have no steps. Transient and frequency
+/// response output has one block per step under the same `BlockRef`.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord
+)]
+pub struct SteppedBlockRef {
+  /// The subcase and type.
+  pub block_ref: BlockRef,
+  /// The time step or frequency, if any.
+  pub step: Option<Step>
+}
+
+impl From<BlockRef> for SteppedBlockRef {
+  fn from(block_ref: BlockRef) -> Self {
+    return Self { block_ref, step: None };
+  }
+}
+
 /// This is the output of an F06 parser.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct F06File {
@@ -32,14 +59,56 @@ pub struct F06File {
   pub filename: Option<String>,
   /// The flavour of file.
   pub flavour: Flavour,
+  /// How the flavour was resolved, field by field.
+  pub flavour_resolution: FlavourResolution,
   /// The detected blocks.
   pub blocks: BTreeMap<BlockRef, Vec<FinalBlock>>,
   /// The line numbers for warning messages.
   pub warnings: BTreeMap<usize, String>,
   /// The line numbers for fatal error messages.
   pub fatal_errors: BTreeMap<usize, String>,
+  /// Warning messages of other files merged into this one, by the name of the
+  /// file they came from, then by their line number in it.
+  #[serde(default)]
+  pub merged_warnings: BTreeMap<String, BTreeMap<usize, String>>,
+  /// Fatal error messages of other files merged into this one, by the name of
+  /// the file they came from, then by their line number in it.
+  #[serde(default)]
+  pub merged_fatal_errors: BTreeMap<String, BTreeMap<usize, String>>,
+  /// Diagnostics of other files merged into this one, by the name of the file
+  /// they came from.
+  #[serde(default)]
+  pub merged_diagnostics: BTreeMap<String, Diagnostics>,
+  /// Byte offsets of the lines with warning and fatal error messages, by line
+  /// number.
+  #[serde(default)]
+  pub message_offsets: BTreeMap<usize, u64>,
+  /// Aggregated diagnostics from the parsing process.
+  pub diagnostics: Diagnostics,
   /// Lines with potential, unknown headers, and their line ranges.
-  pub potential_headers: BTreeSet<PotentialHeader>
+  pub potential_headers: BTreeSet<PotentialHeader>,
+  /// Statistics about the run, from the solver's summary pages.
+  #[serde(default)]
+  pub run_stats: RunStats,
+  /// The checks on the solution of each subcase (epsilon and external work),
+  /// for linear statics.
+  #[serde(default)]
+  pub solution_diagnostics: BTreeMap<usize, SolutionDiagnostics>,
+  /// The output requests in each subcase, from the case control echo.
+  #[serde(default)]
+  pub requests: BTreeMap<usize, Vec<OutputRequest>>,
+  /// The title of each subcase, from the case control echo.
+  #[serde(default)]
+  pub subcase_titles: BTreeMap<usize, String>,
+  /// The number of lines in the file, if known.
+  #[serde(default)]
+  pub line_count: Option<usize>,
+  /// Whether reals were read with decimal commas (from localised solvers).
+  #[serde(default)]
+  pub decimal_comma: bool,
+  /// The names of the post-processors run on the file, in order.
+  #[serde(default)]
+  pub post_processed: Vec<String>
 }
 
 impl Default for F06File {
@@ -54,13 +123,31 @@ impl F06File {
     return Self {
       filename: None,
       flavour: Flavour::default(),
+      flavour_resolution: FlavourResolution::default(),
       blocks: BTreeMap::new(),
       warnings: BTreeMap::new(),
       fatal_errors: BTreeMap::new(),
-      potential_headers: BTreeSet::new()
+      merged_warnings: BTreeMap::new(),
+      merged_fatal_errors: BTreeMap::new(),
+      merged_diagnostics: BTreeMap::new(),
+      message_offsets: BTreeMap::new(),
+      diagnostics: Diagnostics::new(),
+      potential_headers: BTreeSet::new(),
+      run_stats: RunStats::new(),
+      solution_diagnostics: BTreeMap::new(),
+      requests: BTreeMap::new(),
+      subcase_titles: BTreeMap::new(),
+      line_count: None,
+      decimal_comma: false,
+      post_processed: Vec::new()
     };
   }
 
+  /// Returns whether a post-processor was run on the file.
+  pub fn was_post_processed(&self, name: &str) -> bool {
+    return self.post_processed.iter().any(|p| p == name);
+  }
+
   /// Inserts a new block into the file.
   pub fn insert_block(&mut self, block: FinalBlock) {
     let br = block.block_ref();
@@ -90,6 +177,19 @@ impl F06File {
       .flatten();
   }
 
+  /// Returns all blocks grouped by subcase, type and step, so that each step
+  /// of a dynamic analysis has a group of its own.
+  pub fn stepped_blocks(&self) -> BTreeMap<SteppedBlockRef, Vec<&FinalBlock>> {
+    let mut groups: BTreeMap<_, Vec<&FinalBlock>> = BTreeMap::new();
+    for (block_ref, blocks) in self.blocks.iter() {
+      for block in blocks {
+        let sbr = SteppedBlockRef { block_ref: *block_ref, step: block.step };
+        groups.entry(sbr).or_default().push(block);
+      }
+    }
+    return groups;
+  }
+
   /// Merges a vector of blocks having only a mutable reference to that vector.
   fn merge_block_vec(vec: &mut Vec<FinalBlock>, clean: bool) -> usize {
     let mut num_merges = 0;
@@ -196,6 +296,13 @@ impl F06File {
       .into_iter();
   }
 
+  /// Returns the number of NaNs and infinities in all blocks.
+  pub fn non_finite_count(&self) -> usize {
+    return self.all_blocks(false)
+      .map(|b| b.non_finite().len())
+      .sum();
+  }
+
   /// Searches blocks filtering by subcase and/or type.
   pub fn block_search(
     &self,
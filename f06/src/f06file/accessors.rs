@@ -0,0 +1,222 @@
+//! This module implements typed accessors for the most commonly wanted
+//! physical quantities, so one doesn't have to deal with NasIndexes and
+//! block searches just to get a displacement.
+//!
+//! When more than one block of a type is left in a subcase (i.e. they
+//! couldn't be merged), blocks are looked at in the order they appear in the
+//! file, and the first one that has the datum wins.
+
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// The six degrees of freedom, in the order the accessors return them.
+const SIX_DOFS: [Dof; 6] = [DOF_TX, DOF_TY, DOF_TZ, DOF_RX, DOF_RY, DOF_RZ];
+
+/// Stresses at a point on one side of a quadrilateral plate element.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QuadStresses {
+  /// Distance from the reference plane to where the stresses are taken.
+  pub fibre_distance: f64,
+  /// Normal stress in the element's X direction.
+  pub normal_x: f64,
+  /// Normal stress in the element's Y direction.
+  pub normal_y: f64,
+  /// In-plane shear stress.
+  pub shear_xy: f64,
+  /// Angle of the principal directions, in degrees.
+  pub angle: f64,
+  /// Major principal stress.
+  pub major: f64,
+  /// Minor principal stress.
+  pub minor: f64,
+  /// Von Mises equivalent stress.
+  pub von_mises: f64
+}
+
+impl F06File {
+  /// Returns the blocks of a type in a subcase (or in all of them), in file
+  /// order. Blocks with no known line range go last, in the order they're
+  /// stored.
+  fn blocks_in_order(
+    &self,
+    block_type: BlockType,
+    subcase: Option<usize>
+  ) -> Vec<&FinalBlock> {
+    let mut blocks = self.block_search(Some(block_type), subcase, false)
+      .collect::<Vec<_>>();
+    blocks.sort_by_key(|b| b.line_range.map_or(usize::MAX, |lr| lr.0));
+    return blocks;
+  }
+
+  /// Returns the first value the closure finds in the blocks of a type in a
+  /// subcase.
+  fn first_found<T, F: Fn(&FinalBlock) -> Option<T>>(
+    &self,
+    block_type: BlockType,
+    subcase: usize,
+    f: F
+  ) -> Option<T> {
+    return self.blocks_in_order(block_type, Some(subcase))
+      .into_iter()
+      .find_map(f);
+  }
+
+  /// Gets the six values for a grid point from a grid-point-by-DOF block.
+  fn six_dofs(
+    &self,
+    block_type: BlockType,
+    subcase: usize,
+    gid: usize
+  ) -> Option<[f64; 6]> {
+    let gpref = GridPointRef { gid };
+    return self.first_found(block_type, subcase, |block| {
+      let mut values = [0.0; 6];
+      for (value, dof) in values.iter_mut().zip(SIX_DOFS) {
+        *value = f64::from(block.get(gpref, dof)?);
+      }
+      return Some(values);
+    });
+  }
+
+  /// Returns the displacement of a grid point in a subcase (T1, T2, T3, R1,
+  /// R2, R3), if present.
+  pub fn displacement(&self, subcase: usize, gid: usize) -> Option<[f64; 6]> {
+    return self.six_dofs(BlockType::Displacements, subcase, gid);
+  }
+
+  /// Returns the forces of single-point constraint on a grid point in a
+  /// subcase (T1, T2, T3, R1, R2, R3), if present.
+  pub fn spc_force(&self, subcase: usize, gid: usize) -> Option<[f64; 6]> {
+    return self.six_dofs(BlockType::SpcForces, subcase, gid);
+  }
+
+  /// Returns the forces of multi-point constraint on a grid point in a
+  /// subcase (T1, T2, T3, R1, R2, R3), if present.
+  pub fn mpc_force(&self, subcase: usize, gid: usize) -> Option<[f64; 6]> {
+    return self.six_dofs(BlockType::MpcForces, subcase, gid);
+  }
+
+  /// Returns the applied forces on a grid point in a subcase (T1, T2, T3, R1,
+  /// R2, R3), if present.
+  pub fn applied_force(&self, subcase: usize, gid: usize) -> Option<[f64; 6]> {
+    return self.six_dofs(BlockType::AppliedForces, subcase, gid);
+  }
+
+  /// Returns the temperature of a grid point in a subcase, if present.
+  pub fn temperature(&self, subcase: usize, gid: usize) -> Option<f64> {
+    let gpref = GridPointRef { gid };
+    return self.first_found(BlockType::Temperatures, subcase, |block| {
+      return block.get(gpref, SingleTemperature::Temperature).map(f64::from);
+    });
+  }
+
+  /// Returns the stresses at a point on a side of a quadrilateral element in
+  /// a subcase, if present. The element type in the file doesn't matter,
+  /// only the ID.
+  pub fn quad_stress(
+    &self,
+    subcase: usize,
+    eid: usize,
+    side: ElementSide,
+    point: ElementPoint
+  ) -> Option<QuadStresses> {
+    return self.first_found(BlockType::QuadStresses, subcase, |block| {
+      let row = block.row_indexes.keys().copied().find(|ri| {
+        return match ri {
+          NasIndex::ElementSidedPoint(esp) => {
+            esp.element.eid == eid && esp.side == side && esp.point == point
+          },
+          _ => false
+        };
+      })?;
+      let get = |col: PlateStressField| block.get(row, col).map(f64::from);
+      return Some(QuadStresses {
+        fibre_distance: get(PlateStressField::FibreDistance)?,
+        normal_x: get(PlateStressField::NormalX)?,
+        normal_y: get(PlateStressField::NormalY)?,
+        shear_xy: get(PlateStressField::ShearXY)?,
+        angle: get(PlateStressField::Angle)?,
+        major: get(PlateStressField::Major)?,
+        minor: get(PlateStressField::Minor)?,
+        von_mises: get(PlateStressField::VonMises)?
+      });
+    });
+  }
+
+  /// Returns the axial force in a rod element (CROD or CONROD) in a subcase,
+  /// if present.
+  pub fn rod_axial_force(&self, subcase: usize, eid: usize) -> Option<f64> {
+    let axial = |block: &FinalBlock| {
+      let row = block.row_indexes.keys().copied().find(|ri| {
+        return matches!(ri, NasIndex::ElementRef(er) if er.eid == eid);
+      })?;
+      return block.get(row, RodForceField::AxialForce).map(f64::from);
+    };
+    return self.first_found(BlockType::RodForces, subcase, axial)
+      .or_else(|| self.first_found(BlockType::ConrodForces, subcase, axial));
+  }
+
+  /// Returns the natural frequencies (in Hz) in the real eigenvalue tables,
+  /// by mode number. These tables belong to the modal solution as a whole,
+  /// so their subcases are ignored; if a mode is in more than one, the first
+  /// one in the file wins.
+  pub fn natural_frequencies(&self) -> BTreeMap<usize, f64> {
+    let mut freqs = BTreeMap::new();
+    for block in self.blocks_in_order(BlockType::RealEigenvalues, None) {
+      for row in block.row_indexes.keys().copied() {
+        let mode_number = match row {
+          NasIndex::ModeRef(mr) => mr.mode_number,
+          _ => continue
+        };
+        if let Some(hz) = block.get(row, EigenvalueField::Cycles) {
+          freqs.entry(mode_number).or_insert(f64::from(hz));
+        }
+      }
+    }
+    return freqs;
+  }
+
+  /// Returns the load factors in the buckling eigenvalue tables, as pairs of
+  /// mode number and load factor, in mode order. Like with
+  /// `natural_frequencies`, the subcases are ignored and the first table in
+  /// the file with a mode wins.
+  pub fn buckling_factors(&self) -> Vec<(usize, f64)> {
+    let mut factors = BTreeMap::new();
+    for block in self.blocks_in_order(BlockType::BucklingEigenvalues, None) {
+      for row in block.row_indexes.keys().copied() {
+        let mode_number = match row {
+          NasIndex::ModeRef(mr) => mr.mode_number,
+          _ => continue
+        };
+        if let Some(factor) = block.get(row, EigenvalueField::Eigenvalue) {
+          factors.entry(mode_number).or_insert(f64::from(factor));
+        }
+      }
+    }
+    return factors.into_iter().collect();
+  }
+
+  /// Returns the roots in the complex eigenvalue summaries, as their real and
+  /// imaginary parts, by root number. Like with `natural_frequencies`, the
+  /// subcases are ignored and the first summary in the file with a root wins.
+  pub fn complex_roots(&self) -> BTreeMap<usize, (f64, f64)> {
+    let mut roots = BTreeMap::new();
+    for block in self.blocks_in_order(BlockType::ComplexEigenvalues, None) {
+      for row in block.row_indexes.keys().copied() {
+        let root = match row {
+          NasIndex::ModeRef(mr) => mr.mode_number,
+          _ => continue
+        };
+        let re = block.get(row, ComplexEigenvalueField::RealPart);
+        let im = block.get(row, ComplexEigenvalueField::ImaginaryPart);
+        if let (Some(re), Some(im)) = (re, im) {
+          roots.entry(root).or_insert((f64::from(re), f64::from(im)));
+        }
+      }
+    }
+    return roots;
+  }
+}
@@ -0,0 +1,136 @@
+//! This module implements the output axis of dynamic analyses: the time steps
+//! or frequencies a subcase printed its blocks at, and the analysis of where
+//! the output was skipped or the solver changed its step size.
+
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// How many times the expected interval an interval has to be to count as a
+/// gap in the output, by default.
+pub const DEFAULT_GAP_FACTOR: f64 = 1.5;
+
+/// The time steps or frequencies a subcase printed its blocks at, in
+/// ascending order and without repeats.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct OutputAxis {
+  /// The subcase.
+  pub subcase: usize,
+  /// The steps, in ascending order of value, without repeats.
+  pub steps: Vec<Step>,
+  /// How many times the expected interval an interval has to be to count as
+  /// a gap.
+  pub gap_factor: f64
+}
+
+/// An interval of an output axis larger than expected, where output was
+/// skipped or the solver took a larger step.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AxisGap {
+  /// The last step before the gap.
+  pub after_step: Step,
+  /// The first step after the gap.
+  pub before_step: Step,
+  /// How many times the expected interval the gap is.
+  pub ratio: f64
+}
+
+impl OutputAxis {
+  /// Builds the axis of a subcase from the steps of its blocks, in any order.
+  pub fn new(subcase: usize, steps: impl IntoIterator<Item = Step>) -> Self {
+    let mut steps = steps.into_iter().collect::<Vec<_>>();
+    steps.sort_by(|a, b| a.value().total_cmp(&b.value()));
+    steps.dedup();
+    return Self { subcase, steps, gap_factor: DEFAULT_GAP_FACTOR };
+  }
+
+  /// Returns the number of steps in the axis.
+  pub fn len(&self) -> usize {
+    return self.steps.len();
+  }
+
+  /// Returns true if the axis has no steps.
+  pub fn is_empty(&self) -> bool {
+    return self.steps.is_empty();
+  }
+
+  /// Returns the first and last values of the axis, if it has any.
+  pub fn span(&self) -> Option<(f64, f64)> {
+    let first = self.steps.first()?.value();
+    let last = self.steps.last()?.value();
+    return Some((first, last));
+  }
+
+  /// Returns the position of a step in the axis, if it's in it.
+  pub fn position(&self, step: Step) -> Option<usize> {
+    return self.steps.iter().position(|s| *s == step);
+  }
+
+  /// Returns the intervals between consecutive steps.
+  pub fn intervals(&self) -> Vec<f64> {
+    return self.steps.windows(2)
+      .map(|w| w[1].value() - w[0].value())
+      .collect();
+  }
+
+  /// Returns the median interval between consecutive steps, if there are at
+  /// least two of them.
+  pub fn median_interval(&self) -> Option<f64> {
+    let mut intervals = self.intervals();
+    if intervals.is_empty() {
+      return None;
+    }
+    intervals.sort_by(f64::total_cmp);
+    let mid = intervals.len() / 2;
+    if intervals.len().is_multiple_of(2) {
+      return Some((intervals[mid - 1] + intervals[mid]) / 2.0);
+    }
+    return Some(intervals[mid]);
+  }
+
+  /// Returns the intervals larger than the expected one by more than the gap
+  /// factor. The expected interval is the median one, unless one is given.
+  pub fn gaps(&self, expected_dt: Option<f64>) -> Vec<AxisGap> {
+    let dt = match expected_dt.or_else(|| self.median_interval()) {
+      Some(dt) if dt > 0.0 => dt,
+      _ => return Vec::new()
+    };
+    return self.steps.windows(2)
+      .map(|w| AxisGap {
+        after_step: w[0],
+        before_step: w[1],
+        ratio: (w[1].value() - w[0].value()) / dt
+      })
+      .filter(|gap| gap.ratio > self.gap_factor)
+      .collect();
+  }
+}
+
+impl F06File {
+  /// Returns the output axis of each subcase with blocks printed at time
+  /// steps or frequencies, by subcase.
+  pub fn output_axes(&self) -> BTreeMap<usize, OutputAxis> {
+    let mut steps: BTreeMap<usize, Vec<Step>> = BTreeMap::new();
+    for block in self.all_blocks(false) {
+      if let Some(step) = block.step {
+        steps.entry(block.subcase).or_default().push(step);
+      }
+    }
+    return steps.into_iter()
+      .map(|(subcase, steps)| (subcase, OutputAxis::new(subcase, steps)))
+      .collect();
+  }
+}
+
+/// Returns the position of a block along the output axis of its subcase, out
+/// of the axes of its file (see `F06File::output_axes`), if it was printed at
+/// a time step or frequency.
+pub fn axis_position(
+  axes: &BTreeMap<usize, OutputAxis>,
+  block: &FinalBlock
+) -> Option<usize> {
+  let step = block.step?;
+  return axes.get(&block.subcase)?.position(step);
+}
@@ -7,6 +7,7 @@ use std::fmt::Display;
 use clap::Args;
 use serde::{Serialize, Deserialize};
 
+use crate::blocks::worst_precision;
 use crate::prelude::*;
 
 /// This enum encodes reasons why blocks within a file were not compared.
@@ -21,7 +22,12 @@ pub enum NonCompareReason {
   NotUniqueInBoth,
   /// The blockref was unique in both files, but the blocks were not
   /// compatible.
-  NotCompatible(IncompatibilityReason)
+  NotCompatible(IncompatibilityReason),
+  /// The tolerances are tighter than the blocks were printed with.
+  BelowPrintPrecision(ToleranceTooTight),
+  /// When pairing by content, the block's counterpart couldn't be told apart
+  /// from another candidate.
+  AmbiguousPairing(AmbiguousPairing)
 }
 
 impl Display for NonCompareReason {
@@ -45,10 +51,308 @@ impl Display for NonCompareReason {
       NonCompareReason::NotCompatible(reason) => {
         write!(f, "incompatibility: {}", reason)
       },
+      NonCompareReason::BelowPrintPrecision(ttt) => {
+        write!(f, "{}; loosen it or use auto-tolerance", ttt)
+      },
+      NonCompareReason::AmbiguousPairing(amb) => {
+        write!(f, "ambiguous pairing: {}", amb)
+      },
+    };
+  }
+}
+
+/// Returns how many representable doubles apart two finite values are, i.e.
+/// their distance in units in the last place (ULPs). Zeroes of either sign
+/// are the same, values of opposite signs are as far apart as both are from
+/// zero, and denormals are a ULP apart from their neighbours like any other
+/// value.
+pub fn ulp_distance(a: f64, b: f64) -> u64 {
+  // maps the bits to integers ordered like the values, with both zeroes at 0
+  let ordered = |x: f64| {
+    let bits = x.to_bits() as i64;
+    return if bits < 0 { i64::MIN - bits } else { bits };
+  };
+  let distance = (ordered(a) as i128 - ordered(b) as i128).unsigned_abs();
+  return distance.min(u64::MAX as u128) as u64;
+}
+
+/// How the criteria of a `Tolerance` are combined.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CombinationRule {
+  /// Values pass if they're within any of the criteria.
+  Any,
+  /// Values pass only if they're within all of the criteria.
+  All
+}
+
+impl Default for CombinationRule {
+  fn default() -> Self {
+    return Self::Any;
+  }
+}
+
+/// A set of tolerances for comparing values. In an override, the ones left
+/// out are taken from the layer under it.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Tolerance {
+  /// The absolute floor: values this close pass, however small.
+  pub absolute: Option<f64>,
+  /// The relative tolerance, over the larger absolute value.
+  pub relative: Option<f64>,
+  /// The max distance in ULPs (see `ulp_distance`).
+  pub ulps: Option<u64>,
+  /// How the criteria are combined (any, by default).
+  pub rule: Option<CombinationRule>
+}
+
+impl Tolerance {
+  /// Returns this tolerance layered over another one, which fills in what
+  /// this one leaves out.
+  pub fn over(&self, under: &Self) -> Self {
+    return Self {
+      absolute: self.absolute.or(under.absolute),
+      relative: self.relative.or(under.relative),
+      ulps: self.ulps.or(under.ulps),
+      rule: self.rule.or(under.rule)
+    };
+  }
+
+  /// Returns whether no criteria are set.
+  pub fn is_empty(&self) -> bool {
+    return self.absolute.is_none()
+      && self.relative.is_none()
+      && self.ulps.is_none();
+  }
+
+  /// Checks a pair of values against this tolerance.
+  ///
+  /// NaNs and infinities are always flagged (a diff leaves them to
+  /// `Criteria::check_non_finite` instead), and equal values always pass
+  /// (so do zeroes of opposite signs). Otherwise, the set criteria are
+  /// combined by the rule; with none set, only equal values pass. At the
+  /// boundaries:
+  /// - zero against a tiny value is a relative difference of one, so only
+  ///   the absolute floor (or a huge number of ULPs) lets it pass;
+  /// - values of opposite signs are more than a relative difference of one
+  ///   apart, and as many ULPs apart as both are from zero;
+  /// - denormals are compared like any other value, so the smallest denormal
+  ///   is one ULP from zero, though relatively as far as any tiny value.
+  pub fn check(&self, a: f64, b: f64) -> Option<FlagReason> {
+    if a.is_nan() || b.is_nan() {
+      return Some(FlagReason::NaN);
+    }
+    if a.is_infinite() || b.is_infinite() {
+      return Some(FlagReason::Infinity);
+    }
+    if a == b {
+      return None;
+    }
+    let abs_difference = (a - b).abs();
+    let rel_difference = abs_difference / a.abs().max(b.abs());
+    let ulp_distance = ulp_distance(a, b);
+    let passed = [
+      self.absolute.map(|t| abs_difference <= t),
+      self.relative.map(|t| rel_difference <= t),
+      self.ulps.map(|t| ulp_distance <= t)
+    ];
+    let mut passed = passed.into_iter().flatten().peekable();
+    let within = match self.rule.unwrap_or_default() {
+      CombinationRule::Any => passed.any(|p| p),
+      CombinationRule::All => passed.peek().is_some() && passed.all(|p| p)
+    };
+    if within {
+      return None;
+    }
+    return Some(FlagReason::OutOfTolerance {
+      abs_difference,
+      rel_difference,
+      ulp_distance
+    });
+  }
+
+  /// Returns the relative print precision in ULPs.
+  fn ulp_resolution(precision: f64) -> u64 {
+    return (precision / f64::EPSILON).ceil() as u64;
+  }
+
+  /// Checks the tolerance against the relative precision some data was
+  /// printed with and its largest absolute value, like
+  /// `Criteria::check_precision`. A criterion is too tight if print rounding
+  /// alone can exceed it. When any criterion passing is enough, only one of
+  /// them has to be loose enough; otherwise, all of them do.
+  pub fn check_precision(
+    &self,
+    precision: f64,
+    magnitude: f64
+  ) -> Result<(), ToleranceTooTight> {
+    let resolution = precision * magnitude;
+    let ulp_resolution = Self::ulp_resolution(precision);
+    // for each criterion set, whether it's too tight
+    let absolute = self.absolute.map(|max_difference| {
+      return (max_difference < resolution).then_some(
+        ToleranceTooTight::Difference { max_difference, resolution }
+      );
+    });
+    let relative = self.relative.map(|max_relative| {
+      return (max_relative < precision).then_some(
+        ToleranceTooTight::Relative {
+          max_relative,
+          print_precision: precision
+        }
+      );
+    });
+    let ulps = self.ulps.map(|max_ulps| {
+      return (max_ulps < ulp_resolution).then_some(
+        ToleranceTooTight::Ulps { max_ulps, resolution: ulp_resolution }
+      );
+    });
+    let verdicts = [absolute, relative, ulps].into_iter()
+      .flatten()
+      .collect::<Vec<_>>();
+    let any_loose = verdicts.iter().any(Option::is_none);
+    if self.rule.unwrap_or_default() == CombinationRule::Any && any_loose {
+      return Ok(());
+    }
+    return match verdicts.into_iter().flatten().next() {
+      Some(ttt) => Err(ttt),
+      None => Ok(())
+    };
+  }
+
+  /// Returns this tolerance loosened to the precision some data was printed
+  /// with and its largest absolute value, like
+  /// `Criteria::with_auto_tolerance`: the set criteria are raised to twice
+  /// the print precision (or the printed resolution) if under it. With no
+  /// criteria set, a relative one of twice the print precision is.
+  pub fn with_auto_tolerance(&self, precision: f64, magnitude: f64) -> Self {
+    let relative = 2.0 * precision;
+    if self.is_empty() {
+      return Self { relative: Some(relative), ..*self };
+    }
+    let ulps = 2 * Self::ulp_resolution(precision);
+    let absolute = 2.0 * precision * magnitude;
+    return Self {
+      absolute: self.absolute.map(|t| t.max(absolute)),
+      relative: self.relative.map(|t| t.max(relative)),
+      ulps: self.ulps.map(|t| t.max(ulps)),
+      rule: self.rule
+    };
+  }
+}
+
+/// An error while reading a tolerance model.
+#[derive(Debug, derive_more::From)]
+#[non_exhaustive]
+pub enum ToleranceModelError {
+  /// The TOML was malformed.
+  Toml(toml::de::Error),
+  /// A column override is for a column the block type doesn't have.
+  UnknownColumn {
+    /// The block type.
+    block_type: BlockType,
+    /// The column, as written.
+    column: String
+  },
+  /// A tolerance is negative or not a number.
+  BadTolerance(f64)
+}
+
+impl Display for ToleranceModelError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::Toml(e) => write!(f, "TOML error: {}", e),
+      Self::UnknownColumn { block_type, column } => {
+        write!(f, "{} blocks have no column {}", block_type, column)
+      },
+      Self::BadTolerance(x) => write!(f, "bad tolerance {}", x),
     };
   }
 }
 
+impl std::error::Error for ToleranceModelError {}
+
+/// A model of how close values must be to pass: a default tolerance, with
+/// overrides per block type and, over those, per column of a block type.
+/// Meant to be kept in version control as TOML, like so:
+///
+/// ```toml
+/// [default]
+/// absolute = 1e-9
+/// relative = 1e-4
+///
+/// [blocks.QuadStresses]
+/// relative = 1e-3
+/// rule = "all"
+///
+/// [columns.QuadStresses."VON MISES"]
+/// absolute = 0.5
+/// ```
+///
+/// Columns are named as they're displayed (e.g. "Tx", "VON MISES"), in any
+/// case.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ToleranceModel {
+  /// The tolerance for everything not overridden.
+  #[serde(default)]
+  pub default: Tolerance,
+  /// The overrides per block type.
+  #[serde(default)]
+  pub blocks: BTreeMap<BlockType, Tolerance>,
+  /// The overrides per column of a block type, over the block type's.
+  #[serde(default)]
+  pub columns: BTreeMap<BlockType, BTreeMap<String, Tolerance>>
+}
+
+impl ToleranceModel {
+  /// Reads a model from TOML, checking that the tolerances make sense and
+  /// that the overridden columns exist.
+  pub fn from_toml(text: &str) -> Result<Self, ToleranceModelError> {
+    let model: Self = toml::from_str(text)?;
+    let layers = std::iter::once(&model.default)
+      .chain(model.blocks.values())
+      .chain(model.columns.values().flat_map(BTreeMap::values));
+    for layer in layers {
+      let reals = [layer.absolute, layer.relative];
+      let bad = reals.into_iter().flatten().find(|x| x.is_nan() || *x < 0.0);
+      if let Some(bad) = bad {
+        return Err(ToleranceModelError::BadTolerance(bad));
+      }
+    }
+    for (block_type, columns) in model.columns.iter() {
+      let known = block_type.columns();
+      for column in columns.keys() {
+        if !known.iter().any(|c| c.to_string().eq_ignore_ascii_case(column)) {
+          return Err(ToleranceModelError::UnknownColumn {
+            block_type: *block_type,
+            column: column.clone()
+          });
+        }
+      }
+    }
+    return Ok(model);
+  }
+
+  /// Returns the tolerance for a column of a block type: its column
+  /// override, over its block type override, over the default.
+  pub fn resolve(&self, block_type: BlockType, col: NasIndex) -> Tolerance {
+    let mut tolerance = self.default;
+    if let Some(block) = self.blocks.get(&block_type) {
+      tolerance = block.over(&tolerance);
+    }
+    let name = col.to_string();
+    let column = self.columns.get(&block_type)
+      .and_then(|cols| cols.iter().find(|(k, _)| k.eq_ignore_ascii_case(&name)))
+      .map(|(_, column)| column);
+    if let Some(column) = column {
+      tolerance = column.over(&tolerance);
+    }
+    return tolerance;
+  }
+}
+
 /// This contains the settings for when you need to compare two files.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Args)]
 pub struct DiffSettings {
@@ -62,7 +366,17 @@ pub struct DiffSettings {
   /// Limit for the number of flagged values per block (0 for no limit)
   #[clap(default_value = "0")]
   #[arg(short = 'F')]
-  pub max_flags: Option<usize>
+  pub max_flags: Option<usize>,
+  /// Derive each block's max ratio from the precision its values were
+  /// printed with, and raise a max difference that's under it, instead of
+  /// refusing to compare.
+  #[arg(long)]
+  pub auto_tol: bool,
+  /// How to pair up the blocks of the two files: by subcase, or by how alike
+  /// their contents are, for when the subcases were numbered differently.
+  #[arg(long = "pair-by", value_enum, default_value_t = PairBy::Subcase)]
+  #[serde(default)]
+  pub pair_by: PairBy
 }
 
 impl From<DiffSettings> for DataDiffer {
@@ -77,75 +391,241 @@ impl From<DiffSettings> for DataDiffer {
 /// This structure holds the differences found between two F06Files.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct F06Diff {
-  /// Blocks that were compared and the positions that were flagged.
-  pub compared: BTreeMap<BlockRef, Vec<FlaggedPosition>>,
+  /// Blocks that were compared and the positions that were flagged. The
+  /// blocks of dynamic analyses are paired and compared step by step.
+  pub compared: BTreeMap<SteppedBlockRef, Vec<FlaggedPosition>>,
   /// Blocks that were not compared due to their being incompatible.
-  pub not_compared: BTreeMap<BlockRef, NonCompareReason>,
+  pub not_compared: BTreeMap<SteppedBlockRef, NonCompareReason>,
+  /// The pairing of the blocks, when they're paired by content. The blocks
+  /// are then referred to as in the first file.
+  #[serde(default)]
+  pub pairing: Option<ContentPairing>,
+  /// When the blocks are paired by content, the blocks of the second file
+  /// that nothing in the first one was compared to, referred to as in the
+  /// second file.
+  #[serde(default)]
+  pub not_compared_b: BTreeMap<SteppedBlockRef, NonCompareReason>
 }
 
 impl F06Diff {
   /// Diffs two `F06File`s.
   pub fn compare(settings: &DiffSettings, a: &F06File, b: &F06File) -> Self {
+    return Self::compare_with_tolerance(settings, None, a, b);
+  }
+
+  /// Diffs two `F06File`s, comparing values with a tolerance model (e.g.
+  /// read from a criteria file), if one is given, instead of the max
+  /// difference and ratio.
+  pub fn compare_with_tolerance(
+    settings: &DiffSettings,
+    tolerance: Option<&ToleranceModel>,
+    a: &F06File,
+    b: &F06File
+  ) -> Self {
+    if settings.pair_by == PairBy::Content {
+      return Self::compare_by_content(settings, tolerance, a, b);
+    }
     // init inners
-    let mut compared: BTreeMap<BlockRef, Vec<FlaggedPosition>>;
-    let mut not_compared: BTreeMap<BlockRef, NonCompareReason>;
+    let mut compared: BTreeMap<SteppedBlockRef, Vec<FlaggedPosition>>;
+    let mut not_compared: BTreeMap<SteppedBlockRef, NonCompareReason>;
     compared = BTreeMap::new();
     not_compared = BTreeMap::new();
-    let differ: DataDiffer = (*settings).into();
-    let brs = a.blocks.keys().chain(b.blocks.keys()).collect::<BTreeSet<_>>();
-    for br in brs {
-      let ta: Vec<FinalBlock> = Vec::new();
-      let tb: Vec<FinalBlock> = Vec::new();
-      let va = a.blocks.get(br).unwrap_or(&ta);
-      let vb = b.blocks.get(br).unwrap_or(&tb);
+    let (stepped_a, stepped_b) = (a.stepped_blocks(), b.stepped_blocks());
+    let sbrs = stepped_a.keys()
+      .chain(stepped_b.keys())
+      .collect::<BTreeSet<_>>();
+    for sbr in sbrs {
+      let va = stepped_a.get(sbr).map_or(&[][..], Vec::as_slice);
+      let vb = stepped_b.get(sbr).map_or(&[][..], Vec::as_slice);
       let afn = a.filename.clone();
       let bfn = b.filename.clone();
       match (va.len(), vb.len()) {
         (0, 0) => panic!("block type missing in both files?!"),
         (0, 1) => {
           not_compared.insert(
-            *br,
+            *sbr,
             NonCompareReason::NoCounterpart(afn)
           );
         },
         (1, 0) => {
           not_compared.insert(
-            *br,
+            *sbr,
             NonCompareReason::NoCounterpart(bfn)
           );
         },
         (1, 1) => {
-          let block_a = va.first().unwrap();
-          let block_b = vb.first().unwrap();
-          if let Ok(flags) = differ.compare(block_a, block_b) {
-            let mf = settings.max_flags.unwrap_or(0);
-            if mf == 0 {
-              compared.insert(*br, flags.collect());
-            } else {
-              compared.insert(*br, flags.take(mf).collect());
+          match Self::compare_pair(settings, tolerance, va[0], vb[0]) {
+            Ok(flags) => {
+              compared.insert(*sbr, flags);
+            },
+            Err(reason) => {
+              not_compared.insert(*sbr, reason);
             }
-          }
+          };
         },
         (_, 1) => {
           not_compared.insert(
-            *br,
+            *sbr,
             NonCompareReason::NotUniqueInOne(afn)
           );
         },
         (1, _) => {
           not_compared.insert(
-            *br,
+            *sbr,
             NonCompareReason::NotUniqueInOne(bfn)
           );
         },
         (_, _) => {
           not_compared.insert(
-            *br,
+            *sbr,
             NonCompareReason::NotUniqueInBoth
           );
         },
       };
     }
-    return Self { compared, not_compared };
+    return Self {
+      compared,
+      not_compared,
+      pairing: None,
+      not_compared_b: BTreeMap::new()
+    };
+  }
+
+  /// Compares a pair of blocks, one from each file. With a tolerance model,
+  /// each column is compared with its own tolerance, which the print
+  /// precision is checked against (or loosens, with auto-tolerance). NaNs and
+  /// infinities are checked by the criteria either way.
+  fn compare_pair(
+    settings: &DiffSettings,
+    tolerance: Option<&ToleranceModel>,
+    block_a: &FinalBlock,
+    block_b: &FinalBlock
+  ) -> Result<Vec<FlaggedPosition>, NonCompareReason> {
+    let mut differ = DataDiffer::new(
+      settings.criteria,
+      settings.dxn_behaviour.unwrap_or_default()
+    );
+    let precision = worst_precision(
+      block_a.print_precision,
+      block_b.print_precision
+    );
+    let magnitude = block_a.max_abs().unwrap_or(0.0)
+      .max(block_b.max_abs().unwrap_or(0.0));
+    let limit = |flags: Vec<FlaggedPosition>| {
+      return match settings.max_flags.unwrap_or(0) {
+        0 => flags,
+        mf => flags.into_iter().take(mf).collect()
+      };
+    };
+    if let Some(model) = tolerance {
+      let mut tolerances: BTreeMap<NasIndex, Tolerance> = BTreeMap::new();
+      for col in block_a.col_indexes.keys().copied() {
+        let mut tolerance = model.resolve(block_a.block_type, col);
+        if let Some(p) = precision {
+          if settings.auto_tol {
+            tolerance = tolerance.with_auto_tolerance(p, magnitude);
+          } else {
+            tolerance.check_precision(p, magnitude)
+              .map_err(NonCompareReason::BelowPrintPrecision)?;
+          }
+        }
+        tolerances.insert(col, tolerance);
+      }
+      let check = |col, x: f64, y: f64| {
+        if !x.is_finite() || !y.is_finite() {
+          return settings.criteria.check_non_finite(x, y);
+        }
+        return tolerances.get(&col)?.check(x, y);
+      };
+      let flags = differ.compare_with(block_a, block_b, check)
+        .map_err(NonCompareReason::NotCompatible)?;
+      return Ok(limit(flags.collect()));
+    }
+    if let Some(p) = precision {
+      if settings.auto_tol {
+        differ.criteria = differ.criteria.with_auto_tolerance(p, magnitude);
+      } else if let Err(ttt) = differ.criteria.check_precision(p, magnitude) {
+        return Err(NonCompareReason::BelowPrintPrecision(ttt));
+      }
+    }
+    let flags = differ.compare(block_a, block_b)
+      .map_err(NonCompareReason::NotCompatible)?;
+    return Ok(limit(flags.collect()));
+  }
+
+  /// Diffs two `F06File`s, pairing their blocks by content (see
+  /// `ContentPairing::propose`) rather than by subcase.
+  fn compare_by_content(
+    settings: &DiffSettings,
+    tolerance: Option<&ToleranceModel>,
+    a: &F06File,
+    b: &F06File
+  ) -> Self {
+    let mut compared: BTreeMap<SteppedBlockRef, Vec<FlaggedPosition>>;
+    let mut not_compared: BTreeMap<SteppedBlockRef, NonCompareReason>;
+    compared = BTreeMap::new();
+    not_compared = BTreeMap::new();
+    let pairing = ContentPairing::propose(a, b);
+    let stepped_b = b.stepped_blocks();
+    let mut seen_b: BTreeSet<SteppedBlockRef> = BTreeSet::new();
+    for (sbr, va) in a.stepped_blocks() {
+      let br = sbr.block_ref;
+      if va.len() != 1 {
+        let reason = NonCompareReason::NotUniqueInOne(a.filename.clone());
+        not_compared.insert(sbr, reason);
+      } else if let Some(pair) = pairing.counterpart(br) {
+        // the same step of the paired subcase
+        let sbr_b = SteppedBlockRef { block_ref: pair.block_ref_b(), ..sbr };
+        seen_b.insert(sbr_b);
+        let block_b = match stepped_b.get(&sbr_b).map(Vec::as_slice) {
+          Some([block_b]) => block_b,
+          Some(_) => {
+            let reason = NonCompareReason::NotUniqueInOne(b.filename.clone());
+            not_compared.insert(sbr, reason);
+            continue;
+          },
+          None => {
+            let reason = NonCompareReason::NoCounterpart(b.filename.clone());
+            not_compared.insert(sbr, reason);
+            continue;
+          }
+        };
+        // the subcases differ on purpose
+        let mut block_b = (*block_b).clone();
+        block_b.subcase = br.subcase;
+        match Self::compare_pair(settings, tolerance, va[0], &block_b) {
+          Ok(flags) => {
+            compared.insert(sbr, flags);
+          },
+          Err(reason) => {
+            not_compared.insert(sbr, reason);
+          }
+        };
+      } else if let Some(amb) = pairing.ambiguity(br) {
+        not_compared.insert(sbr, NonCompareReason::AmbiguousPairing(*amb));
+      } else {
+        let reason = NonCompareReason::NoCounterpart(b.filename.clone());
+        not_compared.insert(sbr, reason);
+      }
+    }
+    // and the blocks of b that nothing was compared to
+    let mut not_compared_b: BTreeMap<SteppedBlockRef, NonCompareReason>;
+    not_compared_b = BTreeMap::new();
+    for (sbr, vb) in stepped_b.iter().filter(|(k, _)| !seen_b.contains(k)) {
+      let reason = if vb.len() != 1 {
+        NonCompareReason::NotUniqueInOne(b.filename.clone())
+      } else if let Some(amb) = pairing.ambiguity_b(sbr.block_ref) {
+        NonCompareReason::AmbiguousPairing(*amb)
+      } else {
+        NonCompareReason::NoCounterpart(a.filename.clone())
+      };
+      not_compared_b.insert(*sbr, reason);
+    }
+    return Self {
+      compared,
+      not_compared,
+      pairing: Some(pairing),
+      not_compared_b
+    };
   }
 }
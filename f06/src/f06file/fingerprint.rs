@@ -0,0 +1,154 @@
+//! This submodule implements a determinism fingerprint for parsed files: a
+//! cheap hash of what was parsed, to compare parses of the same F06 across
+//! machines and reading backends. It leaves out whatever depends on where and
+//! how the file was read (its name, byte offsets) and the run statistics,
+//! which are mostly timestamps and times.
+
+use std::fmt::Debug;
+
+use crate::prelude::*;
+
+/// A 64-bit FNV-1a hasher. Unlike the standard library's default hasher, its
+/// output is the same on every platform and compiler version, as long as it's
+/// only fed fixed-width, little-endian numbers (hence no `Hasher` impl, whose
+/// `usize`s are native-width).
+#[derive(Copy, Clone, Debug)]
+pub struct StableHasher {
+  /// The state.
+  state: u64
+}
+
+impl Default for StableHasher {
+  fn default() -> Self {
+    return Self::new();
+  }
+}
+
+impl StableHasher {
+  /// The FNV offset basis.
+  const OFFSET: u64 = 0xcbf29ce484222325;
+  /// The FNV prime.
+  const PRIME: u64 = 0x100000001b3;
+
+  /// Starts a new hash.
+  pub fn new() -> Self {
+    return Self { state: Self::OFFSET };
+  }
+
+  /// Feeds some bytes.
+  pub fn bytes(&mut self, bytes: &[u8]) {
+    for b in bytes {
+      self.state ^= u64::from(*b);
+      self.state = self.state.wrapping_mul(Self::PRIME);
+    }
+  }
+
+  /// Feeds a number.
+  pub fn u64(&mut self, x: u64) {
+    self.bytes(&x.to_le_bytes());
+  }
+
+  /// Feeds a size or an index.
+  pub fn usize(&mut self, x: usize) {
+    self.u64(x as u64);
+  }
+
+  /// Feeds a string, prefixed by its length so that consecutive ones can't
+  /// run into each other.
+  pub fn str(&mut self, s: &str) {
+    self.usize(s.len());
+    self.bytes(s.as_bytes());
+  }
+
+  /// Feeds the debug representation of something.
+  pub fn debug<T: Debug>(&mut self, x: &T) {
+    self.str(&format!("{:?}", x));
+  }
+
+  /// Returns the hash so far.
+  pub fn finish(&self) -> u64 {
+    return self.state;
+  }
+}
+
+/// Feeds a block to a hasher: its reference, line range, indexes, values (by
+/// index, so the order the rows were stored in doesn't matter) and metadata.
+fn hash_block(hasher: &mut StableHasher, block: &FinalBlock) {
+  hasher.debug(&block.block_ref());
+  hasher.debug(&block.line_range);
+  hasher.usize(block.row_indexes.len());
+  hasher.usize(block.col_indexes.len());
+  for col in block.col_indexes.keys() {
+    hasher.debug(col);
+  }
+  for (row, irow) in block.row_indexes.iter() {
+    hasher.debug(row);
+    for icol in block.col_indexes.values() {
+      let bits = match block.data {
+        Some(FinalDMat::Reals(ref m)) => m[(*irow, *icol)].to_bits(),
+        Some(FinalDMat::Integers(ref m)) => m[(*irow, *icol)] as u64,
+        Some(FinalDMat::Naturals(ref m)) => m[(*irow, *icol)] as u64,
+        None => 0
+      };
+      hasher.u64(bits);
+    }
+  }
+  for (key, value) in block.metadata.iter() {
+    hasher.str(key);
+    hasher.str(value);
+  }
+}
+
+impl F06File {
+  /// Returns a fingerprint of what was parsed: the flavour and how it was
+  /// resolved, the blocks (counts and contents), the kinds and counts of
+  /// diagnostics and messages, and the potential headers. Two parses of the
+  /// same F06 should always have the same fingerprint, whatever the machine
+  /// or the way it was read.
+  pub fn fingerprint(&self) -> u64 {
+    let mut hasher = StableHasher::new();
+    // flavour
+    let res = &self.flavour_resolution;
+    hasher.debug(&self.flavour);
+    hasher.debug(&(res.solver.value, res.solver.source, res.solver.line));
+    hasher.debug(&(res.soltype.value, res.soltype.source, res.soltype.line));
+    hasher.usize(res.solver.conflicts.len());
+    hasher.usize(res.soltype.conflicts.len());
+    hasher.u64(u64::from(self.decimal_comma));
+    hasher.debug(&self.line_count);
+    // blocks
+    hasher.usize(self.blocks.len());
+    for blocks in self.blocks.values() {
+      hasher.usize(blocks.len());
+      for block in blocks {
+        hash_block(&mut hasher, block);
+      }
+    }
+    // diagnostics and messages
+    hasher.usize(self.diagnostics.entries.len());
+    for entry in self.diagnostics.entries.iter() {
+      hasher.debug(&entry.kind);
+      hasher.usize(entry.count);
+    }
+    hasher.usize(self.diagnostics.overflow);
+    hasher.usize(self.warnings.len());
+    hasher.usize(self.fatal_errors.len());
+    // potential headers
+    hasher.usize(self.potential_headers.len());
+    for ph in self.potential_headers.iter() {
+      hasher.usize(ph.start);
+      hasher.usize(ph.span);
+      hasher.str(&ph.text);
+    }
+    // post-processing
+    for name in self.post_processed.iter() {
+      hasher.str(name);
+    }
+    return hasher.finish();
+  }
+
+  /// Returns the fingerprint as sixteen hex digits, as the tools print it.
+  pub fn fingerprint_hex(&self) -> String {
+    return format!("{:016x}", self.fingerprint());
+  }
+}
@@ -0,0 +1,213 @@
+//! This module implements a post-parse check of the internal consistency of
+//! a file: things that can't happen unless the parser or a decoder has a bug,
+//! like two blocks claiming the same lines or indexes pointing outside of the
+//! data.
+//!
+//! Merging blocks forgets their line ranges, so the checks on those are only
+//! meaningful on freshly parsed files.
+
+use std::fmt::Display;
+
+use log::{error, warn};
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// Where a block is in a file: its reference, its position among the blocks
+/// with that reference, and its line range.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlockLocation {
+  /// The subcase and type.
+  pub block_ref: BlockRef,
+  /// The position among the blocks with the same subcase and type.
+  pub nth: usize,
+  /// The line range, if known.
+  pub line_range: Option<(usize, usize)>
+}
+
+impl Display for BlockLocation {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{} block #{} in subcase {}",
+      self.block_ref.block_type,
+      self.nth,
+      self.block_ref.subcase
+    )?;
+    if let Some((start, end)) = self.line_range {
+      write!(f, " (lines {} to {})", start, end.saturating_sub(1))?;
+    }
+    return Ok(());
+  }
+}
+
+/// A broken internal invariant of a parsed file.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
+pub enum InvariantViolation {
+  /// Two blocks claim some of the same lines.
+  OverlappingRanges {
+    /// The block that starts first.
+    first: BlockLocation,
+    /// The block that starts inside the first one.
+    second: BlockLocation
+  },
+  /// A block's line range goes beyond the end of the file (or is inverted).
+  RangeOutsideFile {
+    /// The block.
+    block: BlockLocation,
+    /// The number of lines in the file.
+    line_count: usize
+  },
+  /// A block has no rows, but claims some lines.
+  EmptyWithRange {
+    /// The block.
+    block: BlockLocation
+  },
+  /// A row index points outside of the data.
+  RowOutOfBounds {
+    /// The block.
+    block: BlockLocation,
+    /// The row index.
+    index: NasIndex,
+    /// The row it points to.
+    position: usize,
+    /// The number of rows in the data.
+    rows: usize
+  },
+  /// A column index points outside of the data.
+  ColumnOutOfBounds {
+    /// The block.
+    block: BlockLocation,
+    /// The column index.
+    index: NasIndex,
+    /// The column it points to.
+    position: usize,
+    /// The number of columns in the data.
+    columns: usize
+  }
+}
+
+impl Display for InvariantViolation {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::OverlappingRanges { first, second } => {
+        write!(f, "{} overlaps {}", second, first)
+      },
+      Self::RangeOutsideFile { block, line_count } => write!(
+        f,
+        "{} lies outside of the file, which has {} lines",
+        block,
+        line_count
+      ),
+      Self::EmptyWithRange { block } => {
+        write!(f, "{} has no rows but claims some lines", block)
+      },
+      Self::RowOutOfBounds { block, index, position, rows } => write!(
+        f,
+        "row index {} of {} points to row {}, but there are {}",
+        index,
+        block,
+        position,
+        rows
+      ),
+      Self::ColumnOutOfBounds { block, index, position, columns } => write!(
+        f,
+        "column index {} of {} points to column {}, but there are {}",
+        index,
+        block,
+        position,
+        columns
+      ),
+    };
+  }
+}
+
+impl F06File {
+  /// Checks the internal consistency of the file, returning everything that
+  /// is off. An empty vector means all is well.
+  pub fn check_invariants(&self) -> Vec<InvariantViolation> {
+    let mut violations: Vec<InvariantViolation> = Vec::new();
+    let located = self.blocks.iter()
+      .flat_map(|(br, v)| v.iter().enumerate().map(move |(nth, b)| {
+        let loc = BlockLocation {
+          block_ref: *br,
+          nth,
+          line_range: b.line_range
+        };
+        return (loc, b);
+      }))
+      .collect::<Vec<_>>();
+    for (loc, block) in located.iter() {
+      let bounds = (loc.line_range, self.line_count);
+      if let (Some((start, end)), Some(n)) = bounds {
+        // ranges are half-open, so the end can be one past the last line
+        if start > end || end > n + 1 {
+          violations.push(InvariantViolation::RangeOutsideFile {
+            block: *loc,
+            line_count: n
+          });
+        }
+      }
+      let claims_lines = matches!(loc.line_range, Some((s, e)) if e > s);
+      if block.row_indexes.is_empty() && claims_lines {
+        violations.push(InvariantViolation::EmptyWithRange { block: *loc });
+      }
+      let rows = block.data.as_ref().map_or(0, FinalDMat::nrows);
+      let columns = block.data.as_ref().map_or(0, FinalDMat::ncols);
+      for (index, position) in block.row_indexes.iter() {
+        if *position >= rows {
+          violations.push(InvariantViolation::RowOutOfBounds {
+            block: *loc,
+            index: *index,
+            position: *position,
+            rows
+          });
+        }
+      }
+      for (index, position) in block.col_indexes.iter() {
+        if *position >= columns {
+          violations.push(InvariantViolation::ColumnOutOfBounds {
+            block: *loc,
+            index: *index,
+            position: *position,
+            columns
+          });
+        }
+      }
+    }
+    // sweep the ranges in order of start, comparing each with the ones after
+    // it that start before it ends
+    let mut ranged = located.iter()
+      .filter_map(|(loc, _)| loc.line_range.map(|lr| (lr, *loc)))
+      .filter(|((start, end), _)| end > start)
+      .collect::<Vec<_>>();
+    ranged.sort_by_key(|(lr, _)| *lr);
+    for (i, ((_, end), first)) in ranged.iter().enumerate() {
+      let overlapping = ranged[i+1..].iter()
+        .take_while(|((start, _), _)| start < end);
+      for (_, second) in overlapping {
+        violations.push(InvariantViolation::OverlappingRanges {
+          first: *first,
+          second: *second
+        });
+      }
+    }
+    return violations;
+  }
+
+  /// Checks the internal consistency of the file and logs everything that is
+  /// off: as errors if strict, as warnings otherwise. Returns how many
+  /// violations there are.
+  pub fn report_violations(&self, strict: bool) -> usize {
+    let violations = self.check_invariants();
+    for violation in violations.iter() {
+      if strict {
+        error!("Internal inconsistency: {}!", violation);
+      } else {
+        warn!("Internal inconsistency: {}!", violation);
+      }
+    }
+    return violations.len();
+  }
+}
@@ -0,0 +1,306 @@
+//! This module implements pairing up the blocks of two files by how alike
+//! their contents are, for comparing outputs whose subcases were numbered
+//! differently (e.g. after the case control was reworked).
+
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use itertools::Itertools;
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// Candidates for a block scoring within this fraction of the best one's
+/// score make its pairing ambiguous.
+pub const AMBIGUITY_MARGIN: f64 = 0.02;
+
+/// Blocks scoring less than this are too unlike to be paired at all.
+pub const MIN_PAIRING_SCORE: f64 = 0.5;
+
+/// How the blocks of two files are paired up for comparison.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, ValueEnum
+)]
+pub enum PairBy {
+  /// Blocks of the same type and subcase are compared.
+  Subcase,
+  /// Blocks of the same type are paired by how alike their contents are,
+  /// whatever their subcases.
+  Content
+}
+
+impl Default for PairBy {
+  fn default() -> Self {
+    return Self::Subcase;
+  }
+}
+
+/// Returns how alike two series of values are, from 0 to 1: their
+/// correlation (uncentred, so that flat values still correlate with
+/// themselves, and negative counting as none) times the ratio of their
+/// norms, so that the same pattern at another scale scores less.
+fn value_similarity(xs: &[f64], ys: &[f64]) -> f64 {
+  let norm = |v: &[f64]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+  let (nx, ny) = (norm(xs), norm(ys));
+  if nx == 0.0 || ny == 0.0 {
+    // all zeroes are only like all zeroes
+    return if nx == ny && !xs.is_empty() { 1.0 } else { 0.0 };
+  }
+  let dot = xs.iter().zip(ys.iter()).map(|(x, y)| x * y).sum::<f64>();
+  let correlation = dot / (nx * ny);
+  return correlation.max(0.0) * nx.min(ny) / nx.max(ny);
+}
+
+/// Returns how alike two blocks are, from 0 to 1: the fraction of all their
+/// rows that they share, times how alike the values in the shared rows are.
+/// Blocks of different types or with different columns score 0.
+pub fn block_similarity(a: &FinalBlock, b: &FinalBlock) -> f64 {
+  if a.block_type != b.block_type {
+    return 0.0;
+  }
+  let aci = a.col_indexes.keys().copied().collect::<BTreeSet<_>>();
+  let bci = b.col_indexes.keys().copied().collect::<BTreeSet<_>>();
+  if aci != bci {
+    return 0.0;
+  }
+  let ari = a.row_indexes.keys().copied().collect::<BTreeSet<_>>();
+  let bri = b.row_indexes.keys().copied().collect::<BTreeSet<_>>();
+  let shared = &ari & &bri;
+  if shared.is_empty() {
+    return 0.0;
+  }
+  let overlap = shared.len() as f64 / (&ari | &bri).len() as f64;
+  let (xs, ys): (Vec<f64>, Vec<f64>) = shared.iter()
+    .cartesian_product(aci.iter())
+    .filter_map(|(r, c)| Some((a.get(*r, *c)?, b.get(*r, *c)?)))
+    .map(|(x, y)| (f64::from(x), f64::from(y)))
+    .filter(|(x, y)| x.is_finite() && y.is_finite())
+    .unzip();
+  return overlap * value_similarity(&xs, &ys);
+}
+
+/// Returns how alike the blocks of two subcases are, step by step for
+/// dynamic analyses: the mean of the similarities at each step either has,
+/// with the steps only one of them has counting as 0.
+fn stepped_similarity(a: &[&FinalBlock], b: &[&FinalBlock]) -> f64 {
+  let steps = a.iter()
+    .chain(b.iter())
+    .map(|block| block.step)
+    .collect::<BTreeSet<_>>();
+  let total = steps.iter()
+    .map(|step| {
+      let x = a.iter().find(|block| block.step == *step);
+      let y = b.iter().find(|block| block.step == *step);
+      return match (x, y) {
+        (Some(x), Some(y)) => block_similarity(x, y),
+        _ => 0.0
+      };
+    })
+    .sum::<f64>();
+  return total / steps.len() as f64;
+}
+
+/// Returns the blocks of a type whose subcase has one of them per step (or
+/// only one, for blocks with no steps), along with the subcase, and the
+/// references to the ones that have more than that, which can't be paired.
+fn unique_blocks(
+  file: &F06File,
+  block_type: BlockType
+) -> (Vec<(usize, Vec<&FinalBlock>)>, Vec<BlockRef>) {
+  let mut unique = Vec::new();
+  let mut not_unique = Vec::new();
+  for (br, v) in file.blocks.iter() {
+    if br.block_type != block_type {
+      continue;
+    }
+    let steps = v.iter().map(|b| b.step).collect::<BTreeSet<_>>();
+    if steps.len() == v.len() {
+      unique.push((br.subcase, v.iter().collect()));
+    } else {
+      not_unique.push(*br);
+    }
+  }
+  return (unique, not_unique);
+}
+
+/// A pair of blocks of the same type, one from each file, proposed because
+/// of how alike they are.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProposedPair {
+  /// The type of both blocks.
+  pub block_type: BlockType,
+  /// The subcase of the block in the first file.
+  pub subcase_a: usize,
+  /// The subcase of the block in the second file.
+  pub subcase_b: usize,
+  /// How alike the blocks are, from 0 to 1.
+  pub score: f64,
+  /// How much better this pair scored than the next best candidate for
+  /// either block (or the score itself, if there was none). The larger, the
+  /// more confident the pairing.
+  pub margin: f64
+}
+
+impl ProposedPair {
+  /// Returns the reference to the block in the first file.
+  pub fn block_ref_a(&self) -> BlockRef {
+    return BlockRef { subcase: self.subcase_a, block_type: self.block_type };
+  }
+
+  /// Returns the reference to the block in the second file.
+  pub fn block_ref_b(&self) -> BlockRef {
+    return BlockRef { subcase: self.subcase_b, block_type: self.block_type };
+  }
+}
+
+/// Two candidate pairs sharing a block that scored too close to each other
+/// to pick one; neither is used, and none of their blocks are paired.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AmbiguousPairing {
+  /// The best candidate pair.
+  pub best: ProposedPair,
+  /// The rival candidate pair, scoring within `AMBIGUITY_MARGIN` of it,
+  /// relative to its score.
+  pub rival: ProposedPair
+}
+
+impl Display for AmbiguousPairing {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(
+      f,
+      "subcase {} with {} ({:.4}) or subcase {} with {} ({:.4})",
+      self.best.subcase_a,
+      self.best.subcase_b,
+      self.best.score,
+      self.rival.subcase_a,
+      self.rival.subcase_b,
+      self.rival.score
+    );
+  }
+}
+
+/// The blocks of two files paired up by content.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ContentPairing {
+  /// The proposed pairs.
+  pub pairs: Vec<ProposedPair>,
+  /// The pairings that were too close to call.
+  pub ambiguous: Vec<AmbiguousPairing>,
+  /// The blocks in the first file left without a counterpart.
+  pub unpaired_a: Vec<BlockRef>,
+  /// The blocks in the second file left without a counterpart.
+  pub unpaired_b: Vec<BlockRef>,
+  /// The blocks in the first file that weren't paired for having more than
+  /// one block for some step.
+  #[serde(default)]
+  pub not_unique_a: Vec<BlockRef>,
+  /// The blocks in the second file that weren't paired for having more than
+  /// one block for some step.
+  #[serde(default)]
+  pub not_unique_b: Vec<BlockRef>
+}
+
+impl ContentPairing {
+  /// Pairs up the blocks of two files by content. For each block type, every
+  /// subcase with a block per step (or a single one) in the first file is
+  /// scored against every such subcase in the second one, step by step, and
+  /// pairs scoring at least `MIN_PAIRING_SCORE` are taken best first. When a
+  /// pair's blocks have another candidate scoring within `AMBIGUITY_MARGIN`
+  /// of it (relative to its score), neither is taken: the ambiguity is
+  /// recorded instead, and the blocks of both candidates are left out of any
+  /// other pair. Subcases with more than one block for some step can't be
+  /// paired, and are listed apart.
+  pub fn propose(a: &F06File, b: &F06File) -> Self {
+    let block_types = a.blocks.keys()
+      .chain(b.blocks.keys())
+      .map(|br| br.block_type)
+      .collect::<BTreeSet<_>>();
+    let mut pairing = Self::default();
+    for block_type in block_types {
+      let (blocks_a, not_unique_a) = unique_blocks(a, block_type);
+      let (blocks_b, not_unique_b) = unique_blocks(b, block_type);
+      pairing.not_unique_a.extend(not_unique_a);
+      pairing.not_unique_b.extend(not_unique_b);
+      let mut candidates = blocks_a.iter()
+        .cartesian_product(blocks_b.iter())
+        .map(|((sa, ba), (sb, bb))| ProposedPair {
+          block_type,
+          subcase_a: *sa,
+          subcase_b: *sb,
+          score: stepped_similarity(ba, bb),
+          margin: 0.0
+        })
+        .filter(|p| p.score >= MIN_PAIRING_SCORE)
+        .collect::<Vec<_>>();
+      candidates.sort_by(|p, q| q.score.total_cmp(&p.score));
+      let mut taken_a: BTreeSet<usize> = BTreeSet::new();
+      let mut taken_b: BTreeSet<usize> = BTreeSet::new();
+      loop {
+        let mut free = candidates.iter()
+          .filter(|p| {
+            return !taken_a.contains(&p.subcase_a)
+              && !taken_b.contains(&p.subcase_b);
+          });
+        let best = match free.next() {
+          Some(best) => *best,
+          None => break
+        };
+        let rival = free
+          .find(|p| {
+            return p.subcase_a == best.subcase_a
+              || p.subcase_b == best.subcase_b;
+          })
+          .copied();
+        taken_a.insert(best.subcase_a);
+        taken_b.insert(best.subcase_b);
+        match rival {
+          Some(rival)
+            if best.score - rival.score <= AMBIGUITY_MARGIN * best.score => {
+            taken_a.insert(rival.subcase_a);
+            taken_b.insert(rival.subcase_b);
+            pairing.ambiguous.push(AmbiguousPairing { best, rival });
+          },
+          _ => pairing.pairs.push(ProposedPair {
+            margin: best.score - rival.map_or(0.0, |r| r.score),
+            ..best
+          })
+        };
+      }
+      let left = |blocks: &[(usize, Vec<&FinalBlock>)], taken: &BTreeSet<_>| {
+        return blocks.iter()
+          .filter(|(subcase, _)| !taken.contains(subcase))
+          .map(|(subcase, _)| BlockRef { subcase: *subcase, block_type })
+          .collect::<Vec<_>>();
+      };
+      pairing.unpaired_a.extend(left(&blocks_a, &taken_a));
+      pairing.unpaired_b.extend(left(&blocks_b, &taken_b));
+    }
+    return pairing;
+  }
+
+  /// Returns the pair proposed for a block in the first file, if any.
+  pub fn counterpart(&self, block_ref: BlockRef) -> Option<&ProposedPair> {
+    return self.pairs.iter().find(|p| p.block_ref_a() == block_ref);
+  }
+
+  /// Returns the ambiguity a block in the first file is caught in, as part of
+  /// either candidate, if any.
+  pub fn ambiguity(&self, block_ref: BlockRef) -> Option<&AmbiguousPairing> {
+    return self.ambiguous.iter()
+      .find(|amb| {
+        return amb.best.block_ref_a() == block_ref
+          || amb.rival.block_ref_a() == block_ref;
+      });
+  }
+
+  /// Returns the ambiguity a block in the second file is caught in, as part
+  /// of either candidate, if any.
+  pub fn ambiguity_b(&self, block_ref: BlockRef) -> Option<&AmbiguousPairing> {
+    return self.ambiguous.iter()
+      .find(|amb| {
+        return amb.best.block_ref_b() == block_ref
+          || amb.rival.block_ref_b() == block_ref;
+      });
+  }
+}
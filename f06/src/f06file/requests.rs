@@ -0,0 +1,441 @@
+//! This submodule implements the reading of the case control echo, so we
+//! know which outputs were requested in each subcase and can tell a missing
+//! block that was never asked for from one the solver failed to write.
+
+#[cfg(feature = "parser")]
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// The kinds of output requests we know to map to decoded blocks.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord
+)]
+#[non_exhaustive]
+pub enum OutputKeyword {
+  /// DISPLACEMENT (or VECTOR).
+  Displacement,
+  /// SPCFORCES.
+  SpcForces,
+  /// MPCFORCES.
+  MpcForces,
+  /// OLOAD.
+  Oload,
+  /// GPFORCE.
+  GpForce,
+  /// FORCE (or ELFORCE).
+  Force,
+  /// STRESS (or ELSTRESS).
+  Stress,
+  /// STRAIN (or ELSTRAIN).
+  Strain,
+  /// GPSTRESS.
+  GpStress,
+  /// ESE.
+  Ese
+}
+
+impl Display for OutputKeyword {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(f, "{}", self.names()[0]);
+  }
+}
+
+impl OutputKeyword {
+  /// All of them.
+  const ALL: [Self; 10] = [
+    Self::Displacement,
+    Self::SpcForces,
+    Self::MpcForces,
+    Self::Oload,
+    Self::GpForce,
+    Self::Force,
+    Self::Stress,
+    Self::Strain,
+    Self::GpStress,
+    Self::Ese
+  ];
+
+  /// The names of the keyword, canonical one first.
+  pub const fn names(&self) -> &'static [&'static str] {
+    return match self {
+      Self::Displacement => &["DISPLACEMENT", "VECTOR"],
+      Self::SpcForces => &["SPCFORCES"],
+      Self::MpcForces => &["MPCFORCES"],
+      Self::Oload => &["OLOAD"],
+      Self::GpForce => &["GPFORCE"],
+      Self::Force => &["FORCE", "ELFORCE"],
+      Self::Stress => &["STRESS", "ELSTRESS"],
+      Self::Strain => &["STRAIN", "ELSTRAIN"],
+      Self::GpStress => &["GPSTRESS"],
+      Self::Ese => &["ESE"],
+    };
+  }
+
+  /// Finds the keyword a word in the echo stands for. Like the solvers, we
+  /// accept abbreviations down to four letters (e.g. "DISP"); shorter words
+  /// only stand for keywords that short (e.g. "ESE").
+  pub fn from_word(word: &str) -> Option<Self> {
+    let word = word.trim().to_ascii_uppercase();
+    if word.len() < 4 {
+      return Self::ALL.into_iter()
+        .find(|kw| kw.names().contains(&word.as_str()));
+    }
+    return Self::ALL.into_iter()
+      .find(|kw| kw.names().iter().any(|n| n.starts_with(word.as_str())));
+  }
+
+  /// The decoded block types that hold this output. Empty if we decode none,
+  /// in which case we can't tell whether it was written.
+  pub const fn block_types(&self) -> &'static [BlockType] {
+    return match self {
+      Self::Displacement => &[BlockType::Displacements],
+      Self::SpcForces => &[BlockType::SpcForces],
+      Self::MpcForces => &[BlockType::MpcForces],
+      Self::Oload => &[BlockType::AppliedForces],
+      Self::GpForce => &[BlockType::GridPointForceBalance],
+      Self::Force => &[
+        BlockType::RodForces,
+        BlockType::ConrodForces,
+        BlockType::BarForces,
+        BlockType::BeamForces,
+        BlockType::TriaForces,
+        BlockType::QuadForces,
+        BlockType::CshearForces,
+        BlockType::CgapForces,
+        BlockType::BushForces
+      ],
+      Self::Stress => &[
+        BlockType::RodStresses,
+        BlockType::ConrodStresses,
+        BlockType::BarStresses,
+        BlockType::BeamStresses,
+        BlockType::CompositePlystresses,
+        BlockType::TriaStresses,
+        BlockType::QuadStresses,
+        BlockType::ChexaStresses,
+        BlockType::CpentaStresses,
+        BlockType::CtetraStresses,
+        BlockType::CshearStresses,
+        BlockType::BushStresses
+      ],
+      Self::Strain => &[
+        BlockType::RodStrains,
+        BlockType::ConrodStrains,
+        BlockType::BarStrains,
+        BlockType::BeamStrains,
+        BlockType::TriaStrains,
+        BlockType::QuadStrains,
+        BlockType::ChexaStrains,
+        BlockType::CpentaStrains,
+        BlockType::CtetraStrains,
+        BlockType::BushStrains
+      ],
+      Self::GpStress => &[BlockType::GridPointStresses],
+      Self::Ese => &[BlockType::StrainEnergy],
+    };
+  }
+}
+
+/// Which entities an output request asks for.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RequestSet {
+  /// All of them.
+  All,
+  /// None of them, which turns off an inherited request.
+  None,
+  /// The ones in a set defined in case control.
+  Set(usize)
+}
+
+impl Display for RequestSet {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::All => write!(f, "ALL"),
+      Self::None => write!(f, "NONE"),
+      Self::Set(id) => write!(f, "SET {}", id),
+    };
+  }
+}
+
+impl RequestSet {
+  /// Decodes the right-hand side of a request, like "ALL" or "SET 10".
+  pub fn from_value(value: &str) -> Option<Self> {
+    let value = value.trim().to_ascii_uppercase();
+    let id = value.strip_prefix("SET").unwrap_or(&value).trim();
+    return match value.as_str() {
+      "ALL" => Some(Self::All),
+      "NONE" => Some(Self::None),
+      _ => id.parse().ok().map(Self::Set)
+    };
+  }
+}
+
+/// An output request from the case control echo.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OutputRequest {
+  /// What was requested.
+  pub keyword: OutputKeyword,
+  /// For which entities.
+  pub set: RequestSet,
+  /// Whether it goes to the F06.
+  pub print: bool,
+  /// Whether it goes to the punch file.
+  pub punch: bool,
+  /// Whether it goes to the plot file.
+  pub plot: bool
+}
+
+impl Display for OutputRequest {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let media = [
+      ("PRINT", self.print),
+      ("PUNCH", self.punch),
+      ("PLOT", self.plot)
+    ];
+    let media = media.into_iter()
+      .filter_map(|(name, on)| on.then_some(name))
+      .collect::<Vec<_>>();
+    return write!(f, "{}({}) = {}", self.keyword, media.join(","), self.set);
+  }
+}
+
+impl OutputRequest {
+  /// Decodes a request from both sides of its equals sign, as in
+  /// "STRAIN(PRINT,FIBER)" and "ALL". Returns None if it's not an output
+  /// request we know.
+  pub fn from_sides(left: &str, right: &str) -> Option<Self> {
+    let (word, options) = match left.split_once('(') {
+      Some((w, o)) => (w, o.trim_end().strip_suffix(')')?),
+      None => (left, "")
+    };
+    let options = options.split(',')
+      .map(|o| o.trim().to_ascii_uppercase())
+      .collect::<Vec<_>>();
+    let has = |o: &str| options.iter().any(|x| x == o);
+    let (punch, plot) = (has("PUNCH"), has("PLOT"));
+    // printing is the default, unless another medium is given
+    let print = has("PRINT") || !(punch || plot);
+    return Some(Self {
+      keyword: OutputKeyword::from_word(word)?,
+      set: RequestSet::from_value(right)?,
+      print,
+      punch,
+      plot
+    });
+  }
+
+  /// Returns whether the output should be in the F06.
+  pub fn expects_blocks(&self) -> bool {
+    return self.print && self.set != RequestSet::None;
+  }
+}
+
+/// The case control commands that title a subcase, most specific first.
+#[cfg(feature = "parser")]
+const TITLE_COMMANDS: [&str; 3] = ["SUBTITLE", "LABEL", "TITLE"];
+
+/// Returns whether a line has some text in it once all whitespace is ignored,
+/// as in the spaced-out titles, without allocating.
+#[cfg(feature = "parser")]
+fn has_unspaced(line: &str, text: &str) -> bool {
+  // most lines don't even have its first letter
+  if text.chars().next().is_some_and(|c| !line.contains(c)) {
+    return false;
+  }
+  let mut chars = line.chars().filter(|c| !c.is_whitespace());
+  loop {
+    let matched = chars.clone()
+      .zip(text.chars())
+      .take_while(|(a, b)| a == b)
+      .count();
+    if matched == text.len() {
+      return true;
+    }
+    if chars.next().is_none() {
+      return false;
+    }
+  }
+}
+
+/// Where we are relative to the case control echo.
+#[cfg(feature = "parser")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum EchoState {
+  /// Haven't seen it yet.
+  Before,
+  /// Reading it.
+  Inside,
+  /// Went past it.
+  After
+}
+
+/// Reads the output requests from the case control echo. Simcenter prints it
+/// under its own header with a count before each command; MYSTRAN just
+/// copies it after the CEND line. Both end it with BEGIN BULK.
+#[cfg(feature = "parser")]
+#[derive(Clone, Debug)]
+pub(crate) struct CaseControlScanner {
+  /// Where we are.
+  state: EchoState,
+  /// The requests above the first subcase.
+  global: Vec<OutputRequest>,
+  /// The requests in each subcase.
+  subcases: BTreeMap<usize, Vec<OutputRequest>>,
+  /// The subcases, in the order they're declared.
+  order: Vec<usize>,
+  /// The subcase being read, if any.
+  current: Option<usize>,
+  /// The titles given above the first subcase (None) and in each subcase,
+  /// with the position of their command in `TITLE_COMMANDS`.
+  titles: BTreeMap<Option<usize>, (usize, String)>
+}
+
+#[cfg(feature = "parser")]
+impl Default for CaseControlScanner {
+  fn default() -> Self {
+    return Self::new();
+  }
+}
+
+#[cfg(feature = "parser")]
+impl CaseControlScanner {
+  /// Instantiates a new scanner.
+  pub(crate) fn new() -> Self {
+    return Self {
+      state: EchoState::Before,
+      global: Vec::new(),
+      subcases: BTreeMap::new(),
+      order: Vec::new(),
+      current: None,
+      titles: BTreeMap::new()
+    };
+  }
+
+  /// Feeds a line into the scanner. Returns true if it was part of the echo.
+  pub(crate) fn consume(&mut self, line: &str) -> bool {
+    match self.state {
+      EchoState::Before => {
+        let cend = line.split_whitespace().eq(["CEND"]);
+        if cend || has_unspaced(line, "CASECONTROLECHO") {
+          self.state = EchoState::Inside;
+        }
+        return false;
+      },
+      EchoState::After => return false,
+      EchoState::Inside => {}
+    };
+    if has_unspaced(line, "OUTPUTFORSUBCASE") {
+      // no BEGIN BULK, the echo is long gone
+      self.state = EchoState::After;
+      return false;
+    }
+    // skip simcenter's count
+    let mut command = line.trim();
+    if let Some((count, rest)) = command.split_once(char::is_whitespace) {
+      if count.parse::<usize>().is_ok() {
+        command = rest.trim_start();
+      }
+    }
+    if command.starts_with('$') {
+      return true;
+    }
+    let command = command.split('$').next().unwrap_or("");
+    if let Some((left, right)) = command.split_once('=') {
+      let left = left.trim();
+      let rank = TITLE_COMMANDS.iter()
+        .position(|c| c.eq_ignore_ascii_case(left));
+      if let Some(rank) = rank {
+        self.title(rank, right.trim());
+        return true;
+      }
+    }
+    let mut words = command.split_whitespace();
+    let is = |word: Option<&str>, expected: &str| {
+      return word.is_some_and(|w| w.eq_ignore_ascii_case(expected));
+    };
+    match (words.next(), words.next(), words.next()) {
+      (first, second, _) if is(first, "BEGIN") && is(second, "BULK") => {
+        self.state = EchoState::After;
+        return true;
+      },
+      (first, Some(n), None) if is(first, "SUBCASE") => {
+        if let Ok(n) = n.parse() {
+          self.current = Some(n);
+          if !self.subcases.contains_key(&n) {
+            self.order.push(n);
+          }
+          self.subcases.entry(n).or_default();
+          return true;
+        }
+      },
+      _ => {}
+    };
+    if let Some((left, right)) = command.split_once('=') {
+      if let Some(req) = OutputRequest::from_sides(left, right) {
+        match self.current {
+          Some(n) => self.subcases.entry(n).or_default().push(req),
+          None => self.global.push(req)
+        };
+      }
+      return true;
+    }
+    return false;
+  }
+
+  /// Keeps a title for the current subcase, unless it has a more specific one
+  /// already.
+  fn title(&mut self, rank: usize, title: &str) {
+    if title.is_empty() {
+      return;
+    }
+    let kept = self.titles.get(&self.current);
+    if kept.is_none_or(|(r, _)| rank <= *r) {
+      self.titles.insert(self.current, (rank, title.to_owned()));
+    }
+  }
+
+  /// Returns the subcases declared, in the order they're declared in, which
+  /// needn't be ascending.
+  pub(crate) fn subcase_order(&self) -> &[usize] {
+    return &self.order;
+  }
+
+  /// Returns the title of each subcase: its SUBTITLE, or else its LABEL, or
+  /// else whatever was given above the first subcase. Without any subcases,
+  /// the global one goes into the default one.
+  pub(crate) fn titles(&self) -> BTreeMap<usize, String> {
+    let mut subcases = self.subcases.keys().copied().collect::<Vec<_>>();
+    if subcases.is_empty() {
+      subcases.push(1);
+    }
+    return subcases.into_iter()
+      .filter_map(|sc| {
+        let (_, title) = self.titles.get(&Some(sc))
+          .or_else(|| self.titles.get(&None))?;
+        return Some((sc, title.clone()));
+      })
+      .collect();
+  }
+
+  /// Returns the requests in each subcase, with the global ones inherited
+  /// unless the subcase has its own for the same keyword. Without any
+  /// subcases, the global ones go into the default one.
+  pub(crate) fn finish(mut self) -> BTreeMap<usize, Vec<OutputRequest>> {
+    if self.subcases.is_empty() && !self.global.is_empty() {
+      self.subcases.insert(1, Vec::new());
+    }
+    for reqs in self.subcases.values_mut() {
+      let inherited = self.global.iter()
+        .filter(|g| reqs.iter().all(|r| r.keyword != g.keyword))
+        .cloned()
+        .collect::<Vec<_>>();
+      reqs.splice(0..0, inherited);
+    }
+    return self.subcases;
+  }
+}
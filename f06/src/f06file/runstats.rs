@@ -0,0 +1,257 @@
+//! This module implements the gathering of the statistics solvers report
+//! about the run itself (times, memory usage, set and matrix sizes), mostly
+//! from their summary pages.
+
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+#[cfg(feature = "parser")]
+use crate::prelude::*;
+#[cfg(feature = "parser")]
+use crate::util::*;
+
+/// Statistics about the run, as reported by the solver.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RunStats {
+  /// Elapsed (wall-clock) time, in seconds.
+  pub wall_time: Option<f64>,
+  /// CPU time, in seconds.
+  pub cpu_time: Option<f64>,
+  /// Peak memory usage, in bytes.
+  pub peak_memory: Option<u64>,
+  /// Number of degrees of freedom in each set, by set name (e.g. "G", "A").
+  pub dof_counts: BTreeMap<String, usize>,
+  /// Number of rows in each matrix the solver reported, by name (e.g.
+  /// "KLL").
+  pub matrix_sizes: BTreeMap<String, usize>
+}
+
+impl Default for RunStats {
+  fn default() -> Self {
+    return Self::new();
+  }
+}
+
+impl RunStats {
+  /// Instantiates empty run statistics.
+  pub fn new() -> Self {
+    return Self {
+      wall_time: None,
+      cpu_time: None,
+      peak_memory: None,
+      dof_counts: BTreeMap::new(),
+      matrix_sizes: BTreeMap::new()
+    };
+  }
+
+  /// Returns true if nothing was found.
+  pub fn is_empty(&self) -> bool {
+    return self.wall_time.is_none()
+      && self.cpu_time.is_none()
+      && self.peak_memory.is_none()
+      && self.dof_counts.is_empty()
+      && self.matrix_sizes.is_empty();
+  }
+
+  /// Returns the statistics present as labelled values, for display.
+  pub fn entries(&self) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    if let Some(t) = self.wall_time {
+      entries.push(("Wall time (s)".to_owned(), t.to_string()));
+    }
+    if let Some(t) = self.cpu_time {
+      entries.push(("CPU time (s)".to_owned(), t.to_string()));
+    }
+    if let Some(m) = self.peak_memory {
+      entries.push(("Peak memory (bytes)".to_owned(), m.to_string()));
+    }
+    for (set, n) in self.dof_counts.iter() {
+      entries.push((format!("DOFs in {}-set", set), n.to_string()));
+    }
+    for (matrix, n) in self.matrix_sizes.iter() {
+      entries.push((format!("Rows in {}", matrix), n.to_string()));
+    }
+    return entries;
+  }
+}
+
+/// Returns what comes after the first equals sign after a label in a line,
+/// if the label is there. Case-insensitive.
+#[cfg(feature = "parser")]
+fn value_after<'s>(line: &'s str, label: &str) -> Option<&'s str> {
+  let start = line.to_ascii_uppercase().find(label)? + label.len();
+  let eq = start + line[start..].find('=')? + 1;
+  return Some(line[eq..].trim());
+}
+
+/// Returns the first word of a value as a natural.
+#[cfg(feature = "parser")]
+fn first_natural(value: &str) -> Option<usize> {
+  return value.split_whitespace().next()?.parse().ok();
+}
+
+/// Returns the name of the set mentioned in a line, as in "G-SET".
+#[cfg(feature = "parser")]
+fn set_name(line: &str) -> Option<String> {
+  let upper = line.to_ascii_uppercase();
+  let end = upper.find("-SET")?;
+  let name = upper[..end]
+    .rsplit(|c: char| !c.is_ascii_alphanumeric())
+    .next()?;
+  if name.is_empty() {
+    return None;
+  }
+  return Some(name.to_owned());
+}
+
+/// Gathers run statistics from the lines fed into it.
+#[cfg(feature = "parser")]
+#[derive(Clone, Debug)]
+pub(crate) struct RunStatsScanner {
+  /// What was found so far.
+  stats: RunStats,
+  /// The date and time of day (in seconds) the run began at.
+  began: Option<(String, f64)>,
+  /// The data block whose parameters are being printed, if any.
+  data_block: Option<String>
+}
+
+#[cfg(feature = "parser")]
+impl Default for RunStatsScanner {
+  fn default() -> Self {
+    return Self::new();
+  }
+}
+
+#[cfg(feature = "parser")]
+impl RunStatsScanner {
+  /// Instantiates a new scanner.
+  pub(crate) fn new() -> Self {
+    return Self {
+      stats: RunStats::new(),
+      began: None,
+      data_block: None
+    };
+  }
+
+  /// Feeds a line into the scanner. Returns true if it had a statistic.
+  pub(crate) fn consume(
+    &mut self,
+    solver: Option<Solver>,
+    line: &str
+  ) -> bool {
+    // every line we care about has one of these, and data lines don't.
+    if !line.contains('=') && !line.contains(">>") {
+      return false;
+    }
+    if self.common_line(line) {
+      return true;
+    }
+    return match solver {
+      Some(Solver::Mystran) => self.mystran_line(line),
+      Some(Solver::Simcenter) => self.simcenter_line(line),
+      _ => false
+    };
+  }
+
+  /// Returns what was found.
+  pub(crate) fn finish(self) -> RunStats {
+    return self.stats;
+  }
+
+  /// Handles the lines all solvers print alike: set sizes and peak memory.
+  fn common_line(&mut self, line: &str) -> bool {
+    let upper = line.to_ascii_uppercase();
+    let about_dofs = ["DEGREES OF FREEDOM", "DOF", "SIZE"].iter()
+      .any(|w| upper.contains(w));
+    if let (true, Some(set)) = (about_dofs, set_name(line)) {
+      let label = format!("{}-SET", set);
+      if let Some(n) = value_after(line, &label).and_then(first_natural) {
+        self.stats.dof_counts.insert(set, n);
+        return true;
+      }
+    }
+    let peak = ["PEAK", "MAXIMUM", "HIWATER", "HIGH WATER"].iter()
+      .any(|w| upper.contains(w));
+    if peak && upper.contains("MEMORY") {
+      if let Some(b) = value_after(line, "MEMORY").and_then(decode_bytes) {
+        let old = self.stats.peak_memory.unwrap_or(0);
+        self.stats.peak_memory = Some(old.max(b));
+        return true;
+      }
+    }
+    return false;
+  }
+
+  /// Handles MYSTRAN's lines: the start and end stamps, and the CPU time.
+  fn mystran_line(&mut self, line: &str) -> bool {
+    if let Some(t) = value_after(line, "TOTAL CPU TIME") {
+      if let Some(secs) = decode_duration(t) {
+        self.stats.cpu_time = Some(secs);
+        return true;
+      }
+      return false;
+    }
+    // like ">> MYSTRAN END    :  1/12/2024 at 10:38:24.739"
+    let is_begin = line.contains(">> MYSTRAN BEGIN");
+    if !is_begin && !line.contains(">> MYSTRAN END") {
+      return false;
+    }
+    let stamp = line.split_once(':').map(|(_, s)| s);
+    let mut words = stamp.unwrap_or("").split_whitespace();
+    let (date, at, time) = (words.next(), words.next(), words.next());
+    // the seconds are printed to some decimals, which the elapsed time is
+    // rounded to, so that the subtraction adds no digits of its own
+    let decimals = time.and_then(|t| t.split_once('.'))
+      .map_or(0, |(_, d)| d.len() as i32);
+    let (date, time) = match (date, at, time.and_then(decode_duration)) {
+      (Some(d), Some("at"), Some(t)) => (d.to_owned(), t),
+      _ => return false
+    };
+    if is_begin {
+      self.began = Some((date, time));
+    } else if let Some((ref bdate, btime)) = self.began {
+      // runs that cross midnight are assumed to take less than a day
+      let mut elapsed = time - btime;
+      if *bdate != date && elapsed < 0.0 {
+        elapsed += 86400.0;
+      }
+      let scale = 10f64.powi(decimals);
+      self.stats.wall_time = Some((elapsed * scale).round() / scale);
+    }
+    return true;
+  }
+
+  /// Handles Simcenter's lines: the resource summary, and the matrix sizes in
+  /// decomposition messages.
+  fn simcenter_line(&mut self, line: &str) -> bool {
+    if let Some(pos) = line.find("OF DATA BLOCK") {
+      let rest = &line[pos+"OF DATA BLOCK".len()..];
+      self.data_block = rest.split_whitespace().next().map(str::to_owned);
+      return false;
+    }
+    if let Some(n) = value_after(line, "MATRIX SIZE").and_then(first_natural) {
+      if let Some(name) = self.data_block.take() {
+        self.stats.matrix_sizes.insert(name, n);
+        return true;
+      }
+      return false;
+    }
+    let upper = line.to_ascii_uppercase();
+    if upper.contains("ESTIMATE") {
+      return false;
+    }
+    for label in ["ELAPSED TIME", "WALL CLOCK TIME"] {
+      if let Some(t) = value_after(line, label).and_then(decode_duration) {
+        self.stats.wall_time = Some(t);
+        return true;
+      }
+    }
+    if let Some(t) = value_after(line, "CPU TIME").and_then(decode_duration) {
+      self.stats.cpu_time = Some(t);
+      return true;
+    }
+    return false;
+  }
+}
@@ -0,0 +1,209 @@
+//! This module implements the gathering of the checks solvers print on the
+//! quality of linear static solutions: the epsilon (the relative residual of
+//! the solution, large when the stiffness matrix is nearly singular) and the
+//! external work, for each load case.
+
+#[cfg(feature = "parser")]
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+#[cfg(feature = "parser")]
+use crate::util::*;
+
+/// Epsilons larger than this (in absolute value) are flagged by Simcenter,
+/// and mean the solution might not be trustworthy.
+pub const EPSILON_THRESHOLD: f64 = 1e-3;
+
+/// The checks a solver printed on the solution of a load case.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SolutionDiagnostics {
+  /// The number of the load case, counting from one, in the order they were
+  /// solved ("LOAD SEQ. NO." or "INTERNAL SUBCASE NUMBER").
+  pub load_sequence: usize,
+  /// The epsilon (relative residual), if printed.
+  pub epsilon: Option<f64>,
+  /// The external work, if printed (MYSTRAN doesn't).
+  pub external_work: Option<f64>,
+  /// Whether the solver flagged the epsilon as too large.
+  pub flagged: bool,
+  /// The line it was found in.
+  pub line: usize
+}
+
+impl SolutionDiagnostics {
+  /// Returns whether the solution looks ill-conditioned: the solver flagged
+  /// it, or its epsilon is above the threshold.
+  pub fn ill_conditioned(&self) -> bool {
+    return self.flagged
+      || self.epsilon.is_some_and(|e| e.abs() > EPSILON_THRESHOLD);
+  }
+}
+
+impl Display for SolutionDiagnostics {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self.epsilon {
+      Some(e) => write!(f, "epsilon {:E}", e)?,
+      None => write!(f, "epsilon unknown")?
+    };
+    if let Some(w) = self.external_work {
+      write!(f, ", external work {:E}", w)?;
+    }
+    if self.ill_conditioned() {
+      write!(f, " (ill-conditioned)")?;
+    }
+    return Ok(());
+  }
+}
+
+impl F06File {
+  /// Returns the solution checks present as labelled values, for display.
+  /// They're in scientific notation, like the reals in the CSV output.
+  pub fn solution_diagnostic_entries(&self) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    for (subcase, diag) in self.solution_diagnostics.iter() {
+      if let Some(e) = diag.epsilon {
+        let label = format!("Epsilon (subcase {})", subcase);
+        entries.push((label, format!("{:E}", e)));
+      }
+      if let Some(w) = diag.external_work {
+        let label = format!("External work (subcase {})", subcase);
+        entries.push((label, format!("{:E}", w)));
+      }
+    }
+    return entries;
+  }
+
+  /// Returns whether any load case looks ill-conditioned.
+  pub fn ill_conditioned(&self) -> bool {
+    return self.solution_diagnostics.values()
+      .any(SolutionDiagnostics::ill_conditioned);
+  }
+}
+
+/// What MYSTRAN prints the epsilon of a load case after.
+#[cfg(feature = "parser")]
+const MYSTRAN_EPSILON: &str = "EPSILON ERROR ESTIMATE";
+
+/// What MYSTRAN prints the number of a load case after.
+#[cfg(feature = "parser")]
+const MYSTRAN_SUBCASE: &str = "INTERNAL SUBCASE NUMBER";
+
+/// How many lines of explanations can come between Simcenter's table header
+/// and its rows.
+#[cfg(feature = "parser")]
+const MAX_TABLE_PREAMBLE: usize = 10;
+
+/// Gathers solution checks from the lines fed into it.
+#[cfg(feature = "parser")]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SolutionDiagnosticsScanner {
+  /// What was found so far, by load sequence number.
+  found: BTreeMap<usize, SolutionDiagnostics>,
+  /// Lines since the header of Simcenter's table, if in one, and whether its
+  /// rows have begun.
+  table: Option<(usize, bool)>
+}
+
+#[cfg(feature = "parser")]
+impl SolutionDiagnosticsScanner {
+  /// Instantiates a new scanner.
+  pub(crate) fn new() -> Self {
+    return Self::default();
+  }
+
+  /// Feeds a line into the scanner, along with its number. Returns true if
+  /// it was part of a solution check.
+  pub(crate) fn consume(&mut self, line: &str, line_no: usize) -> bool {
+    if line.contains(MYSTRAN_EPSILON) {
+      return self.mystran_line(line, line_no);
+    }
+    // like "LOAD SEQ. NO.      EPSILON      EXTERNAL WORK      EPSILONS ..."
+    if line.contains("LOAD SEQ. NO.") && line.contains("EPSILON") {
+      self.table = Some((0, false));
+      return true;
+    }
+    let Some((since, begun)) = self.table else {
+      return false;
+    };
+    if self.simcenter_row(line, line_no) {
+      self.table = Some((since + 1, true));
+      return true;
+    }
+    // the rows end with the first line that isn't one, and explanations
+    // come before them
+    self.table = if begun || since >= MAX_TABLE_PREAMBLE {
+      None
+    } else {
+      Some((since + 1, false))
+    };
+    return false;
+  }
+
+  /// Handles a line like "*INFORMATION: FOR INTERNAL SUBCASE NUMBER 1
+  /// EPSILON ERROR ESTIMATE = 2.584605E-16 Based on ...".
+  fn mystran_line(&mut self, line: &str, line_no: usize) -> bool {
+    let number = |label: &str| {
+      let start = line.find(label)? + label.len();
+      return line[start..].split_whitespace().next();
+    };
+    let seq = number(MYSTRAN_SUBCASE).and_then(|s| s.parse().ok());
+    let epsilon = line.find(MYSTRAN_EPSILON)
+      .and_then(|start| line[start..].split_once('='))
+      .and_then(|(_, rest)| rest.split_whitespace().next())
+      .and_then(decode_nasfloat);
+    let (Some(load_sequence), Some(epsilon)) = (seq, epsilon) else {
+      return false;
+    };
+    self.found.insert(load_sequence, SolutionDiagnostics {
+      load_sequence,
+      epsilon: Some(epsilon),
+      external_work: None,
+      flagged: false,
+      line: line_no
+    });
+    return true;
+  }
+
+  /// Handles a row of Simcenter's table, like "1  5.3482390E-16
+  /// 4.2147120E+02", with asterisks if the epsilon is flagged.
+  fn simcenter_row(&mut self, line: &str, line_no: usize) -> bool {
+    let flagged = line.contains('*');
+    let unflagged = line.replace('*', " ");
+    let mut words = unflagged.split_whitespace();
+    let seq = words.next().and_then(|w| w.parse::<usize>().ok());
+    let epsilon = words.next().and_then(decode_nasfloat);
+    let work = words.next().and_then(decode_nasfloat);
+    let (Some(load_sequence), Some(_), Some(_), None) =
+      (seq, epsilon, work, words.next())
+    else {
+      return false;
+    };
+    self.found.insert(load_sequence, SolutionDiagnostics {
+      load_sequence,
+      epsilon,
+      external_work: work,
+      flagged,
+      line: line_no
+    });
+    return true;
+  }
+
+  /// Returns what was found, by subcase. Load cases are solved in the order
+  /// their subcases are declared in, so the nth one is the nth of those given
+  /// (in the order of the case control echo, which needn't be ascending);
+  /// past them, the number is taken as the subcase.
+  pub(crate) fn finish(
+    self,
+    subcases: &[usize]
+  ) -> BTreeMap<usize, SolutionDiagnostics> {
+    return self.found.into_iter()
+      .map(|(seq, diag)| {
+        let subcase = seq.checked_sub(1).and_then(|i| subcases.get(i));
+        return (subcase.copied().unwrap_or(seq), diag);
+      })
+      .collect();
+  }
+}
@@ -0,0 +1,67 @@
+//! Tests that run with and without the `parser` feature, to make sure the
+//! data model stands on its own and the full build still has the parser.
+
+use std::collections::BTreeMap;
+
+use crate::prelude::*;
+use crate::util::{fmt_f64, non_finite_name, IdSelection};
+
+/// Builds a displacements block by hand, the way a file without the parser
+/// would have to get one (short of deserialising it).
+fn hand_made_block() -> FinalBlock {
+  let cols = [DOF_TX, DOF_TY, DOF_TZ, DOF_RX, DOF_RY, DOF_RZ]
+    .iter()
+    .enumerate()
+    .map(|(i, dof)| (*dof, i))
+    .collect::<BTreeMap<_, _>>();
+  let mut rb: RowBlock<f64, GridPointRef, Dof, 6> = RowBlock::new(cols);
+  rb.insert_raw(GridPointRef { gid: 1 }, &[1.0, 2.0, 3.0, 0.0, 0.0, 0.0], 1);
+  let gp2 = [0.0, 0.0, f64::NAN, 0.0, 0.0, 0.0];
+  rb.insert_raw(GridPointRef { gid: 2 }, &gp2, 2);
+  return rb.finalise(BlockType::Displacements, 1, None);
+}
+
+#[test]
+fn test_data_model() {
+  // blocks, their schemas and the file holding them
+  for bt in BlockType::all() {
+    assert!(!bt.columns().is_empty(), "{} has no columns", bt);
+  }
+  let schema = BlockType::QuadStresses.schema();
+  assert_eq!(schema.row_dimension, RowDimension::Side);
+  let mut file = F06File::new();
+  file.insert_block(hand_made_block());
+  let block = file.block_search(Some(BlockType::Displacements), None, true)
+    .next()
+    .unwrap();
+  let x = block.get(GridPointRef { gid: 1 }, DOF_TY).map(f64::from);
+  assert_eq!(x, Some(2.0));
+  assert_eq!(file.non_finite_count(), 1);
+  assert_eq!(FileReport::from(&file).blocks.len(), 1);
+  // flavours, selections and formatting
+  let flavour: Flavour = "mystran".parse().unwrap();
+  assert_eq!(flavour.solver, Some(Solver::Mystran));
+  let sel: IdSelection = "1-10,!5".parse().unwrap();
+  assert!(sel.contains(4) && !sel.contains(5));
+  let mut s = String::new();
+  fmt_f64(&mut s, 1.5, 0, 3, 2, true, true).unwrap();
+  assert_eq!(s, "1.500E+00");
+  assert_eq!(non_finite_name(f64::NEG_INFINITY), Some("-Inf"));
+}
+
+#[cfg(feature = "parser")]
+#[test]
+fn test_parser_schemas() {
+  // decoders build blocks with the columns their block types declare
+  for bt in BlockType::all() {
+    let dec = bt.init_decoder(Flavour::default());
+    assert_eq!(dec.block_type(), *bt);
+  }
+  let text = crate::samples::minimal_static();
+  let file = OnePassParser::parse_bufread(text.as_bytes()).unwrap();
+  assert!(file.all_blocks(false).count() > 0);
+  for block in file.all_blocks(false) {
+    let columns = block.block_type.columns();
+    assert!(block.col_indexes.keys().all(|c| columns.contains(c)));
+  }
+}
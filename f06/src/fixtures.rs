@@ -0,0 +1,53 @@
+//! Fixtures the crates' tests share. Like the stability checks, they're kept
+//! out of the API: f06 declares this module only for its tests, and the other
+//! crates' tests include it by path.
+
+/// A Simcenter-like output with NaNs and infinities in the displacements,
+/// written in the ways solvers print them, including an overflowed field.
+pub const NON_FINITE_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    NON-FINITE TEST                                     SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1.000000E+00   NaN            0.0            0.0            0.0            0.0
+             2      G      Infinity      -1.#INF00       0.0            0.0            0.0            **************
+             3      G     -1.000000E+00   0.0            0.0            0.0            0.0            0.0
+";
+
+/// A MYSTRAN-like table of the stresses in the plies of a 4-ply laminate,
+/// with von Mises stresses.
+pub const MYSTRAN_PLY_STRESSES_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+                                                                                                                  OUTPUT FOR SUBCASE          1
+ 
+                        S T R E S S E S   I N   L A Y E R E D   C O M P O S I T E   E L E M E N T S   ( Q U A D 4 )
+ 
+   Element  Ply  Stresses in fiber and matrix directions     Inter-laminar stresses   Principal stresses (zero shear)              Max        von Mises
+     ID      ID    Normal-1     Normal-2     Shear-12         Shear-1Z     Shear-2Z    Angle     Major        Minor           Shear
+       101    1  -3.81691E+01 -2.53869E+01 -5.09133E+00    -1.85378E-01 -1.53285E-01  -68.91 -2.34228E+01 -4.01332E+01   8.35518E+00  3.49372E+01
+       101    2   1.20000E+01  4.00000E+00  3.00000E+00     1.00000E-01  2.00000E-01   18.43  1.30000E+01  3.00000E+00   5.00000E+00  1.17898E+01
+       101    3   1.10000E+01  3.00000E+00  2.00000E+00     1.00000E-01  1.00000E-01   13.28  1.14721E+01  2.52786E+00   4.47214E+00  1.04403E+01
+       101    4  -3.60000E+01 -2.40000E+01 -4.00000E+00    -1.00000E-01 -1.00000E-01  -73.15 -2.27889E+01 -3.72111E+01   7.21110E+00  3.28634E+01
+ ------------------------------------------------------------------------------------------------------------------------------------------------------------
+";
+
+/// A Simcenter-like mechanical load vector and the equivalent nodal forces of
+/// a thermal load, in the same subcase.
+pub const THERMAL_LOAD_FIXTURE: &str = "
+0                                                                                                            SUBCASE 1
+                                                     L O A D   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+          1011      G      0.0            0.0           -4.687500E+02   0.0            0.0            0.0
+          1012      G      0.0            0.0           -9.375000E+02   0.0            0.0            0.0
+1    THERMAL TEST                                                           JANUARY  12, 2024  SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                                            SUBCASE 1
+                                          T H E R M A L   L O A D   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+          1011      G      1.250000E+02   0.0            0.0            0.0            0.0            0.0
+          1012      G     -1.250000E+02   0.0            0.0            0.0            0.0            0.0
+";
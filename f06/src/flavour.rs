@@ -2,6 +2,7 @@
 //! solvers and their varied configurtions/solution types.
 
 use std::fmt::Display;
+use std::str::FromStr;
 
 use serde::{Serialize, Deserialize};
 
@@ -37,6 +38,17 @@ impl Solver {
     };
   }
 
+  /// Returns the names this solver goes by in specs, lowest-common-denominator
+  /// form first. Matching ignores case, spaces, dashes and underscores.
+  pub const fn aliases(&self) -> &'static [&'static str] {
+    return match self {
+      Solver::Mystran => &["mystran"],
+      Solver::Simcenter => &[
+        "simcenter", "simcenter nastran", "scn", "nx", "nx nastran", "nxn"
+      ]
+    };
+  }
+
   /// Returns an array of "block ending" strings tht we should test for.
   pub const fn block_enders(&self) -> &'static [&'static str] {
     return match self {
@@ -50,10 +62,28 @@ impl Solver {
     };
   }
 
-  /// Returns the exceptions to block enders.
+  /// Returns the prefixes of the progress lines some debug settings make the
+  /// solver print, even in the middle of a block. These are kept from the
+  /// decoders. Others can be added with `OnePassParser::debug_line_prefixes`.
+  pub const fn debug_line_prefixes(&self) -> &'static [&'static str] {
+    return match self {
+      Solver::Mystran => &[
+        "STRESS DATA RECOVERY",
+        "STRAIN DATA RECOVERY"
+      ],
+      Solver::Simcenter => &[],
+    };
+  }
+
+  /// Returns the exceptions to block enders. MYSTRAN underlines load vectors
+  /// before their totals, which are part of the block.
   pub const fn ender_exceptions(&self) -> &'static [BlockType] {
     return match self {
-      Solver::Mystran => &[BlockType::GridPointForceBalance],
+      Solver::Mystran => &[
+        BlockType::GridPointForceBalance,
+        BlockType::AppliedForces,
+        BlockType::ThermalLoads
+      ],
       Solver::Simcenter => &[],
     };
   }
@@ -75,6 +105,28 @@ pub enum SolType {
   NonLinearStatic
 }
 
+impl FromStr for Solver {
+  type Err = FlavourParseError;
+
+  /// Parses a solver from one of its aliases (see `aliases()`) or its name,
+  /// ignoring a trailing version number, as in "mystran 15.2".
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut words = s.split_whitespace().collect::<Vec<_>>();
+    while words.len() > 1 && words.last().is_some_and(|w| is_version(w)) {
+      words.pop();
+    }
+    let key = spec_key(&words.join(" "));
+    return Self::all()
+      .iter()
+      .copied()
+      .find(|sv| {
+        return sv.aliases().iter().any(|a| spec_key(a) == key)
+          || spec_key(sv.name()) == key;
+      })
+      .ok_or_else(|| FlavourParseError::UnknownSolver(s.trim().to_owned()));
+  }
+}
+
 impl From<SolType> for usize {
   fn from(value: SolType) -> Self {
     return match value {
@@ -107,7 +159,55 @@ impl Display for SolType {
   }
 }
 
+impl FromStr for SolType {
+  type Err = FlavourParseError;
+
+  /// Parses a solution type from its number (with or without a "SOL"
+  /// prefix), one of its aliases (see `aliases()`) or its name.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let key = spec_key(s);
+    let number = key.strip_prefix("sol").unwrap_or(&key);
+    if let Ok(n) = number.parse::<usize>() {
+      if let Ok(sol) = Self::try_from(n) {
+        return Ok(sol);
+      }
+    }
+    return Self::all()
+      .iter()
+      .copied()
+      .find(|sol| {
+        return sol.aliases().iter().any(|a| spec_key(a) == key)
+          || spec_key(sol.name()) == key;
+      })
+      .ok_or_else(|| FlavourParseError::UnknownSolType(s.trim().to_owned()));
+  }
+}
+
 impl SolType {
+  /// Returns all known solution types.
+  pub const fn all() -> &'static [Self] {
+    return &[
+      Self::LinearStatic,
+      Self::Eigenvalue,
+      Self::LinearStaticDiffStiff,
+      Self::LinearBuckling,
+      Self::NonLinearStatic
+    ];
+  }
+
+  /// Returns the names this solution type goes by in specs, besides its
+  /// number. The first one is the "SOL n" form. Matching ignores case,
+  /// spaces, dashes and underscores.
+  pub const fn aliases(&self) -> &'static [&'static str] {
+    return match self {
+      SolType::LinearStatic => &["sol101", "static", "sestatic"],
+      SolType::Eigenvalue => &["sol103", "modes", "semodes", "normal modes"],
+      SolType::LinearStaticDiffStiff => &["sol104", "diffstiff"],
+      SolType::LinearBuckling => &["sol105", "buckling", "sebuckl"],
+      SolType::NonLinearStatic => &["sol106", "nlstatic", "nonlinear"],
+    };
+  }
+
   /// Returns a user-friendly display name for the solution.
   pub const fn name(&self) -> &'static str {
     return match self {
@@ -131,3 +231,377 @@ pub struct Flavour {
   /// The solution type that resulted in the file, if known.
   pub soltype: Option<SolType>
 }
+
+impl FromStr for Flavour {
+  type Err = FlavourParseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    return Self::parse_spec(s);
+  }
+}
+
+impl Flavour {
+  /// Parses a flavour spec: a solver, optionally followed by a slash and a
+  /// solution type, as in "simcenter/sol101" or "mystran". The solver can be
+  /// "auto" to leave it to be detected.
+  pub fn parse_spec(spec: &str) -> Result<Self, FlavourParseError> {
+    let (solver, soltype) = match spec.split_once('/') {
+      Some((solver, soltype)) => (solver, Some(soltype)),
+      None => (spec, None)
+    };
+    if solver.trim().is_empty() {
+      return Err(FlavourParseError::Empty);
+    }
+    let solver = if spec_key(solver) == spec_key(AUTO_SOLVER) {
+      None
+    } else {
+      Some(solver.parse::<Solver>()?)
+    };
+    let soltype = match soltype {
+      Some(st) if !st.trim().is_empty() => Some(st.parse::<SolType>()?),
+      _ => None
+    };
+    return Ok(Self { solver, soltype });
+  }
+
+  /// Returns the spec for this flavour, in the form `parse_spec()` takes.
+  pub fn spec(&self) -> String {
+    let solver = self.solver.map_or(AUTO_SOLVER, |sv| sv.aliases()[0]);
+    return match self.soltype {
+      Some(sol) => format!("{}/{}", solver, sol.aliases()[0]),
+      None => solver.to_owned()
+    };
+  }
+
+  /// Returns the combinations of solver and solution type that can be told
+  /// apart, for listing. Decoders only differ by solver for now, but the
+  /// solution type is still detected and reported for every solver.
+  pub fn supported_combinations() -> Vec<Self> {
+    return Solver::all()
+      .iter()
+      .flat_map(|sv| SolType::all().iter().map(|sol| Self {
+        solver: Some(*sv),
+        soltype: Some(*sol)
+      }))
+      .collect();
+  }
+
+  /// Returns the flavour described by a resolution.
+  pub fn from_resolution(res: &FlavourResolution) -> Self {
+    return Self {
+      solver: res.solver.value,
+      soltype: res.soltype.value
+    };
+  }
+}
+
+/// The solver part of a flavour spec that leaves the solver to be detected.
+const AUTO_SOLVER: &str = "auto";
+
+/// Reduces a name in a spec to what's compared when matching: lowercase,
+/// without spaces, dashes or underscores.
+fn spec_key(s: &str) -> String {
+  return s.chars()
+    .filter(|c| !c.is_whitespace() && *c != '-' && *c != '_')
+    .flat_map(char::to_lowercase)
+    .collect();
+}
+
+/// Returns true if a word looks like a version number, as in "15.2" or
+/// "v2306".
+fn is_version(word: &str) -> bool {
+  let digits = word.strip_prefix(['v', 'V']).unwrap_or(word);
+  return digits.starts_with(|c: char| c.is_ascii_digit())
+    && digits.chars().all(|c| c.is_ascii_digit() || c == '.');
+}
+
+/// Returns a comma-separated list of names.
+fn name_list<'a, I: IntoIterator<Item = &'a str>>(names: I) -> String {
+  return names.into_iter().collect::<Vec<_>>().join(", ");
+}
+
+/// An error found when parsing a solver, solution type or flavour spec.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FlavourParseError {
+  /// The spec had no solver.
+  Empty,
+  /// The solver wasn't recognised (contains it).
+  UnknownSolver(String),
+  /// The solution type wasn't recognised (contains it).
+  UnknownSolType(String)
+}
+
+impl Display for FlavourParseError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let solvers = name_list(
+      Solver::all().iter().flat_map(|sv| sv.aliases().iter().copied())
+        .chain([AUTO_SOLVER])
+    );
+    return match self {
+      Self::Empty => write!(
+        f,
+        "no solver given (expected SOLVER or SOLVER/SOL, with SOLVER one of \
+        {})",
+        solvers
+      ),
+      Self::UnknownSolver(s) => write!(
+        f,
+        "unknown solver \"{}\" (accepted: {})",
+        s,
+        solvers
+      ),
+      Self::UnknownSolType(s) => write!(
+        f,
+        "unknown solution type \"{}\" (accepted: a SOL number, or one of {})",
+        s,
+        name_list(SolType::all().iter().flat_map(|sol| {
+          return sol.aliases().iter().copied();
+        }))
+      ),
+    };
+  }
+}
+
+impl std::error::Error for FlavourParseError {}
+
+/// Where a piece of knowledge about the flavour came from.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FlavourSource {
+  /// It was supplied by the user (e.g. a hint to the parser).
+  UserSupplied,
+  /// It was read off a banner or an echoed line.
+  Banner,
+  /// It was inferred from the layout of the file.
+  Heuristic {
+    /// The line that gave it away.
+    line: usize
+  },
+  /// Nothing was found, so it's the default (or the one fallen back on).
+  Default
+}
+
+impl FlavourSource {
+  /// How much this source is trusted, higher being more: whatever the user
+  /// said beats a banner, which beats a heuristic, which beats a default.
+  pub const fn rank(&self) -> u8 {
+    return match self {
+      Self::UserSupplied => 3,
+      Self::Banner => 2,
+      Self::Heuristic { .. } => 1,
+      Self::Default => 0
+    };
+  }
+}
+
+impl Display for FlavourSource {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::UserSupplied => write!(f, "user-supplied"),
+      Self::Banner => write!(f, "banner"),
+      Self::Heuristic { line } => write!(f, "heuristic (line {})", line),
+      Self::Default => write!(f, "default"),
+    };
+  }
+}
+
+/// A single piece of evidence about the value of a flavour field.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FlavourEvidence<T> {
+  /// Where it came from.
+  pub source: FlavourSource,
+  /// The value it points to.
+  pub value: T,
+  /// The line it was found in, if any.
+  pub line: Option<usize>
+}
+
+/// What happened when a piece of evidence was offered to a field.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EvidenceOutcome {
+  /// The field had no value, now it does.
+  Resolved,
+  /// The evidence agrees with the current value.
+  Agrees,
+  /// The evidence disagrees with the current value and was recorded as a
+  /// conflict.
+  Conflicts,
+  /// The evidence disagrees with the current value, but comes from a more
+  /// trusted source, so it replaced it; the old value was recorded as a
+  /// conflict.
+  Overridden
+}
+
+/// How a single flavour field was resolved, and what else was seen about it.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FieldResolution<T> {
+  /// The value, if known.
+  pub value: Option<T>,
+  /// Where the value came from.
+  pub source: FlavourSource,
+  /// The line where the value came from, if any.
+  pub line: Option<usize>,
+  /// Evidence that contradicted the value.
+  pub conflicts: Vec<FlavourEvidence<T>>
+}
+
+impl<T> Default for FieldResolution<T> {
+  fn default() -> Self {
+    return Self {
+      value: None,
+      source: FlavourSource::Default,
+      line: None,
+      conflicts: Vec::new()
+    };
+  }
+}
+
+impl<T: Copy + PartialEq> FieldResolution<T> {
+  /// Offers a piece of evidence. The first one to arrive sets the value.
+  /// After that, the more trusted of two disagreeing sources (see
+  /// `FlavourSource::rank`) wins, and the other one is recorded as a
+  /// conflict; on a tie, the value already there stays.
+  pub fn offer(
+    &mut self,
+    source: FlavourSource,
+    value: T,
+    line: Option<usize>
+  ) -> EvidenceOutcome {
+    let stronger = source.rank() > self.source.rank();
+    return match self.value {
+      None => {
+        self.value = Some(value);
+        self.source = source;
+        self.line = line;
+        EvidenceOutcome::Resolved
+      },
+      Some(v) if v == value => {
+        if stronger {
+          self.source = source;
+          self.line = line;
+        }
+        EvidenceOutcome::Agrees
+      },
+      Some(v) if stronger => {
+        self.conflicts.push(FlavourEvidence {
+          source: self.source,
+          value: v,
+          line: self.line
+        });
+        self.value = Some(value);
+        self.source = source;
+        self.line = line;
+        EvidenceOutcome::Overridden
+      },
+      Some(_) => {
+        self.conflicts.push(FlavourEvidence { source, value, line });
+        EvidenceOutcome::Conflicts
+      }
+    };
+  }
+}
+
+impl<T: Display> Display for FieldResolution<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self.value {
+      Some(ref v) => write!(f, "{} ({}", v, self.source)?,
+      None => write!(f, "unknown ({}", self.source)?
+    };
+    if let Some(line) = self.line {
+      write!(f, ", line {}", line)?;
+    }
+    write!(f, ")")?;
+    if !self.conflicts.is_empty() {
+      write!(f, ", contradicted by ")?;
+      for (i, ev) in self.conflicts.iter().enumerate() {
+        if i > 0 {
+          write!(f, ", ")?;
+        }
+        write!(f, "{} ({}", ev.value, ev.source)?;
+        if let Some(line) = ev.line {
+          write!(f, ", line {}", line)?;
+        }
+        write!(f, ")")?;
+      }
+    }
+    return Ok(());
+  }
+}
+
+/// Records how each field of the flavour was resolved.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct FlavourResolution {
+  /// The solver.
+  pub solver: FieldResolution<Solver>,
+  /// The solution type.
+  pub soltype: FieldResolution<SolType>
+}
+
+impl FlavourResolution {
+  /// Returns the resolved flavour.
+  pub fn flavour(&self) -> Flavour {
+    return Flavour::from_resolution(self);
+  }
+
+  /// Returns whether any field saw conflicting evidence.
+  pub fn has_conflicts(&self) -> bool {
+    return !self.solver.conflicts.is_empty()
+      || !self.soltype.conflicts.is_empty();
+  }
+}
+
+/// How the flavour of each file in a batch is resolved: detected from the
+/// file itself unless forced, falling back on a default for what the file
+/// doesn't give away, and optionally required to be something in particular.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct FlavourPolicy {
+  /// The flavour to fall back on, for what isn't detected.
+  pub default: Option<Flavour>,
+  /// The flavour to use regardless of what's detected (which is reported as
+  /// a conflict if it disagrees).
+  pub forced: Option<Flavour>,
+  /// The flavour files must resolve to; those that don't are rejected. Only
+  /// the fields it has are checked.
+  pub required: Option<Flavour>
+}
+
+impl FlavourPolicy {
+  /// Checks the flavour a file was resolved as against the required one.
+  pub fn check(&self, res: &FlavourResolution) -> Result<(), FlavourMismatch> {
+    let Some(required) = self.required else {
+      return Ok(());
+    };
+    let resolved = res.flavour();
+    let solver_ok = required.solver.is_none()
+      || required.solver == resolved.solver;
+    let soltype_ok = required.soltype.is_none()
+      || required.soltype == resolved.soltype;
+    if solver_ok && soltype_ok {
+      return Ok(());
+    }
+    return Err(FlavourMismatch { required, resolved });
+  }
+}
+
+/// A file resolved as some flavour other than the required one.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FlavourMismatch {
+  /// The flavour that was required.
+  pub required: Flavour,
+  /// The flavour the file was resolved as.
+  pub resolved: Flavour
+}
+
+impl Display for FlavourMismatch {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(
+      f,
+      "the flavour was resolved as {}, but {} is required",
+      self.resolved.spec(),
+      self.required.spec()
+    );
+  }
+}
+
+impl std::error::Error for FlavourMismatch {}
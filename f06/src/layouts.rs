@@ -0,0 +1,657 @@
+//! This module implements fixed column layouts declared at runtime, for the
+//! tables of solver variants we don't know. A layout says which header starts
+//! the table, which standard block type it holds, where the IDs of each row
+//! are and which characters of a line hold each column; the parser then
+//! decodes matching tables with a generic decoder.
+//!
+//! Layouts are read from a sidecar TOML file with a `[[layout]]` table per
+//! table, and handed to the parser with `OnePassParser::custom_layouts`.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use log::warn;
+use nalgebra::DMatrix;
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+use crate::util::*;
+
+/// A range of characters within a line: zero-based, the start included and
+/// the end not, e.g. `[0, 8]` for the first eight characters.
+pub type CharRange = [usize; 2];
+
+/// A column of a custom layout.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LayoutColumn {
+  /// The name of the column, as it's displayed (e.g. "TX" for a DOF).
+  pub name: String,
+  /// Where its values are in each line.
+  pub range: CharRange
+}
+
+/// The layout of a table printed by an unknown solver variant.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustomLayout {
+  /// The header that starts the table, spaced out or not, with the lines of
+  /// a wrapped header joined by a space. Matched case-insensitively.
+  pub header: String,
+  /// The block type the table holds.
+  pub block_type: BlockType,
+  /// Where the grid point or element ID of each row is.
+  pub id: CharRange,
+  /// Where the point within the element is ("CENTER", "CEN/4" or a corner
+  /// grid point ID), for blocks whose rows are for points in elements.
+  #[serde(default)]
+  pub point: Option<CharRange>,
+  /// Where the side of the element is ("TOP", "BOTTOM", "Z1" or "Z2"), for
+  /// blocks whose rows are for sides of points in elements.
+  #[serde(default)]
+  pub side: Option<CharRange>,
+  /// Ranges of characters that hold nothing we want (e.g. a point type).
+  #[serde(default)]
+  pub ignore: Vec<CharRange>,
+  /// The columns, in the order they're printed.
+  #[serde(rename = "column")]
+  pub columns: Vec<LayoutColumn>
+}
+
+/// How the row indexes of a custom layout are made.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum RowRule {
+  /// From a grid point ID.
+  Grid,
+  /// From an element ID.
+  Element,
+  /// From an element ID and a point within it.
+  Point,
+  /// From an element ID, a point within it and a side.
+  SidedPoint
+}
+
+impl RowRule {
+  /// Returns the rule for the row indexes of a block type, if it's one we
+  /// can make from fixed fields.
+  fn of(block_type: BlockType) -> Option<Self> {
+    return match block_type.schema().row_index {
+      GridPointRef::INDEX_NAME => Some(Self::Grid),
+      ElementRef::INDEX_NAME => Some(Self::Element),
+      PointInElement::INDEX_NAME => Some(Self::Point),
+      ElementSidedPoint::INDEX_NAME => Some(Self::SidedPoint),
+      _ => None
+    };
+  }
+}
+
+/// The layout of a TOML file of layouts: a `[[layout]]` table per layout.
+#[derive(Clone, Debug, Deserialize)]
+struct CustomLayoutsToml {
+  /// The layouts.
+  layout: Vec<CustomLayout>
+}
+
+/// A set of custom layouts, checked for consistency.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustomLayouts {
+  /// The layouts, with their headers normalised.
+  layouts: Vec<CustomLayout>
+}
+
+/// An error in a custom layout.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LayoutError {
+  /// The TOML was malformed.
+  Toml(toml::de::Error),
+  /// A layout has an empty header.
+  EmptyHeader,
+  /// A layout has no columns.
+  NoColumns {
+    /// The header of the layout.
+    header: String
+  },
+  /// A range is empty or ends before it starts.
+  BadRange {
+    /// The header of the layout.
+    header: String,
+    /// What the range is for.
+    field: String,
+    /// The range.
+    range: CharRange
+  },
+  /// Two ranges overlap.
+  Overlap {
+    /// The header of the layout.
+    header: String,
+    /// What the first range is for.
+    first: String,
+    /// What the second range is for.
+    second: String
+  },
+  /// A column isn't one of the block type's.
+  UnknownColumn {
+    /// The header of the layout.
+    header: String,
+    /// The name of the column.
+    name: String,
+    /// The block type.
+    block_type: BlockType
+  },
+  /// A column is declared twice.
+  DuplicateColumn {
+    /// The header of the layout.
+    header: String,
+    /// The name of the column.
+    name: String
+  },
+  /// The rows of the block type can't be made from fixed fields.
+  UnsupportedRows {
+    /// The header of the layout.
+    header: String,
+    /// The block type.
+    block_type: BlockType
+  },
+  /// A field the block type's rows need wasn't given.
+  MissingField {
+    /// The header of the layout.
+    header: String,
+    /// The name of the field.
+    field: &'static str
+  },
+  /// A field was given that the block type's rows don't have.
+  UnexpectedField {
+    /// The header of the layout.
+    header: String,
+    /// The name of the field.
+    field: &'static str
+  }
+}
+
+impl From<toml::de::Error> for LayoutError {
+  fn from(e: toml::de::Error) -> Self {
+    return Self::Toml(e);
+  }
+}
+
+impl Display for LayoutError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::Toml(e) => write!(f, "TOML error: {}", e),
+      Self::EmptyHeader => write!(f, "a layout has an empty header"),
+      Self::NoColumns { header } => {
+        write!(f, "layout \"{}\" has no columns", header)
+      },
+      Self::BadRange { header, field, range } => write!(
+        f,
+        "range [{}, {}] for {} in layout \"{}\" is empty",
+        range[0],
+        range[1],
+        field,
+        header
+      ),
+      Self::Overlap { header, first, second } => write!(
+        f,
+        "ranges for {} and {} in layout \"{}\" overlap",
+        first,
+        second,
+        header
+      ),
+      Self::UnknownColumn { header, name, block_type } => {
+        let known = block_type.columns()
+          .iter()
+          .map(NasIndex::to_string)
+          .collect::<Vec<_>>();
+        write!(
+          f,
+          "column \"{}\" in layout \"{}\" isn't one of {}, which are: {}",
+          name,
+          header,
+          block_type,
+          known.join(", ")
+        )
+      },
+      Self::DuplicateColumn { header, name } => write!(
+        f,
+        "column \"{}\" is declared twice in layout \"{}\"",
+        name,
+        header
+      ),
+      Self::UnsupportedRows { header, block_type } => write!(
+        f,
+        "the rows of {} (layout \"{}\") can't be read from fixed fields",
+        block_type,
+        header
+      ),
+      Self::MissingField { header, field } => write!(
+        f,
+        "layout \"{}\" needs a \"{}\" range for its block type",
+        header,
+        field
+      ),
+      Self::UnexpectedField { header, field } => write!(
+        f,
+        "layout \"{}\" has a \"{}\" range its block type doesn't use",
+        header,
+        field
+      ),
+    };
+  }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// Normalises a header, or part of one, for matching: spaced-out words are
+/// joined up, and runs of blanks become a single space.
+fn normalise_header(text: &str) -> String {
+  // unspacing only starts after some indentation, like in the files
+  let unspaced = unspace(&format!("    {}", text.trim()))
+    .filter(|s| !s.is_empty());
+  return unspaced.unwrap_or_else(|| {
+    return text.split_whitespace().collect::<Vec<_>>().join(" ");
+  }).to_uppercase();
+}
+
+impl CustomLayout {
+  /// Returns the column index for a column name, if it's one of the block
+  /// type's.
+  fn resolve_column(&self, name: &str) -> Option<NasIndex> {
+    return self.block_type.columns()
+      .into_iter()
+      .find(|c| c.to_string().eq_ignore_ascii_case(name.trim()));
+  }
+
+  /// Returns every range in this layout, with what it's for.
+  fn ranges(&self) -> Vec<(String, CharRange)> {
+    let mut ranges = vec![("the ID".to_owned(), self.id)];
+    ranges.extend(self.point.map(|r| ("the point".to_owned(), r)));
+    ranges.extend(self.side.map(|r| ("the side".to_owned(), r)));
+    ranges.extend(self.ignore.iter().map(|r| {
+      return ("an ignored range".to_owned(), *r);
+    }));
+    ranges.extend(self.columns.iter().map(|c| {
+      return (format!("column \"{}\"", c.name), c.range);
+    }));
+    return ranges;
+  }
+
+  /// Checks this layout for consistency, normalising its header.
+  fn validate(&mut self) -> Result<(), LayoutError> {
+    self.header = normalise_header(&self.header);
+    let header = self.header.clone();
+    if header.is_empty() {
+      return Err(LayoutError::EmptyHeader);
+    }
+    if self.columns.is_empty() {
+      return Err(LayoutError::NoColumns { header });
+    }
+    let rule = RowRule::of(self.block_type).ok_or_else(|| {
+      return LayoutError::UnsupportedRows {
+        header: header.clone(),
+        block_type: self.block_type
+      };
+    })?;
+    let needs_point = matches!(rule, RowRule::Point | RowRule::SidedPoint);
+    let needs_side = rule == RowRule::SidedPoint;
+    for (field, needed, given) in [
+      ("point", needs_point, self.point.is_some()),
+      ("side", needs_side, self.side.is_some())
+    ] {
+      if needed && !given {
+        return Err(LayoutError::MissingField { header, field });
+      } else if given && !needed {
+        return Err(LayoutError::UnexpectedField { header, field });
+      }
+    }
+    let mut seen: Vec<NasIndex> = Vec::new();
+    for col in self.columns.iter() {
+      let name = col.name.clone();
+      let ix = self.resolve_column(&col.name).ok_or_else(|| {
+        return LayoutError::UnknownColumn {
+          header: header.clone(),
+          name: name.clone(),
+          block_type: self.block_type
+        };
+      })?;
+      if seen.contains(&ix) {
+        return Err(LayoutError::DuplicateColumn { header, name });
+      }
+      seen.push(ix);
+    }
+    let ranges = self.ranges();
+    for (i, (field, range)) in ranges.iter().enumerate() {
+      if range[0] >= range[1] {
+        return Err(LayoutError::BadRange {
+          header,
+          field: field.clone(),
+          range: *range
+        });
+      }
+      let overlapping = ranges[..i].iter()
+        .find(|(_, r)| r[0] < range[1] && range[0] < r[1]);
+      if let Some((first, _)) = overlapping {
+        return Err(LayoutError::Overlap {
+          header,
+          first: first.clone(),
+          second: field.clone()
+        });
+      }
+    }
+    return Ok(());
+  }
+
+  /// Returns a decoder for the tables with this layout.
+  pub fn decoder(&self) -> Box<dyn OpaqueDecoder> {
+    return Box::new(LayoutDecoder::new(self.clone()));
+  }
+}
+
+impl CustomLayouts {
+  /// Instantiates an empty set of layouts.
+  pub fn new() -> Self {
+    return Self::default();
+  }
+
+  /// Adds a layout. Errs if it's inconsistent.
+  pub fn insert(
+    &mut self,
+    mut layout: CustomLayout
+  ) -> Result<(), LayoutError> {
+    layout.validate()?;
+    self.layouts.push(layout);
+    return Ok(());
+  }
+
+  /// Reads layouts from TOML with a `[[layout]]` table per layout, each with
+  /// `header`, `block_type` and `id` keys (and `point`, `side` and `ignore`
+  /// where needed), and a `[[layout.column]]` table per column, with `name`
+  /// and `range` keys.
+  pub fn parse(text: &str) -> Result<Self, LayoutError> {
+    let parsed: CustomLayoutsToml = toml::from_str(text)?;
+    let mut layouts = Self::new();
+    for layout in parsed.layout {
+      layouts.insert(layout)?;
+    }
+    return Ok(layouts);
+  }
+
+  /// Returns true if there are no layouts.
+  pub fn is_empty(&self) -> bool {
+    return self.layouts.is_empty();
+  }
+
+  /// Returns the layouts.
+  pub fn layouts(&self) -> &[CustomLayout] {
+    return &self.layouts;
+  }
+
+  /// Returns a line normalised for matching, if it's part of the header of
+  /// any layout (for wrapped headers) or has one in it.
+  pub(crate) fn header_part(&self, line: &str) -> Option<String> {
+    if self.layouts.is_empty() {
+      return None;
+    }
+    let part = normalise_header(line);
+    // parts too short could be anything
+    if part.len() < 4 {
+      return None;
+    }
+    return self.layouts.iter()
+      .any(|l| l.header.contains(&part) || part.contains(&l.header))
+      .then_some(part);
+  }
+
+  /// Returns the layout for a full (normalised) header, if any. The longest
+  /// matching header wins.
+  pub fn find(&self, header: &str) -> Option<&CustomLayout> {
+    return self.layouts.iter()
+      .filter(|l| header.contains(&l.header))
+      .max_by_key(|l| l.header.len());
+  }
+}
+
+/// Takes a range of characters from a line, trimmed. Parts of it past the
+/// end of the line are blank.
+fn take_range(chars: &[char], range: CharRange) -> String {
+  let end = range[1].min(chars.len());
+  let start = range[0].min(end);
+  return chars[start..end].iter().collect::<String>().trim().to_owned();
+}
+
+/// Decodes a value from a column. Stricter than the decoders for known
+/// blocks, so that a misplaced range doesn't silently read part of a number.
+fn decode_value(text: &str) -> Option<f64> {
+  return text.parse::<f64>().ok().or_else(|| decode_non_finite(text));
+}
+
+/// Decodes a point within an element.
+fn decode_point(text: &str) -> Option<ElementPoint> {
+  let upper = text.to_ascii_uppercase();
+  let name = upper.split('/').next().unwrap_or_default();
+  return match name {
+    "CEN" | "CENTER" | "CENTRE" | "CENTROID" => Some(ElementPoint::Centroid),
+    _ => name.parse().ok().map(|gid| ElementPoint::Corner(GridPointRef { gid }))
+  };
+}
+
+/// The generic decoder for tables with a custom layout. Lines that don't fit
+/// the layout are warned about and skipped.
+pub(crate) struct LayoutDecoder {
+  /// The layout.
+  layout: CustomLayout,
+  /// How the row indexes are made.
+  rule: RowRule,
+  /// The column indexes, in the order of the layout's columns.
+  columns: Vec<NasIndex>,
+  /// Every range of characters the layout reads or ignores.
+  ranges: Vec<CharRange>,
+  /// The data, row after row.
+  values: Vec<f64>,
+  /// The row indexes.
+  row_indexes: BTreeMap<NasIndex, usize>,
+  /// The line each row was read from, where known.
+  row_lines: BTreeMap<NasIndex, usize>,
+  /// The last row index inserted.
+  last: Option<NasIndex>,
+  /// How many lines didn't fit the layout.
+  mismatches: usize
+}
+
+impl LayoutDecoder {
+  /// Makes a decoder for a layout, which must have been validated.
+  fn new(layout: CustomLayout) -> Self {
+    let columns = layout.columns.iter()
+      .map(|c| layout.resolve_column(&c.name).expect("unvalidated layout"))
+      .collect();
+    let rule = RowRule::of(layout.block_type).expect("unvalidated layout");
+    let ranges = layout.ranges().into_iter().map(|(_, r)| r).collect();
+    return Self {
+      layout,
+      rule,
+      columns,
+      ranges,
+      values: Vec::new(),
+      row_indexes: BTreeMap::new(),
+      row_lines: BTreeMap::new(),
+      last: None,
+      mismatches: 0
+    };
+  }
+
+  /// Makes the row index for a line from its ID, or says why it can't.
+  fn row_index(&self, chars: &[char], id: usize) -> Result<NasIndex, String> {
+    let etype = self.layout.block_type.elem_type();
+    let element = ElementRef { eid: id, etype };
+    let point = || {
+      let range = self.layout.point.unwrap_or_default();
+      let text = take_range(chars, range);
+      return decode_point(&text).ok_or_else(|| {
+        return format!("the point at {:?} holds \"{}\"", range, text);
+      });
+    };
+    let side = || {
+      let range = self.layout.side.unwrap_or_default();
+      let text = take_range(chars, range);
+      return decode_side(&text).ok_or_else(|| {
+        return format!("the side at {:?} holds \"{}\"", range, text);
+      });
+    };
+    return Ok(match self.rule {
+      RowRule::Grid => GridPointRef { gid: id }.into(),
+      RowRule::Element => element.into(),
+      RowRule::Point => PointInElement { element, point: point()? }.into(),
+      RowRule::SidedPoint => ElementSidedPoint {
+        element,
+        point: point()?,
+        side: side()?
+      }.into()
+    });
+  }
+
+  /// Returns the stretches of text in a line outside of every range of the
+  /// layout, with where they start.
+  fn stray_text(&self, chars: &[char]) -> Vec<(usize, String)> {
+    let mut stray: Vec<(usize, String)> = Vec::new();
+    let mut open = false;
+    for (i, ch) in chars.iter().enumerate() {
+      let covered = self.ranges.iter().any(|r| r[0] <= i && i < r[1]);
+      if covered || ch.is_whitespace() {
+        open = false;
+      } else if open {
+        stray.last_mut().expect("open stretch").1.push(*ch);
+      } else {
+        open = true;
+        stray.push((i, ch.to_string()));
+      }
+    }
+    return stray;
+  }
+
+  /// Decodes a data line into its row index and values, or says why it
+  /// doesn't fit the layout.
+  fn decode(
+    &self,
+    chars: &[char],
+    id: &str
+  ) -> Result<(NasIndex, Vec<f64>), Vec<String>> {
+    let mut problems: Vec<String> = Vec::new();
+    let index = match id.parse::<usize>() {
+      Ok(id) => self.row_index(chars, id).map_err(|p| problems.push(p)).ok(),
+      Err(_) => {
+        let range = self.layout.id;
+        problems.push(format!("the ID at {:?} holds \"{}\"", range, id));
+        None
+      }
+    };
+    let mut values = Vec::with_capacity(self.columns.len());
+    for col in self.layout.columns.iter() {
+      let text = take_range(chars, col.range);
+      match decode_value(&text) {
+        Some(x) => values.push(x),
+        None => problems.push(format!(
+          "column \"{}\" at {:?} holds \"{}\"",
+          col.name,
+          col.range,
+          text
+        ))
+      };
+    }
+    for (start, text) in self.stray_text(chars) {
+      problems.push(format!(
+        "\"{}\" at character {} is outside of every range",
+        text,
+        start
+      ));
+    }
+    if let Some(ix) = index.filter(|ix| self.row_indexes.contains_key(ix)) {
+      problems.push(format!("row {} was already read", ix));
+    }
+    return match index {
+      Some(ix) if problems.is_empty() => Ok((ix, values)),
+      _ => Err(problems)
+    };
+  }
+}
+
+impl OpaqueDecoder for LayoutDecoder {
+  fn block_type(&self) -> BlockType {
+    return self.layout.block_type;
+  }
+
+  fn consume(&mut self, line: &str, line_no: usize) -> LineResponse {
+    let chars = line.chars().collect::<Vec<_>>();
+    let id = take_range(&chars, self.layout.id);
+    // headings, column titles and blank lines have no ID at all
+    if !id.chars().any(|c| c.is_ascii_digit()) {
+      return LineResponse::Useless;
+    }
+    return match self.decode(&chars, &id) {
+      Ok((ix, values)) => {
+        self.row_indexes.insert(ix, self.row_indexes.len());
+        self.row_lines.insert(ix, line_no);
+        self.values.extend(values);
+        self.last = Some(ix);
+        LineResponse::Data
+      },
+      Err(problems) => {
+        self.mismatches += 1;
+        warn!(
+          "Line {} doesn't fit the layout for \"{}\", skipping it: {}.",
+          line_no,
+          self.layout.header,
+          problems.join("; ")
+        );
+        LineResponse::Useless
+      }
+    };
+  }
+
+  fn good_header(&mut self, _header: &str) -> bool {
+    return true;
+  }
+
+  fn hint_last(&mut self, _last: NasIndex) {}
+
+  fn last_index(&self) -> Option<NasIndex> {
+    return self.last;
+  }
+
+  fn finalise(
+    self: Box<Self>,
+    subcase: usize,
+    line_range: Option<(usize, usize)>
+  ) -> FinalBlock {
+    let nrows = self.row_indexes.len();
+    let ncols = self.columns.len();
+    let data = (nrows > 0).then(|| {
+      return FinalDMat::from(
+        DMatrix::from_row_slice(nrows, ncols, &self.values)
+      );
+    });
+    let mut metadata = BTreeMap::new();
+    if self.mismatches > 0 {
+      metadata.insert(
+        META_LAYOUT_MISMATCHES.to_owned(),
+        self.mismatches.to_string()
+      );
+    }
+    return FinalBlock {
+      line_range,
+      byte_range: None,
+      block_type: self.layout.block_type,
+      subcase,
+      row_indexes: self.row_indexes,
+      col_indexes: self.columns.into_iter()
+        .enumerate()
+        .map(|(i, c)| (c, i))
+        .collect(),
+      data,
+      print_precision: None,
+      metadata,
+      row_lines: self.row_lines,
+      uid: None,
+      provenance: MergeProvenance::default(),
+      line_counts: LineCounts::default(),
+      step: None
+    };
+  }
+}
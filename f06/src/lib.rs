@@ -8,33 +8,98 @@
 //! However, the code is modular -- one can easily expand the library to
 //! support parsing different "flavours" of text output, different solvers,
 //! more elements/formulations, etc.
+//!
+//! The parser lives behind the `parser` feature, which is on by default. With
+//! `default-features = false`, only the data model is built: blocks and their
+//! indexes, elements, geometry, flavours, diagnostics and the formatting
+//! utilities. That subset doesn't touch the filesystem (the few things it
+//! reads, like superelement ID ranges, come from strings, and their
+//! `from_file` helpers need `parser`), so it builds for targets like
+//! `wasm32-unknown-unknown`.
 
 #![warn(missing_docs)] // almost sure this is default but whatever
 #![warn(clippy::missing_docs_in_private_items)] // sue me
 #![allow(clippy::needless_return)] // i'll never forgive rust for this
 #![allow(dead_code)] // temporary
 
+#[cfg(feature = "async")]
+pub mod async_parser;
+#[cfg(feature = "parser")]
+pub mod batch;
 pub mod blocks;
+#[cfg(feature = "parser")]
+pub mod cli;
+pub mod cross_validate;
+pub mod diagnostics;
 pub mod elements;
 pub mod f06file;
 pub mod flavour;
 pub mod geometry;
+#[cfg(feature = "parser")]
+pub mod layouts;
+#[cfg(feature = "parser")]
+pub mod loader;
+pub mod modal;
+#[cfg(feature = "parser")]
 pub mod parser;
+pub mod postprocess;
+pub mod report;
+#[cfg(any(test, feature = "samples"))]
+pub mod samples;
+pub mod sniff;
+pub mod superelements;
+#[cfg(feature = "parser")]
+pub mod testing;
 pub mod util;
 
 /// Prelude module; includes commonly-used public exports.
 pub mod prelude {
+  #[cfg(feature = "async")]
+  pub use crate::async_parser::*;
+  #[cfg(feature = "parser")]
+  pub use crate::batch::*;
   pub use crate::blocks::*;
   pub use crate::blocks::compare::*;
+  pub use crate::blocks::dimensions::*;
   pub use crate::blocks::indexing::*;
+  pub use crate::blocks::table::*;
   pub use crate::blocks::types::*;
+  pub use crate::blocks::visitor::*;
+  #[cfg(feature = "parser")]
+  pub use crate::cli::*;
+  pub use crate::cross_validate::*;
+  pub use crate::diagnostics::*;
   pub use crate::elements::*;
   pub use crate::f06file::*;
+  pub use crate::f06file::accessors::*;
+  pub use crate::f06file::axis::*;
   pub use crate::f06file::diff::*;
+  pub use crate::f06file::fingerprint::*;
+  pub use crate::f06file::invariants::*;
+  pub use crate::f06file::pairing::*;
+  pub use crate::f06file::requests::*;
+  pub use crate::f06file::runstats::*;
+  pub use crate::f06file::soldiags::*;
   pub use crate::flavour::*;
   pub use crate::geometry::*;
+  #[cfg(feature = "parser")]
+  pub use crate::layouts::*;
+  #[cfg(feature = "parser")]
+  pub use crate::loader::*;
+  pub use crate::modal::*;
+  #[cfg(feature = "parser")]
   pub use crate::parser::*;
+  pub use crate::postprocess::*;
+  pub use crate::report::*;
+  pub use crate::sniff::*;
+  pub use crate::superelements::*;
 }
 
 #[cfg(test)]
+mod feature_tests;
+#[cfg(all(test, feature = "parser"))]
+pub(crate) mod fixtures;
+#[cfg(test)]
+pub(crate) mod stability;
+#[cfg(all(test, feature = "parser"))]
 mod tests;
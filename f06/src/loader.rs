@@ -0,0 +1,314 @@
+//! This module implements an index of where each block of a file is, and a
+//! loader that uses it to decode blocks on demand, one at a time, without
+//! parsing the whole file again.
+//!
+//! Decoders only depend on the flavour at the time the block began, the
+//! header and the last index of the previous block of the same type (to
+//! catch paging), so all that is kept in the index. Lines the parser took
+//! away from a decoder in the middle of a block (e.g. warnings) are kept too,
+//! so they are skipped again.
+
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+use crate::util::*;
+
+/// Where a block is in a file, and what it takes to decode it again.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct IndexEntry {
+  /// The subcase and type.
+  pub block_ref: BlockRef,
+  /// The position among the blocks with the same subcase and type.
+  pub nth: usize,
+  /// The line range (half-open, 1-based).
+  pub line_range: (usize, usize),
+  /// The byte range (half-open) of the lines in the line range.
+  pub byte_range: (u64, u64),
+  /// The flavour as it was known when the block began.
+  pub flavour: Flavour,
+  /// The header that began the block.
+  pub header: String,
+  /// The last index of the previous block of the same type, if any.
+  pub hint: Option<NasIndex>,
+  /// The time step or frequency the block was printed for, if any.
+  #[serde(default)]
+  pub step: Option<Step>,
+  /// Lines within the range that weren't passed to the decoder, in order.
+  pub skipped: Vec<usize>
+}
+
+/// An index of the blocks in a file, as found by a full parse.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FileIndex {
+  /// The flavour of the file.
+  pub flavour: Flavour,
+  /// The number of lines in the file.
+  pub line_count: usize,
+  /// The first line read with decimal commas, if they were in use.
+  #[serde(default)]
+  pub decimal_comma_since: Option<usize>,
+  /// The prefixes of debug lines skipped besides the solver's own.
+  #[serde(default)]
+  pub debug_prefixes: Vec<String>,
+  /// The blocks, in the order they were finished.
+  pub entries: Vec<IndexEntry>
+}
+
+impl FileIndex {
+  /// Parses something, returning the parsed file and its index.
+  pub fn build<R: BufRead>(reader: R) -> io::Result<(F06File, Self)> {
+    let mut parser = OnePassParser::new();
+    parser.record_index();
+    parser.feed_bufread(reader)?;
+    return Ok(parser.finish_indexed());
+  }
+
+  /// Parses a file, returning it and its index.
+  pub fn build_file<P: AsRef<Path>>(path: P) -> io::Result<(F06File, Self)> {
+    let file = File::open(path.as_ref())?;
+    let (mut f06, index) = Self::build(BufReader::new(file))?;
+    f06.filename = path.as_ref().file_name()
+      .and_then(|s| s.to_str())
+      .map(String::from);
+    return Ok((f06, index));
+  }
+
+  /// Finds the entry for a block.
+  pub fn find(&self, block_ref: BlockRef, nth: usize) -> Option<&IndexEntry> {
+    return self.entries.iter()
+      .find(|e| e.block_ref == block_ref && e.nth == nth);
+  }
+}
+
+/// What the parser records about the block being decoded, for the index.
+#[derive(Clone, Debug)]
+pub(crate) struct BlockRecord {
+  /// The flavour when the block began.
+  pub(crate) flavour: Flavour,
+  /// The header that began the block.
+  pub(crate) header: String,
+  /// The hint given to the decoder.
+  pub(crate) hint: Option<NasIndex>,
+  /// The time step or frequency when the block began.
+  pub(crate) step: Option<Step>,
+  /// The lines not passed to the decoder so far.
+  pub(crate) skipped: Vec<usize>
+}
+
+impl BlockRecord {
+  /// Turns this into an index entry, once the block is finished.
+  pub(crate) fn into_entry(
+    self,
+    block_ref: BlockRef,
+    nth: usize,
+    line_range: (usize, usize),
+    byte_range: (u64, u64)
+  ) -> IndexEntry {
+    let skipped = self.skipped.into_iter()
+      .filter(|l| *l >= line_range.0 && *l < line_range.1)
+      .collect();
+    return IndexEntry {
+      block_ref,
+      nth,
+      line_range,
+      byte_range,
+      flavour: self.flavour,
+      header: self.header,
+      hint: self.hint,
+      step: self.step,
+      skipped
+    };
+  }
+}
+
+/// An error while loading a block.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ParseError {
+  /// Reading the file failed.
+  Io(io::Error),
+  /// The block isn't in the index.
+  NotIndexed {
+    /// The subcase and type.
+    block_ref: BlockRef,
+    /// The position among the blocks with the same subcase and type.
+    nth: usize
+  },
+  /// The solver wasn't known when the block began.
+  NoSolver,
+  /// The decoder didn't accept the header.
+  BadHeader(String),
+  /// The file ended before the block did.
+  Truncated {
+    /// The line that wasn't there.
+    line: usize
+  },
+  /// The decoder found no rows, so the file must have changed.
+  Empty,
+  /// The file isn't F06 text output at all.
+  WrongFile(WrongFile)
+}
+
+impl Display for ParseError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::Io(e) => write!(f, "I/O error: {}", e),
+      Self::NotIndexed { block_ref, nth } => write!(
+        f,
+        "no {} block #{} in subcase {} in the index",
+        block_ref.block_type,
+        nth,
+        block_ref.subcase
+      ),
+      Self::NoSolver => write!(f, "the solver wasn't known for the block"),
+      Self::BadHeader(h) => write!(f, "the decoder rejected header \"{}\"", h),
+      Self::Truncated { line } => {
+        write!(f, "the file ended before line {}", line)
+      },
+      Self::Empty => write!(f, "no rows were found where the block was"),
+      Self::WrongFile(w) => write!(f, "wrong kind of file: {}", w),
+    };
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<io::Error> for ParseError {
+  fn from(e: io::Error) -> Self {
+    return Self::Io(e);
+  }
+}
+
+impl ParseError {
+  /// Turns this into an I/O error, for the functions that return those. The
+  /// wrong files become invalid data, so they can be told apart.
+  pub(crate) fn into_io(self) -> io::Error {
+    return match self {
+      Self::Io(e) => e,
+      Self::WrongFile(_) => io::Error::new(io::ErrorKind::InvalidData, self),
+      e => io::Error::other(e)
+    };
+  }
+
+  /// Finds out whether an I/O error returned by the parser was about the
+  /// wrong kind of file, and which (e.g. to tell the user so).
+  pub fn wrong_file_in(error: &io::Error) -> Option<&WrongFile> {
+    return match error.get_ref()?.downcast_ref::<Self>()? {
+      Self::WrongFile(w) => Some(w),
+      _ => None
+    };
+  }
+}
+
+/// Loads blocks from a file on demand, using an index of it. Loads are
+/// independent, so they can be done from many threads at once.
+#[derive(Clone, Debug)]
+pub struct BlockLoader {
+  /// The path to the file.
+  path: PathBuf,
+  /// The index of the file.
+  index: FileIndex
+}
+
+impl BlockLoader {
+  /// Sets up a loader for a file. Nothing is read until a block is loaded.
+  pub fn open<P: AsRef<Path>>(path: P, index: FileIndex) -> Self {
+    return Self {
+      path: path.as_ref().to_owned(),
+      index
+    };
+  }
+
+  /// Returns the index in use.
+  pub fn index(&self) -> &FileIndex {
+    return &self.index;
+  }
+
+  /// Loads the first block of a type in a subcase.
+  pub fn load(&self, block_ref: BlockRef) -> Result<FinalBlock, ParseError> {
+    return self.load_nth(block_ref, 0);
+  }
+
+  /// Loads the n-th block of a type in a subcase, in the order a full parse
+  /// would have stored them (before merging).
+  pub fn load_nth(
+    &self,
+    block_ref: BlockRef,
+    nth: usize
+  ) -> Result<FinalBlock, ParseError> {
+    let entry = self.index.find(block_ref, nth)
+      .ok_or(ParseError::NotIndexed { block_ref, nth })?;
+    return self.load_entry(entry);
+  }
+
+  /// Loads the block an index entry points to.
+  pub fn load_entry(
+    &self,
+    entry: &IndexEntry
+  ) -> Result<FinalBlock, ParseError> {
+    let solver = entry.flavour.solver.ok_or(ParseError::NoSolver)?;
+    let block_type = entry.block_ref.block_type;
+    let mut dec = block_type.init_decoder(entry.flavour);
+    if !dec.good_header(&entry.header) {
+      return Err(ParseError::BadHeader(entry.header.clone()));
+    }
+    if let Some(li) = entry.hint {
+      dec.hint_last(li);
+    }
+    let mut reader = BufReader::new(File::open(&self.path)?);
+    reader.seek(SeekFrom::Start(entry.byte_range.0))?;
+    let mut buf: Vec<u8> = Vec::new();
+    let mut print_precision: Option<f64> = None;
+    let mut line_counts = LineCounts::default();
+    let (start, end) = entry.line_range;
+    for n in start..end {
+      let line = match read_raw_line(&mut reader, &mut buf)? {
+        Some((line, _)) => line,
+        None => return Err(ParseError::Truncated { line: n })
+      };
+      if entry.skipped.binary_search(&n).is_ok() {
+        // the parser counted the debug lines it skipped
+        let extra = &self.index.debug_prefixes;
+        if is_debug_line(Some(solver), extra, &line) {
+          line_counts.count_debug();
+        }
+        continue;
+      }
+      let comma = self.index.decimal_comma_since.is_some_and(|s| n >= s);
+      let line = if comma { periodise_decimals(&line) } else { line };
+      // same as the parser does
+      let resp = if ends_block(solver, block_type, &line) {
+        LineResponse::Done
+      } else {
+        dec.consume(&line, n)
+      };
+      line_counts.count(resp);
+      if resp == LineResponse::Data {
+        if let Some(p) = line_print_precision(&line) {
+          print_precision = Some(print_precision.map_or(p, |q| q.max(p)));
+        }
+      }
+      if resp.abnormal() || resp == LineResponse::Done {
+        break;
+      }
+    }
+    let mut block = dec.finalise(entry.block_ref.subcase, Some((start, end)));
+    if block.row_indexes.is_empty() {
+      return Err(ParseError::Empty);
+    }
+    block.print_precision = print_precision;
+    block.line_counts = line_counts;
+    block.byte_range = Some(entry.byte_range);
+    block.step = entry.step;
+    // the parser numbers blocks in the order they end, as does the index
+    block.uid = self.index.entries.iter().position(|e| {
+      return e.block_ref == entry.block_ref && e.nth == entry.nth;
+    });
+    return Ok(block);
+  }
+}
@@ -0,0 +1,28 @@
+//! This module defines concepts specific to modal (eigenvalue) analyses, used
+//! to index their results.
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// A mode (or, for complex eigenvalues, a root), referenced by its number.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq,
+  derive_more::From
+)]
+pub struct ModeRef {
+  /// The number of the mode, as printed.
+  pub mode_number: usize
+}
+
+impl Display for ModeRef {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(f, "MODE {}", self.mode_number);
+  }
+}
+
+impl IndexType for ModeRef {
+  const INDEX_NAME: &'static str = "MODE NUMBER";
+}
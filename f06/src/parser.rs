@@ -1,12 +1,13 @@
 //! This module implements the generic parser for F06 files, and associated
 //! structures and enums.
 
-use std::collections::{BTreeSet, BTreeMap};
+use std::collections::{BTreeSet, BTreeMap, VecDeque};
+use std::fmt::Display;
 use std::fs::File;
 use std::io::{self, BufReader, BufRead};
 use std::path::Path;
 
-use log::{debug, error, warn};
+use log::{debug, error, info, warn};
 use serde::{Serialize, Deserialize};
 
 use crate::prelude::*;
@@ -22,6 +23,8 @@ pub enum ParserResponse {
   Solver(Solver),
   /// This line told us the current subcase.
   Subcase(usize),
+  /// This line told us the current time step or frequency.
+  Step(Step),
   /// The line contained a warning.
   Warning,
   /// The line contained a fatal.
@@ -39,7 +42,28 @@ pub enum ParserResponse {
   /// because we don't even know the solver yet!
   BeginningWithoutSolver,
   /// This line indicates the beginning of a block we don't even know yet.
-  PotentialHeader
+  PotentialHeader,
+  /// The line had statistics about the run in it.
+  RunStats,
+  /// The line was part of the checks on the solution (epsilon and external
+  /// work).
+  SolutionDiagnostics,
+  /// The line was debug output from the solver, kept from the decoder.
+  DebugLine,
+  /// The line was part of the case control echo.
+  CaseControl
+}
+
+/// Number of lines after a page banner that still belong to the page header.
+const PAGE_HEADER_LINES: usize = 3;
+
+/// A subcase change found in the middle of a page, rather than in its header.
+#[derive(Copy, Clone, Debug)]
+struct SubcaseMarker {
+  /// The subcase it switched to.
+  subcase: usize,
+  /// The line it was found on.
+  line: usize
 }
 
 /// This is the F06 parser -- it doesn't care how lines are fed into it.
@@ -55,10 +79,74 @@ pub struct OnePassParser {
   total_lines: usize,
   /// Line of the last block beginning.
   last_block_start: usize,
+  /// How many blocks have been put into the file, for their UIDs.
+  blocks_inserted: usize,
   /// Accumulator of block header strings.
   header_accumulator: Vec<String>,
+  /// How many lines apart the parts of a block header can be.
+  header_window: usize,
+  /// The line of the first part of the header being accumulated.
+  header_start: usize,
+  /// The line of the last part of the header being accumulated.
+  header_end: usize,
   /// Stores last indexes per block type.
-  last_indexes: BTreeMap<BlockType, NasIndex>
+  last_indexes: BTreeMap<BlockType, NasIndex>,
+  /// Number of lines left in the current page header.
+  page_header_left: usize,
+  /// The subcase written in the current page header, if any.
+  page_subcase: Option<usize>,
+  /// The superelement the output is currently for, if any.
+  superelement: Option<usize>,
+  /// The last mid-page subcase change on the current page, if any.
+  marker: Option<SubcaseMarker>,
+  /// Worst print precision seen in the data lines of the current block.
+  print_precision: Option<f64>,
+  /// How the lines of the current block were classified so far.
+  line_counts: LineCounts,
+  /// Gathers the run statistics.
+  run_stats: RunStatsScanner,
+  /// Gathers the checks on the solution of each load case.
+  solution_diagnostics: SolutionDiagnosticsScanner,
+  /// Gathers the output requests from the case control echo.
+  case_control: CaseControlScanner,
+  /// Whether to check the invariants of the file when finishing up.
+  validate: bool,
+  /// The byte offset of the current line.
+  line_offset: u64,
+  /// The byte offset of the next line.
+  next_offset: u64,
+  /// The byte offset of the first line of the header being accumulated.
+  header_offset: u64,
+  /// The byte offset of the line after the last part of that header.
+  header_next_offset: u64,
+  /// The byte offset of the line of the last block beginning.
+  last_block_offset: u64,
+  /// The index of the finished blocks, if one is being recorded.
+  index: Option<Vec<IndexEntry>>,
+  /// What's been recorded about the current block, for the index.
+  record: Option<BlockRecord>,
+  /// The first line read with decimal commas, if they're in use.
+  decimal_comma_since: Option<usize>,
+  /// Whether the current block has had a line telling whether its reals are
+  /// written with decimal commas, so that the rest of it isn't checked.
+  decimal_comma_checked: bool,
+  /// The post-processors to run when finishing up, and their registry.
+  post_processing: Option<(PostProcessorRegistry, Vec<String>)>,
+  /// Layouts for the tables of unknown solver variants.
+  layouts: CustomLayouts,
+  /// The current time step or frequency, in dynamic analyses.
+  step: Option<Step>,
+  /// How many blocks were finished for each subcase and type so far.
+  block_counts: BTreeMap<BlockRef, usize>,
+  /// The finished blocks not handed out yet, when streaming them instead of
+  /// putting them into the file.
+  streamed: Option<VecDeque<FinalBlock>>,
+  /// The flavour to fall back on for whatever the file doesn't give away.
+  fallback: Flavour,
+  /// How many lines apart progress is reported, when it is.
+  progress_interval: usize,
+  /// Prefixes of debug lines to skip besides the solver's own.
+  debug_prefixes: Vec<String>
 }
 
 impl Default for OnePassParser {
@@ -76,31 +164,244 @@ impl OnePassParser {
       current_decoder: None,
       total_lines: 0,
       last_block_start: 0,
+      blocks_inserted: 0,
       header_accumulator: Vec::new(),
-      last_indexes: BTreeMap::new()
+      header_window: HEADER_WINDOW,
+      header_start: 0,
+      header_end: 0,
+      last_indexes: BTreeMap::new(),
+      page_header_left: 0,
+      page_subcase: None,
+      superelement: None,
+      marker: None,
+      print_precision: None,
+      line_counts: LineCounts::default(),
+      run_stats: RunStatsScanner::new(),
+      solution_diagnostics: SolutionDiagnosticsScanner::new(),
+      case_control: CaseControlScanner::new(),
+      validate: cfg!(debug_assertions),
+      line_offset: 0,
+      next_offset: 0,
+      header_offset: 0,
+      header_next_offset: 0,
+      last_block_offset: 0,
+      index: None,
+      record: None,
+      decimal_comma_since: None,
+      decimal_comma_checked: false,
+      post_processing: None,
+      layouts: CustomLayouts::new(),
+      step: None,
+      block_counts: BTreeMap::new(),
+      streamed: None,
+      fallback: Flavour::default(),
+      progress_interval: PROGRESS_INTERVAL,
+      debug_prefixes: Vec::new()
     };
   }
 
   /// Hints the parser about the flavour.
   pub fn hint_flavour(&mut self, flavour: Flavour) {
-    self.file.flavour.solver = self.file.flavour.solver.or(flavour.solver);
-    self.file.flavour.soltype = self.file.flavour.soltype.or(flavour.soltype);
+    let source = FlavourSource::UserSupplied;
+    let res = &mut self.file.flavour_resolution;
+    if let Some(solver) = flavour.solver {
+      offer_evidence(&mut res.solver, "solver", source, solver, None);
+    }
+    if let Some(soltype) = flavour.soltype {
+      offer_evidence(&mut res.soltype, "solution type", source, soltype, None);
+    }
+    self.file.flavour = res.flavour();
+  }
+
+  /// Sets a flavour to fall back on: whatever of it the file doesn't give
+  /// away by the time the first block begins is taken from it. Unlike a hint,
+  /// it doesn't take precedence over what's in the file.
+  pub fn fallback_flavour(&mut self, flavour: Flavour) {
+    self.fallback = flavour;
+  }
+
+  /// Sets up the parser to resolve the flavour according to a policy: its
+  /// forced flavour is hinted, and its default one is fallen back on.
+  pub fn flavour_policy(&mut self, policy: &FlavourPolicy) {
+    if let Some(forced) = policy.forced {
+      self.hint_flavour(forced);
+    }
+    if let Some(default) = policy.default {
+      self.fallback_flavour(default);
+    }
+  }
+
+  /// Takes whatever the flavour is still missing from the fallback one.
+  fn fall_back(&mut self) {
+    let source = FlavourSource::Default;
+    let line = Some(self.total_lines);
+    let res = &mut self.file.flavour_resolution;
+    if let (None, Some(solver)) = (res.solver.value, self.fallback.solver) {
+      debug!("Falling back on {} as the solver.", solver);
+      offer_evidence(&mut res.solver, "solver", source, solver, line);
+    }
+    if let (None, Some(soltype)) = (res.soltype.value, self.fallback.soltype) {
+      debug!("Falling back on {} as the solution type.", soltype);
+      offer_evidence(&mut res.soltype, "solution type", source, soltype, line);
+    }
+    self.file.flavour = res.flavour();
+  }
+
+  /// Sets whether the invariants of the file are checked when finishing up,
+  /// with violations logged as warnings. On by default in debug builds.
+  pub fn validate_internal(&mut self, validate: bool) {
+    self.validate = validate;
   }
 
-  /// Tries to update the solver in based on a line.
+  /// Sets how many lines apart the parts of a block header can be, with
+  /// only blank lines between them. 1 means they must be consecutive; the
+  /// default is `HEADER_WINDOW` and anything above `MAX_HEADER_WINDOW` is
+  /// clamped down to it.
+  pub fn header_window(&mut self, window: usize) {
+    self.header_window = window.clamp(1, MAX_HEADER_WINDOW);
+  }
+
+  /// Makes the parser record an index of the blocks it finds, to be gotten
+  /// with `finish_indexed`.
+  pub fn record_index(&mut self) {
+    self.index = Some(Vec::new());
+  }
+
+  /// Sets whether reals are read with decimal commas (e.g. "1,5E+03") from
+  /// the start. Off by default, but turned on anyway when a data line clearly
+  /// uses them.
+  pub fn decimal_comma(&mut self, on: bool) {
+    self.decimal_comma_since = on.then_some(self.total_lines + 1);
+  }
+
+  /// Selects post-processors from a registry, by name, to run when finishing
+  /// up (along with whatever provides their requirements). Errs right away if
+  /// they can't be put in order, in which case nothing is selected.
+  pub fn post_process<S: AsRef<str>>(
+    &mut self,
+    registry: PostProcessorRegistry,
+    names: &[S]
+  ) -> Result<(), PostProcessError> {
+    registry.plan(names)?;
+    let names = names.iter().map(|s| s.as_ref().to_owned()).collect();
+    self.post_processing = Some((registry, names));
+    return Ok(());
+  }
+
+  /// Sets the layouts for the tables of unknown solver variants. Their
+  /// headers take precedence over those of known blocks. The solver must
+  /// still be known (e.g. hinted), and blocks decoded with them aren't put
+  /// in the index, since the loader doesn't know the layouts.
+  pub fn custom_layouts(&mut self, layouts: CustomLayouts) {
+    self.layouts = layouts;
+  }
+
+  /// Sets how many lines apart progress is reported when parsing with
+  /// `parse_bufread_with_progress` (besides whenever a block finishes). The
+  /// default is `PROGRESS_INTERVAL`; zero is taken as one.
+  pub fn progress_interval(mut self, lines: usize) -> Self {
+    self.progress_interval = lines.max(1);
+    return self;
+  }
+
+  /// Adds prefixes of debug lines to skip, besides the ones the solver is
+  /// known to print (see `Solver::debug_line_prefixes`), for debug settings
+  /// and solver builds that print others. These are skipped whatever the
+  /// solver, even before it's known; leading whitespace is ignored.
+  pub fn debug_line_prefixes<S: AsRef<str>>(&mut self, prefixes: &[S]) {
+    self.debug_prefixes.extend(
+      prefixes.iter().map(|p| p.as_ref().trim_start().to_owned())
+    );
+  }
+
+  /// Sets the limits on how many diagnostics are kept.
+  pub fn limit_diagnostics(&mut self, limits: DiagnosticLimits) {
+    self.file.diagnostics.limits = limits;
+  }
+
+  /// Records a diagnostic for the current line. Returns true if the line
+  /// number was kept.
+  fn diagnose(&mut self, kind: DiagnosticKind, line: &str) -> bool {
+    return self.file.diagnostics.push_at(
+      kind,
+      self.total_lines,
+      Some(self.line_offset),
+      line
+    );
+  }
+
+  /// Offers a piece of evidence about the solver.
+  fn offer_solver(
+    &mut self,
+    source: FlavourSource,
+    solver: Solver
+  ) -> EvidenceOutcome {
+    let outcome = offer_evidence(
+      &mut self.file.flavour_resolution.solver,
+      "solver",
+      source,
+      solver,
+      Some(self.total_lines)
+    );
+    self.file.flavour = self.file.flavour_resolution.flavour();
+    return outcome;
+  }
+
+  /// Offers a piece of evidence about the solution type.
+  fn offer_soltype(
+    &mut self,
+    source: FlavourSource,
+    soltype: SolType
+  ) -> EvidenceOutcome {
+    let outcome = offer_evidence(
+      &mut self.file.flavour_resolution.soltype,
+      "solution type",
+      source,
+      soltype,
+      Some(self.total_lines)
+    );
+    self.file.flavour = self.file.flavour_resolution.flavour();
+    return outcome;
+  }
+
+  /// Tries to find a solver name in a line.
   fn detect_solver(&self, line: &str) -> Option<Solver> {
-    if self.file.flavour.solver.is_none() {
-      for cand in Solver::all() {
-        if line.contains(cand.name()) {
-          return Some(*cand);
-        }
-      }
+    return Solver::all().iter().copied().find(|s| line.contains(s.name()));
+  }
+
+  /// Tries to find a solution type in an echoed "SOL n" line, or one naming
+  /// the solution (e.g. "SOL BUCKLING").
+  fn detect_soltype(&self, line: &str) -> Option<SolType> {
+    // most lines aren't, and breaking them down isn't cheap
+    if !line.contains("SOL") {
+      return None;
+    }
+    let mut bd = line_breakdown(line);
+    return match (bd.next(), bd.next(), bd.next()) {
+      (Some(LineField::NoIdea("SOL")), Some(LineField::Integer(n)), None) => {
+        SolType::try_from(n as usize).ok()
+      },
+      (Some(LineField::NoIdea("SOL")), Some(LineField::NoIdea(name)), None) => {
+        name.parse().ok()
+      },
+      _ => None
+    };
+  }
+
+  /// Guesses the solver from the way a subcase line is written.
+  fn subcase_solver_hint(&self, line: &str) -> Solver {
+    if line.contains("OUTPUT FOR SUBCASE") {
+      return Solver::Mystran;
+    } else {
+      return Solver::Simcenter;
     }
-    return None;
   }
 
   /// Tries to detect a change in subcase.
   fn detect_subcase(&self, line: &str) -> Option<usize> {
+    if !line.contains("SUBCASE") {
+      return None;
+    }
     let bd: Vec<_> = line_breakdown(line).collect();
     if line.contains("OUTPUT FOR SUBCASE") {
       return bd.into_iter()
@@ -120,51 +421,419 @@ impl OnePassParser {
     return None;
   }
 
-  /// Flushes the current block decoder into the file.
-  fn flush_decoder(&mut self) {
+  /// Tries to detect a time step or frequency line, printed above the
+  /// headers of dynamic analysis output, like `TIME = 1.000000E-02`.
+  fn detect_step(&self, line: &str) -> Option<Step> {
+    let (name, value) = line.split_once('=')?;
+    let mut value = value.split_whitespace();
+    let x = decode_nasfloat(value.next()?)?;
+    if value.next().is_some() {
+      return None;
+    }
+    return Step::from_name(name.trim(), x);
+  }
+
+  /// Switches to output for another time step or frequency (or for none),
+  /// which ends the current block.
+  fn switch_step(&mut self, step: Option<Step>) {
+    if self.step != step {
+      self.flush_decoder(self.total_lines, self.line_offset);
+      debug!("Switched to step {:?} on line {}.", step, self.total_lines);
+      // blocks for another step never continue the previous ones
+      self.last_indexes.clear();
+      self.step = step;
+    }
+  }
+
+  /// Tries to find the superelement a subcase line says the output is for.
+  fn detect_superelement(&self, line: &str) -> Option<usize> {
+    let bd: Vec<_> = line_breakdown(line).collect();
+    return bd.windows(2)
+      .find_map(|w| match w {
+        [LineField::NoIdea("SUPERELEMENT"), LineField::Integer(se)] => {
+          usize::try_from(*se).ok()
+        },
+        _ => None
+      });
+  }
+
+  /// Switches to output for another superelement (or for none), which ends
+  /// the current block.
+  fn switch_superelement(&mut self, superelement: Option<usize>) {
+    if self.superelement != superelement {
+      self.flush_decoder(self.total_lines, self.line_offset);
+      debug!(
+        "Switched to superelement {:?} on line {}.",
+        superelement,
+        self.total_lines
+      );
+      self.superelement = superelement;
+    }
+  }
+
+  /// Checks if a line is a page banner (the first line of a page header).
+  fn is_page_banner(&self, line: &str) -> bool {
+    if !line.starts_with('1') {
+      return false;
+    }
+    let bd: Vec<_> = line_breakdown(line).collect();
+    return matches!(
+      bd.as_slice(),
+      [.., LineField::NoIdea("PAGE"), LineField::Integer(_)]
+    );
+  }
+
+  /// Takes note of where a subcase line was found. The page header shows the
+  /// subcase the page began in, so a subcase change that happened mid-page
+  /// takes precedence over it for the blocks after it on the same page; the
+  /// next page header is believed again, even if it goes back to the subcase
+  /// from before the change.
+  fn note_subcase_line(
+    &mut self,
+    found: usize,
+    line: &str,
+    in_page_header: bool
+  ) {
+    if in_page_header {
+      self.page_subcase = Some(found);
+    } else if line.starts_with('0') {
+      // same form as the one in page headers, but mid-page
+      self.marker = Some(SubcaseMarker {
+        subcase: found,
+        line: self.total_lines
+      });
+    }
+  }
+
+  /// Warns if the page header and a mid-page marker disagree on the subcase
+  /// of a block that is about to begin.
+  fn check_subcase_agreement(&mut self, line: &str) {
+    if let (Some(page), Some(mk)) = (self.page_subcase, self.marker) {
+      if page != mk.subcase {
+        warn!(
+          concat!(
+            "Block on line {} is under a page header for subcase {}, but a ",
+            "subcase marker on line {} says {}; using subcase {}."
+          ),
+          self.total_lines,
+          page,
+          mk.line,
+          mk.subcase,
+          self.subcase
+        );
+        let kind = DiagnosticKind::SubcaseDisagreement {
+          page_header: page,
+          marker: mk.subcase,
+          chosen: self.subcase
+        };
+        self.diagnose(kind, line);
+      }
+    }
+  }
+
+  /// Flushes the current block decoder into the file. The block's line range
+  /// ends right before the line passed, which is at a byte offset.
+  fn flush_decoder(&mut self, end: usize, end_offset: u64) {
     if let Some(dec) = self.current_decoder.take() {
       debug!(
         "Finishing up a \"{}\" block on line {}.",
         dec.block_type(),
         self.total_lines
       );
-      let line_range = Some((self.last_block_start, self.total_lines+1));
+      let line_range = Some((self.last_block_start, end));
       if let Some(li) = dec.last_index() {
         self.last_indexes.insert(dec.block_type(), li);
       }
-      let fb = dec.finalise(self.subcase, line_range);
+      let byte_range = (self.last_block_offset, end_offset);
+      let mut fb = dec.finalise(self.subcase, line_range);
+      fb.print_precision = self.print_precision.take();
+      fb.step = self.step;
+      fb.line_counts = std::mem::take(&mut self.line_counts);
+      if let Some(se) = self.superelement {
+        fb.metadata.insert(META_SUPERELEMENT.to_owned(), se.to_string());
+      }
+      fb.byte_range = Some(byte_range);
+      // these must never go unnoticed
+      let non_finite = fb.non_finite();
+      if let Some((row, col, x)) = non_finite.first() {
+        warn!(
+          concat!(
+            "{} NaN or infinite values in {} block (subcase {}), ",
+            "first {} at {}, {}!"
+          ),
+          non_finite.len(),
+          fb.block_type,
+          fb.subcase,
+          non_finite_name(*x).unwrap_or("?"),
+          row,
+          col
+        );
+      }
+      let record = self.record.take();
       if !fb.row_indexes.is_empty() {
-        self.file.insert_block(fb);
+        let br = fb.block_ref();
+        let count = self.block_counts.entry(br).or_default();
+        if let (Some(index), Some(record)) = (self.index.as_mut(), record) {
+          let lr = (self.last_block_start, end);
+          index.push(record.into_entry(br, *count, lr, byte_range));
+        }
+        *count += 1;
+        fb.uid = Some(self.blocks_inserted);
+        self.blocks_inserted += 1;
+        match self.streamed {
+          Some(ref mut queue) => queue.push_back(fb),
+          None => self.file.insert_block(fb)
+        }
       }
     }
   }
 
-  /// Flushes the current block header accumulator.
-  fn flush_header(&mut self) -> Option<(String, usize)> {
+  /// Flushes the current block header accumulator, returning the header
+  /// along with its first line and the number of lines it spans.
+  fn flush_header(&mut self) -> Option<(String, usize, usize)> {
     if self.header_accumulator.is_empty() {
       return None;
     } else {
-      let num = self.header_accumulator.len();
+      let span = self.header_end + 1 - self.header_start;
       let full_name = self.header_accumulator.join(" ");
       self.header_accumulator.clear();
-      return Some((full_name, num));
+      return Some((full_name, self.header_start, span));
     }
   }
 
-  /// Consumes a line into the parser.
+  /// Ends the block header being accumulated, if any, on the line after it:
+  /// either begins its block or records it as a potential header. Returns a
+  /// response if the line shouldn't go on to the decoder.
+  fn end_header(&mut self, line: &str) -> Option<ParserResponse> {
+    let (full_name, start, span) = self.flush_header()?;
+    // not a block header, but we were accumulating one.
+    // first, flush the current decoder, which ends before the header.
+    self.flush_decoder(start, self.header_offset);
+    // layouts for unknown solver variants come first, since their headers
+    // can look a lot like those of known blocks.
+    if let Some(layout) = self.layouts.find(&full_name) {
+      let dec = layout.decoder();
+      if !self.solver_known(line) {
+        return Some(ParserResponse::BeginningWithoutSolver);
+      }
+      // the loader wouldn't know the layout, so no header is recorded
+      self.begin_block(dec, None, line);
+      return None;
+    }
+    // is it the header of a known block? the most specific match wins.
+    let match_len = |bt: &BlockType| bt.headers()
+      .iter()
+      .filter(|s| full_name.contains(*s))
+      .map(|s| s.len())
+      .max();
+    let best = BlockType::all().iter().filter_map(match_len).max();
+    let mut candidates = BlockType::all()
+      .iter()
+      .copied()
+      .filter(|bt| best.is_some() && match_len(bt) == best)
+      .collect::<BTreeSet<_>>();
+    match candidates.len() {
+      0 => {
+        // not a known block. push a potential header.
+        // ensure no bad words
+        if BAD_WORDS.iter().any(|w| full_name.contains(w)) {
+          return Some(ParserResponse::Useless);
+        }
+        self.file.potential_headers.insert(PotentialHeader {
+          start,
+          span,
+          text: full_name,
+          offset: self.header_offset
+        });
+        debug!(
+          "Found a potential header ending in line {}! Flushing.",
+          self.total_lines
+        );
+        return Some(ParserResponse::PotentialHeader);
+      },
+      1 => {
+        if !self.solver_known(line) {
+          return Some(ParserResponse::BeginningWithoutSolver);
+        } else {
+          // ok, begin the block then.
+          let bt = candidates.pop_first()
+            .unwrap()
+            .in_solution(self.file.flavour.soltype);
+          let mut dec = bt.init_decoder(self.file.flavour);
+          if dec.good_header(&full_name) {
+            self.begin_block(dec, Some(full_name), line);
+          } else if !BAD_WORDS.iter().any(|w| full_name.contains(w)) {
+            // bad header, whoops.
+            self.file.potential_headers.insert(PotentialHeader {
+              start,
+              span,
+              text: full_name,
+              offset: self.header_offset
+            });
+            debug!(
+              "Found a potential header ending in line {}! Flushing.",
+              self.total_lines
+            );
+            return Some(ParserResponse::PotentialHeader);
+          }
+        }
+      },
+      _ => {
+        warn!(
+          "Line {} matches more than one block type!",
+          self.total_lines
+        );
+        self.diagnose(DiagnosticKind::AmbiguousHeader, line);
+      }
+    }
+    return None;
+  }
+
+  /// Returns whether the solver is known, so that a block can begin on a
+  /// line. If not, that's diagnosed.
+  fn solver_known(&mut self, line: &str) -> bool {
+    self.fall_back();
+    if self.file.flavour.solver.is_some() {
+      return true;
+    }
+    error!(
+      "Found a block start on line {} before knowing the solver!",
+      self.total_lines
+    );
+    self.diagnose(DiagnosticKind::BlockWithoutSolver, line);
+    return false;
+  }
+
+  /// Begins a block right after its header, with a decoder for it. The
+  /// header is recorded for the index, if one is being recorded and the
+  /// header is given.
+  fn begin_block(
+    &mut self,
+    mut dec: Box<dyn OpaqueDecoder>,
+    header: Option<String>,
+    line: &str
+  ) {
+    let bt = dec.block_type();
+    debug!("Started a \"{}\" block on line {}!", bt, self.total_lines);
+    let hint = self.last_indexes.remove(&bt);
+    if let Some(li) = hint {
+      dec.hint_last(li);
+    }
+    if let (Some(header), true) = (header, self.index.is_some()) {
+      self.record = Some(BlockRecord {
+        flavour: self.file.flavour,
+        header,
+        hint,
+        step: self.step,
+        skipped: (self.header_end+1..self.total_lines).collect()
+      });
+    }
+    // the block begins right after the header, blank lines and all.
+    self.last_block_start = self.header_end + 1;
+    self.last_block_offset = self.header_next_offset;
+    self.print_precision = None;
+    self.line_counts = LineCounts::default();
+    self.decimal_comma_checked = false;
+    self.current_decoder = Some(dec);
+    self.check_subcase_agreement(line);
+  }
+
+  /// Returns whether a line is a blank one within the window after a part of
+  /// the header being accumulated, so that the header may go on after it.
+  fn within_header(&self, line: &str) -> bool {
+    return !self.header_accumulator.is_empty()
+      && line.trim().is_empty()
+      && self.total_lines - self.header_end < self.header_window;
+  }
+
+  /// Consumes a line into the parser, assuming it was followed by a single
+  /// line feed in the source. Use `consume_sized` to get the byte offsets
+  /// right otherwise.
   pub fn consume(&mut self, line: &str) -> ParserResponse {
+    return self.consume_sized(line, line.len() as u64 + 1);
+  }
+
+  /// Consumes a line into the parser, along with the number of bytes it took
+  /// up in the source, line break (and byte-order mark) included.
+  pub fn consume_sized(&mut self, line: &str, size: u64) -> ParserResponse {
+    // this is the only place the byte counter moves
+    self.line_offset = self.next_offset;
+    self.next_offset += size;
+    // localised files write reals with decimal commas, which we rewrite; the
+    // first line with reals in each block tells, until one says they're used
+    let unchecked = self.current_decoder.is_some()
+      && !self.decimal_comma_checked;
+    if self.decimal_comma_since.is_none() && unchecked {
+      let verdict = decimal_comma_verdict(line);
+      if verdict == Some(true) {
+        let n = self.total_lines + 1;
+        info!("Line {} has reals with decimal commas, reading them so.", n);
+        self.decimal_comma_since = Some(n);
+      }
+      self.decimal_comma_checked = verdict.is_some();
+    }
+    let periodised = self.decimal_comma_since.map(|_| periodise_decimals(line));
+    let response = self.consume_line(periodised.as_deref().unwrap_or(line));
+    // the loader has to skip whatever the decoder didn't see
+    if let Some(ref mut record) = self.record {
+      if !matches!(response, ParserResponse::PassedToDecoder(..)) {
+        record.skipped.push(self.total_lines);
+      }
+    }
+    return response;
+  }
+
+  /// Does the actual consuming of a line.
+  fn consume_line(&mut self, line: &str) -> ParserResponse {
     self.total_lines += 1;
+    // keep track of page headers
+    let in_page_header = self.page_header_left > 0;
+    self.page_header_left = self.page_header_left.saturating_sub(1);
+    if self.is_page_banner(line) {
+      self.page_header_left = PAGE_HEADER_LINES;
+      self.page_subcase = None;
+      // mid-page subcase changes only hold for the rest of their page
+      self.marker = None;
+    }
     // first, try and enhance our knowledge of the flavour from the line.
     if let Some(solver) = self.detect_solver(line) {
-      self.file.flavour.solver = Some(solver);
-      debug!("Line {} told us the solver is {}!", self.total_lines, solver);
-      return ParserResponse::Solver(solver);
+      let outcome = self.offer_solver(FlavourSource::Banner, solver);
+      let changed = matches!(
+        outcome,
+        EvidenceOutcome::Resolved | EvidenceOutcome::Overridden
+      );
+      if changed {
+        debug!("Line {} told us the solver is {}!", self.total_lines, solver);
+        return ParserResponse::Solver(solver);
+      }
+    }
+    if let Some(soltype) = self.detect_soltype(line) {
+      self.offer_soltype(FlavourSource::Banner, soltype);
+      debug!("Line {} says the solution is {}.", self.total_lines, soltype);
+      return ParserResponse::SolType(soltype);
+    }
+    // the case control echo has subcase lines of its own
+    if self.case_control.consume(line) {
+      debug!("Line {} is in the case control echo.", self.total_lines);
+      return ParserResponse::CaseControl;
     }
     // check for a subcase change
     if let Some(subcase) = self.detect_subcase(line) {
+      if self.file.flavour.solver.is_none() {
+        // the way the subcase is written gives the solver away
+        let hint = self.subcase_solver_hint(line);
+        let source = FlavourSource::Heuristic { line: self.total_lines };
+        self.offer_solver(source, hint);
+        debug!("Line {} suggests the solver is {}.", self.total_lines, hint);
+      }
+      // superelement output is tagged on the subcase lines, and lines without
+      // a tag are for the residual structure
+      let superelement = self.detect_superelement(line);
+      self.switch_superelement(superelement);
+      self.note_subcase_line(subcase, line, in_page_header);
       if self.subcase != subcase {
         // a subcase change definitely means we should stop the block
-        self.flush_decoder();
+        self.flush_decoder(self.total_lines, self.line_offset);
         debug!(
           "Switched from subcase {} to {} on line {}!",
           self.subcase,
@@ -172,96 +841,77 @@ impl OnePassParser {
           self.total_lines
         );
         self.subcase = subcase;
+        // the steps of a new subcase come after its subcase lines
+        self.step = None;
       }
       self.flush_header();
       return ParserResponse::Subcase(subcase);
     }
+    // check for a time step or frequency change
+    if let Some(step) = self.detect_step(line) {
+      self.switch_step(Some(step));
+      self.flush_header();
+      return ParserResponse::Step(step);
+    }
     // check for warning
     if line.contains("WARNING") {
       debug!("Found warning on line {}: {}", self.total_lines, line);
-      self.file.warnings.insert(self.total_lines, line.to_string());
+      let template = message_template(line);
+      if self.diagnose(DiagnosticKind::SolverWarning { template }, line) {
+        self.file.warnings.insert(self.total_lines, line.to_string());
+        self.file.message_offsets.insert(self.total_lines, self.line_offset);
+      }
       self.flush_header();
       return ParserResponse::Warning;
     }
     // check for fatal
     if line.contains("FATAL") {
       debug!("Found fatal on line {}: {}", self.total_lines, line);
-      self.file.fatal_errors.insert(self.total_lines, line.to_string());
+      let template = message_template(line);
+      if self.diagnose(DiagnosticKind::SolverFatal { template }, line) {
+        self.file.fatal_errors.insert(self.total_lines, line.to_string());
+        self.file.message_offsets.insert(self.total_lines, self.line_offset);
+      }
       self.flush_header();
       return ParserResponse::Fatal;
     }
+    // check for the checks on the solution
+    if self.solution_diagnostics.consume(line, self.total_lines) {
+      debug!("Found a solution check on line {}.", self.total_lines);
+      self.flush_header();
+      return ParserResponse::SolutionDiagnostics;
+    }
+    // check for run statistics
+    if self.run_stats.consume(self.file.flavour.solver, line) {
+      debug!("Found run statistics on line {}.", self.total_lines);
+      self.flush_header();
+      return ParserResponse::RunStats;
+    }
+    // some debug settings print progress lines in the middle of blocks, which
+    // look enough like headers or data to be trouble
+    if is_debug_line(self.file.flavour.solver, &self.debug_prefixes, line) {
+      debug!("Line {} is debug output.", self.total_lines);
+      if self.current_decoder.is_some() {
+        self.line_counts.count_debug();
+      }
+      return ParserResponse::DebugLine;
+    }
     // check for a block header part.
-    if let Some(unspaced) = check_header(line) {
+    let part = check_header(line).or_else(|| self.layouts.header_part(line));
+    if let Some(unspaced) = part {
+      if self.header_accumulator.is_empty() {
+        self.header_offset = self.line_offset;
+        self.header_start = self.total_lines;
+      }
+      self.header_end = self.total_lines;
+      self.header_next_offset = self.next_offset;
       self.header_accumulator.push(unspaced);
       return ParserResponse::BlockHeader;
-    } else if let Some((full_name, num_lines)) = self.flush_header() {
-      // not a block header, but we were accumulating one.
-      // first, flush the current decoder.
-      self.flush_decoder();
-      // is it the header of a known block?
-      let mut candidates = BlockType::all()
-        .iter()
-        .copied()
-        .filter(|bt| bt.headers().iter().any(|s| full_name.contains(s)))
-        .collect::<BTreeSet<_>>();
-      match candidates.len() {
-        0 => {
-          // not a known block. push a potential header.
-          // ensure no bad words
-          if BAD_WORDS.iter().any(|w| full_name.contains(w)) {
-            return ParserResponse::Useless;
-          }
-          self.file.potential_headers.insert(PotentialHeader {
-            start: self.total_lines-num_lines,
-            span: num_lines,
-            text: full_name,
-          });
-          debug!(
-            "Found a potential header ending in line {}! Flushing.",
-            self.total_lines
-          );
-          return ParserResponse::PotentialHeader;
-        },
-        1 => {
-          let bt = candidates.pop_first().unwrap();
-          // do we know the solver?
-          if self.file.flavour.solver.is_none() {
-            // nope
-            error!(
-              "Found a block start on line {} before knowing the solver!",
-              self.total_lines
-            );
-            return ParserResponse::BeginningWithoutSolver;
-          } else {
-            // ok, begin the block then.
-            let mut dec = bt.init_decoder(self.file.flavour);
-            if dec.good_header(&full_name) {
-              debug!("Started a \"{}\" block on line {}!", bt, self.total_lines);
-              if let Some(li) = self.last_indexes.remove(&dec.block_type()) {
-                dec.hint_last(li);
-              }
-              self.last_block_start = self.total_lines;
-              self.current_decoder = Some(dec);
-            } else if !BAD_WORDS.iter().any(|w| full_name.contains(w)) {
-              // bad header, whoops.
-              self.file.potential_headers.insert(PotentialHeader {
-                start: self.total_lines-num_lines,
-                span: num_lines,
-                text: full_name,
-              });
-              debug!(
-                "Found a potential header ending in line {}! Flushing.",
-                self.total_lines
-              );
-              return ParserResponse::PotentialHeader;
-            }
-          }
-        },
-        _ => warn!(
-          "Line {} matches more than one block type!",
-          self.total_lines
-        )
-      }
+    } else if self.within_header(line) {
+      // a blank line in a wrapped header; the rest of it may come next.
+      return ParserResponse::BlockHeader;
+    } else if let Some(resp) = self.end_header(line) {
+      return resp;
     }
     // if we got here, the line NOT a block header, and if there was a header
     // being accumulated, it was flushed and the decoder is active.
@@ -269,22 +919,58 @@ impl OnePassParser {
     if let Some(ref mut dec) = self.current_decoder {
       // check for a block-ender
       let resp = if let Some(solver) = self.file.flavour.solver {
-        if solver.block_enders().iter().any(|s| line.contains(s))
-          && !solver.ender_exceptions().contains(&dec.block_type()){
+        if ends_block(solver, dec.block_type(), line) {
           // line has block ender and block is not exempt from ender
           LineResponse::Done
         } else {
           // no block ender, pass to decoder
-          dec.consume(line)
+          if has_overflowed_field(line) {
+            let kind = DiagnosticKind::OverflowedField {
+              block_type: dec.block_type()
+            };
+            self.file.diagnostics.push_at(
+              kind,
+              self.total_lines,
+              Some(self.line_offset),
+              line
+            );
+          }
+          if has_non_finite_field(line) {
+            let kind = DiagnosticKind::NonFiniteValue {
+              block_type: dec.block_type()
+            };
+            self.file.diagnostics.push_at(
+              kind,
+              self.total_lines,
+              Some(self.line_offset),
+              line
+            );
+          }
+          dec.consume(line, self.total_lines)
         }
       } else {
         // no solver but we're in a block?!
-        self.flush_decoder();
+        self.flush_decoder(self.total_lines, self.line_offset);
+        self.diagnose(DiagnosticKind::BlockWithoutSolver, line);
         return ParserResponse::BeginningWithoutSolver;
       };
       let bt = dec.block_type();
+      self.line_counts.count(resp);
+      if resp == LineResponse::Data {
+        if let Some(p) = line_print_precision(line) {
+          let worst = self.print_precision.map_or(p, |q| q.max(p));
+          self.print_precision = Some(worst);
+        }
+      }
+      if resp.abnormal() {
+        let kind = DiagnosticKind::AbnormalLine {
+          block_type: bt,
+          response: resp
+        };
+        self.diagnose(kind, line);
+      }
       if resp.abnormal() || resp == LineResponse::Done {
-        self.flush_decoder();
+        self.flush_decoder(self.total_lines+1, self.next_offset);
       }
       return ParserResponse::PassedToDecoder(bt, resp);
     }
@@ -293,39 +979,483 @@ impl OnePassParser {
   }
 
   /// Finishes up and returns the file struct.
-  pub fn finish(mut self) -> F06File {
-    self.flush_decoder();
-    return self.file;
+  pub fn finish(self) -> F06File {
+    return self.finish_indexed().0;
   }
 
-  /// Parses from a BufRead instance.
-  pub fn parse_bufread<R: BufRead>(reader: R) -> io::Result<F06File> {
-    let mut parser = Self::new();
-    for line in reader.lines() {
-      match parser.consume(&line?) {
-        ParserResponse::PassedToDecoder(bt, lr) if lr.abnormal() => warn!(
-          "Got abnormal response {:?} from {} while parsing line {}!",
-          lr,
-          bt,
-          parser.total_lines
-        ),
-        ParserResponse::BeginningWithoutSolver => warn!(
-          "Found block beginning in line {} before detecting the solver!",
-          parser.total_lines
-        ),
-        _ => {}
+  /// Finishes up and returns the file struct, with some blocks that were
+  /// handed out put back into it first.
+  pub fn finish_with(
+    mut self,
+    blocks: impl IntoIterator<Item = FinalBlock>
+  ) -> F06File {
+    for block in blocks {
+      self.file.insert_block(block);
+    }
+    return self.finish();
+  }
+
+  /// Returns the file parsed so far, without the blocks handed out.
+  pub fn file(&self) -> &F06File {
+    return &self.file;
+  }
+
+  /// Finishes up and returns the file struct along with the index of its
+  /// blocks, which is empty unless `record_index` was called.
+  pub fn finish_indexed(mut self) -> (F06File, FileIndex) {
+    self.end_header("");
+    self.flush_decoder(self.total_lines+1, self.next_offset);
+    self.fall_back();
+    // blocks finished but not handed out yet stay in the file
+    for block in self.take_streamed() {
+      self.file.insert_block(block);
+    }
+    self.file.run_stats = self.run_stats.finish();
+    // load cases are numbered in the order of the subcases in the echo
+    let mut subcases = self.case_control.subcase_order().to_vec();
+    self.file.subcase_titles = self.case_control.titles();
+    self.file.requests = self.case_control.finish();
+    if subcases.is_empty() {
+      let mut implied = self.file.requests.keys()
+        .chain(self.file.subcase_titles.keys())
+        .copied()
+        .collect::<BTreeSet<_>>();
+      if implied.is_empty() {
+        implied = self.block_counts.keys().map(|k| k.subcase).collect();
       }
+      subcases = implied.into_iter().collect();
     }
-    return Ok(parser.finish());
+    self.file.solution_diagnostics = std::mem::take(
+      &mut self.solution_diagnostics
+    ).finish(&subcases);
+    self.file.line_count = Some(self.total_lines);
+    self.file.decimal_comma = self.decimal_comma_since.is_some();
+    if self.validate {
+      for violation in self.file.check_invariants() {
+        warn!("Internal inconsistency: {}!", violation);
+      }
+    }
+    if let Some((registry, names)) = self.post_processing.take() {
+      match registry.run(&names, &mut self.file) {
+        Ok(diagnostics) => {
+          for diag in diagnostics {
+            warn!("{}!", diag);
+          }
+        },
+        Err(e) => error!("Couldn't post-process the file: {}!", e)
+      }
+    }
+    let index = FileIndex {
+      flavour: self.file.flavour,
+      line_count: self.total_lines,
+      decimal_comma_since: self.decimal_comma_since,
+      debug_prefixes: std::mem::take(&mut self.debug_prefixes),
+      entries: self.index.take().unwrap_or_default()
+    };
+    return (self.file, index);
+  }
+
+  /// Parses from a BufRead instance.
+  pub fn parse_bufread<R: BufRead>(reader: R) -> io::Result<F06File> {
+    return Self::new().read_bufread(reader);
+  }
+
+  /// Parses bytes already in memory.
+  pub fn parse_bytes(bytes: &[u8]) -> io::Result<F06File> {
+    return Self::new().read_bytes(bytes);
   }
 
   /// Utility method -- reads and parses a file.
   pub fn parse_file<S: AsRef<Path>>(p: S) -> io::Result<F06File> {
+    return Self::new().read_file(p);
+  }
+
+  /// Consumes a line, warning about the responses that deserve it.
+  fn consume_logged(&mut self, line: &str, size: u64) {
+    match self.consume_sized(line, size) {
+      ParserResponse::PassedToDecoder(bt, lr) if lr.abnormal() => warn!(
+        "Got abnormal response {:?} from {} while parsing line {}!",
+        lr,
+        bt,
+        self.total_lines
+      ),
+      ParserResponse::BeginningWithoutSolver => warn!(
+        "Found block beginning in line {} before detecting the solver!",
+        self.total_lines
+      ),
+      _ => {}
+    }
+  }
+
+  /// Feeds a line into the parser, assuming it was followed by a single line
+  /// feed in the source, and returns the blocks it finished. From then on,
+  /// finished blocks are handed out this way instead of kept in the file.
+  pub fn feed_line(&mut self, line: &str) -> Vec<FinalBlock> {
+    return self.feed_sized(line, line.len() as u64 + 1);
+  }
+
+  /// Feeds a line into the parser, along with the number of bytes it took up
+  /// in the source, and returns the blocks it finished.
+  pub fn feed_sized(&mut self, line: &str, size: u64) -> Vec<FinalBlock> {
+    self.streamed.get_or_insert_with(VecDeque::new);
+    self.consume_logged(line, size);
+    return self.take_streamed();
+  }
+
+  /// Tells the parser the input is over, and returns the block it ended, if
+  /// lines were being fed to it.
+  pub fn end_of_input(&mut self) -> Vec<FinalBlock> {
+    self.end_header("");
+    self.flush_decoder(self.total_lines+1, self.next_offset);
+    return self.take_streamed();
+  }
+
+  /// Takes the finished blocks not handed out yet, if streaming.
+  fn take_streamed(&mut self) -> Vec<FinalBlock> {
+    return match self.streamed {
+      Some(ref mut queue) => queue.drain(..).collect(),
+      None => Vec::new()
+    };
+  }
+
+  /// Consumes all lines from a BufRead instance.
+  pub(crate) fn feed_bufread<R: BufRead>(
+    &mut self,
+    mut reader: R
+  ) -> io::Result<()> {
+    let mut buf: Vec<u8> = Vec::new();
+    while let Some((line, size)) = read_raw_line(&mut reader, &mut buf)? {
+      self.consume_logged(&line, size);
+    }
+    return Ok(());
+  }
+
+  /// Consumes all lines in some bytes.
+  pub(crate) fn feed_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+    if let Some(wrong) = sniff(bytes) {
+      return Err(ParseError::WrongFile(wrong).into_io());
+    }
+    for raw in bytes.split_inclusive(|b| *b == b'\n') {
+      self.consume_logged(decode_raw_str(raw)?, raw.len() as u64);
+    }
+    return Ok(());
+  }
+
+  /// Parses from a BufRead instance with this parser, which might have been
+  /// set up beforehand (e.g. hinted about the flavour).
+  pub fn read_bufread<R: BufRead>(self, reader: R) -> io::Result<F06File> {
+    let mut stream = self.read_streaming(reader);
+    let blocks = stream.by_ref().collect::<Result<Vec<_>, _>>();
+    return match blocks {
+      Ok(blocks) => Ok(stream.finish_with(blocks)),
+      Err(e) => Err(e.into_io())
+    };
+  }
+
+  /// Returns how far the parse has gone.
+  fn progress(&self) -> ProgressInfo {
+    return ProgressInfo {
+      lines_parsed: self.total_lines,
+      blocks_completed: self.blocks_inserted,
+      bytes_read: Some(self.next_offset)
+    };
+  }
+
+  /// Parses from a BufRead instance with this parser, which might have been
+  /// set up beforehand, reporting progress to a callback every so many lines
+  /// (see `progress_interval`), whenever a block finishes and once at the
+  /// end.
+  pub fn parse_bufread_with_progress<R, F>(
+    self,
+    reader: R,
+    mut callback: F
+  ) -> io::Result<F06File>
+  where
+    R: BufRead,
+    F: FnMut(ProgressInfo)
+  {
+    let interval = self.progress_interval;
+    let mut stream = self.read_streaming(reader);
+    // the finished blocks are left in the stream, to be put in the file
+    let mut blocks = 0;
+    while let Some(res) = stream.advance() {
+      res.map_err(ParseError::into_io)?;
+      let due = stream.parser.total_lines.is_multiple_of(interval);
+      if due || stream.finished.len() > blocks {
+        blocks = stream.finished.len();
+        callback(stream.parser.progress());
+      }
+    }
+    callback(stream.parser.progress());
+    return Ok(stream.finish());
+  }
+
+  /// Parses from a BufRead instance, handing out the blocks as they finish
+  /// instead of keeping them all in memory.
+  pub fn parse_streaming<R: BufRead>(reader: R) -> BlockStream<R> {
+    return Self::new().read_streaming(reader);
+  }
+
+  /// Parses from a BufRead instance with this parser, which might have been
+  /// set up beforehand, handing out the blocks as they finish.
+  pub fn read_streaming<R: BufRead>(mut self, reader: R) -> BlockStream<R> {
+    self.streamed = Some(VecDeque::new());
+    return BlockStream {
+      parser: self,
+      reader,
+      buf: Vec::new(),
+      finished: VecDeque::new(),
+      done: false,
+      sniffed: false,
+      reported_line: 0
+    };
+  }
+
+  /// Parses bytes already in memory (e.g. a memory-mapped file) with this
+  /// parser, which might have been set up beforehand.
+  pub fn read_bytes(mut self, bytes: &[u8]) -> io::Result<F06File> {
+    self.feed_bytes(bytes)?;
+    return Ok(self.finish());
+  }
+
+  /// Reads and parses a file with this parser, which might have been set up
+  /// beforehand (e.g. hinted about the flavour).
+  pub fn read_file<S: AsRef<Path>>(self, p: S) -> io::Result<F06File> {
     let file = File::open(p.as_ref())?;
-    let mut f06 = Self::parse_bufread(BufReader::new(file))?;
+    let mut f06 = self.read_bufread(BufReader::new(file))?;
     f06.filename = p.as_ref().file_name()
       .and_then(|s| s.to_str())
       .map(String::from);
     return Ok(f06);
   }
+
+  /// Maps a file into memory and parses it, going through its lines without
+  /// copying them. Only slightly faster than reading very large files
+  /// through a buffer, since parsing the lines takes most of the time (see
+  /// the parse_mmap bench).
+  #[cfg(feature = "mmap")]
+  pub fn parse_mmap(path: &Path) -> io::Result<F06File> {
+    return Self::new().read_mmap(path);
+  }
+
+  /// Maps a file into memory and parses it with this parser, which might have
+  /// been set up beforehand (e.g. hinted about the flavour). The file must
+  /// not be written to meanwhile: if it's truncated, the process is killed
+  /// by a SIGBUS instead of getting an error.
+  #[cfg(feature = "mmap")]
+  pub fn read_mmap(self, path: &Path) -> io::Result<F06File> {
+    let file = File::open(path)?;
+    // SAFETY: the mapping is read-only and dropped before returning, but
+    // nothing stops another process from changing the file meanwhile. That's
+    // undefined behaviour: its bytes would change under the &[u8], and if
+    // it's truncated, touching the pages past its new end raises SIGBUS and
+    // kills the process instead of failing with an I/O error. Callers must
+    // only map files nothing else writes to while they're being parsed.
+    let map = unsafe { memmap2::Mmap::map(&file)? };
+    let mut f06 = self.read_bytes(&map)?;
+    f06.filename = path.file_name()
+      .and_then(|s| s.to_str())
+      .map(String::from);
+    return Ok(f06);
+  }
+}
+
+/// How far a parse has gone, as reported to a progress callback.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ProgressInfo {
+  /// The number of lines consumed so far.
+  pub lines_parsed: usize,
+  /// The number of blocks finished so far (before merging).
+  pub blocks_completed: usize,
+  /// The number of bytes consumed so far, if known.
+  pub bytes_read: Option<u64>
+}
+
+/// A block handed out by a streaming parse, along with the solver messages
+/// found since the previous one.
+#[derive(Clone, Debug)]
+pub struct StreamedBlock {
+  /// The finished block.
+  pub block: FinalBlock,
+  /// The warnings found since the previous block, by line.
+  pub warnings: BTreeMap<usize, String>,
+  /// The fatal errors found since the previous block, by line.
+  pub fatal_errors: BTreeMap<usize, String>
+}
+
+/// Parses an F06 from a BufRead instance one line at a time, yielding each
+/// block as soon as it's finished. Everything but the blocks accumulates in
+/// the file as usual, and is returned by `finish`.
+pub struct BlockStream<R: BufRead> {
+  /// The parser, which hands the finished blocks out.
+  parser: OnePassParser,
+  /// Where the lines come from.
+  reader: R,
+  /// Buffer for the raw lines.
+  buf: Vec<u8>,
+  /// The finished blocks not handed out yet.
+  finished: VecDeque<FinalBlock>,
+  /// Whether the reader ran out or failed.
+  done: bool,
+  /// Whether the beginning of the input was checked for the wrong kind of
+  /// file.
+  sniffed: bool,
+  /// The last line whose solver messages were handed out.
+  reported_line: usize
+}
+
+impl<R: BufRead> BlockStream<R> {
+  /// Returns the file parsed so far, without the blocks handed out.
+  pub fn file(&self) -> &F06File {
+    return &self.parser.file;
+  }
+
+  /// Returns the number of lines consumed so far.
+  pub fn lines_read(&self) -> usize {
+    return self.parser.total_lines;
+  }
+
+  /// Returns the solver messages of a kind found since the last report.
+  fn messages_since(
+    &self,
+    messages: &BTreeMap<usize, String>
+  ) -> BTreeMap<usize, String> {
+    return messages.range(self.reported_line+1..)
+      .map(|(l, m)| (*l, m.clone()))
+      .collect();
+  }
+
+  /// Wraps a finished block with the messages found since the previous one.
+  fn hand_out(&mut self, block: FinalBlock) -> StreamedBlock {
+    let warnings = self.messages_since(&self.parser.file.warnings);
+    let fatal_errors = self.messages_since(&self.parser.file.fatal_errors);
+    self.reported_line = self.parser.total_lines;
+    return StreamedBlock { block, warnings, fatal_errors };
+  }
+
+  /// Reads a line, queueing the blocks it finishes (all of them, at the end
+  /// of the input). Returns `None` once the reader ran out or failed.
+  fn advance(&mut self) -> Option<Result<(), ParseError>> {
+    if self.done {
+      return None;
+    } else if !self.sniffed {
+      self.sniffed = true;
+      let wrong = match self.reader.fill_buf() {
+        Ok(head) => sniff(head),
+        Err(e) => {
+          self.done = true;
+          return Some(Err(ParseError::Io(e)));
+        }
+      };
+      if let Some(wrong) = wrong {
+        self.done = true;
+        return Some(Err(ParseError::WrongFile(wrong)));
+      }
+    }
+    match read_raw_line(&mut self.reader, &mut self.buf) {
+      Ok(Some((line, size))) => {
+        self.finished.extend(self.parser.feed_sized(&line, size));
+      },
+      Ok(None) => {
+        // the last block ends with the file
+        self.done = true;
+        self.finished.extend(self.parser.end_of_input());
+      },
+      Err(e) => {
+        self.done = true;
+        return Some(Err(ParseError::Io(e)));
+      }
+    };
+    return Some(Ok(()));
+  }
+
+  /// Finishes up and returns the file, with the blocks not handed out yet in
+  /// it. Lines not read yet are left alone.
+  pub fn finish(self) -> F06File {
+    return self.finish_with(Vec::new());
+  }
+
+  /// Finishes up and returns the file, with some blocks handed out earlier
+  /// put back into it, before the ones not handed out yet.
+  pub fn finish_with(
+    self,
+    blocks: impl IntoIterator<Item = StreamedBlock>
+  ) -> F06File {
+    let blocks = blocks.into_iter().map(|streamed| streamed.block)
+      .chain(self.finished);
+    return self.parser.finish_with(blocks);
+  }
+}
+
+impl<R: BufRead> Iterator for BlockStream<R> {
+  type Item = Result<StreamedBlock, ParseError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(block) = self.finished.pop_front() {
+        return Some(Ok(self.hand_out(block)));
+      }
+      if let Err(e) = self.advance()? {
+        return Some(Err(e));
+      }
+    }
+  }
+}
+
+/// Returns true if a line ends a block of a type, for a solver.
+pub(crate) fn ends_block(
+  solver: Solver,
+  block_type: BlockType,
+  line: &str
+) -> bool {
+  return solver.block_enders().iter().any(|s| line.contains(s))
+    && !solver.ender_exceptions().contains(&block_type);
+}
+
+/// Returns true if a line is debug output, from a solver (see
+/// `Solver::debug_line_prefixes`) or with one of some extra prefixes.
+pub(crate) fn is_debug_line(
+  solver: Option<Solver>,
+  extra: &[String],
+  line: &str
+) -> bool {
+  let trimmed = line.trim_start();
+  let known = solver.map_or(&[][..], |sv| sv.debug_line_prefixes());
+  return known.iter().any(|p| trimmed.starts_with(p))
+    || extra.iter().any(|p| trimmed.starts_with(p.as_str()));
+}
+
+/// Offers a piece of evidence to a flavour field, warning about conflicts
+/// and overrides.
+fn offer_evidence<T: Copy + PartialEq + Display>(
+  field: &mut FieldResolution<T>,
+  name: &str,
+  source: FlavourSource,
+  value: T,
+  line: Option<usize>
+) -> EvidenceOutcome {
+  let outcome = field.offer(source, value, line);
+  if outcome == EvidenceOutcome::Conflicts {
+    match line {
+      Some(l) => warn!(
+        "Line {} ({}) says the {} is {}, but it was resolved as {}!",
+        l,
+        source,
+        name,
+        value,
+        field
+      ),
+      None => warn!(
+        "The {} ({}) is {}, but it was resolved as {}!",
+        name,
+        source,
+        value,
+        field
+      ),
+    };
+  }
+  if outcome == EvidenceOutcome::Overridden {
+    warn!("The {} was changed to {} ({}) instead!", name, value, field);
+  }
+  return outcome;
 }
@@ -0,0 +1,396 @@
+//! This module implements post-processors: steps that run on a file after it
+//! is parsed (merging blocks, sorting them, checking them, etc.), and a
+//! registry that puts them in order.
+//!
+//! Each post-processor has a unique name, and declares tags it provides and
+//! tags it requires. Selecting some by name pulls in whatever provides their
+//! requirements, and they're run after those. Otherwise, they run in the
+//! order they were registered in.
+//!
+//! A file remembers which post-processors were run on it. Running one again
+//! is a no-op if it's idempotent, and an error otherwise -- so that, say,
+//! flipping signs twice can't happen by accident.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// The post-processors run by default, to tidy a file up.
+pub const DEFAULT_POST_PROCESSORS: &[&str] = &[
+  "merge_blocks",
+  "merge_potential_headers",
+  "sort_blocks"
+];
+
+/// A step that runs on a file after it's parsed.
+pub trait PostProcessor {
+  /// The name of this post-processor, unique within a registry.
+  fn name(&self) -> &str;
+
+  /// Tags for what this post-processor does, for others to require.
+  fn provides(&self) -> &[&str] {
+    return &[];
+  }
+
+  /// Tags that have to be provided before this post-processor runs.
+  fn requires(&self) -> &[&str] {
+    return &[];
+  }
+
+  /// Whether running this more than once is the same as running it once. If
+  /// not, running it on a file that already had it run is an error.
+  fn idempotent(&self) -> bool {
+    return false;
+  }
+
+  /// Runs on a file, returning diagnostics about it.
+  fn process(&self, file: &mut F06File) -> Vec<Diagnostic>;
+}
+
+/// An error setting up or running post-processors.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PostProcessError {
+  /// No post-processor has this name.
+  Unknown(String),
+  /// A post-processor with this name is already registered.
+  Duplicate(String),
+  /// Nothing provides a tag a post-processor requires.
+  Unprovided {
+    /// The post-processor.
+    processor: String,
+    /// The tag it requires.
+    tag: String
+  },
+  /// The requirements of these post-processors go around in circles.
+  Cycle(Vec<String>),
+  /// A post-processor that isn't idempotent was already run on the file.
+  AlreadyApplied(String)
+}
+
+impl Display for PostProcessError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::Unknown(name) => write!(f, "unknown post-processor \"{}\"", name),
+      Self::Duplicate(name) => {
+        write!(f, "post-processor \"{}\" is already registered", name)
+      },
+      Self::Unprovided { processor, tag } => write!(
+        f,
+        "post-processor \"{}\" requires \"{}\", which nothing provides",
+        processor,
+        tag
+      ),
+      Self::Cycle(names) => write!(
+        f,
+        "post-processors {} require each other",
+        names.join(", ")
+      ),
+      Self::AlreadyApplied(name) => write!(
+        f,
+        "post-processor \"{}\" was already run on the file",
+        name
+      ),
+    };
+  }
+}
+
+impl std::error::Error for PostProcessError {}
+
+/// Makes a diagnostic on behalf of a post-processor.
+pub fn post_diagnostic(
+  processor: &str,
+  line: Option<usize>,
+  text: &str
+) -> Diagnostic {
+  return Diagnostic {
+    kind: DiagnosticKind::PostProcessing {
+      processor: processor.to_owned(),
+      template: message_template(text)
+    },
+    line,
+    text: text.to_owned()
+  };
+}
+
+/// Checks the invariants of the file (see `F06File::check_invariants`). Only
+/// meaningful before merging blocks, so it's registered first.
+struct CheckInvariants;
+
+impl PostProcessor for CheckInvariants {
+  fn name(&self) -> &str {
+    return "check_invariants";
+  }
+
+  fn provides(&self) -> &[&str] {
+    return &["invariants_checked"];
+  }
+
+  fn idempotent(&self) -> bool {
+    return true;
+  }
+
+  fn process(&self, file: &mut F06File) -> Vec<Diagnostic> {
+    return file.check_invariants()
+      .into_iter()
+      .map(|v| {
+        let block = match &v {
+          InvariantViolation::OverlappingRanges { second, .. } => second,
+          InvariantViolation::RangeOutsideFile { block, .. } => block,
+          InvariantViolation::EmptyWithRange { block } => block,
+          InvariantViolation::RowOutOfBounds { block, .. } => block,
+          InvariantViolation::ColumnOutOfBounds { block, .. } => block
+        };
+        let line = block.line_range.map(|lr| lr.0);
+        return post_diagnostic(self.name(), line, &v.to_string());
+      })
+      .collect();
+  }
+}
+
+/// Merges blocks cleanly (see `F06File::merge_blocks`).
+struct MergeBlocks;
+
+impl PostProcessor for MergeBlocks {
+  fn name(&self) -> &str {
+    return "merge_blocks";
+  }
+
+  fn provides(&self) -> &[&str] {
+    return &["merged"];
+  }
+
+  fn idempotent(&self) -> bool {
+    return true;
+  }
+
+  fn process(&self, file: &mut F06File) -> Vec<Diagnostic> {
+    file.merge_blocks(true);
+    return Vec::new();
+  }
+}
+
+/// Merges potential headers (see `F06File::merge_potential_headers`).
+struct MergePotentialHeaders;
+
+impl PostProcessor for MergePotentialHeaders {
+  fn name(&self) -> &str {
+    return "merge_potential_headers";
+  }
+
+  fn provides(&self) -> &[&str] {
+    return &["merged_headers"];
+  }
+
+  fn idempotent(&self) -> bool {
+    return true;
+  }
+
+  fn process(&self, file: &mut F06File) -> Vec<Diagnostic> {
+    file.merge_potential_headers();
+    return Vec::new();
+  }
+}
+
+/// Sorts the rows and columns of all blocks, after merging them, since merges
+/// append rows.
+struct SortBlocks;
+
+impl PostProcessor for SortBlocks {
+  fn name(&self) -> &str {
+    return "sort_blocks";
+  }
+
+  fn provides(&self) -> &[&str] {
+    return &["sorted"];
+  }
+
+  fn requires(&self) -> &[&str] {
+    return &["merged"];
+  }
+
+  fn idempotent(&self) -> bool {
+    return true;
+  }
+
+  fn process(&self, file: &mut F06File) -> Vec<Diagnostic> {
+    file.sort_all_blocks();
+    return Vec::new();
+  }
+}
+
+/// A set of post-processors, to select and run by name.
+pub struct PostProcessorRegistry {
+  /// The post-processors, in the order they were registered.
+  processors: Vec<Box<dyn PostProcessor>>
+}
+
+impl Default for PostProcessorRegistry {
+  fn default() -> Self {
+    return Self::new();
+  }
+}
+
+impl PostProcessorRegistry {
+  /// Makes an empty registry.
+  pub fn new() -> Self {
+    return Self { processors: Vec::new() };
+  }
+
+  /// Makes a registry with the built-in post-processors.
+  pub fn builtin() -> Self {
+    return Self {
+      processors: vec![
+        Box::new(CheckInvariants),
+        Box::new(MergeBlocks),
+        Box::new(MergePotentialHeaders),
+        Box::new(SortBlocks),
+      ]
+    };
+  }
+
+  /// Registers a post-processor. Errs if the name is taken.
+  pub fn register(
+    &mut self,
+    processor: Box<dyn PostProcessor>
+  ) -> Result<(), PostProcessError> {
+    if self.get(processor.name()).is_some() {
+      return Err(PostProcessError::Duplicate(processor.name().to_owned()));
+    }
+    self.processors.push(processor);
+    return Ok(());
+  }
+
+  /// Returns a post-processor by name.
+  pub fn get(&self, name: &str) -> Option<&dyn PostProcessor> {
+    return self.processors.iter()
+      .find(|p| p.name() == name)
+      .map(|p| p.as_ref());
+  }
+
+  /// Returns the names of the registered post-processors, in order.
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    return self.processors.iter().map(|p| p.name());
+  }
+
+  /// Resolves the order to run some post-processors in, along with whatever
+  /// provides their requirements. Returns their positions in the registry.
+  fn plan_positions<S: AsRef<str>>(
+    &self,
+    names: &[S]
+  ) -> Result<Vec<usize>, PostProcessError> {
+    let position = |name: &str| self.processors.iter()
+      .position(|p| p.name() == name)
+      .ok_or(PostProcessError::Unknown(name.to_owned()));
+    // pull in the providers of requirements until there's nothing new
+    let mut selected: BTreeSet<usize> = BTreeSet::new();
+    let mut pending: Vec<usize> = names.iter()
+      .map(|n| position(n.as_ref()))
+      .collect::<Result<_, _>>()?;
+    while let Some(ix) = pending.pop() {
+      if !selected.insert(ix) {
+        continue;
+      }
+      let proc = &self.processors[ix];
+      for tag in proc.requires() {
+        let providers = self.providers(tag);
+        if providers.is_empty() {
+          return Err(PostProcessError::Unprovided {
+            processor: proc.name().to_owned(),
+            tag: (*tag).to_owned()
+          });
+        }
+        // any selected provider will do, else the first registered
+        if !providers.iter().any(|p| selected.contains(p)) {
+          pending.push(providers[0]);
+        }
+      }
+    }
+    // each one runs after all selected providers of its requirements
+    let deps: BTreeMap<usize, BTreeSet<usize>> = selected.iter()
+      .map(|ix| {
+        let before = self.processors[*ix].requires()
+          .iter()
+          .flat_map(|tag| self.providers(tag))
+          .filter(|p| selected.contains(p) && p != ix)
+          .collect();
+        return (*ix, before);
+      })
+      .collect();
+    // kahn's algorithm, taking the earliest registered one that's ready
+    let mut order: Vec<usize> = Vec::new();
+    let mut left = selected;
+    while !left.is_empty() {
+      let ready = left.iter()
+        .copied()
+        .find(|ix| deps[ix].iter().all(|d| !left.contains(d)));
+      match ready {
+        Some(ix) => {
+          left.remove(&ix);
+          order.push(ix);
+        },
+        None => {
+          let names = left.iter()
+            .map(|ix| self.processors[*ix].name().to_owned())
+            .collect();
+          return Err(PostProcessError::Cycle(names));
+        }
+      }
+    }
+    return Ok(order);
+  }
+
+  /// Returns the positions of the post-processors that provide a tag.
+  fn providers(&self, tag: &str) -> Vec<usize> {
+    return self.processors.iter()
+      .enumerate()
+      .filter(|(_, p)| p.provides().contains(&tag))
+      .map(|(i, _)| i)
+      .collect();
+  }
+
+  /// Resolves the order to run some post-processors in, along with whatever
+  /// provides their requirements. Returns their names.
+  pub fn plan<S: AsRef<str>>(
+    &self,
+    names: &[S]
+  ) -> Result<Vec<&str>, PostProcessError> {
+    return Ok(
+      self.plan_positions(names)?
+        .into_iter()
+        .map(|ix| self.processors[ix].name())
+        .collect()
+    );
+  }
+
+  /// Runs some post-processors on a file, in order, along with whatever
+  /// provides their requirements. Their diagnostics are added to the file's
+  /// and returned. Nothing is run if any of them can't be.
+  pub fn run<S: AsRef<str>>(
+    &self,
+    names: &[S],
+    file: &mut F06File
+  ) -> Result<Vec<Diagnostic>, PostProcessError> {
+    let order = self.plan_positions(names)?
+      .into_iter()
+      .map(|ix| self.processors[ix].as_ref())
+      .filter(|p| !(p.idempotent() && file.was_post_processed(p.name())))
+      .collect::<Vec<_>>();
+    if let Some(p) = order.iter().find(|p| file.was_post_processed(p.name())) {
+      return Err(PostProcessError::AlreadyApplied(p.name().to_owned()));
+    }
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    for proc in order {
+      let found = proc.process(file);
+      for diag in found.iter() {
+        file.diagnostics.record(diag);
+      }
+      diagnostics.extend(found);
+      file.post_processed.push(proc.name().to_owned());
+    }
+    return Ok(diagnostics);
+  }
+}
@@ -0,0 +1,279 @@
+//! This module implements serialisable summaries of a parsed file (what
+//! `f06info` prints out) and manifests of the artifacts a tool produced from
+//! it, so that tools can write them out in whatever format they like.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// Summary of a single block.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BlockSummary {
+  /// The subcase it's in.
+  pub subcase: usize,
+  /// The block type.
+  pub block_type: BlockType,
+  /// The block type's description.
+  pub description: String,
+  /// Number of rows.
+  pub rows: usize,
+  /// Number of columns.
+  pub columns: usize,
+  /// The line range, if known.
+  pub line_range: Option<(usize, usize)>,
+  /// The byte range, if known.
+  #[serde(default)]
+  pub byte_range: Option<(u64, u64)>,
+  /// The relative precision the values were printed with, if known.
+  pub print_precision: Option<f64>,
+  /// The time step or frequency it was printed at, if any.
+  #[serde(default)]
+  pub step: Option<Step>,
+  /// Its position along the output axis of its subcase, if it has a step.
+  #[serde(default)]
+  pub axis_position: Option<usize>
+}
+
+impl From<&FinalBlock> for BlockSummary {
+  fn from(block: &FinalBlock) -> Self {
+    return Self {
+      subcase: block.subcase,
+      block_type: block.block_type,
+      description: block.block_type.desc().to_owned(),
+      rows: block.row_indexes.len(),
+      columns: block.col_indexes.len(),
+      line_range: block.line_range,
+      byte_range: block.byte_range,
+      print_precision: block.print_precision,
+      step: block.step,
+      axis_position: None
+    };
+  }
+}
+
+/// Summary of the output axis of a subcase of a dynamic analysis: its span,
+/// its median interval and the gaps in it.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TransientSummary {
+  /// The subcase.
+  pub subcase: usize,
+  /// The first and last values of the axis.
+  pub span: Option<(f64, f64)>,
+  /// The number of steps.
+  pub count: usize,
+  /// The median interval between steps, if there are at least two.
+  pub median_interval: Option<f64>,
+  /// The gaps found, against the median interval or the expected one.
+  pub gaps: Vec<AxisGap>
+}
+
+impl TransientSummary {
+  /// Summarises an output axis, finding gaps against an expected interval or,
+  /// if none is given, the median one.
+  pub fn new(axis: &OutputAxis, expected_dt: Option<f64>) -> Self {
+    return Self {
+      subcase: axis.subcase,
+      span: axis.span(),
+      count: axis.len(),
+      median_interval: axis.median_interval(),
+      gaps: axis.gaps(expected_dt)
+    };
+  }
+}
+
+/// A summary of a parsed file: flavour, messages, blocks and unsupported
+/// headers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FileReport {
+  /// Name of the file, if known.
+  pub filename: Option<String>,
+  /// The fingerprint of the parsed file (see `F06File::fingerprint`), as it
+  /// was before merging, if known. It's not filled in from a file, which may
+  /// have been merged since.
+  #[serde(default)]
+  pub fingerprint: Option<String>,
+  /// The flavour of the file.
+  pub flavour: Flavour,
+  /// How the flavour was resolved.
+  pub flavour_resolution: FlavourResolution,
+  /// Number of warnings found.
+  pub num_warnings: usize,
+  /// The fatal errors found, by line.
+  pub fatal_errors: BTreeMap<usize, String>,
+  /// Byte offsets of the lines with warning and fatal error messages, by
+  /// line number.
+  #[serde(default)]
+  pub message_offsets: BTreeMap<usize, u64>,
+  /// The aggregated diagnostics.
+  pub diagnostics: Diagnostics,
+  /// All the subcases.
+  pub subcases: Vec<usize>,
+  /// Summaries of all blocks, ordered by subcase.
+  pub blocks: Vec<BlockSummary>,
+  /// Potential headers for unsupported blocks, with the lines where each
+  /// first appeared.
+  pub potential_headers: BTreeMap<String, usize>,
+  /// The byte offsets of the lines where each potential header first
+  /// appeared.
+  #[serde(default)]
+  pub potential_header_offsets: BTreeMap<String, u64>,
+  /// Statistics about the run, as reported by the solver.
+  pub run_stats: RunStats,
+  /// The checks on the solution of each subcase (epsilon and external work).
+  #[serde(default)]
+  pub solution_diagnostics: BTreeMap<usize, SolutionDiagnostics>,
+  /// Summaries of the output axes of the subcases with time steps or
+  /// frequencies.
+  #[serde(default)]
+  pub transient: Vec<TransientSummary>
+}
+
+impl From<&F06File> for FileReport {
+  fn from(file: &F06File) -> Self {
+    let mut potential_headers: BTreeMap<String, usize> = BTreeMap::new();
+    let mut potential_header_offsets: BTreeMap<String, u64> = BTreeMap::new();
+    // they're sorted by line, so the first one seen is the first one
+    for ph in file.potential_headers.iter() {
+      if !potential_headers.contains_key(&ph.text) {
+        potential_headers.insert(ph.text.clone(), ph.start);
+        potential_header_offsets.insert(ph.text.clone(), ph.offset);
+      }
+    }
+    let axes = file.output_axes();
+    let blocks = file.all_blocks(false)
+      .map(|block| BlockSummary {
+        axis_position: axis_position(&axes, block),
+        ..BlockSummary::from(block)
+      })
+      .collect();
+    return Self {
+      filename: file.filename.clone(),
+      fingerprint: None,
+      flavour: file.flavour,
+      flavour_resolution: file.flavour_resolution.clone(),
+      num_warnings: file.warnings.len(),
+      fatal_errors: file.fatal_errors.clone(),
+      message_offsets: file.message_offsets.clone(),
+      diagnostics: file.diagnostics.clone(),
+      subcases: file.subcases().collect(),
+      blocks,
+      potential_headers,
+      potential_header_offsets,
+      run_stats: file.run_stats.clone(),
+      solution_diagnostics: file.solution_diagnostics.clone(),
+      transient: axes.values()
+        .map(|axis| TransientSummary::new(axis, None))
+        .collect()
+    };
+  }
+}
+
+/// The kinds of artifact a tool can produce.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord
+)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ArtifactKind {
+  /// CSV data.
+  Csv,
+  /// A file report.
+  Report,
+  /// A human-readable summary of the run, as an HTML page.
+  HtmlReport,
+  /// A manifest of the other artifacts.
+  Manifest
+}
+
+impl Display for ArtifactKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(f, "{}", match self {
+      Self::Csv => "CSV",
+      Self::Report => "report",
+      Self::HtmlReport => "HTML report",
+      Self::Manifest => "manifest",
+    });
+  }
+}
+
+/// An artifact that was (or was meant to be) produced.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Artifact {
+  /// What it is.
+  pub kind: ArtifactKind,
+  /// Where it was written to.
+  pub path: String,
+  /// Its size, if it was written.
+  pub bytes: Option<u64>,
+  /// The error that stopped it from being written, if any.
+  pub error: Option<String>
+}
+
+impl Artifact {
+  /// Was it written without errors?
+  pub fn ok(&self) -> bool {
+    return self.error.is_none();
+  }
+}
+
+/// An input the artifacts were produced from, and how its flavour was
+/// resolved.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ManifestInput {
+  /// The path to it ("-" for standard input).
+  pub path: String,
+  /// The flavour it was resolved as, as a spec (see `Flavour::spec`), if it
+  /// was parsed.
+  pub flavour: Option<String>,
+  /// How each field of the flavour was resolved, if it was parsed.
+  pub resolution: Option<FlavourResolution>,
+  /// Why it was left out, if it was (e.g. it's not of the required flavour).
+  pub error: Option<String>,
+  /// The fingerprint of the parsed file (see `F06File::fingerprint`), if it
+  /// was converted.
+  #[serde(default)]
+  pub fingerprint: Option<String>
+}
+
+/// A manifest of the artifacts produced from the input files (usually just
+/// one), each converted on its own.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Manifest {
+  /// The input file, if known and the only one.
+  pub input: Option<String>,
+  /// The tool that produced the artifacts.
+  pub tool: String,
+  /// The tool's version.
+  pub version: String,
+  /// The fingerprint of the parsed file (see `F06File::fingerprint`), if
+  /// known and the only one.
+  #[serde(default)]
+  pub fingerprint: Option<String>,
+  /// The inputs, and the flavour each was resolved as.
+  #[serde(default)]
+  pub inputs: Vec<ManifestInput>,
+  /// The artifacts, in the order they were produced.
+  pub artifacts: Vec<Artifact>
+}
+
+impl Manifest {
+  /// Starts an empty manifest.
+  pub fn new(input: Option<String>, tool: &str, version: &str) -> Self {
+    return Self {
+      input,
+      tool: tool.to_owned(),
+      version: version.to_owned(),
+      fingerprint: None,
+      inputs: Vec::new(),
+      artifacts: Vec::new()
+    };
+  }
+
+  /// Were all artifacts written without errors?
+  pub fn all_ok(&self) -> bool {
+    return self.artifacts.iter().all(Artifact::ok);
+  }
+}
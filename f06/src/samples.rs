@@ -0,0 +1,236 @@
+//! This module generates tiny, valid F06 outputs, for docs and for testing
+//! code downstream without shipping real solver output. Each generator is
+//! deterministic, and the shapes of the blocks the parser decodes from it are
+//! documented (and tested in this crate).
+//!
+//! The plate stresses are chosen so that the derived quantities (principal
+//! stresses and von Mises) are exact, e.g. normal-x = normal-y = 110 and
+//! shear-xy = 50 give principals 160 and 60, and von Mises 140.
+//!
+//! Only compiled with the `samples` feature.
+
+/// A plate stress state at a fibre: fibre distance, normal-x, normal-y and
+/// shear-xy. The rest is derived.
+type PlateState = (f64, f64, f64, f64);
+
+/// The plate stress states at the centroids of the sample quads: element ID,
+/// then the bottom and top fibres.
+const QUAD_STATES: &[(usize, PlateState, PlateState)] = &[
+  (1, (-5.0e-2, 110.0, 110.0, 50.0), (5.0e-2, -110.0, -110.0, -50.0)),
+  (2, (-5.0e-2, 50.0, -30.0, 0.0), (5.0e-2, -50.0, 30.0, 0.0)),
+];
+
+/// Writes a real the way Nastran does, e.g. "1.500000E+02", with some digits
+/// after the point. Zero is written as "0.0".
+fn nastran_real(x: f64, digits: usize) -> String {
+  if x == 0.0 {
+    return "0.0".to_owned();
+  }
+  let formatted = format!("{:.*E}", digits, x);
+  let (mantissa, exponent) = formatted.split_once('E')
+    .expect("rust always writes an exponent");
+  let exponent: i32 = exponent.parse().expect("rust writes valid exponents");
+  let sign = if exponent < 0 { '-' } else { '+' };
+  return format!("{}E{}{:02}", mantissa, sign, exponent.abs());
+}
+
+/// Writes a real in a field where positive numbers leave a blank for the
+/// sign, left-aligned in a certain width.
+fn signed_field(x: f64, digits: usize, width: usize) -> String {
+  let real = nastran_real(x, digits);
+  let signed = if real.starts_with('-') { real } else { format!(" {}", real) };
+  return format!("{:<w$}", signed, w = width);
+}
+
+/// Derives the angle, major, minor and von Mises stresses of a plate stress
+/// state.
+fn derive_plate(state: PlateState) -> [f64; 4] {
+  let (_, sx, sy, txy) = state;
+  let centre = (sx + sy) / 2.0;
+  let radius = (((sx - sy) / 2.0).powi(2) + txy.powi(2)).sqrt();
+  let angle = (2.0 * txy).atan2(sx - sy).to_degrees() / 2.0;
+  let vm = (sx * sx - sx * sy + sy * sy + 3.0 * txy * txy).sqrt();
+  return [angle, centre + radius, centre - radius, vm];
+}
+
+/// The MYSTRAN banner.
+fn mystran_banner() -> String {
+  return "\n MYSTRAN Version 15.1.3\n".to_owned();
+}
+
+/// A MYSTRAN subcase line.
+fn mystran_subcase(subcase: usize) -> String {
+  return format!("\n OUTPUT FOR SUBCASE {:>8}\n\n", subcase);
+}
+
+/// A MYSTRAN table of six values per grid point, under some header.
+fn mystran_grid_table(header: &str, rows: &[(usize, [f64; 6])]) -> String {
+  let mut out = format!("{:>55}{}\n", "", header);
+  out.push_str(&format!(
+    "{:>46}(in global coordinate system at each grid)\n",
+    ""
+  ));
+  out.push_str(concat!(
+    "           GRID     COORD      T1            T2            T3",
+    "            R1            R2            R3\n",
+    "                     SYS\n"
+  ));
+  for (gid, values) in rows {
+    out.push_str(&format!("{:>15}{:>9}", gid, 0));
+    for v in values {
+      out.push(' ');
+      out.push_str(&signed_field(*v, 6, 13));
+    }
+    out.push('\n');
+  }
+  out.push_str(&format!("{:>25}{}\n", "", ["-------------"; 6].join(" ")));
+  return out;
+}
+
+/// A MYSTRAN table of quad stresses at the centroids, scaled by a factor.
+fn mystran_quad_stresses(scale: f64) -> String {
+  let mut out = format!(
+    "{:>42}{}\n{:>71}{}\n",
+    "",
+    "E L E M E N T   S T R E S S E S   I N   L O C A L   E L E M E N T   \
+    C O O R D I N A T E   S Y S T E M",
+    "",
+    "F O R   E L E M E N T   T Y P E   Q U A D 4"
+  );
+  out.push_str(concat!(
+    "    Elem  Location       Fibre       Stresses In Element Coord System",
+    "     Principal Stresses (Zero Shear)               Transverse",
+    "   Transverse\n",
+    "     ID                 Distance    Normal-X     Normal-Y     Shear-XY",
+    "     Angle     Major        Minor      von Mises    Shear-XZ",
+    "     Shear-YZ\n\n"
+  ));
+  for (eid, bottom, top) in QUAD_STATES {
+    for (i, state) in [bottom, top].into_iter().enumerate() {
+      let (fibre, sx, sy, txy) = *state;
+      let state = (fibre, sx * scale, sy * scale, txy * scale);
+      let [angle, major, minor, vm] = derive_plate(state);
+      if i == 0 {
+        out.push_str(&format!("{:>9}  CENTER    ", eid));
+      } else {
+        out.push_str(&format!("{:>21}", ""));
+      }
+      out.push_str(&format!("{:>12}", nastran_real(fibre, 3)));
+      for v in [state.1, state.2, state.3] {
+        out.push_str(&format!("{:>13}", nastran_real(v, 5)));
+      }
+      out.push_str(&format!("{:>9.2}", angle));
+      for v in [major, minor, vm] {
+        out.push_str(&format!("{:>13}", nastran_real(v, 5)));
+      }
+      if i == 0 {
+        out.push_str("  0.0          0.0");
+      }
+      out.push('\n');
+    }
+    out.push('\n');
+  }
+  return out;
+}
+
+/// The first line of a Simcenter page.
+fn simcenter_page_header(page: usize) -> String {
+  return format!(
+    "1    SAMPLE STATIC RUN{:>33}SIMCENTER NASTRAN  2/11/21   PAGE {:>5}\n",
+    "",
+    page
+  );
+}
+
+/// A minimal MYSTRAN static run: one subcase (1), with displacements for
+/// grids 1 to 4 (grids 1 and 2 clamped) and SPC forces for grids 1 and 2.
+///
+/// The parser decodes it into two blocks in subcase 1:
+///   - `Displacements`, 4 rows (grid points 1 to 4) by 6 columns (the DOFs);
+///   - `SpcForces`, 2 rows (grid points 1 and 2) by 6 columns (the DOFs).
+pub fn minimal_static() -> String {
+  let displacements = [
+    (1, [0.0; 6]),
+    (2, [0.0; 6]),
+    (3, [1.0e-3, -2.5e-3, 0.0, 0.0, 0.0, 5.0e-4]),
+    (4, [-1.0e-3, -2.5e-3, 0.0, 0.0, 0.0, -5.0e-4]),
+  ];
+  let spc_forces = [
+    (1, [-5.0e1, 1.0e2, 0.0, 0.0, 0.0, 2.5e1]),
+    (2, [5.0e1, 1.0e2, 0.0, 0.0, 0.0, -2.5e1]),
+  ];
+  let mut out = mystran_banner();
+  out.push_str(&mystran_subcase(1));
+  out.push_str(&mystran_grid_table(
+    "D I S P L A C E M E N T S",
+    &displacements
+  ));
+  out.push_str(&mystran_subcase(1));
+  out.push_str(&mystran_grid_table(
+    "   S P C   F O R C E S",
+    &spc_forces
+  ));
+  return out;
+}
+
+/// A MYSTRAN run with two subcases (1 and 2, the second with twice the load)
+/// and the stresses in two quads (1 and 2) at their centroids, at both
+/// fibres.
+///
+/// The parser decodes it into one `QuadStresses` block per subcase, each with
+/// 4 rows (elements 1 and 2, centroid, bottom and top) by 8 columns (fibre
+/// distance, normal-x, normal-y, shear-xy, angle, major, minor, von Mises).
+/// The principal and von Mises stresses are exact, and so are the angles (45
+/// and -45 degrees for element 1, 0 and 90 for element 2).
+pub fn two_subcase_shell() -> String {
+  let mut out = mystran_banner();
+  for (subcase, scale) in [(1, 1.0), (2, 2.0)] {
+    out.push_str(&mystran_subcase(subcase));
+    out.push_str(&mystran_quad_stresses(scale));
+  }
+  return out;
+}
+
+/// A Simcenter Nastran static run: one subcase (1), with
+/// displacements for grids 1 to 3 (grid 1 clamped) and SPC forces for grid 1,
+/// each on its own page.
+///
+/// The parser decodes it into two blocks in subcase 1:
+///   - `Displacements`, 3 rows (grid points 1 to 3) by 6 columns (the DOFs);
+///   - `SpcForces`, 1 row (grid point 1) by 6 columns (the DOFs).
+pub fn simcenter_static() -> String {
+  let pages = [
+    (
+      "D I S P L A C E M E N T   V E C T O R",
+      vec![
+        (1, [0.0; 6]),
+        (2, [1.0e-3, 0.0, -2.0e-3, 0.0, 4.0e-4, 0.0]),
+        (3, [2.0e-3, 0.0, -8.0e-3, 0.0, 8.0e-4, 0.0]),
+      ]
+    ),
+    (
+      "F O R C E S   O F   S I N G L E - P O I N T   C O N S T R A I N T",
+      vec![(1, [-1.0e3, 0.0, 5.0e2, 0.0, -2.0e3, 0.0])]
+    ),
+  ];
+  let mut out = format!("\n{:>22}Welcome to Simcenter Nastran\n", "");
+  for (page, (header, rows)) in pages.iter().enumerate() {
+    out.push_str(&simcenter_page_header(page + 1));
+    out.push_str(&format!("0{:>80}SUBCASE 1\n \n", ""));
+    out.push_str(&format!("{:>35}{}\n \n", "", header));
+    out.push_str(concat!(
+      "      POINT ID.   TYPE          T1             T2             T3",
+      "             R1             R2             R3\n"
+    ));
+    for (gid, values) in rows {
+      out.push_str(&format!("{:>14}      G   ", gid));
+      for v in values {
+        out.push_str("  ");
+        out.push_str(&signed_field(*v, 6, 13));
+      }
+      out.push('\n');
+    }
+  }
+  out.push_str(&simcenter_page_header(pages.len() + 1));
+  return out;
+}
@@ -0,0 +1,219 @@
+//! This module implements recognising files that aren't F06 text output at
+//! all, like the OP2 binary that sits next to the F06, from their first few
+//! kilobytes, so that the parser can stop right away with a helpful error
+//! instead of reading through them and finding nothing.
+
+use std::fmt::Display;
+
+use serde::{Serialize, Deserialize};
+
+/// How many bytes from the beginning of a file are inspected.
+pub const SNIFF_LEN: usize = 4096;
+
+/// The fraction of inspected bytes that have to be control characters (other
+/// than tabs, line breaks and form feeds) for a file to count as binary.
+pub const BINARY_RATIO: f64 = 0.1;
+
+/// The magic number at the beginning of HDF5 files.
+const HDF5_MAGIC: &[u8] = b"\x89HDF\r\n\x1a\n";
+
+/// Magic numbers of compressed files, and their formats.
+const COMPRESSED_MAGICS: &[(&[u8], &str)] = &[
+  (b"\x1f\x8b", "gzip"),
+  (b"PK\x03\x04", "zip"),
+  (b"\xfd7zXZ\x00", "xz"),
+  (b"BZh", "bzip2"),
+  (b"\x28\xb5\x2f\xfd", "zstd"),
+  (b"7z\xbc\xaf\x27\x1c", "7-zip"),
+];
+
+/// The label written in the header of OP2 files.
+const OP2_LABEL: &str = "NASTRAN FORT TAPE ID CODE";
+
+/// Names of tables commonly found in OP2 files.
+const OP2_TABLES: &[&str] = &[
+  "OUGV1", "OES1X1", "OES1C", "OSTR1X", "OEF1X", "OQG1", "OPG1", "ONRGY1",
+  "LAMA", "BOUGV1", "GEOM1", "GEOM2", "EPT", "MPT", "PVT0", "CASECC"
+];
+
+/// Statements that begin the executive or case control sections of input
+/// decks, at the very beginning of a line.
+const DECK_STATEMENTS: &[&str] = &[
+  "ID", "SOL", "TIME", "CEND", "INIT", "ASSIGN", "NASTRAN", "INCLUDE",
+  "DIAG", "COMPILE", "ALTER", "APP", "BEGIN", "ENDDATA", "ECHO", "TITLE",
+  "SUBCASE"
+];
+
+/// Common bulk data cards, which begin lines in input decks and in files
+/// with only the bulk data.
+const BULK_CARDS: &[&str] = &[
+  "GRID", "CORD2R", "CORD2C", "CQUAD4", "CQUAD8", "CTRIA3", "CTRIA6",
+  "CBAR", "CBEAM", "CROD", "CONROD", "CHEXA", "CPENTA", "CTETRA", "CBUSH",
+  "CELAS1", "CELAS2", "CSHEAR", "CGAP", "RBE2", "RBE3", "CONM2", "MAT1",
+  "MAT8", "PSHELL", "PCOMP", "PBAR", "PBARL", "PBEAM", "PBEAML", "PROD",
+  "PSOLID", "PBUSH", "SPC", "SPC1", "FORCE", "MOMENT", "PLOAD4", "LOAD",
+  "TEMP", "TEMPD", "EIGRL", "PARAM"
+];
+
+/// A kind of file that isn't F06 text output, recognised from its first few
+/// kilobytes.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WrongFile {
+  /// An OP2 (OUTPUT2) binary results file.
+  Op2 {
+    /// What gave it away.
+    signature: String
+  },
+  /// An HDF5 file, like the results databases of MSC Nastran.
+  Hdf5,
+  /// A compressed file.
+  Compressed {
+    /// The compression format.
+    format: String
+  },
+  /// A solver input deck (a .bdf or .dat file), or just its bulk data.
+  InputDeck {
+    /// The first statement or card in it.
+    first_line: String
+  },
+  /// Some other binary file.
+  Binary {
+    /// How many of the inspected bytes were control characters.
+    control: usize,
+    /// How many bytes were inspected.
+    inspected: usize
+  }
+}
+
+impl Display for WrongFile {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::Op2 { signature } => write!(
+        f,
+        "this looks like an OP2 binary results file (found {}); this tool \
+        parses F06 text output",
+        signature
+      ),
+      Self::Hdf5 => write!(
+        f,
+        "this looks like an HDF5 file (e.g. an MSC Nastran .h5 results \
+        database); this tool parses F06 text output"
+      ),
+      Self::Compressed { format } => write!(
+        f,
+        "this looks like a {} compressed file; decompress it first, this \
+        tool parses F06 text output",
+        format
+      ),
+      Self::InputDeck { first_line } => write!(
+        f,
+        "this looks like a solver input deck (.bdf/.dat), starting with \
+        \"{}\"; this tool parses the F06 text output the solver writes when \
+        running it",
+        first_line
+      ),
+      Self::Binary { control, inspected } => write!(
+        f,
+        "this looks like a binary file ({} of the first {} bytes are control \
+        characters); this tool parses F06 text output",
+        control,
+        inspected
+      ),
+    };
+  }
+}
+
+impl std::error::Error for WrongFile {}
+
+/// Returns true if a byte is a control character that doesn't belong in text.
+fn is_control(byte: u8) -> bool {
+  return (byte < 0x20 && !b"\t\n\r\x0c".contains(&byte)) || byte == 0x7f;
+}
+
+/// Returns the number of control characters in some bytes.
+fn count_control(head: &[u8]) -> usize {
+  return head.iter().filter(|b| is_control(**b)).count();
+}
+
+/// Looks for an OP2 signature: the label in its header, the Fortran record
+/// markers it begins with, or (in binary content) the names of its tables.
+fn op2_signature(head: &[u8], binary: bool) -> Option<String> {
+  let text = String::from_utf8_lossy(head);
+  if text.contains(OP2_LABEL) {
+    return Some(format!("the \"{}\" label", OP2_LABEL));
+  }
+  // a 4-byte record, between markers saying so
+  if head.len() >= 12 && head[..4] == head[8..12] {
+    match head[..4] {
+      [4, 0, 0, 0] => {
+        return Some("Fortran record markers, little-endian".to_owned());
+      },
+      [0, 0, 0, 4] => {
+        return Some("Fortran record markers, big-endian".to_owned());
+      },
+      _ => {}
+    }
+  }
+  if binary {
+    if let Some(table) = OP2_TABLES.iter().find(|t| text.contains(*t)) {
+      return Some(format!("the {} table", table));
+    }
+  }
+  return None;
+}
+
+/// Returns true if a line begins with one of some words, right at the first
+/// column, followed by a separator (so that cut lines never match).
+fn starts_with_word(line: &str, words: &[&str]) -> bool {
+  return words.iter().any(|w| {
+    return line.strip_prefix(w)
+      .and_then(|rest| rest.chars().next())
+      .is_some_and(|c| c == ' ' || c == ',' || c == '*' || c == '=');
+  });
+}
+
+/// Looks for an input deck: the first meaningful line (not blank, not a
+/// comment) is a statement or a card, right at the first column. F06 files
+/// might echo the deck, but never begin with it.
+fn deck_first_line(head: &[u8]) -> Option<String> {
+  let text = String::from_utf8_lossy(head);
+  let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+  let mut lines = text.lines();
+  // the last line might have been cut, so it doesn't count
+  if !text.ends_with('\n') {
+    lines.next_back();
+  }
+  let first = lines
+    .find(|l| !l.trim().is_empty() && !l.starts_with('$'))?
+    .trim_end();
+  let upper = first.to_ascii_uppercase();
+  let deck = starts_with_word(&upper, DECK_STATEMENTS)
+    || upper == "CEND"
+    || starts_with_word(first, BULK_CARDS);
+  return if deck { Some(first.to_owned()) } else { None };
+}
+
+/// Inspects the beginning of a file (up to `SNIFF_LEN` bytes of it), and
+/// returns what it is if it's clearly not F06 text output.
+pub fn sniff(head: &[u8]) -> Option<WrongFile> {
+  let head = &head[..head.len().min(SNIFF_LEN)];
+  if head.starts_with(HDF5_MAGIC) {
+    return Some(WrongFile::Hdf5);
+  }
+  for (magic, format) in COMPRESSED_MAGICS {
+    if head.starts_with(magic) {
+      return Some(WrongFile::Compressed { format: (*format).to_owned() });
+    }
+  }
+  let control = count_control(head);
+  let binary = control as f64 > BINARY_RATIO * head.len() as f64;
+  if let Some(signature) = op2_signature(head, binary) {
+    return Some(WrongFile::Op2 { signature });
+  }
+  if binary {
+    return Some(WrongFile::Binary { control, inspected: head.len() });
+  }
+  return deck_first_line(head)
+    .map(|first_line| WrongFile::InputDeck { first_line });
+}
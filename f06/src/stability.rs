@@ -0,0 +1,578 @@
+//! This module implements the checks that keep the crates from breaking the
+//! ones that depend on them, without any tools beyond the test harness.
+//!
+//! The public API is snapshotted by scanning the sources for public
+//! declarations, starting at `lib.rs` and following the public modules. Each
+//! crate checks its declarations in against an `api.txt`, one per line, and a
+//! test fails with the difference when they change.
+//!
+//! The minimum supported Rust version (MSRV) is declared as the
+//! `rust-version` of every manifest in the workspace, and guarded by a list
+//! of constructs stabilised after it, which the sources mustn't contain.
+
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The minimum supported Rust version, as its major and minor numbers.
+pub const MSRV: (u32, u32) = (1, 87);
+
+/// Constructs stabilised in some Rust version: text that gives their use
+/// away, the version, and what they are. The ones newer than the MSRV are the
+/// ones the sources mustn't contain.
+pub const VERSIONED_CONSTRUCTS: &[(&str, (u32, u32), &str)] = &[
+  ("is_none_or(", (1, 82), "Option::is_none_or"),
+  ("is_multiple_of(", (1, 87), "unsigned integer is_multiple_of"),
+  ("&& let ", (1, 88), "let chains"),
+  ("cfg(true)", (1, 88), "boolean literals in cfg"),
+  ("cfg(false)", (1, 88), "boolean literals in cfg"),
+  (".as_chunks(", (1, 88), "slice as_chunks"),
+  (".as_rchunks(", (1, 88), "slice as_rchunks"),
+  ("select_unpredictable(", (1, 88), "hint::select_unpredictable"),
+  ("; _]", (1, 89), "inferred array lengths"),
+  ("NonNull::from_ref(", (1, 89), "NonNull::from_ref"),
+  ("_sub_signed(", (1, 90), "unsigned integer *_sub_signed"),
+  ("Duration::from_mins(", (1, 91), "Duration::from_mins"),
+  ("Duration::from_hours(", (1, 91), "Duration::from_hours")
+];
+
+/// The environment variable that makes a snapshot check rewrite the snapshot
+/// instead of failing.
+pub const BLESS_VAR: &str = "BLESS_API";
+
+/// A use of a construct stabilised after the MSRV.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MsrvViolation {
+  /// The line it's on, 1-based.
+  pub line: usize,
+  /// What the construct is.
+  pub construct: &'static str,
+  /// The version that stabilised it.
+  pub since: (u32, u32)
+}
+
+impl Display for MsrvViolation {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(
+      f,
+      "line {}: {} needs Rust {}.{}, but the MSRV is {}.{}",
+      self.line,
+      self.construct,
+      self.since.0,
+      self.since.1,
+      MSRV.0,
+      MSRV.1
+    );
+  }
+}
+
+/// Returns the uses of constructs stabilised after the MSRV in some source,
+/// ignoring comments and literals.
+pub fn newer_than_msrv(source: &str) -> Vec<MsrvViolation> {
+  let mut violations = Vec::new();
+  for (i, code) in code_lines(source).iter().enumerate() {
+    for (pattern, since, construct) in VERSIONED_CONSTRUCTS {
+      if *since > MSRV && code.contains(pattern) {
+        let since = *since;
+        violations.push(MsrvViolation { line: i + 1, construct, since });
+      }
+    }
+  }
+  return violations;
+}
+
+/// Returns the version in a `rust-version` field, as its major and minor
+/// numbers.
+pub fn parse_rust_version(version: &str) -> Option<(u32, u32)> {
+  let mut parts = version.split('.');
+  let major = parts.next()?.parse().ok()?;
+  let minor = parts.next().unwrap_or("0").parse().ok()?;
+  return Some((major, minor));
+}
+
+/// Where the lexer is, between lines.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum LexState {
+  /// In code.
+  Code,
+  /// In a string literal.
+  Str,
+  /// In a raw string literal, closed by a quote and that many hashes.
+  RawStr(usize),
+  /// In block comments, this deep.
+  Comment(usize)
+}
+
+/// Returns the lines of some source with the comments and the contents of
+/// string and character literals removed, so that only code is left.
+fn code_lines(source: &str) -> Vec<String> {
+  let mut state = LexState::Code;
+  let mut lines = Vec::new();
+  for line in source.lines() {
+    let chars: Vec<char> = line.chars().collect();
+    let mut code = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+      let c = chars[i];
+      let next = chars.get(i + 1).copied();
+      match state {
+        LexState::Code => match (c, next) {
+          ('/', Some('/')) => break,
+          ('/', Some('*')) => {
+            state = LexState::Comment(1);
+            i += 1;
+          },
+          ('"', _) => {
+            code.push('"');
+            state = LexState::Str;
+          },
+          ('r', Some('"' | '#')) if !continues_ident(&chars, i) => {
+            let hashes = chars[i+1..].iter().take_while(|c| **c == '#').count();
+            if chars.get(i + 1 + hashes) == Some(&'"') {
+              code.push('"');
+              state = LexState::RawStr(hashes);
+              i += hashes + 1;
+            } else {
+              code.push(c);
+            }
+          },
+          ('\'', Some('\\')) => {
+            // an escaped character literal, which may escape a quote
+            code.push_str("''");
+            i += 3;
+            while i < chars.len() && chars[i] != '\'' {
+              i += 1;
+            }
+          },
+          ('\'', Some(_)) if chars.get(i + 2) == Some(&'\'') => {
+            code.push_str("''");
+            i += 2;
+          },
+          _ => code.push(c)
+        },
+        LexState::Str => match c {
+          '\\' => i += 1,
+          '"' => {
+            code.push('"');
+            state = LexState::Code;
+          },
+          _ => {}
+        },
+        LexState::RawStr(hashes) => {
+          let closes = chars[i+1..].iter().take(hashes)
+            .filter(|c| **c == '#')
+            .count() == hashes;
+          if c == '"' && closes {
+            code.push('"');
+            state = LexState::Code;
+            i += hashes;
+          }
+        },
+        LexState::Comment(depth) => match (c, next) {
+          ('*', Some('/')) => {
+            state = match depth {
+              1 => LexState::Code,
+              d => LexState::Comment(d - 1)
+            };
+            i += 1;
+          },
+          ('/', Some('*')) => {
+            state = LexState::Comment(depth + 1);
+            i += 1;
+          },
+          _ => {}
+        }
+      }
+      i += 1;
+    }
+    lines.push(code);
+  }
+  return lines;
+}
+
+/// Returns whether the character at a position continues an identifier, i.e.
+/// the one before it is part of one too.
+fn continues_ident(chars: &[char], i: usize) -> bool {
+  return i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_');
+}
+
+/// A public declaration found in the sources.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ApiItem {
+  /// The module it's declared in, e.g. `f06::parser`.
+  pub module: String,
+  /// The type it belongs to, for methods, fields, variants and trait impls.
+  pub owner: Option<String>,
+  /// The name it declares, or the path it re-exports.
+  pub name: String,
+  /// The declaration, normalised to a single line.
+  pub signature: String
+}
+
+impl ApiItem {
+  /// Returns what the item is about: its type, or the name it declares.
+  pub fn subject(&self) -> &str {
+    return self.owner.as_deref().unwrap_or(&self.name);
+  }
+}
+
+impl Display for ApiItem {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self.owner {
+      Some(ref owner) => write!(f, "{}: {}", owner, self.signature),
+      None => write!(f, "{}: {}", self.module, self.signature)
+    };
+  }
+}
+
+/// What the code being scanned is in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Scope {
+  /// A public module, by path.
+  Module(String),
+  /// The body of a macro, whose items land in the module around it.
+  Macro,
+  /// The inherent or trait impl of a type.
+  Impl(String),
+  /// A public struct with named fields.
+  Struct(String),
+  /// A public enum.
+  Enum(String),
+  /// A variant of a public enum with named fields.
+  Variant(String),
+  /// A public trait.
+  Trait(String),
+  /// Anything else, whose contents aren't part of the API.
+  Other
+}
+
+/// The keywords of the declarations that can be public.
+const ITEM_KEYWORDS: &[&str] = &[
+  "struct", "enum", "trait", "fn", "const", "static", "type", "mod", "use"
+];
+
+/// Returns the keyword and name a public item declaration declares, if the
+/// line is one, skipping qualifiers like `const` and `unsafe` for functions.
+fn item_keyword(decl: &str) -> Option<(&'static str, String)> {
+  let mut words = decl.strip_prefix("pub ")?.split_whitespace().peekable();
+  while let Some(word) = words.next() {
+    if let Some(kw) = ITEM_KEYWORDS.iter().find(|kw| **kw == word) {
+      // `const fn` is a function
+      if *kw == "const" && words.peek() == Some(&"fn") {
+        continue;
+      }
+      let name = words.next().unwrap_or("")
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .next()
+        .unwrap_or("")
+        .to_owned();
+      return Some((kw, name));
+    } else if !matches!(word, "async" | "unsafe" | "extern") {
+      return None;
+    }
+  }
+  return None;
+}
+
+/// Normalises a declaration spread over lines to a single line, without its
+/// body, value or trailing punctuation.
+fn normalise(decl: &str) -> String {
+  let mut sig = decl.split_whitespace().collect::<Vec<_>>().join(" ");
+  if let Some(i) = sig.find('{') {
+    sig.truncate(i);
+  }
+  // the values of constants aren't part of their signature
+  let valued = ["pub const ", "pub static ", "const "].iter()
+    .any(|p| sig.starts_with(p)) && !sig.contains(" fn ");
+  if let Some(i) = sig.find(" = ").filter(|_| valued) {
+    sig.truncate(i);
+  }
+  // mutable bindings are up to the body
+  let cleanups = [
+    ("( ", "("), (" )", ")"), (",)", ")"), ("< ", "<"), ("(mut ", "("),
+    (", mut ", ", ")
+  ];
+  for (from, to) in cleanups {
+    sig = sig.replace(from, to);
+  }
+  sig = sig.replace(" >", ">").replace(",>", ">");
+  return sig.trim_end_matches([' ', ';', ',']).to_owned();
+}
+
+/// Returns the type a header of an impl block is for, without its path or
+/// generic parameters.
+fn impl_owner(header: &str) -> String {
+  let mut rest = header.trim_start_matches("unsafe ")
+    .trim_start_matches("impl");
+  // skip the generic parameters of the impl itself
+  if rest.starts_with('<') {
+    let mut depth = 0;
+    for (i, c) in rest.char_indices() {
+      match c {
+        '<' => depth += 1,
+        '>' => depth -= 1,
+        _ => {}
+      }
+      if depth == 0 {
+        rest = &rest[i+1..];
+        break;
+      }
+    }
+  }
+  let target = rest.split(" for ").last().unwrap_or(rest).trim();
+  let target = target.split(" where").next().unwrap_or(target);
+  let base = target.split(['<', '{', ' ']).next().unwrap_or(target);
+  return base.rsplit("::").next().unwrap_or(base).to_owned();
+}
+
+/// Returns whether a declaration's line should be joined with the following
+/// ones to get all of it.
+fn needs_joining(decl: &str, scope: &Scope) -> bool {
+  if decl.contains('{') || decl.contains(';') {
+    return false;
+  }
+  let opens = decl.starts_with("impl")
+    || decl.starts_with("unsafe impl")
+    || decl.starts_with("macro_rules!")
+    || matches!(
+      item_keyword(decl),
+      Some(("fn" | "struct" | "enum" | "trait", _))
+    );
+  let trait_fn = matches!(scope, Scope::Trait(_)) && decl.starts_with("fn ");
+  return opens || trait_fn;
+}
+
+/// Scans the public declarations out of a source file, which is a module at
+/// some path. Returns them along with the public modules it declares, which
+/// live in other files.
+fn scan_file(module: &str, source: &str) -> (Vec<ApiItem>, Vec<String>) {
+  let lines = code_lines(source);
+  let mut items = Vec::new();
+  let mut submodules = Vec::new();
+  let mut scopes: Vec<(Scope, usize)> = Vec::new();
+  let mut depth = 0;
+  let mut attributes: Vec<String> = Vec::new();
+  let mut i = 0;
+  while i < lines.len() {
+    let mut decl = lines[i].trim().to_owned();
+    i += 1;
+    if decl.is_empty() {
+      continue;
+    }
+    let scope = scopes.iter().rev()
+      .map(|(s, _)| s)
+      .find(|s| **s != Scope::Macro)
+      .cloned()
+      .unwrap_or(Scope::Module(module.to_owned()));
+    while needs_joining(&decl, &scope) && i < lines.len() {
+      decl.push(' ');
+      decl.push_str(lines[i].trim());
+      i += 1;
+    }
+    // attributes that matter to users are kept for the next declaration
+    if decl.starts_with("#[") {
+      while !decl.ends_with(']') && i < lines.len() {
+        decl.push(' ');
+        decl.push_str(lines[i].trim());
+        i += 1;
+      }
+      if decl.starts_with("#[derive(") || decl == "#[non_exhaustive]" {
+        attributes.push(normalise(&decl));
+      }
+      continue;
+    }
+    let opens = decl.matches('{').count();
+    let closes = decl.matches('}').count();
+    let mut opened: Option<Scope> = None;
+    let mut item = |path: &str, owner: Option<&str>, name: &str, sig| {
+      items.push(ApiItem {
+        module: path.to_owned(),
+        owner: owner.map(String::from),
+        name: name.to_owned(),
+        signature: sig
+      });
+    };
+    if decl.contains('$') {
+      // a macro's metavariables; nothing we can name
+      opened = Some(Scope::Other);
+    } else if decl.starts_with("macro_rules!") {
+      opened = Some(Scope::Macro);
+    } else {
+      match (&scope, item_keyword(&decl)) {
+        (Scope::Module(path), Some((kw, name))) => {
+          let mut sig = normalise(&decl);
+          if matches!(kw, "struct" | "enum") && !attributes.is_empty() {
+            sig = format!("{} {}", attributes.join(" "), sig);
+          }
+          match kw {
+            "mod" if opens == 0 => submodules.push(name.clone()),
+            "mod" => {
+              opened = Some(Scope::Module(format!("{}::{}", path, name)));
+            },
+            "struct" => opened = Some(Scope::Struct(name.clone())),
+            "enum" => opened = Some(Scope::Enum(name.clone())),
+            "trait" => opened = Some(Scope::Trait(name.clone())),
+            _ => {}
+          };
+          let name = match kw {
+            "use" => sig.trim_start_matches("pub use ").to_owned(),
+            _ => name
+          };
+          item(path, None, &name, sig);
+        },
+        (Scope::Module(_), None) if decl.starts_with("impl")
+          || decl.starts_with("unsafe impl") => {
+          let owner = impl_owner(&decl);
+          let sig = normalise(&decl);
+          if sig.contains(" for ") {
+            item(module, Some(&owner), &owner, sig);
+          }
+          opened = Some(Scope::Impl(owner));
+        },
+        (Scope::Impl(owner) | Scope::Trait(owner), Some((_, name))) => {
+          item(module, Some(owner), &name, normalise(&decl));
+        },
+        (Scope::Trait(owner), None) if ["fn ", "const ", "type "].iter()
+          .any(|kw| decl.starts_with(kw)) => {
+          let name = decl.split([' ', '(', '<', ':']).nth(1).unwrap_or("");
+          item(module, Some(owner), name, normalise(&decl));
+        },
+        (Scope::Struct(owner), _) if decl.starts_with("pub ") => {
+          item(module, Some(owner), owner, normalise(&decl));
+        },
+        (Scope::Variant(owner), _) if decl.contains(':') => {
+          item(module, Some(owner), owner, normalise(&decl));
+        },
+        (Scope::Enum(owner), None)
+          if decl.starts_with(|c: char| c.is_ascii_uppercase()) => {
+          let name = decl.split([' ', '(', '{', ',', '=']).next().unwrap_or("");
+          item(module, Some(owner), owner, normalise(&decl));
+          opened = Some(Scope::Variant(format!("{}::{}", owner, name)));
+        },
+        _ => {}
+      }
+    }
+    attributes.clear();
+    // only the scopes whose brace is still open are kept
+    if opens > closes {
+      let inner = match scope {
+        Scope::Module(_) if opened.is_none() && in_macro(&scopes) => {
+          Scope::Macro
+        },
+        _ => opened.unwrap_or(Scope::Other)
+      };
+      scopes.push((inner, depth));
+      depth += opens - closes;
+    } else {
+      depth = depth.saturating_sub(closes - opens);
+      while scopes.last().is_some_and(|(_, d)| *d >= depth) {
+        scopes.pop();
+      }
+    }
+  }
+  return (items, submodules);
+}
+
+/// Returns whether the innermost scopes are within a macro's body.
+fn in_macro(scopes: &[(Scope, usize)]) -> bool {
+  return scopes.last().is_some_and(|(s, _)| *s == Scope::Macro);
+}
+
+/// Returns the public declarations of a crate, starting at its `lib.rs` and
+/// following the public modules it declares. Items of types that aren't
+/// public themselves are left out. Sorted, without duplicates.
+pub fn api_surface(crate_name: &str, src: &Path) -> io::Result<Vec<ApiItem>> {
+  let mut items = Vec::new();
+  let mut pending = vec![(crate_name.to_owned(), src.join("lib.rs"))];
+  while let Some((module, path)) = pending.pop() {
+    let (found, submodules) = scan_file(&module, &fs::read_to_string(&path)?);
+    items.extend(found);
+    // a module's children live in a directory named after it
+    let dir = match path.file_stem().and_then(|s| s.to_str()) {
+      Some("lib" | "mod") => path.parent().unwrap_or(src).to_owned(),
+      Some(stem) => path.with_file_name(stem),
+      None => continue
+    };
+    for sub in submodules {
+      let file = dir.join(format!("{}.rs", sub));
+      let file = match file.exists() {
+        true => file,
+        false => dir.join(&sub).join("mod.rs")
+      };
+      pending.push((format!("{}::{}", module, sub), file));
+    }
+  }
+  let types: BTreeSet<String> = items.iter()
+    .filter(|it| it.owner.is_none())
+    .map(|it| it.name.clone())
+    .collect();
+  let mut items = items.into_iter()
+    .filter(|it| match it.owner {
+      Some(ref owner) => {
+        types.contains(owner.split("::").next().unwrap_or(owner))
+      },
+      None => true
+    })
+    .collect::<Vec<_>>();
+  items.sort_by_key(|it| it.to_string());
+  items.dedup_by_key(|it| it.to_string());
+  return Ok(items);
+}
+
+/// Returns the differences between a snapshot and the actual API lines, as
+/// lines marked "-" for those gone and "+" for those new. Blank lines and
+/// lines starting with "#" in the snapshot are comments.
+pub fn api_diff(snapshot: &str, actual: &[String]) -> Vec<String> {
+  let expected: BTreeSet<&str> = snapshot.lines()
+    .map(str::trim)
+    .filter(|l| !l.is_empty() && !l.starts_with('#'))
+    .collect();
+  let actual: BTreeSet<&str> = actual.iter().map(String::as_str).collect();
+  let mut diff = expected.difference(&actual)
+    .map(|l| format!("- {}", l))
+    .chain(actual.difference(&expected).map(|l| format!("+ {}", l)))
+    .collect::<Vec<_>>();
+  diff.sort_by(|a, b| a[2..].cmp(&b[2..]));
+  return diff;
+}
+
+/// The comment at the top of a snapshot file.
+const SNAPSHOT_PREAMBLE: &str = "\
+# The public API, as checked by the API snapshot test. Don't change this
+# without a good reason: removed or changed lines break downstream users,
+# and need a new minor version at least.
+";
+
+/// Checks the actual API lines against a snapshot file, returning a message
+/// with the differences and how to update the snapshot if they don't match.
+/// With the `BLESS_API` environment variable set, the snapshot is rewritten
+/// instead.
+pub fn check_api_snapshot(path: &Path, actual: &[String]) -> Option<String> {
+  let snapshot = fs::read_to_string(path).unwrap_or_default();
+  let diff = api_diff(&snapshot, actual);
+  if diff.is_empty() {
+    return None;
+  }
+  if std::env::var_os(BLESS_VAR).is_some() {
+    let mut text = SNAPSHOT_PREAMBLE.to_owned();
+    text.push_str(&actual.join("\n"));
+    text.push('\n');
+    return match fs::write(path, text) {
+      Ok(()) => None,
+      Err(e) => Some(format!("couldn't write {}: {}", path.display(), e))
+    };
+  }
+  return Some(format!(
+    concat!(
+      "The public API differs from the snapshot in {}:\n\n{}\n\n",
+      "If the change is intended, apply the lines above to the snapshot ",
+      "(or run the test again with {}=1 to rewrite it), and mind that ",
+      "removed or changed lines call for a new minor version."
+    ),
+    path.display(),
+    diff.join("\n"),
+    BLESS_VAR
+  ));
+}
@@ -0,0 +1,388 @@
+//! This module implements a consistency check for superelement output: given
+//! the ranges of grid point and element IDs each superelement is supposed to
+//! own, it flags rows in blocks tagged with a superelement whose IDs fall
+//! outside of them.
+//!
+//! Some solvers print residual structure IDs inside a superelement's blocks,
+//! which then silently end up mixed with the superelement's own results.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+#[cfg(feature = "parser")]
+use std::fs;
+use std::io::{self, Read};
+#[cfg(feature = "parser")]
+use std::path::Path;
+use std::path::PathBuf;
+
+use clap::Args;
+#[cfg(feature = "parser")]
+use log::error;
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// How many offending IDs are listed in a finding, at most.
+pub const SE_RANGE_ID_CAP: usize = 10;
+
+/// A range of IDs (inclusive on both ends) owned by a superelement.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord
+)]
+pub struct SeIdRange {
+  /// The superelement ID.
+  pub seid: usize,
+  /// The first ID in the range.
+  pub id_start: usize,
+  /// The last ID in the range.
+  pub id_end: usize
+}
+
+impl Display for SeIdRange {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(f, "{}-{}", self.id_start, self.id_end);
+  }
+}
+
+impl SeIdRange {
+  /// Returns whether an ID is in this range.
+  pub const fn contains(&self, id: usize) -> bool {
+    return self.id_start <= id && id <= self.id_end;
+  }
+}
+
+/// The layout of a TOML file of ranges: a `[[range]]` table per range.
+#[derive(Clone, Debug, Deserialize)]
+struct SeRangesToml {
+  /// The ranges.
+  range: Vec<SeIdRange>
+}
+
+/// The ID ranges declared for each superelement.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SeRanges {
+  /// The ranges, per superelement ID.
+  pub ranges: BTreeMap<usize, Vec<SeIdRange>>
+}
+
+/// An error while reading superelement ID ranges.
+#[derive(Debug, derive_more::From)]
+#[non_exhaustive]
+pub enum SeRangesError {
+  /// The file couldn't be read.
+  Io(io::Error),
+  /// The CSV reader failed.
+  Csv(csv::Error),
+  /// The TOML was malformed.
+  Toml(toml::de::Error),
+  /// A CSV record had a missing or unparseable field.
+  BadField {
+    /// The 1-based record number.
+    record: usize,
+    /// The 0-based field index.
+    field: usize
+  },
+  /// A range ends before it starts.
+  Inverted(SeIdRange)
+}
+
+impl Display for SeRangesError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::Io(e) => write!(f, "I/O error: {}", e),
+      Self::Csv(e) => write!(f, "CSV error: {}", e),
+      Self::Toml(e) => write!(f, "TOML error: {}", e),
+      Self::BadField { record, field } => {
+        write!(f, "bad field {} in record {}", field, record)
+      },
+      Self::Inverted(r) => write!(
+        f,
+        "range {} of superelement {} ends before it starts",
+        r,
+        r.seid
+      ),
+    };
+  }
+}
+
+impl std::error::Error for SeRangesError {}
+
+impl SeRanges {
+  /// Instantiates an empty set of ranges.
+  pub fn new() -> Self {
+    return Self::default();
+  }
+
+  /// Adds a range. Errs if it ends before it starts.
+  pub fn insert(&mut self, range: SeIdRange) -> Result<(), SeRangesError> {
+    if range.id_end < range.id_start {
+      return Err(SeRangesError::Inverted(range));
+    }
+    self.ranges.entry(range.seid).or_default().push(range);
+    return Ok(());
+  }
+
+  /// Reads ranges from a CSV with `seid,id_start,id_end` records. Blank
+  /// records, records starting with `#` and a header record are ignored.
+  pub fn from_csv<R: Read>(reader: R) -> Result<Self, SeRangesError> {
+    let mut ranges = Self::new();
+    let mut rdr = csv::ReaderBuilder::new()
+      .has_headers(false)
+      .flexible(true)
+      .trim(csv::Trim::All)
+      .comment(Some(b'#'))
+      .from_reader(reader);
+    for (i, rec) in rdr.records().enumerate() {
+      let rec = rec?;
+      let record = i + 1;
+      match rec.get(0) {
+        None | Some("") => continue,
+        Some(s) if s.eq_ignore_ascii_case("seid") => continue,
+        Some(_) => {}
+      };
+      let int = |field: usize| -> Result<usize, SeRangesError> {
+        return rec.get(field)
+          .and_then(|s| s.parse().ok())
+          .ok_or(SeRangesError::BadField { record, field });
+      };
+      ranges.insert(SeIdRange {
+        seid: int(0)?,
+        id_start: int(1)?,
+        id_end: int(2)?
+      })?;
+    }
+    return Ok(ranges);
+  }
+
+  /// Reads ranges from TOML with a `[[range]]` table per range, each with
+  /// `seid`, `id_start` and `id_end` keys.
+  pub fn from_toml(text: &str) -> Result<Self, SeRangesError> {
+    let parsed: SeRangesToml = toml::from_str(text)?;
+    let mut ranges = Self::new();
+    for range in parsed.range {
+      ranges.insert(range)?;
+    }
+    return Ok(ranges);
+  }
+
+  /// Reads ranges from either TOML or CSV, telling them apart by the first
+  /// line that isn't blank or a comment.
+  pub fn parse(text: &str) -> Result<Self, SeRangesError> {
+    let first = text.lines()
+      .map(str::trim)
+      .find(|l| !l.is_empty() && !l.starts_with('#'));
+    return match first {
+      Some(l) if l.starts_with('[') || l.contains('=') => Self::from_toml(text),
+      _ => Self::from_csv(text.as_bytes())
+    };
+  }
+
+  /// Reads ranges from a TOML or CSV file, as `parse` does.
+  #[cfg(feature = "parser")]
+  pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SeRangesError> {
+    return Self::parse(&fs::read_to_string(path)?);
+  }
+
+  /// Returns the ranges declared for a superelement, if any.
+  pub fn of(&self, seid: usize) -> Option<&[SeIdRange]> {
+    return self.ranges.get(&seid).map(Vec::as_slice);
+  }
+}
+
+/// The rows of a superelement's block whose IDs are outside the ranges
+/// declared for it.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SeRangeViolation {
+  /// The subcase and type of the block.
+  pub block_ref: BlockRef,
+  /// The superelement the block is tagged with.
+  pub seid: usize,
+  /// The line range of the block, if known.
+  pub line_range: Option<(usize, usize)>,
+  /// The first few offending IDs, in order (at most `SE_RANGE_ID_CAP`).
+  pub ids: Vec<usize>,
+  /// How many offending IDs there are in total.
+  pub total: usize,
+  /// The ranges declared for the superelement.
+  pub expected: Vec<SeIdRange>
+}
+
+impl Display for SeRangeViolation {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let ids = self.ids.iter().map(usize::to_string).collect::<Vec<_>>();
+    let expected = self.expected.iter()
+      .map(SeIdRange::to_string)
+      .collect::<Vec<_>>();
+    write!(
+      f,
+      "{} block in subcase {} for superelement {} has {} ID{} outside of {}: {}",
+      self.block_ref.block_type,
+      self.block_ref.subcase,
+      self.seid,
+      self.total,
+      if self.total == 1 { "" } else { "s" },
+      expected.join(", "),
+      ids.join(", ")
+    )?;
+    if self.total > self.ids.len() {
+      write!(f, " and {} more", self.total - self.ids.len())?;
+    }
+    return Ok(());
+  }
+}
+
+/// Finds the grid point or element a row is for: the first of them in its
+/// index.
+struct OwnerId(Option<usize>);
+
+impl NasIndexVisitor for OwnerId {
+  fn grid(&mut self, grid_point: GridPointRef) {
+    self.0.get_or_insert(grid_point.gid);
+  }
+
+  fn element(&mut self, element: ElementRef) {
+    self.0.get_or_insert(element.eid);
+  }
+}
+
+/// Returns the grid point or element ID a row is for, if it's for one.
+fn owner_id(index: NasIndex) -> Option<usize> {
+  let mut owner = OwnerId(None);
+  index.visit(&mut owner);
+  return owner.0;
+}
+
+/// Checks that the rows of every block tagged with a superelement are for
+/// IDs within the ranges declared for it. Blocks without a tag, or tagged
+/// with a superelement that has no ranges, are not checked. Findings are
+/// aggregated per block.
+pub fn check_se_ranges(
+  file: &F06File,
+  ranges: &SeRanges
+) -> Vec<SeRangeViolation> {
+  let mut findings = Vec::new();
+  for (block_ref, blocks) in file.blocks.iter() {
+    for block in blocks.iter() {
+      let seid = match block.superelement() {
+        Some(seid) => seid,
+        None => continue
+      };
+      let expected = match ranges.of(seid) {
+        Some(expected) => expected,
+        None => continue
+      };
+      let mut outside = block.row_indexes.keys()
+        .filter_map(|ix| owner_id(*ix))
+        .filter(|id| !expected.iter().any(|r| r.contains(*id)))
+        .collect::<Vec<_>>();
+      outside.sort();
+      outside.dedup();
+      if outside.is_empty() {
+        continue;
+      }
+      let total = outside.len();
+      outside.truncate(SE_RANGE_ID_CAP);
+      findings.push(SeRangeViolation {
+        block_ref: *block_ref,
+        seid,
+        line_range: block.line_range,
+        ids: outside,
+        total,
+        expected: expected.to_vec()
+      });
+    }
+  }
+  return findings;
+}
+
+/// The option the tools take the superelement ID ranges to check with.
+#[derive(Clone, Debug, Default, Args)]
+pub struct SeRangesArgs {
+  /// The grid point and element ID ranges each superelement owns, as TOML
+  /// (a [[range]] table per range, with seid, id_start and id_end) or as a
+  /// CSV with "seid,id_start,id_end" records. Rows of superelement blocks
+  /// with IDs outside of them are warned about.
+  #[arg(long = "se-ranges", value_name = "PATH")]
+  pub path: Option<PathBuf>
+}
+
+impl SeRangesArgs {
+  /// Checks the superelement IDs of some files against the ranges, if any
+  /// were given, adding what's found to their diagnostics and returning it.
+  /// Exits with an error if the ranges can't be read or checked.
+  #[cfg(feature = "parser")]
+  pub fn check<'f, I>(&self, files: I) -> Vec<Diagnostic>
+  where
+    I: IntoIterator<Item = &'f mut F06File>
+  {
+    let Some(ref path) = self.path else {
+      return Vec::new();
+    };
+    let check = match CheckSeRanges::from_file(path) {
+      Ok(check) => check,
+      Err(e) => {
+        error!("Couldn't read the superelement ranges: {}!", e);
+        std::process::exit(1);
+      }
+    };
+    let mut registry = PostProcessorRegistry::new();
+    if let Err(e) = registry.register(Box::new(check)) {
+      error!("Couldn't set up the superelement check: {}!", e);
+      std::process::exit(1);
+    }
+    let mut diagnostics = Vec::new();
+    for file in files {
+      match registry.run(&[CheckSeRanges::NAME], file) {
+        Ok(found) => diagnostics.extend(found),
+        Err(e) => {
+          error!("Couldn't check the superelement ranges: {}!", e);
+          std::process::exit(1);
+        }
+      }
+    }
+    return diagnostics;
+  }
+}
+
+/// Post-processor that runs `check_se_ranges` with some ranges, turning each
+/// finding into a diagnostic.
+pub struct CheckSeRanges {
+  /// The declared ranges.
+  ranges: SeRanges
+}
+
+impl CheckSeRanges {
+  /// The name this post-processor is registered under.
+  pub const NAME: &'static str = "check_se_ranges";
+
+  /// Makes the post-processor for some ranges.
+  pub fn new(ranges: SeRanges) -> Self {
+    return Self { ranges };
+  }
+
+  /// Makes the post-processor for the ranges in a TOML or CSV file.
+  #[cfg(feature = "parser")]
+  pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SeRangesError> {
+    return SeRanges::from_file(path).map(Self::new);
+  }
+}
+
+impl PostProcessor for CheckSeRanges {
+  fn name(&self) -> &str {
+    return Self::NAME;
+  }
+
+  fn idempotent(&self) -> bool {
+    return true;
+  }
+
+  fn process(&self, file: &mut F06File) -> Vec<Diagnostic> {
+    return check_se_ranges(file, &self.ranges)
+      .into_iter()
+      .map(|v| {
+        let line = v.line_range.map(|lr| lr.0);
+        return post_diagnostic(self.name(), line, &v.to_string());
+      })
+      .collect();
+  }
+}
@@ -0,0 +1,143 @@
+//! This module contains helpers for testing and fuzzing the parser from
+//! outside the crate: a dictionary of tokens the parser reacts to, and a way
+//! to cut small seed files out of real F06 output.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::prelude::*;
+use crate::util::SUS_WORDS;
+
+/// Numeric field patterns commonly found in (or near) F06 tables.
+pub const NUMERIC_PATTERNS: &[&str] = &[
+  "0.0",
+  "1.000000E+00",
+  "-1.234567E-03",
+  "9.999999E+99",
+  "1.0-3",
+  "-2.5+2",
+  "1011",
+  "-1",
+  "0",
+  "9223372036854775808",
+  "**********",
+  "1.0E",
+  ".",
+  "-",
+  "+"
+];
+
+/// Other tokens that change the parser's state.
+pub const CONTROL_TOKENS: &[&str] = &[
+  "SUBCASE 1",
+  "OUTPUT FOR SUBCASE 1",
+  "PAGE 1",
+  "SOL 101",
+  "*** USER WARNING MESSAGE",
+  "*** USER FATAL MESSAGE",
+  "POINT ID.",
+  "ELEMENT ID.",
+  "TYPE",
+  "G",
+  "S",
+  "CEN/4",
+  "TOP",
+  "BOTTOM"
+];
+
+/// Number of lines before a block's start taken when cutting a seed. Enough
+/// for the header, the blank lines around it, and the subcase line.
+const SEED_CONTEXT_LINES: usize = 4;
+
+/// Writes a header in the spaced form it takes in F06 files, e.g.
+/// "SPC FORCES" becomes "S P C   F O R C E S".
+pub fn respace(header: &str) -> String {
+  return header.split_whitespace()
+    .map(|w| w.chars().map(String::from).collect::<Vec<_>>().join(" "))
+    .collect::<Vec<_>>()
+    .join("   ");
+}
+
+/// Returns a dictionary of tokens for fuzzing the parser: block headers
+/// (spaced and unspaced), column labels, element and solver names, header
+/// words, control tokens and numeric field patterns. Sorted, no duplicates.
+pub fn fuzz_dictionary() -> Vec<String> {
+  let mut dict: BTreeSet<String> = BTreeSet::new();
+  for bt in BlockType::all() {
+    for header in bt.headers() {
+      dict.insert(header.to_string());
+      dict.insert(respace(header));
+    }
+  }
+  // column labels, of every block type
+  for bt in BlockType::all() {
+    dict.extend(bt.columns().iter().map(|c| c.to_string()));
+  }
+  // names and words the parser looks for
+  dict.extend(ElementType::all().iter().map(|e| e.name().to_owned()));
+  dict.extend(Solver::all().iter().map(|s| s.name().to_owned()));
+  dict.extend(SUS_WORDS.iter().map(|w| w.to_string()));
+  dict.extend(CONTROL_TOKENS.iter().map(|t| t.to_string()));
+  dict.extend(NUMERIC_PATTERNS.iter().map(|p| p.to_string()));
+  return dict.into_iter().collect();
+}
+
+/// Renders dictionary entries in the format libFuzzer takes with `-dict=`.
+pub fn libfuzzer_dict(entries: &[String]) -> String {
+  let mut out = String::new();
+  for entry in entries {
+    out.push('"');
+    for byte in entry.bytes() {
+      match byte {
+        b'"' | b'\\' => {
+          out.push('\\');
+          out.push(byte as char);
+        },
+        b' '..=b'~' => out.push(byte as char),
+        _ => out.push_str(&format!("\\x{:02X}", byte)),
+      };
+    }
+    out.push_str("\"\n");
+  }
+  return out;
+}
+
+/// Cuts small seed files out of a parsed file's source: for the first block
+/// of each type, the line that gave the solver away, the lines right before
+/// the block, and a few of its data lines. The file should not have had its
+/// blocks merged, so that line ranges are still accurate.
+pub fn block_seeds(
+  source: &str,
+  file: &F06File,
+  data_lines: usize
+) -> BTreeMap<BlockType, String> {
+  let lines: Vec<&str> = source.lines().collect();
+  // line numbers are 1-based
+  let line = |n: usize| lines.get(n.wrapping_sub(1)).copied();
+  let banner = match file.flavour_resolution.solver.line.and_then(line) {
+    Some(l) => l.to_owned(),
+    None => file.flavour.solver.map(|s| s.name()).unwrap_or("").to_owned()
+  };
+  let mut seeds: BTreeMap<BlockType, String> = BTreeMap::new();
+  for block in file.all_blocks(false) {
+    let (start, end) = match block.line_range {
+      Some(lr) => lr,
+      None => continue
+    };
+    if seeds.contains_key(&block.block_type) {
+      continue;
+    }
+    let first = start.saturating_sub(SEED_CONTEXT_LINES).max(1);
+    // the column headers are data lines to the decoder too
+    let last = end.min(start + data_lines + 2);
+    let mut seed = format!("{}\n", banner);
+    for n in first..last {
+      if let Some(l) = line(n) {
+        seed.push_str(l);
+        seed.push('\n');
+      }
+    }
+    seeds.insert(block.block_type, seed);
+  }
+  return seeds;
+}
+
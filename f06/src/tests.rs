@@ -1,4 +1,28 @@
-use crate::util::decode_nasfloat;
+use std::collections::{BTreeMap, BTreeSet};
+
+use nalgebra::DMatrix;
+
+use crate::prelude::*;
+use crate::fixtures::{
+  MYSTRAN_PLY_STRESSES_FIXTURE,
+  NON_FINITE_FIXTURE,
+  THERMAL_LOAD_FIXTURE
+};
+use crate::stability::*;
+use crate::util::{
+  decode_bytes,
+  decode_duration,
+  decode_nasfloat,
+  decode_non_finite,
+  extract_reals_with_status,
+  is_comma_decimal,
+  looks_comma_decimal,
+  printed_precision,
+  IdSelection,
+  IdSelectionError,
+  IdSelectionErrorKind,
+  PotentialHeader
+};
 
 #[test]
 fn test_decode_nasfloat() {
@@ -55,4 +79,6056 @@ fn test_decode_nasfloat() {
   must_fail("E.");
   must_fail(".e");
   must_fail(".E");
+  // non-ascii stuff used to slice mid-character and panic
+  direct("é1.5", 1.5);
+  direct("1.5é", 1.5);
+  direct("1.0é-3", 1.0e-3);
+  must_fail("ü");
+  must_fail("€€€");
+  must_fail("١٢");
+}
+
+#[test]
+fn test_fuzz_dictionary() {
+  let dict = crate::testing::fuzz_dictionary();
+  assert!(dict.iter().any(|w| w == "S P C   F O R C E S"));
+  assert!(dict.iter().any(|w| w == "SPC FORCES"));
+  for bt in BlockType::all() {
+    for column in bt.columns() {
+      assert!(dict.contains(&column.to_string()), "{:?}", column);
+    }
+  }
+  assert!(dict.windows(2).all(|w| w[0] < w[1]));
+  let entries = ["a \"b\"".to_owned(), "é".to_owned()];
+  let rendered = crate::testing::libfuzzer_dict(&entries);
+  assert_eq!(rendered, "\"a \\\"b\\\"\"\n\"\\xC3\\xA9\"\n");
+}
+
+/// A small MYSTRAN-like output with one deliberately sign-flipped rod force
+/// (rod 20 is compressed but reports tension).
+const ROD_SIGN_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+
+ OUTPUT FOR SUBCASE        1
+
+                                                       D I S P L A C E M E N T S
+                                              (in global coordinate system at each grid)
+           GRID     COORD      T1            T2            T3            R1            R2            R3
+                     SYS
+              1        0  0.0           0.0           0.0           0.0           0.0           0.0
+              2        0  1.000000E-03  0.0           0.0           0.0           0.0           0.0
+              3        0  0.0          -1.000000E-03  0.0           0.0           0.0           0.0
+                         ------------- ------------- ------------- ------------- ------------- -------------
+
+ OUTPUT FOR SUBCASE        1
+
+                                                       E L E M E N T   E N G I N E E R I N G   F O R C E S
+                                                     F O R   E L E M E N T   T Y P E   R O D
+                 Element     Axial        Torque      Element     Axial        Torque
+                    ID       Force                       ID       Force
+                      10  1.000000E+02  0.000000E+00      20  5.000000E+01  0.000000E+00
+                         ------------- -------------
+";
+
+/// Geometry for the rod sign fixture.
+const ROD_SIGN_GEOMETRY: &str = "
+GRID,1,0.0,0.0,0.0
+GRID,2,1.0,0.0,0.0
+GRID,3,0.0,1.0,0.0
+CROD,10,1,2
+CROD,20,1,3
+";
+
+#[test]
+fn test_rod_force_sign_mismatch() {
+  let file = OnePassParser::parse_bufread(ROD_SIGN_FIXTURE.as_bytes())
+    .unwrap();
+  let geom = ModelGeometry::from_csv(ROD_SIGN_GEOMETRY.as_bytes()).unwrap();
+  let findings = cross_validate(&file, &geom, DEFAULT_SIGN_THRESHOLD);
+  let expected = CrossValidationFinding::RodForceSignMismatch {
+    subcase: 1,
+    eid: 20,
+    elongation: -1.0e-3,
+    axial_force: 5.0e1
+  };
+  assert_eq!(findings, vec![expected]);
+}
+
+/// A Simcenter-like output with global requests, overrides in subcase 1 and
+/// 2, a subtitle in subcase 2, and the SPC forces requested in subcase 2
+/// missing.
+const REQUESTS_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    REQUESTS TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     1
+      TEST MODEL
+0                                        C A S E    C O N T R O L    E C H O
+                 COMMAND
+                 COUNT
+                  1      $SPCFORCES = ALL
+                  2        TITLE = REQUESTS TEST
+                  3        DISPLACEMENT(PRINT) = ALL
+                  4        STRESS(PLOT) = ALL
+                  5        OLOAD = ALL
+                  6        SPC = 1
+                  7      SUBCASE 1
+                  8        OLOAD = NONE
+                  9      SUBCASE 2
+                 10        SUBTITLE = Side pressure
+                 11        DISP(PRINT,SORT1) = SET 10
+                 12        SPCFORCES(PRINT) = ALL
+                 13      BEGIN BULK
+1    REQUESTS TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     2
+      TEST MODEL
+0                                                                                SUBCASE 1
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+          1011      G      1.000000E+00   0.0            0.0            0.0            0.0            0.0
+1    REQUESTS TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     3
+      TEST MODEL
+0                                                                                SUBCASE 2
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+          1011      G      2.000000E+00   0.0            0.0            0.0            0.0            0.0
+1    REQUESTS TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     4
+      TEST MODEL
+0                                                                                SUBCASE 2
+ 
+                                                     L O A D   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+          1011      G      0.0            0.0           -4.687500E+02   0.0            0.0            0.0
+";
+
+#[test]
+fn test_requested_outputs() {
+  let file = parse_str(REQUESTS_FIXTURE);
+  let req = |keyword, set, print, plot| OutputRequest {
+    keyword,
+    set,
+    print,
+    punch: false,
+    plot
+  };
+  let disp_all = req(OutputKeyword::Displacement, RequestSet::All, true, false);
+  let disp_set = req(
+    OutputKeyword::Displacement,
+    RequestSet::Set(10),
+    true,
+    false
+  );
+  let stress = req(OutputKeyword::Stress, RequestSet::All, false, true);
+  let oload_all = req(OutputKeyword::Oload, RequestSet::All, true, false);
+  let oload_none = req(OutputKeyword::Oload, RequestSet::None, true, false);
+  let spcs = req(OutputKeyword::SpcForces, RequestSet::All, true, false);
+  // globals are inherited unless overridden, and comments are ignored
+  assert_eq!(file.requests.len(), 2);
+  assert_eq!(file.requests[&1], vec![disp_all, stress.clone(), oload_none]);
+  assert_eq!(
+    file.requests[&2],
+    vec![stress, oload_all, disp_set, spcs.clone()]
+  );
+  // subcases without a title of their own get the global one
+  assert_eq!(file.subcase_titles[&1], "REQUESTS TEST");
+  assert_eq!(file.subcase_titles[&2], "Side pressure");
+  // the echo's subcase lines didn't get in the way of the blocks
+  assert_eq!(file.subcases().collect::<Vec<_>>(), vec![1, 2]);
+  // only the spc forces are missing
+  let expected = CrossValidationFinding::RequestedButMissing {
+    subcase: 2,
+    request: spcs
+  };
+  assert_eq!(check_requested_outputs(&file), vec![expected]);
+}
+
+/// A displacement block with mixed magnitudes, negatives and zeroes.
+const TABLE_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+
+                                                       D I S P L A C E M E N T S
+                                              (in global coordinate system at each grid)
+           GRID     COORD      T1            T2            T3            R1            R2            R3
+                     SYS
+              1        0  0.0           0.0           0.0           0.0           0.0           0.0
+              2        0  1.234567E-03 -2.500000E+01  3.000000E+02  0.0          -7.000000E-10  1.000000E+00
+              3        0 -4.560000E+00  0.0           1.000000E-01  2.000000E+05  0.0          -1.000000E+00
+                         ------------- ------------- ------------- ------------- ------------- -------------
+";
+
+/// Expected plain table for the table fixture.
+const TABLE_GOLDEN_PLAIN: &str = concat!(
+  "GRID POINT ID           Tx      Ty    Tz   Rx      Ry    Rz\n",
+  "-------------  -----------  ------  ----  ---  ------  ----\n",
+  "       GRID 1          0E0     0E0   0E0  0E0     0E0   0E0\n",
+  "       GRID 2  1.234567E-3  -2.5E1   3E2  0E0  -7E-10   1E0\n",
+  "       GRID 3      -4.56E0     0E0  1E-1  2E5     0E0  -1E0\n"
+);
+
+/// Expected markdown table for the table fixture.
+const TABLE_GOLDEN_MARKDOWN: &str = concat!(
+  "| GRID POINT ID |      Tx [in] | Ty [in] | Tz [in] | Rx [rad] | Ry [rad] | Rz [rad] |\n",
+  "|:--------------|-------------:|--------:|--------:|---------:|---------:|---------:|\n",
+  "| GRID 1        |  0       E0  |   0  E0 |    0E0  |      0E0 |    0E0   |      0E0 |\n",
+  "| GRID 2        |  1.234567E-3 |  -2.5E1 |    3E2  |      0E0 |   -7E-10 |      1E0 |\n",
+  "| GRID 3        | -4.56    E0  |   0  E0 |    1E-1 |      2E5 |    0E0   |     -1E0 |\n"
+);
+
+/// Expected org table for the table fixture.
+const TABLE_GOLDEN_ORG: &str = concat!(
+  "| GRID P |           Tx |           Ty |           Tz |      Rx |       Ry |       Rz |\n",
+  "|        |           in |           in |           in |         |          |          |\n",
+  "|--------+--------------+--------------+--------------+---------+----------+----------|\n",
+  "| GRID 1 |    0.000E+00 |    0.000E+00 |    0.000E+00 | 0.0E+00 |  0.0E+00 |  0.0E+00 |\n",
+  "| GRID 2 |    1.235E-03 |   -2.500E+01 |    3.000E+02 | 0.0E+00 | -7.0E-10 |  1.0E+00 |\n",
+  "| GRID 3 |   -4.560E+00 |    0.000E+00 |    1.000E-01 | 2.0E+05 |  0.0E+00 | -1.0E+00 |\n"
+);
+
+/// Parses the table fixture and returns its only block.
+fn table_fixture_block() -> FinalBlock {
+  let file = OnePassParser::parse_bufread(TABLE_FIXTURE.as_bytes()).unwrap();
+  return file.all_blocks(false).next().unwrap().clone();
+}
+
+#[test]
+fn test_table_plain() {
+  let style = TableStyle::new(TableMode::Plain);
+  assert_eq!(style.render(&table_fixture_block()), TABLE_GOLDEN_PLAIN);
+}
+
+#[test]
+fn test_table_markdown() {
+  let col = |units: &str| ColumnStyle {
+    alignment: TableAlignment::Decimal,
+    units: Some(units.to_owned()),
+    ..Default::default()
+  };
+  let style = TableStyle {
+    mode: TableMode::Markdown,
+    index: ColumnStyle {
+      alignment: TableAlignment::Left,
+      ..Default::default()
+    },
+    columns: vec![
+      col("in"), col("in"), col("in"), col("rad"), col("rad"), col("rad")
+    ],
+    default_column: ColumnStyle::default()
+  };
+  assert_eq!(style.render(&table_fixture_block()), TABLE_GOLDEN_MARKDOWN);
+}
+
+#[test]
+fn test_table_org() {
+  let col = |units: &str| ColumnStyle {
+    min_width: 12,
+    alignment: TableAlignment::Decimal,
+    precision: Some(3),
+    units: Some(units.to_owned()),
+    ..Default::default()
+  };
+  let style = TableStyle {
+    mode: TableMode::Org,
+    index: ColumnStyle {
+      max_width: Some(6),
+      alignment: TableAlignment::Left,
+      ..Default::default()
+    },
+    columns: vec![col("in"), col("in"), col("in")],
+    default_column: ColumnStyle {
+      alignment: TableAlignment::Decimal,
+      precision: Some(1),
+      ..Default::default()
+    }
+  };
+  assert_eq!(style.render(&table_fixture_block()), TABLE_GOLDEN_ORG);
+}
+
+/// Parses a string as an F06 file.
+fn parse_str(s: &str) -> F06File {
+  return OnePassParser::parse_bufread(s.as_bytes()).unwrap();
+}
+
+#[test]
+fn test_flavour_sources() {
+  // banner and echoed solution
+  let file = parse_str(" MYSTRAN Version 15.1.3\nSOL 1\n");
+  let res = &file.flavour_resolution;
+  assert_eq!(res.solver.value, Some(Solver::Mystran));
+  assert_eq!(res.solver.source, FlavourSource::Banner);
+  assert_eq!(res.solver.line, Some(1));
+  assert_eq!(res.soltype.value, Some(SolType::LinearStatic));
+  assert_eq!(res.soltype.source, FlavourSource::Banner);
+  assert_eq!(file.flavour, res.flavour());
+  // the subcase line layout
+  let file = parse_str("\n OUTPUT FOR SUBCASE        1\n");
+  let res = &file.flavour_resolution;
+  assert_eq!(res.solver.value, Some(Solver::Mystran));
+  assert_eq!(res.solver.source, FlavourSource::Heuristic { line: 2 });
+  // nothing at all
+  let file = parse_str("nothing to see here\n");
+  let res = &file.flavour_resolution;
+  assert_eq!(res.solver.value, None);
+  assert_eq!(res.solver.source, FlavourSource::Default);
+  assert_eq!(res.soltype.source, FlavourSource::Default);
+  // hinted by the user
+  let mut parser = OnePassParser::new();
+  parser.hint_flavour(Flavour {
+    solver: None,
+    soltype: Some(SolType::Eigenvalue)
+  });
+  let res = parser.finish().flavour_resolution;
+  assert_eq!(res.soltype.value, Some(SolType::Eigenvalue));
+  assert_eq!(res.soltype.source, FlavourSource::UserSupplied);
+  assert!(!res.has_conflicts());
+}
+
+#[test]
+fn test_flavour_conflict() {
+  let mut parser = OnePassParser::new();
+  parser.hint_flavour(Flavour {
+    solver: Some(Solver::Simcenter),
+    soltype: None
+  });
+  parser.consume(" MYSTRAN Version 15.1.3");
+  let file = parser.finish();
+  let res = &file.flavour_resolution;
+  assert_eq!(file.flavour.solver, Some(Solver::Simcenter));
+  assert_eq!(res.solver.source, FlavourSource::UserSupplied);
+  let expected = FlavourEvidence {
+    source: FlavourSource::Banner,
+    value: Solver::Mystran,
+    line: Some(1)
+  };
+  assert_eq!(res.solver.conflicts, vec![expected]);
+  assert!(res.has_conflicts());
+}
+
+#[test]
+fn test_flavour_ranking() {
+  // a banner overrides a heuristic, which is kept as a conflict
+  let file = parse_str(
+    "\n OUTPUT FOR SUBCASE        1\n Welcome to Simcenter Nastran\n"
+  );
+  let res = &file.flavour_resolution;
+  assert_eq!(file.flavour.solver, Some(Solver::Simcenter));
+  assert_eq!(res.solver.source, FlavourSource::Banner);
+  assert_eq!(res.solver.line, Some(3));
+  let expected = FlavourEvidence {
+    source: FlavourSource::Heuristic { line: 2 },
+    value: Solver::Mystran,
+    line: Some(2)
+  };
+  assert_eq!(res.solver.conflicts, vec![expected]);
+  // weaker evidence only ever conflicts, and ties keep the first value
+  let mut field = FieldResolution::default();
+  let banner = FlavourSource::Banner;
+  let heuristic = FlavourSource::Heuristic { line: 9 };
+  let outcome = field.offer(banner, Solver::Mystran, Some(1));
+  assert_eq!(outcome, EvidenceOutcome::Resolved);
+  let outcome = field.offer(heuristic, Solver::Simcenter, Some(9));
+  assert_eq!(outcome, EvidenceOutcome::Conflicts);
+  let outcome = field.offer(banner, Solver::Simcenter, Some(12));
+  assert_eq!(outcome, EvidenceOutcome::Conflicts);
+  assert_eq!(field.value, Some(Solver::Mystran));
+  assert_eq!(field.conflicts.len(), 2);
+  // and agreeing stronger evidence takes over the source
+  let outcome = field.offer(FlavourSource::UserSupplied, Solver::Mystran, None);
+  assert_eq!(outcome, EvidenceOutcome::Agrees);
+  assert_eq!(field.source, FlavourSource::UserSupplied);
+}
+
+#[test]
+fn test_flavour_fallback() {
+  let fallback = Flavour {
+    solver: Some(Solver::Simcenter),
+    soltype: Some(SolType::Eigenvalue)
+  };
+  // what the file says comes first, without conflicts
+  let mut parser = OnePassParser::new();
+  parser.fallback_flavour(fallback);
+  parser.consume(" MYSTRAN Version 15.1.3");
+  let res = parser.finish().flavour_resolution;
+  assert_eq!(res.solver.value, Some(Solver::Mystran));
+  assert_eq!(res.solver.source, FlavourSource::Banner);
+  assert!(!res.has_conflicts());
+  // and what it doesn't say is taken from the fallback
+  assert_eq!(res.soltype.value, Some(SolType::Eigenvalue));
+  assert_eq!(res.soltype.source, FlavourSource::Default);
+  // a policy forces one flavour and falls back on another
+  let mut parser = OnePassParser::new();
+  parser.flavour_policy(&FlavourPolicy {
+    default: Some(fallback),
+    forced: Some("mystran".parse().unwrap()),
+    required: None
+  });
+  let res = parser.finish().flavour_resolution;
+  assert_eq!(res.solver.source, FlavourSource::UserSupplied);
+  assert_eq!(res.flavour().spec(), "mystran/sol103");
+  // requirements only check the fields they have
+  let policy = FlavourPolicy {
+    required: Some("mystran".parse().unwrap()),
+    ..Default::default()
+  };
+  assert!(policy.check(&res).is_ok());
+  let policy = FlavourPolicy {
+    required: Some("mystran/sol101".parse().unwrap()),
+    ..Default::default()
+  };
+  let mismatch = policy.check(&res).unwrap_err();
+  assert_eq!(mismatch.resolved.soltype, Some(SolType::Eigenvalue));
+  assert_eq!(
+    mismatch.to_string(),
+    "the flavour was resolved as mystran/sol103, but mystran/sol101 is \
+    required"
+  );
+}
+
+#[test]
+fn test_batch_flavours() {
+  let examples = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("../examples");
+  let paths = [
+    examples.join("mystran/SB-BUSH-01-OFFSET-2b.F06"),
+    examples.join("scnastran/SB-BUSH-01-OFFSET-2b_scnas_postexport.f06")
+  ];
+  // each file gets its own flavour, whatever the fallback
+  let policy = FlavourPolicy {
+    default: Some("mystran".parse().unwrap()),
+    ..Default::default()
+  };
+  let batch = parse_files_flavoured(&paths, &policy, OnePassParser::new);
+  let specs = batch.iter()
+    .map(|bf| bf.resolution.as_ref().unwrap().flavour().spec())
+    .collect::<Vec<_>>();
+  assert_eq!(specs, ["mystran/sol101", "simcenter/sol101"]);
+  assert!(batch.iter().all(|bf| bf.result.is_ok()));
+  let input = batch[1].manifest_input();
+  assert_eq!(input.flavour.as_deref(), Some("simcenter/sol101"));
+  assert_eq!(input.error, None);
+  // files of other flavours than the required one are rejected
+  let policy = FlavourPolicy {
+    required: Some("mystran".parse().unwrap()),
+    ..Default::default()
+  };
+  let batch = parse_files_flavoured(&paths, &policy, OnePassParser::new);
+  assert!(batch[0].result.is_ok());
+  assert!(batch[0].flavour_mismatch().is_none());
+  let mismatch = batch[1].flavour_mismatch().unwrap();
+  assert_eq!(mismatch.resolved.solver, Some(Solver::Simcenter));
+  let resolution = batch[1].resolution.as_ref().unwrap();
+  assert_eq!(resolution.solver.source, FlavourSource::Banner);
+  let err = batch[1].result.as_ref().unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+  assert!(batch[1].manifest_input().error.is_some());
+  // the ones that are left can be merged
+  let merged = merge_parsed(batch.into_iter().map(|bf| bf.result)).unwrap();
+  assert_eq!(merged.flavour.solver, Some(Solver::Mystran));
+  #[cfg(feature = "rayon")]
+  {
+    let parallel = parse_files_parallel_flavoured(
+      &paths,
+      &policy,
+      OnePassParser::new
+    );
+    assert!(parallel[0].result.is_ok());
+    assert!(parallel[1].flavour_mismatch().is_some());
+  }
+}
+
+#[test]
+fn test_merge_keeps_fatal_errors() {
+  let first = parse_str(SOLSET_FIXTURE);
+  let fatal = format!(
+    "{}\n *** USER FATAL MESSAGE 9050 (SEKRRS)\n",
+    ten_block_f06()
+  );
+  let mut second = parse_str(&fatal);
+  second.filename = Some("fatal.f06".to_owned());
+  assert_eq!(second.fatal_errors.len(), 1);
+  let merged = merge_parsed([Ok(first), Ok(second.clone())]).unwrap();
+  // the first file's are clean, but the batch isn't, and says where
+  assert!(merged.fatal_errors.is_empty());
+  assert_eq!(merged.merged_fatal_errors.len(), 1);
+  assert_eq!(
+    merged.merged_fatal_errors.get("fatal.f06"),
+    Some(&second.fatal_errors)
+  );
+  let diagnostics = &merged.merged_diagnostics["fatal.f06"];
+  assert!(diagnostics.iter().any(|d| {
+    return matches!(d.kind, DiagnosticKind::SolverFatal {..});
+  }));
+  // and they're kept when merged again, under the same name
+  let again = merge_parsed([Ok(parse_str(SOLSET_FIXTURE)), Ok(merged)]);
+  let again = again.unwrap();
+  assert_eq!(
+    again.merged_fatal_errors.get("fatal.f06"),
+    Some(&second.fatal_errors)
+  );
+  assert!(again.merged_diagnostics.contains_key("fatal.f06"));
+}
+
+#[test]
+fn test_warning_aggregation() {
+  let text = (1..=500)
+    .map(|i| format!(" *** USER WARNING MESSAGE 1234 GRID {} HAS NO MASS", i))
+    .collect::<Vec<_>>()
+    .join("\n");
+  let file = parse_str(&text);
+  let diags = file.diagnostics.iter().collect::<Vec<_>>();
+  assert_eq!(diags.len(), 1);
+  let diag = diags[0];
+  let template = "*** USER WARNING MESSAGE # GRID # HAS NO MASS".to_owned();
+  assert_eq!(diag.kind, DiagnosticKind::SolverWarning { template });
+  assert_eq!(diag.count, 500);
+  assert_eq!(diag.lines, (1..=DEFAULT_MAX_LINES).collect::<Vec<_>>());
+  assert_eq!(diag.samples.len(), DEFAULT_MAX_SAMPLES);
+  assert_eq!(file.warnings.len(), DEFAULT_MAX_LINES);
+  assert!(diag.to_string().contains("500 occurrences, first at lines 1, 2"));
+  let more = format!(", ... ({} more)", 500 - DEFAULT_MAX_LINES);
+  assert!(diag.to_string().ends_with(&more));
+  assert!(diag.samples[0].ends_with("GRID 1 HAS NO MASS"));
+}
+
+#[test]
+fn test_diagnostic_cap() {
+  let limits = DiagnosticLimits {
+    max_lines: 2,
+    max_samples: 1,
+    max_entries: 2
+  };
+  let mut diags = Diagnostics::with_limits(limits);
+  let kinds = [
+    DiagnosticKind::AmbiguousHeader,
+    DiagnosticKind::BlockWithoutSolver,
+    DiagnosticKind::OverflowedField { block_type: BlockType::Displacements }
+  ];
+  for line in 0..30 {
+    diags.push(kinds[line % 3].clone(), line, "whatever");
+  }
+  assert_eq!(diags.entries.len(), 2);
+  assert_eq!(diags.entries[0].kind, kinds[0]);
+  assert_eq!(diags.entries[0].count, 10);
+  assert_eq!(diags.entries[0].lines, vec![0, 3]);
+  assert_eq!(diags.entries[1].lines, vec![1, 4]);
+  assert_eq!(diags.overflow, 10);
+  assert_eq!(diags.total(), 30);
+}
+
+/// A Simcenter-like output with physical and solution set displacements in
+/// the same subcase.
+const SOLSET_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    SOLUTION SET TEST                                   SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1.000000E+00   2.000000E+00   3.000000E+00   4.000000E+00   5.000000E+00   6.000000E+00
+             2      G      0.0            0.0            0.0            0.0            0.0            0.0
+1    SOLUTION SET TEST                                   SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                                 D I S P L A C E M E N T   V E C T O R   (SOLUTION SET)
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      S      5.000000E-01   0.0            0.0            0.0            0.0            0.0
+             3      S     -5.000000E-01   0.0            0.0            0.0            0.0            0.0
+1    SOLUTION SET TEST                                   SIMCENTER NASTRAN  2/11/21   PAGE     3
+";
+
+#[test]
+fn test_solution_set_displacements() {
+  let file = parse_str(SOLSET_FIXTURE);
+  let types = file.block_types().collect::<Vec<_>>();
+  assert_eq!(
+    types,
+    vec![BlockType::Displacements, BlockType::SolutionSetDisplacements]
+  );
+  let disp = file.block_search(Some(BlockType::Displacements), None, false)
+    .next()
+    .unwrap();
+  let solset = file.block_search(
+    Some(BlockType::SolutionSetDisplacements),
+    None,
+    false
+  ).next().unwrap();
+  assert_eq!(disp.row_indexes.len(), 2);
+  assert_eq!(solset.row_indexes.len(), 2);
+  let is_gid = |ri: &NasIndex| matches!(ri, NasIndex::GridPointRef(_));
+  let is_seq = |ri: &NasIndex| matches!(ri, NasIndex::SeqRef(_));
+  assert!(disp.row_indexes.keys().all(is_gid));
+  assert!(solset.row_indexes.keys().all(is_seq));
+  assert_eq!(disp.get(GridPointRef { gid: 1 }, DOF_TX), Some(F06Number::Real(1.0)));
+  assert_eq!(solset.get(SeqRef(1), DOF_TX), Some(F06Number::Real(0.5)));
+  assert_eq!(solset.get(GridPointRef { gid: 1 }, DOF_TX), None);
+  assert_eq!(disp.get(SeqRef(3), DOF_TX), None);
+  assert!(solset.can_merge(disp).is_err());
+}
+
+#[test]
+fn test_column_dimensions() {
+  // every column of every block type has a known dimension, save for the
+  // ones that depend on the row or on normalisation
+  let unknown = [
+    (BlockType::RealEigenvalues, EigenvalueField::GeneralizedMass.into()),
+    (BlockType::RealEigenvalues, EigenvalueField::GeneralizedStiffness.into()),
+    (BlockType::BucklingEigenvalues, EigenvalueField::GeneralizedMass.into()),
+    (
+      BlockType::BucklingEigenvalues,
+      EigenvalueField::GeneralizedStiffness.into()
+    ),
+    (BlockType::GridPointWeight, GpwgField::Value.into()),
+  ];
+  for bt in BlockType::all() {
+    assert!(!bt.columns().is_empty(), "no columns in {}", bt);
+    for col in bt.columns() {
+      let dim = col.dimension(*bt);
+      assert_eq!(
+        dim == PhysicalDimension::Unknown,
+        unknown.contains(&(*bt, col)),
+        "{} in {} is {}", col, bt, dim
+      );
+      assert_eq!(dim.exponents().is_none(), dim == PhysicalDimension::Unknown);
+    }
+  }
+  // the tricky ones
+  let table: &[(BlockType, NasIndex, PhysicalDimension)] = &[
+    (BlockType::Displacements, DOF_TX.into(), PhysicalDimension::Length),
+    (BlockType::Displacements, DOF_RX.into(), PhysicalDimension::Angle),
+    (BlockType::Eigenvectors, DOF_RX.into(), PhysicalDimension::Angle),
+    (BlockType::SpcForces, DOF_TX.into(), PhysicalDimension::Force),
+    (BlockType::SpcForces, DOF_RX.into(), PhysicalDimension::Moment),
+    (BlockType::ThermalLoads, DOF_RX.into(), PhysicalDimension::Moment),
+    (
+      BlockType::Velocities,
+      DOF_RX.into(),
+      PhysicalDimension::AngularVelocity
+    ),
+    (
+      BlockType::Accelerations,
+      DOF_RX.into(),
+      PhysicalDimension::AngularAcceleration
+    ),
+    (
+      BlockType::BushStresses,
+      BushStressField::RotationX.into(),
+      PhysicalDimension::Stress
+    ),
+    (
+      BlockType::BushStrains,
+      BushStrainField(BushStressField::RotationX).into(),
+      PhysicalDimension::Strain
+    ),
+    (
+      BlockType::QuadStresses,
+      PlateStressField::Angle.into(),
+      PhysicalDimension::Angle
+    ),
+    (
+      BlockType::QuadStrains,
+      PlateStrainField(PlateStressField::FibreDistance).into(),
+      PhysicalDimension::Length
+    ),
+    (
+      BlockType::QuadStrains,
+      PlateStrainField(PlateStressField::VonMises).into(),
+      PhysicalDimension::Strain
+    ),
+    (
+      BlockType::QuadForces,
+      PlateForceField::MomentX.into(),
+      PhysicalDimension::MomentPerLength
+    ),
+    (
+      BlockType::CompositePlystresses,
+      CompositeStressField::Angle.into(),
+      PhysicalDimension::Angle
+    ),
+    (
+      BlockType::BarStresses,
+      BarStressField::SafetyMargin(NormalStressDirection::Tension).into(),
+      PhysicalDimension::Dimensionless
+    ),
+    (
+      BlockType::BeamStrains,
+      BeamStrainField(BeamStressField::MarginCompression).into(),
+      PhysicalDimension::Dimensionless
+    ),
+    (
+      BlockType::RodStresses,
+      RodStressField::TorsionalSafetyMargin.into(),
+      PhysicalDimension::Dimensionless
+    ),
+    (
+      BlockType::CshearStresses,
+      CshearStressField::MarginOfSafety.into(),
+      PhysicalDimension::Dimensionless
+    ),
+    (
+      BlockType::CshearForces,
+      CshearForceField::Shear12.into(),
+      PhysicalDimension::ForcePerLength
+    ),
+    (
+      BlockType::CgapForces,
+      CgapForceField::Slip1.into(),
+      PhysicalDimension::Length
+    ),
+    (
+      BlockType::RealEigenvalues,
+      EigenvalueField::Eigenvalue.into(),
+      PhysicalDimension::AngularFrequencySquared
+    ),
+    (
+      BlockType::BucklingEigenvalues,
+      EigenvalueField::Eigenvalue.into(),
+      PhysicalDimension::Dimensionless
+    ),
+    (
+      BlockType::ModalEffectiveMass,
+      ModalMassField::Rx.into(),
+      PhysicalDimension::MomentOfInertia
+    ),
+    (
+      BlockType::StrainEnergy,
+      StrainEnergyField::Density.into(),
+      PhysicalDimension::EnergyDensity
+    ),
+    (BlockType::ChexaStresses, DOF_TX.into(), PhysicalDimension::Unknown),
+  ];
+  for (bt, col, dim) in table.iter() {
+    assert_eq!(col.dimension(*bt), *dim, "{} in {}", col, bt);
+  }
+  // moments and energies share exponents, angles and strains have none
+  assert_eq!(
+    PhysicalDimension::Moment.exponents(),
+    PhysicalDimension::Energy.exponents()
+  );
+  assert_eq!(
+    PhysicalDimension::MomentPerLength.exponents(),
+    PhysicalDimension::Force.exponents()
+  );
+  assert!(PhysicalDimension::Angle.exponents().unwrap().is_dimensionless());
+  assert!(PhysicalDimension::Strain.exponents().unwrap().is_dimensionless());
+}
+
+#[test]
+fn test_scale_units() {
+  // metres to millimetres and kilograms to tonnes: forces are unchanged,
+  // stresses go from Pa to MPa
+  let scales = UnitScales { mass: 1e-3, length: 1e3, ..UnitScales::identity() };
+  let factor = |d| scales.factor(d).unwrap();
+  assert!((factor(PhysicalDimension::Force) - 1.0).abs() < 1e-12);
+  assert!((factor(PhysicalDimension::Stress) - 1e-6).abs() < 1e-18);
+  assert!((factor(PhysicalDimension::Moment) - 1e3).abs() < 1e-9);
+  assert_eq!(factor(PhysicalDimension::Angle), 1.0);
+  assert_eq!(scales.factor(PhysicalDimension::Unknown), None);
+  // translations scale, rotations don't, in both kinds of displacements
+  let mut file = parse_str(SOLSET_FIXTURE);
+  assert!(file.scale_units(&scales).is_empty());
+  let disp = file.block_search(Some(BlockType::Displacements), None, false)
+    .next()
+    .unwrap();
+  assert_eq!(disp.column_dimension(DOF_RX.into()), PhysicalDimension::Angle);
+  let gid = GridPointRef { gid: 1 };
+  assert_eq!(disp.get(gid, DOF_TX), Some(F06Number::Real(1000.0)));
+  assert_eq!(disp.get(gid, DOF_RX), Some(F06Number::Real(4.0)));
+  let solset = file.block_search(
+    Some(BlockType::SolutionSetDisplacements),
+    None,
+    false
+  ).next().unwrap();
+  assert_eq!(solset.get(SeqRef(3), DOF_TX), Some(F06Number::Real(-500.0)));
+}
+
+/// Tiny deterministic pseudo-random generator for property tests.
+fn lcg(state: &mut u64) -> usize {
+  *state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+  return (*state >> 33) as usize;
+}
+
+#[test]
+fn test_id_selection_properties() {
+  let mut state = 0x5eed;
+  for _ in 0..500 {
+    // generate some random terms, keeping them for a brute-force check
+    let nterms = 1 + lcg(&mut state) % 6;
+    let mut terms: Vec<(bool, usize, usize)> = Vec::new();
+    let mut text: Vec<String> = Vec::new();
+    for _ in 0..nterms {
+      let exclude = lcg(&mut state).is_multiple_of(3);
+      let a = lcg(&mut state) % 40;
+      let (b, s) = match lcg(&mut state) % 4 {
+        0 => (a, a.to_string()),
+        1 => (usize::MAX, format!("{}-", a)),
+        2 => (usize::MAX, String::from("all")),
+        _ => {
+          let b = a + lcg(&mut state) % 10;
+          (b, format!("{}-{}", a, b))
+        }
+      };
+      let a = if s == "all" { 0 } else { a };
+      terms.push((exclude, a, b));
+      text.push(if exclude { format!("!{}", s) } else { s });
+    }
+    let text = text.join(",");
+    let sel: IdSelection = text.parse().unwrap();
+    // later terms win, a leading exclusion starts from everything
+    let reference = |id: usize| -> bool {
+      let mut inside = terms[0].0;
+      for &(exclude, a, b) in terms.iter() {
+        if a <= id && id <= b {
+          inside = !exclude;
+        }
+      }
+      return inside;
+    };
+    for id in 0..60 {
+      assert_eq!(sel.contains(id), reference(id), "{} at {}", text, id);
+    }
+    // display is canonical and round-trips
+    let shown = sel.to_string();
+    let reparsed: IdSelection = shown.parse().unwrap();
+    assert_eq!(reparsed, sel, "{} shown as {}", text, shown);
+    assert_eq!(reparsed.to_string(), shown);
+    let within = sel.iter_within(0..=59).collect::<Vec<_>>();
+    let expected = (0..60).filter(|i| reference(*i)).collect::<Vec<_>>();
+    assert_eq!(within, expected);
+  }
+}
+
+#[test]
+fn test_id_selection_errors() {
+  let cases = [
+    ("1,,2", 2, IdSelectionErrorKind::EmptyTerm),
+    ("1,", 2, IdSelectionErrorKind::EmptyTerm),
+    ("1, x-3", 3, IdSelectionErrorKind::BadNumber("x".into())),
+    ("-5", 0, IdSelectionErrorKind::BadNumber("".into())),
+    ("7,10-3", 2, IdSelectionErrorKind::ReversedRange(10, 3)),
+    ("1,!", 2, IdSelectionErrorKind::EmptyExclusion),
+  ];
+  for (text, position, kind) in cases {
+    let err = text.parse::<IdSelection>().unwrap_err();
+    assert_eq!(err, IdSelectionError { position, kind }, "{}", text);
+  }
+  // and some junk shouldn't panic
+  let mut state = 0xbad;
+  let alphabet = b"0123456789-!,a ";
+  for _ in 0..500 {
+    let len = lcg(&mut state) % 12;
+    let junk: String = (0..len)
+      .map(|_| alphabet[lcg(&mut state) % alphabet.len()] as char)
+      .collect();
+    if let Err(e) = junk.parse::<IdSelection>() {
+      assert!(e.position <= junk.len());
+    }
+  }
+}
+
+/// A Simcenter-like output where subcase 2 begins mid-page, under a page
+/// header that shows subcase 1, and the next page goes back to subcase 1.
+const MIDPAGE_SUBCASE_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    MID-PAGE SUBCASE TEST                                 SIMCENTER NASTRAN  2/11/21   PAGE     1
+      TEST MODEL
+0     LOAD A                                                                     SUBCASE 1
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+          1011      G      1.000000E+00   0.0            0.0            0.0            0.0            0.0
+ 
+0     LOAD B                                                                     SUBCASE 2
+ 
+                               F O R C E S   O F   S I N G L E - P O I N T   C O N S T R A I N T
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+          1011      G      2.000000E+00   0.0            0.0            0.0            0.0            0.0
+          1012      G      2.000000E+00   0.0            0.0            0.0            0.0            0.0
+1    MID-PAGE SUBCASE TEST                                 SIMCENTER NASTRAN  2/11/21   PAGE     2
+      TEST MODEL
+0     LOAD A                                                                     SUBCASE 1
+ 
+                               F O R C E S   O F   S I N G L E - P O I N T   C O N S T R A I N T
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+          1013      G      2.000000E+00   0.0            0.0            0.0            0.0            0.0
+1    MID-PAGE SUBCASE TEST                                 SIMCENTER NASTRAN  2/11/21   PAGE     3
+      TEST MODEL
+0     LOAD B                                                                     SUBCASE 2
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+          1011      G      3.000000E+00   0.0            0.0            0.0            0.0            0.0
+1    MID-PAGE SUBCASE TEST                                 SIMCENTER NASTRAN  2/11/21   PAGE     4
+";
+
+#[test]
+fn test_midpage_subcase_change() {
+  let file = parse_str(MIDPAGE_SUBCASE_FIXTURE);
+  // the spc forces after the marker belong to subcase 2, and those on the
+  // next page to subcase 1, as its header says
+  let spc_rows = |subcase| file.block_search(
+    Some(BlockType::SpcForces),
+    Some(subcase),
+    false
+  ).map(|b| b.row_indexes.len()).sum::<usize>();
+  assert_eq!(spc_rows(2), 2);
+  assert_eq!(spc_rows(1), 1);
+  // displacements are unaffected
+  let disp_subcases = file.block_search(
+    Some(BlockType::Displacements),
+    None,
+    false
+  ).map(|b| b.subcase).collect::<Vec<_>>();
+  assert_eq!(disp_subcases, vec![1, 2]);
+  // and the disagreement was reported
+  let kind = DiagnosticKind::SubcaseDisagreement {
+    page_header: 1,
+    marker: 2,
+    chosen: 2
+  };
+  let diag = file.diagnostics.iter().find(|d| d.kind == kind).unwrap();
+  assert_eq!(diag.count, 1);
+}
+
+#[test]
+fn test_midpage_subcase_corpus() {
+  // how many blocks each subcase of each example has: the page headers and
+  // the markers never disagree in them, so nothing moves
+  let known: &[(&str, &[(usize, usize)])] = &[
+    ("mystran/SB-ALL-ELEM-TEST.F06", &[(91, 16), (92, 16)]),
+    ("mystran/SB-BUSH-01-OFFSET-2b.F06", &[(1, 7)]),
+    (
+      "scnastran/SB-ALL-ELEM-TEST_scnas_postexport.f06",
+      &[(1, 1), (91, 23), (92, 22)]
+    ),
+    ("scnastran/SB-BUSH-01-OFFSET-2b_scnas_postexport.f06", &[(1, 7)]),
+    ("scnastran/beam_transient_sol109.f06", &[(1, 3)]),
+    ("scnastran/beam_transient_steps_sol109.f06", &[(1, 3)]),
+    ("scnastran/column_buckling_sol105.f06", &[(1, 1), (2, 1)]),
+    ("scnastran/many_quads_scnas_postexport.f06", &[(1, 1027)])
+  ];
+  let corpus = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("../examples");
+  for (name, subcases) in known {
+    let file = OnePassParser::parse_file(corpus.join(name)).unwrap();
+    let mut found: BTreeMap<usize, usize> = BTreeMap::new();
+    for block in file.all_blocks(false) {
+      *found.entry(block.subcase).or_default() += 1;
+    }
+    let expected = subcases.iter().copied().collect::<BTreeMap<_, _>>();
+    assert_eq!(found, expected, "{}", name);
+    let disagreements = file.diagnostics.iter()
+      .filter(|d| matches!(d.kind, DiagnosticKind::SubcaseDisagreement {..}))
+      .count();
+    assert_eq!(disagreements, 0, "{}", name);
+  }
+}
+
+/// An example file with lots of different block types.
+const ALL_ELEM_F06: &str = include_str!(
+  "../../examples/mystran/SB-ALL-ELEM-TEST.F06"
+);
+
+#[test]
+fn test_typed_accessors() {
+  let mut file = OnePassParser::parse_bufread(ALL_ELEM_F06.as_bytes())
+    .unwrap();
+  file.merge_blocks(true);
+  let assert_near = |a: f64, b: f64| {
+    assert!((a - b).abs() <= 1e-6 * b.abs().max(1.0), "{} != {}", a, b);
+  };
+  let assert_all_near = |a: [f64; 6], b: [f64; 6]| {
+    a.into_iter().zip(b).for_each(|(x, y)| assert_near(x, y));
+  };
+  // grid point quantities
+  assert_all_near(
+    file.displacement(92, 1021).unwrap(),
+    [
+      7.850157E-04, 5.943438E-03, -7.420563E-02,
+      -2.040270E-03, -1.235242E-03, -1.809845E-05
+    ]
+  );
+  assert_all_near(
+    file.spc_force(92, 1012).unwrap(),
+    [2.273737E-13, -8.664782E+03, 2.712613E+03, 0.0, 0.0, 0.0]
+  );
+  assert_all_near(
+    file.applied_force(92, 1031).unwrap(),
+    [0.0, 1.0E+03, -3.0E+03, 0.0, 0.0, 0.0]
+  );
+  // element quantities
+  let bottom = file.quad_stress(
+    91, 11, ElementSide::Bottom, ElementPoint::Centroid
+  ).unwrap();
+  assert_near(bottom.fibre_distance, -6.25E-02);
+  assert_near(bottom.normal_x, 7.26577E+01);
+  assert_near(bottom.von_mises, 3.28018E+02);
+  let top = file.quad_stress(91, 12, ElementSide::Top, ElementPoint::Centroid)
+    .unwrap();
+  assert_near(top.fibre_distance, 6.25E-02);
+  assert_near(top.shear_xy, -3.66643E+00);
+  assert_near(top.angle, -88.83);
+  assert_near(file.rod_axial_force(91, 1242).unwrap(), 1.167338E+03);
+  assert_near(file.rod_axial_force(91, 4232).unwrap(), -2.632349E+03);
+  // absent stuff
+  assert!(file.displacement(92, 9999).is_none());
+  assert!(file.displacement(12345, 1021).is_none());
+  assert!(file.applied_force(92, 1011).is_none());
+  assert!(file.rod_axial_force(91, 11).is_none());
+  let corner = ElementPoint::Corner(GridPointRef { gid: 1 });
+  assert!(file.quad_stress(91, 11, ElementSide::Top, corner).is_none());
+  // an unmerged, earlier, empty block doesn't get in the way
+  let br = BlockRef { subcase: 92, block_type: BlockType::Displacements };
+  let mut empty = file.blocks[&br][0].clone();
+  empty.row_indexes.clear();
+  empty.line_range = Some((1, 2));
+  file.blocks.get_mut(&br).unwrap().insert(0, empty);
+  assert_near(file.displacement(92, 1021).unwrap()[2], -7.420563E-02);
+}
+
+#[test]
+fn test_mystran_rod_forces() {
+  // pins what the rod force decoder got out of the MYSTRAN examples before
+  // it started splitting packed lines like the springs
+  let file = parse_str(ALL_ELEM_F06);
+  let expected: [(usize, [f64; 6]); 2] = [
+    (91, [
+      6.525059E+02, 1.167338E+03, 6.525059E+02,
+      -1.447996E+03, -2.632349E+03, -1.447996E+03
+    ]),
+    (92, [
+      2.950894E+03, 2.865406E+03, 2.950894E+03,
+      -6.600234E+03, -6.403573E+03, -6.600234E+03
+    ])
+  ];
+  let eids = [1141, 1242, 1343, 4131, 4232, 4333];
+  for (subcase, axials) in expected {
+    let br = BlockRef { subcase, block_type: BlockType::RodForces };
+    let blocks = &file.blocks[&br];
+    assert_eq!(blocks.len(), 1);
+    let block = &blocks[0];
+    let rows: Vec<NasIndex> = block.row_indexes.keys().copied().collect();
+    let erefs: Vec<NasIndex> = eids.iter()
+      .map(|&eid| ElementRef { eid, etype: Some(ElementType::Rod) }.into())
+      .collect();
+    assert_eq!(rows, erefs);
+    for (eid, axial) in eids.into_iter().zip(axials) {
+      let eref = ElementRef { eid, etype: Some(ElementType::Rod) };
+      let got = block.get(eref, RodForceField::AxialForce).unwrap();
+      assert_eq!(f64::from(got), axial);
+      let torque = block.get(eref, RodForceField::Torque).unwrap();
+      assert_eq!(f64::from(torque), 0.0);
+    }
+  }
+}
+
+/// Adds the progress lines some MYSTRAN debug settings print in the middle
+/// of stress tables to an output, before each line that starts with an ID.
+fn with_progress_lines(f06: &str) -> String {
+  let mut noisy = String::new();
+  let mut in_stresses = false;
+  for line in f06.lines() {
+    if line.contains("S T R E S S E S") {
+      in_stresses = true;
+    } else if line.contains("OUTPUT FOR SUBCASE") {
+      in_stresses = false;
+    }
+    let id = line.split_whitespace()
+      .next()
+      .and_then(|w| w.parse::<usize>().ok())
+      .filter(|_| in_stresses);
+    if let Some(id) = id {
+      noisy.push_str(
+        &format!(" STRESS DATA RECOVERY FOR ELEMENT {:>9} OF {:>9}\n", id, 45)
+      );
+    }
+    noisy.push_str(line);
+    noisy.push('\n');
+  }
+  return noisy;
+}
+
+#[test]
+fn test_debug_lines() {
+  let clean = parse_str(ALL_ELEM_F06);
+  let noisy_f06 = with_progress_lines(ALL_ELEM_F06);
+  let noisy = parse_str(&noisy_f06);
+  assert_eq!(clean.potential_headers.len(), noisy.potential_headers.len());
+  assert!(clean.blocks.keys().eq(noisy.blocks.keys()));
+  let mut debug = 0;
+  for (br, blocks) in clean.blocks.iter() {
+    let twins = &noisy.blocks[br];
+    assert_eq!(blocks.len(), twins.len());
+    for (block, twin) in blocks.iter().zip(twins.iter()) {
+      assert_eq!(block.row_indexes, twin.row_indexes);
+      assert_eq!(block.col_indexes, twin.col_indexes);
+      assert_eq!(block.data, twin.data);
+      // the progress lines are counted as metadata, and only as that
+      let counts = &block.line_counts;
+      let extra = twin.line_counts.debug;
+      assert_eq!(counts.debug, 0);
+      assert_eq!(
+        twin.line_counts.of(LineResponse::Metadata),
+        counts.of(LineResponse::Metadata) + extra
+      );
+      assert_eq!(
+        twin.line_counts.of(LineResponse::Data),
+        counts.of(LineResponse::Data)
+      );
+      debug += extra;
+    }
+  }
+  assert_eq!(debug, noisy_f06.matches("DATA RECOVERY").count());
+  assert!(debug > 0);
+}
+
+#[test]
+fn test_extra_debug_lines() {
+  let clean = parse_str(ALL_ELEM_F06);
+  let noisy_f06 = with_progress_lines(ALL_ELEM_F06)
+    .replace("STRESS DATA RECOVERY", "ELEMENT PROGRESS");
+  // unknown progress lines reach the decoders
+  let noisy = parse_str(&noisy_f06);
+  let debug_in = |file: &F06File| -> usize {
+    return file.all_blocks(false).map(|b| b.line_counts.debug).sum();
+  };
+  assert_eq!(debug_in(&noisy), 0);
+  let mut parser = OnePassParser::new();
+  parser.debug_line_prefixes(&["ELEMENT PROGRESS"]);
+  parser.record_index();
+  parser.feed_bytes(noisy_f06.as_bytes()).unwrap();
+  let (skipped, index) = parser.finish_indexed();
+  assert_eq!(index.debug_prefixes, ["ELEMENT PROGRESS"]);
+  assert!(clean.blocks.keys().eq(skipped.blocks.keys()));
+  for (br, blocks) in clean.blocks.iter() {
+    for (block, twin) in blocks.iter().zip(skipped.blocks[br].iter()) {
+      assert_eq!(block.row_indexes, twin.row_indexes);
+      assert_eq!(block.data, twin.data);
+    }
+  }
+  let count = noisy_f06.matches("ELEMENT PROGRESS").count();
+  assert_eq!(debug_in(&skipped), count);
+}
+
+#[test]
+fn test_print_precision() {
+  let near = |a: Option<f64>, b: f64| {
+    assert!(a.is_some_and(|a| (a - b).abs() < b * 1e-9), "{:?} != {}", a, b);
+  };
+  near(printed_precision("7.850157E-04"), 1e-6);
+  near(printed_precision("-6.250E-02"), 1e-3);
+  near(printed_precision("+1.5-3"), 1e-1);
+  assert_eq!(printed_precision("0.0"), None);
+  assert_eq!(printed_precision("0.000000E+00"), None);
+  assert_eq!(printed_precision("85.71"), None);
+  assert_eq!(printed_precision("1011"), None);
+  assert_eq!(printed_precision("CENTER"), None);
+  // a block printed with six decimals
+  let mut file = OnePassParser::parse_bufread(ALL_ELEM_F06.as_bytes())
+    .unwrap();
+  file.merge_blocks(true);
+  let disp = file.block_search(Some(BlockType::Displacements), None, false)
+    .next()
+    .unwrap();
+  near(disp.print_precision, 1e-6);
+  // tria forces say their lines are data, so those count for it too
+  let forces = file.block_search(Some(BlockType::TriaForces), None, false)
+    .collect::<Vec<_>>();
+  assert!(!forces.is_empty());
+  for block in forces {
+    let rows = block.row_indexes.len();
+    assert_eq!(block.line_counts.of(LineResponse::Data), rows);
+    near(block.print_precision, 1e-6);
+  }
+}
+
+#[test]
+fn test_auto_tolerance() {
+  // the same file, with every real in exponent form re-printed with four
+  // decimals, right-aligned in the same width
+  let reformatted = ALL_ELEM_F06.lines()
+    .map(|line| {
+      return line.split(' ')
+        .map(|tok| match printed_precision(tok) {
+          Some(_) => {
+            let x = decode_nasfloat(tok).unwrap();
+            format!("{:>w$}", format!("{:.4E}", x), w = tok.len())
+          },
+          None => tok.to_owned()
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    })
+    .collect::<Vec<_>>()
+    .join("\n");
+  let load = |text: &str| {
+    let mut file = OnePassParser::parse_bufread(text.as_bytes()).unwrap();
+    file.merge_blocks(true);
+    file.sort_all_blocks();
+    return file;
+  };
+  let original = load(ALL_ELEM_F06);
+  let coarse = load(&reformatted);
+  let br = BlockRef { subcase: 92, block_type: BlockType::Displacements };
+  let sbr = SteppedBlockRef::from(br);
+  let p = coarse.blocks[&br][0].print_precision.unwrap();
+  assert!((p - 1e-4).abs() < 1e-12);
+  let mut settings = DiffSettings {
+    criteria: Criteria {
+      difference: None,
+      ratio: Some(1.0 + 1e-7),
+      nan: true,
+      inf: true,
+      sig: false
+    },
+    dxn_behaviour: Some(DisjunctionBehaviour::Skip),
+    max_flags: None,
+    auto_tol: false,
+    pair_by: PairBy::Subcase
+  };
+  // too tight, refused
+  let diff = F06Diff::compare(&settings, &original, &coarse);
+  assert!(matches!(
+    diff.not_compared.get(&sbr),
+    Some(NonCompareReason::BelowPrintPrecision(ToleranceTooTight::Ratio {
+      ..
+    }))
+  ));
+  // derived from the print precision, everything passes
+  settings.auto_tol = true;
+  let diff = F06Diff::compare(&settings, &original, &coarse);
+  assert!(diff.compared.contains_key(&sbr));
+  for (br, flags) in diff.compared.iter() {
+    assert!(flags.is_empty(), "{:?} flagged: {:?}", br, flags);
+  }
+}
+
+#[test]
+fn test_non_finite_values() {
+  for (text, expected) in [
+    ("NaN", Some(f64::NAN)),
+    ("-nan", Some(f64::NAN)),
+    ("1.#QNAN0", Some(f64::NAN)),
+    ("-1.#IND", Some(f64::NAN)),
+    ("****", Some(f64::NAN)),
+    ("+Inf", Some(f64::INFINITY)),
+    ("-INFINITY", Some(f64::NEG_INFINITY)),
+    ("1.#INF00", Some(f64::INFINITY)),
+    ("***", None),
+    ("INFO", None),
+    ("1.5", None)
+  ] {
+    // compared by bits, so NaNs are all the same one
+    let decoded = decode_non_finite(text).map(f64::to_bits);
+    assert_eq!(decoded, expected.map(f64::to_bits), "{}", text);
+  }
+  let file = parse_str(NON_FINITE_FIXTURE);
+  // the rows are all there, with the values as printed
+  let one = file.displacement(1, 1).unwrap();
+  assert_eq!(one[0], 1.0);
+  assert!(one[1].is_nan());
+  let two = file.displacement(1, 2).unwrap();
+  assert_eq!((two[0], two[1]), (f64::INFINITY, f64::NEG_INFINITY));
+  assert!(two[5].is_nan());
+  assert_eq!(file.displacement(1, 3).unwrap()[0], -1.0);
+  let block = file.block_search(Some(BlockType::Displacements), None, false)
+    .next()
+    .unwrap();
+  let found = block.non_finite();
+  assert_eq!(found.len(), 4);
+  assert_eq!(
+    (found[0].0, found[0].1),
+    (GridPointRef { gid: 1 }.into(), DOF_TY.into())
+  );
+  assert_eq!(file.non_finite_count(), 4);
+  assert_eq!(block.max_abs(), Some(1.0));
+  // reported per line, with the overflow on its own too
+  let count = |kind: DiagnosticKind| file.diagnostics.iter()
+    .find(|d| d.kind == kind)
+    .map(|d| (d.count, d.lines.clone()));
+  let block_type = BlockType::Displacements;
+  assert_eq!(
+    count(DiagnosticKind::NonFiniteValue { block_type }),
+    Some((2, vec![9, 10]))
+  );
+  assert_eq!(
+    count(DiagnosticKind::OverflowedField { block_type }),
+    Some((1, vec![10]))
+  );
+  // against themselves, only flagged when asked to
+  let mut settings = DiffSettings {
+    criteria: Criteria {
+      difference: Some(1.0),
+      ratio: Some(2.0),
+      nan: false,
+      inf: false,
+      sig: false
+    },
+    dxn_behaviour: Some(DisjunctionBehaviour::Flag),
+    max_flags: None,
+    auto_tol: true,
+    pair_by: PairBy::Subcase
+  };
+  let br = BlockRef { subcase: 1, block_type };
+  let sbr = SteppedBlockRef::from(br);
+  let mut tolerance: Option<ToleranceModel> = None;
+  let reasons = |
+    settings: &DiffSettings,
+    tolerance: &Option<ToleranceModel>,
+    other: &F06File
+  | {
+    let tolerance = tolerance.as_ref();
+    return F06Diff::compare_with_tolerance(settings, tolerance, &file, other)
+      .compared[&sbr]
+      .iter()
+      .map(|fp| fp.reason)
+      .collect::<Vec<_>>();
+  };
+  assert_eq!(reasons(&settings, &tolerance, &file), []);
+  settings.criteria.nan = true;
+  assert_eq!(
+    reasons(&settings, &tolerance, &file),
+    [FlagReason::NaN, FlagReason::NaN]
+  );
+  settings.criteria.inf = true;
+  let all = [
+    FlagReason::NaN,
+    FlagReason::Infinity,
+    FlagReason::Infinity,
+    FlagReason::NaN
+  ];
+  assert_eq!(reasons(&settings, &tolerance, &file), all);
+  // and the same with a tolerance model
+  let model = ToleranceModel::from_toml("[default]\nrelative = 1.0").unwrap();
+  tolerance = Some(model);
+  assert_eq!(reasons(&settings, &tolerance, &file), all);
+  settings.criteria.nan = false;
+  settings.criteria.inf = false;
+  assert_eq!(reasons(&settings, &tolerance, &file), []);
+  // against finite values, never within tolerance, without asking
+  let finite = parse_str(
+    &NON_FINITE_FIXTURE
+      .replace("NaN           ", "0.0           ")
+      .replace("Infinity      -1.#INF00     ", "0.0            0.0          ")
+      .replace("**************", "0.0           ")
+  );
+  assert_eq!(finite.non_finite_count(), 0);
+  assert_eq!(reasons(&settings, &tolerance, &finite), all);
+  tolerance = None;
+  assert_eq!(reasons(&settings, &tolerance, &finite), all);
+  // and in the stresses too
+  let chexa = SIMCENTER_CHEXA_FIXTURE.replace("1.020000E+02", "NaN");
+  let file = parse_str(&chexa);
+  let block = file.block_search(Some(BlockType::ChexaStresses), None, false)
+    .next()
+    .unwrap();
+  let corner = ElementPoint::Corner(GridPointRef { gid: 2 });
+  let get = |field| {
+    return chexa_stress(block, 101, corner, ElementSide::Bottom, field);
+  };
+  assert!(get(ChexaStressField::VonMises).is_nan());
+  assert_eq!(get(ChexaStressField::NormalX), 20.0);
+  assert_eq!(file.non_finite_count(), 1);
+}
+
+/// A MYSTRAN-like output with a grid point temperature per line.
+const MYSTRAN_TEMPERATURES_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+
+ OUTPUT FOR SUBCASE        1
+
+                                                       T E M P E R A T U R E S
+           GRID      TEMPERATURE
+              1     2.000000E+01
+              2     2.500000E+01
+              3    -4.000000E+01
+                    -------------
+
+ OUTPUT FOR SUBCASE        2
+
+                                                       T E M P E R A T U R E S
+           GRID      TEMPERATURE
+              1     1.000000E+02
+              2     1.250000E+02
+              3     1.500000E+02
+                    -------------
+";
+
+/// A Simcenter-like output with packed temperatures and a thermal load vector
+/// that must not be taken for applied forces.
+const SIMCENTER_TEMPERATURES_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    THERMAL TEST                                        SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                              T E M P E R A T U R E   V E C T O R
+ 
+      POINT ID.   VALUE       POINT ID.   VALUE       POINT ID.   VALUE
+             1   2.000000E+01        2   2.500000E+01        3   3.000000E+01
+             4   3.500000E+01
+1    THERMAL TEST                                        SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                                          T H E R M A L   L O A D   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1.000000E+00   0.0            0.0            0.0            0.0            0.0
+1    THERMAL TEST                                        SIMCENTER NASTRAN  2/11/21   PAGE     3
+0                                                                                SUBCASE 2
+ 
+                                              T E M P E R A T U R E   V E C T O R
+ 
+      POINT ID.   VALUE       POINT ID.   VALUE       POINT ID.   VALUE
+             1   1.000000E+02        2   1.250000E+02        3   1.500000E+02
+             4   1.750000E+02
+1    THERMAL TEST                                        SIMCENTER NASTRAN  2/11/21   PAGE     4
+";
+
+#[test]
+fn test_temperatures() {
+  let mystran = parse_str(MYSTRAN_TEMPERATURES_FIXTURE);
+  assert_eq!(mystran.flavour.solver, Some(Solver::Mystran));
+  assert_eq!(mystran.temperature(1, 3), Some(-40.0));
+  assert_eq!(mystran.temperature(2, 2), Some(125.0));
+  assert_eq!(mystran.temperature(2, 4), None);
+  let simcenter = parse_str(SIMCENTER_TEMPERATURES_FIXTURE);
+  assert_eq!(simcenter.flavour.solver, Some(Solver::Simcenter));
+  assert_eq!(
+    simcenter.block_types().collect::<Vec<_>>(),
+    vec![BlockType::ThermalLoads, BlockType::Temperatures]
+  );
+  assert_eq!(simcenter.temperature(1, 2), Some(25.0));
+  assert_eq!(simcenter.temperature(1, 4), Some(35.0));
+  assert_eq!(simcenter.temperature(2, 3), Some(150.0));
+  assert!(!simcenter.potential_headers.iter()
+    .any(|ph| ph.text.contains("THERMAL LOAD VECTOR")));
+}
+
+/// A MYSTRAN-like output with MPC forces next to SPC forces.
+const MYSTRAN_MPC_FORCES_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+
+ OUTPUT FOR SUBCASE        1
+
+                                                          S P C   F O R C E S
+                                              (in global coordinate system at each grid)
+           GRID     COORD      T1            T2            T3            R1            R2            R3
+                     SYS
+              1        0  1.000000E+02  0.0           0.0           0.0           0.0           0.0         
+                         ------------- ------------- ------------- ------------- ------------- -------------
+
+ OUTPUT FOR SUBCASE        1
+
+                                                          M P C   F O R C E S
+                                              (in global coordinate system at each grid)
+           GRID     COORD      T1            T2            T3            R1            R2            R3
+                     SYS
+              2        0 -5.000000E+01  0.0           2.500000E+00  0.0           0.0           1.000000E+00
+              3        0 -5.000000E+01  0.0          -2.500000E+00  0.0           0.0          -1.000000E+00
+                         ------------- ------------- ------------- ------------- ------------- -------------
+";
+
+/// A Simcenter-like output with MPC forces next to SPC forces.
+const SIMCENTER_MPC_FORCES_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    MPC TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                               F O R C E S   O F   S I N G L E - P O I N T   C O N S T R A I N T
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1.000000E+02   0.0            0.0            0.0            0.0            0.0
+1    MPC TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                                F O R C E S   O F   M U L T I - P O I N T   C O N S T R A I N T
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             2      G     -5.000000E+01   0.0            2.500000E+00   0.0            0.0            1.000000E+00
+             3      G     -5.000000E+01   0.0           -2.500000E+00   0.0            0.0           -1.000000E+00
+1    MPC TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     3
+";
+
+#[test]
+fn test_mpc_forces() {
+  for fixture in [MYSTRAN_MPC_FORCES_FIXTURE, SIMCENTER_MPC_FORCES_FIXTURE] {
+    let file = parse_str(fixture);
+    assert!(file.potential_headers.is_empty());
+    let bt = Some(BlockType::MpcForces);
+    assert_eq!(file.block_search(bt, Some(1), false).count(), 1);
+    assert_eq!(
+      file.mpc_force(1, 2),
+      Some([-50.0, 0.0, 2.5, 0.0, 0.0, 1.0])
+    );
+    assert_eq!(file.mpc_force(1, 3).map(|f| f[5]), Some(-1.0));
+    // the constraints don't get mixed up
+    assert_eq!(file.mpc_force(1, 1), None);
+    assert_eq!(file.spc_force(1, 1).map(|f| f[0]), Some(100.0));
+    assert_eq!(file.spc_force(1, 2), None);
+  }
+}
+
+#[test]
+fn test_stat_units() {
+  assert_eq!(decode_duration("00:01:23"), Some(83.0));
+  assert_eq!(decode_duration("01:00:00.5"), Some(3600.5));
+  assert_eq!(decode_duration("02:30"), Some(150.0));
+  let cpu = decode_duration("7.81E-02 seconds").unwrap();
+  assert!((cpu - 7.81e-2).abs() < 1e-12);
+  assert_eq!(decode_duration("2 MIN"), Some(120.0));
+  assert_eq!(decode_duration("12 parsecs"), None);
+  assert_eq!(decode_bytes("12.5 MB"), Some(13_107_200));
+  assert_eq!(decode_bytes("512 KB"), Some(524_288));
+  assert_eq!(decode_bytes("12345678 WORDS"), Some(98_765_424));
+  assert_eq!(decode_bytes("84 K WORDS"), Some(688_128));
+  assert_eq!(decode_bytes("84"), None);
+}
+
+/// A MYSTRAN-like summary, with a run that crosses midnight.
+const MYSTRAN_RUN_STATS_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3   Dec 20 2023 MYSTRAN developed by Dr Bill Case
+ >> MYSTRAN BEGIN  : 12/31/2023 at 23:59:50.000 The input file is STATS.DAT
+ *INFORMATION: NUMBER OF DEGREES OF FREEDOM IN THE G-SET =        36
+ *INFORMATION: NUMBER OF DEGREES OF FREEDOM IN THE A-SET =        24
+ >> LINK  9 END
+
+    Total CPU time =  1.25E+00 seconds
+
+ >> MYSTRAN END    :  1/1/2024 at 00:00:02.500
+";
+
+/// A Simcenter-like decomposition message and resource summary.
+const SIMCENTER_RUN_STATS_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+ *** USER INFORMATION MESSAGE 4157 (DFMSYM)
+     PARAMETERS FOR PARALLEL SPARSE DECOMPOSITION OF DATA BLOCK KLL      ( TYPE=RDP ) FOLLOW
+                            MATRIX SIZE =     1266 ROWS             NUMBER OF NONZEROES =     20505 TERMS
+                 NUMBER OF ZERO COLUMNS =        0        NUMBER OF ZERO DIAGONAL TERMS =         0
+                           CPU TIME ESTIMATE =        0 SEC                I/O TIME ESTIMATE =         0 SEC
+                    MINIMUM MEMORY REQUIREMENT =       84 K WORDS             MEMORY AVAILABLE =   262144 K WORDS
+                                   * * * RESOURCE SUMMARY * * *
+                 G-SET SIZE           =        7566
+                 A-SET SIZE           =        7470
+                 ELAPSED TIME         =    00:01:23.5
+                 CPU TIME             =    00:00:41.25
+                 MAXIMUM MEMORY USED  =    512.0 MB
+                 HIWATER MEMORY       =    70000000 WORDS
+1                                        * * * END OF JOB * * *
+";
+
+#[test]
+fn test_run_stats() {
+  let close = |a: Option<f64>, b: f64| (a.unwrap() - b).abs() < 1e-9;
+  // the real thing
+  let example = parse_str(ALL_ELEM_F06);
+  assert!(close(example.run_stats.cpu_time, 7.81e-2));
+  // as printed, with no digits from the subtraction
+  assert_eq!(example.run_stats.wall_time, Some(0.171));
+  assert_eq!(example.run_stats.peak_memory, None);
+  // mystran, with set sizes
+  let mystran = parse_str(MYSTRAN_RUN_STATS_FIXTURE).run_stats;
+  assert!(close(mystran.cpu_time, 1.25));
+  assert!(close(mystran.wall_time, 12.5));
+  assert_eq!(mystran.dof_counts.get("G"), Some(&36));
+  assert_eq!(mystran.dof_counts.get("A"), Some(&24));
+  // simcenter
+  let simcenter = parse_str(SIMCENTER_RUN_STATS_FIXTURE).run_stats;
+  assert!(close(simcenter.wall_time, 83.5));
+  assert!(close(simcenter.cpu_time, 41.25));
+  assert_eq!(simcenter.peak_memory, Some(560_000_000));
+  assert_eq!(simcenter.dof_counts.get("G"), Some(&7566));
+  assert_eq!(simcenter.dof_counts.get("A"), Some(&7470));
+  assert_eq!(simcenter.matrix_sizes.get("KLL"), Some(&1266));
+  assert_eq!(simcenter.matrix_sizes.len(), 1);
+  assert!(simcenter.entries().contains(
+    &("Rows in KLL".to_owned(), "1266".to_owned())
+  ));
+}
+
+/// A Simcenter-like table of epsilons, with a flagged one (and one printed
+/// without an exponent letter).
+const SIMCENTER_EPSILON_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+ *** USER INFORMATION MESSAGE 5293 (SSG3A)
+    FOR DATA BLOCK KLL
+    LOAD SEQ. NO.             EPSILON              EXTERNAL WORK      EPSILONS LARGER THAN 0.001 ARE FLAGGED WITH ASTERISKS
+    User information:
+    One line of output is printed for each static loading condition.
+                1          5.3482390E-16          4.2147120E+02
+                2          3.1000000E-02 ****     9.1858026E+02
+                3          1.2345678-15           1.0000000+00
+1    TABLE OF EPSILONS                                   SIMCENTER NASTRAN  2/11/21   PAGE     9
+";
+
+/// A Simcenter-like case control echo whose subcases aren't declared in
+/// ascending order.
+const REORDERED_SUBCASES_ECHO: &str = "
+                                          C A S E    C O N T R O L    E C H O
+                 COMMAND
+                 COUNT
+                  1      SUBCASE 20
+                  2        LOAD = 2
+                  3      SUBCASE 10
+                  4        LOAD = 1
+                  5      SUBCASE 30
+                  6        LOAD = 3
+                  7      BEGIN BULK
+";
+
+#[test]
+fn test_solution_diagnostics() {
+  // exact reals, with or without the exponent letter
+  assert_eq!(decode_nasfloat("5.3482390E-16"), Some(5.3482390E-16));
+  assert_eq!(decode_nasfloat("1.2345678-15"), Some(1.2345678e-15));
+  assert_eq!(decode_nasfloat("-1.5+02"), Some(-150.0));
+  assert_eq!(decode_nasfloat("-"), None);
+  assert_eq!(decode_nasfloat("1.0E-"), None);
+  // mystran, mapped onto the subcases of the case control
+  let mystran = parse_str(ALL_ELEM_F06);
+  let subcases = mystran.solution_diagnostics.keys().copied();
+  assert_eq!(subcases.collect::<Vec<_>>(), [91, 92]);
+  let diag = mystran.solution_diagnostics[&91];
+  assert_eq!(diag.load_sequence, 1);
+  assert_eq!(diag.epsilon, Some(2.584605E-16));
+  assert_eq!(diag.external_work, None);
+  assert!(!mystran.ill_conditioned());
+  assert_eq!(diag.to_string(), "epsilon 2.584605E-16");
+  assert!(mystran.solution_diagnostic_entries().contains(
+    &("Epsilon (subcase 91)".to_owned(), "2.584605E-16".to_owned())
+  ));
+  // simcenter, with the external work
+  let simcenter = parse_str(include_str!(
+    "../../examples/scnastran/SB-ALL-ELEM-TEST_scnas_postexport.f06"
+  ));
+  let diag = simcenter.solution_diagnostics[&92];
+  assert_eq!(diag.epsilon, Some(5.7184029E-17));
+  assert_eq!(diag.external_work, Some(9.1858026E+02));
+  assert!(simcenter.solution_diagnostic_entries().contains(
+    &("External work (subcase 91)".to_owned(), "4.214712E2".to_owned())
+  ));
+  // without a case control, the load sequence numbers are the subcases
+  let flagged = parse_str(SIMCENTER_EPSILON_FIXTURE);
+  let diags = &flagged.solution_diagnostics;
+  assert_eq!(diags.len(), 3);
+  assert!(!diags[&1].ill_conditioned());
+  assert!(diags[&2].flagged);
+  assert_eq!(
+    diags[&2].to_string(),
+    "epsilon 3.1E-2, external work 9.1858026E2 (ill-conditioned)"
+  );
+  assert_eq!(diags[&3].epsilon, Some(1.2345678e-15));
+  assert_eq!(diags[&3].external_work, Some(1.0));
+  assert!(flagged.ill_conditioned());
+  // load cases go in the order of the echo, not of the subcase IDs
+  let reordered = parse_str(&format!(
+    "{}{}",
+    REORDERED_SUBCASES_ECHO,
+    SIMCENTER_EPSILON_FIXTURE
+  ));
+  let diags = &reordered.solution_diagnostics;
+  assert_eq!(diags.keys().copied().collect::<Vec<_>>(), [10, 20, 30]);
+  assert_eq!(diags[&20].load_sequence, 1);
+  assert_eq!(diags[&10].load_sequence, 2);
+  assert_eq!(diags[&30].load_sequence, 3);
+}
+
+/// A Simcenter-like composite failure indices table: two elements with two
+/// plies each, the bonds between plies and the maxima.
+const COMPOSITE_FAILURE_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    COMPOSITE TEST                                      SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                   F A I L U R E   I N D I C E S   F O R   L A Y E R E D   C O M P O S I T E   E L E M E N T S   ( Q U A D 4 )
+   ELEMENT  FAILURE    PLY   FP=FAILURE INDEX FOR PLY    FB=FAILURE INDEX FOR BONDING   MAX OF FP,FB FOR ALL PLIES
+     ID      THEORY     ID  (DIRECT STRESSES/STRAINS)     (INTER-LAMINAR STRESSES)
+       101   HOFFMAN     1      0.2104   4.7529
+                                                              0.0312   32.051
+                         2      0.1870   5.3476
+                                                              0.0281   35.587
+                                                                                                0.2104   4.7529
+       102   HOFFMAN     1      0.5120   1.9531
+                                                              0.0405   24.691
+                         2      0.6348   1.5753
+                                                              0.0377   26.525
+                                                                                                0.6348   1.5753
+1    COMPOSITE TEST                                      SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+#[test]
+fn test_composite_failure_indices() {
+  let file = parse_str(COMPOSITE_FAILURE_FIXTURE);
+  assert_eq!(file.flavour.solver, Some(Solver::Simcenter));
+  let blocks = file.all_blocks(false).collect::<Vec<_>>();
+  assert_eq!(blocks.len(), 1);
+  let block = blocks[0];
+  assert_eq!(block.block_type, BlockType::CompositeFailureIndices);
+  assert_eq!(block.block_type.schema().row_dimension, RowDimension::Ply);
+  assert_eq!(
+    block.metadata.get(META_FAILURE_THEORY).map(String::as_str),
+    Some("HOFFMAN")
+  );
+  // every line made it in: plies, bonds and maxima
+  assert_eq!(block.row_indexes.len(), 10);
+  // with only the columns by theory
+  assert_eq!(block.col_indexes.len(), 2);
+  let row = |eid: usize, layer: CompositeLayer| ElementPlyRef {
+    element: ElementRef { eid, etype: Some(ElementType::Quad4) },
+    layer,
+    side: ElementSide::Middle
+  };
+  let get = |eid, layer, col| block.get(row(eid, layer), col).map(f64::from);
+  let fi = CompositeFailureField::FailureIndex;
+  let sr = CompositeFailureField::StrengthRatio;
+  assert_eq!(get(101, CompositeLayer::Ply(2), fi), Some(0.1870));
+  assert_eq!(get(101, CompositeLayer::Bond(1), sr), Some(32.051));
+  assert_eq!(get(101, CompositeLayer::AllPlies, fi), Some(0.2104));
+  assert_eq!(get(102, CompositeLayer::Bond(2), fi), Some(0.0377));
+  assert_eq!(get(102, CompositeLayer::AllPlies, sr), Some(1.5753));
+  assert!(file.potential_headers.is_empty());
+  // a missing strength ratio isn't made up
+  let file = parse_str(
+    &COMPOSITE_FAILURE_FIXTURE.replace("0.1870   5.3476", "0.1870")
+  );
+  let block = file.all_blocks(false).next().unwrap();
+  let ply = row(101, CompositeLayer::Ply(2));
+  assert_eq!(block.get(ply, fi).map(f64::from), Some(0.1870));
+  assert!(block.get(ply, sr).map(f64::from).unwrap().is_nan());
+}
+
+/// A Simcenter-like table of the stresses in the plies of a 4-ply laminate,
+/// without von Mises stresses.
+const SIMCENTER_PLY_STRESSES_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    COMPOSITE TEST                                      SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                   S T R E S S E S   I N   L A Y E R E D   C O M P O S I T E   E L E M E N T S   ( Q U A D 4 )
+   ELEMENT  PLY  STRESSES IN FIBER AND MATRIX DIRECTIONS    INTER-LAMINAR  STRESSES  PRINCIPAL STRESSES (ZERO SHEAR)      MAX
+     ID      ID    NORMAL-1     NORMAL-2     SHEAR-12     SHEAR XZ-MAT  SHEAR YZ-MAT  ANGLE    MAJOR        MINOR        SHEAR
+       101    1  -3.81691E+01 -2.53869E+01 -5.09133E+00   -1.85378E-01 -1.53285E-01   -68.91  -2.34228E+01 -4.01332E+01  8.35518E+00
+       101    2   1.20000E+01  4.00000E+00  3.00000E+00    1.00000E-01  2.00000E-01    18.43   1.30000E+01  3.00000E+00  5.00000E+00
+       101    3   1.10000E+01  3.00000E+00  2.00000E+00    1.00000E-01  1.00000E-01    13.28   1.14721E+01  2.52786E+00  4.47214E+00
+       101    4  -3.60000E+01 -2.40000E+01 -4.00000E+00   -1.00000E-01 -1.00000E-01   -73.15  -2.27889E+01 -3.72111E+01  7.21110E+00
+1    COMPOSITE TEST                                      SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+#[test]
+fn test_composite_ply_stresses() {
+  for (fixture, von_mises) in [
+    (MYSTRAN_PLY_STRESSES_FIXTURE, Some(34.9372)),
+    (SIMCENTER_PLY_STRESSES_FIXTURE, None)
+  ] {
+    let file = parse_str(fixture);
+    assert!(file.potential_headers.is_empty());
+    let blocks = file.all_blocks(false).collect::<Vec<_>>();
+    assert_eq!(blocks.len(), 1);
+    let block = blocks[0];
+    assert_eq!(block.block_type, BlockType::CompositePlystresses);
+    assert_eq!(block.block_type.schema().row_dimension, RowDimension::Ply);
+    assert_eq!(block.row_indexes.len(), 4);
+    let row = |ply| ElementPlyRef {
+      element: ElementRef { eid: 101, etype: Some(ElementType::Quad4) },
+      layer: CompositeLayer::Ply(ply),
+      side: ElementSide::Middle
+    };
+    let get = |ply, col| block.get(row(ply), col).map(f64::from);
+    assert_eq!(get(1, CompositeStressField::Normal1), Some(-38.1691));
+    assert_eq!(get(2, CompositeStressField::Shear12), Some(3.0));
+    assert_eq!(get(3, CompositeStressField::Angle), Some(13.28));
+    assert_eq!(get(4, CompositeStressField::Minor), Some(-37.2111));
+    assert_eq!(get(4, CompositeStressField::MaxShear), Some(7.2111));
+    // without von Mises stresses, there's no column for them
+    assert_eq!(get(1, CompositeStressField::VonMises), von_mises);
+    assert_eq!(
+      block.col_indexes.len(),
+      CompositeStressField::all().len() - usize::from(von_mises.is_none())
+    );
+  }
+  // a side printed after the ply ID is kept
+  let sided = SIMCENTER_PLY_STRESSES_FIXTURE
+    .replace("    1  -3.81691E+01", "    1 Z2 -3.81691E+01");
+  let file = parse_str(&sided);
+  let block = file.all_blocks(false).next().unwrap();
+  let top = ElementPlyRef {
+    element: ElementRef { eid: 101, etype: Some(ElementType::Quad4) },
+    layer: CompositeLayer::Ply(1),
+    side: ElementSide::Top
+  };
+  assert!(block.row_indexes.contains_key(&top.into()));
+}
+
+/// A MYSTRAN-like table of the failure indices by criterion of a 2-ply
+/// laminate, with a ply that fails.
+const PLY_FAILURE_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+                                                                                                                  OUTPUT FOR SUBCASE          1
+ 
+                F A I L U R E   I N D I C E S   I N   L A Y E R E D   C O M P O S I T E   E L E M E N T S   ( Q U A D 4 )
+ 
+   Element  Ply     Tsai-Wu      Tsai-Hill      Hoffman     Max Stress    Max Strain    Status
+     ID      ID
+       101    1  1.23456E-01  1.10000E-01  1.20000E-01  9.00000E-02  8.50000E-02    NO FAIL
+       101    2  1.05000E+00  9.80000E-01  1.02000E+00  1.10000E+00  9.70000E-01    FAIL
+       102    1  4.00000E-01  3.50000E-01  3.90000E-01  3.00000E-01  2.90000E-01    NO FAIL
+       102    2  5.00000E-01  4.50000E-01  4.90000E-01  4.00000E-01  3.90000E-01
+ ------------------------------------------------------------------------------------------------------------------------------------------------------------
+";
+
+#[test]
+fn test_composite_failure_by_criterion() {
+  let mut parser = OnePassParser::new();
+  let responses = PLY_FAILURE_FIXTURE.lines()
+    .map(|l| parser.consume(l))
+    .filter_map(|resp| match resp {
+      ParserResponse::PassedToDecoder(_, lr) => Some(lr),
+      _ => None
+    })
+    .collect::<Vec<_>>();
+  assert!(!responses.iter().any(|lr| lr.abnormal()));
+  let data = responses.iter().filter(|lr| **lr == LineResponse::Data);
+  assert_eq!(data.count(), 4);
+  let file = parser.finish();
+  assert!(file.potential_headers.is_empty());
+  let block = file.all_blocks(false).next().unwrap();
+  assert_eq!(block.block_type, BlockType::CompositeFailureIndices);
+  // the columns by theory aren't there
+  assert_eq!(block.col_indexes.len(), 6);
+  let by_theory = CompositeFailureField::FailureIndex.into();
+  assert!(!block.col_indexes.contains_key(&by_theory));
+  let row = |eid, ply| ElementPlyRef {
+    element: ElementRef { eid, etype: Some(ElementType::Quad4) },
+    layer: CompositeLayer::Ply(ply),
+    side: ElementSide::Middle
+  };
+  let get = |eid, ply, col| block.get(row(eid, ply), col)
+    .map(f64::from)
+    .unwrap();
+  assert_eq!(get(101, 1, CompositeFailureField::TsaiWu), 0.123456);
+  assert_eq!(get(101, 2, CompositeFailureField::Hoffman), 1.02);
+  assert_eq!(get(102, 1, CompositeFailureField::MaxStrain), 0.29);
+  assert_eq!(get(101, 1, CompositeFailureField::Failed), 0.0);
+  assert_eq!(get(101, 2, CompositeFailureField::Failed), 1.0);
+  assert!(get(102, 2, CompositeFailureField::Failed).is_nan());
+  assert!(!block.metadata.contains_key(META_FAILURE_THEORY));
+  // a side printed after the ply ID is kept
+  let sided = PLY_FAILURE_FIXTURE
+    .replace("  101    2  1.05000E+00", "  101    2 Z1 1.05000E+00");
+  let file = parse_str(&sided);
+  let block = file.all_blocks(false).next().unwrap();
+  let bottom = ElementPlyRef { side: ElementSide::Bottom, ..row(101, 2) };
+  let failed = CompositeFailureField::Failed;
+  assert_eq!(block.get(bottom, failed).map(f64::from), Some(1.0));
+  assert!(!block.row_indexes.contains_key(&row(101, 2).into()));
+  // the status is whatever follows the last real
+  let line = "  101  2  1.0E+00  2.0E+00  NO FAIL";
+  let found = extract_reals_with_status::<2>(line);
+  assert_eq!(found, Some(([1.0, 2.0], Some("NO FAIL".to_owned()))));
+  let found = extract_reals_with_status::<2>("  101  2  1.0E+00  2.0E+00");
+  assert_eq!(found, Some(([1.0, 2.0], None)));
+  assert_eq!(extract_reals_with_status::<3>(line), None);
+}
+
+/// A Simcenter-like table of grid point stresses on a surface, with two grid
+/// points, the first of them with a mid-plane fibre.
+const GRID_STRESS_FIXTURE: &str = "
+                                                                                                                  SUBCASE 1
+0
+              S T R E S S E S   A T   G R I D   P O I N T S   - -     S U R F A C E       1
+0                       SURFACE X-AXIS X  NORMAL(Z-AXIS)  Z         REFERENCE COORDINATE SYSTEM FOR SURFACE DEFINITION CID        0
+     GRID      ELEMENT            STRESSES IN SURFACE SYSTEM           PRINCIPAL STRESSES            MAX
+     ID          ID    FIBRE   NORMAL-X   NORMAL-Y    SHEAR-XY     ANGLE      MAJOR      MINOR      SHEAR     VON MISES
+0        1           0     Z1 -8.934E+03 -2.112E+03  1.250E+02   -88.95 -2.110E+03 -8.936E+03  3.413E+03  8.010E+03
+                           Z2  8.934E+03  2.112E+03 -1.250E+02   -1.05  8.936E+03  2.110E+03  3.413E+03  8.010E+03
+                           MID  0.000E+00  0.000E+00  0.000E+00    0.00  0.000E+00  0.000E+00  0.000E+00  0.000E+00
+0        2           0     Z1 -4.500E+03 -1.000E+03  5.000E+01   -89.18 -9.993E+02 -4.501E+03  1.751E+03  4.075E+03
+                           Z2  4.500E+03  1.000E+03 -5.000E+01   -0.82  4.501E+03  9.993E+02  1.751E+03  4.075E+03
+0        2          11     Z1 -4.000E+03 -1.000E+03  5.000E+01   -89.05 -9.992E+02 -4.001E+03  1.501E+03  3.606E+03
+0        2          12     Z1 -5.000E+03 -1.000E+03  5.000E+01   -89.28 -9.994E+02 -5.001E+03  2.001E+03  4.585E+03
+";
+
+#[test]
+fn test_grid_point_stresses() {
+  let mut parser = OnePassParser::new();
+  let responses = GRID_STRESS_FIXTURE.lines()
+    .map(|l| parser.consume(l))
+    .filter_map(|resp| match resp {
+      ParserResponse::PassedToDecoder(_, lr) => Some(lr),
+      _ => None
+    })
+    .collect::<Vec<_>>();
+  assert!(!responses.iter().any(|lr| lr.abnormal()));
+  let data = responses.iter().filter(|lr| **lr == LineResponse::Data);
+  assert_eq!(data.count(), 7);
+  let file = parser.finish();
+  let block = file.all_blocks(false).next().unwrap();
+  assert_eq!(block.block_type, BlockType::GridPointStresses);
+  let row = |gid, side| GridSurfacePoint {
+    grid_point: GridPointRef { gid },
+    surface: 1,
+    element: None,
+    side
+  };
+  let get = |row, col| block.get(row, col).map(f64::from).unwrap();
+  let normal_x = GridStressField::NormalX;
+  assert_eq!(get(row(1, ElementSide::Bottom), normal_x), -8934.0);
+  assert_eq!(get(row(1, ElementSide::Top), GridStressField::Angle), -1.05);
+  let mid = row(1, ElementSide::Middle);
+  assert_eq!(get(mid, GridStressField::VonMises), 0.0);
+  assert_eq!(get(row(2, ElementSide::Top), GridStressField::Minor), 999.3);
+  // the rows for each element around a grid point are kept apart
+  let of_element = |eid| GridSurfacePoint {
+    element: Some(ElementRef { eid, etype: None }),
+    ..row(2, ElementSide::Bottom)
+  };
+  assert_eq!(get(row(2, ElementSide::Bottom), normal_x), -4500.0);
+  assert_eq!(get(of_element(11), normal_x), -4000.0);
+  assert_eq!(get(of_element(12), normal_x), -5000.0);
+  assert_eq!(block.row_indexes.len(), 7);
+}
+
+/// A Simcenter-like table of element strain energies, with the percentage of
+/// the total, across two pages for two element types.
+const STRAIN_ENERGY_FIXTURE: &str = "
+0                                                                                                            SUBCASE 91
+                                           E L E M E N T   S T R A I N   E N E R G I E S
+ 
+                ELEMENT-TYPE = BAR                 * TOTAL ENERGY OF ALL ELEMENTS IN PROBLEM     =   4.214712E+02
+                SUBCASE              91            * TOTAL ENERGY OF ALL ELEMENTS IN SET      -1 =   4.214712E+02
+0
+                                    ELEMENT-ID          STRAIN-ENERGY           PERCENT OF TOTAL    STRAIN-ENERGY-DENSITY
+                                          1112          1.119175E+00                 0.2655              1.119175E-02
+                                          1121          4.270042E+01                10.1313              4.270042E-01
+
+                        TYPE = BAR      SUBTOTAL        4.382060E+01                10.3968
+1    TEST OF ALL ELEMENTS                                                   JANUARY  12, 2024  SIMCENTER NASTRAN  2/11/21   PAGE    49
+0                                                                                                            SUBCASE 91
+                                           E L E M E N T   S T R A I N   E N E R G I E S
+ 
+                ELEMENT-TYPE = ELAS1               * TOTAL ENERGY OF ALL ELEMENTS IN PROBLEM     =   4.214712E+02
+                SUBCASE              91            * TOTAL ENERGY OF ALL ELEMENTS IN SET      -1 =   4.214712E+02
+0
+                                    ELEMENT-ID          STRAIN-ENERGY           PERCENT OF TOTAL    STRAIN-ENERGY-DENSITY
+                                            58          1.132169E+02                26.8623    
+
+                        TYPE = ELAS1    SUBTOTAL        1.132169E+02                26.8623
+";
+
+/// A table of element strain energies with the element type on each line, no
+/// percentage of the total and the total in a row of its own.
+const STRAIN_ENERGY_BARE_FIXTURE: &str = "
+                                                                                                                  SUBCASE 1
+                                           E L E M E N T   S T R A I N   E N E R G I E S
+ 
+     ELEMENT-ID   ELEMENT-TYPE   STRAIN-ENERGY
+            101          QUAD4    1.538959E-03
+            102          TRIA3    1.323719E-03
+          TOTAL                   2.862678E-03
+";
+
+#[test]
+fn test_strain_energy() {
+  let mut file = parse_str(STRAIN_ENERGY_FIXTURE);
+  file.merge_blocks(false);
+  let blocks = file.block_search(Some(BlockType::StrainEnergy), None, false)
+    .collect::<Vec<_>>();
+  assert_eq!(blocks.len(), 1);
+  let block = blocks[0];
+  assert_eq!(block.subcase, 91);
+  assert_eq!(block.row_indexes.len(), 3);
+  let bar = ElementRef { eid: 1121, etype: Some(ElementType::Bar) };
+  let spring = ElementRef { eid: 58, etype: Some(ElementType::Elas1) };
+  let get = |row, col| block.get(row, col).map(f64::from).unwrap();
+  assert_eq!(get(bar, StrainEnergyField::Energy), 42.70042);
+  assert_eq!(get(bar, StrainEnergyField::PercentTotal), 10.1313);
+  assert_eq!(get(spring, StrainEnergyField::PercentTotal), 26.8623);
+  assert_eq!(get(bar, StrainEnergyField::Density), 0.4270042);
+  assert!(get(spring, StrainEnergyField::Density).is_nan());
+  // the subtotals aren't rows, but the total is kept
+  assert_eq!(block.total_energy(), Some(421.4712));
+  // without the percentage, and with the type on each line
+  let file = parse_str(STRAIN_ENERGY_BARE_FIXTURE);
+  let block = file.all_blocks(false).next().unwrap();
+  assert_eq!(block.block_type, BlockType::StrainEnergy);
+  let tria = ElementRef { eid: 102, etype: Some(ElementType::Tria3) };
+  let get = |row, col| block.get(row, col).map(f64::from).unwrap();
+  assert_eq!(get(tria, StrainEnergyField::Energy), 1.323719e-3);
+  assert!(get(tria, StrainEnergyField::PercentTotal).is_nan());
+  assert_eq!(block.row_indexes.len(), 2);
+  assert_eq!(block.total_energy(), Some(2.862678e-3));
+  assert_eq!(OutputKeyword::from_word("ese"), Some(OutputKeyword::Ese));
+  assert_eq!(OutputKeyword::from_word("es"), None);
+}
+
+/// MYSTRAN-like applied forces, with the totals below them.
+const APPLIED_TOTALS_FIXTURE: &str = "
+ OUTPUT FOR SUBCASE        1
+                                                     A P P L I E D    F O R C E S
+                                              (in global coordinate system at each grid)
+           GRID     COORD      T1            T2            T3            R1            R2            R3
+                     SYS
+           1011        0  0.0           0.0          -4.687500E+02 -3.906250E+03  3.906250E+03  0.0         
+           1012        0  0.0           0.0          -9.375000E+02 -7.812500E+03  0.0           0.0         
+                         ------------- ------------- ------------- ------------- ------------- -------------
+ APPLIED FORCE TOTALS:    0.0           0.0          -1.406250E+03 -1.171875E+04  3.906250E+03  0.0         
+   (for output set)
+";
+
+#[test]
+fn test_thermal_load_vectors() {
+  let mut file = parse_str(THERMAL_LOAD_FIXTURE);
+  file.merge_blocks(false);
+  assert!(file.potential_headers.is_empty());
+  let types = file.all_blocks(false)
+    .map(|b| (b.block_type, b.subcase, b.row_indexes.len()))
+    .collect::<Vec<_>>();
+  assert_eq!(
+    types,
+    [(BlockType::AppliedForces, 1, 2), (BlockType::ThermalLoads, 1, 2)]
+  );
+  let tx = |bt| file.block_search(Some(bt), Some(1), false)
+    .next()
+    .and_then(|b| b.get(GridPointRef { gid: 1011 }, DOF_TX))
+    .map(f64::from);
+  assert_eq!(tx(BlockType::AppliedForces), Some(0.0));
+  assert_eq!(tx(BlockType::ThermalLoads), Some(125.0));
+  // each header maps to one block type
+  let thermal = BlockType::ThermalLoads.headers()[0];
+  let mut applied = BlockType::AppliedForces.init_decoder(Flavour::default());
+  assert!(!applied.good_header(thermal));
+  assert!(file.all_blocks(false).all(|b| b.load_totals().is_none()));
+  // the totals are neither a grid point nor a block of their own, but they're
+  // kept with the block
+  let file = parse_str(APPLIED_TOTALS_FIXTURE);
+  let blocks = file.all_blocks(false).collect::<Vec<_>>();
+  assert_eq!(blocks.len(), 1);
+  assert_eq!(blocks[0].block_type, BlockType::AppliedForces);
+  assert_eq!(blocks[0].row_indexes.len(), 2);
+  let totals = [0.0, 0.0, -1406.25, -11718.75, 3906.25, 0.0];
+  assert_eq!(blocks[0].load_totals(), Some(totals));
+  // thermal load vectors keep theirs too
+  let thermal = APPLIED_TOTALS_FIXTURE.replace(
+    "A P P L I E D    F O R C E S",
+    "T H E R M A L   L O A D   V E C T O R"
+  );
+  let file = parse_str(&thermal);
+  let blocks = file.all_blocks(false).collect::<Vec<_>>();
+  assert_eq!(blocks.len(), 1);
+  assert_eq!(blocks[0].block_type, BlockType::ThermalLoads);
+  assert_eq!(blocks[0].row_indexes.len(), 2);
+  assert_eq!(blocks[0].load_totals(), Some(totals));
+}
+
+#[test]
+fn test_flavour_specs() {
+  // every alias and name round-trips, whatever the case
+  for sv in Solver::all() {
+    for alias in sv.aliases().iter().copied().chain([sv.name()]) {
+      assert_eq!(alias.parse::<Solver>(), Ok(*sv));
+      assert_eq!(alias.to_uppercase().parse::<Solver>(), Ok(*sv));
+    }
+  }
+  for sol in SolType::all() {
+    for alias in sol.aliases().iter().copied().chain([sol.name()]) {
+      assert_eq!(alias.parse::<SolType>(), Ok(*sol));
+    }
+    assert_eq!(usize::from(*sol).to_string().parse::<SolType>(), Ok(*sol));
+  }
+  for fl in Flavour::supported_combinations() {
+    assert_eq!(Flavour::parse_spec(&fl.spec()), Ok(fl));
+  }
+  assert_eq!(
+    Flavour::supported_combinations().len(),
+    Solver::all().len() * SolType::all().len()
+  );
+  // the usual ways people write them
+  assert_eq!("Simcenter Nastran".parse::<Solver>(), Ok(Solver::Simcenter));
+  assert_eq!("NX_Nastran".parse::<Solver>(), Ok(Solver::Simcenter));
+  assert_eq!("mystran 15.2".parse::<Solver>(), Ok(Solver::Mystran));
+  assert_eq!("SOL 101".parse::<SolType>(), Ok(SolType::LinearStatic));
+  assert_eq!(
+    Flavour::parse_spec("simcenter/sol103"),
+    Ok(Flavour {
+      solver: Some(Solver::Simcenter),
+      soltype: Some(SolType::Eigenvalue)
+    })
+  );
+  assert_eq!(
+    "nx".parse::<Flavour>(),
+    Ok(Flavour { solver: Some(Solver::Simcenter), soltype: None })
+  );
+  assert_eq!(
+    Flavour::parse_spec("auto/buckling"),
+    Ok(Flavour { solver: None, soltype: Some(SolType::LinearBuckling) })
+  );
+  // and what's rejected
+  assert_eq!(
+    "msc".parse::<Solver>(),
+    Err(FlavourParseError::UnknownSolver("msc".to_owned()))
+  );
+  assert!("15.2".parse::<Solver>().is_err());
+  assert_eq!(
+    Flavour::parse_spec("mystran/sol999"),
+    Err(FlavourParseError::UnknownSolType("sol999".to_owned()))
+  );
+  assert_eq!(Flavour::parse_spec(" /sol101"), Err(FlavourParseError::Empty));
+  let msg = "abaqus".parse::<Solver>().unwrap_err().to_string();
+  assert!(msg.contains("abaqus") && msg.contains("mystran, simcenter"));
+}
+
+/// A Simcenter-like output with two blocks on the same page, the second one
+/// interrupting the first.
+const BLOCK_PAIR_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    BLOCK PAIR TEST                                     SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1.000000E+00   0.0            0.0            0.0            0.0            0.0
+             2      G      2.000000E+00   0.0            0.0            0.0            0.0            0.0
+ 
+                               F O R C E S   O F   S I N G L E - P O I N T   C O N S T R A I N T
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G     -1.000000E+00   0.0            0.0            0.0            0.0            0.0
+             2      G     -2.000000E+00   0.0            0.0            0.0            0.0            0.0
+1    BLOCK PAIR TEST                                     SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+#[test]
+fn test_invariants() {
+  let file = parse_str(BLOCK_PAIR_FIXTURE);
+  assert_eq!(file.line_count, Some(17));
+  let block_of = |bt: BlockType| {
+    return file.block_search(Some(bt), None, false).next().unwrap().clone();
+  };
+  // the first block ends right before the header of the second
+  assert_eq!(block_of(BlockType::Displacements).line_range, Some((7, 12)));
+  assert_eq!(block_of(BlockType::SpcForces).line_range, Some((13, 18)));
+  assert!(file.check_invariants().is_empty());
+  assert!(parse_str(ALL_ELEM_F06).check_invariants().is_empty());
+  // checks a single extra block against the fine ones
+  let violations_with = |block: FinalBlock| {
+    let mut broken = file.clone();
+    broken.insert_block(block);
+    return broken.check_invariants();
+  };
+  // a decoder that ate into the next block
+  let mut runaway = block_of(BlockType::SpcForces);
+  runaway.line_range = Some((11, 14));
+  let violations = violations_with(runaway);
+  let overlaps = violations.iter()
+    .map(|v| match v {
+      InvariantViolation::OverlappingRanges { first, second } => {
+        (first.line_range.unwrap(), second.line_range.unwrap())
+      },
+      other => panic!("unexpected violation: {}", other)
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(overlaps, [((7, 12), (11, 14)), ((11, 14), (13, 18))]);
+  // a range past the end of the file, but right after the last block
+  let mut outside = block_of(BlockType::SpcForces);
+  outside.line_range = Some((18, 30));
+  assert!(matches!(
+    violations_with(outside).as_slice(),
+    [InvariantViolation::RangeOutsideFile { line_count: 17, .. }]
+  ));
+  // no rows, but some lines
+  let mut empty = block_of(BlockType::SpcForces);
+  empty.row_indexes.clear();
+  empty.line_range = Some((1, 3));
+  assert!(matches!(
+    violations_with(empty).as_slice(),
+    [InvariantViolation::EmptyWithRange { .. }]
+  ));
+  // a row index pointing past the data
+  let mut dangling = block_of(BlockType::SpcForces);
+  dangling.line_range = None;
+  dangling.row_indexes.insert(GridPointRef { gid: 99 }.into(), 5);
+  let violations = violations_with(dangling);
+  assert!(matches!(
+    violations.as_slice(),
+    [InvariantViolation::RowOutOfBounds { position: 5, rows: 2, .. }]
+  ));
+  assert!(violations[0].to_string().contains("points to row 5"));
+}
+
+/// Asserts two lists of blocks are equal, taking the NaNs of values a solver
+/// didn't print as equal to each other.
+fn assert_same<'a, 'b>(
+  a: impl IntoIterator<Item = &'a FinalBlock>,
+  b: impl IntoIterator<Item = &'b FinalBlock>
+) {
+  /// Returns whether two matrices are equal, NaNs included.
+  fn same_data(a: &Option<FinalDMat>, b: &Option<FinalDMat>) -> bool {
+    return match (a, b) {
+      (Some(FinalDMat::Reals(x)), Some(FinalDMat::Reals(y))) => {
+        x.shape() == y.shape()
+          && x.iter().zip(y.iter())
+            .all(|(x, y)| x == y || (x.is_nan() && y.is_nan()))
+      },
+      _ => a == b
+    };
+  }
+  let a = a.into_iter().collect::<Vec<_>>();
+  let b = b.into_iter().collect::<Vec<_>>();
+  assert_eq!(a.len(), b.len(), "different numbers of blocks");
+  for (a, b) in a.into_iter().zip(b) {
+    assert!(
+      same_data(&a.data, &b.data),
+      "different data in the {} of subcase {}",
+      a.block_type,
+      a.subcase
+    );
+    let without_data = |block: &FinalBlock| FinalBlock {
+      data: None,
+      ..block.clone()
+    };
+    assert_eq!(without_data(a), without_data(b));
+  }
+}
+
+#[test]
+fn test_block_loader() {
+  let corpus = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("../examples");
+  let mut types: BTreeSet<BlockType> = BTreeSet::new();
+  for solver in ["mystran", "scnastran"] {
+    for dirent in std::fs::read_dir(corpus.join(solver)).unwrap() {
+      let path = dirent.unwrap().path();
+      let (file, index) = FileIndex::build_file(&path).unwrap();
+      // recording the index changes nothing
+      let plain = OnePassParser::parse_file(&path).unwrap();
+      assert_eq!(
+        file.blocks.keys().collect::<Vec<_>>(),
+        plain.blocks.keys().collect::<Vec<_>>()
+      );
+      assert_same(file.all_blocks(false), plain.all_blocks(false));
+      assert_eq!(index.entries.len(), file.all_blocks(false).count());
+      let loader = BlockLoader::open(&path, index);
+      std::thread::scope(|scope| {
+        for (br, blocks) in file.blocks.iter() {
+          for (nth, block) in blocks.iter().enumerate() {
+            let loader = &loader;
+            scope.spawn(move || {
+              assert_same([&loader.load_nth(*br, nth).unwrap()], [block]);
+            });
+          }
+        }
+      });
+      types.extend(file.block_types());
+    }
+  }
+  assert!(types.len() > 5);
+}
+
+#[test]
+fn test_block_loader_offsets() {
+  // line breaks and a byte-order mark all take up bytes
+  let crlf = format!("\u{feff}{}", BLOCK_PAIR_FIXTURE.replace('\n', "\r\n"));
+  let path = std::env::temp_dir()
+    .join(format!("f06-loader-{}.f06", std::process::id()));
+  std::fs::write(&path, &crlf).unwrap();
+  let (file, index) = FileIndex::build_file(&path).unwrap();
+  let loader = BlockLoader::open(&path, index.clone());
+  for entry in index.entries.iter() {
+    let start = crlf.split_inclusive('\n')
+      .take(entry.line_range.0 - 1)
+      .map(str::len)
+      .sum::<usize>();
+    assert_eq!(entry.byte_range.0, start as u64);
+    let blocks = file.blocks.get(&entry.block_ref).unwrap();
+    assert_eq!(loader.load_entry(entry).unwrap(), blocks[entry.nth]);
+  }
+  assert_eq!(index.entries.len(), 2);
+  let missing = BlockRef { subcase: 2, block_type: BlockType::SpcForces };
+  assert!(matches!(
+    loader.load(missing),
+    Err(ParseError::NotIndexed { nth: 0, .. })
+  ));
+  std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_byte_offsets() {
+  // a warning and an unknown header after the blocks, all with CRLFs and a
+  // byte-order mark up front
+  let source = format!(
+    "\u{feff}{}{}",
+    BLOCK_PAIR_FIXTURE,
+    concat!(
+      " *** USER WARNING MESSAGE 4321 (TEST)\n",
+      "                             W I D G E T   F O R C E S\n",
+      " \n"
+    )
+  ).replace('\n', "\r\n");
+  let buffered = OnePassParser::parse_bufread(source.as_bytes()).unwrap();
+  let in_memory = OnePassParser::parse_bytes(source.as_bytes()).unwrap();
+  // both backends agree
+  assert_eq!(buffered.blocks, in_memory.blocks);
+  assert_eq!(buffered.diagnostics, in_memory.diagnostics);
+  assert_eq!(buffered.message_offsets, in_memory.message_offsets);
+  let headers = |f: &F06File| f.potential_headers.iter()
+    .map(|ph| (ph.start, ph.offset))
+    .collect::<Vec<_>>();
+  assert_eq!(headers(&buffered), headers(&in_memory));
+  // and they're right
+  let line_starts = std::iter::once(0)
+    .chain(source.split_inclusive('\n').scan(0, |acc, l| {
+      *acc += l.len();
+      return Some(*acc);
+    }))
+    .map(|o| o as u64)
+    .collect::<Vec<_>>();
+  let offset_of = |line: usize| line_starts[line - 1];
+  for block in buffered.all_blocks(false) {
+    let (start, end) = block.line_range.unwrap();
+    assert_eq!(block.byte_range, Some((offset_of(start), offset_of(end))));
+  }
+  assert_eq!(buffered.message_offsets.len(), 1);
+  for (line, offset) in buffered.message_offsets.iter() {
+    assert_eq!(*offset, offset_of(*line));
+    assert!(source[*offset as usize..].starts_with(" *** USER WARNING"));
+  }
+  let diag = buffered.diagnostics.iter().next().unwrap();
+  assert_eq!(diag.offsets, [offset_of(diag.lines[0])]);
+  let ph = buffered.potential_headers.first().unwrap();
+  assert_eq!(ph.text, "WIDGET FORCES");
+  assert_eq!(ph.offset, offset_of(ph.start));
+  let report = FileReport::from(&buffered);
+  assert_eq!(report.potential_header_offsets["WIDGET FORCES"], ph.offset);
+  // plain lines are taken to end in a single line feed
+  let mut parser = OnePassParser::new();
+  BLOCK_PAIR_FIXTURE.lines().for_each(|l| { parser.consume(l); });
+  let plain = parser.finish();
+  let lf = OnePassParser::parse_bytes(BLOCK_PAIR_FIXTURE.as_bytes()).unwrap();
+  assert_eq!(plain.blocks, lf.blocks);
+}
+
+/// A displacements block from a localised solver, with decimal commas.
+const DECIMAL_COMMA_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    DECIMAL COMMA TEST                                  SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1,000000E+00   2,500000E-01  -3,000000E+02   0,0            5,000000E-03   6,000000E+00
+             2      G      0,0            0,0            0,0            0,0            0,0            0,0
+             3      G     -1,234567E+03   0,0            7,500000E-01   0,0            0,0           -2,000000E-06
+1    DECIMAL COMMA TEST                                  SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+#[test]
+fn test_decimal_comma() {
+  assert!(is_comma_decimal("1,234567E+03"));
+  assert!(is_comma_decimal("-5,0-03"));
+  assert!(is_comma_decimal("0,0"));
+  assert!(!is_comma_decimal("1,5"));
+  assert!(!is_comma_decimal("1,"));
+  assert!(!is_comma_decimal("A,1E+03"));
+  // never on the corpus
+  let corpus = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("../examples");
+  for solver in ["mystran", "scnastran"] {
+    for dirent in std::fs::read_dir(corpus.join(solver)).unwrap() {
+      let path = dirent.unwrap().path();
+      let text = String::from_utf8_lossy(&std::fs::read(&path).unwrap())
+        .into_owned();
+      assert!(!text.lines().any(looks_comma_decimal), "{}", path.display());
+      assert!(!OnePassParser::parse_file(&path).unwrap().decimal_comma);
+    }
+  }
+  // lines outside of blocks aren't checked
+  assert!(!parse_str(" 1,5E+03 2,5E+03\n").decimal_comma);
+  // the localised file reads just like its twin
+  let file = parse_str(DECIMAL_COMMA_FIXTURE);
+  assert!(file.decimal_comma);
+  let twin = parse_str(&DECIMAL_COMMA_FIXTURE.replace(',', "."));
+  assert!(!twin.decimal_comma);
+  let block = file.all_blocks(false).next().unwrap();
+  assert_eq!(block.row_indexes.len(), 3);
+  assert_eq!(file.blocks, twin.blocks);
+  let tz = block.get(GridPointRef { gid: 1 }, DOF_TZ);
+  assert_eq!(tz, Some(F06Number::Real(-300.0)));
+  // also when asked for from the start, and when loaded on demand
+  let mut parser = OnePassParser::new();
+  parser.decimal_comma(true);
+  let forced = parser.read_bytes(DECIMAL_COMMA_FIXTURE.as_bytes()).unwrap();
+  assert_eq!(forced.blocks, twin.blocks);
+  let path = std::env::temp_dir()
+    .join(format!("f06-decimal-comma-{}.f06", std::process::id()));
+  std::fs::write(&path, DECIMAL_COMMA_FIXTURE).unwrap();
+  let (_, index) = FileIndex::build_file(&path).unwrap();
+  let loader = BlockLoader::open(&path, index);
+  let br = block.block_ref();
+  assert_eq!(&loader.load(br).unwrap(), block);
+  std::fs::remove_file(&path).unwrap();
+}
+
+/// A post-processor for tests, which marks every block it runs on.
+struct MarkBlocks {
+  /// Its name.
+  name: &'static str,
+  /// The tags it provides.
+  provides: Vec<&'static str>,
+  /// The tags it requires.
+  requires: Vec<&'static str>
+}
+
+impl PostProcessor for MarkBlocks {
+  fn name(&self) -> &str {
+    return self.name;
+  }
+
+  fn provides(&self) -> &[&str] {
+    return &self.provides;
+  }
+
+  fn requires(&self) -> &[&str] {
+    return &self.requires;
+  }
+
+  fn process(&self, file: &mut F06File) -> Vec<Diagnostic> {
+    for block in file.all_blocks_mut(false) {
+      block.metadata.entry("MARKS".to_owned()).or_default().push('x');
+    }
+    return vec![post_diagnostic(self.name, None, "marked 2 blocks")];
+  }
+}
+
+#[test]
+fn test_post_processors() {
+  let marker = |name, provides: &[_], requires: &[_]| Box::new(MarkBlocks {
+    name,
+    provides: provides.to_vec(),
+    requires: requires.to_vec()
+  });
+  let mut registry = PostProcessorRegistry::builtin();
+  // requirements are pulled in and go first, otherwise registration order
+  let plan = registry.plan(&["sort_blocks", "check_invariants"]).unwrap();
+  assert_eq!(plan, ["check_invariants", "merge_blocks", "sort_blocks"]);
+  let plan = registry.plan(DEFAULT_POST_PROCESSORS).unwrap();
+  assert_eq!(plan, DEFAULT_POST_PROCESSORS);
+  assert_eq!(
+    registry.plan(&["flip_signs"]),
+    Err(PostProcessError::Unknown("flip_signs".to_owned()))
+  );
+  // custom ones go in the same registry
+  registry.register(marker("mark", &[], &["sorted"])).unwrap();
+  assert_eq!(
+    registry.register(marker("mark", &[], &[])),
+    Err(PostProcessError::Duplicate("mark".to_owned()))
+  );
+  registry.register(marker("needy", &[], &["nothing"])).unwrap();
+  assert!(matches!(
+    registry.plan(&["needy"]),
+    Err(PostProcessError::Unprovided { .. })
+  ));
+  registry.register(marker("chicken", &["a"], &["b"])).unwrap();
+  registry.register(marker("egg", &["b"], &["a"])).unwrap();
+  assert_eq!(
+    registry.plan(&["egg"]),
+    Err(PostProcessError::Cycle(vec!["chicken".into(), "egg".into()]))
+  );
+  // running them
+  let mut file = parse_str(BLOCK_PAIR_FIXTURE);
+  let diagnostics = registry.run(&["mark"], &mut file).unwrap();
+  assert_eq!(file.post_processed, ["merge_blocks", "sort_blocks", "mark"]);
+  assert_eq!(diagnostics.len(), 1);
+  assert!(file.diagnostics.iter().any(|d| d.kind == diagnostics[0].kind));
+  assert!(file.all_blocks(false).all(|b| b.metadata["MARKS"] == "x"));
+  // idempotent ones are skipped, others can't run again
+  let done = file.clone();
+  registry.run(DEFAULT_POST_PROCESSORS, &mut file).unwrap();
+  assert_eq!(file.post_processed.len(), 4);
+  assert_eq!(file.blocks, done.blocks);
+  assert_eq!(
+    registry.run(&["check_invariants", "mark"], &mut file),
+    Err(PostProcessError::AlreadyApplied("mark".to_owned()))
+  );
+  assert_eq!(file.post_processed.len(), 4);
+  assert_eq!(file.blocks, done.blocks);
+  // and the parser runs them when finishing up
+  let mut parser = OnePassParser::new();
+  assert!(parser.post_process(registry, &["needy"]).is_err());
+  let registry = PostProcessorRegistry::builtin();
+  parser.post_process(registry, DEFAULT_POST_PROCESSORS).unwrap();
+  let parsed = parser.read_bytes(BLOCK_PAIR_FIXTURE.as_bytes()).unwrap();
+  assert_eq!(parsed.post_processed, DEFAULT_POST_PROCESSORS);
+}
+
+/// Displacements of the residual structure and of superelement 10, where
+/// the solver slipped a residual grid point into the superelement's block.
+const SUPERELEMENT_FIXTURE: &str = "
+1    SE TEST                                             SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1.000000E+00   0.0            0.0            0.0            0.0            0.0
+1    SE TEST                                             SIMCENTER NASTRAN  2/11/21   PAGE     2
+0      SUPERELEMENT 10                                                           SUBCASE 1
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+          1001      G      2.000000E+00   0.0            0.0            0.0            0.0            0.0
+             2      G      3.000000E+00   0.0            0.0            0.0            0.0            0.0
+          1002      G      4.000000E+00   0.0            0.0            0.0            0.0            0.0
+";
+
+#[test]
+fn test_superelement_ranges() {
+  let mut file = parse_str(SUPERELEMENT_FIXTURE);
+  let blocks = file.all_blocks(false).collect::<Vec<_>>();
+  assert_eq!(blocks.len(), 2);
+  assert_eq!(blocks[0].superelement(), None);
+  assert_eq!(blocks[1].superelement(), Some(10));
+  // the two can't be merged, despite being the same type and subcase
+  assert_eq!(
+    blocks[0].can_merge(blocks[1]),
+    Err(MergeIncompatible::SuperelementMismatch)
+  );
+  // both formats give the same ranges
+  let toml = "[[range]]\nseid = 10\nid_start = 1000\nid_end = 1999\n";
+  let csv = "seid,id_start,id_end\n10,1000,1999\n";
+  let ranges = SeRanges::parse(toml).unwrap();
+  assert_eq!(ranges, SeRanges::parse(csv).unwrap());
+  assert!(SeRanges::parse("10,1999,1000\n").is_err());
+  // exactly one warning, naming the stray grid point
+  let mut registry = PostProcessorRegistry::builtin();
+  registry.register(Box::new(CheckSeRanges::new(ranges))).unwrap();
+  let names = [DEFAULT_POST_PROCESSORS, &[CheckSeRanges::NAME]].concat();
+  let diagnostics = registry.run(&names, &mut file).unwrap();
+  assert_eq!(diagnostics.len(), 1);
+  assert!(diagnostics[0].text.contains("superelement 10 has 1 ID"));
+  assert!(diagnostics[0].text.ends_with("outside of 1000-1999: 2"));
+}
+
+#[test]
+fn test_samples() {
+  let shapes = |file: &F06File| file.all_blocks(false)
+    .map(|b| {
+      let shape = (b.row_indexes.len(), b.col_indexes.len());
+      return (b.block_type, b.subcase, shape);
+    })
+    .collect::<BTreeSet<_>>();
+  let minimal = parse_str(&crate::samples::minimal_static());
+  assert_eq!(minimal.flavour.solver, Some(Solver::Mystran));
+  assert_eq!(shapes(&minimal), BTreeSet::from([
+    (BlockType::Displacements, 1, (4, 6)),
+    (BlockType::SpcForces, 1, (2, 6)),
+  ]));
+  let simcenter = parse_str(&crate::samples::simcenter_static());
+  assert_eq!(simcenter.flavour.solver, Some(Solver::Simcenter));
+  assert_eq!(shapes(&simcenter), BTreeSet::from([
+    (BlockType::Displacements, 1, (3, 6)),
+    (BlockType::SpcForces, 1, (1, 6)),
+  ]));
+  // deterministic, and the derived stresses are exact
+  let text = crate::samples::two_subcase_shell();
+  assert_eq!(text, crate::samples::two_subcase_shell());
+  let shell = parse_str(&text);
+  assert_eq!(shapes(&shell), BTreeSet::from([
+    (BlockType::QuadStresses, 1, (4, 8)),
+    (BlockType::QuadStresses, 2, (4, 8)),
+  ]));
+  for block in shell.all_blocks(false) {
+    for row in block.row_indexes.keys().copied() {
+      let get = |col: PlateStressField| {
+        return f64::from(block.get(row, col).unwrap());
+      };
+      let (sx, sy, txy) = (
+        get(PlateStressField::NormalX),
+        get(PlateStressField::NormalY),
+        get(PlateStressField::ShearXY)
+      );
+      let radius = (((sx - sy) / 2.0).powi(2) + txy.powi(2)).sqrt();
+      let vm = (sx * sx - sx * sy + sy * sy + 3.0 * txy * txy).sqrt();
+      assert_eq!(get(PlateStressField::Major), (sx + sy) / 2.0 + radius);
+      assert_eq!(get(PlateStressField::Minor), (sx + sy) / 2.0 - radius);
+      assert_eq!(get(PlateStressField::VonMises), vm);
+    }
+  }
+}
+
+/// A Simcenter-like output where a page break falls between the fibres of
+/// quad 12, the orphan top fibre coming after a carriage control zero.
+const QUAD_PAGE_BREAK_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    QUAD PAGE BREAK TEST                                SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                         S T R E S S E S   I N   Q U A D R I L A T E R A L   E L E M E N T S   ( Q U A D 4 )
+  ELEMENT      FIBER               STRESSES IN ELEMENT COORD SYSTEM             PRINCIPAL STRESSES (ZERO SHEAR)
+    ID.       DISTANCE           NORMAL-X       NORMAL-Y      SHEAR-XY       ANGLE         MAJOR           MINOR        VON MISES
+0      11   -6.250000E-02      1.100000E+01   0.0            0.0            0.0000    1.100000E+01    0.0            1.100000E+01
+             6.250000E-02      1.200000E+01   0.0            0.0            0.0000    1.200000E+01    0.0            1.200000E+01
+0      12   -6.250000E-02      2.100000E+01   0.0            0.0            0.0000    2.100000E+01    0.0            2.100000E+01
+1    QUAD PAGE BREAK TEST                                SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                         S T R E S S E S   I N   Q U A D R I L A T E R A L   E L E M E N T S   ( Q U A D 4 )
+  ELEMENT      FIBER               STRESSES IN ELEMENT COORD SYSTEM             PRINCIPAL STRESSES (ZERO SHEAR)
+    ID.       DISTANCE           NORMAL-X       NORMAL-Y      SHEAR-XY       ANGLE         MAJOR           MINOR        VON MISES
+0            6.250000E-02      2.200000E+01   0.0            0.0            0.0000    2.200000E+01    0.0            2.200000E+01
+0      13   -6.250000E-02      3.100000E+01   0.0            0.0            0.0000    3.100000E+01    0.0            3.100000E+01
+             6.250000E-02      3.200000E+01   0.0            0.0            0.0000    3.200000E+01    0.0            3.200000E+01
+1    QUAD PAGE BREAK TEST                                SIMCENTER NASTRAN  2/11/21   PAGE     3
+";
+
+#[test]
+fn test_quad_page_break() {
+  let mut file = parse_str(QUAD_PAGE_BREAK_FIXTURE);
+  file.merge_blocks(true);
+  let blocks = file.block_search(Some(BlockType::QuadStresses), None, false)
+    .collect::<Vec<_>>();
+  assert_eq!(blocks.len(), 1);
+  let block = blocks[0];
+  let sides = block.row_indexes.keys()
+    .map(|ri| match ri {
+      NasIndex::ElementSidedPoint(esp) => {
+        let nx = block.get(*ri, PlateStressField::NormalX).unwrap();
+        return (esp.element.eid, esp.side, f64::from(nx));
+      },
+      other => panic!("unexpected row index {}", other)
+    })
+    .collect::<Vec<_>>();
+  // each fibre once, with its own values; tens are the eid, units the fibre
+  let unique = sides.iter()
+    .map(|(eid, side, _)| (*eid, *side))
+    .collect::<BTreeSet<_>>();
+  assert_eq!((sides.len(), unique.len()), (6, 6));
+  for (eid, side, nx) in sides {
+    let fibre = if side == ElementSide::Bottom { 1.0 } else { 2.0 };
+    assert_eq!(nx, (eid as f64 - 10.0) * 10.0 + fibre);
+  }
+  // and a continuation with nothing left to continue is dropped
+  let ambiguous = QUAD_PAGE_BREAK_FIXTURE.replacen(
+    "0      12   -6.250000E-02",
+    "             6.250000E-02",
+    1
+  );
+  let file = parse_str(&ambiguous);
+  assert!(file.diagnostics.iter().any(|d| matches!(
+    d.kind,
+    DiagnosticKind::AbnormalLine {
+      response: LineResponse::MissingMetadata,
+      ..
+    }
+  )));
+}
+
+/// A MYSTRAN-like output with the stresses at the centroids of two CHEXAs.
+const MYSTRAN_CHEXA_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+
+ OUTPUT FOR SUBCASE        1
+
+                        E L E M E N T   S T R E S S E S   I N   L O C A L   E L E M E N T   C O O R D I N A T E   S Y S T E M
+                                                   F O R   E L E M E N T   T Y P E   H E X A 8
+  Element  Location      Sigma-xx      Sigma-yy      Sigma-zz       Tau-xy        Tau-yz        Tau-zx      von Mises
+     ID
+      101   CENTER   1.000000E+02  5.000000E+01 -2.500000E+01  1.000000E+01           0.0 -5.000000E+00  9.000000E+01
+      102   CENTER  -2.000000E+01           0.0  4.000000E+01           0.0  1.500000E+01           0.0  6.000000E+01
+";
+
+/// A Simcenter-like output with the stresses at the centroid and corners of a
+/// CHEXA. The normal X stress at corner N is 10*N, and von Mises is 100+N.
+const SIMCENTER_CHEXA_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    CHEXA TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                   S T R E S S E S   I N   H E X A H E D R O N   S O L I D   E L E M E N T S   ( H E X A )
+               CORNER        ------CENTER AND CORNER POINT STRESSES---------       DIR.  COSINES       MEAN
+  ELEMENT-ID    GRID-ID        NORMAL              SHEAR             PRINCIPAL       -A-  -B-  -C-     PRESSURE       VON MISES
+0       101           0GRID CS  8 GP
+0          CENTER  X   5.000000E+00  XY   1.000000E+00   A   5.000000E+00  LX 1.00 0.00 0.00  -1.666667E+00   1.000000E+02
+                          Y   2.000000E+00  YZ   3.000000E+00   B   2.000000E+00  LY-0.00 1.00 0.00
+                          Z  -4.000000E+00  ZX            0.0   C  -4.000000E+00  LZ 0.00 0.00 1.00
+0               1  X   1.000000E+01  XY   1.000000E+00   A   1.000000E+01  LX 1.00 0.00 0.00  -3.333333E+00   1.010000E+02
+                          Y   2.000000E+00  YZ   3.000000E+00   B   2.000000E+00  LY-0.00 1.00 0.00
+                          Z  -4.000000E+00  ZX            0.0   C  -4.000000E+00  LZ 0.00 0.00 1.00
+0               2  X   2.000000E+01  XY   1.000000E+00   A   2.000000E+01  LX 1.00 0.00 0.00  -6.666667E+00   1.020000E+02
+                          Y   2.000000E+00  YZ   3.000000E+00   B   2.000000E+00  LY-0.00 1.00 0.00
+                          Z  -4.000000E+00  ZX            0.0   C  -4.000000E+00  LZ 0.00 0.00 1.00
+0               3  X   3.000000E+01  XY   1.000000E+00   A   3.000000E+01  LX 1.00 0.00 0.00  -1.000000E+01   1.030000E+02
+                          Y   2.000000E+00  YZ   3.000000E+00   B   2.000000E+00  LY-0.00 1.00 0.00
+                          Z  -4.000000E+00  ZX            0.0   C  -4.000000E+00  LZ 0.00 0.00 1.00
+0               4  X   4.000000E+01  XY   1.000000E+00   A   4.000000E+01  LX 1.00 0.00 0.00  -1.333333E+01   1.040000E+02
+                          Y   2.000000E+00  YZ   3.000000E+00   B   2.000000E+00  LY-0.00 1.00 0.00
+                          Z  -4.000000E+00  ZX            0.0   C  -4.000000E+00  LZ 0.00 0.00 1.00
+0               5  X   5.000000E+01  XY   1.000000E+00   A   5.000000E+01  LX 1.00 0.00 0.00  -1.666667E+01   1.050000E+02
+                          Y   2.000000E+00  YZ   3.000000E+00   B   2.000000E+00  LY-0.00 1.00 0.00
+                          Z  -4.000000E+00  ZX            0.0   C  -4.000000E+00  LZ 0.00 0.00 1.00
+0               6  X   6.000000E+01  XY   1.000000E+00   A   6.000000E+01  LX 1.00 0.00 0.00  -2.000000E+01   1.060000E+02
+                          Y   2.000000E+00  YZ   3.000000E+00   B   2.000000E+00  LY-0.00 1.00 0.00
+                          Z  -4.000000E+00  ZX            0.0   C  -4.000000E+00  LZ 0.00 0.00 1.00
+0               7  X   7.000000E+01  XY   1.000000E+00   A   7.000000E+01  LX 1.00 0.00 0.00  -2.333333E+01   1.070000E+02
+                          Y   2.000000E+00  YZ   3.000000E+00   B   2.000000E+00  LY-0.00 1.00 0.00
+                          Z  -4.000000E+00  ZX            0.0   C  -4.000000E+00  LZ 0.00 0.00 1.00
+0               8  X   8.000000E+01  XY   1.000000E+00   A   8.000000E+01  LX 1.00 0.00 0.00  -2.666667E+01   1.080000E+02
+                          Y   2.000000E+00  YZ   3.000000E+00   B   2.000000E+00  LY-0.00 1.00 0.00
+                          Z  -4.000000E+00  ZX            0.0   C  -4.000000E+00  LZ 0.00 0.00 1.00
+1    CHEXA TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+/// Finds the CHEXA stress at a point of an element.
+fn chexa_stress(
+  block: &FinalBlock,
+  eid: usize,
+  point: ElementPoint,
+  side: ElementSide,
+  field: ChexaStressField
+) -> f64 {
+  let element = ElementRef { eid, etype: Some(ElementType::Hexa) };
+  let row = ElementSidedPoint { element, point, side };
+  return f64::from(block.get(row, field).unwrap());
+}
+
+#[test]
+fn test_chexa_stresses_centroid() {
+  let file = parse_str(MYSTRAN_CHEXA_FIXTURE);
+  let block = file.block_search(Some(BlockType::ChexaStresses), None, false)
+    .next()
+    .unwrap();
+  assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (2, 7));
+  assert_eq!(block.data.as_ref().unwrap().ncols(), 7);
+  let get = |eid, field| chexa_stress(
+    block,
+    eid,
+    ElementPoint::Centroid,
+    ElementSide::Bottom,
+    field
+  );
+  assert_eq!(get(101, ChexaStressField::NormalX), 100.0);
+  assert_eq!(get(101, ChexaStressField::ShearZX), -5.0);
+  assert_eq!(get(102, ChexaStressField::NormalZ), 40.0);
+  assert_eq!(get(102, ChexaStressField::VonMises), 60.0);
+  // octahedral shear isn't printed, nor the principal stresses
+  let octahedral = ChexaStressField::Octahedral.into();
+  assert!(!block.col_indexes.contains_key(&octahedral));
+  let pressure = ChexaStressField::MeanPressure.into();
+  assert!(!block.col_indexes.contains_key(&pressure));
+}
+
+/// Renumbers the corners of the Simcenter CHEXA fixture, the N-th one
+/// getting a GRID-ID of 90 - 10*N.
+fn renumbered_chexa_fixture() -> String {
+  let mut text = SIMCENTER_CHEXA_FIXTURE.to_owned();
+  for n in 1..=8 {
+    text = text.replace(
+      &format!("0               {}  X", n),
+      &format!("0              {}  X", 90 - 10 * n)
+    );
+  }
+  return text;
+}
+
+#[test]
+fn test_chexa_stresses_corners() {
+  let check = |text: &str, octahedral: bool, grid: fn(usize) -> usize| {
+    let file = parse_str(text);
+    let block = file.block_search(Some(BlockType::ChexaStresses), None, false)
+      .next()
+      .unwrap();
+    assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (9, 11));
+    assert_eq!(block.data.as_ref().unwrap().ncols(), 11);
+    let centre = (ElementPoint::Centroid, ElementSide::Bottom, 5.0, 100.0);
+    let corners = (1..=8_usize).map(|n| {
+      let side = if n > 4 { ElementSide::Top } else { ElementSide::Bottom };
+      let point = ElementPoint::Corner(GridPointRef { gid: grid(n) });
+      return (point, side, 10.0 * n as f64, 100.0 + n as f64);
+    });
+    for (point, side, nx, last) in std::iter::once(centre).chain(corners) {
+      let get = |field| chexa_stress(block, 101, point, side, field);
+      assert_eq!(get(ChexaStressField::NormalX), nx);
+      assert_eq!(get(ChexaStressField::NormalY), 2.0);
+      assert_eq!(get(ChexaStressField::NormalZ), -4.0);
+      assert_eq!(get(ChexaStressField::ShearXY), 1.0);
+      assert_eq!(get(ChexaStressField::ShearYZ), 3.0);
+      assert_eq!(get(ChexaStressField::ShearZX), 0.0);
+      assert_eq!(get(ChexaStressField::PrincipalA), nx);
+      assert_eq!(get(ChexaStressField::PrincipalB), 2.0);
+      assert_eq!(get(ChexaStressField::PrincipalC), -4.0);
+      let pressure = get(ChexaStressField::MeanPressure);
+      assert!((pressure + nx / 3.0).abs() < 1e-5);
+      let (printed, missing) = if octahedral {
+        (ChexaStressField::Octahedral, ChexaStressField::VonMises)
+      } else {
+        (ChexaStressField::VonMises, ChexaStressField::Octahedral)
+      };
+      assert_eq!(get(printed), last);
+      assert!(!block.col_indexes.contains_key(&missing.into()));
+    }
+  };
+  check(SIMCENTER_CHEXA_FIXTURE, false, |n| n);
+  let octahedral = SIMCENTER_CHEXA_FIXTURE.replace("VON MISES", "OCTAHEDRAL");
+  check(&octahedral, true, |n| n);
+  // the corners are the printed GRID-IDs, whatever their order
+  check(&renumbered_chexa_fixture(), false, |n| 90 - 10 * n);
+}
+
+#[test]
+fn test_chexa_stresses_flavour_mismatch() {
+  let mystran_line = MYSTRAN_CHEXA_FIXTURE.lines()
+    .find(|l| l.contains("CENTER"))
+    .unwrap();
+  let simcenter_line = SIMCENTER_CHEXA_FIXTURE.lines()
+    .find(|l| l.contains("CENTER") && l.contains("XY"))
+    .unwrap();
+  let decoder = |solver: Option<Solver>| {
+    let flavour = Flavour { solver, soltype: None };
+    return BlockType::ChexaStresses.init_decoder(flavour);
+  };
+  assert_eq!(
+    decoder(None).consume(mystran_line, 1),
+    LineResponse::BadFlavour
+  );
+  assert_eq!(
+    decoder(Some(Solver::Mystran)).consume(simcenter_line, 1),
+    LineResponse::WrongSolver
+  );
+  assert_eq!(
+    decoder(Some(Solver::Simcenter)).consume(mystran_line, 1),
+    LineResponse::WrongSolver
+  );
+  assert_eq!(
+    decoder(Some(Solver::Mystran)).consume(mystran_line, 1),
+    LineResponse::Data
+  );
+}
+
+/// A MYSTRAN-like output with the engineering forces of a CBEAM.
+const MYSTRAN_BEAM_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+
+ OUTPUT FOR SUBCASE        1
+
+                                                       E L E M E N T   E N G I N E E R I N G   F O R C E S
+                                                             F O R   E L E M E N T   T Y P E   B E A M
+                 Element       Bend-Moment End A           Bend-Moment End B              - Shear -              Axial         Torque
+                    ID       Plane 1       Plane 2       Plane 1       Plane 2      Plane 1       Plane 2        Force
+                       7  1.000000E+01  2.000000E+01 -3.000000E+01  4.000000E+01  5.000000E+00 -6.000000E+00  7.000000E+02  8.000000E+00
+";
+
+/// A Simcenter-like output with the engineering forces of two CBEAMs, the
+/// first one with an intermediate station and cut by a page break.
+const SIMCENTER_BEAM_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    BEAM TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                 F O R C E S   I N   B E A M   E L E M E N T S        ( C B E A M )
+                    STAT DIST/   - BENDING MOMENTS -            - WEB  SHEARS -           AXIAL          TOTAL          WARPING
+   ELEMENT-ID  GRID   LENGTH    PLANE 1       PLANE 2        PLANE 1       PLANE 2        FORCE          TORQUE         TORQUE
+0        1
+0                1   0.000   1.000000E+02            0.0  -3.000000E+01   4.000000E+01   5.000000E+02   6.000000E+00   5.000000E-01
+0                0   0.500            0.0  -5.000000E+01  -3.000000E+01   4.000000E+01   5.000000E+02   6.000000E+00   5.000000E-01
+1    BEAM TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                                 F O R C E S   I N   B E A M   E L E M E N T S        ( C B E A M )
+                    STAT DIST/   - BENDING MOMENTS -            - WEB  SHEARS -           AXIAL          TOTAL          WARPING
+   ELEMENT-ID  GRID   LENGTH    PLANE 1       PLANE 2        PLANE 1       PLANE 2        FORCE          TORQUE         TORQUE
+0                2   1.000  -1.000000E+02  -1.000000E+02  -3.000000E+01   4.000000E+01   5.000000E+02   6.000000E+00   5.000000E-01
+0        2
+0                2   0.000   2.000000E+02            0.0  -6.000000E+01   8.000000E+01   1.000000E+03   1.200000E+01   1.000000E+00
+0                3   1.000  -2.000000E+02  -2.000000E+02  -6.000000E+01   8.000000E+01   1.000000E+03   1.200000E+01   1.000000E+00
+1    BEAM TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     3
+";
+
+/// Finds a beam force at a station of an element.
+fn beam_force(
+  block: &FinalBlock,
+  eid: usize,
+  fraction: f64,
+  field: BeamForceField
+) -> f64 {
+  let element = ElementRef { eid, etype: Some(ElementType::Beam) };
+  let row = ElementStation::at_fraction(element, fraction);
+  return f64::from(block.get(row, field).unwrap());
+}
+
+#[test]
+fn test_beam_forces() {
+  // mystran: the ends only, no warping
+  let file = parse_str(MYSTRAN_BEAM_FIXTURE);
+  let block = file.block_search(Some(BlockType::BeamForces), None, false)
+    .next()
+    .unwrap();
+  assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (2, 6));
+  let warping = BeamForceField::WarpingTorque.into();
+  assert!(!block.col_indexes.contains_key(&warping));
+  assert_eq!(block.data.as_ref().unwrap().ncols(), 6);
+  assert_eq!(block.non_finite().len(), 0);
+  assert_eq!(beam_force(block, 7, 0.0, BeamForceField::MomentPlane2), 20.0);
+  assert_eq!(beam_force(block, 7, 1.0, BeamForceField::MomentPlane1), -30.0);
+  for fraction in [0.0, 1.0] {
+    let get = |field| beam_force(block, 7, fraction, field);
+    assert_eq!(get(BeamForceField::ShearPlane2), -6.0);
+    assert_eq!(get(BeamForceField::AxialForce), 700.0);
+    assert_eq!(get(BeamForceField::TotalTorque), 8.0);
+  }
+  // simcenter: stations across a page break
+  let mut file = parse_str(SIMCENTER_BEAM_FIXTURE);
+  file.merge_blocks(true);
+  let blocks = file.block_search(Some(BlockType::BeamForces), None, false)
+    .collect::<Vec<_>>();
+  assert_eq!(blocks.len(), 1);
+  let block = blocks[0];
+  let stations = block.row_indexes.keys()
+    .map(|ix| match ix {
+      NasIndex::ElementStation(es) => (es.element.eid, es.station),
+      _ => panic!("bad row index {}", ix)
+    })
+    .collect::<BTreeSet<_>>();
+  let expected = [(1, 0), (1, 500), (1, 1000), (2, 0), (2, 1000)];
+  assert_eq!(stations, expected.into_iter().collect());
+  assert_eq!(beam_force(block, 1, 0.5, BeamForceField::MomentPlane2), -50.0);
+  assert_eq!(beam_force(block, 1, 1.0, BeamForceField::MomentPlane1), -100.0);
+  assert_eq!(beam_force(block, 2, 0.0, BeamForceField::AxialForce), 1000.0);
+  assert_eq!(beam_force(block, 2, 1.0, BeamForceField::WarpingTorque), 1.0);
+  // no solver, no decoding
+  let flavour = Flavour { solver: None, soltype: None };
+  let line = MYSTRAN_BEAM_FIXTURE.lines().last().unwrap();
+  assert_eq!(
+    BlockType::BeamForces.init_decoder(flavour).consume(line, 1),
+    LineResponse::BadFlavour
+  );
+}
+
+/// A Simcenter-like output with the engineering forces of two CBEAMs, with
+/// the ends named by tokens instead of the grid and the distance.
+const SIMCENTER_BEAM_ENDS_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    BEAM TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                 F O R C E S   I N   B E A M   E L E M E N T S        ( C B E A M )
+                             - BENDING MOMENTS -            - WEB  SHEARS -           AXIAL          TOTAL          WARPING
+   ELEMENT-ID             PLANE 1       PLANE 2        PLANE 1       PLANE 2        FORCE          TORQUE         TORQUE
+0        5   END A   1.000000E+01   2.000000E+01   3.000000E+00   4.000000E+00   5.000000E+02   6.000000E+00            0.0
+0            END B  -1.000000E+01  -2.000000E+01   3.000000E+00   4.000000E+00   5.000000E+02   6.000000E+00            0.0
+0        6   END-A   7.000000E+01            0.0  -1.000000E+00   2.000000E+00  -3.000000E+02   1.000000E+00   5.000000E-01
+0            END-B  -7.000000E+01            0.0  -1.000000E+00   2.000000E+00  -3.000000E+02   1.000000E+00   5.000000E-01
+";
+
+#[test]
+fn test_beam_force_end_tokens() {
+  let file = parse_str(SIMCENTER_BEAM_ENDS_FIXTURE);
+  let block = file.block_search(Some(BlockType::BeamForces), None, false)
+    .next()
+    .unwrap();
+  let ends = block.row_indexes.keys()
+    .map(|ix| match ix {
+      NasIndex::ElementStation(es) => (es.element.eid, es.end()),
+      _ => panic!("bad row index {}", ix)
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(ends, vec![
+    (5, Some(BarEnd::EndA)),
+    (5, Some(BarEnd::EndB)),
+    (6, Some(BarEnd::EndA)),
+    (6, Some(BarEnd::EndB))
+  ]);
+  assert_eq!(beam_force(block, 5, 0.0, BeamForceField::MomentPlane2), 20.0);
+  assert_eq!(beam_force(block, 5, 1.0, BeamForceField::MomentPlane1), -10.0);
+  assert_eq!(beam_force(block, 6, 0.0, BeamForceField::AxialForce), -300.0);
+  assert_eq!(beam_force(block, 6, 1.0, BeamForceField::WarpingTorque), 0.5);
+}
+
+#[test]
+fn test_fingerprint_backends() {
+  let text = ALL_ELEM_F06;
+  let reference = OnePassParser::parse_bufread(text.as_bytes()).unwrap();
+  let expected = reference.fingerprint();
+  // bytes in memory, and crlf line breaks
+  let crlf = text.replace('\n', "\r\n");
+  let mut parses = vec![
+    ("bytes", OnePassParser::parse_bytes(text.as_bytes()).unwrap()),
+    ("crlf", OnePassParser::parse_bytes(crlf.as_bytes()).unwrap()),
+  ];
+  // line by line, as when streaming from standard input
+  let mut parser = OnePassParser::new();
+  for line in text.lines() {
+    parser.consume(line);
+  }
+  parses.push(("stream", parser.finish()));
+  // from a file, directly and through the block index
+  let path = std::env::temp_dir()
+    .join(format!("f06-fingerprint-{}.f06", std::process::id()));
+  std::fs::write(&path, text).unwrap();
+  parses.push(("file", OnePassParser::parse_file(&path).unwrap()));
+  parses.push(("index", FileIndex::build_file(&path).unwrap().0));
+  std::fs::remove_file(&path).unwrap();
+  for (backend, file) in parses.iter() {
+    assert_eq!(file.fingerprint(), expected, "{} backend differs", backend);
+  }
+  // but it does change with what was parsed
+  let mut changed = reference.clone();
+  changed.fatal_errors.insert(1, "FATAL".to_owned());
+  assert_ne!(changed.fingerprint(), expected);
+  let other = parse_str(MYSTRAN_BEAM_FIXTURE);
+  assert_ne!(other.fingerprint(), expected);
+  assert_eq!(reference.fingerprint_hex().len(), 16);
+}
+
+/// The MYSTRAN CHEXA stresses, printed as strains.
+fn mystran_chexa_strains() -> String {
+  return MYSTRAN_CHEXA_FIXTURE
+    .replace("S T R E S S E S", "S T R A I N S")
+    .replace("Sigma", "Epsilon")
+    .replace("Tau", "Gamma");
+}
+
+#[test]
+fn test_chexa_strains() {
+  let file = parse_str(&mystran_chexa_strains());
+  assert!(file.block_search(Some(BlockType::ChexaStresses), None, false)
+    .next()
+    .is_none());
+  let block = file.block_search(Some(BlockType::ChexaStrains), None, false)
+    .next()
+    .unwrap();
+  assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (2, 7));
+  let expected = [
+    (101, [100.0, 50.0, -25.0, 10.0, 0.0, -5.0, 90.0]),
+    (102, [-20.0, 0.0, 40.0, 0.0, 15.0, 0.0, 60.0]),
+  ];
+  for (eid, values) in expected {
+    let element = ElementRef { eid, etype: Some(ElementType::Hexa) };
+    let row = ElementSidedPoint {
+      element,
+      point: ElementPoint::Centroid,
+      side: ElementSide::Bottom
+    };
+    for (field, value) in ChexaStressField::all().iter().zip(values) {
+      let got = f64::from(block.get(row, ChexaStrainField(*field)).unwrap());
+      assert_eq!(got, value, "{} of {}", field, eid);
+    }
+  }
+  // simcenter, at the corners too
+  let text = SIMCENTER_CHEXA_FIXTURE.replace("S T R E S S E S", "S T R A I N S");
+  let file = parse_str(&text);
+  let block = file.block_search(Some(BlockType::ChexaStrains), None, false)
+    .next()
+    .unwrap();
+  assert_eq!(block.row_indexes.len(), 9);
+  let element = ElementRef { eid: 101, etype: Some(ElementType::Hexa) };
+  let row = ElementSidedPoint {
+    element,
+    point: ElementPoint::Corner(GridPointRef { gid: 7 }),
+    side: ElementSide::Top
+  };
+  let get = |field| f64::from(block.get(row, ChexaStrainField(field)).unwrap());
+  assert_eq!(get(ChexaStressField::NormalX), 70.0);
+  assert_eq!(get(ChexaStressField::VonMises), 107.0);
+}
+
+/// A MYSTRAN-like output with the stresses of two CBEAMs, the second one
+/// without margins of safety.
+const MYSTRAN_BEAM_STRESS_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+
+ OUTPUT FOR SUBCASE        1
+
+             E L E M E N T   S T R E S S E S   I N   L O C A L   E L E M E N T   C O O R D I N A T E   S Y S T E M
+                                          F O R   E L E M E N T   T Y P E   B E A M
+  Element      SA1           SA2           SA3           SA4          Axial         SA-Max        SA-Min      M.S.-T
+     ID        SB1           SB2           SB3           SB4          Stress        SB-Max        SB-Min      M.S.-C
+
+        7  1.000000E+01  2.000000E+01 -1.000000E+01 -2.000000E+01  1.000000E+02  1.200000E+02  8.000000E+01  1.50E+00
+           3.000000E+01  4.000000E+01 -3.000000E+01 -4.000000E+01                1.400000E+02  6.000000E+01  2.50E+00
+
+        8  5.000000E+00 -5.000000E+00  5.000000E+00 -5.000000E+00  0.0           5.000000E+00 -5.000000E+00
+           6.000000E+00 -6.000000E+00  6.000000E+00 -6.000000E+00                6.000000E+00 -6.000000E+00
+";
+
+/// A Simcenter-like output with the stresses of a CBEAM with an intermediate
+/// station, where the margins of safety were left blank.
+const SIMCENTER_BEAM_STRESS_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    BEAM TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                 S T R E S S E S   I N   B E A M   E L E M E N T S        ( C B E A M )
+                    STAT DIST/
+   ELEMENT-ID  GRID   LENGTH    SXC           SXD           SXE           SXF           S-MAX         S-MIN         M.S.-T   M.S.-C
+0        7
+0                1   0.000   1.100000E+02   1.200000E+02   9.000000E+01   8.000000E+01   1.200000E+02   8.000000E+01   1.5E+00   2.5E+00
+0                0   0.500   1.000000E+02   1.000000E+02   1.000000E+02   1.000000E+02   1.000000E+02   1.000000E+02
+0                2   1.000   1.300000E+02   1.400000E+02   7.000000E+01   6.000000E+01   1.400000E+02   6.000000E+01   1.5E+00
+1    BEAM TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+/// Finds the beam stresses at a station of an element.
+fn beam_stresses(block: &FinalBlock, eid: usize, fraction: f64) -> Vec<f64> {
+  let element = ElementRef { eid, etype: Some(ElementType::Beam) };
+  let row = ElementStation::at_fraction(element, fraction);
+  return BeamStressField::all().iter()
+    .map(|field| f64::from(block.get(row, *field).unwrap()))
+    .collect();
+}
+
+/// Asserts two lists of values are the same, with NaN matching NaN.
+fn assert_values(got: Vec<f64>, expected: Vec<f64>) {
+  assert_eq!(format!("{:?}", got), format!("{:?}", expected));
+}
+
+#[test]
+fn test_beam_stresses() {
+  let end_a = [110.0, 120.0, 90.0, 80.0, 120.0, 80.0, 1.5];
+  let end_b = [130.0, 140.0, 70.0, 60.0, 140.0, 60.0, 1.5];
+  let with = |values: &[f64], margins: &[f64]| [values, margins].concat();
+  // mystran: the axial stress goes into each point, the margins to both ends
+  let file = parse_str(MYSTRAN_BEAM_STRESS_FIXTURE);
+  let block = file.block_search(Some(BlockType::BeamStresses), None, false)
+    .next()
+    .unwrap();
+  assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (4, 8));
+  assert_eq!(beam_stresses(block, 7, 0.0), with(&end_a, &[2.5]));
+  assert_eq!(beam_stresses(block, 7, 1.0), with(&end_b, &[2.5]));
+  assert_values(
+    beam_stresses(block, 8, 1.0),
+    vec![6.0, -6.0, 6.0, -6.0, 6.0, -6.0, f64::NAN, f64::NAN]
+  );
+  // simcenter: blank margins are NaN, and don't shift anything
+  let file = parse_str(SIMCENTER_BEAM_STRESS_FIXTURE);
+  let block = file.block_search(Some(BlockType::BeamStresses), None, false)
+    .next()
+    .unwrap();
+  assert_eq!(block.row_indexes.len(), 3);
+  assert_eq!(beam_stresses(block, 7, 0.0), with(&end_a, &[2.5]));
+  let middle = with(&[100.0; 6], &[f64::NAN; 2]);
+  assert_values(beam_stresses(block, 7, 0.5), middle);
+  assert_values(beam_stresses(block, 7, 1.0), with(&end_b, &[f64::NAN]));
+}
+
+#[test]
+fn test_beam_strains() {
+  // same layouts as the stresses, under strain headers
+  let mystran = MYSTRAN_BEAM_STRESS_FIXTURE
+    .replace("S T R E S S E S", "S T R A I N S");
+  let simcenter = SIMCENTER_BEAM_STRESS_FIXTURE
+    .replace("S T R E S S E S", "S T R A I N S");
+  for (text, rows) in [(mystran, 4), (simcenter, 3)] {
+    let file = parse_str(&text);
+    assert!(file.block_search(Some(BlockType::BeamStresses), None, false)
+      .next()
+      .is_none());
+    let block = file.block_search(Some(BlockType::BeamStrains), None, false)
+      .next()
+      .unwrap();
+    assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (rows, 8));
+    let element = ElementRef { eid: 7, etype: Some(ElementType::Beam) };
+    let row = ElementStation::at_fraction(element, 1.0);
+    let get = |field| {
+      return f64::from(block.get(row, BeamStrainField(field)).unwrap());
+    };
+    assert_eq!(get(BeamStressField::LongStressC), 130.0);
+    assert_eq!(get(BeamStressField::MinStress), 60.0);
+  }
+}
+
+/// A MYSTRAN-like output with the strains at the centroid and corners of a
+/// CPENTA. The normal X strain at corner N is N/1000, and von Mises is 1+N/100.
+const MYSTRAN_CPENTA_STRAIN_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+
+ OUTPUT FOR SUBCASE        1
+
+                          E L E M E N T   S T R A I N S   I N   L O C A L   E L E M E N T   C O O R D I N A T E   S Y S T E M
+                                                   F O R   E L E M E N T   T Y P E   P E N T A 6
+  Element  Location      Epsilon-xx    Epsilon-yy    Epsilon-zz     Gamma-xy      Gamma-yz      Gamma-zx     von Mises
+     ID
+      301   CENTER    5.000000E-04  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.000000E+00
+              1       1.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.010000E+00
+              2       2.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.020000E+00
+              3       3.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.030000E+00
+              4       4.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.040000E+00
+              5       5.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.050000E+00
+              6       6.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.060000E+00
+";
+
+#[test]
+fn test_cpenta_corners() {
+  let corner = |eid, gid: usize, side| ElementSidedPoint {
+    element: ElementRef { eid, etype: Some(ElementType::Penta) },
+    point: ElementPoint::Corner(GridPointRef { gid }),
+    side
+  };
+  // simcenter stresses, from the chexa fixture without the last two corners
+  let chexa = SIMCENTER_CHEXA_FIXTURE
+    .replace("H E X A H E D R O N", "P E N T A H E D R O N")
+    .replace("( H E X A )", "( P E N T A )")
+    .replace("8 GP", "6 GP");
+  let text = &chexa[..chexa.find("0               7").unwrap()];
+  let file = parse_str(text);
+  assert!(file.block_search(Some(BlockType::ChexaStresses), None, false)
+    .next()
+    .is_none());
+  let block = file.block_search(Some(BlockType::CpentaStresses), None, false)
+    .next()
+    .unwrap();
+  assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (7, 11));
+  for gid in 1..=6 {
+    // the first three corners are on one face, the other three on the other
+    let side = if gid > 3 { ElementSide::Top } else { ElementSide::Bottom };
+    let get = |field: CpentaStressField| {
+      return f64::from(block.get(corner(101, gid, side), field).unwrap());
+    };
+    assert_eq!(get(CpentaStressField::NormalX), 10.0 * gid as f64);
+    assert_eq!(get(CpentaStressField::ShearYZ), 3.0);
+    assert_eq!(get(CpentaStressField::VonMises), 100.0 + gid as f64);
+    assert_eq!(get(CpentaStressField::PrincipalA), 10.0 * gid as f64);
+  }
+  // mystran strains
+  let file = parse_str(MYSTRAN_CPENTA_STRAIN_FIXTURE);
+  let block = file.block_search(Some(BlockType::CpentaStrains), None, false)
+    .next()
+    .unwrap();
+  assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (7, 7));
+  for gid in 1..=6 {
+    let side = if gid > 3 { ElementSide::Top } else { ElementSide::Bottom };
+    let get = |field: CpentaStressField| {
+      let col = CpentaStrainField(field);
+      return f64::from(block.get(corner(301, gid, side), col).unwrap());
+    };
+    assert_eq!(get(CpentaStressField::NormalX), gid as f64 / 1000.0);
+    let vm = get(CpentaStressField::VonMises);
+    assert!((vm - (1.0 + gid as f64 / 100.0)).abs() < 1e-12);
+  }
+}
+
+/// A MYSTRAN-like output with the strains at the centroids and corners of two
+/// CTETRA10s, with the corners on continuation lines. The normal X strain at
+/// corner N is N/1000.
+const MYSTRAN_CTETRA_STRAIN_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+
+ OUTPUT FOR SUBCASE        1
+
+                          E L E M E N T   S T R A I N S   I N   L O C A L   E L E M E N T   C O O R D I N A T E   S Y S T E M
+                                                   F O R   E L E M E N T   T Y P E   T E T R A 1 0
+  Element  Location      Epsilon-xx    Epsilon-yy    Epsilon-zz     Gamma-xy      Gamma-yz      Gamma-zx     von Mises
+     ID
+      401   CENTER    5.000000E-04  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.000000E+00
+              1       1.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.010000E+00
+              2       2.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.020000E+00
+              3       3.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.030000E+00
+              4       4.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.040000E+00
+      402   CENTER    5.000000E-04  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.000000E+00
+              3       3.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.030000E+00
+              4       4.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.040000E+00
+              5       5.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.050000E+00
+              6       6.000000E-03  2.000000E-04 -1.000000E-04  3.000000E-04           0.0 -2.000000E-04  1.060000E+00
+";
+
+#[test]
+fn test_ctetra_corners() {
+  let point = |eid, gid: usize| ElementSidedPoint {
+    element: ElementRef { eid, etype: Some(ElementType::Tetra) },
+    point: match gid {
+      0 => ElementPoint::Centroid,
+      _ => ElementPoint::Corner(GridPointRef { gid })
+    },
+    // no opposite faces
+    side: ElementSide::Bottom
+  };
+  // simcenter stresses, from the chexa fixture with only four corners
+  let chexa = SIMCENTER_CHEXA_FIXTURE
+    .replace("H E X A H E D R O N", "T E T R A H E D R O N")
+    .replace("( H E X A )", "( C T E T R A )")
+    .replace("8 GP", "4 GP");
+  let text = &chexa[..chexa.find("0               5").unwrap()];
+  let file = parse_str(text);
+  let block = file.block_search(Some(BlockType::CtetraStresses), None, false)
+    .next()
+    .unwrap();
+  assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (5, 11));
+  for gid in 1..=4 {
+    let get = |field: CtetraStressField| {
+      return f64::from(block.get(point(101, gid), field).unwrap());
+    };
+    assert_eq!(get(CtetraStressField::NormalX), 10.0 * gid as f64);
+    assert_eq!(get(CtetraStressField::VonMises), 100.0 + gid as f64);
+  }
+  // mystran strains, for ten-noded ones
+  let file = parse_str(MYSTRAN_CTETRA_STRAIN_FIXTURE);
+  let block = file.block_search(Some(BlockType::CtetraStrains), None, false)
+    .next()
+    .unwrap();
+  assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (10, 7));
+  assert_eq!(block.data.as_ref().unwrap().ncols(), 7);
+  let get = |eid, gid| {
+    let col = CtetraStrainField(CtetraStressField::NormalX);
+    return f64::from(block.get(point(eid, gid), col).unwrap());
+  };
+  assert_eq!(get(401, 0), 5.0e-4);
+  assert_eq!(get(401, 4), 4.0e-3);
+  assert_eq!(get(402, 0), 5.0e-4);
+  for gid in 3..=6 {
+    assert_eq!(get(402, gid), gid as f64 / 1000.0);
+  }
+}
+
+/// A Simcenter-like table of shear panel stresses, two panels per line, with
+/// a margin printed as "N/A" and another one left blank.
+const SIMCENTER_CSHEAR_FIXTURE: &str = "
+1    CSHEAR TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                   S T R E S S E S   I N   S H E A R   P A N E L S      ( C S H E A R )
+      ELEMENT            MAX            AVG        SAFETY         ELEMENT            MAX            AVG        SAFETY
+        ID.             SHEAR          SHEAR       MARGIN           ID.             SHEAR          SHEAR       MARGIN
+0       501         1.200000E+02  -1.100000E+02      N/A              502         2.400000E+02   2.000000E+02   5.000000E-01
+        503         3.600000E+02   3.000000E+02
+";
+
+#[test]
+fn test_cshear_stresses() {
+  let file = parse_str(SIMCENTER_CSHEAR_FIXTURE);
+  let block = file.block_search(Some(BlockType::CshearStresses), None, false)
+    .next()
+    .unwrap();
+  assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (3, 3));
+  let get = |eid, field| {
+    let eref = ElementRef { eid, etype: Some(ElementType::Shear) };
+    return f64::from(block.get(eref, field).unwrap());
+  };
+  assert_eq!(get(501, CshearStressField::MaxShearStress), 120.0);
+  assert_eq!(get(501, CshearStressField::AverageShear), -110.0);
+  assert!(get(501, CshearStressField::MarginOfSafety).is_nan());
+  assert_eq!(get(502, CshearStressField::MarginOfSafety), 0.5);
+  assert_eq!(get(503, CshearStressField::AverageShear), 300.0);
+  assert!(get(503, CshearStressField::MarginOfSafety).is_nan());
+}
+
+/// A Simcenter-like table of shear panel forces, with the kick forces and the
+/// shear flows of each panel on two lines.
+const SIMCENTER_CSHEAR_FORCES_FIXTURE: &str = "
+1    CSHEAR TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                                   F O R C E S   I N   S H E A R   P A N E L S      ( C S H E A R )
+  ELEMENT        ====== POINT  1 ======      ====== POINT  2 ======      ====== POINT  3 ======      ====== POINT  4 ======
+     ID          F-FROM-4      F-FROM-2      F-FROM-1      F-FROM-3      F-FROM-2      F-FROM-4      F-FROM-3      F-FROM-1
+                  KICK-1       SHEAR-12       KICK-2       SHEAR-23       KICK-3       SHEAR-34       KICK-4       SHEAR-41
+0       501   1.000000E+00  2.000000E+00  3.000000E+00  4.000000E+00  5.000000E+00  6.000000E+00  7.000000E+00  8.000000E+00
+              1.100000E+01  1.200000E+01  1.300000E+01  1.400000E+01  1.500000E+01  1.600000E+01  1.700000E+01  1.800000E+01
+0       502  -1.000000E+00 -2.000000E+00 -3.000000E+00 -4.000000E+00 -5.000000E+00 -6.000000E+00 -7.000000E+00 -8.000000E+00
+             -1.100000E+01 -1.200000E+01 -1.300000E+01 -1.400000E+01 -1.500000E+01 -1.600000E+01 -1.700000E+01 -1.800000E+01
+";
+
+#[test]
+fn test_cshear_forces() {
+  let file = parse_str(SIMCENTER_CSHEAR_FORCES_FIXTURE);
+  let block = file.block_search(Some(BlockType::CshearForces), None, false)
+    .next()
+    .unwrap();
+  assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (2, 16));
+  let get = |eid, field| {
+    let eref = ElementRef { eid, etype: Some(ElementType::Shear) };
+    return f64::from(block.get(eref, field).unwrap());
+  };
+  assert_eq!(get(501, CshearForceField::F4ToF1), 1.0);
+  assert_eq!(get(501, CshearForceField::F1ToF4), 8.0);
+  assert_eq!(get(501, CshearForceField::Kick1), 11.0);
+  assert_eq!(get(501, CshearForceField::Shear41), 18.0);
+  assert_eq!(get(502, CshearForceField::Shear23), -14.0);
+}
+
+#[test]
+fn test_tetra_then_hexa() {
+  // a tetra block immediately followed by a hexa one, no page break between
+  let tetra = SIMCENTER_CHEXA_FIXTURE
+    .replace("H E X A H E D R O N", "T E T R A H E D R O N")
+    .replace("( H E X A )", "( C T E T R A )")
+    .replace("8 GP", "4 GP");
+  let tetra = &tetra[..tetra.find("0               5").unwrap()];
+  let hexa = SIMCENTER_CHEXA_FIXTURE.replace("0       101", "0       201");
+  let hexa = &hexa[hexa.find(" \n").unwrap()..];
+  let file = parse_str(&format!("{}{}", tetra, hexa));
+  let only = |bt: BlockType, eid: usize, etype: ElementType| {
+    let block = file.block_search(Some(bt), None, false).next().unwrap();
+    let expected = ElementRef { eid, etype: Some(etype) };
+    return block.row_indexes.keys().all(|ri| match ri {
+      NasIndex::ElementSidedPoint(p) => p.element == expected,
+      _ => false
+    });
+  };
+  assert!(only(BlockType::CtetraStresses, 101, ElementType::Tetra));
+  assert!(only(BlockType::ChexaStresses, 201, ElementType::Hexa));
+  let counts = file.all_blocks(false)
+    .map(|b| (b.block_type, b.row_indexes.len()))
+    .collect::<BTreeSet<_>>();
+  assert_eq!(
+    counts,
+    BTreeSet::from([
+      (BlockType::CtetraStresses, 5),
+      (BlockType::ChexaStresses, 9)
+    ])
+  );
+}
+
+/// A Simcenter-like output with the forces of two CBUSHes, the second one's
+/// line wrapped after the forces.
+const SIMCENTER_BUSH_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    BUSH TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                 F O R C E S   I N   B U S H   E L E M E N T S        ( C B U S H )
+ 
+                  ELEMENT-ID        FORCE-X       FORCE-Y       FORCE-Z      MOMENT-X      MOMENT-Y      MOMENT-Z
+0                     301      1.000000E+01  2.000000E+01  3.000000E+01  4.000000E+01  5.000000E+01  6.000000E+01
+0                     302     -1.000000E+00 -2.000000E+00 -3.000000E+00
+                               -4.000000E+00 -5.000000E+00 -6.000000E+00
+1    BUSH TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+#[test]
+fn test_bush_forces() {
+  let file = parse_str(SIMCENTER_BUSH_FIXTURE);
+  let block = file.block_search(Some(BlockType::BushForces), None, false)
+    .next()
+    .unwrap();
+  assert_eq!((block.row_indexes.len(), block.col_indexes.len()), (2, 6));
+  let get = |eid, dof| {
+    let eref = ElementRef { eid, etype: Some(ElementType::Bush) };
+    return f64::from(block.get(eref, dof).unwrap());
+  };
+  assert_eq!(get(301, DOF_RZ), 60.0);
+  assert_eq!(get(302, DOF_TZ), -3.0);
+  assert_eq!(get(302, DOF_RX), -4.0);
+  assert_eq!(get(302, DOF_RZ), -6.0);
+}
+
+/// A Simcenter-like output with the forces of two CBUSHes, with the element
+/// type printed before each ID.
+const SIMCENTER_BUSH_TYPED_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    BUSH TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                 F O R C E S   I N   B U S H   E L E M E N T S        ( C B U S H )
+ 
+                  ELEMENT-ID        FORCE-X       FORCE-Y       FORCE-Z      MOMENT-X      MOMENT-Y      MOMENT-Z
+0            CBUSH      7      1.000000E+01  2.000000E+01  3.000000E+01  4.000000E+01  5.000000E+01  6.000000E+01
+0            CBUSH   1008     -1.000000E+00 -2.000000E+00 -3.000000E+00
+                               -4.000000E+00 -5.000000E+00 -6.000000E+00
+1    BUSH TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+#[test]
+fn test_bush_forces_type_before_id() {
+  let file = parse_str(SIMCENTER_BUSH_TYPED_FIXTURE);
+  let block = file.block_search(Some(BlockType::BushForces), None, false)
+    .next()
+    .unwrap();
+  let eids = block.row_indexes.keys()
+    .map(|ix| match ix {
+      NasIndex::ElementRef(er) => (er.eid, er.etype),
+      _ => panic!("bad row index {}", ix)
+    })
+    .collect::<Vec<_>>();
+  let bush = Some(ElementType::Bush);
+  assert_eq!(eids, vec![(7, bush), (1008, bush)]);
+  let get = |eid, dof| {
+    let eref = ElementRef { eid, etype: bush };
+    return f64::from(block.get(eref, dof).unwrap());
+  };
+  assert_eq!(get(7, DOF_TX), 10.0);
+  assert_eq!(get(1008, DOF_RY), -5.0);
+}
+
+/// The Simcenter BUSH example, with forces, stresses and strains.
+const SIMCENTER_BUSH_F06: &str = include_str!(
+  "../../examples/scnastran/SB-BUSH-01-OFFSET-2b_scnas_postexport.f06"
+);
+
+#[test]
+fn test_bush_stresses_and_strains() {
+  let file = parse_str(SIMCENTER_BUSH_F06);
+  assert!(file.potential_headers.iter().all(|ph| !ph.text.contains("BUSH")));
+  let eref = ElementRef { eid: 1, etype: Some(ElementType::Bush) };
+  let stresses = file.block_search(Some(BlockType::BushStresses), None, false)
+    .next()
+    .unwrap();
+  let get = |field| f64::from(stresses.get(eref, field).unwrap());
+  assert_eq!(get(BushStressField::TranslationZ), 1800.0);
+  assert_eq!(get(BushStressField::RotationY), -11500.0);
+  let strains = file.block_search(Some(BlockType::BushStrains), None, false)
+    .next()
+    .unwrap();
+  let get = |field| {
+    let col = BushStrainField(field);
+    return f64::from(strains.get(eref, col).unwrap());
+  };
+  assert_eq!(get(BushStressField::TranslationX), 0.4);
+  assert_eq!(get(BushStressField::RotationX), 0.225);
+  assert_eq!(BushStressField::RotationX.dof(), DOF_RX);
+  assert_eq!(BushStressField::from(DOF_TY), BushStressField::TranslationY);
+}
+
+/// BUSH stresses printed with signs.
+const SIGNED_BUSH_STRESSES_FIXTURE: &str = "
+1    BUSH TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                  S T R E S S E S   I N   B U S H   E L E M E N T S        ( C B U S H )
+ 
+                  ELEMENT-ID        STRESS-TX     STRESS-TY     STRESS-TZ    STRESS-RX     STRESS-RY     STRESS-RZ
+0                          1      6.000000E+02  1.200000E+03  0.0          -1.500000E+03  1.150000E+04  1.250000E+04
+0                          2     -6.000000E+02 -1.200000E+03  0.0          -1.500000E+03 -1.150000E+04  1.250000E+04
+";
+
+/// The same BUSH stresses, printed as magnitudes with a polarity column that
+/// has a letter per component, one of the lines wrapping.
+const POLAR_BUSH_STRESSES_FIXTURE: &str = "
+1    BUSH TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                  S T R E S S E S   I N   B U S H   E L E M E N T S        ( C B U S H )
+ 
+                  ELEMENT-ID        STRESS-TX     STRESS-TY     STRESS-TZ    STRESS-RX     STRESS-RY     STRESS-RZ   POLARITY
+0                          1      6.000000E+02  1.200000E+03  0.0           1.500000E+03  1.150000E+04  1.250000E+04   TT-CTT
+0                          2      6.000000E+02  1.200000E+03  0.0
+                                 -1.500000E+03  1.150000E+04  1.250000E+04   CC--CT
+";
+
+#[test]
+fn test_bush_stress_polarity() {
+  let stresses = |f06| {
+    let file = parse_str(f06);
+    let block = file.block_search(Some(BlockType::BushStresses), None, false)
+      .next()
+      .unwrap()
+      .clone();
+    return block;
+  };
+  let signed = stresses(SIGNED_BUSH_STRESSES_FIXTURE);
+  let polar = stresses(POLAR_BUSH_STRESSES_FIXTURE);
+  assert_eq!(signed.row_indexes, polar.row_indexes);
+  assert_eq!(signed.data, polar.data);
+  let get = |eid, field| {
+    let eref = ElementRef { eid, etype: Some(ElementType::Bush) };
+    return f64::from(polar.get(eref, field).unwrap());
+  };
+  assert_eq!(get(1, BushStressField::RotationX), -1500.0);
+  assert_eq!(get(1, BushStressField::RotationY), 11500.0);
+  assert_eq!(get(2, BushStressField::RotationX), -1500.0);
+  assert_eq!(get(2, BushStressField::RotationY), -11500.0);
+  assert_eq!(get(2, BushStressField::RotationZ), 12500.0);
+  assert!(get(2, BushStressField::TranslationZ).is_sign_positive());
+  // forces don't have a polarity column, so nothing is flipped there
+  let forces = parse_str(&POLAR_BUSH_STRESSES_FIXTURE.replace(
+    "S T R E S S E S   I N   B U S H",
+    " F O R C E S   I N   B U S H"
+  ));
+  let block = forces.block_search(Some(BlockType::BushForces), None, false)
+    .next()
+    .unwrap();
+  let eref = ElementRef { eid: 2, etype: Some(ElementType::Bush) };
+  assert_eq!(f64::from(block.get(eref, DOF_TX).unwrap()), 600.0);
+}
+
+/// Spring forces packed five to a line, MYSTRAN-style, the last line only
+/// partly filled.
+const MYSTRAN_PACKED_SPRINGS_FIXTURE: &str = "
+ OUTPUT FOR SUBCASE        1
+
+                                                 E L E M E N T   E N G I N E E R I N G   F O R C E S
+                                                     F O R   E L E M E N T   T Y P E   E L A S 1
+                 Element     Force      Element     Force      Element     Force      Element     Force      Element     Force
+                    ID                     ID                     ID                     ID                     ID
+                      11  1.000000E+00         12  2.000000E+00         13  3.000000E+00         14  4.000000E+00         15  5.000000E+00
+                      16  6.000000E+00         17 -7.000000E+00
+                         -------------
+                MAX* :    6.000000E+00
+                MIN* :   -7.000000E+00
+
+ OUTPUT FOR SUBCASE        1
+
+                                        E L E M E N T   S T R E S S E S   I N   L O C A L   E L E M E N T   C O O R D I N A T E   S Y S T E M
+                                                     F O R   E L E M E N T   T Y P E   E L A S 1
+  Element     Stress     Element     Stress     Element     Stress     Element     Stress     Element     Stress
+     ID                     ID                     ID                     ID                     ID
+       11  1.000000E+01        12  2.000000E+01        13  3.000000E+01        14  4.000000E+01        15  5.000000E+01
+       16  6.000000E+01
+";
+
+/// Elements, subcases and values of the ELAS1 blocks in a file, sorted.
+fn elas1_values(file: &F06File) -> Vec<(BlockType, usize, usize, f64)> {
+  let mut found = Vec::new();
+  for block in file.all_blocks(false) {
+    if block.block_type.elem_type() != Some(ElementType::Elas1) {
+      continue;
+    }
+    for row in block.row_indexes.keys() {
+      let eid = match row {
+        NasIndex::ElementRef(eref) => eref.eid,
+        _ => panic!("ELAS1 row isn't an element")
+      };
+      for col in block.col_indexes.keys() {
+        let x = f64::from(block.get(*row, *col).unwrap());
+        found.push((block.block_type, block.subcase, eid, x));
+      }
+    }
+  }
+  found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+  return found;
+}
+
+#[test]
+fn test_packed_scalar_blocks() {
+  use BlockType::*;
+  let mystran = elas1_values(&parse_str(ALL_ELEM_F06));
+  assert_eq!(mystran, [
+    (Elas1Forces, 91, 58, 2555.301),
+    (Elas1Forces, 92, 58, 465.612),
+    (Elas1Stresses, 91, 58, 1916.476),
+    (Elas1Stresses, 92, 58, 349.209),
+  ]);
+  let simcenter = elas1_values(&parse_str(include_str!(
+    "../../examples/scnastran/SB-ALL-ELEM-TEST_scnas_postexport.f06"
+  )));
+  assert_eq!(simcenter, [
+    (Elas1Forces, 91, 58, -1842.961),
+    (Elas1Forces, 92, 58, -465.7955),
+    (Elas1Stresses, 91, 58, -1382.221),
+    (Elas1Stresses, 92, 58, -349.3466),
+    (Elas1Strains, 91, 58, -0.09214807),
+    (Elas1Strains, 92, 58, -0.02328978),
+  ]);
+  let packed = elas1_values(&parse_str(MYSTRAN_PACKED_SPRINGS_FIXTURE));
+  let forces = packed.iter()
+    .filter(|v| v.0 == Elas1Forces)
+    .map(|v| (v.2, v.3))
+    .collect::<Vec<_>>();
+  assert_eq!(forces, [
+    (11, 1.0), (12, 2.0), (13, 3.0), (14, 4.0), (15, 5.0), (16, 6.0),
+    (17, -7.0)
+  ]);
+  let stresses = packed.iter().filter(|v| v.0 == Elas1Stresses).count();
+  assert_eq!(stresses, 6);
+}
+
+/// ELAS2 spring forces and stresses, Simcenter-style.
+const SIMCENTER_ELAS2_FIXTURE: &str = "
+1    SPRING TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                              F O R C E S   I N   S C A L A R   S P R I N G S        ( C E L A S 2 )
+      ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE
+        ID.                              ID.                              ID.                              ID.
+           21      1.000000E+01           22     -2.000000E+01           23      3.000000E+01           24      4.000000E+01
+           25      5.000000E+01
+1    SPRING TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                              S T R E S S E S   I N   S C A L A R   S P R I N G S        ( C E L A S 2 )
+      ELEMENT         STRESS           ELEMENT         STRESS           ELEMENT         STRESS           ELEMENT         STRESS
+        ID.                              ID.                              ID.                              ID.
+           21      1.000000E+02           22     -2.000000E+02
+";
+
+#[test]
+fn test_elas2_springs() {
+  let file = parse_str(SIMCENTER_ELAS2_FIXTURE);
+  assert!(file.potential_headers.is_empty());
+  let forces = file.block_search(Some(BlockType::Elas2Forces), None, false)
+    .next()
+    .unwrap();
+  assert_eq!(forces.row_indexes.len(), 5);
+  let eref = |eid| ElementRef { eid, etype: Some(ElementType::Elas2) };
+  let get = |eid| f64::from(forces.get(eref(eid), SingleForce::Force).unwrap());
+  assert_eq!(get(22), -20.0);
+  assert_eq!(get(25), 50.0);
+  let stresses = file.block_search(Some(BlockType::Elas2Stresses), None, false)
+    .next()
+    .unwrap();
+  let stress = stresses.get(eref(22), SingleStress::Stress).unwrap();
+  assert_eq!(f64::from(stress), -200.0);
+  assert!(file.block_search(Some(BlockType::Elas1Forces), None, false)
+    .next()
+    .is_none());
+}
+
+/// A Simcenter-style file with forces and stresses for both CROD and CONROD
+/// elements, printed in separate tables.
+const SIMCENTER_CONROD_FIXTURE: &str = "
+1    ROD TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                           F O R C E S   I N   R O D   E L E M E N T S     ( C R O D )
+       ELEMENT           AXIAL                                     ELEMENT           AXIAL
+         ID.             FORCE          TORQUE                       ID.             FORCE          TORQUE
+            11        1.000000E+02   0.0                                12       -2.000000E+02   0.0
+1    ROD TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                                           F O R C E S   I N   R O D   E L E M E N T S     ( C O N R O D )
+       ELEMENT           AXIAL                                     ELEMENT           AXIAL
+         ID.             FORCE          TORQUE                       ID.             FORCE          TORQUE
+            21        3.000000E+02   0.0                                22       -4.000000E+02   1.000000E+00
+1    ROD TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     3
+0                                                                                SUBCASE 1
+ 
+                                     S T R E S S E S   I N   R O D   E L E M E N T S      ( C O N R O D )
+       ELEMENT       AXIAL       SAFETY      TORSIONAL     SAFETY       ELEMENT       AXIAL       SAFETY      TORSIONAL     SAFETY
+         ID.        STRESS       MARGIN        STRESS      MARGIN         ID.        STRESS       MARGIN        STRESS      MARGIN
+            21    3.000000E+03   2.5E+01    0.0                              22   -4.000000E+03   1.1E+01    0.0
+";
+
+#[test]
+fn test_conrod_blocks() {
+  let file = parse_str(SIMCENTER_CONROD_FIXTURE);
+  assert!(file.potential_headers.is_empty());
+  let rods = file.block_search(Some(BlockType::RodForces), None, false)
+    .next()
+    .unwrap();
+  let conrods = file.block_search(Some(BlockType::ConrodForces), None, false)
+    .next()
+    .unwrap();
+  let erefs = |block: &FinalBlock| -> Vec<ElementRef> {
+    return block.row_indexes.keys()
+      .map(|ix| match ix {
+        NasIndex::ElementRef(eref) => *eref,
+        _ => panic!("not an element row: {:?}", ix)
+      })
+      .collect();
+  };
+  let rod = |eid| ElementRef { eid, etype: Some(ElementType::Rod) };
+  let conrod = |eid| ElementRef { eid, etype: Some(ElementType::Conrod) };
+  assert_eq!(erefs(rods), vec![rod(11), rod(12)]);
+  assert_eq!(erefs(conrods), vec![conrod(21), conrod(22)]);
+  let axial = conrods.get(conrod(22), RodForceField::AxialForce).unwrap();
+  assert_eq!(f64::from(axial), -400.0);
+  let stresses = file.block_search(Some(BlockType::ConrodStresses), None, false)
+    .next()
+    .unwrap();
+  assert_eq!(erefs(stresses), vec![conrod(21), conrod(22)]);
+  let axial = stresses.get(conrod(21), RodStressField::Axial).unwrap();
+  assert_eq!(f64::from(axial), 3000.0);
+  assert!(file.block_search(Some(BlockType::RodStresses), None, false)
+    .next()
+    .is_none());
+}
+
+/// Gap element forces, with the MYSTRAN header in the first subcase and the
+/// Simcenter one in the second.
+const GAP_FORCES_FIXTURE: &str = "
+1    GAP TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                     F O R C E S   I N   G A P   E L E M E N T S   ( C G A P )
+  ELEMENT   - F O R C E S  I N  E L E M  S Y S T -     - D I S P L A C E M E N T S  I N  E L E M  S Y S T -
+    ID       COMP-X       SHEAR-Y      SHEAR-Z       AXIAL-U      TOTAL-V      TOTAL-W      SLIP-V       SLIP-W     STATUS
+        7   1.500000E+02  2.000000E+00 -3.000000E+00 -1.000000E-03  0.0          0.0          0.0          0.0         CLOSE
+        8   0.0           0.0          0.0           2.000000E-03  0.0          0.0          0.0          0.0         OPEN
+        9   2.000000E+02  5.000000E+01  0.0          -2.000000E-03  4.000000E-03  0.0          3.000000E-03  0.0         SLIDE
+1    GAP TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 2
+ 
+                                                 E L E M E N T   E N G I N E E R I N G   F O R C E S
+                                                     F O R   E L E M E N T   T Y P E   G A P
+  ELEMENT   - F O R C E S  I N  E L E M  S Y S T -     - D I S P L A C E M E N T S  I N  E L E M  S Y S T -
+    ID       COMP-X       SHEAR-Y      SHEAR-Z       AXIAL-U      TOTAL-V      TOTAL-W      SLIP-V       SLIP-W     STATUS
+        7   0.0           0.0          0.0           5.000000E-04  0.0          0.0          0.0          0.0         OPEN
+";
+
+#[test]
+fn test_cgap_forces() {
+  let file = parse_str(GAP_FORCES_FIXTURE);
+  assert!(file.potential_headers.is_empty());
+  let blocks = file.block_search(Some(BlockType::CgapForces), None, false)
+    .collect::<Vec<_>>();
+  assert_eq!(blocks.len(), 2);
+  let eref = |eid| ElementRef { eid, etype: Some(ElementType::Gap) };
+  let get = |block: &FinalBlock, eid, col| {
+    return f64::from(block.get(eref(eid), col).unwrap());
+  };
+  let (first, second) = (blocks[0], blocks[1]);
+  assert_eq!(first.subcase, 1);
+  assert_eq!(get(first, 7, CgapForceField::CompressiveForce), 150.0);
+  assert_eq!(get(first, 7, CgapForceField::ShearPlane1), 2.0);
+  assert_eq!(get(first, 7, CgapForceField::ShearPlane2), -3.0);
+  assert_eq!(get(first, 7, CgapForceField::Status), 1.0);
+  assert_eq!(get(first, 8, CgapForceField::Status), 0.0);
+  assert_eq!(get(first, 8, CgapForceField::AxialDisplacement), 0.002);
+  assert_eq!(get(first, 9, CgapForceField::TotalDisplacement1), 0.004);
+  assert_eq!(get(first, 9, CgapForceField::Slip1), 0.003);
+  // sliding is told apart from sticking
+  assert_eq!(get(first, 9, CgapForceField::Status), 2.0);
+  assert_eq!(second.subcase, 2);
+  assert_eq!(second.row_indexes.len(), 1);
+  assert_eq!(get(second, 7, CgapForceField::Status), 0.0);
+}
+
+/// Forces in all four kinds of scalar springs, then MYSTRAN-style stresses in
+/// ELAS3 springs.
+const ALL_SPRINGS_FIXTURE: &str = "
+1    SPRING TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                              F O R C E S   I N   S C A L A R   S P R I N G S        ( C E L A S 1 )
+      ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE
+        ID.                              ID.                              ID.                              ID.
+           11      1.000000E+01           12     -1.000000E+02
+1    SPRING TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                              F O R C E S   I N   S C A L A R   S P R I N G S        ( C E L A S 2 )
+      ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE
+        ID.                              ID.                              ID.                              ID.
+           21      2.000000E+01           22     -2.000000E+02
+1    SPRING TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     3
+0                                                                                SUBCASE 1
+ 
+                              F O R C E S   I N   S C A L A R   S P R I N G S        ( C E L A S 3 )
+      ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE
+        ID.                              ID.                              ID.                              ID.
+           31      3.000000E+01           32     -3.000000E+02
+1    SPRING TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     4
+0                                                                                SUBCASE 1
+ 
+                              F O R C E S   I N   S C A L A R   S P R I N G S        ( C E L A S 4 )
+      ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE
+        ID.                              ID.                              ID.                              ID.
+           41      4.000000E+01           42     -4.000000E+02
+ 
+                                                 E L E M E N T   S T R E S S E S   I N   L O C A L   E L E M E N T   C O O R D I N A T E   S Y S T E M
+                                                     F O R   E L E M E N T   T Y P E   E L A S 3
+  Element     Stress     Element     Stress     Element     Stress     Element     Stress     Element     Stress
+     ID                     ID                     ID                     ID                     ID
+       31  3.000000E+03        32 -3.000000E+04
+";
+
+#[test]
+fn test_all_spring_types() {
+  let file = parse_str(ALL_SPRINGS_FIXTURE);
+  assert!(file.potential_headers.is_empty());
+  let kinds = [
+    (BlockType::Elas1Forces, ElementType::Elas1),
+    (BlockType::Elas2Forces, ElementType::Elas2),
+    (BlockType::Elas3Forces, ElementType::Elas3),
+    (BlockType::Elas4Forces, ElementType::Elas4),
+  ];
+  for (n, (bt, etype)) in kinds.into_iter().enumerate() {
+    let blocks = file.block_search(Some(bt), None, false)
+      .collect::<Vec<_>>();
+    assert_eq!(blocks.len(), 1, "{}", bt);
+    let n = n + 1;
+    let eref = |eid| ElementRef { eid, etype: Some(etype) };
+    let get = |eid| {
+      return f64::from(blocks[0].get(eref(eid), SingleForce::Force).unwrap());
+    };
+    assert_eq!(blocks[0].row_indexes.len(), 2);
+    assert_eq!(get(10*n + 1), 10.0 * n as f64);
+    assert_eq!(get(10*n + 2), -100.0 * n as f64);
+  }
+  let stresses = file.block_search(Some(BlockType::Elas3Stresses), None, false)
+    .next()
+    .unwrap();
+  let eref = ElementRef { eid: 32, etype: Some(ElementType::Elas3) };
+  let stress = stresses.get(eref, SingleStress::Stress).unwrap();
+  assert_eq!(f64::from(stress), -30000.0);
+}
+
+#[test]
+fn test_row_source_lines() {
+  let file = parse_str(GAP_FORCES_FIXTURE);
+  let lines = GAP_FORCES_FIXTURE.lines().collect::<Vec<_>>();
+  let blocks = file.block_search(Some(BlockType::CgapForces), None, false)
+    .collect::<Vec<_>>();
+  assert_eq!(blocks[0].uid, Some(0));
+  assert_eq!(blocks[1].uid, Some(1));
+  for block in blocks {
+    for row in block.row_indexes.keys() {
+      let line = block.source_line(*row).unwrap();
+      let eid = match row {
+        NasIndex::ElementRef(eref) => eref.eid.to_string(),
+        _ => panic!("not an element row: {:?}", row)
+      };
+      assert_eq!(lines[line-1].split_whitespace().next(), Some(eid.as_str()));
+    }
+  }
+  // merging keeps them
+  let lines_of = |file: &F06File| {
+    return file.all_blocks(false)
+      .flat_map(|b| {
+        return b.row_lines.iter().map(|(row, line)| {
+          return ((b.block_ref(), *row), *line);
+        });
+      })
+      .collect::<BTreeSet<_>>();
+  };
+  let mut file = parse_str(ALL_ELEM_F06);
+  let before = lines_of(&file);
+  let rows = file.all_blocks(false)
+    .map(|b| b.row_indexes.len())
+    .sum::<usize>();
+  assert_eq!(before.len(), rows);
+  file.merge_blocks(true);
+  assert_eq!(lines_of(&file), before);
+}
+
+/// A Simcenter-like output where the quad stress header wraps, with a blank
+/// line between its two parts.
+const WRAPPED_HEADER_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    WRAPPED HEADER TEST                                 SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                         S T R E S S E S   I N   Q U A D R I L A T E R A L
+ 
+                                             E L E M E N T S   ( Q U A D 4 )
+  ELEMENT      FIBER               STRESSES IN ELEMENT COORD SYSTEM             PRINCIPAL STRESSES (ZERO SHEAR)
+    ID.       DISTANCE           NORMAL-X       NORMAL-Y      SHEAR-XY       ANGLE         MAJOR           MINOR        VON MISES
+0      11   -6.250000E-02      1.100000E+01   0.0            0.0            0.0000    1.100000E+01    0.0            1.100000E+01
+             6.250000E-02      1.200000E+01   0.0            0.0            0.0000    1.200000E+01    0.0            1.200000E+01
+0      12   -6.250000E-02      2.100000E+01   0.0            0.0            0.0000    2.100000E+01    0.0            2.100000E+01
+             6.250000E-02      2.200000E+01   0.0            0.0            0.0000    2.200000E+01    0.0            2.200000E+01
+1    WRAPPED HEADER TEST                                 SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+#[test]
+fn test_wrapped_header() {
+  let file = parse_str(WRAPPED_HEADER_FIXTURE);
+  assert!(file.potential_headers.is_empty());
+  let block = file.block_search(Some(BlockType::QuadStresses), None, false)
+    .next()
+    .unwrap();
+  assert_eq!(block.row_indexes.len(), 4);
+  assert_eq!(block.line_range, Some((9, 16)));
+  let top = block.row_indexes.keys()
+    .copied()
+    .find(|ri| matches!(
+      ri,
+      NasIndex::ElementSidedPoint(esp)
+        if esp.element.eid == 12 && esp.side == ElementSide::Top
+    ))
+    .unwrap();
+  let nx = block.get(top, PlateStressField::NormalX).unwrap();
+  assert_eq!(f64::from(nx), 22.0);
+  // the same block when loaded on demand
+  let path = std::env::temp_dir()
+    .join(format!("f06-wrapped-header-{}.f06", std::process::id()));
+  std::fs::write(&path, WRAPPED_HEADER_FIXTURE).unwrap();
+  let (_, index) = FileIndex::build_file(&path).unwrap();
+  let loader = BlockLoader::open(&path, index);
+  assert_eq!(&loader.load(block.block_ref()).unwrap(), block);
+  std::fs::remove_file(&path).unwrap();
+  // with the parts required to be consecutive, they're two potential headers
+  let mut parser = OnePassParser::new();
+  parser.header_window(1);
+  WRAPPED_HEADER_FIXTURE.lines().for_each(|l| { parser.consume(l); });
+  let mut strict = parser.finish();
+  assert!(strict.blocks.is_empty());
+  assert_eq!(strict.potential_headers.len(), 2);
+  // which merging then puts back together
+  assert_eq!(strict.merge_potential_headers(), 1);
+  let merged = strict.potential_headers.first().unwrap();
+  assert_eq!((merged.start, merged.span), (6, 3));
+  // but two unrelated headers a line apart stay separate
+  let header = |start: usize, text: &str| PotentialHeader {
+    start,
+    span: 1,
+    text: text.to_owned(),
+    offset: 0
+  };
+  let first = header(6, "S T R E S S E S   I N   S O M E T H I N G");
+  let second = header(8, "F O R C E S   I N   S O M E T H I N G   E L S E");
+  let (first, second) = first.try_merge(second).unwrap_err();
+  assert_eq!((first.start, second.start), (6, 8));
+  // unless they're consecutive
+  assert_eq!(first.try_merge(header(7, "T H I N G")).unwrap().span, 2);
+}
+
+/// Simcenter-like forces in two kinds of scalar dampers.
+const CDAMP_FORCES_FIXTURE: &str = "
+1    DAMPER TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                              F O R C E S   I N   S C A L A R   D A M P E R S        ( C D A M P 1 )
+      ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE
+        ID.                              ID.                              ID.                              ID.
+           11      1.000000E+01           12     -1.000000E+02
+1    DAMPER TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 2
+ 
+                              F O R C E S   I N   S C A L A R   D A M P E R S        ( C D A M P 4 )
+      ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE
+        ID.                              ID.                              ID.                              ID.
+           41      4.000000E+01           42     -4.000000E+02           43      4.000000E+00
+";
+
+#[test]
+fn test_cdamp_forces() {
+  let file = parse_str(CDAMP_FORCES_FIXTURE);
+  assert!(file.potential_headers.is_empty());
+  // the subcase, the damper kind and how many dampers
+  let kinds = [(1, 1, ElementType::Damp1, 2), (2, 4, ElementType::Damp4, 3)];
+  for (subcase, n, etype, rows) in kinds {
+    let bt = Some(BlockType::CdampForces);
+    let block = file.block_search(bt, Some(subcase), false).next().unwrap();
+    assert_eq!(block.row_indexes.len(), rows);
+    let eref = ElementRef { eid: 10*n + 2, etype: Some(etype) };
+    let force = block.get(eref, SingleForce::Force).unwrap();
+    assert_eq!(f64::from(force), -100.0 * n as f64);
+  }
+}
+
+/// Simcenter-like forces in viscous dampers, two to a line.
+const CVISC_FORCES_FIXTURE: &str = "
+1    DAMPER TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                 F O R C E S   I N   V I S C   E L E M E N T S   ( C V I S C )
+                 ELEMENT           AXIAL                                       ELEMENT           AXIAL
+                   ID.             FORCE           TORQUE                        ID.             FORCE           TORQUE
+                    21         1.500000E+01     -2.000000E-01                     22        -3.000000E+01      0.0
+                    23         4.500000E+01      1.000000E+00
+";
+
+#[test]
+fn test_cvisc_forces() {
+  let file = parse_str(CVISC_FORCES_FIXTURE);
+  assert!(file.potential_headers.is_empty());
+  let bt = Some(BlockType::ViscForces);
+  let block = file.block_search(bt, Some(1), false).next().unwrap();
+  assert_eq!(block.row_indexes.len(), 3);
+  let eref = |eid| ElementRef { eid, etype: Some(ElementType::Visc) };
+  let get = |eid, col| f64::from(block.get(eref(eid), col).unwrap());
+  assert_eq!(get(21, RodForceField::AxialForce), 15.0);
+  assert_eq!(get(21, RodForceField::Torque), -0.2);
+  assert_eq!(get(22, RodForceField::AxialForce), -30.0);
+  assert_eq!(get(23, RodForceField::Torque), 1.0);
+}
+
+/// Returns a Simcenter-like page with a packed table of spring results.
+fn spring_page(kind: &str, elas: usize, values: &str) -> String {
+  let spaced = |s: &str| s.chars()
+    .map(|c| c.to_string())
+    .collect::<Vec<_>>()
+    .join(" ");
+  return format!(
+    concat!(
+      "1    SPRING TEST                                         ",
+      "SIMCENTER NASTRAN  2/11/21   PAGE     1\n",
+      "0                                                                     ",
+      "           SUBCASE 1\n",
+      " \n",
+      "                         {}   I N   S C A L A R   S P R I N G S",
+      "        ( C E L A S {} )\n",
+      "      ELEMENT         {:<6}           ELEMENT         {:<6}\n",
+      "        ID.                              ID.\n",
+      "{}\n"
+    ),
+    spaced(kind),
+    elas,
+    kind,
+    kind,
+    values
+  );
+}
+
+#[test]
+fn test_spring_stresses_and_strains() {
+  let etypes = [
+    ElementType::Elas1,
+    ElementType::Elas2,
+    ElementType::Elas3,
+    ElementType::Elas4
+  ];
+  let kinds = [
+    ("STRESSES", [
+      BlockType::Elas1Stresses,
+      BlockType::Elas2Stresses,
+      BlockType::Elas3Stresses,
+      BlockType::Elas4Stresses
+    ]),
+    ("STRAINS", [
+      BlockType::Elas1Strains,
+      BlockType::Elas2Strains,
+      BlockType::Elas3Strains,
+      BlockType::Elas4Strains
+    ]),
+  ];
+  for (kind, block_types) in kinds {
+    let f06 = (1..=4)
+      .map(|n| {
+        let values = format!(
+          "{:>13}  {}.000000E+00  {:>11}  -{}.000000E+01",
+          10*n + 1, n, 10*n + 2, n
+        );
+        return spring_page(kind, n, &values);
+      })
+      .collect::<String>();
+    let file = parse_str(&f06);
+    assert!(file.potential_headers.is_empty());
+    for (n, (bt, etype)) in block_types.into_iter().zip(etypes).enumerate() {
+      let n = n + 1;
+      let block = file.block_search(Some(bt), None, false).next().unwrap();
+      assert_eq!(block.row_indexes.len(), 2, "{}", bt);
+      // every row is tagged with the spring's own type
+      for row in block.row_indexes.keys() {
+        assert!(
+          matches!(row, NasIndex::ElementRef(eref) if eref.etype == Some(etype)),
+          "{} in {}", row, bt
+        );
+      }
+      let eref = ElementRef { eid: 10*n + 2, etype: Some(etype) };
+      let col = block.col_indexes.keys().next().copied().unwrap();
+      let value = block.get(eref, col).unwrap();
+      assert_eq!(f64::from(value), -10.0 * n as f64);
+    }
+  }
+}
+
+/// Returns a Simcenter-like output with the displacements of four grid
+/// points in some subcases, each a pattern of translations scaled along the
+/// grid points.
+fn displacement_subcases(subcases: &[(usize, [f64; 3])]) -> String {
+  let mut f06 = String::from("                      Welcome to Simcenter Nastran\n");
+  for (page, (subcase, pattern)) in subcases.iter().enumerate() {
+    f06.push_str(&format!(
+      concat!(
+        "1    PAIRING TEST{:>65}\n",
+        "0{:>87}\n",
+        " \n",
+        "                                             ",
+        "D I S P L A C E M E N T   V E C T O R\n",
+        " \n",
+        "      POINT ID.   TYPE          T1             T2             T3",
+        "             R1             R2             R3\n"
+      ),
+      format!("PAGE {:>5}", page + 1),
+      format!("SUBCASE {}", subcase)
+    ));
+    for gid in 1..=4 {
+      let t = pattern.map(|x| format!("{:>15.6E}", x * gid as f64));
+      f06.push_str(&format!(
+        "{:>14}      G {}{}{}   0.0            0.0            0.0\n",
+        gid, t[0], t[1], t[2]
+      ));
+    }
+  }
+  return f06;
+}
+
+#[test]
+fn test_content_pairing() {
+  let loads = [[1.0, 0.1, 0.0], [0.0, 1.0, 0.2], [0.3, 0.0, 1.0]];
+  // the same three loads, renumbered and slightly off
+  let noisy = |load: [f64; 3], k: f64| load.map(|x| x * (1.0 + 1e-4 * k));
+  let first = parse_str(&displacement_subcases(&[
+    (1, loads[0]),
+    (2, loads[1]),
+    (3, loads[2])
+  ]));
+  let second = parse_str(&displacement_subcases(&[
+    (10, noisy(loads[2], 1.0)),
+    (20, noisy(loads[0], -2.0)),
+    (30, noisy(loads[1], 3.0))
+  ]));
+  let pairing = ContentPairing::propose(&first, &second);
+  assert!(pairing.ambiguous.is_empty());
+  assert!(pairing.unpaired_a.is_empty() && pairing.unpaired_b.is_empty());
+  let found = pairing.pairs.iter()
+    .map(|p| (p.subcase_a, p.subcase_b))
+    .collect::<BTreeSet<_>>();
+  assert_eq!(found, BTreeSet::from([(1, 20), (2, 30), (3, 10)]));
+  assert!(pairing.pairs.iter().all(|p| p.score > 0.99 && p.margin > 0.5));
+  // and the paired blocks get compared
+  let settings = DiffSettings {
+    criteria: Criteria {
+      difference: None,
+      ratio: Some(1.01),
+      nan: false,
+      inf: false,
+      sig: false
+    },
+    dxn_behaviour: Some(DisjunctionBehaviour::Skip),
+    max_flags: None,
+    auto_tol: false,
+    pair_by: PairBy::Content
+  };
+  let diff = F06Diff::compare(&settings, &first, &second);
+  assert!(diff.not_compared.is_empty() && diff.not_compared_b.is_empty());
+  assert_eq!(diff.compared.len(), 3);
+  assert!(diff.compared.values().all(Vec::is_empty));
+  // a block only the second file has is reported too
+  let extra = parse_str(&displacement_subcases(&[
+    (10, noisy(loads[2], 1.0)),
+    (20, noisy(loads[0], -2.0)),
+    (30, noisy(loads[1], 3.0)),
+    (40, [-1.0, 0.0, 0.0])
+  ]));
+  let diff = F06Diff::compare(&settings, &first, &extra);
+  assert_eq!(diff.compared.len(), 3);
+  let br40 = BlockRef { subcase: 40, block_type: BlockType::Displacements };
+  assert!(matches!(
+    diff.not_compared_b.get(&SteppedBlockRef::from(br40)),
+    Some(NonCompareReason::NoCounterpart(_))
+  ));
+  // as are subcases with more than one block, which can't be paired
+  let mut doubled = second.clone();
+  let br10 = BlockRef { subcase: 10, block_type: BlockType::Displacements };
+  let copy = doubled.blocks[&br10][0].clone();
+  doubled.blocks.get_mut(&br10).unwrap().push(copy);
+  let diff = F06Diff::compare(&settings, &first, &doubled);
+  let pairing = diff.pairing.as_ref().unwrap();
+  assert_eq!(pairing.not_unique_b, [br10]);
+  assert_eq!(pairing.pairs.len(), 2);
+  assert!(matches!(
+    diff.not_compared_b.get(&SteppedBlockRef::from(br10)),
+    Some(NonCompareReason::NotUniqueInOne(_))
+  ));
+  // by subcase, nothing has a counterpart
+  let by_subcase = DiffSettings { pair_by: PairBy::Subcase, ..settings };
+  let diff = F06Diff::compare(&by_subcase, &first, &second);
+  assert!(diff.compared.is_empty());
+  // two near-identical candidates are reported, not guessed between
+  let twins = parse_str(&displacement_subcases(&[
+    (10, noisy(loads[0], 1.0)),
+    (20, noisy(loads[0], -1.0))
+  ]));
+  let diff = F06Diff::compare(&settings, &first, &twins);
+  let pairing = diff.pairing.unwrap();
+  assert_eq!(pairing.ambiguous.len(), 1);
+  let amb = pairing.ambiguous[0];
+  assert_eq!((amb.best.subcase_a, amb.rival.subcase_a), (1, 1));
+  // both twins are spoken for, and the other subcases are too unlike them
+  assert!(pairing.pairs.is_empty() && pairing.unpaired_b.is_empty());
+  let unpaired = pairing.unpaired_a.iter()
+    .map(|br| br.subcase)
+    .collect::<Vec<_>>();
+  assert_eq!(unpaired, [2, 3]);
+  let br3 = BlockRef { subcase: 3, block_type: BlockType::Displacements };
+  assert!(matches!(
+    diff.not_compared.get(&SteppedBlockRef::from(br3)),
+    Some(NonCompareReason::NoCounterpart(_))
+  ));
+  let br = BlockRef { subcase: 1, block_type: BlockType::Displacements };
+  let sbr = SteppedBlockRef::from(br);
+  assert!(matches!(
+    diff.not_compared.get(&sbr),
+    Some(NonCompareReason::AmbiguousPairing(_))
+  ));
+  assert!(!diff.compared.contains_key(&sbr));
+  // and so are the twins, from the second file's side
+  assert_eq!(diff.not_compared_b.len(), 2);
+  assert!(diff.not_compared_b.values().all(|reason| {
+    return matches!(reason, NonCompareReason::AmbiguousPairing(_));
+  }));
+}
+
+/// Simcenter-like eigenvalue tables, real and complex.
+const EIGENVALUES_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                              R E A L   E I G E N V A L U E S
+   MODE    EXTRACTION      EIGENVALUE            RADIANS             CYCLES            GENERALIZED         GENERALIZED
+    NO.       ORDER                                                                       MASS              STIFFNESS
+        1         1        3.947842E+04        1.986918E+02        3.162278E+01        1.000000E+00        3.947842E+04
+        2         2        1.579137E+05        3.973835E+02        6.324555E+01        1.000000E+00        1.579137E+05
+        3         3        6.316547E+05        7.947670E+02        1.264911E+02        2.000000E+00        1.263309E+06
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 2
+ 
+                                        C O M P L E X   E I G E N V A L U E   S U M M A R Y
+0                ROOT     EXTRACTION                  EIGENVALUE                     FREQUENCY              DAMPING
+                  NO.        ORDER             (REAL)           (IMAG)                (CYCLES)            COEFFICIENT
+                    1          2          -1.256637E+00      6.283185E+01          1.000000E+01          4.000000E-02
+                    2          1          -2.513274E+00      1.256637E+02          2.000000E+01          4.000000E-02
+";
+
+#[test]
+fn test_eigenvalues() {
+  let file = parse_str(EIGENVALUES_FIXTURE);
+  assert!(file.potential_headers.is_empty());
+  let bt = Some(BlockType::RealEigenvalues);
+  let real = file.block_search(bt, Some(1), false).next().unwrap();
+  assert_eq!(real.row_indexes.len(), 3);
+  let get = |mode_number, col| {
+    return f64::from(real.get(ModeRef { mode_number }, col).unwrap());
+  };
+  assert_eq!(get(2, EigenvalueField::ExtractionOrder), 2.0);
+  assert_eq!(get(1, EigenvalueField::Cycles), 31.62278);
+  assert_eq!(get(3, EigenvalueField::GeneralizedMass), 2.0);
+  assert_eq!(get(3, EigenvalueField::GeneralizedStiffness), 1.263309E6);
+  let bt = Some(BlockType::ComplexEigenvalues);
+  let complex = file.block_search(bt, Some(2), false).next().unwrap();
+  assert_eq!(complex.row_indexes.len(), 2);
+  let root = ModeRef { mode_number: 1 };
+  let get = |col| f64::from(complex.get(root, col).unwrap());
+  assert_eq!(get(ComplexEigenvalueField::ExtractionOrder), 2.0);
+  assert_eq!(get(ComplexEigenvalueField::RealPart), -1.256637);
+  assert_eq!(get(ComplexEigenvalueField::ImaginaryPart), 62.83185);
+  assert_eq!(get(ComplexEigenvalueField::Damping), 0.04);
+  // and the roots, without going through the block
+  let roots = file.complex_roots();
+  assert_eq!(roots.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+  assert_eq!(roots[&1], (-1.256637, 62.83185));
+  assert_eq!(roots[&2], (-2.513274, 125.6637));
+}
+
+/// A real eigenvalue table split over two pages, with no subcase line, then
+/// displacements for a subcase.
+const SPLIT_EIGENVALUES_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     1
+ 
+                                              R E A L   E I G E N V A L U E S
+   MODE    EXTRACTION      EIGENVALUE            RADIANS             CYCLES            GENERALIZED         GENERALIZED
+    NO.       ORDER                                                                       MASS              STIFFNESS
+        1         1        3.947842E+04        1.986918E+02        3.162278E+01        1.000000E+00        3.947842E+04
+        2         2        1.579137E+05        3.973835E+02        6.324555E+01        1.000000E+00        1.579137E+05
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     2
+ 
+                                              R E A L   E I G E N V A L U E S
+   MODE    EXTRACTION      EIGENVALUE            RADIANS             CYCLES            GENERALIZED         GENERALIZED
+    NO.       ORDER                                                                       MASS              STIFFNESS
+        3         3        6.316547E+05        7.947670E+02        1.264911E+02        2.000000E+00        1.263309E+06
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     3
+0                                                                                SUBCASE 7
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1.000000E+00   0.0            0.0            0.0            0.0            0.0
+";
+
+#[test]
+fn test_natural_frequencies() {
+  let mut file = parse_str(SPLIT_EIGENVALUES_FIXTURE);
+  let bt = Some(BlockType::RealEigenvalues);
+  assert_eq!(file.block_search(bt, None, false).count(), 2);
+  assert_eq!(file.natural_frequencies().len(), 3);
+  // both pages take the subcase the parser started with, so they merge
+  file.merge_blocks(true);
+  let tables = file.block_search(bt, None, false).collect::<Vec<_>>();
+  assert_eq!(tables.len(), 1);
+  assert_eq!(tables[0].row_indexes.len(), 3);
+  let freqs = file.natural_frequencies();
+  assert_eq!(freqs.keys().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+  assert_eq!(freqs[&1], 31.62278);
+  assert_eq!(freqs[&3], 126.4911);
+  // the complex summary isn't mistaken for natural frequencies
+  let file = parse_str(EIGENVALUES_FIXTURE);
+  assert_eq!(file.natural_frequencies().len(), 3);
+}
+
+/// A SOL 105 run: a static subcase, then the buckling eigenvalues.
+const BUCKLING_F06: &str = include_str!(
+  "../../examples/scnastran/column_buckling_sol105.f06"
+);
+
+#[test]
+fn test_buckling_eigenvalues() {
+  let file = parse_str(BUCKLING_F06);
+  assert_eq!(file.flavour.soltype, Some(SolType::LinearBuckling));
+  assert!(file.potential_headers.is_empty());
+  let bt = Some(BlockType::RealEigenvalues);
+  assert_eq!(file.block_search(bt, None, false).count(), 0);
+  assert!(file.natural_frequencies().is_empty());
+  let bt = Some(BlockType::BucklingEigenvalues);
+  let table = file.block_search(bt, Some(2), false).next().unwrap();
+  assert_eq!(table.row_indexes.len(), 3);
+  let first = ModeRef { mode_number: 1 };
+  let get = |col| f64::from(table.get(first, col).unwrap());
+  assert_eq!(get(EigenvalueField::Radians), 1.110721);
+  assert_eq!(get(EigenvalueField::GeneralizedStiffness), 1.233701);
+  assert_eq!(
+    file.buckling_factors(),
+    vec![(1, 1.233701), (2, 11.10331), (3, 30.84251)]
+  );
+  // the solution can be named too, like MYSTRAN and older decks do
+  let named = BUCKLING_F06.replace("SOL 105", "SOL BUCKLING");
+  assert_eq!(parse_str(&named).buckling_factors().len(), 3);
+  // and the same table elsewhere is read as natural frequencies
+  let modes = BUCKLING_F06.replace("SOL 105", "SOL 103");
+  let file = parse_str(&modes);
+  assert!(file.buckling_factors().is_empty());
+  assert_eq!(file.natural_frequencies().len(), 3);
+}
+
+/// A MYSTRAN-like table of buckling eigenvalues, named so by its header, from
+/// a run whose solution isn't echoed.
+const MYSTRAN_BUCKLING_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+                                                                                                                  OUTPUT FOR SUBCASE          1
+ 
+                                             B U C K L I N G   E I G E N V A L U E S
+ 
+          MODE   EXTRACTION        EIGENVALUE             RADIANS              CYCLES           GENERALIZED           GENERALIZED
+         NUMBER     ORDER                                                                          MASS                STIFFNESS
+              1         1        1.233701E+00        1.110721E+00        1.767767E-01        1.000000E+00        1.233701E+00
+              2         2        1.110331E+01        3.332163E+00        5.303301E-01        1.000000E+00        1.110331E+01
+              3         3        3.084251E+01        5.553604E+00        8.838835E-01        1.000000E+00        3.084251E+01
+";
+
+#[test]
+fn test_mystran_buckling_eigenvalues() {
+  let file = parse_str(MYSTRAN_BUCKLING_FIXTURE);
+  assert_eq!(file.flavour.solver, Some(Solver::Mystran));
+  assert_eq!(file.flavour.soltype, None);
+  assert!(file.potential_headers.is_empty());
+  let types = file.block_types().collect::<Vec<_>>();
+  assert_eq!(types, [BlockType::BucklingEigenvalues]);
+  assert_eq!(
+    file.buckling_factors(),
+    vec![(1, 1.233701), (2, 11.10331), (3, 30.84251)]
+  );
+  assert!(file.natural_frequencies().is_empty());
+}
+
+/// Modal effective masses, first with the cumulative percentages, then
+/// without them.
+const MODAL_MASS_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+
+                                          M O D A L   E F F E C T I V E   M A S S
+   MODE        T1           T2           T3           R1           R2           R3         T1 CUM       T2 CUM       T3 CUM       R1 CUM       R2 CUM       R3 CUM
+    NO.                                                                                      (%)          (%)          (%)          (%)          (%)          (%)
+       1   4.000000E+00 0.000000E+00 1.000000E-01 0.000000E+00 2.500000E+01 0.000000E+00 8.000000E+01 0.000000E+00 1.000000E+01 0.000000E+00 5.000000E+01 0.000000E+00
+       2   5.000000E-01 3.000000E+00 0.000000E+00 1.200000E+01 0.000000E+00 2.000000E+00 9.000000E+01 6.000000E+01 1.000000E+01 4.000000E+01 5.000000E+01 2.000000E+01
+   TOTAL   4.500000E+00 3.000000E+00 1.000000E-01 1.200000E+01 2.500000E+01 2.000000E+00
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 2
+
+                                          M O D A L   E F F E C T I V E   M A S S
+   MODE        T1           T2           T3           R1           R2           R3
+    NO.
+       1   4.000000E+00 0.000000E+00 1.000000E-01 0.000000E+00 2.500000E+01 0.000000E+00
+       2   5.000000E-01 3.000000E+00 0.000000E+00 1.200000E+01 0.000000E+00 2.000000E+00
+";
+
+#[test]
+fn test_modal_effective_mass() {
+  let file = parse_str(MODAL_MASS_FIXTURE);
+  assert!(file.potential_headers.is_empty());
+  let bt = Some(BlockType::ModalEffectiveMass);
+  let with_cum = file.block_search(bt, Some(1), false).next().unwrap();
+  let without = file.block_search(bt, Some(2), false).next().unwrap();
+  // the totals aren't a mode
+  assert_eq!(with_cum.row_indexes.len(), 2);
+  assert_eq!(without.row_indexes.len(), 2);
+  let get = |block: &FinalBlock, mode_number, col| {
+    return f64::from(block.get(ModeRef { mode_number }, col).unwrap());
+  };
+  assert_eq!(get(with_cum, 2, ModalMassField::Ty), 3.0);
+  assert_eq!(get(with_cum, 2, ModalMassField::CumTy), 60.0);
+  assert_eq!(get(with_cum, 1, ModalMassField::CumRy), 50.0);
+  // the six mandatory fields decode alike either way
+  let mandatory = [
+    ModalMassField::Tx, ModalMassField::Ty, ModalMassField::Tz,
+    ModalMassField::Rx, ModalMassField::Ry, ModalMassField::Rz
+  ];
+  for mode_number in [1, 2] {
+    for col in mandatory {
+      assert_eq!(
+        get(with_cum, mode_number, col),
+        get(without, mode_number, col)
+      );
+    }
+    // the cumulative ones weren't printed, so they're not columns
+    for col in ModalMassField::all().iter().skip(mandatory.len()) {
+      assert!(without.get(ModeRef { mode_number }, *col).is_none());
+    }
+  }
+  assert_eq!(without.data.as_ref().unwrap().ncols(), mandatory.len());
+  assert!(file.check_invariants().is_empty());
+  // so a diff checking for NaNs finds none
+  let criteria = Criteria {
+    difference: None,
+    ratio: None,
+    nan: true,
+    inf: true,
+    sig: false
+  };
+  let differ = DataDiffer::new(criteria, DisjunctionBehaviour::Flag);
+  assert_eq!(differ.compare(without, without).unwrap().count(), 0);
+}
+
+/// The output of the grid point weight generator for a point mass of 2 at
+/// (1, 2, 3), about the origin.
+const GPWG_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    WEIGHT TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     1
+0
+                           O U T P U T   F R O M   G R I D   P O I N T   W E I G H T   G E N E R A T O R
+0                                                     REFERENCE POINT =        0
+                                                                M O
+                      *  2.000000E+00  0.000000E+00  0.000000E+00  0.000000E+00  6.000000E+00 -4.000000E+00 *
+                      *  0.000000E+00  2.000000E+00  0.000000E+00 -6.000000E+00  0.000000E+00  2.000000E+00 *
+                      *  0.000000E+00  0.000000E+00  2.000000E+00  4.000000E+00 -2.000000E+00  0.000000E+00 *
+                      *  0.000000E+00 -6.000000E+00  4.000000E+00  2.600000E+01 -4.000000E+00 -6.000000E+00 *
+                      *  6.000000E+00  0.000000E+00 -2.000000E+00 -4.000000E+00  2.000000E+01 -1.200000E+01 *
+                      * -4.000000E+00  2.000000E+00  0.000000E+00 -6.000000E+00 -1.200000E+01  1.000000E+01 *
+                                                                 S
+                      *  1.000000E+00  0.000000E+00  0.000000E+00 *
+                      *  0.000000E+00  1.000000E+00  0.000000E+00 *
+                      *  0.000000E+00  0.000000E+00  1.000000E+00 *
+                               DIRECTION
+                          MASS AXIS SYSTEM (S)     MASS              X-C.G.        Y-C.G.        Z-C.G.
+                                  X           2.000000E+00      0.000000E+00  2.000000E+00  3.000000E+00
+                                  Y           2.000000E+00      1.000000E+00  0.000000E+00  3.000000E+00
+                                  Z           2.000000E+00      1.000000E+00  2.000000E+00  0.000000E+00
+                                                                I(S)
+                      *  2.600000E+01 -4.000000E+00 -6.000000E+00 *
+                      * -4.000000E+00  2.000000E+01 -1.200000E+01 *
+                      * -6.000000E+00 -1.200000E+01  1.000000E+01 *
+                                                                I(Q)
+                      *  3.000000E+01  0.000000E+00  0.000000E+00 *
+                      *  0.000000E+00  2.000000E+01  0.000000E+00 *
+                      *  0.000000E+00  0.000000E+00  6.000000E+00 *
+                                                                 Q
+                      *  1.000000E+00  0.000000E+00  0.000000E+00 *
+                      *  0.000000E+00  1.000000E+00  0.000000E+00 *
+                      *  0.000000E+00  0.000000E+00  1.000000E+00 *
+";
+
+#[test]
+fn test_gpwg() {
+  let file = parse_str(GPWG_FIXTURE);
+  assert!(file.potential_headers.is_empty());
+  let bt = Some(BlockType::GridPointWeight);
+  let block = file.block_search(bt, None, false).next().unwrap();
+  let get = |quantity| {
+    return f64::from(block.get(quantity, GpwgField::Value).unwrap());
+  };
+  assert_eq!(block.row_indexes.len(), GpwgRef::all().len());
+  assert_eq!(get(GpwgRef::Mass), 2.0);
+  assert_eq!(get(GpwgRef::CgX), 1.0);
+  assert_eq!(get(GpwgRef::CgY), 2.0);
+  assert_eq!(get(GpwgRef::CgZ), 3.0);
+  // the inertia is from the rigid body mass matrix, not the principal one
+  assert_eq!(get(GpwgRef::Ixx), 26.0);
+  assert_eq!(get(GpwgRef::Iyy), 20.0);
+  assert_eq!(get(GpwgRef::Izz), 10.0);
+  // with the products of inertia signed as usual, not negated as printed
+  assert_eq!(get(GpwgRef::Ixy), 4.0);
+  assert_eq!(get(GpwgRef::Iyz), 12.0);
+  assert_eq!(get(GpwgRef::Ixz), 6.0);
+}
+
+/// A displacement vector, then the summaries of maximums per DOF after it.
+/// The displacements' summary lists the grid points where the maximums occur,
+/// and the SPC forces' one doesn't.
+const MAX_SUMMARY_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    MAXIMA TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+           101      G      1.000000E-03   0.0           -2.500000E-02   0.0            4.000000E-04   0.0
+           102      G     -3.000000E-03   1.000000E-03   0.0            2.000000E-04   0.0            0.0
+1    MAXIMA TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+0                                                  MAXIMUM  DISPLACEMENTS
+  SUBCASE/
+  DAREA ID        T1             T2             T3             R1             R2             R3
+0        1   3.0000000E-03  1.0000000E-03  2.5000000E-02  2.0000000E-04  4.0000000E-04  0.0
+                      102            102            101            102            101              0
+0                                                  MAXIMUM  APPLIED LOADS
+  SUBCASE/
+  DAREA ID        T1             T2             T3             R1             R2             R3
+0        1   0.0            0.0            5.0000000E+02  0.0            0.0            0.0
+                        0              0            101              0              0              0
+0                                                  MAXIMUM  SPCFORCES
+  SUBCASE/
+  DAREA ID        T1             T2             T3             R1             R2             R3
+0        1   1.2000000E+01  0.0            5.0000000E+02  0.0            7.5000000E+01  0.0
+";
+
+#[test]
+fn test_max_summaries() {
+  let file = parse_str(MAX_SUMMARY_FIXTURE);
+  assert!(file.potential_headers.is_empty());
+  assert_eq!(
+    file.block_types().collect::<Vec<_>>(),
+    vec![
+      BlockType::Displacements,
+      BlockType::MaxDisplacements,
+      BlockType::MaxAppliedLoads,
+      BlockType::MaxSpcForces
+    ]
+  );
+  // the vector before the summaries is left alone
+  let bt = Some(BlockType::Displacements);
+  let disp = file.block_search(bt, Some(1), false).next().unwrap();
+  assert_eq!(disp.row_indexes.len(), 2);
+  let get = |bt, col| {
+    let block = file.block_search(Some(bt), Some(1), false).next().unwrap();
+    assert_eq!(block.row_indexes.len(), 1);
+    return f64::from(block.get(CaseRef(1), col).unwrap());
+  };
+  // the maximums, and where they occur
+  let maxd = BlockType::MaxDisplacements;
+  assert_eq!(get(maxd, MaxSummaryField::Tz), 2.5e-2);
+  assert_eq!(get(maxd, MaxSummaryField::GridTz), 101.0);
+  assert_eq!(get(maxd, MaxSummaryField::Tx), 3e-3);
+  assert_eq!(get(maxd, MaxSummaryField::GridTx), 102.0);
+  assert_eq!(get(maxd, MaxSummaryField::GridRz), 0.0);
+  let maxl = BlockType::MaxAppliedLoads;
+  assert_eq!(get(maxl, MaxSummaryField::Tz), 500.0);
+  assert_eq!(get(maxl, MaxSummaryField::GridTz), 101.0);
+  // without the line of grid points, their columns are left out
+  let maxs = BlockType::MaxSpcForces;
+  assert_eq!(get(maxs, MaxSummaryField::Ry), 75.0);
+  let spc = file.block_search(Some(maxs), Some(1), false).next().unwrap();
+  assert_eq!(spc.col_indexes.len(), SIXDOF);
+  assert!(spc.col_indexes.keys().all(|c| {
+    return matches!(c, NasIndex::MaxSummaryField(f) if !f.is_grid());
+  }));
+  assert!(file.check_invariants().is_empty());
+  // the columns are split by DOF, with the same dimensions as the vectors
+  for field in MaxSummaryField::all() {
+    assert_eq!(MaxSummaryField::of_dof(field.dof(), field.is_grid()), *field);
+  }
+  let dim = |bt, field: MaxSummaryField| NasIndex::from(field).dimension(bt);
+  assert_eq!(dim(maxd, MaxSummaryField::Tx), PhysicalDimension::Length);
+  assert_eq!(dim(maxd, MaxSummaryField::Rx), PhysicalDimension::Angle);
+  assert_eq!(dim(maxs, MaxSummaryField::Rx), PhysicalDimension::Moment);
+  assert_eq!(
+    dim(maxl, MaxSummaryField::GridTx),
+    PhysicalDimension::Dimensionless
+  );
+}
+
+/// Eigenvectors for two modes, the first one going over a page break.
+const EIGENVECTORS_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+      EIGENVALUE =  3.947842E+04
+          CYCLES =  3.162278E+01         R E A L   E I G E N V E C T O R   N O .          1
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1.000000E+00   0.0            0.0            0.0            0.0            0.0
+             2      G      5.000000E-01   0.0            0.0            0.0            0.0            0.0
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+      EIGENVALUE =  3.947842E+04
+          CYCLES =  3.162278E+01         R E A L   E I G E N V E C T O R   N O .          1
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             3      G      2.500000E-01   0.0            0.0            0.0            0.0            0.0
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     3
+0                                                                                SUBCASE 1
+      EIGENVALUE =  1.579137E+05
+          CYCLES =  6.324555E+01         R E A L   E I G E N V E C T O R   N O .          2
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      0.0            1.000000E+00   0.0            0.0            0.0            0.0
+             2      G      0.0           -1.000000E+00   0.0            0.0            0.0            0.0
+             3      G      0.0            1.000000E+00   0.0            0.0            0.0            0.0
+";
+
+#[test]
+fn test_eigenvectors() {
+  let mut file = parse_str(EIGENVECTORS_FIXTURE);
+  assert!(file.potential_headers.is_empty());
+  assert_eq!(
+    file.block_types().collect::<Vec<_>>(),
+    vec![BlockType::Eigenvectors]
+  );
+  file.merge_blocks(true);
+  let bt = Some(BlockType::Eigenvectors);
+  let mut modes = file.block_search(bt, Some(1), false).collect::<Vec<_>>();
+  modes.sort_by_key(|b| b.mode());
+  // the pages of mode 1 merge, but the modes don't merge with each other
+  assert_eq!(modes.len(), 2);
+  assert_eq!(modes[0].mode(), Some(1));
+  assert_eq!(modes[1].mode(), Some(2));
+  assert_eq!(modes[0].row_indexes.len(), 3);
+  assert_eq!(
+    modes[0].can_merge(modes[1]),
+    Err(MergeIncompatible::ModeMismatch)
+  );
+  let get = |b: &FinalBlock, gid, dof| {
+    return f64::from(b.get(GridPointRef { gid }, dof).unwrap());
+  };
+  assert_eq!(get(modes[0], 3, DOF_TX), 0.25);
+  assert_eq!(get(modes[1], 2, DOF_TY), -1.0);
+}
+
+/// Makes an output with a six-DOF grid point vector under some header.
+fn six_dof_grid_fixture(header: &str) -> String {
+  return format!("
+                      Welcome to Simcenter Nastran
+1    VECTORS TEST                                        SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                             {}
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1.000000E+00  -2.000000E+00   3.000000E+00   0.0            0.0            1.000000E-03
+             2      G      5.000000E-01   0.0           -7.500000E-01   2.000000E-03   0.0            0.0
+            17      G      0.0            0.0            0.0            0.0           -4.000000E-03   0.0
+", header);
+}
+
+#[test]
+fn test_six_dof_grid_vectors() {
+  let headers = [
+    (BlockType::Displacements, "D I S P L A C E M E N T   V E C T O R"),
+    (BlockType::Velocities, "V E L O C I T Y   V E C T O R"),
+    (BlockType::Accelerations, "A C C E L E R A T I O N   V E C T O R")
+  ];
+  let blocks = headers.map(|(bt, header)| {
+    let file = parse_str(&six_dof_grid_fixture(header));
+    assert!(file.potential_headers.is_empty());
+    assert_eq!(file.block_types().collect::<Vec<_>>(), vec![bt]);
+    return file.all_blocks(false).next().unwrap().clone();
+  });
+  for block in blocks.iter() {
+    assert_eq!(block.row_indexes, blocks[0].row_indexes);
+    assert_eq!(block.col_indexes, blocks[0].col_indexes);
+    assert_eq!(block.data, blocks[0].data);
+  }
+  let rows = blocks[0].row_indexes.keys().copied().collect::<Vec<_>>();
+  let gids = [1, 2, 17].map(|gid| NasIndex::GridPointRef(gid.into()));
+  assert_eq!(rows, gids);
+  let get = |gid, dof| {
+    return f64::from(blocks[1].get(GridPointRef { gid }, dof).unwrap());
+  };
+  assert_eq!(get(1, DOF_TY), -2.0);
+  assert_eq!(get(2, DOF_TZ), -0.75);
+  assert_eq!(get(17, DOF_RY), -4e-3);
+}
+
+/// A transient step with displacements, velocities and accelerations.
+const TRANSIENT_F06: &str = include_str!(
+  "../../examples/scnastran/beam_transient_sol109.f06"
+);
+
+#[test]
+fn test_transient_vectors() {
+  let file = parse_str(TRANSIENT_F06);
+  assert!(file.potential_headers.is_empty());
+  let kinds = [
+    BlockType::Displacements,
+    BlockType::Velocities,
+    BlockType::Accelerations
+  ];
+  assert_eq!(file.block_types().collect::<Vec<_>>(), kinds.to_vec());
+  let tz = kinds.map(|bt| {
+    let blocks = file.block_search(Some(bt), Some(1), false)
+      .collect::<Vec<_>>();
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].row_indexes.len(), 2);
+    return f64::from(blocks[0].get(GridPointRef { gid: 2 }, DOF_TZ).unwrap());
+  });
+  assert_eq!(tz, [2e-3, 1e-1, 5.0]);
+}
+
+/// Displacements at two time steps, the first one split across two pages.
+const TRANSIENT_STEPS_F06: &str = include_str!(
+  "../../examples/scnastran/beam_transient_steps_sol109.f06"
+);
+
+#[test]
+fn test_transient_steps() {
+  let mut file = parse_str(TRANSIENT_STEPS_F06);
+  assert_eq!(file.all_blocks(false).count(), 3);
+  let blocks = file.all_blocks(false).collect::<Vec<_>>();
+  assert_eq!(blocks[0].step, Some(Step::Time(1e-2)));
+  assert_eq!(blocks[1].step, Some(Step::Time(1e-2)));
+  assert_eq!(blocks[2].step, Some(Step::Time(2e-2)));
+  assert_eq!(blocks[0].can_merge(blocks[1]), Ok(()));
+  assert_eq!(
+    blocks[1].can_merge(blocks[2]),
+    Err(MergeIncompatible::StepMismatch)
+  );
+  file.merge_blocks(false);
+  let mut steps = file.all_blocks(false)
+    .map(|b| (b.step, b.row_indexes.len()))
+    .collect::<Vec<_>>();
+  steps.sort_by(|a, b| a.partial_cmp(b).unwrap());
+  assert_eq!(
+    steps,
+    [(Some(Step::Time(1e-2)), 2), (Some(Step::Time(2e-2)), 2)]
+  );
+  let late = file.all_blocks(false)
+    .find(|b| b.step == Some(Step::Time(2e-2)))
+    .unwrap();
+  let tz = late.get(GridPointRef { gid: 2 }, DOF_TZ).map(f64::from);
+  assert_eq!(tz, Some(6e-3));
+  // the step line and how it's shown
+  let step = Step::from_name("FREQUENCY", 20.0);
+  assert_eq!(step, Some(Step::Frequency(20.0)));
+  assert_eq!(step.unwrap().to_string(), "FREQUENCY = 2E1");
+  assert_eq!(Step::from_name("CYCLES", 20.0), None);
+  // a static subcase after it has no step
+  let more = "0                    SUBCASE 2\n";
+  let rest = TRANSIENT_STEPS_F06.lines()
+    .skip_while(|l| !l.contains("PAGE     3"))
+    .skip(3)
+    .collect::<Vec<_>>()
+    .join("\n");
+  let text = format!("{}{}{}", TRANSIENT_STEPS_F06, more, rest);
+  let file = parse_str(&text);
+  let static_ = file.block_search(None, Some(2), false).next().unwrap();
+  assert_eq!(static_.step, None);
+}
+
+#[test]
+fn test_diff_transient_steps() {
+  // one value off at the second step
+  let load = |text: &str| {
+    let mut file = parse_str(text);
+    file.merge_blocks(false);
+    return file;
+  };
+  let first = load(TRANSIENT_STEPS_F06);
+  let second = load(&TRANSIENT_STEPS_F06.replace(
+    "6.000000E-03",
+    "6.100000E-03"
+  ));
+  let mut settings = DiffSettings {
+    criteria: Criteria {
+      difference: Some(1e-5),
+      ratio: None,
+      nan: false,
+      inf: false,
+      sig: false
+    },
+    dxn_behaviour: Some(DisjunctionBehaviour::Skip),
+    max_flags: None,
+    auto_tol: false,
+    pair_by: PairBy::Subcase
+  };
+  let block_ref = BlockRef { subcase: 1, block_type: BlockType::Displacements };
+  let at = |t| SteppedBlockRef { block_ref, step: Some(Step::Time(t)) };
+  let flagged = |diff: &F06Diff| {
+    return diff.compared.iter()
+      .map(|(sbr, flags)| (*sbr, flags.len()))
+      .collect::<Vec<_>>();
+  };
+  // each step is compared with its counterpart
+  let diff = F06Diff::compare(&settings, &first, &second);
+  assert!(diff.not_compared.is_empty());
+  assert_eq!(flagged(&diff), [(at(1e-2), 0), (at(2e-2), 1)]);
+  // and the same when pairing by content, across subcases
+  let renumbered = load(&TRANSIENT_STEPS_F06.replace("SUBCASE 1", "SUBCASE 7"));
+  settings.pair_by = PairBy::Content;
+  let diff = F06Diff::compare(&settings, &first, &renumbered);
+  assert!(diff.not_compared.is_empty());
+  assert_eq!(flagged(&diff), [(at(1e-2), 0), (at(2e-2), 0)]);
+  let pairs = diff.pairing.unwrap().pairs;
+  assert_eq!(pairs.len(), 1);
+  assert_eq!((pairs[0].subcase_a, pairs[0].subcase_b), (1, 7));
+  // a step only one of them has has no counterpart
+  let cut = TRANSIENT_STEPS_F06.lines()
+    .take_while(|l| !l.contains("PAGE     3"))
+    .collect::<Vec<_>>()
+    .join("\n");
+  settings.pair_by = PairBy::Subcase;
+  let diff = F06Diff::compare(&settings, &first, &load(&cut));
+  assert_eq!(flagged(&diff), [(at(1e-2), 0)]);
+  assert!(matches!(
+    diff.not_compared.get(&at(2e-2)),
+    Some(NonCompareReason::NoCounterpart(_))
+  ));
+}
+
+#[test]
+fn test_output_axis_gaps() {
+  // 0.3 is missing, and 0.5 to 0.7 was refined to half the step
+  let times = [0.0, 0.1, 0.2, 0.4, 0.5, 0.55, 0.6, 0.65, 0.7, 0.8];
+  let axis = OutputAxis::new(1, times.iter().rev().map(|t| Step::Time(*t)));
+  assert_eq!(axis.len(), 10);
+  assert_eq!(axis.span(), Some((0.0, 0.8)));
+  assert_eq!(axis.position(Step::Time(0.4)), Some(3));
+  assert_eq!(axis.position(Step::Time(0.3)), None);
+  assert_eq!(axis.position(Step::Frequency(0.4)), None);
+  let median = axis.median_interval().unwrap();
+  assert!((median - 0.1).abs() < 1e-9);
+  // against the median, only the missing step is a gap
+  let gaps = axis.gaps(None);
+  assert_eq!(gaps.len(), 1);
+  assert_eq!(gaps[0].after_step, Step::Time(0.2));
+  assert_eq!(gaps[0].before_step, Step::Time(0.4));
+  assert!((gaps[0].ratio - 2.0).abs() < 1e-9);
+  // against the refined step, everything that wasn't refined is
+  let gaps = axis.gaps(Some(0.05));
+  let ratios = gaps.iter().map(|g| g.ratio.round()).collect::<Vec<_>>();
+  assert_eq!(ratios, [2.0, 2.0, 4.0, 2.0, 2.0]);
+  // and a larger factor lets the missing step through
+  let lax = OutputAxis { gap_factor: 2.5, ..axis.clone() };
+  assert!(lax.gaps(None).is_empty());
+  assert!(OutputAxis::new(1, [Step::Time(1.0)]).gaps(None).is_empty());
+  // the axes of a parsed file, and the blocks' positions along them
+  let mut file = parse_str(TRANSIENT_STEPS_F06);
+  file.merge_blocks(false);
+  let axes = file.output_axes();
+  assert_eq!(axes.keys().copied().collect::<Vec<_>>(), [1]);
+  assert_eq!(axes[&1].steps, [Step::Time(1e-2), Step::Time(2e-2)]);
+  let report = FileReport::from(&file);
+  let mut positions = report.blocks.iter()
+    .map(|b| b.axis_position)
+    .collect::<Vec<_>>();
+  positions.sort();
+  assert_eq!(positions, [Some(0), Some(1)]);
+  assert_eq!(report.transient.len(), 1);
+  assert_eq!(report.transient[0].count, 2);
+  assert!(report.transient[0].gaps.is_empty());
+}
+
+#[test]
+fn test_permute_columns() {
+  let mut fdm = FinalDMat::from(DMatrix::from_row_slice(2, 3, &[
+    1.0, 2.0, 3.0,
+    4.0, 5.0, 6.0
+  ]));
+  fdm.permute_columns(&[2, 0, 1]);
+  assert_eq!(fdm, FinalDMat::from(DMatrix::from_row_slice(2, 3, &[
+    3.0, 1.0, 2.0,
+    6.0, 4.0, 5.0
+  ])));
+  let mut file = parse_str(QUAD_PAGE_BREAK_FIXTURE);
+  file.merge_blocks(true);
+  let block = file.all_blocks(false).next().unwrap();
+  let mut permuted = block.clone();
+  let width = block.col_indexes.len();
+  let order = (0..width).rev().collect::<Vec<_>>();
+  permuted.permute_columns(&order);
+  for (col, pos) in block.col_indexes.iter() {
+    assert_eq!(permuted.col_indexes[col], width - 1 - pos);
+    for row in block.row_indexes.keys() {
+      assert_eq!(permuted.get(*row, *col), block.get(*row, *col));
+    }
+  }
+  // and back to how it was
+  let moved = permuted.reconcile_columns(&block.col_indexes);
+  assert_eq!(moved.len(), width);
+  assert_eq!(&permuted, block);
+}
+
+/// The in-house solver's motions over two pages, the second printed in the
+/// header variant with the translations first.
+const INHOUSE_VARIANTS_F06: &str = "
+1    ACME STRUCTURES IN-HOUSE SOLVER  (BUILD 7.2)                                          PAGE     1
+
+     STATIC ANALYSIS OF BRACKET ASSEMBLY
+
+                     N O D A L   M O T I O N S   ( R O T A T I O N S   F I R S T )
+
+      NODE  TYPE            RX            RY            RZ            TX            TY            TZ
+       101     G           0.0           0.0  1.250000E-04  1.000000E-03  2.000000E-03 -3.000000E-03
+       102     G  2.500000E-05 -1.000000E-05  1.300000E-04  1.100000E-03  2.200000E-03 -3.100000E-03
+1    ACME STRUCTURES IN-HOUSE SOLVER  (BUILD 7.2)                                          PAGE     2
+
+     STATIC ANALYSIS OF BRACKET ASSEMBLY
+
+                  N O D A L   M O T I O N S   ( T R A N S L A T I O N S   F I R S T )
+
+      NODE  TYPE            TX            TY            TZ            RX            RY            RZ
+       103     G  1.200000E-03  2.400000E-03 -3.200000E-03  5.000000E-05 -2.000000E-05  1.350000E-04
+       104     G  1.300000E-03  2.600000E-03 -3.300000E-03  7.500000E-05 -3.000000E-05  1.400000E-04
+
+     END OF NODAL MOTIONS
+";
+
+/// The layouts of the in-house solver's motions, in both header variants.
+fn inhouse_variant_layouts() -> String {
+  let translations = INHOUSE_LAYOUTS
+    .replace(
+      "N O D A L   M O T I O N S",
+      "N O D A L   M O T I O N S   ( T R A N S L A T I O N S"
+    )
+    .replace("\"R", "\"_")
+    .replace("\"T", "\"R")
+    .replace("\"_", "\"T");
+  return format!("{}\n{}", INHOUSE_LAYOUTS, translations);
+}
+
+#[test]
+fn test_merge_reconciles_columns() {
+  // same columns, different positions
+  let layouts = inhouse_variant_layouts();
+  let mut variants = parse_with_layouts(INHOUSE_VARIANTS_F06, &layouts);
+  let bt = Some(BlockType::Displacements);
+  let motions = variants.block_search(bt, Some(1), false)
+    .cloned()
+    .collect::<Vec<_>>();
+  assert_eq!(motions.len(), 2);
+  assert_ne!(motions[0].col_indexes, motions[1].col_indexes);
+  assert_eq!(motions[0].can_merge(&motions[1]), Ok(()));
+  assert_eq!(variants.merge_blocks(true), 1);
+  let merged = variants.block_search(bt, Some(1), false).next().unwrap();
+  assert_eq!(merged.row_indexes.len(), 4);
+  let get = |gid, dof: Dof| {
+    return f64::from(merged.get(GridPointRef { gid }, dof).unwrap());
+  };
+  assert_eq!(get(102, DOF_RX), 2.5e-5);
+  assert_eq!(get(102, DOF_TX), 1.1e-3);
+  assert_eq!(get(103, DOF_RX), 5e-5);
+  assert_eq!(get(103, DOF_TX), 1.2e-3);
+  assert_eq!(get(104, DOF_RZ), 1.4e-4);
+  assert_eq!(get(104, DOF_TZ), -3.3e-3);
+  // the later page was merged into, so the first one was moved to match it
+  let reconciled = &merged.provenance.reconciliations;
+  assert_eq!(reconciled.len(), 1);
+  assert_eq!(reconciled[0].uid, motions[0].uid);
+  assert_eq!(reconciled[0].moved.len(), 6);
+  assert_eq!(reconciled[0].moved[&DOF_TX.into()], (3, 0));
+  assert_eq!(reconciled[0].moved[&DOF_RZ.into()], (2, 5));
+  assert_eq!(merged.provenance.merged, vec![motions[0].uid]);
+  // sorting alone can't line the columns up around one that's unindexed, in
+  // a different place on each page: without reconciling them, the values
+  // would be a column off
+  let with_spare = |block: &FinalBlock, at: usize| {
+    let mut block = block.clone();
+    let data = match block.data.take() {
+      Some(FinalDMat::Reals(m)) => m,
+      other => panic!("unexpected data {:?}", other)
+    };
+    block.data = Some(FinalDMat::from(data.insert_column(at, 0.0)));
+    for pos in block.col_indexes.values_mut().filter(|pos| **pos >= at) {
+      *pos += 1;
+    }
+    return block;
+  };
+  let spared = with_spare(&motions[1], 6).try_merge(with_spare(&motions[0], 0));
+  let spared = match spared {
+    Ok(MergeResult::Success { merged }) => merged,
+    other => panic!("unexpected merge {:?}", other)
+  };
+  let get = |gid, dof: Dof| {
+    return f64::from(spared.get(GridPointRef { gid }, dof).unwrap());
+  };
+  assert_eq!(get(101, DOF_RZ), 1.25e-4);
+  assert_eq!(get(101, DOF_TX), 1e-3);
+  assert_eq!(get(102, DOF_RX), 2.5e-5);
+  assert_eq!(get(103, DOF_TZ), -3.2e-3);
+  assert_eq!(get(104, DOF_RZ), 1.4e-4);
+  assert_eq!(spared.provenance.reconciliations[0].uid, motions[0].uid);
+  // the same layout needs no reconciliation
+  let file = parse_str(QUAD_PAGE_BREAK_FIXTURE);
+  let pages = file.block_search(Some(BlockType::QuadStresses), None, false)
+    .collect::<Vec<_>>();
+  assert_eq!(pages.len(), 2);
+  let expected = pages[0].clone().try_merge(pages[1].clone()).unwrap();
+  let expected = match expected {
+    MergeResult::Success { merged } => merged,
+    other => panic!("unexpected merge {:?}", other)
+  };
+  assert!(expected.provenance.reconciliations.is_empty());
+  let shear = NasIndex::from(PlateStressField::ShearXY);
+  // two columns in the same place can't be reconciled
+  let mut duplicated = pages[1].clone();
+  let normal_x = duplicated.col_indexes[&PlateStressField::NormalX.into()];
+  duplicated.col_indexes.insert(shear, normal_x);
+  assert_eq!(
+    pages[0].can_merge(&duplicated),
+    Err(MergeIncompatible::BadColumnPositions {
+      secondary: true,
+      duplicated: BTreeSet::from([normal_x]),
+      out_of_range: BTreeSet::new()
+    })
+  );
+  // nor can columns past the data, or data of different widths
+  let mut outside = pages[0].clone();
+  outside.col_indexes.insert(shear, 99);
+  assert!(matches!(
+    outside.can_merge(pages[1]),
+    Err(MergeIncompatible::BadColumnPositions { secondary: false, .. })
+  ));
+  let mut wider = pages[1].clone();
+  let data = match wider.data.take() {
+    Some(FinalDMat::Reals(m)) => m,
+    other => panic!("unexpected data {:?}", other)
+  };
+  wider.data = Some(FinalDMat::from(data.insert_column(0, 0.0)));
+  assert!(matches!(
+    pages[0].can_merge(&wider),
+    Err(MergeIncompatible::WidthMismatch { .. })
+  ));
+  // and different sets of columns are still a conflict
+  let mut fewer = pages[1].clone();
+  fewer.col_indexes.remove(&shear);
+  assert!(matches!(
+    pages[0].can_merge(&fewer),
+    Err(MergeIncompatible::ColumnConflict { .. })
+  ));
+}
+
+/// Makes at least one index of each `NasIndex` variant, with all their
+/// column values, and row indexes with IDs in them wherever they can be.
+fn sample_indexes() -> Vec<NasIndex> {
+  let element = ElementRef { eid: 7, etype: Some(ElementType::Quad4) };
+  let corner = ElementPoint::Corner(GridPointRef { gid: 3 });
+  let mut samples: Vec<NasIndex> = vec![
+    GridPointRef { gid: 2 }.into(),
+    element.into(),
+    PointInElement { element, point: corner }.into(),
+    PointInElement { element, point: ElementPoint::Centroid }.into(),
+    GridPointForceOrigin {
+      grid_point: GridPointRef { gid: 2 },
+      force_origin: ForceOrigin::Element { elem: element }
+    }.into(),
+    GridPointForceOrigin {
+      grid_point: GridPointRef { gid: 2 },
+      force_origin: ForceOrigin::SinglePointConstraint
+    }.into(),
+    ElementSidedPoint {
+      element,
+      point: ElementPoint::Midpoint(GridPointRef { gid: 4 }),
+      side: ElementSide::Top
+    }.into(),
+    ElementStation::at_fraction(element, 0.5).into(),
+    ElementPlyRef {
+      element,
+      layer: CompositeLayer::Bond(2),
+      side: ElementSide::Middle
+    }.into(),
+    ElementPlyRef {
+      element,
+      layer: CompositeLayer::Ply(3),
+      side: ElementSide::Top
+    }.into(),
+    GridSurfacePoint {
+      grid_point: GridPointRef { gid: 2 },
+      surface: 1,
+      element: Some(element),
+      side: ElementSide::Bottom
+    }.into(),
+    SeqRef(5).into(),
+    ModeRef { mode_number: 1 }.into(),
+    CaseRef(3).into()
+  ];
+  let columns = [
+    Dof::fixed_values(),
+    SingleForce::fixed_values(),
+    SingleStress::fixed_values(),
+    SingleStrain::fixed_values(),
+    SingleTemperature::fixed_values(),
+    BarForceField::fixed_values(),
+    BarStressField::fixed_values(),
+    BarStrainField::fixed_values(),
+    BeamForceField::fixed_values(),
+    BeamStressField::fixed_values(),
+    BeamStrainField::fixed_values(),
+    RodForceField::fixed_values(),
+    RodStressField::fixed_values(),
+    RodStrainField::fixed_values(),
+    PlateForceField::fixed_values(),
+    PlateStressField::fixed_values(),
+    PlateStrainField::fixed_values(),
+    ChexaStressField::fixed_values(),
+    ChexaStrainField::fixed_values(),
+    CpentaStressField::fixed_values(),
+    CpentaStrainField::fixed_values(),
+    CtetraStressField::fixed_values(),
+    CtetraStrainField::fixed_values(),
+    CshearForceField::fixed_values(),
+    CshearStressField::fixed_values(),
+    CgapForceField::fixed_values(),
+    BushStressField::fixed_values(),
+    BushStrainField::fixed_values(),
+    CompositeFailureField::fixed_values(),
+    CompositeStressField::fixed_values(),
+    GridStressField::fixed_values(),
+    StrainEnergyField::fixed_values(),
+    EigenvalueField::fixed_values(),
+    ComplexEigenvalueField::fixed_values(),
+    ModalMassField::fixed_values(),
+    GpwgRef::fixed_values(),
+    GpwgField::fixed_values(),
+    MaxSummaryField::fixed_values()
+  ];
+  samples.extend(columns.into_iter().flatten());
+  return samples;
+}
+
+/// Records what a visitor was fed, as text.
+#[derive(Default)]
+struct RecordingVisitor(Vec<String>);
+
+impl NasIndexVisitor for RecordingVisitor {
+  fn compound_start(&mut self, index_name: &'static str) {
+    self.0.push(format!("start {}", index_name));
+  }
+
+  fn compound_end(&mut self, index_name: &'static str) {
+    self.0.push(format!("end {}", index_name));
+  }
+
+  fn grid(&mut self, grid_point: GridPointRef) {
+    self.0.push(format!("grid {}", grid_point.gid));
+  }
+
+  fn element(&mut self, element: ElementRef) {
+    self.0.push(format!("element {}", element.eid));
+  }
+
+  fn dof(&mut self, dof: Dof) {
+    self.0.push(format!("dof {}", dof));
+  }
+
+  fn point(&mut self, point: ElementPoint) {
+    self.0.push(format!("point {}", point));
+  }
+
+  fn force_origin(&mut self, force_origin: ForceOrigin) {
+    self.0.push(format!("origin {}", force_origin));
+  }
+
+  fn side(&mut self, side: ElementSide) {
+    self.0.push(format!("side {}", side));
+  }
+
+  fn station(&mut self, fraction: f64) {
+    self.0.push(format!("station {}", fraction));
+  }
+
+  fn layer(&mut self, layer: CompositeLayer) {
+    self.0.push(format!("layer {}", layer));
+  }
+
+  fn seq(&mut self, seq: SeqRef) {
+    self.0.push(format!("seq {}", seq.0));
+  }
+
+  fn mode(&mut self, mode: ModeRef) {
+    self.0.push(format!("mode {}", mode.mode_number));
+  }
+
+  fn case(&mut self, case: CaseRef) {
+    self.0.push(format!("case {}", case.0));
+  }
+
+  fn field(&mut self, field: NasIndex) {
+    self.0.push(format!("field {}", field));
+  }
+}
+
+#[test]
+fn test_nasindex_visitor() {
+  let samples = sample_indexes();
+  let covered = samples.iter()
+    .map(NasIndex::variant)
+    .collect::<BTreeSet<_>>();
+  let all = NasIndex::VARIANTS.iter().copied().collect::<BTreeSet<_>>();
+  assert_eq!(covered, all, "the samples miss some variants");
+  for index in samples.iter() {
+    let mut rec = RecordingVisitor::default();
+    index.visit(&mut rec);
+    let fed = rec.0;
+    assert!(!fed.is_empty(), "{} fed nothing", index);
+    // compound indexes are wrapped, simple ones are a single part
+    let start = format!("start {}", index.index_name());
+    let end = format!("end {}", index.index_name());
+    if fed[0] == start {
+      assert_eq!(fed.last(), Some(&end));
+      assert!(fed.len() > 3, "{} has too few parts", index);
+    } else {
+      assert_eq!(fed.len(), 1, "{} isn't wrapped", index);
+    }
+    // mapping IDs only changes the IDs
+    let same = index.map_ids(|gid| gid, |eid| eid);
+    assert_eq!(same, *index);
+    let mapped = index.map_ids(|gid| gid + 100, |eid| eid * 1000);
+    let mut rec = RecordingVisitor::default();
+    mapped.visit(&mut rec);
+    let moved = fed.iter().zip(rec.0.iter()).filter(|(a, b)| a != b);
+    for (before, after) in moved {
+      let (kind, id) = before.split_once(' ').unwrap();
+      match kind {
+        "grid" => {
+          let gid = id.parse::<usize>().unwrap();
+          assert_eq!(after, &format!("grid {}", gid + 100));
+        },
+        "element" => {
+          let eid = id.parse::<usize>().unwrap();
+          assert_eq!(after, &format!("element {}", eid * 1000));
+        },
+        "point" => assert!(after.starts_with("point")),
+        "origin" => assert!(after.starts_with("origin")),
+        _ => panic!("{} changed from {} to {}", index, before, after)
+      };
+    }
+    assert_eq!(index.index_name(), mapped.index_name());
+  }
+  // the IDs deep inside are mapped too
+  let gpfo = samples[4].map_ids(|gid| gid + 100, |eid| eid * 1000);
+  assert_eq!(gpfo, NasIndex::from(GridPointForceOrigin {
+    grid_point: GridPointRef { gid: 102 },
+    force_origin: ForceOrigin::Element {
+      elem: ElementRef { eid: 7000, etype: Some(ElementType::Quad4) }
+    }
+  }));
+  let corner = samples[2].map_ids(|gid| gid + 100, |eid| eid);
+  assert!(matches!(
+    corner,
+    NasIndex::PointInElement(PointInElement {
+      point: ElementPoint::Corner(GridPointRef { gid: 103 }),
+      ..
+    })
+  ));
+}
+
+#[test]
+fn test_tolerance_boundaries() {
+  let tol = |absolute, relative, ulps, rule| Tolerance {
+    absolute,
+    relative,
+    ulps,
+    rule: Some(rule)
+  };
+  let passes = |t: &Tolerance, a: f64, b: f64| t.check(a, b).is_none();
+  let tiny = 1e-300;
+  let denormal = f64::from_bits(1);
+  // ULP distances, across zero too
+  assert_eq!(ulp_distance(1.0, 1.0 + f64::EPSILON), 1);
+  assert_eq!(ulp_distance(0.0, -0.0), 0);
+  assert_eq!(ulp_distance(0.0, denormal), 1);
+  assert_eq!(ulp_distance(-denormal, denormal), 2);
+  assert_eq!(ulp_distance(-1.0, 1.0), 2 * ulp_distance(0.0, 1.0));
+  let max = ulp_distance(0.0, f64::MAX);
+  assert_eq!(ulp_distance(f64::MAX, -f64::MAX), 2 * max);
+  // equal values pass even with no criteria, different ones don't
+  let none = Tolerance::default();
+  assert!(passes(&none, 0.0, -0.0) && passes(&none, 2.5, 2.5));
+  assert!(!passes(&none, 2.5, 2.5000001));
+  // zero against a tiny value: relatively as far apart as can be
+  let rel = tol(None, Some(0.5), None, CombinationRule::Any);
+  assert!(!passes(&rel, 0.0, tiny));
+  let floor = tol(Some(1e-12), Some(1e-6), None, CombinationRule::Any);
+  assert!(passes(&floor, 0.0, tiny));
+  let floor_and_rel = Tolerance { rule: Some(CombinationRule::All), ..floor };
+  assert!(!passes(&floor_and_rel, 0.0, tiny));
+  match rel.check(0.0, tiny) {
+    Some(FlagReason::OutOfTolerance { rel_difference, .. }) => {
+      assert_eq!(rel_difference, 1.0);
+    },
+    other => panic!("unexpected {:?}", other)
+  };
+  // opposite signs: more than a relative difference of one apart
+  let rel_of = |r| tol(None, Some(r), None, CombinationRule::Any);
+  assert!(!passes(&rel_of(1.0), -1e-3, 1e-3));
+  assert!(passes(&rel_of(2.0), -1e-3, 1e-3));
+  assert!(passes(&floor, -1e-13, 1e-13));
+  let few_ulps = tol(None, None, Some(1000), CombinationRule::Any);
+  assert!(!passes(&few_ulps, -1e-13, 1e-13));
+  // denormals are a ULP apart, but relatively far
+  let ulps = tol(None, Some(1e-3), Some(4), CombinationRule::Any);
+  assert!(passes(&ulps, denormal, 3.0 * denormal));
+  assert!(passes(&ulps, -denormal, denormal));
+  let both = Tolerance { rule: Some(CombinationRule::All), ..ulps };
+  assert!(!passes(&both, denormal, 3.0 * denormal));
+  assert!(passes(&both, 1.0, 1.0 + 2.0 * f64::EPSILON));
+  // non-finite values are flagged whatever the tolerance
+  let (max, any) = (Some(f64::MAX), CombinationRule::Any);
+  let loose = tol(max, max, Some(u64::MAX), any);
+  assert_eq!(loose.check(f64::NAN, f64::NAN), Some(FlagReason::NaN));
+  assert_eq!(loose.check(f64::INFINITY, 1.0), Some(FlagReason::Infinity));
+  // tolerances tighter than the print precision (1e-6 here)
+  let (p, magnitude) = (1e-6, 1e3);
+  let tight = tol(None, Some(1e-8), None, CombinationRule::Any);
+  assert!(matches!(
+    tight.check_precision(p, magnitude),
+    Err(ToleranceTooTight::Relative { .. })
+  ));
+  let tight = tol(Some(1e-4), None, Some(100), CombinationRule::All);
+  assert!(matches!(
+    tight.check_precision(p, magnitude),
+    Err(ToleranceTooTight::Difference { .. })
+  ));
+  let tight = tol(Some(1e-2), None, Some(100), CombinationRule::All);
+  assert!(matches!(
+    tight.check_precision(p, magnitude),
+    Err(ToleranceTooTight::Ulps { max_ulps: 100, .. })
+  ));
+  // when any will do, one loose enough criterion is enough
+  let mixed = tol(Some(1e-4), Some(1e-5), None, CombinationRule::Any);
+  assert!(mixed.check_precision(p, magnitude).is_ok());
+  let all = Tolerance { rule: Some(CombinationRule::All), ..mixed };
+  assert!(all.check_precision(p, magnitude).is_err());
+  // and loosened to it
+  let auto = all.with_auto_tolerance(p, magnitude);
+  assert_eq!((auto.absolute, auto.relative), (Some(2e-3), Some(1e-5)));
+  assert!(auto.check_precision(p, magnitude).is_ok());
+  assert_eq!(none.with_auto_tolerance(p, magnitude).relative, Some(2e-6));
+}
+
+/// A criteria file with overrides for displacements.
+const CRITERIA_TOML: &str = r#"
+[default]
+relative = 1e-3
+ulps = 16
+
+[blocks.Displacements]
+relative = 1e-5
+rule = "all"
+
+[columns.Displacements.TX]
+relative = 1e-2
+rule = "any"
+"#;
+
+#[test]
+fn test_tolerance_model() {
+  let model = ToleranceModel::from_toml(CRITERIA_TOML).unwrap();
+  // the column over the block type over the default
+  let bt = BlockType::Displacements;
+  let tx = model.resolve(bt, DOF_TX.into());
+  assert_eq!(tx.relative, Some(1e-2));
+  assert_eq!((tx.ulps, tx.rule), (Some(16), Some(CombinationRule::Any)));
+  let ty = model.resolve(bt, DOF_TY.into());
+  assert_eq!(ty.relative, Some(1e-5));
+  assert_eq!(ty.rule, Some(CombinationRule::All));
+  let other = model.resolve(BlockType::SpcForces, DOF_TX.into());
+  assert_eq!(other, model.default);
+  // overrides must be for real columns, and tolerances make sense
+  let err = ToleranceModel::from_toml("[columns.Displacements.T9]\nulps = 1");
+  assert!(matches!(err, Err(ToleranceModelError::UnknownColumn { .. })));
+  let err = ToleranceModel::from_toml("[default]\nrelative = -1.0");
+  assert!(matches!(err, Err(ToleranceModelError::BadTolerance(_))));
+  let err = ToleranceModel::from_toml("[default]\nrelativ = 1.0");
+  assert!(matches!(err, Err(ToleranceModelError::Toml(_))));
+  // in a diff, every column gets its own tolerance
+  let loads = [[1.0, 1.0, 1.0]];
+  let first = parse_str(&displacement_subcases(&[(1, loads[0])]));
+  let second = parse_str(&displacement_subcases(&[(1, [1.001, 1.001, 1.0])]));
+  let mut settings = DiffSettings {
+    criteria: Criteria {
+      difference: None,
+      ratio: None,
+      nan: false,
+      inf: false,
+      sig: false
+    },
+    dxn_behaviour: Some(DisjunctionBehaviour::Skip),
+    max_flags: None,
+    auto_tol: false,
+    pair_by: PairBy::Subcase
+  };
+  let sbr = SteppedBlockRef::from(BlockRef { subcase: 1, block_type: bt });
+  let diff_with_model = |settings: &DiffSettings| {
+    return F06Diff::compare_with_tolerance(
+      settings,
+      Some(&model),
+      &first,
+      &second
+    );
+  };
+  // the ULP distance can't be under the six decimals printed
+  let diff = diff_with_model(&settings);
+  assert!(matches!(
+    diff.not_compared.get(&sbr),
+    Some(NonCompareReason::BelowPrintPrecision(ToleranceTooTight::Ulps { .. }))
+  ));
+  // loosened, only T2 is flagged
+  settings.auto_tol = true;
+  let diff = diff_with_model(&settings);
+  let flags = &diff.compared[&sbr];
+  assert_eq!(flags.len(), 4);
+  assert!(flags.iter().all(|f| f.values.col == DOF_TY.into()));
+  assert!(flags.iter().all(|f| matches!(
+    f.reason,
+    FlagReason::OutOfTolerance { .. }
+  )));
+}
+
+/// Displacements printed by an in-house solver, unknown to us.
+const INHOUSE_F06: &str = include_str!(
+  "../../examples/layouts/inhouse_motions.f06"
+);
+
+/// The layout of the in-house solver's displacements.
+const INHOUSE_LAYOUTS: &str = include_str!(
+  "../../examples/layouts/inhouse_motions.toml"
+);
+
+/// Parses some output from the in-house solver with some layouts.
+fn parse_with_layouts(f06: &str, layouts: &str) -> F06File {
+  let mut parser = OnePassParser::new();
+  parser.hint_flavour(Flavour {
+    solver: Some(Solver::Simcenter),
+    soltype: None
+  });
+  parser.custom_layouts(CustomLayouts::parse(layouts).unwrap());
+  parser.feed_bytes(f06.as_bytes()).unwrap();
+  return parser.finish();
+}
+
+/// Parses the in-house solver's output with some layouts.
+fn parse_inhouse(layouts: &str) -> F06File {
+  return parse_with_layouts(INHOUSE_F06, layouts);
+}
+
+#[test]
+fn test_custom_layouts() {
+  // with no layouts, the table is unknown
+  assert!(parse_str(INHOUSE_F06).blocks.is_empty());
+  let file = parse_inhouse(INHOUSE_LAYOUTS);
+  assert!(file.potential_headers.is_empty());
+  let bt = Some(BlockType::Displacements);
+  let block = file.block_search(bt, Some(1), false).next().unwrap();
+  let get = |gid, dof: Dof| {
+    let row = GridPointRef { gid };
+    return f64::from(block.get(row, dof).unwrap());
+  };
+  assert_eq!(get(101, DOF_RZ), 1.25e-4);
+  assert_eq!(get(102, DOF_RX), 2.5e-5);
+  assert_eq!(get(102, DOF_TZ), -3.1e-3);
+  assert_eq!(get(104, DOF_TY), 2.6e-3);
+  assert_eq!(block.source_line(GridPointRef { gid: 104 }.into()), Some(11));
+  // the misaligned line is skipped and counted
+  assert_eq!(block.row_indexes.len(), 3);
+  assert!(!block.row_indexes.contains_key(&GridPointRef { gid: 103 }.into()));
+  assert_eq!(
+    block.metadata.get(META_LAYOUT_MISMATCHES).map(String::as_str),
+    Some("1")
+  );
+  // a range that cuts off the signs doesn't fit any line
+  let shifted = INHOUSE_LAYOUTS.replace("[86, 100]", "[88, 100]");
+  let file = parse_inhouse(&shifted);
+  assert!(file.blocks.is_empty());
+}
+
+#[test]
+fn test_custom_layout_errors() {
+  let layout = |extra: &str, columns: &str| format!(
+    "[[layout]]\nheader = \"FOREIGN TABLE\"\n{}\n{}",
+    extra,
+    columns
+  );
+  let displacements = "block_type = \"Displacements\"\nid = [0, 10]";
+  let column = |name: &str, range: &str| format!(
+    "[[layout.column]]\nname = \"{}\"\nrange = {}\n",
+    name,
+    range
+  );
+  let ok = layout(displacements, &column("tx", "[10, 20]"));
+  let layouts = CustomLayouts::parse(&ok).unwrap();
+  assert!(layouts.find("THE FOREIGN TABLE").is_some());
+  assert!(layouts.header_part("   F O R E I G N   T A B L E").is_some());
+  let err = |extra: &str, columns: &str| {
+    return CustomLayouts::parse(&layout(extra, columns)).unwrap_err();
+  };
+  assert!(matches!(
+    err(displacements, &column("T1", "[10, 20]")),
+    LayoutError::UnknownColumn { .. }
+  ));
+  assert!(matches!(
+    err(displacements, &column("TX", "[20, 10]")),
+    LayoutError::BadRange { .. }
+  ));
+  assert!(matches!(
+    err(displacements, &column("TX", "[5, 20]")),
+    LayoutError::Overlap { .. }
+  ));
+  let twice = column("TX", "[10, 20]") + &column("TX", "[20, 30]");
+  assert!(matches!(
+    err(displacements, &twice),
+    LayoutError::DuplicateColumn { .. }
+  ));
+  assert!(matches!(
+    err(displacements, ""),
+    LayoutError::Toml(_)
+  ));
+  let stresses = "block_type = \"QuadStresses\"\nid = [0, 10]";
+  assert!(matches!(
+    err(stresses, &column("VON MISES", "[30, 40]")),
+    LayoutError::MissingField { field: "point", .. }
+  ));
+  let sided = format!("{}\nside = [10, 14]", displacements);
+  assert!(matches!(
+    err(&sided, &column("TX", "[30, 40]")),
+    LayoutError::UnexpectedField { field: "side", .. }
+  ));
+}
+
+/// Writes a Simcenter-like file with a load vector in each of ten subcases,
+/// and a warning after the fourth one.
+fn ten_block_f06() -> String {
+  let mut text = String::new();
+  for subcase in 1..=10 {
+    text.push_str(concat!(
+      "1    STREAM TEST                                                   ",
+      "        JANUARY  12, 2024  SIMCENTER NASTRAN  2/11/21   PAGE     1\n"
+    ));
+    text.push_str(&format!("0{:>110}\n", format!("SUBCASE {}", subcase)));
+    text.push_str(concat!(
+      "                                                     ",
+      "L O A D   V E C T O R\n \n",
+      "      POINT ID.   TYPE          T1             T2             T3   ",
+      "          R1             R2             R3\n"
+    ));
+    text.push_str(&format!(
+      "          {:>4}      G      {:.6E}   0.0            0.0            \
+      0.0            0.0            0.0\n",
+      subcase,
+      subcase as f64
+    ));
+    if subcase == 4 {
+      text.push_str(" *** USER WARNING MESSAGE 4321 (TEST)\n");
+    }
+  }
+  return text;
+}
+
+#[test]
+fn test_streaming_parse() {
+  let text = ten_block_f06();
+  let mut stream = OnePassParser::parse_streaming(text.as_bytes());
+  let mut streamed = Vec::new();
+  while let Some(item) = stream.next() {
+    streamed.push(item.unwrap());
+    // handed out blocks aren't kept
+    assert!(stream.file().blocks.is_empty());
+  }
+  assert_eq!(streamed.len(), 10);
+  for (i, sb) in streamed.iter().enumerate() {
+    assert_eq!(sb.block.block_type, BlockType::AppliedForces);
+    assert_eq!(sb.block.subcase, i + 1);
+    assert_eq!(sb.block.uid, Some(i));
+    let gp = GridPointRef { gid: i + 1 };
+    let tx = F06Number::Real((i + 1) as f64);
+    assert_eq!(sb.block.get(gp, DOF_TX), Some(tx));
+  }
+  // the warning comes with the block it was printed in
+  let with_warnings = streamed.iter()
+    .map(|sb| sb.warnings.len())
+    .collect::<Vec<_>>();
+  assert_eq!(with_warnings, [0, 0, 0, 1, 0, 0, 0, 0, 0, 0]);
+  assert_eq!(stream.file().warnings.len(), 1);
+  // putting the blocks back gets the file a regular parse does
+  let whole = stream.finish_with(streamed);
+  let parsed = parse_str(&text);
+  assert_same(whole.all_blocks(false), parsed.all_blocks(false));
+  assert_eq!(whole.warnings, parsed.warnings);
+  assert_eq!(whole.line_count, parsed.line_count);
+}
+
+#[test]
+fn test_parse_with_progress() {
+  let text = ten_block_f06();
+  let mut reports: Vec<ProgressInfo> = Vec::new();
+  let file = OnePassParser::new()
+    .progress_interval(5)
+    .parse_bufread_with_progress(text.as_bytes(), |p| reports.push(p))
+    .unwrap();
+  let parsed = parse_str(&text);
+  assert_same(file.all_blocks(false), parsed.all_blocks(false));
+  assert_eq!(file.warnings, parsed.warnings);
+  // every five lines, whenever a block finishes, and at the end
+  assert!(reports.iter().any(|p| p.lines_parsed == 5));
+  assert!(reports.windows(2).all(|w| w[0].lines_parsed <= w[1].lines_parsed));
+  let finished = reports.windows(2)
+    .filter(|w| w[1].blocks_completed > w[0].blocks_completed)
+    .count();
+  assert_eq!(finished, 10);
+  let last = reports.last().unwrap();
+  assert_eq!(last.blocks_completed, 10);
+  assert_eq!(last.lines_parsed, file.line_count.unwrap());
+  assert_eq!(last.bytes_read, Some(text.len() as u64));
+  // wrong files are still caught
+  let deck = "SOL 101\nCEND\nBEGIN BULK\nENDDATA\n";
+  let err = OnePassParser::new()
+    .parse_bufread_with_progress(deck.as_bytes(), |_| panic!())
+    .unwrap_err();
+  assert!(ParseError::wrong_file_in(&err).is_some());
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_async_parse() {
+  use tokio_stream::StreamExt;
+  let text = ten_block_f06();
+  let blocks = OnePassParser::parse_async(text.as_bytes())
+    .collect::<Result<Vec<_>, _>>()
+    .await
+    .unwrap();
+  let streamed = OnePassParser::parse_streaming(text.as_bytes())
+    .map(|item| item.unwrap().block)
+    .collect::<Vec<_>>();
+  assert_eq!(blocks.len(), 10);
+  assert_same(&blocks, &streamed);
+  let subcases = blocks.iter().map(|b| b.subcase).collect::<Vec<_>>();
+  assert_eq!(subcases, (1..=10).collect::<Vec<_>>());
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_async_parse_chunked() {
+  use tokio_stream::StreamExt;
+  // lines split across reads, CRLF breaks and no break at the very end
+  let text = ten_block_f06().replace('\n', "\r\n");
+  let text = text.trim_end();
+  let reader = tokio::io::BufReader::with_capacity(7, text.as_bytes());
+  let mut stream = OnePassParser::parse_async(reader);
+  let first = stream.next().await.unwrap().unwrap();
+  assert_eq!(first.subcase, 1);
+  assert!(stream.file().blocks.is_empty());
+  let mut rest = 0;
+  while let Some(item) = stream.next().await {
+    item.unwrap();
+    rest += 1;
+  }
+  assert_eq!(rest, 9);
+  let file = stream.finish();
+  assert_eq!(file.warnings.len(), 1);
+  assert_eq!(file.line_count, Some(text.lines().count()));
+  // bad bytes are an error, and the end of the stream
+  let mut bad = ten_block_f06().into_bytes();
+  bad.splice(0..0, [b'\xff', b'\n']);
+  let items = OnePassParser::parse_async(&bad[..])
+    .collect::<Vec<_>>()
+    .await;
+  assert_eq!(items.len(), 1);
+  assert!(matches!(items[0], Err(ParseError::Io(_))));
+  // so is the wrong kind of file, right away
+  let items = OnePassParser::parse_async(&b"\x89HDF\r\n\x1a\n\0\0"[..])
+    .collect::<Vec<_>>()
+    .await;
+  assert_eq!(items.len(), 1);
+  let wrong = &items[0];
+  assert!(matches!(wrong, Err(ParseError::WrongFile(WrongFile::Hdf5))));
+}
+
+/// The types downstream users rely on the most, whose API is snapshotted.
+const API_FACADE: &[&str] = &[
+  "F06File",
+  "FinalBlock",
+  "BlockType",
+  "NasIndex",
+  "OnePassParser",
+  "BlockStream",
+  "AsyncBlockStream",
+  "StreamedBlock",
+  "ParserResponse",
+  "ParseError"
+];
+
+#[test]
+fn test_api_snapshot() {
+  let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+  let items = api_surface("f06", &root.join("src")).unwrap();
+  let facade = |it: &ApiItem| match it.owner {
+    Some(_) => API_FACADE.contains(&it.subject().split("::").next().unwrap()),
+    None => API_FACADE.contains(&it.subject())
+      || it.module == "f06"
+      || it.module == "f06::prelude"
+  };
+  let mut lines = items.iter()
+    .filter(|it| facade(it))
+    .map(ToString::to_string)
+    .collect::<Vec<_>>();
+  // the variants generated by macros aren't in the sources as such
+  lines.extend(
+    BlockType::all().iter().map(|bt| format!("BlockType: {}", bt.short_name()))
+  );
+  lines.extend(
+    NasIndex::VARIANTS.iter().map(|v| format!("NasIndex: {}({})", v, v))
+  );
+  lines.sort();
+  lines.dedup();
+  if let Some(message) = check_api_snapshot(&root.join("api.txt"), &lines) {
+    panic!("{}", message);
+  }
+}
+
+#[test]
+fn test_api_scan() {
+  let source = concat!(
+    "/// Docs.\n",
+    "#[derive(Clone)]\n",
+    "pub struct Thing {\n",
+    "  /// A field.\n",
+    "  pub x: usize,\n",
+    "  y: usize\n",
+    "}\n",
+    "impl Thing {\n",
+    "  pub fn new(\n",
+    "    x: usize\n",
+    "  ) -> Self {\n",
+    "    let s = \"pub fn fake() {\";\n",
+    "    return Self { x, y: 0 };\n",
+    "  }\n",
+    "  fn private(&self) {}\n",
+    "}\n",
+    "struct Hidden;\n",
+    "impl Hidden {\n",
+    "  pub fn invisible() {}\n",
+    "}\n"
+  );
+  let dir = std::env::temp_dir()
+    .join(format!("f06-api-scan-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  std::fs::write(dir.join("lib.rs"), source).unwrap();
+  let lines = api_surface("thing", &dir).unwrap()
+    .iter()
+    .map(ToString::to_string)
+    .collect::<Vec<_>>();
+  std::fs::remove_dir_all(&dir).unwrap();
+  assert_eq!(lines, [
+    "Thing: pub fn new(x: usize) -> Self",
+    "Thing: pub x: usize",
+    "thing: #[derive(Clone)] pub struct Thing"
+  ]);
+  let diff = api_diff("# comment\nThing: pub x: usize\nThing: gone\n", &lines);
+  assert_eq!(diff, [
+    "- Thing: gone",
+    "+ Thing: pub fn new(x: usize) -> Self",
+    "+ thing: #[derive(Clone)] pub struct Thing"
+  ]);
+}
+
+/// Returns the Rust sources in a directory and the ones under it.
+fn rust_sources(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+  let mut sources = Vec::new();
+  let entries = match std::fs::read_dir(dir) {
+    Ok(entries) => entries,
+    Err(_) => return sources
+  };
+  for path in entries.flatten().map(|e| e.path()) {
+    if path.is_dir() {
+      sources.extend(rust_sources(&path));
+    } else if path.extension().is_some_and(|e| e == "rs") {
+      sources.push(path);
+    }
+  }
+  return sources;
+}
+
+#[test]
+fn test_msrv() {
+  // constructs in comments and literals don't count
+  let source = concat!(
+    "// x.as_chunks() would be nice\n",
+    "let s = \"Duration::from_mins(1)\";\n",
+    "let even = n.is_multiple_of(2);\n",
+    "let t = Duration::from_hours(1);\n"
+  );
+  let violations = newer_than_msrv(source);
+  assert_eq!(violations.len(), 1);
+  assert_eq!(violations[0].line, 4);
+  assert_eq!(violations[0].since, (1, 91));
+  // every member of the workspace declares the MSRV and sticks to it
+  let workspace = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+  let read_toml = |p: std::path::PathBuf| {
+    return std::fs::read_to_string(p).unwrap().parse::<toml::Table>().unwrap();
+  };
+  let manifest = read_toml(workspace.join("Cargo.toml"));
+  let members = manifest["workspace"]["members"].as_array().unwrap();
+  assert!(!members.is_empty());
+  for member in members.iter().filter_map(|m| m.as_str()) {
+    let dir = workspace.join(member);
+    let package = read_toml(dir.join("Cargo.toml"));
+    let declared = package["package"].get("rust-version")
+      .and_then(|v| v.as_str())
+      .and_then(parse_rust_version);
+    assert_eq!(declared, Some(MSRV), "{} doesn't declare the MSRV", member);
+    let sources = rust_sources(&dir.join("src")).into_iter()
+      .chain(rust_sources(&dir.join("tests")));
+    for path in sources {
+      let source = std::fs::read_to_string(&path).unwrap();
+      if let Some(v) = newer_than_msrv(&source).first() {
+        panic!("{}, {}!", path.display(), v);
+      }
+    }
+  }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_parse_files_parallel() {
+  let dir = std::env::temp_dir()
+    .join(format!("f06-batch-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  let paths = ["solset.f06", "stream.f06", "missing.f06"]
+    .map(|name| dir.join(name));
+  std::fs::write(&paths[0], SOLSET_FIXTURE).unwrap();
+  std::fs::write(&paths[1], ten_block_f06()).unwrap();
+  // in the same order as the paths, each on its own
+  let results = parse_files_parallel(&paths);
+  assert_eq!(results.len(), 3);
+  let solset = results[0].as_ref().unwrap();
+  assert_eq!(solset.filename.as_deref(), Some("solset.f06"));
+  assert_eq!(solset.blocks.len(), 2);
+  assert_eq!(results[1].as_ref().unwrap().blocks.len(), 10);
+  assert!(results[2].is_err());
+  // the ones that parsed are merged, the rest are skipped
+  let merged = parse_files_parallel_merge(&paths).unwrap();
+  assert_eq!(merged.filename, None);
+  assert_eq!(merged.blocks.len(), 12);
+  // the messages are the first file's, whose line numbers they go by; the
+  // others' are kept apart, under their file's name
+  assert_eq!(merged.warnings.len(), 0);
+  let stream = results[1].as_ref().unwrap();
+  assert_eq!(merged.merged_warnings.len(), 1);
+  assert_eq!(merged.merged_warnings.get("stream.f06"), Some(&stream.warnings));
+  assert_eq!(merged.all_blocks(false).count(), 12);
+  // the others' blocks are numbered after the first's, and lose the ranges
+  // of lines and bytes that were in their own file
+  let solset_uids = solset.all_blocks(false)
+    .filter_map(|b| b.uid)
+    .collect::<BTreeSet<_>>();
+  let uids = merged.all_blocks(false)
+    .filter_map(|b| b.uid)
+    .collect::<BTreeSet<_>>();
+  assert_eq!(uids.len(), 12);
+  assert!(uids.is_superset(&solset_uids));
+  for block in merged.all_blocks(false) {
+    let from_solset = solset_uids.contains(&block.uid.unwrap());
+    assert_eq!(block.line_range.is_some(), from_solset);
+    assert_eq!(block.byte_range.is_some(), from_solset);
+    assert_eq!(block.row_lines.is_empty(), !from_solset);
+  }
+  // the same file twice has the same rows twice, so those aren't merged
+  let same = [paths[0].clone(), paths[0].clone()];
+  let twice = parse_files_parallel_merge(&same).unwrap();
+  assert_eq!(twice.blocks.len(), 2);
+  assert_eq!(twice.all_blocks(false).count(), 4);
+  let uids = twice.all_blocks(false)
+    .filter_map(|b| b.uid)
+    .collect::<BTreeSet<_>>();
+  assert_eq!(uids.len(), 4);
+  // failing only if none parsed
+  assert!(parse_files_parallel_merge(&paths[2..]).is_err());
+  assert!(parse_files_parallel_merge(&[]).unwrap().blocks.is_empty());
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_parse_mmap() {
+  // the same as reading through a buffer, for every example
+  for path in example_f06s() {
+    let mapped = OnePassParser::parse_mmap(&path).unwrap();
+    let read = OnePassParser::parse_file(&path).unwrap();
+    assert_eq!(mapped.fingerprint(), read.fingerprint(), "{:?}", path);
+    assert_eq!(mapped.filename, read.filename);
+    assert_eq!(mapped.warnings, read.warnings);
+  }
+  // empty files, missing files and the wrong kind of file
+  let dir = std::env::temp_dir()
+    .join(format!("f06-mmap-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  let empty = dir.join("empty.f06");
+  std::fs::write(&empty, "").unwrap();
+  assert!(OnePassParser::parse_mmap(&empty).unwrap().blocks.is_empty());
+  assert!(OnePassParser::parse_mmap(&dir.join("missing.f06")).is_err());
+  let gzip = dir.join("run.f06.gz");
+  std::fs::write(&gzip, b"\x1f\x8b\x08\x00").unwrap();
+  let err = OnePassParser::parse_mmap(&gzip).unwrap_err();
+  assert!(ParseError::wrong_file_in(&err).is_some());
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Collects the example F06 files, from all solvers.
+fn example_f06s() -> Vec<std::path::PathBuf> {
+  let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("../examples");
+  let mut found = Vec::new();
+  for dir in std::fs::read_dir(root).unwrap() {
+    for entry in std::fs::read_dir(dir.unwrap().path()).unwrap() {
+      let path = entry.unwrap().path();
+      let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+      if ext.eq_ignore_ascii_case("f06") {
+        found.push(path);
+      }
+    }
+  }
+  return found;
+}
+
+#[test]
+fn test_sniff_wrong_files() {
+  // an OP2 begins with a 4-byte record, between markers
+  let mut op2 = vec![4, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0, 28, 0, 0, 0];
+  op2.extend_from_slice(b"NASTRAN FORT TAPE ID CODE - ");
+  let Some(WrongFile::Op2 { signature }) = sniff(&op2) else { panic!() };
+  assert!(signature.contains("NASTRAN FORT TAPE ID CODE"));
+  assert!(sniff(&op2[..12]).unwrap().to_string().contains("little-endian"));
+  let big = [0, 0, 0, 4, 0, 0, 0, 3, 0, 0, 0, 4];
+  assert!(sniff(&big).unwrap().to_string().contains("big-endian"));
+  // an OP2 without its header, found by its tables
+  let mut tables = vec![0u8; 200];
+  tables.extend_from_slice(b"OUGV1   ");
+  let Some(WrongFile::Op2 { signature }) = sniff(&tables) else { panic!() };
+  assert_eq!(signature, "the OUGV1 table");
+  // other binaries
+  assert_eq!(sniff(b"\x89HDF\r\n\x1a\n\0\0\0"), Some(WrongFile::Hdf5));
+  assert_eq!(
+    sniff(b"\x1f\x8b\x08\x00"),
+    Some(WrongFile::Compressed { format: "gzip".to_owned() })
+  );
+  let junk: Vec<u8> = (0..=255).cycle().take(1000).collect();
+  assert_eq!(
+    sniff(&junk),
+    Some(WrongFile::Binary { control: 116, inspected: 1000 })
+  );
+  // input decks, with a comment or a BOM before the first statement
+  let deck = "$ a comment\n\nSOL 101\nCEND\nBEGIN BULK\nGRID,1,,0.,0.,0.\n";
+  assert_eq!(
+    sniff(deck.as_bytes()),
+    Some(WrongFile::InputDeck { first_line: "SOL 101".to_owned() })
+  );
+  let bulk = "\u{feff}GRID*   1               0\n";
+  let Some(WrongFile::InputDeck { .. }) = sniff(bulk.as_bytes()) else {
+    panic!()
+  };
+  // a cut first line proves nothing
+  assert_eq!(sniff(b"SOL 101"), None);
+  // F06 text with a BOM, form feeds, tabs and a stray control character
+  let f06 = "\u{feff}\x0c  MYSTRAN Version 15.1.3\n\tSOL 1\x07\r\nSOL 1\n";
+  assert_eq!(sniff(f06.as_bytes()), None);
+  assert_eq!(sniff(b""), None);
+  // none of the examples, whose decks are echoed, is taken for anything else;
+  // there are nine, from both solvers and the layouts
+  let examples = example_f06s();
+  assert_eq!(examples.len(), 9);
+  for path in examples {
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(sniff(&bytes), None, "{}", path.display());
+  }
+}
+
+#[test]
+fn test_wrong_file_errors() {
+  let deck = "SOL 101\nCEND\nBEGIN BULK\nENDDATA\n";
+  let wrong = WrongFile::InputDeck { first_line: "SOL 101".to_owned() };
+  // the streaming parse fails right away, and then stops
+  let mut stream = OnePassParser::parse_streaming(deck.as_bytes());
+  let Some(Err(ParseError::WrongFile(w))) = stream.next() else { panic!() };
+  assert_eq!(w, wrong);
+  assert!(stream.next().is_none());
+  assert_eq!(stream.lines_read(), 0);
+  // the others give I/O errors with the wrong file in them
+  let err = OnePassParser::parse_bufread(deck.as_bytes()).unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+  assert_eq!(ParseError::wrong_file_in(&err), Some(&wrong));
+  assert!(err.to_string().contains("input deck"));
+  let err = OnePassParser::new().read_bytes(deck.as_bytes()).unwrap_err();
+  assert_eq!(ParseError::wrong_file_in(&err), Some(&wrong));
+  // other errors aren't about the wrong file
+  let err = OnePassParser::parse_file("/nonexistent/file.f06").unwrap_err();
+  assert_eq!(ParseError::wrong_file_in(&err), None);
 }
@@ -1,368 +1,40 @@
 //! This module implements utility functions without much need for defining
 //! context or not enough of it to warrant them having their own modules.
 
-use std::cell::Cell;
-use std::collections::BTreeMap;
-use std::fmt::Write;
-use serde::{Serialize, Deserialize};
-
-use crate::elements::ElementType;
-
-/// Words that we can find in a spaced block header to make us suspicious.
-pub(crate) const SUS_WORDS: &[&str] = &[
-  "ELEMENT",
-  "ELEM",
-  "FORCE",
-  "FORCES",
-  "STRESS",
-  "STRESSES",
-  "STRAIN",
-  "STRAINS",
-  "SPC",
-  "CONSTRAINT",
-  "CONSTRAINTS",
-  "MPC",
-  "GRID",
-  "DISPLACEMENT",
-  "APPLIED",
-  "LOAD",
-  "TEMPERATURE",
-  "HEAT",
-  "FLUX",
-  "GRAVITY",
-  "GRID",
-  "POINT",
-  "COORDINATE",
-  "COORD",
-  "SYSTEM",
-  "LOCAL",
-];
-
-/// Words that make us ignore a block because it's definitely not gonna be
-/// supported.
-pub(crate) const BAD_WORDS: &[&str] = &[
-  "NASTRAN",
-  "CONTROL",
-  "BULK",
-  "ECHO",
-  "NODAL",
-  "GENERATOR"
-];
-
-/// Decodes a Nastran-format floating point number. Hyper-lenient and doesn't
-/// require pulling a whole regex library.
-pub(crate) fn decode_nasfloat(s: &str) -> Option<f64> {
-  // mantissa start/end, exponent start/end
-  let mut ixs: [usize; 4] = [0, 0, 0, 0];
-  // 0-1 = looking for mantissa start/end, 2-3 = looking for exponent start/end
-  let step: Cell<usize> = 0.into();
-  let mut mark = |i| { ixs[step.get()] = i; step.replace(step.get() + 1); };
-  let mut seen_chars: usize = 0;
-  for (i, c) in s.chars().enumerate() {
-    seen_chars += 1;
-    match (step.get() % 2, c.is_numeric() || c == '.', c == '+' || c == '-') {
-      // looking for number start. nothing yet. keep looking.
-      (0, false, false) => continue,
-      // looking for number start, found something. mark it and look for end.
-      (0, _, _) => mark(i),
-      // looking for number end, saw number/dot. keep looking.
-      (1, true, _) => continue,
-      // looking for number end, saw not numerical/dot/sign. mark end.
-      (1, false, false) => mark(i),
-      // looking for number end, saw sign. mark end, mark start.
-      (1, _, true) => { mark(i); mark(i); },
-      // should be unreachable
-      _ => panic!("unreachable branch 1 in decoding nasfloat \"{}\"", s)
-    };
-    if step.get() > 3 { break; }
-  }
-  // handle empty string
-  if seen_chars == 0 {
-    return None;
-  }
-  // handle end at end-of-string
-  if step.get() % 2 == 1 {
-    mark(seen_chars);
-  }
-  let mantissa = || s[ixs[0]..ixs[1]].parse::<f64>().ok();
-  let exponent = || s[ixs[2]..ixs[3]].parse::<i32>().ok();
-  match step.get() {
-    // never found mantissa
-    0 => return None,
-    // only found a mantissa
-    2 => return mantissa(),
-    // found mantissa and exponent
-    4 => return Some(mantissa()? * 10.0_f64.powi(exponent()?)),
-    // should be unreachable
-    _ => panic!("unreachable branch 2 in returning nasfloat \"{}\"", s)
-  };
-}
-
-/// A line field as decoded.
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
-pub(crate) enum LineField<'s> {
-  /// Managed to parse an integer out of it.
-  Integer(isize),
-  /// Managed to parse a real out of it.
-  Real(f64),
-  /// Field is a single character.
-  Character(char),
-  /// Field is an element type.
-  ElementType(ElementType),
-  /// Couldn't parse it.
-  NoIdea(&'s str)
-}
-
-impl<'s> LineField<'s> {
-  /// Parses a single field into a LineField.
-  fn parse(s: &'s str) -> Self {
-    if let Ok(i) = s.parse::<isize>() {
-      return Self::Integer(i);
-    }
-    if let Ok(x) = s.parse::<f64>()/*.or(decode_nasfloat(s))*/ {
-      return Self::Real(x);
-    }
-    if s.len() == 1 {
-      return Self::Character(s.chars().nth(0).unwrap());
-    }
-    for cand in ElementType::all() {
-      if s.contains(cand.name()) {
-        return Self::ElementType(*cand);
-      }
-    }
-    return Self::NoIdea(s);
-  }
-}
-
-/// Breaks down a line into an iterator of fields.
-pub(crate) fn line_breakdown(
-  s: &str
-) -> impl Iterator<Item = LineField<'_>> {
-  return s.split(' ')
-    .filter(|subs| !subs.is_empty())
-    .map(LineField::parse);
-}
-
-/// Gets a certain number of reals from a line.
-pub(crate) fn extract_reals<const N: usize>(line: &str) -> Option<[f64; N]> {
-  let mut arr: [f64; N] = [0.0; N];
-  let mut found = 0;
-  for field in line_breakdown(line) {
-    match field {
-      LineField::Real(x) if found < N => {
-        arr[found] = x;
-        found += 1;
-      },
-      LineField::Real(_) if found == N => {
-        return None;
-      },
-      _ => continue
-    }
-  }
-  if found == N {
-    return Some(arr);
-  } else {
-    return None;
-  }
-}
+#[cfg(feature = "parser")]
+mod lines;
 
-/// Gets a certain number of reals from a line, but ignores extras.
-pub(crate) fn lax_reals<const N: usize>(line: &str) -> Option<[f64; N]> {
-  let mut arr: [f64; N] = [0.0; N];
-  let mut found = 0;
-  for field in line_breakdown(line) {
-    match field {
-      LineField::Real(x) if found < N => {
-        arr[found] = x;
-        found += 1;
-      },
-      _ => continue
-    }
-  }
-  if found == N {
-    return Some(arr);
-  } else {
-    return None;
-  }
-}
-
-/// Gets the N-th integer in a line.
-pub(crate) fn nth_integer(line: &str, n: usize) -> Option<isize> {
-  return line_breakdown(line)
-    .filter_map(|field| {
-      if let LineField::Integer(x) = field {
-        return Some(x)
-      } else {
-        None
-      }
-    }).nth(n);
-}
-
-/// Returns the n-th integer in a line and casts it to a usize.
-pub(crate) fn nth_natural(line: &str, n: usize) -> Option<usize> {
-  return line_breakdown(line)
-    .filter_map(|field| {
-      if let LineField::Integer(x) = field {
-        return Some(x as usize)
-      } else {
-        None
-      }
-    }).nth(n);
-}
-
-/// Gets the N-th string in a line.
-pub(crate) fn nth_string(line: &str, n: usize) -> Option<&str> {
-  return line_breakdown(line)
-    .filter_map(|field| {
-      if let LineField::NoIdea(s) = field {
-        return Some(s)
-      } else {
-        None
-      }
-    }).nth(n);
-}
+use std::cmp::Ordering;
+use std::fmt::{Display, Write};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use serde::{Serialize, Deserialize};
 
-/// Gets the N-th element type in a line.
-pub(crate) fn nth_etype(line: &str, n: usize) -> Option<ElementType> {
-  return line_breakdown(line)
-    .filter_map(|field| {
-      if let LineField::ElementType(etype) = field {
-        return Some(etype)
-      } else {
-        None
-      }
-    }).nth(n)
-}
+use crate::blocks::types::BlockType;
+#[cfg(feature = "parser")]
+pub(crate) use lines::*;
 
-/// Extracts all forms given by integers followed by some floats in a line.
-/// Ignores all other fields. Useful for some kinds of tables.
-pub(crate) fn int_pattern(line: &str) -> BTreeMap<usize, Vec<f64>> {
-  let mut res: BTreeMap<usize, Vec<f64>> = BTreeMap::new();
-  let mut current_nat: Option<(usize, Vec<f64>)> = None;
-  let flush = |
-    r: &mut BTreeMap<usize, Vec<f64>>,
-    cur: &mut Option<(usize, Vec<f64>)>
-  | {
-    if let Some((i, v)) = cur.take() {
-      r.insert(i, v);
-    };
-  };
-  for field in line_breakdown(line) {
-    match field {
-      LineField::Integer(i) => {
-        flush(&mut res, &mut current_nat);
-        current_nat = Some((i as usize, Vec::new()));
-      },
-      LineField::Real(x) => {
-        if let Some((_, ref mut v)) = current_nat {
-          v.push(x);
-        }
-      },
-      _ => flush(&mut res, &mut current_nat)
-    };
+/// Returns how a value that isn't finite is written out ("NaN", "Inf" or
+/// "-Inf"), or None if it's finite.
+pub fn non_finite_name(x: f64) -> Option<&'static str> {
+  if x.is_nan() {
+    return Some("NaN");
+  } else if x.is_infinite() {
+    return Some(if x > 0.0 { "Inf" } else { "-Inf" });
   }
-  flush(&mut res, &mut current_nat);
-  return res;
+  return None;
 }
 
-/// Returns the last integer in a line.
-pub(crate) fn last_int(line: &str) -> Option<isize> {
-  return line_breakdown(line)
-    .filter_map(|f| if let LineField::Integer(i) = f { Some(i) } else { None })
-    .last()
-}
+/// How many lines apart the parts of a block header can be by default, i.e.
+/// one blank line is allowed between them.
+pub const HEADER_WINDOW: usize = 2;
 
-/// Returns the last natural in a line.
-pub(crate) fn last_natural(line: &str) -> Option<usize> {
-  return last_int(line).map(|i| i as usize);
-}
+/// How many lines apart the parts of a block header can be, at most.
+pub const MAX_HEADER_WINDOW: usize = 3;
 
-/// Checks if a character is an uppercase letter or a digit.
-fn upper_or_digit_or_special(ch: char) -> bool {
-  /// Allowed special characters in a spaced header line.
-  const SPEC: &str = "()[]-";
-  return ch.is_ascii_uppercase() || ch.is_ascii_digit() || SPEC.contains(ch);
-}
-
-/// Turns a line made of spaced upper-case ASCII into a line of upper-case
-/// words, used for detecting block headers.
-pub(crate) fn unspace(line: &str) -> Option<String> {
-  let mut cap: usize = 0;
-  let mut last: char = ' ';
-  let mut stop_at: usize = 0;
-  for ch in line.chars() {
-    stop_at += 1;
-    if upper_or_digit_or_special(ch) {
-      if last == ' ' {
-        last = ch;
-        cap += 2;
-        continue;
-      } else {
-        // not spaced. but have we seen a lot?
-        if cap > 20 {
-          // we've seen enough, this is fine. drop the extra chars tho
-          stop_at = 0.max(stop_at - 2);
-          break;
-        } else {
-          // nah, we've seen it too soon.
-          return None;
-        }
-      }
-    }
-    if ch == ' ' {
-      last = ch;
-      continue;
-    }
-    // bad char
-    return None;
-  }
-  if cap < 4 {
-    // too small
-    return None;
-  }
-  let mut sb = String::with_capacity(cap);
-  let mut space_run: usize = 0;
-  let mut started = false;
-  for (i, ch) in line.chars().enumerate() {
-    if i == stop_at {
-      break;
-    }
-    if ch == ' ' {
-      space_run += 1;
-    }
-    if space_run > 3 {
-      started = true;
-    }
-    if !started {
-      continue;
-    }
-    if upper_or_digit_or_special(ch) {
-      if (2..15).contains(&space_run) {
-        sb.push(' ');
-      }
-      sb.push(ch);
-      space_run = 0;
-    }
-  }
-  return Some(sb.trim().to_string());
-}
-
-/// Checks if a line is a likely block header.
-pub(crate) fn check_header(line: &str) -> Option<String> {
-  // unspace it
-  let unspaced = unspace(line)?;
-  // check for sus words
-  if SUS_WORDS.iter().any(|w| unspaced.contains(w)) {
-    return Some(unspaced);
-  }
-  // check for element type names
-  if ElementType::all().iter().any(|et| unspaced.contains(et.name())) {
-    return Some(unspaced);
-  }
-  return None;
-}
-
-use std::cmp::Ordering;
+/// How many lines apart progress is reported by default, when parsing with
+/// progress reports.
+pub const PROGRESS_INTERVAL: usize = 1000;
 
 /// This contains a potential header.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -372,7 +44,10 @@ pub struct PotentialHeader {
   /// Number of lines this takes up.
   pub span: usize,
   /// The unspaced text.
-  pub text: String
+  pub text: String,
+  /// Byte offset of the starting line.
+  #[serde(default)]
+  pub offset: u64
 }
 
 impl AsRef<str> for PotentialHeader {
@@ -407,8 +82,31 @@ impl PotentialHeader {
     return self.start..(self.start+self.span);
   }
 
-  /// Merges this potential header with another, if possible.
+  /// Merges this potential header with another, if possible, i.e. if the
+  /// second one starts right after the first. If they're together the header
+  /// of a known block, wrapped across lines, the second one may also start
+  /// within `HEADER_WINDOW` lines of the end of the first.
   pub fn try_merge(self, other: Self) -> Result<Self, (Self, Self)> {
+    let (first, second) = if self.start <= other.start {
+      (&self, &other)
+    } else {
+      (&other, &self)
+    };
+    let joined = format!("{} {}", first.text, second.text);
+    let wrapped = BlockType::all()
+      .iter()
+      .any(|bt| bt.headers().iter().any(|h| joined.contains(h)));
+    let window = if wrapped { HEADER_WINDOW } else { 1 };
+    return self.try_merge_within(other, window);
+  }
+
+  /// Merges this potential header with another, if the second one starts
+  /// within `window` lines of the end of the first.
+  pub fn try_merge_within(
+    self,
+    other: Self,
+    window: usize
+  ) -> Result<Self, (Self, Self)> {
     // put them in order
     let (mut first, second) = if self.start <= other.start {
       (self, other)
@@ -416,10 +114,11 @@ impl PotentialHeader {
       (other, self)
     };
     // check if the ranges work glued together
-    if first.lines().last().unwrap() == (second.lines().nth(0).unwrap() - 1) {
+    let end = first.start + first.span;
+    if second.start >= end && second.start < end + window {
       first.text.push(' ');
       first.text.push_str(&second.text);
-      first.span += second.span;
+      first.span = second.start + second.span - first.start;
       return Ok(first);
     }
     return Err((first, second));
@@ -437,6 +136,9 @@ pub fn fmt_f64<W: Write>(
   capital_e: bool,
   omit_plus: bool
 ) -> std::fmt::Result {
+  if let Some(name) = non_finite_name(num) {
+    return write!(f, "{:>width$}", name, width = width);
+  }
   let mut num = if omit_plus {
     format!(
       "{:.precision$e}",
@@ -470,3 +172,277 @@ pub fn fmt_f64<W: Write>(
 
   return write!(f, "{:>width$}", num, width = width);
 }
+
+/// A closed interval of IDs; an end of `usize::MAX` means "open-ended".
+type IdInterval = (usize, usize);
+
+/// Merges overlapping and adjacent intervals, sorting them.
+fn normalise_intervals(intervals: &mut Vec<IdInterval>) {
+  intervals.sort_unstable();
+  let mut merged: Vec<IdInterval> = Vec::with_capacity(intervals.len());
+  for &(a, b) in intervals.iter() {
+    match merged.last_mut() {
+      Some(last) if a <= last.1.saturating_add(1) => last.1 = last.1.max(b),
+      _ => merged.push((a, b))
+    };
+  }
+  *intervals = merged;
+}
+
+/// Removes an interval from a normalised set of intervals.
+fn subtract_interval(intervals: &mut Vec<IdInterval>, cut: IdInterval) {
+  let (ca, cb) = cut;
+  let mut out: Vec<IdInterval> = Vec::with_capacity(intervals.len() + 1);
+  for &(a, b) in intervals.iter() {
+    if b < ca || a > cb {
+      out.push((a, b));
+      continue;
+    }
+    if a < ca {
+      out.push((a, ca - 1));
+    }
+    if b > cb {
+      out.push((cb + 1, b));
+    }
+  }
+  *intervals = out;
+}
+
+/// Writes an interval in the selection syntax.
+fn fmt_interval(f: &mut String, (a, b): IdInterval) -> std::fmt::Result {
+  return match (a, b) {
+    (0, usize::MAX) => write!(f, "all"),
+    (a, usize::MAX) => write!(f, "{}-", a),
+    (a, b) if a == b => write!(f, "{}", a),
+    (a, b) => write!(f, "{}-{}", a, b),
+  };
+}
+
+/// The kinds of error found when parsing an ID selection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IdSelectionErrorKind {
+  /// A term was empty (e.g. two commas in a row).
+  EmptyTerm,
+  /// Something that should've been a number wasn't.
+  BadNumber(String),
+  /// A range had its start after its end.
+  ReversedRange(usize, usize),
+  /// An exclusion mark wasn't followed by anything.
+  EmptyExclusion
+}
+
+/// An error found when parsing an ID selection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdSelectionError {
+  /// The 0-based byte offset of the offending term in the input.
+  pub position: usize,
+  /// What went wrong.
+  pub kind: IdSelectionErrorKind
+}
+
+impl Display for IdSelectionError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "at position {}: ", self.position)?;
+    return match &self.kind {
+      IdSelectionErrorKind::EmptyTerm => write!(f, "empty term"),
+      IdSelectionErrorKind::BadNumber(s) => write!(f, "bad number \"{}\"", s),
+      IdSelectionErrorKind::ReversedRange(a, b) => {
+        write!(f, "range {}-{} has its start after its end", a, b)
+      },
+      IdSelectionErrorKind::EmptyExclusion => {
+        write!(f, "'!' must be followed by an ID, a range or \"all\"")
+      },
+    };
+  }
+}
+
+impl std::error::Error for IdSelectionError {}
+
+/// A set of IDs (grid points, elements, subcases, modes...), as written in
+/// the command line: comma-separated terms, each one being an ID ("5"), an
+/// inclusive range ("100-200"), an open-ended range ("500-") or "all".
+/// Terms prefixed with a '!' are exclusions. Terms are applied in order, so
+/// later ones win: "1-10,!5" doesn't contain 5, but "!5,1-10" does. If the
+/// first term is an exclusion, the selection starts out with all IDs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct IdSelection {
+  /// The selected IDs as sorted, disjoint, non-adjacent intervals.
+  intervals: Vec<IdInterval>
+}
+
+impl IdSelection {
+  /// Returns a selection containing nothing.
+  pub fn new() -> Self {
+    return Self::default();
+  }
+
+  /// Returns a selection containing every ID.
+  pub fn all() -> Self {
+    return Self { intervals: vec![(0, usize::MAX)] };
+  }
+
+  /// Returns true if this selects no IDs at all.
+  pub fn is_empty(&self) -> bool {
+    return self.intervals.is_empty();
+  }
+
+  /// Returns true if this selects every ID.
+  pub fn is_all(&self) -> bool {
+    return self.intervals == [(0, usize::MAX)];
+  }
+
+  /// Returns true if the selection contains an ID.
+  pub fn contains(&self, id: usize) -> bool {
+    let after = self.intervals.partition_point(|&(a, _)| a <= id);
+    return after > 0 && self.intervals[after - 1].1 >= id;
+  }
+
+  /// Iterates, in order, over the selected IDs within some bounds.
+  pub fn iter_within(
+    &self,
+    bounds: RangeInclusive<usize>
+  ) -> impl Iterator<Item = usize> + '_ {
+    let (lo, hi) = bounds.into_inner();
+    return self.intervals.iter()
+      .flat_map(move |&(a, b)| a.max(lo)..=b.min(hi));
+  }
+
+  /// Parses a single term (without the exclusion mark).
+  fn parse_term(
+    term: &str,
+    position: usize
+  ) -> Result<IdInterval, IdSelectionError> {
+    let err = |kind| IdSelectionError { position, kind };
+    let num = |s: &str| -> Result<usize, IdSelectionError> {
+      return s.trim()
+        .parse::<usize>()
+        .map_err(|_| err(IdSelectionErrorKind::BadNumber(s.trim().into())));
+    };
+    if term.eq_ignore_ascii_case("all") {
+      return Ok((0, usize::MAX));
+    }
+    return match term.split_once('-') {
+      Some((a, b)) if b.trim().is_empty() => Ok((num(a)?, usize::MAX)),
+      Some((a, b)) => {
+        let (a, b) = (num(a)?, num(b)?);
+        if a > b {
+          return Err(err(IdSelectionErrorKind::ReversedRange(a, b)));
+        }
+        Ok((a, b))
+      },
+      None => num(term).map(|x| (x, x))
+    };
+  }
+
+  /// Renders the selection as a list of included intervals.
+  fn positive_form(&self) -> String {
+    let mut s = String::new();
+    for (i, iv) in self.intervals.iter().enumerate() {
+      if i > 0 {
+        s.push(',');
+      }
+      fmt_interval(&mut s, *iv).expect("writing to a string shouldn't fail");
+    }
+    return s;
+  }
+
+  /// Renders the selection as "all" minus a list of excluded intervals.
+  fn negative_form(&self) -> String {
+    let mut excluded = vec![(0, usize::MAX)];
+    for iv in self.intervals.iter() {
+      subtract_interval(&mut excluded, *iv);
+    }
+    let mut s = String::from("all");
+    for iv in excluded {
+      s.push_str(",!");
+      fmt_interval(&mut s, iv).expect("writing to a string shouldn't fail");
+    }
+    return s;
+  }
+}
+
+impl FromStr for IdSelection {
+  type Err = IdSelectionError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut intervals: Vec<IdInterval> = Vec::new();
+    if s.trim().is_empty() {
+      return Ok(Self { intervals });
+    }
+    let mut position = 0;
+    for (i, raw) in s.split(',').enumerate() {
+      let here = position + raw.len() - raw.trim_start().len();
+      position += raw.len() + 1;
+      let term = raw.trim();
+      if term.is_empty() {
+        return Err(IdSelectionError {
+          position: here,
+          kind: IdSelectionErrorKind::EmptyTerm
+        });
+      }
+      match term.strip_prefix('!') {
+        Some(rest) => {
+          if rest.trim().is_empty() {
+            return Err(IdSelectionError {
+              position: here,
+              kind: IdSelectionErrorKind::EmptyExclusion
+            });
+          }
+          // a leading exclusion means "everything but"
+          if i == 0 {
+            intervals.push((0, usize::MAX));
+          }
+          let cut = Self::parse_term(rest.trim(), here)?;
+          subtract_interval(&mut intervals, cut);
+        },
+        None => {
+          intervals.push(Self::parse_term(term, here)?);
+          normalise_intervals(&mut intervals);
+        }
+      };
+    }
+    return Ok(Self { intervals });
+  }
+}
+
+impl Display for IdSelection {
+  /// Writes the selection in a canonical form: either the included ranges or
+  /// "all" followed by the excluded ranges, whichever is shorter.
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let positive = self.positive_form();
+    if self.is_empty() || self.is_all() {
+      return write!(f, "{}", positive);
+    }
+    let negative = self.negative_form();
+    if negative.len() < positive.len() {
+      return write!(f, "{}", negative);
+    }
+    return write!(f, "{}", positive);
+  }
+}
+
+impl TryFrom<String> for IdSelection {
+  type Error = IdSelectionError;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    return value.parse();
+  }
+}
+
+impl From<IdSelection> for String {
+  fn from(value: IdSelection) -> Self {
+    return value.to_string();
+  }
+}
+
+impl FromIterator<usize> for IdSelection {
+  fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
+    let mut intervals: Vec<IdInterval> = iter.into_iter()
+      .map(|x| (x, x))
+      .collect();
+    normalise_intervals(&mut intervals);
+    return Self { intervals };
+  }
+}
@@ -0,0 +1,714 @@
+//! This module implements the helpers the parser and the decoders use to
+//! pick lines apart: field breakdown, number extraction and header detection.
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead};
+use serde::{Serialize, Deserialize};
+
+use crate::blocks::indexing::ElementSide;
+use crate::blocks::types::BlockType;
+use crate::elements::ElementType;
+
+/// Words that we can find in a spaced block header to make us suspicious.
+pub(crate) const SUS_WORDS: &[&str] = &[
+  "ELEMENT",
+  "ELEM",
+  "FORCE",
+  "FORCES",
+  "STRESS",
+  "STRESSES",
+  "STRAIN",
+  "STRAINS",
+  "SPC",
+  "CONSTRAINT",
+  "CONSTRAINTS",
+  "MPC",
+  "GRID",
+  "DISPLACEMENT",
+  "VELOCITY",
+  "ACCELERATION",
+  "APPLIED",
+  "LOAD",
+  "TEMPERATURE",
+  "HEAT",
+  "FLUX",
+  "GRAVITY",
+  "GRID",
+  "POINT",
+  "COORDINATE",
+  "COORD",
+  "SYSTEM",
+  "LOCAL",
+  "EIGENVALUE",
+  "EIGENVALUES",
+  "EIGENVECTOR",
+  "EFFECTIVE",
+];
+
+/// Words that make us ignore a block because it's definitely not gonna be
+/// supported.
+pub(crate) const BAD_WORDS: &[&str] = &[
+  "NASTRAN",
+  "CONTROL",
+  "BULK",
+  "ECHO",
+  "NODAL",
+  "GENERATOR"
+];
+
+/// Decodes a Nastran-format floating point number. Hyper-lenient and doesn't
+/// require pulling a whole regex library.
+pub(crate) fn decode_nasfloat(s: &str) -> Option<f64> {
+  // mantissa start/end, exponent start/end
+  let mut ixs: [usize; 4] = [0, 0, 0, 0];
+  // 0-1 = looking for mantissa start/end, 2-3 = looking for exponent start/end
+  let step: Cell<usize> = 0.into();
+  let mut mark = |i| { ixs[step.get()] = i; step.replace(step.get() + 1); };
+  let mut seen_chars: usize = 0;
+  // byte offsets, since we slice the string with them later
+  for (i, c) in s.char_indices() {
+    seen_chars += 1;
+    let numeric = c.is_ascii_digit() || c == '.';
+    match (step.get() % 2, numeric, c == '+' || c == '-') {
+      // looking for number start. nothing yet. keep looking.
+      (0, false, false) => continue,
+      // looking for number start, found something. mark it and look for end.
+      (0, _, _) => mark(i),
+      // looking for number end, saw number/dot. keep looking.
+      (1, true, _) => continue,
+      // looking for number end, saw not numerical/dot/sign. mark end.
+      (1, false, false) => mark(i),
+      // looking for number end, saw sign. mark end, mark start.
+      (1, _, true) => { mark(i); mark(i); },
+      // should be unreachable
+      _ => panic!("unreachable branch 1 in decoding nasfloat \"{}\"", s)
+    };
+    if step.get() > 3 { break; }
+  }
+  // handle empty string
+  if seen_chars == 0 {
+    return None;
+  }
+  // handle end at end-of-string
+  if step.get() % 2 == 1 {
+    mark(s.len());
+  }
+  let mantissa = || s[ixs[0]..ixs[1]].parse::<f64>().ok();
+  let exponent = || s[ixs[2]..ixs[3]].parse::<i32>().ok();
+  match step.get() {
+    // never found mantissa
+    0 => return None,
+    // only found a mantissa
+    2 => return mantissa(),
+    // found mantissa and exponent; parsed as a whole so as not to lose
+    // precision, with an exponent letter if it was left out
+    4 => {
+      exponent()?;
+      let (mantissa, exponent) = (&s[ixs[0]..ixs[1]], &s[ixs[2]..ixs[3]]);
+      return s[ixs[0]..ixs[3]].parse::<f64>().ok().or_else(|| {
+        return format!("{}E{}", mantissa, exponent).parse().ok();
+      });
+    },
+    // should be unreachable
+    _ => panic!("unreachable branch 2 in returning nasfloat \"{}\"", s)
+  };
+}
+
+/// Size of a Nastran word in bytes, for memory figures given in words. Words
+/// are eight bytes long in the 64-bit (ILP-64) builds solvers ship nowadays.
+pub(crate) const BYTES_PER_WORD: u64 = 8;
+
+/// Decodes a duration into seconds. Takes clock-style durations ("HH:MM:SS",
+/// "MM:SS", with optional fractional seconds) and numbers followed by an
+/// optional unit ("7.81E-02 seconds", "41.5 SEC", "2 MIN"). Seconds are
+/// assumed if there's no unit.
+pub(crate) fn decode_duration(s: &str) -> Option<f64> {
+  let mut words = s.split_whitespace();
+  let first = words.next()?;
+  if first.contains(':') {
+    let parts = first.split(':')
+      .map(|p| p.parse::<f64>().ok())
+      .collect::<Option<Vec<f64>>>()?;
+    if !(2..=3).contains(&parts.len()) {
+      return None;
+    }
+    return Some(parts.into_iter().fold(0.0, |acc, x| acc*60.0 + x));
+  }
+  let x = decode_nasfloat(first)?;
+  let unit = words.next().unwrap_or("S").to_ascii_uppercase();
+  let factor = match unit.as_str() {
+    "S" | "SEC" | "SECS" | "SECOND" | "SECONDS" => 1.0,
+    "MIN" | "MINS" | "MINUTE" | "MINUTES" => 60.0,
+    "H" | "HOUR" | "HOURS" => 3600.0,
+    _ => return None
+  };
+  return Some(x * factor);
+}
+
+/// Decodes an amount of memory into bytes. Takes a number followed by a unit
+/// ("12.3 MB", "512 KB", "12345678 WORDS", "84 K WORDS"); units are binary,
+/// and words are converted with `BYTES_PER_WORD`.
+pub(crate) fn decode_bytes(s: &str) -> Option<u64> {
+  let mut words = s.split_whitespace();
+  let x = decode_nasfloat(words.next()?)?;
+  let mut unit = words.next()?.to_ascii_uppercase();
+  if unit == "K" || unit == "M" {
+    // "K WORDS" and the like
+    unit.push(' ');
+    unit.push_str(&words.next()?.to_ascii_uppercase());
+  }
+  let factor: u64 = match unit.as_str() {
+    "B" | "BYTES" => 1,
+    "KB" | "KBYTES" => 1 << 10,
+    "MB" | "MBYTES" => 1 << 20,
+    "GB" | "GBYTES" => 1 << 30,
+    "WORDS" => BYTES_PER_WORD,
+    "K WORDS" | "KWORDS" => (1 << 10) * BYTES_PER_WORD,
+    "M WORDS" | "MWORDS" => (1 << 20) * BYTES_PER_WORD,
+    _ => return None
+  };
+  if x < 0.0 {
+    return None;
+  }
+  return Some((x * factor as f64).round() as u64);
+}
+
+/// A line field as decoded.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub(crate) enum LineField<'s> {
+  /// Managed to parse an integer out of it.
+  Integer(isize),
+  /// Managed to parse a real out of it.
+  Real(f64),
+  /// Field is a single character.
+  Character(char),
+  /// Field is an element type.
+  ElementType(ElementType),
+  /// Couldn't parse it.
+  NoIdea(&'s str)
+}
+
+impl<'s> LineField<'s> {
+  /// Parses a single field into a LineField.
+  fn parse(s: &'s str) -> Self {
+    if let Ok(i) = s.parse::<isize>() {
+      return Self::Integer(i);
+    }
+    if let Some(x) = decode_non_finite(s) {
+      return Self::Real(x);
+    }
+    if let Ok(x) = s.parse::<f64>()/*.or(decode_nasfloat(s))*/ {
+      return Self::Real(x);
+    }
+    if s.len() == 1 {
+      return Self::Character(s.chars().nth(0).unwrap());
+    }
+    for cand in ElementType::all() {
+      if s.contains(cand.name()) {
+        return Self::ElementType(*cand);
+      }
+    }
+    return Self::NoIdea(s);
+  }
+}
+
+/// Breaks down a line into an iterator of fields.
+pub(crate) fn line_breakdown(
+  s: &str
+) -> impl Iterator<Item = LineField<'_>> {
+  return s.split(' ')
+    .filter(|subs| !subs.is_empty())
+    .map(LineField::parse);
+}
+
+/// Minimum length of a field of asterisks for it to be an overflowed number.
+/// Fortran fills the whole width of a field with asterisks when a value
+/// doesn't fit in it, and no numeric field in the tables we decode is
+/// narrower than four characters; the markers of solver messages (as in
+/// "*** USER WARNING MESSAGE") are three asterisks long, so they don't count.
+const OVERFLOW_MIN_LEN: usize = 4;
+
+/// Returns true if a field overflowed (only asterisks).
+fn is_overflowed(field: &str) -> bool {
+  return field.len() >= OVERFLOW_MIN_LEN && field.bytes().all(|b| b == b'*');
+}
+
+/// Returns true if a line has a field that overflowed.
+pub(crate) fn has_overflowed_field(s: &str) -> bool {
+  return s.contains('*') && s.split(' ').any(is_overflowed);
+}
+
+/// Decodes the ways solvers print values that aren't finite: the IEEE text
+/// forms ("NaN", "Inf", "-Infinity"), the ones from older Fortran runtimes
+/// ("1.#INF", "-1.#IND", "1.#QNAN0") and overflowed fields, whose value is
+/// unknown, so they're NaN.
+pub(crate) fn decode_non_finite(s: &str) -> Option<f64> {
+  if is_overflowed(s) {
+    return Some(f64::NAN);
+  }
+  let (negative, unsigned) = match s.strip_prefix('-') {
+    Some(rest) => (true, rest),
+    None => (false, s.strip_prefix('+').unwrap_or(s))
+  };
+  let is = |names: &[&str], s: &str| {
+    return names.iter().any(|n| n.eq_ignore_ascii_case(s));
+  };
+  let old = unsigned.strip_prefix("1.#").map(|w| w.trim_end_matches('0'));
+  let x = if is(&["NAN", "NANQ", "NANS"], unsigned) {
+    f64::NAN
+  } else if is(&["INF", "INFINITY"], unsigned) {
+    f64::INFINITY
+  } else {
+    match old {
+      Some(w) if is(&["QNAN", "SNAN", "IND"], w) => f64::NAN,
+      Some(w) if is(&["INF"], w) => f64::INFINITY,
+      _ => return None
+    }
+  };
+  // NaNs all get the same bits, sign included, so fingerprints agree
+  return Some(if negative && x.is_infinite() { -x } else { x });
+}
+
+/// Returns true if a line has a field with a value that isn't finite. Those
+/// all have an asterisk, a hash or a letter of "NaN" or "Inf" in them, so the
+/// lines of plain numbers, which are most of them, are told apart quickly.
+pub(crate) fn has_non_finite_field(s: &str) -> bool {
+  let might = s.bytes()
+    .any(|b| matches!(b, b'*' | b'#' | b'N' | b'n' | b'I' | b'i'));
+  return might && s.split(' ').any(|f| decode_non_finite(f).is_some());
+}
+
+/// Returns the relative precision a real field was printed with, i.e. one
+/// unit in the last place of its mantissa, if it's in exponent form and not
+/// zero. Fixed-point fields (angles, percentages) don't count, since their
+/// relative precision depends on their magnitude.
+pub(crate) fn printed_precision(field: &str) -> Option<f64> {
+  let s = field.trim_start_matches(['+', '-']);
+  let dot = s.find('.')?;
+  // the exponent starts at the first letter or sign after the dot
+  let exp_start = dot + 1 + s[dot+1..].find(
+    ['e', 'E', 'd', 'D', '+', '-']
+  )?;
+  let mantissa = &s[..exp_start];
+  let exponent = s[exp_start..].trim_start_matches(char::is_alphabetic);
+  if !mantissa.chars().all(|c| c.is_ascii_digit() || c == '.')
+    || !mantissa.chars().any(|c| matches!(c, '1'..='9'))
+    || exponent.parse::<i32>().is_err() {
+    return None;
+  }
+  let decimals = (exp_start - dot - 1) as i32;
+  return Some(10.0_f64.powi(-decimals));
+}
+
+/// Returns the worst (largest) precision of the real fields in a line.
+pub(crate) fn line_print_precision(s: &str) -> Option<f64> {
+  return s.split(' ')
+    .filter_map(printed_precision)
+    .reduce(f64::max);
+}
+
+/// Returns true if a string is made of digits only, and isn't empty.
+fn all_digits(s: &str) -> bool {
+  return !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+}
+
+/// Returns true if a field is a real written with a decimal comma, e.g.
+/// "1,234567E+03": a comma directly between digits, with an exponent after
+/// them. Zeroes count too ("0,0"), since they're printed without exponents.
+pub(crate) fn is_comma_decimal(field: &str) -> bool {
+  let unsigned = field.trim_start_matches(['+', '-']);
+  let (int, rest) = match unsigned.split_once(',') {
+    Some(parts) => parts,
+    None => return false
+  };
+  let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+  let (frac, exp) = rest.split_at(digits);
+  if !all_digits(int) || frac.is_empty() {
+    return false;
+  }
+  if exp.is_empty() {
+    return int.bytes().chain(frac.bytes()).all(|b| b == b'0');
+  }
+  // either a letter and maybe a sign, or just a sign (e.g. "1,5-03")
+  let (letter, exp) = match exp.strip_prefix(['E', 'e', 'D', 'd']) {
+    Some(e) => (true, e),
+    None => (false, exp)
+  };
+  return match exp.strip_prefix(['+', '-']) {
+    Some(e) => all_digits(e),
+    None => letter && all_digits(exp)
+  };
+}
+
+/// Tells whether a line of data has its reals written with decimal commas:
+/// yes if it has no reals with a decimal point but more than one in exponent
+/// form with a decimal comma, no if it has a real with a decimal point, and
+/// nothing if it can't tell (e.g. it has no reals at all).
+pub(crate) fn decimal_comma_verdict(line: &str) -> Option<bool> {
+  let fields = line.split(' ').filter(|f| !f.is_empty());
+  let mut commas = 0;
+  for field in fields {
+    if field.contains('.') && field.parse::<f64>().is_ok() {
+      return Some(false);
+    }
+    let zero = field.bytes().all(|b| matches!(b, b'0' | b',' | b'+' | b'-'));
+    if is_comma_decimal(field) && !zero {
+      commas += 1;
+    }
+  }
+  return (commas > 1).then_some(true);
+}
+
+/// Returns true if a line looks like data written with decimal commas (see
+/// `decimal_comma_verdict`).
+pub(crate) fn looks_comma_decimal(line: &str) -> bool {
+  return decimal_comma_verdict(line) == Some(true);
+}
+
+/// Rewrites the reals with decimal commas in a line to use decimal points,
+/// leaving everything else (including field positions) as it was.
+pub(crate) fn periodise_decimals(line: &str) -> String {
+  return line.split(' ')
+    .map(|f| {
+      if is_comma_decimal(f) {
+        return f.replacen(',', ".", 1);
+      }
+      return f.to_owned();
+    })
+    .collect::<Vec<_>>()
+    .join(" ");
+}
+
+/// Gets a certain number of reals from a line.
+pub(crate) fn extract_reals<const N: usize>(line: &str) -> Option<[f64; N]> {
+  let mut arr: [f64; N] = [0.0; N];
+  let mut found = 0;
+  for field in line_breakdown(line) {
+    match field {
+      LineField::Real(x) if found < N => {
+        arr[found] = x;
+        found += 1;
+      },
+      LineField::Real(_) if found == N => {
+        return None;
+      },
+      _ => continue
+    }
+  }
+  if found == N {
+    return Some(arr);
+  } else {
+    return None;
+  }
+}
+
+/// Gets a certain number of reals from a line, like `extract_reals`, along
+/// with the words printed after the last of them, if any (e.g. a status like
+/// "NO FAIL").
+pub(crate) fn extract_reals_with_status<const N: usize>(
+  line: &str
+) -> Option<([f64; N], Option<String>)> {
+  let reals = extract_reals::<N>(line)?;
+  let mut words: Vec<&str> = Vec::new();
+  for field in line.split(' ').filter(|subs| !subs.is_empty()) {
+    if let LineField::Real(_) = LineField::parse(field) {
+      words.clear();
+    } else {
+      words.push(field);
+    }
+  }
+  let status = Some(words.join(" ")).filter(|w| !w.is_empty());
+  return Some((reals, status));
+}
+
+/// Gets a certain number of reals from a line, but ignores extras.
+pub(crate) fn lax_reals<const N: usize>(line: &str) -> Option<[f64; N]> {
+  let mut arr: [f64; N] = [0.0; N];
+  let mut found = 0;
+  for field in line_breakdown(line) {
+    match field {
+      LineField::Real(x) if found < N => {
+        arr[found] = x;
+        found += 1;
+      },
+      _ => continue
+    }
+  }
+  if found == N {
+    return Some(arr);
+  } else {
+    return None;
+  }
+}
+
+/// Gets the N-th integer in a line.
+pub(crate) fn nth_integer(line: &str, n: usize) -> Option<isize> {
+  return line_breakdown(line)
+    .filter_map(|field| {
+      if let LineField::Integer(x) = field {
+        return Some(x)
+      } else {
+        None
+      }
+    }).nth(n);
+}
+
+/// Returns the n-th integer in a line and casts it to a usize.
+pub(crate) fn nth_natural(line: &str, n: usize) -> Option<usize> {
+  return line_breakdown(line)
+    .filter_map(|field| {
+      if let LineField::Integer(x) = field {
+        return Some(x as usize)
+      } else {
+        None
+      }
+    }).nth(n);
+}
+
+/// Gets the N-th string in a line.
+pub(crate) fn nth_string(line: &str, n: usize) -> Option<&str> {
+  return line_breakdown(line)
+    .filter_map(|field| {
+      if let LineField::NoIdea(s) = field {
+        return Some(s)
+      } else {
+        None
+      }
+    }).nth(n);
+}
+
+/// Gets the N-th element type in a line.
+pub(crate) fn nth_etype(line: &str, n: usize) -> Option<ElementType> {
+  return line_breakdown(line)
+    .filter_map(|field| {
+      if let LineField::ElementType(etype) = field {
+        return Some(etype)
+      } else {
+        None
+      }
+    }).nth(n)
+}
+
+/// Decodes a side of an element, as printed ("TOP", "BOTTOM", "Z1" and so
+/// on).
+pub(crate) fn decode_side(text: &str) -> Option<ElementSide> {
+  return match text.to_ascii_uppercase().as_str() {
+    "BOTTOM" | "BOT" | "Z1" => Some(ElementSide::Bottom),
+    "TOP" | "Z2" => Some(ElementSide::Top),
+    "MID" | "MIDDLE" => Some(ElementSide::Middle),
+    _ => None
+  };
+}
+
+/// Extracts all forms given by integers followed by some floats in a line.
+/// Ignores all other fields. Useful for some kinds of tables.
+pub(crate) fn int_pattern(line: &str) -> BTreeMap<usize, Vec<f64>> {
+  let mut res: BTreeMap<usize, Vec<f64>> = BTreeMap::new();
+  let mut current_nat: Option<(usize, Vec<f64>)> = None;
+  let flush = |
+    r: &mut BTreeMap<usize, Vec<f64>>,
+    cur: &mut Option<(usize, Vec<f64>)>
+  | {
+    if let Some((i, v)) = cur.take() {
+      r.insert(i, v);
+    };
+  };
+  for field in line_breakdown(line) {
+    match field {
+      LineField::Integer(i) => {
+        flush(&mut res, &mut current_nat);
+        current_nat = Some((i as usize, Vec::new()));
+      },
+      LineField::Real(x) => {
+        if let Some((_, ref mut v)) = current_nat {
+          v.push(x);
+        }
+      },
+      _ => flush(&mut res, &mut current_nat)
+    };
+  }
+  flush(&mut res, &mut current_nat);
+  return res;
+}
+
+/// Extracts the leading run of integer-real pairs in a line, as found in
+/// "packed" tables that print several IDs and values side by side (spring
+/// forces, temperatures). Stops at the first field that breaks the pattern.
+pub(crate) fn packed_pairs(line: &str) -> Vec<(isize, f64)> {
+  let mut fields = line_breakdown(line);
+  let mut pairs = Vec::new();
+  while let (Some(LineField::Integer(i)), Some(LineField::Real(x))) =
+    (fields.next(), fields.next())
+  {
+    pairs.push((i, x));
+  }
+  return pairs;
+}
+
+/// Returns the last integer in a line.
+pub(crate) fn last_int(line: &str) -> Option<isize> {
+  return line_breakdown(line)
+    .filter_map(|f| if let LineField::Integer(i) = f { Some(i) } else { None })
+    .last()
+}
+
+/// Returns the last natural in a line.
+pub(crate) fn last_natural(line: &str) -> Option<usize> {
+  return last_int(line).map(|i| i as usize);
+}
+
+/// Checks if a character is an uppercase letter or a digit.
+fn upper_or_digit_or_special(ch: char) -> bool {
+  /// Allowed special characters in a spaced header line.
+  const SPEC: &str = "()[]-.";
+  return ch.is_ascii_uppercase() || ch.is_ascii_digit() || SPEC.contains(ch);
+}
+
+/// Turns a line made of spaced upper-case ASCII into a line of upper-case
+/// words, used for detecting block headers.
+pub(crate) fn unspace(line: &str) -> Option<String> {
+  let mut cap: usize = 0;
+  let mut last: char = ' ';
+  let mut stop_at: usize = 0;
+  for ch in line.chars() {
+    stop_at += 1;
+    if upper_or_digit_or_special(ch) {
+      if last == ' ' {
+        last = ch;
+        cap += 2;
+        continue;
+      } else {
+        // not spaced. but have we seen a lot?
+        if cap > 20 {
+          // we've seen enough, this is fine. drop the extra chars tho
+          stop_at = stop_at.saturating_sub(2);
+          break;
+        } else {
+          // nah, we've seen it too soon.
+          return None;
+        }
+      }
+    }
+    if ch == ' ' {
+      last = ch;
+      continue;
+    }
+    // bad char
+    return None;
+  }
+  if cap < 4 {
+    // too small
+    return None;
+  }
+  let mut sb = String::with_capacity(cap);
+  let mut space_run: usize = 0;
+  let mut started = false;
+  for (i, ch) in line.chars().enumerate() {
+    if i == stop_at {
+      break;
+    }
+    if ch == ' ' {
+      space_run += 1;
+    }
+    if space_run > 3 {
+      started = true;
+    }
+    if !started {
+      continue;
+    }
+    if upper_or_digit_or_special(ch) {
+      if (2..15).contains(&space_run) {
+        sb.push(' ');
+      }
+      sb.push(ch);
+      space_run = 0;
+    }
+  }
+  // keep unspaced qualifiers at the end, like "(SOLUTION SET)"
+  let tail: String = line.chars().skip(stop_at).collect();
+  let tail = tail.trim();
+  if stop_at > 0 && !tail.is_empty()
+    && tail.chars().all(|c| c == ' ' || upper_or_digit_or_special(c)) {
+    sb.push(' ');
+    sb.push_str(tail);
+  }
+  return Some(sb.trim().to_string());
+}
+
+/// Blanks out a leading `NAME = value` assignment in a line, keeping the
+/// columns of what comes after it. Eigenvector headers are printed after the
+/// frequency of their mode, like `CYCLES = 3.16E+01   R E A L   E I G E N...`.
+fn blank_assignment(line: &str) -> Option<String> {
+  let (name, rest) = line.split_once('=')?;
+  let name = name.trim();
+  if name.is_empty() || !name.chars().all(|c| c.is_ascii_uppercase()) {
+    return None;
+  }
+  let value = rest.split_whitespace().next()?;
+  decode_nasfloat(value)?;
+  let end = line.len() - rest.len() + rest.find(value)? + value.len();
+  return Some(" ".repeat(end) + &line[end..]);
+}
+
+/// Checks if a line is a whole block header printed in plain words instead of
+/// spaced-out letters (e.g. "MAXIMUM  DISPLACEMENTS"), after the carriage
+/// control character, if any. Returns it with single spaces.
+fn plain_header(line: &str) -> Option<String> {
+  let mut words = line.split_whitespace().peekable();
+  words.next_if(|w| *w == "0" || *w == "1");
+  let joined = words.collect::<Vec<_>>().join(" ");
+  let known = BlockType::all().iter()
+    .any(|bt| bt.headers().contains(&joined.as_str()));
+  return known.then_some(joined);
+}
+
+/// Checks if a line is a likely block header.
+pub(crate) fn check_header(line: &str) -> Option<String> {
+  if let Some(plain) = plain_header(line) {
+    return Some(plain);
+  }
+  // unspace it
+  let unspaced = unspace(line)
+    .or_else(|| unspace(&blank_assignment(line)?))?;
+  // check for sus words
+  if SUS_WORDS.iter().any(|w| unspaced.contains(w)) {
+    return Some(unspaced);
+  }
+  // check for element type names
+  if ElementType::all().iter().any(|et| unspaced.contains(et.name())) {
+    return Some(unspaced);
+  }
+  return None;
+}
+
+/// Reads a line, returning it without its line break along with the number
+/// of bytes it took up. Returns None at the end.
+pub(crate) fn read_raw_line<R: BufRead>(
+  reader: &mut R,
+  buf: &mut Vec<u8>
+) -> io::Result<Option<(String, u64)>> {
+  buf.clear();
+  let len = reader.read_until(b'\n', buf)?;
+  if len == 0 {
+    return Ok(None);
+  }
+  return Ok(Some((decode_raw_line(buf)?, len as u64)));
+}
+
+/// Turns the bytes of a line into a string without its line break, just like
+/// BufRead::lines does.
+pub(crate) fn decode_raw_line(raw: &[u8]) -> io::Result<String> {
+  return decode_raw_str(raw).map(str::to_owned);
+}
+
+/// Same as `decode_raw_line`, but borrows the line from the bytes instead of
+/// copying it.
+pub(crate) fn decode_raw_str(raw: &[u8]) -> io::Result<&str> {
+  let raw = match raw.strip_suffix(b"\n") {
+    Some(r) => r.strip_suffix(b"\r").unwrap_or(r),
+    None => raw
+  };
+  return std::str::from_utf8(raw)
+    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+}
@@ -0,0 +1,1060 @@
+//! The workings of f06csv, a command-line application to convert Nastran F06
+//! output to CSV. The binary only calls `run`; the conversion is public too,
+//! so the integration tests can drive it on files made in memory, which would
+//! take too long to write out and parse.
+
+#![allow(clippy::needless_return)]
+#![warn(missing_docs)]
+#![warn(clippy::missing_docs_in_private_items)]
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{ArgAction, Args, Parser, Subcommand};
+use csv::Terminator;
+use log::*;
+use f06::prelude::*;
+use f06::util::IdSelection;
+use nas_csv::from_f06::templates::all_converters;
+use nas_csv::prelude::*;
+use serde::Serialize;
+
+/// The arguments passed to the program.
+#[derive(Clone, Debug, Parser)]
+#[command(
+  name = "f06csv",
+  author,
+  version,
+  about = "A command-line utility to convert Nastran F06 output to CSV."
+)]
+pub struct Cli {
+  /// What to do.
+  #[command(subcommand)]
+  pub command: Command
+}
+
+/// The subcommands.
+#[derive(Clone, Debug, Subcommand)]
+#[allow(clippy::large_enum_variant)]
+pub enum Command {
+  /// Convert an F06 file to CSV, optionally writing a report and a manifest.
+  Convert(ConvertArgs),
+  /// Check an F06 file for internal inconsistencies and for outputs that
+  /// were requested but not found, exiting with an error if there are any.
+  Check(CheckArgs)
+}
+
+/// The exit code when inputs were left out for not being of the required
+/// flavour.
+const EXIT_FLAVOUR_MISMATCH: i32 = 4;
+
+/// What the command line can start with without being a legacy (flat)
+/// invocation, which is taken to be an implicit "convert".
+const NOT_LEGACY: &[&str] = &[
+  "convert",
+  "check",
+  "help",
+  "-h",
+  "--help",
+  "-V",
+  "--version"
+];
+
+/// Options shared by all subcommands: the input and how to parse it.
+#[derive(Clone, Debug, Args)]
+struct InputArgs {
+  /// The flavour to fall back on for what isn't detected in each file: a
+  /// solver, optionally followed by a slash and a solution type, e.g. "nx",
+  /// "mystran/sol101" or "simcenter/modes". The solver can be "auto".
+  ///
+  /// What the file itself says takes precedence.
+  #[arg(long = "flavour", visible_alias = "solver", value_name = "SPEC")]
+  flavour: Option<Flavour>,
+  /// The flavour of every file, to use instead of detecting it, in the same
+  /// form as --flavour.
+  ///
+  /// Evidence in a file that disagrees is reported as a conflict.
+  #[arg(
+    long = "force-flavour",
+    value_name = "SPEC",
+    conflicts_with = "flavour"
+  )]
+  force_flavour: Option<Flavour>,
+  /// The flavour every file must resolve to, in the same form as --flavour
+  /// (only the parts given are checked). Files that resolve to anything else
+  /// are left out, and the exit code is 4.
+  #[arg(long = "require-flavour", value_name = "SPEC")]
+  require_flavour: Option<Flavour>,
+  /// Parsing options.
+  #[command(flatten)]
+  parsing: ParsingArgs,
+  /// Post-processors to run after parsing, comma-separated, in addition to
+  /// whatever they require. Defaults to merging and sorting blocks.
+  #[arg(long = "post-process", value_delimiter = ',')]
+  post_process: Option<Vec<String>>,
+  /// The superelement ID ranges to check.
+  #[command(flatten)]
+  se_ranges: SeRangesArgs,
+  /// Fixed column layouts for the tables of solver variants this tool
+  /// doesn't know, as TOML (a [[layout]] table per table, with its header,
+  /// block type, ID range and columns). Lines that don't fit them are warned
+  /// about and skipped.
+  #[arg(long = "layouts", value_name = "PATH")]
+  layouts: Option<PathBuf>,
+  /// Output extra/debug info while parsing and converting.
+  #[arg(short = 'v', long = "verbose", verbatim_doc_comment)]
+  verbose: bool,
+  /// The names of the input F06 files.
+  ///
+  /// With more than one, each is parsed and converted on its own (with its
+  /// own flavour), into artifacts named after it: the input's name is added
+  /// to the paths asked for, e.g. "out_run1.csv" for "run1.f06". The
+  /// manifest covers them all.
+  ///
+  /// If -, reads from standard input (which must then be the only input).
+  #[arg(required = true, num_args = 1..)]
+  inputs: Vec<PathBuf>,
+}
+
+/// The inputs, each parsed on its own, and how each went.
+struct ParsedInputs {
+  /// The files that weren't left out, each with the index of its input.
+  files: Vec<(usize, F06File)>,
+  /// Each input, and the flavour it was resolved as.
+  inputs: Vec<ManifestInput>,
+  /// Whether any input was left out for its flavour.
+  rejected: bool
+}
+
+/// The arguments for a check.
+#[derive(Clone, Debug, Args)]
+pub struct CheckArgs {
+  /// Model geometry to cross-check the results against, as a connectivity
+  /// CSV with records like "GRID,gid,x,y,z" and "CROD,eid,ga,gb".
+  #[arg(long = "geometry", value_name = "PATH")]
+  geometry: Option<PathBuf>,
+  /// Input and parsing options.
+  #[command(flatten)]
+  common: InputArgs
+}
+
+/// The arguments for a conversion.
+#[derive(Clone, Debug, Args)]
+pub struct ConvertArgs {
+  /// Path to write the CSV to.
+  ///
+  /// If absent, writes it to standard output, unless other artifacts (the
+  /// reports or the manifest) were asked for, in which case no CSV is
+  /// written.
+  #[arg(short = 'o', long = "csv")]
+  output: Option<PathBuf>,
+  /// Path to write a JSON report on the parsed file to (solver, diagnostics,
+  /// blocks, unsupported headers).
+  #[arg(long = "report")]
+  report: Option<PathBuf>,
+  /// Path to write a one-page HTML summary of the run to: its subcases, the
+  /// largest displacement and von Mises stress in each, reaction totals and
+  /// message counts.
+  #[arg(long = "report-html", value_name = "PATH")]
+  report_html: Option<PathBuf>,
+  /// Path to write a JSON manifest of the other artifacts to, with their
+  /// sizes and any errors writing them.
+  #[arg(long = "manifest")]
+  manifest: Option<PathBuf>,
+  /// Stop at the first artifact that fails to be written.
+  ///
+  /// By default, the others are still written, and the exit code reflects
+  /// the failure at the end.
+  #[arg(long = "fail-fast")]
+  fail_fast: bool,
+  /// CSV blocks to write. Can be specified more than once, or comma-separated.
+  ///
+  /// You can also write the numerical IDs for shorthand.
+  ///
+  /// If absent, all blocks are written.
+  #[arg(short = 'b', long = "blocks", num_args = 0.., value_delimiter = ',')]
+  csv_blocks: Vec<CsvBlockId>,
+  /// Grid point ID filter.
+  ///
+  /// If a record has a grid point ID, only output those that contain the
+  /// specified IDs.
+  ///
+  /// Takes a comma-separated list of IDs, ranges ("100-200"), open-ended
+  /// ranges ("500-") and exclusions ("!150"), e.g. "1,5,100-200,!150".
+  /// Can be specified more than once, selecting the IDs any of them selects.
+  ///
+  /// If absent, no grid point ID filter is applied.
+  #[arg(short = 'g', long = "gids", action = ArgAction::Append)]
+  gids: Vec<IdSelection>,
+  /// Element ID filter.
+  ///
+  /// If a record has an element ID, only output those that contain the
+  /// specified IDs.
+  ///
+  /// Takes a comma-separated list of IDs, ranges ("100-200"), open-ended
+  /// ranges ("500-") and exclusions ("!150"), e.g. "1,5,100-200,!150".
+  /// Can be specified more than once, selecting the IDs any of them selects.
+  ///
+  /// If absent, no element ID filter is applied.
+  #[arg(short = 'e', long = "eids", action = ArgAction::Append)]
+  eids: Vec<IdSelection>,
+  /// Element type filter.
+  ///
+  /// If a record has an element type, only output those that contain the
+  /// specified types.
+  ///
+  /// Can be specified more than once, or comma-separated.
+  ///
+  /// If absent, no element type filter is applied.
+  #[arg(short = 't', long = "etypes", num_args = 0.., value_delimiter = ',')]
+  etypes: Vec<ElementType>,
+  /// Subcase filter.
+  ///
+  /// If a record has subcase ID, only output those that contain the
+  /// specified IDs.
+  ///
+  /// Takes a comma-separated list of IDs, ranges ("100-200"), open-ended
+  /// ranges ("500-") and exclusions ("!150"), e.g. "1,5,100-200,!150".
+  /// Can be specified more than once, selecting the IDs any of them selects.
+  ///
+  /// If absent, no subcase filter is applied.
+  #[arg(short = 's', long = "subcases", action = ArgAction::Append)]
+  subcases: Vec<IdSelection>,
+  /// Always write columns for all the extra row dimensions (side, ply, end,
+  /// station, component, step), even if no block in the output has them.
+  ///
+  /// By default, only the ones relevant to the blocks being written are
+  /// added, after the usual eleven columns.
+  #[arg(long = "fixed-schema")]
+  fixed_schema: bool,
+  /// Don't write the schema row, which otherwise comes first and describes
+  /// the layout: its version, the header style and the extra columns.
+  ///
+  /// Files split by type never get one, since they have their own layout.
+  #[arg(long = "no-schema-row")]
+  no_schema_row: bool,
+  /// Write the canonical form of the CSV, for golden-file comparisons:
+  /// sorted records, reals with nine significant digits, no metadata and a
+  /// fixed schema. The exact definition is versioned, and the version is
+  /// written in the 0-block.
+  ///
+  /// Overrides the formatting, alignment, delimiter, line break, header and
+  /// schema options. Filters still apply.
+  ///
+  /// Records are sorted a block ID and subcase at a time, so the records for
+  /// the largest of those are held in memory, unlike other outputs.
+  #[arg(long = "canonical")]
+  canonical: bool,
+  /// Split the CSV into one file per CSV block ID, named after the output
+  /// path, e.g. "out_disp.csv" and "out_stress.csv".
+  ///
+  /// Each file only gets the columns that mean something for its block ID,
+  /// without the block ID itself, and always has headers.
+  #[arg(
+    long = "split-types",
+    requires = "output",
+    conflicts_with = "canonical"
+  )]
+  split_types: bool,
+  /// Split the CSV into one file per subcase, named after the output path,
+  /// e.g. "out_sc2.csv". The 0-block goes to the output path itself.
+  ///
+  /// With --split-types, writes a file per block ID and subcase instead,
+  /// e.g. "out_disp_sc2.csv".
+  #[arg(
+    long = "split-subcases",
+    requires = "output",
+    conflicts_with = "canonical"
+  )]
+  split_subcases: bool,
+  /// Append provenance columns to every record: the line of the F06 file its
+  /// row was read from ("derived" if it wasn't read but computed), the UID
+  /// of its block and the first line of that block.
+  #[arg(long = "provenance-columns", conflicts_with = "canonical")]
+  provenance_columns: bool,
+  /// Enable writing CSV headers.
+  ///
+  /// Be warned, they're written every time there's a change.
+  #[arg(short = 'H', long = "headers")]
+  headers: bool,
+  /// Names to use in headers, instead of the usual ones: "machine" for
+  /// snake case identifiers (e.g. "normal_x"), "short" for short names (e.g.
+  /// "NormalX") or "long" for descriptive names with units (e.g. "Normal
+  /// stress X [MPa]").
+  #[arg(long = "header-style", value_name = "STYLE")]
+  header_style: Option<HeaderStyle>,
+  /// Units to put in long headers, as comma-separated pairs, e.g.
+  /// "stress=MPa,length=mm". Known quantities are length, angle, force,
+  /// moment, stress, strain and temperature; those not given are left as
+  /// their names.
+  #[arg(long = "units", value_name = "MAP")]
+  units: Option<UnitMap>,
+  /// The delimiter used in the CSV.
+  #[arg(short = 'd', long, default_value = ",", verbatim_doc_comment)]
+  delim: char,
+  /// Use CRLF (Windows) line breaks. Default is Unix (LF).
+  #[arg(long = "crlf", verbatim_doc_comment)]
+  crlf: bool,
+  /// Formatting options.
+  #[command(flatten)]
+  fmtr: CsvFormatting,
+  /// Number of threads to convert blocks on. Each thread takes a few
+  /// thousand rows at a time, and the output is the same regardless.
+  ///
+  /// If absent, uses all available cores.
+  #[cfg(feature = "parallel")]
+  #[arg(short = 'j', long = "jobs")]
+  jobs: Option<usize>,
+  /// Input and parsing options.
+  #[command(flatten)]
+  common: InputArgs
+}
+
+/// Parses a command line, taking flat ones from before the subcommands to be
+/// implicit conversions. Returns it, and whether it was one of those.
+pub fn parse_args<I>(argv: I) -> (Cli, bool)
+where
+  I: IntoIterator<Item = OsString>
+{
+  let mut argv = argv.into_iter().collect::<Vec<_>>();
+  let legacy = is_legacy(&argv);
+  if legacy {
+    argv.insert(1, OsString::from("convert"));
+  }
+  return (Cli::parse_from(argv), legacy);
+}
+
+/// Runs f06csv with a command line.
+pub fn run<I>(argv: I) -> Result<(), Box<dyn Error>>
+where
+  I: IntoIterator<Item = OsString>
+{
+  let (cli, legacy) = parse_args(argv);
+  let common = match cli.command {
+    Command::Convert(ref args) => &args.common,
+    Command::Check(ref args) => &args.common
+  };
+  common.init_logging();
+  if legacy {
+    warn!(concat!(
+      "Running f06csv without a subcommand is deprecated and will stop ",
+      "working in a future version; use \"f06csv convert\" instead."
+    ));
+  }
+  return match cli.command {
+    Command::Convert(ref args) => convert(args),
+    Command::Check(ref args) => check(args)
+  };
+}
+
+/// Returns whether a command line is a legacy one, without a subcommand.
+fn is_legacy(argv: &[OsString]) -> bool {
+  return match argv.get(1).map(|a| a.to_str()) {
+    Some(Some(first)) => !NOT_LEGACY.contains(&first),
+    // not even UTF-8, so it's a path
+    Some(None) => true,
+    None => false
+  };
+}
+
+/// Reports the flavour each input was resolved as, and leaves out those that
+/// aren't of the required one. Fails if one's not an F06 at all, and exits if
+/// none are left.
+fn accept_inputs(
+  parsed: Vec<BatchFile>
+) -> Result<ParsedInputs, Box<dyn Error>> {
+  let inputs = parsed.iter()
+    .map(BatchFile::manifest_input)
+    .collect::<Vec<_>>();
+  let mut files = Vec::new();
+  let mut rejected = false;
+  for (index, input) in parsed.into_iter().enumerate() {
+    let shown = input.path.display().to_string();
+    if let Some(ref res) = input.resolution {
+      info!(
+        "{} is {} (solver: {}; solution type: {}).",
+        shown,
+        res.flavour().spec(),
+        res.solver,
+        res.soltype
+      );
+    }
+    if let Some(mismatch) = input.flavour_mismatch() {
+      error!("Leaving {} out: {}!", shown, mismatch);
+      rejected = true;
+      continue;
+    }
+    files.push((index, input.result?));
+  }
+  if files.is_empty() {
+    error!("No inputs were left to convert!");
+    std::process::exit(EXIT_FLAVOUR_MISMATCH);
+  }
+  return Ok(ParsedInputs { files, inputs, rejected });
+}
+
+/// Returns the name an input's artifacts are told apart by, when there are
+/// several inputs: its file name, without the extension.
+fn input_name(path: &Path) -> String {
+  return path.file_stem()
+    .and_then(|s| s.to_str())
+    .unwrap_or("input")
+    .to_owned();
+}
+
+/// Returns the path to write an artifact for an input to: the path asked for,
+/// with the input's name (if there are several) added to its stem, e.g.
+/// "out.csv" becoming "out_run1.csv" for "run1.f06".
+fn input_artifact(path: &Path, input: Option<&str>) -> PathBuf {
+  let Some(input) = input else {
+    return path.to_owned();
+  };
+  let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("f06csv");
+  let name = match path.extension().and_then(|s| s.to_str()) {
+    Some(ext) => format!("{}_{}.{}", stem, input, ext),
+    None => format!("{}_{}", stem, input)
+  };
+  return path.with_file_name(name);
+}
+
+/// Exits with a distinct code if any input was left out for its flavour, once
+/// everything else was done.
+fn exit_if_rejected(rejected: bool) {
+  if rejected {
+    error!("Some inputs were left out for their flavour, see above.");
+    std::process::exit(EXIT_FLAVOUR_MISMATCH);
+  }
+}
+
+impl InputArgs {
+  /// Sets up logging, more verbose if asked to.
+  fn init_logging(&self) {
+    let log_level = if self.verbose {
+      LevelFilter::Debug
+    } else {
+      LevelFilter::Info
+    };
+    env_logger::builder().filter_level(log_level).init();
+  }
+
+  /// Returns the policy for resolving the flavour of each input.
+  fn flavour_policy(&self) -> FlavourPolicy {
+    return FlavourPolicy {
+      default: self.flavour,
+      forced: self.force_flavour,
+      required: self.require_flavour
+    };
+  }
+
+  /// Makes a parser set up with the parsing options.
+  fn parser(&self) -> Result<OnePassParser, Box<dyn Error>> {
+    let mut parser = OnePassParser::new();
+    self.parsing.configure(&mut parser);
+    if let Some(ref path) = self.layouts {
+      parser.custom_layouts(CustomLayouts::parse(&fs::read_to_string(path)?)?);
+    }
+    return Ok(parser);
+  }
+
+  /// Parses standard input, as a batch of one.
+  fn read_stdin(
+    &self,
+    policy: &FlavourPolicy
+  ) -> Result<BatchFile, Box<dyn Error>> {
+    let path = Path::new("-");
+    let stdin = BufReader::new(io::stdin());
+    return Ok(parse_flavoured(self.parser()?, path, stdin, policy));
+  }
+
+  /// Parses the input files, each on its own and with its own flavour.
+  /// Exits if one's not there or not an F06 at all, or if several would
+  /// have their artifacts named the same.
+  fn read(&self) -> Result<ParsedInputs, Box<dyn Error>> {
+    let policy = self.flavour_policy();
+    let stdin = self.inputs.iter()
+      .any(|p| p.as_os_str().eq_ignore_ascii_case("-"));
+    if stdin && self.inputs.len() > 1 {
+      error!("Standard input (-) must be the only input!");
+      std::process::exit(1);
+    }
+    let mut parsed = Vec::new();
+    if stdin {
+      parsed.push(self.read_stdin(&policy)?);
+      return accept_inputs(parsed);
+    }
+    let names = self.inputs.iter()
+      .map(|path| input_name(path))
+      .collect::<BTreeSet<_>>();
+    if names.len() < self.inputs.len() {
+      error!("The inputs must have different names, to name their outputs!");
+      std::process::exit(1);
+    }
+    for path in self.inputs.iter() {
+      if !path.is_file() {
+        error!(
+          "{} either does not exist or is not a file!",
+          path.display()
+        );
+        std::process::exit(1);
+      }
+      match path.file_name().and_then(|bn| bn.to_str()) {
+        Some(sbn) => info!("Parsing {}...", sbn),
+        None => info!("Parsing...")
+      }
+      parsed.push(parse_flavoured_file(self.parser()?, path, &policy));
+    }
+    return accept_inputs(parsed);
+  }
+
+  /// Runs the post-processors asked for, or the default ones.
+  fn post_process(&self, f06: &mut F06File) -> Result<(), Box<dyn Error>> {
+    let mut registry = PostProcessorRegistry::builtin();
+    let mut names = match self.post_process {
+      Some(ref names) => names.clone(),
+      None => DEFAULT_POST_PROCESSORS.iter().map(|s| s.to_string()).collect()
+    };
+    if let Some(ref path) = self.se_ranges.path {
+      registry.register(Box::new(CheckSeRanges::from_file(path)?))?;
+      names.push(CheckSeRanges::NAME.to_owned());
+    }
+    let diagnostics = registry.run(&names, f06)?;
+    for diag in diagnostics {
+      warn!("{}!", diag);
+    }
+    return Ok(());
+  }
+}
+
+impl ConvertArgs {
+  /// Returns whether to write a CSV: if a path was given for it, or if no
+  /// other artifact was asked for (then, to standard output).
+  fn wants_csv(&self) -> bool {
+    return self.output.is_some() || (
+      self.report.is_none()
+        && self.report_html.is_none()
+        && self.manifest.is_none()
+    );
+  }
+
+  /// Returns the schema row to write for some extra row dimensions, if any.
+  fn schema(&self, dims: &[RowDimension]) -> Option<CsvSchemaDescriptor> {
+    if self.no_schema_row {
+      return None;
+    }
+    let schema = CsvSchemaDescriptor::new(self.headers, self.header_style, dims);
+    return Some(schema.with_provenance(self.provenance_columns));
+  }
+}
+
+/// Converts the files, each on its own, writing the artifacts asked for.
+fn convert(args: &ConvertArgs) -> Result<(), Box<dyn Error>> {
+  let several = args.common.inputs.len() > 1;
+  if several && args.wants_csv() && args.output.is_none() {
+    error!("With several inputs, the CSV needs a path to name theirs after!");
+    std::process::exit(1);
+  }
+  // parse the files
+  let parsed = args.common.read()?;
+  let mut manifest = Manifest::new(None, "f06csv", env!("CARGO_PKG_VERSION"));
+  manifest.inputs = parsed.inputs;
+  for (index, mut f06) in parsed.files {
+    let name = several.then(|| input_name(&args.common.inputs[index]));
+    if let Some(ref name) = name {
+      info!("Converting {}...", name);
+    }
+    let input = name.as_deref();
+    let fingerprint = convert_one(args, &mut f06, input, &mut manifest)?;
+    if !several {
+      manifest.input = f06.filename.clone();
+      manifest.fingerprint = Some(fingerprint.clone());
+    }
+    manifest.inputs[index].fingerprint = Some(fingerprint);
+  }
+  if let Some(ref path) = args.manifest {
+    if let Err(e) = write_json(path, &manifest) {
+      error!("Failed to write the manifest to {}: {}", path.display(), e);
+      std::process::exit(1);
+    }
+    info!("Wrote the manifest to {}.", path.display());
+  }
+  if !manifest.all_ok() {
+    error!("Some artifacts could not be written, see above.");
+    std::process::exit(1);
+  }
+  exit_if_rejected(parsed.rejected);
+  info!("All done.");
+  // done
+  return Ok(());
+}
+
+/// Converts a file, writing the artifacts asked for (named after the input,
+/// if there are several) and recording them in the manifest. Returns the
+/// fingerprint of the file.
+fn convert_one(
+  args: &ConvertArgs,
+  f06: &mut F06File,
+  input: Option<&str>,
+  manifest: &mut Manifest
+) -> Result<String, Box<dyn Error>> {
+  // of the raw parse, before anything's checked or merged, like f06info's
+  let fingerprint = f06.fingerprint_hex();
+  // before merging, while line ranges are still there
+  let count = args.common.parsing.validate(f06);
+  if count > 0 {
+    return Err(format!("found {} internal inconsistencies", count).into());
+  }
+  if args.common.parsing.strict {
+    reject_non_finite(f06);
+  }
+  args.common.post_process(f06)?;
+  info!("Done parsing.");
+  info!("Parse fingerprint is {}.", fingerprint);
+  // write the artifacts
+  let named = |path: &PathBuf| input_artifact(path, input);
+  let output = args.output.as_ref().map(named);
+  if args.wants_csv() && (args.split_types || args.split_subcases) {
+    let output = output.expect("splitting the output requires an output path");
+    write_split_csv(args, &output, f06, &fingerprint, manifest);
+  } else if args.wants_csv() {
+    let res = write_csv(args, output.as_deref(), f06, &fingerprint);
+    let path = output.as_deref();
+    add_artifact(manifest, ArtifactKind::Csv, path, res, args.fail_fast);
+  }
+  if let Some(ref path) = args.report.as_ref().map(named) {
+    let report = FileReport {
+      fingerprint: Some(fingerprint.clone()),
+      ..FileReport::from(&*f06)
+    };
+    let res = write_json(path, &report);
+    let kind = ArtifactKind::Report;
+    add_artifact(manifest, kind, Some(path), res, args.fail_fast);
+  }
+  if let Some(ref path) = args.report_html.as_ref().map(named) {
+    let meta = ReportMeta::now("f06csv", env!("CARGO_PKG_VERSION"));
+    let html = RunReport::from_file(f06).to_html(&meta);
+    let res = fs::write(path, html).map_err(Box::<dyn Error>::from);
+    let kind = ArtifactKind::HtmlReport;
+    add_artifact(manifest, kind, Some(path), res, args.fail_fast);
+  }
+  return Ok(fingerprint);
+}
+
+/// Checks the files, each on its own, printing their internal
+/// inconsistencies and what the cross checks found. Exits with an error if
+/// there's anything.
+fn check(args: &CheckArgs) -> Result<(), Box<dyn Error>> {
+  let several = args.common.inputs.len() > 1;
+  let parsed = args.common.read()?;
+  let geometry = match args.geometry {
+    Some(ref path) => Some(ModelGeometry::from_csv(File::open(path)?)?),
+    None => None
+  };
+  let mut problems = 0;
+  for (index, mut f06) in parsed.files {
+    // what's found in each file is told apart by its name
+    let prefix = if several {
+      format!("{}: ", args.common.inputs[index].display())
+    } else {
+      String::new()
+    };
+    // before merging, while line ranges are still there
+    let violations = f06.check_invariants();
+    args.common.post_process(&mut f06)?;
+    info!("Done parsing.");
+    let findings = match geometry {
+      Some(ref geometry) => {
+        cross_validate(&f06, geometry, DEFAULT_SIGN_THRESHOLD)
+      },
+      None => check_requested_outputs(&f06)
+    };
+    for violation in violations.iter() {
+      println!("{}Internal inconsistency: {}", prefix, violation);
+    }
+    for finding in findings.iter() {
+      println!("{}{}", prefix, finding);
+    }
+    problems += violations.len() + findings.len();
+  }
+  if problems > 0 {
+    error!("Found {} problems.", problems);
+    std::process::exit(1);
+  }
+  exit_if_rejected(parsed.rejected);
+  info!("No problems found.");
+  return Ok(());
+}
+
+/// Stops if a file has NaNs or infinities anywhere, for strict mode.
+fn reject_non_finite(f06: &F06File) {
+  let count = f06.non_finite_count();
+  if count > 0 {
+    error!("Found {} NaN or infinite values, stopping.", count);
+    std::process::exit(1);
+  }
+}
+
+/// Records how writing an artifact went in the manifest, logging errors. If
+/// failing fast, exits on the first error.
+fn add_artifact(
+  manifest: &mut Manifest,
+  kind: ArtifactKind,
+  path: Option<&Path>,
+  res: Result<(), Box<dyn Error>>,
+  fail_fast: bool
+) {
+  let shown = path.map_or("<stdout>".to_owned(), |p| p.display().to_string());
+  let error = match res {
+    Ok(()) => {
+      info!("Wrote the {} to {}.", kind, shown);
+      None
+    },
+    Err(e) => {
+      error!("Failed to write the {} to {}: {}", kind, shown, e);
+      Some(e.to_string())
+    }
+  };
+  let bytes = match error {
+    None => path.and_then(|p| fs::metadata(p).ok()).map(|m| m.len()),
+    Some(_) => None
+  };
+  let failed = error.is_some();
+  manifest.artifacts.push(Artifact { kind, path: shown, bytes, error });
+  if failed && fail_fast {
+    error!("Stopping here, since failing fast.");
+    std::process::exit(1);
+  }
+}
+
+/// Writes something out as pretty JSON.
+fn write_json<T: Serialize>(path: &Path, t: &T) -> Result<(), Box<dyn Error>> {
+  let mut output = BufWriter::new(File::create(path)?);
+  serde_json::to_writer_pretty(&mut output, t)?;
+  output.write_all(b"\n")?;
+  output.flush()?;
+  return Ok(());
+}
+
+/// Filter only if there is at least one in the filter.
+fn lax_filter<T: PartialEq>(v: &[T], x: &Option<T>) -> bool {
+  return v.is_empty()
+    || x.is_none()
+    || x.as_ref().is_some_and(|k| v.contains(k));
+}
+
+/// Filter only if there is at least one selection.
+fn lax_select(sels: &[IdSelection], x: &Option<usize>) -> bool {
+  return sels.is_empty()
+    || x.is_none_or(|k| sels.iter().any(|s| s.contains(k)));
+}
+
+/// Should we write a record?
+fn should_write(r: &CsvRecord, a: &ConvertArgs) -> bool {
+  let f_blocks = lax_filter(&a.csv_blocks, &Some(r.block_id));
+  let f_gids = lax_select(&a.gids, &r.gid);
+  let f_eids = lax_select(&a.eids, &r.eid);
+  let f_etypes = lax_filter(&a.etypes, &r.etype);
+  let f_subcases = lax_select(&a.subcases, &r.subcase);
+  return f_gids && f_eids && f_etypes && f_subcases && f_blocks;
+}
+
+/// Returns the number of threads to convert blocks on.
+fn num_jobs(args: &ConvertArgs) -> usize {
+  #[cfg(feature = "parallel")]
+  return args.jobs.unwrap_or_else(|| {
+    return std::thread::available_parallelism().map_or(1, |n| n.get());
+  });
+  #[cfg(not(feature = "parallel"))]
+  {
+    let _ = args;
+    return 1;
+  }
+}
+
+/// Converts the blocks of the file whose CSV block ID and subcase pass a
+/// filter, in parallel if there's more than one job. The records are made
+/// as they're asked for.
+fn convert_where<'a, P>(
+  f06: &'a F06File,
+  converters: &'a BTreeMap<BlockType, BlockConverter>,
+  jobs: usize,
+  filter: P
+) -> Box<dyn Iterator<Item = CsvRecord> + 'a>
+where
+  P: Fn(CsvBlockId, usize) -> bool + 'a
+{
+  #[cfg(feature = "parallel")]
+  if jobs > 1 {
+    return Box::new(to_records_parallel_where(f06, converters, jobs, filter));
+  }
+  let _ = jobs;
+  return Box::new(to_records_where(f06, converters, filter));
+}
+
+/// Converts the whole file, in parallel if there's more than one job, with
+/// the given fingerprint in the 0-block.
+fn convert_all<'a>(
+  f06: &'a F06File,
+  fingerprint: &str,
+  converters: &'a BTreeMap<BlockType, BlockConverter>,
+  jobs: usize
+) -> Box<dyn Iterator<Item = CsvRecord> + 'a> {
+  let blocks = convert_where(f06, converters, jobs, |_, _| true);
+  let zeroth = zeroth_block_with(f06, fingerprint.to_owned());
+  return Box::new(zeroth.chain(blocks));
+}
+
+/// Converts the file and writes the CSV out, to a file or standard output.
+/// The fingerprint goes in the 0-block; it should be the one of the raw
+/// parse, so it matches what the other tools report.
+pub fn write_csv(
+  args: &ConvertArgs,
+  output: Option<&Path>,
+  f06: &F06File,
+  fingerprint: &str
+) -> Result<(), Box<dyn Error>> {
+  // open the output
+  let mut output: BufWriter<Box<dyn Write>> = BufWriter::new(
+    if let Some(op) = output {
+      Box::new(File::create(op)?)
+    } else {
+      Box::new(io::stdout())
+    }
+  );
+  // determine extra columns
+  let converters = all_converters();
+  let written_types = f06.block_types().filter(|bt| {
+    return converters.get(bt).is_some_and(|c| {
+      return lax_filter(&args.csv_blocks, &Some(c.output_block_id));
+    });
+  }).collect::<BTreeSet<_>>();
+  let stepped = f06.all_blocks(false)
+    .any(|b| b.step.is_some() && written_types.contains(&b.block_type));
+  let dims = extra_dimensions(written_types, args.fixed_schema);
+  let dims = if stepped { stepped_dimensions(dims) } else { dims };
+  let jobs = num_jobs(args);
+  // the canonical form has its own fixed layout
+  if args.canonical {
+    info!("Writing CSV records in {} form...", CANONICAL_VERSION);
+    let converters = &converters;
+    let groups = record_groups(f06, converters)
+      .into_iter()
+      .map(|(bid, sc)| {
+        let group = move |b, s| b == bid && s == sc;
+        return convert_where(f06, converters, jobs, group)
+          .filter(|rec| should_write(rec, args));
+      });
+    write_canonical_grouped(groups, &mut output)?;
+    output.flush()?;
+    return Ok(());
+  }
+  info!("Writing CSV records...");
+  let records = || {
+    return convert_all(f06, fingerprint, &converters, jobs)
+      .filter(|rec| should_write(rec, args));
+  };
+  let schema = args.schema(&dims);
+  let selection = ColumnSelection::full(dims)
+    .with_provenance(args.provenance_columns);
+  return write_records(
+    args,
+    records,
+    &selection,
+    schema.as_ref(),
+    args.headers,
+    output
+  );
+}
+
+/// What goes in a part of a split CSV, as told from its records.
+#[derive(Default)]
+struct PartContents {
+  /// The block types the records come from.
+  types: BTreeSet<BlockType>,
+  /// The headers of the records.
+  headers: BTreeSet<&'static RowHeader>,
+  /// Whether any record is for a time step or frequency.
+  stepped: bool
+}
+
+/// Converts the file and writes the CSV out split into several files, by CSV
+/// block ID and/or subcase, named after the output path. Files split by type
+/// get the natural columns and headers of the records in them. Each one is
+/// recorded in the manifest.
+///
+/// The file is converted once to see what's in each part, without keeping the
+/// records, and then each part is written on its own, converting only the
+/// blocks that go in it (again, if aligning, like `write_csv`), so records
+/// are never all held in memory. The fingerprint goes in the 0-block, as in
+/// `write_csv`.
+pub fn write_split_csv(
+  args: &ConvertArgs,
+  output: &Path,
+  f06: &F06File,
+  fingerprint: &str,
+  manifest: &mut Manifest
+) {
+  let dir = output.parent().unwrap_or(Path::new(""));
+  let stem = output.file_stem().and_then(|s| s.to_str()).unwrap_or("f06csv");
+  let ext = output.extension().and_then(|s| s.to_str()).unwrap_or("csv");
+  let converters = all_converters();
+  let jobs = num_jobs(args);
+  let (by_type, by_subcase) = (args.split_types, args.split_subcases);
+  // what's in each part; parts without records aren't written
+  let mut parts: BTreeMap<SplitKey, PartContents> = BTreeMap::new();
+  let records = convert_all(f06, fingerprint, &converters, jobs)
+    .filter(|rec| should_write(rec, args));
+  for rec in records {
+    let key = SplitKey::of(rec.block_id, rec.subcase, by_type, by_subcase);
+    let part = parts.entry(key).or_default();
+    part.types.extend(rec.block_type);
+    part.headers.insert(rec.headers);
+    part.stepped |= rec.extras.contains_key(&RowDimension::Step);
+  }
+  info!("Writing CSV records to {} files...", parts.len());
+  let zeroth = SplitKey::of(CsvBlockId::Metadata, None, by_type, by_subcase);
+  for (key, part) in parts {
+    let in_part = move |bid, sc| {
+      return SplitKey::of(bid, Some(sc), by_type, by_subcase) == key;
+    };
+    // the 0-block is only made for the part it goes in
+    let records = || {
+      return (key == zeroth)
+        .then(|| zeroth_block_with(f06, fingerprint.to_owned()))
+        .into_iter()
+        .flatten()
+        .chain(convert_where(f06, &converters, jobs, in_part))
+        .filter(|rec| should_write(rec, args));
+    };
+    let path = dir.join(key.file_name(stem, ext));
+    let dims = extra_dimensions(part.types, args.fixed_schema);
+    let dims = if part.stepped { stepped_dimensions(dims) } else { dims };
+    let (selection, schema) = if args.split_types {
+      (ColumnSelection::natural(part.headers, dims), None)
+    } else {
+      (ColumnSelection::full(dims.clone()), args.schema(&dims))
+    };
+    let selection = selection.with_provenance(args.provenance_columns);
+    let headers = args.headers || args.split_types;
+    let res = File::create(&path)
+      .map_err(Box::<dyn Error>::from)
+      .and_then(|file| {
+        let output = BufWriter::new(file);
+        return write_records(
+          args,
+          records,
+          &selection,
+          schema.as_ref(),
+          headers,
+          output
+        );
+      });
+    let kind = ArtifactKind::Csv;
+    add_artifact(manifest, kind, Some(&path), res, args.fail_fast);
+  }
+}
+
+/// Writes records out as CSV, in the selected columns, after the schema row
+/// if any. Takes a function that produces the records, since they're gone
+/// through twice when aligning.
+fn write_records<F, I, W>(
+  args: &ConvertArgs,
+  records: F,
+  selection: &ColumnSelection,
+  schema: Option<&CsvSchemaDescriptor>,
+  headers: bool,
+  output: W
+) -> Result<(), Box<dyn Error>>
+where
+  F: Fn() -> I,
+  I: Iterator<Item = CsvRecord>,
+  W: Write
+{
+  // header names, in the chosen style if any
+  let units = args.units.clone().unwrap_or_default();
+  let header_of = |rec: &CsvRecord| -> Vec<String> {
+    return match args.header_style {
+      Some(style) => selection.styled_header(rec, style, &units),
+      None => selection.header(rec).into_iter().map(str::to_owned).collect()
+    };
+  };
+  // determine padding
+  let largest: Option<usize> = if args.fmtr.align != Alignment::None {
+    records()
+      .filter_map(|rec| {
+        if rec.block_id != CsvBlockId::Metadata {
+          let h = if headers {
+            header_of(&rec).into_iter().map(|f| f.len()).max()
+          } else {
+            None
+          };
+          let n = selection.fields(rec)
+            .into_iter()
+            .map(|f| args.fmtr.to_string(f).len())
+            .max();
+          return n.max(h);
+        } else {
+          return None;
+        }
+      }).max()
+  } else {
+    None
+  };
+  // padding fn
+  let pad = |s: &str| -> String {
+    if let Some(w) = largest {
+      if s.len() > w {
+        return s.to_owned();
+      }
+      let p1 = w - s.len();
+      let ps = p1/2;
+      let pb = p1 - ps;
+      let (lpad, rpad) = match args.fmtr.align {
+        Alignment::None => return s.to_owned(),
+        Alignment::Right => (p1, 0),
+        Alignment::Left => (0, p1),
+        Alignment::Center => (pb, ps),
+      };
+      return format!(
+        "{}{}{}",
+        " ".repeat(lpad),
+        s,
+        " ".repeat(rpad),
+      );
+    } else {
+      return s.to_owned();
+    }
+  };
+  // init the csv writer
+  let delim_byte: u8 = args.delim.try_into()
+    .expect("Delimiter must not be a special character1");
+  let term = if args.crlf { Terminator::CRLF } else { Terminator::default() };
+  let mut wtr = csv::WriterBuilder::new()
+    .delimiter(delim_byte)
+    .terminator(term)
+    .from_writer(output);
+  // the schema row isn't padded, so it reads the same whatever the alignment
+  if let Some(schema) = schema {
+    wtr.write_record(schema.to_fields())?;
+  }
+  // write blocks
+  let mut last_header: Option<(Vec<String>, CsvBlockId)> = None;
+  for rec in records() {
+    if headers {
+      let cur = (header_of(&rec), rec.block_id);
+      if last_header.as_ref() != Some(&cur) {
+        // header change
+        wtr.write_record(cur.0.iter().map(|h| pad(h)))?;
+        last_header = Some(cur);
+      }
+    }
+    wtr.write_record(
+      selection.fields(rec).into_iter().map(|f| pad(&args.fmtr.to_string(f)))
+    )?;
+  }
+  wtr.flush()?;
+  return Ok(());
+}
@@ -0,0 +1,125 @@
+//! Runs the binary asking for several artifacts from one parse.
+
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use assert_cmd::Command;
+use common::{EXAMPLE, scratch};
+use serde_json::Value;
+
+/// Reads a JSON file.
+fn read_json(path: &PathBuf) -> Value {
+  return serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+}
+
+#[test]
+fn test_all_artifacts() {
+  let dir = scratch("all");
+  let csv = dir.join("out.csv");
+  let report = dir.join("report.json");
+  let manifest = dir.join("manifest.json");
+  Command::cargo_bin("f06csv").unwrap()
+    .arg("--csv").arg(&csv)
+    .arg("--report").arg(&report)
+    .arg("--manifest").arg(&manifest)
+    .arg(EXAMPLE)
+    .assert()
+    .success();
+  // the csv has the schema row, the metadata block and some data
+  let text = fs::read_to_string(&csv).unwrap();
+  let lines = text.lines().collect::<Vec<_>>();
+  assert!(lines.len() > 10);
+  assert!(lines[0].starts_with("#schema,nas-csv-v1,"));
+  assert!(lines[1].starts_with("0,Solver,"));
+  let ncols = lines[0].split(',').count();
+  assert!(lines.iter().all(|l| l.split(',').count() == ncols));
+  let fingerprint = lines.iter()
+    .find_map(|l| l.strip_prefix("0,Fingerprint,"))
+    .and_then(|rest| rest.split(',').next())
+    .expect("no fingerprint in the 0-block");
+  // the report knows the solver and has blocks
+  let report_json = read_json(&report);
+  assert_eq!(report_json["flavour"]["solver"], "Mystran");
+  assert!(report_json["subcases"].as_array().unwrap().len() == 2);
+  let blocks = report_json["blocks"].as_array().unwrap();
+  assert!(!blocks.is_empty());
+  assert!(blocks.iter().all(|b| b["rows"].as_u64().unwrap() > 0));
+  // both have the fingerprint of the raw parse, like the manifest
+  assert_eq!(report_json["fingerprint"], fingerprint);
+  // the manifest lists both, with their sizes
+  let manifest_json = read_json(&manifest);
+  assert_eq!(manifest_json["fingerprint"], fingerprint);
+  let artifacts = manifest_json["artifacts"].as_array().unwrap();
+  assert_eq!(artifacts.len(), 2);
+  assert_eq!(artifacts[0]["kind"], "csv");
+  assert_eq!(artifacts[1]["kind"], "report");
+  for (artifact, path) in artifacts.iter().zip([&csv, &report]) {
+    let size = fs::metadata(path).unwrap().len();
+    assert_eq!(artifact["bytes"].as_u64(), Some(size));
+    assert!(artifact["error"].is_null());
+  }
+}
+
+#[test]
+fn test_failed_artifact() {
+  let dir = scratch("fail");
+  let bad = dir.join("missing").join("out.csv");
+  let report = dir.join("report.json");
+  let manifest = dir.join("manifest.json");
+  // the csv fails, but the others are still written
+  Command::cargo_bin("f06csv").unwrap()
+    .arg("--csv").arg(&bad)
+    .arg("--report").arg(&report)
+    .arg("--manifest").arg(&manifest)
+    .arg(EXAMPLE)
+    .assert()
+    .failure();
+  assert!(report.is_file());
+  let manifest_json = read_json(&manifest);
+  let artifacts = manifest_json["artifacts"].as_array().unwrap();
+  assert!(artifacts[0]["error"].is_string());
+  assert!(artifacts[1]["error"].is_null());
+  // unless failing fast
+  fs::remove_file(&report).unwrap();
+  fs::remove_file(&manifest).unwrap();
+  Command::cargo_bin("f06csv").unwrap()
+    .arg("--csv").arg(&bad)
+    .arg("--report").arg(&report)
+    .arg("--manifest").arg(&manifest)
+    .arg("--fail-fast")
+    .arg(EXAMPLE)
+    .assert()
+    .failure();
+  assert!(!report.exists());
+  assert!(!manifest.exists());
+}
+
+#[test]
+fn test_html_report() {
+  let dir = scratch("html");
+  let html = dir.join("report.html");
+  let manifest = dir.join("manifest.json");
+  Command::cargo_bin("f06csv").unwrap()
+    .arg("convert")
+    .arg("--report-html").arg(&html)
+    .arg("--manifest").arg(&manifest)
+    .arg(EXAMPLE)
+    .assert()
+    .success();
+  // a whole page, with both subcases and a timestamp
+  let text = fs::read_to_string(&html).unwrap();
+  assert!(text.starts_with("<!DOCTYPE html>"));
+  assert!(text.trim_end().ends_with("</html>"));
+  assert!(text.contains("PRESSURE LOAD ON PLATE ELEMENTS"));
+  assert!(text.contains("END LOADS ON TRUSS"));
+  assert!(text.contains(" UTC.</footer>"));
+  // and no csv, since it wasn't asked for
+  let artifacts = read_json(&manifest)["artifacts"].clone();
+  let artifacts = artifacts.as_array().unwrap();
+  assert_eq!(artifacts.len(), 1);
+  assert_eq!(artifacts[0]["kind"], "html_report");
+}
@@ -0,0 +1,65 @@
+//! Helpers shared by the integration tests, and by nas_csv's tests and
+//! benchmarks, which include this by path. Each uses only some of them.
+
+#![allow(clippy::needless_return)]
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use f06::prelude::*;
+
+/// An example file with lots of different block types.
+pub const EXAMPLE: &str = concat!(
+  env!("CARGO_MANIFEST_DIR"),
+  "/../examples/mystran/SB-ALL-ELEM-TEST.F06"
+);
+
+/// Returns the binary of another package of the workspace, built from this
+/// tree. It's built here, in a target directory of its own so as not to wait
+/// on the lock cargo holds on this one while testing. That's under the
+/// temporary directory cargo gives integration tests, or the system's one
+/// for unit tests, which don't get one.
+pub fn sibling_binary(package: &str) -> PathBuf {
+  let target = option_env!("CARGO_TARGET_TMPDIR")
+    .map_or_else(std::env::temp_dir, PathBuf::from)
+    .join(package);
+  let status = Command::new(env!("CARGO"))
+    .args(["build", "--quiet", "--offline", "-p", package])
+    .arg("--target-dir")
+    .arg(&target)
+    .status()
+    .expect("couldn't run cargo");
+  assert!(status.success(), "couldn't build {}", package);
+  return target.join("debug").join(package);
+}
+
+/// Makes a fresh scratch directory for a test, in the temporary directory.
+pub fn scratch(name: &str) -> PathBuf {
+  let dir = std::env::temp_dir()
+    .join(format!("f06csv-{}-{}", name, std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  fs::create_dir_all(&dir).unwrap();
+  return dir;
+}
+
+/// Makes a many-subcase file by parsing some output and copying every one of
+/// its blocks, merged, into subcases 1 through n. Panics if the output can't
+/// be parsed.
+pub fn many_subcases(source: &str, n: usize) -> F06File {
+  let mut file = OnePassParser::parse_bufread(source.as_bytes())
+    .expect("couldn't parse the output");
+  file.merge_blocks(true);
+  let originals = std::mem::take(&mut file.blocks);
+  for subcase in 1..=n {
+    for (br, blocks) in originals.iter() {
+      let copies = blocks.iter().cloned().map(|mut b| {
+        b.subcase = subcase;
+        return b;
+      }).collect();
+      file.blocks.insert(BlockRef { subcase, ..*br }, copies);
+    }
+  }
+  return file;
+}
@@ -0,0 +1,51 @@
+//! Checks that f06csv and f06info print the same fingerprint for a file.
+
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use common::sibling_binary;
+
+/// The examples, one for each solver.
+const EXAMPLES: &[&str] = &[
+  "mystran/SB-ALL-ELEM-TEST.F06",
+  "scnastran/SB-ALL-ELEM-TEST_scnas_postexport.f06"
+];
+
+/// Returns the fingerprint a tool printed to its log.
+fn printed_fingerprint(stderr: &[u8]) -> String {
+  let stderr = String::from_utf8_lossy(stderr);
+  let line = stderr.lines()
+    .find_map(|l| l.split_once("Parse fingerprint is "))
+    .map(|(_, rest)| rest.trim_end_matches('.').to_owned());
+  return line.expect("no fingerprint was printed");
+}
+
+#[test]
+fn test_same_fingerprint_as_f06info() {
+  let examples = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../examples");
+  let f06info = sibling_binary("f06info");
+  for example in EXAMPLES {
+    let path = examples.join(example);
+    let info = Command::new(&f06info)
+      .arg(&path)
+      .output()
+      .unwrap();
+    assert!(info.status.success(), "f06info failed on {}", example);
+    let csv = Command::new(env!("CARGO_BIN_EXE_f06csv"))
+      .arg("convert")
+      .arg(&path)
+      .output()
+      .unwrap();
+    assert!(csv.status.success(), "f06csv failed on {}", example);
+    assert_eq!(
+      printed_fingerprint(&info.stderr),
+      printed_fingerprint(&csv.stderr),
+      "the fingerprints of {} differ",
+      example
+    );
+  }
+}
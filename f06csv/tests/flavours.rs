@@ -0,0 +1,128 @@
+//! Runs the binary on inputs from different solvers at once.
+
+#![allow(clippy::needless_return)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use assert_cmd::Command;
+use serde_json::Value;
+
+/// The same model, solved by MYSTRAN and by Simcenter Nastran.
+fn mixed_pair() -> [PathBuf; 2] {
+  let examples = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../examples");
+  return [
+    examples.join("mystran/SB-BUSH-01-OFFSET-2b.F06"),
+    examples.join("scnastran/SB-BUSH-01-OFFSET-2b_scnas_postexport.f06")
+  ];
+}
+
+/// Converts the mixed pair with some flags, writing a manifest, and returns
+/// the exit code, the standard error and the manifest.
+fn convert_pair(name: &str, flags: &[&str]) -> (Option<i32>, String, Value) {
+  let manifest = std::env::temp_dir()
+    .join(format!("f06csv-flavours-{}-{}.json", std::process::id(), name));
+  let out = Command::cargo_bin("f06csv").unwrap()
+    .arg("convert")
+    .args(flags)
+    .arg("--manifest").arg(&manifest)
+    .args(mixed_pair())
+    .output()
+    .unwrap();
+  let stderr = String::from_utf8(out.stderr).unwrap();
+  let json = serde_json::from_str(&fs::read_to_string(&manifest).unwrap())
+    .unwrap();
+  fs::remove_file(&manifest).unwrap();
+  return (out.status.code(), stderr, json);
+}
+
+/// Returns the flavour each input was resolved as, from a manifest.
+fn flavours(manifest: &Value) -> Vec<String> {
+  return manifest["inputs"].as_array()
+    .unwrap()
+    .iter()
+    .map(|input| input["flavour"].as_str().unwrap().to_owned())
+    .collect();
+}
+
+/// Returns where the CSV of an input of the mixed pair goes, for an output
+/// path.
+fn input_csv(csv: &Path, input: &Path) -> PathBuf {
+  let stem = csv.file_stem().unwrap().to_str().unwrap();
+  let name = input.file_stem().unwrap().to_str().unwrap();
+  return csv.with_file_name(format!("{}_{}.csv", stem, name));
+}
+
+#[test]
+fn test_mixed_flavours() {
+  // the flavour given is only a fallback, so each file gets its own
+  let csv = std::env::temp_dir()
+    .join(format!("f06csv-mixed-{}.csv", std::process::id()));
+  let (code, stderr, manifest) = convert_pair("mixed", &[
+    "-v", "--flavour", "nx", "--csv", csv.to_str().unwrap()
+  ]);
+  assert_eq!(code, Some(0));
+  assert_eq!(flavours(&manifest), ["mystran/sol101", "simcenter/sol101"]);
+  assert!(!stderr.contains("but it was resolved as"));
+  let input = &manifest["inputs"][1];
+  assert_eq!(input["resolution"]["solver"]["source"], "Banner");
+  assert!(input["error"].is_null());
+  // and each is converted on its own, with nothing merged across them
+  assert!(!stderr.contains("merge failed"));
+  assert!(manifest["fingerprint"].is_null());
+  let solvers = ["MYSTRAN", "Simcenter Nastran"];
+  for (i, (path, solver)) in mixed_pair().iter().zip(solvers).enumerate() {
+    let text = fs::read_to_string(input_csv(&csv, path)).unwrap();
+    let lines = text.lines().collect::<Vec<_>>();
+    assert_eq!(lines[1], format!("0,Solver,{}{}", solver, ",-----".repeat(8)));
+    let name = path.file_name().unwrap().to_str().unwrap();
+    assert!(lines[3].starts_with(&format!("0,Filename,{},", name)));
+    // a single subcase, with its displacements
+    assert!(lines[4].starts_with("0,#Subcases,1,"));
+    assert_eq!(lines.iter().filter(|l| l.starts_with("1,")).count(), 2);
+    assert!(manifest["inputs"][i]["fingerprint"].is_string());
+    assert_eq!(
+      manifest["artifacts"][i]["path"],
+      input_csv(&csv, path).display().to_string()
+    );
+    fs::remove_file(input_csv(&csv, path)).unwrap();
+  }
+  // forcing it is still possible, though it conflicts with the files
+  let (_, stderr, manifest) = convert_pair("forced", &[
+    "--force-flavour", "mystran"
+  ]);
+  assert_eq!(flavours(&manifest), ["mystran/sol101", "mystran/sol101"]);
+  assert!(stderr.contains("but it was resolved as MYSTRAN (user-supplied)"));
+}
+
+#[test]
+fn test_required_flavour() {
+  // files of other flavours are left out, and the rest are converted
+  let csv = std::env::temp_dir()
+    .join(format!("f06csv-flavours-{}.csv", std::process::id()));
+  let (code, stderr, manifest) = convert_pair("required", &[
+    "--require-flavour", "mystran", "--csv", csv.to_str().unwrap()
+  ]);
+  assert_eq!(code, Some(4));
+  assert_eq!(flavours(&manifest), ["mystran/sol101", "simcenter/sol101"]);
+  assert!(manifest["inputs"][0]["error"].is_null());
+  let error = manifest["inputs"][1]["error"].as_str().unwrap();
+  assert!(error.contains("resolved as simcenter/sol101"));
+  assert!(stderr.contains("Leaving"));
+  let artifacts = manifest["artifacts"].as_array().unwrap();
+  assert_eq!(artifacts.len(), 1);
+  assert!(artifacts[0]["error"].is_null());
+  // named after its input, as one of several
+  let csv = input_csv(&csv, &mixed_pair()[0]);
+  assert!(fs::read_to_string(&csv).unwrap().lines().count() > 1);
+  fs::remove_file(&csv).unwrap();
+  // with none left, nothing is converted
+  let out = Command::cargo_bin("f06csv").unwrap()
+    .args(["check", "--require-flavour", "mystran/sol103"])
+    .args(mixed_pair())
+    .output()
+    .unwrap();
+  assert_eq!(out.status.code(), Some(4));
+  let stderr = String::from_utf8(out.stderr).unwrap();
+  assert!(stderr.contains("No inputs were left"));
+}
@@ -0,0 +1,43 @@
+//! Runs the binary on a file with NaNs and infinities in it.
+
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::fs;
+
+use assert_cmd::Command;
+use common::scratch;
+use fixtures::NON_FINITE_FIXTURE;
+
+/// The fixtures shared with f06's tests.
+#[allow(dead_code)]
+#[path = "../../f06/src/fixtures.rs"]
+mod fixtures;
+
+#[test]
+fn test_non_finite_values() {
+  let dir = scratch("non-finite");
+  let path = dir.join("non_finite.f06");
+  fs::write(&path, NON_FINITE_FIXTURE).unwrap();
+  // written as words, with a warning
+  let out = Command::cargo_bin("f06csv").unwrap()
+    .arg("convert")
+    .arg(&path)
+    .assert()
+    .success();
+  let stdout = String::from_utf8_lossy(&out.get_output().stdout).to_string();
+  let stderr = String::from_utf8_lossy(&out.get_output().stderr).to_string();
+  assert!(stdout.lines().any(|l| l.contains(",NaN,")));
+  assert!(stdout.lines().any(|l| l.contains(",Inf,-Inf,")));
+  assert!(stderr.contains("4 NaN or infinite values"));
+  // refused in strict mode
+  let out = Command::cargo_bin("f06csv").unwrap()
+    .arg("convert")
+    .arg("--strict")
+    .arg(&path)
+    .assert()
+    .failure();
+  assert!(out.get_output().stdout.is_empty());
+  let _ = fs::remove_dir_all(dir);
+}
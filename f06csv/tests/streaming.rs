@@ -0,0 +1,232 @@
+//! Checks that converting huge files to CSV streams: records are made as
+//! they're written, so memory doesn't grow with the size of the output, and
+//! the streaming paths write the same as the ones that buffer everything.
+
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use common::{many_subcases, scratch};
+use f06::prelude::*;
+use f06csv::{Command, ConvertArgs, parse_args, write_csv, write_split_csv};
+use nas_csv::from_f06::templates::all_converters;
+use nas_csv::prelude::*;
+
+/// The system allocator, keeping count of the bytes in use and their peak.
+struct CountingAllocator;
+
+/// The bytes allocated right now.
+static IN_USE: AtomicUsize = AtomicUsize::new(0);
+
+/// The most bytes allocated at once since the last reset.
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    let ptr = System.alloc(layout);
+    if !ptr.is_null() {
+      let now = IN_USE.fetch_add(layout.size(), Ordering::SeqCst);
+      PEAK.fetch_max(now + layout.size(), Ordering::SeqCst);
+    }
+    return ptr;
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout);
+    IN_USE.fetch_sub(layout.size(), Ordering::SeqCst);
+  }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Keeps the tests here from running at once, so that the counts are only
+/// for one of them.
+static SERIAL: Mutex<()> = Mutex::new(());
+
+/// How much more memory writing the whole output may take, in bytes. Holding
+/// all the records at once would take over ten times this.
+const MEMORY_BUDGET: usize = 32 << 20;
+
+/// The line of a grid point in a displacements table.
+fn displacement_line(gid: usize) -> String {
+  let x = gid as f64;
+  let values = [x, -x, 1.0 / x, 0.0, x * 1e-3, 2.0]
+    .map(|v| format!("{:>14.6E}", v));
+  return format!("{:>14}      G     {}\n", gid, values.join(" "));
+}
+
+/// Makes a Simcenter-like output with displacements for some grid points,
+/// which vary with the grid point ID.
+fn displacements(grid_points: usize) -> String {
+  let mut f06 = String::from(concat!(
+    "                      Welcome to Simcenter Nastran\n",
+    "1    STREAMING TEST                                      ",
+    "SIMCENTER NASTRAN  2/11/21   PAGE     1\n",
+    "0                                                         ",
+    "                       SUBCASE 1\n",
+    " \n",
+    "                                             ",
+    "D I S P L A C E M E N T   V E C T O R\n",
+    " \n",
+    "      POINT ID.   TYPE          T1             T2             T3",
+    "             R1             R2             R3\n"
+  ));
+  for gid in 1..=grid_points {
+    f06.push_str(&displacement_line(gid));
+  }
+  return f06;
+}
+
+/// Makes a file with a displacements block in each of some subcases.
+fn synthetic_file(grid_points: usize, subcases: usize) -> F06File {
+  return many_subcases(&displacements(grid_points), subcases);
+}
+
+/// Writes records out like f06csv does by default, returning how many.
+fn write_out<I, W>(records: I, output: W) -> usize
+where
+  I: Iterator<Item = CsvRecord>,
+  W: Write
+{
+  let fmtr = CsvFormatting {
+    reals: FloatFormat::default(),
+    blanks: BlankDisplay::default(),
+    align: Alignment::None
+  };
+  let selection = ColumnSelection::full(vec![]);
+  let mut wtr = csv::Writer::from_writer(output);
+  let mut count = 0;
+  for rec in records {
+    let fields = selection.fields(rec).into_iter();
+    wtr.write_record(fields.map(|f| fmtr.to_string(f))).unwrap();
+    count += 1;
+  }
+  wtr.flush().unwrap();
+  return count;
+}
+
+/// Runs something, returning what it returned and how many more bytes were
+/// allocated at once while it ran than before.
+fn peak_growth<T, F: FnOnce() -> T>(f: F) -> (T, usize) {
+  let before = IN_USE.load(Ordering::SeqCst);
+  PEAK.store(before, Ordering::SeqCst);
+  let res = f();
+  return (res, PEAK.load(Ordering::SeqCst) - before);
+}
+
+/// Returns f06csv's arguments for a conversion, with some options.
+fn convert_args(options: &[&str]) -> ConvertArgs {
+  let argv = ["f06csv", "convert", "in-memory.f06"].iter()
+    .chain(options)
+    .map(OsString::from);
+  let (cli, _) = parse_args(argv);
+  let Command::Convert(args) = cli.command else {
+    unreachable!("not a conversion");
+  };
+  return args;
+}
+
+/// Returns the number of lines in a file.
+fn line_count(path: &Path) -> usize {
+  let file = File::open(path).unwrap();
+  return BufReader::new(file).lines().count();
+}
+
+// a million rows take a while in debug builds, so this only runs when asked
+// for, as CI does
+#[test]
+#[ignore]
+fn test_streaming_memory() {
+  let _serial = SERIAL.lock().unwrap();
+  // a million rows, and the 0-block
+  let file = synthetic_file(2000, 500);
+  let expected = 1_000_000 + zeroth_block(&file).count();
+  let fingerprint = file.fingerprint_hex();
+  let dir = scratch("streaming");
+  // the whole file, after the schema row
+  let whole = dir.join("whole.csv");
+  let args = convert_args(&[]);
+  let (res, growth) = peak_growth(|| write_csv(&args, Some(&whole), &file, &fingerprint));
+  res.unwrap();
+  assert_eq!(line_count(&whole), expected + 1);
+  assert!(growth < MEMORY_BUDGET, "whole peak grew {} bytes", growth);
+  // a part per subcase, each after its schema row, converting in parallel
+  let split = dir.join("split.csv");
+  let mut options = vec!["--split-subcases", "--csv", split.to_str().unwrap()];
+  if cfg!(feature = "parallel") {
+    options.extend(["--jobs", "4"]);
+  }
+  let args = convert_args(&options);
+  let mut manifest = Manifest::new(None, "f06csv", "test");
+  let (_, growth) = peak_growth(|| {
+    write_split_csv(&args, &split, &file, &fingerprint, &mut manifest);
+  });
+  assert!(manifest.all_ok());
+  assert_eq!(manifest.artifacts.len(), 501);
+  let lines = manifest.artifacts.iter()
+    .map(|artifact| line_count(Path::new(&artifact.path)) - 1)
+    .sum::<usize>();
+  assert_eq!(lines, expected);
+  assert!(growth < MEMORY_BUDGET, "split peak grew {} bytes", growth);
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_streaming_matches_buffered() {
+  let _serial = SERIAL.lock().unwrap();
+  let converters = all_converters();
+  // blocks longer than what a thread converts at a time
+  let file = synthetic_file(5000, 3);
+  let all = to_records(&file, &converters).collect::<Vec<_>>();
+  let mut buffered = Vec::new();
+  write_out(all.iter().cloned(), &mut buffered);
+  #[cfg(feature = "parallel")]
+  for jobs in [1, 2, 7] {
+    let mut streamed = Vec::new();
+    write_out(to_records_parallel(&file, &converters, jobs), &mut streamed);
+    assert!(streamed == buffered, "parallel output differs with {}", jobs);
+  }
+  // the canonical form, sorting a group at a time
+  let mut canonical = Vec::new();
+  write_canonical(all.iter().cloned(), &mut canonical).unwrap();
+  let mut grouped = Vec::new();
+  let groups = record_groups(&file, &converters)
+    .into_iter()
+    .map(|(bid, sc)| {
+      return to_records_where(&file, &converters, move |b, s| {
+        return b == bid && s == sc;
+      });
+    });
+  write_canonical_grouped(groups, &mut grouped).unwrap();
+  assert!(grouped == canonical, "grouped canonical output differs");
+  // split parts, one at a time
+  for (by_type, by_subcase) in [(true, false), (false, true), (true, true)] {
+    let parts = split_records(all.iter().cloned(), by_type, by_subcase);
+    let keys = split_keys(&file, &converters, by_type, by_subcase);
+    assert!(keys.iter().eq(parts.keys()));
+    let zeroth = SplitKey::of(
+      CsvBlockId::Metadata, None, by_type, by_subcase
+    );
+    for key in keys {
+      let mut streamed = Vec::new();
+      let records = zeroth_block(&file)
+        .filter(|_| zeroth == key)
+        .chain(to_records_where(&file, &converters, |b, s| {
+          return SplitKey::of(b, Some(s), by_type, by_subcase) == key;
+        }));
+      write_out(records, &mut streamed);
+      let mut expected = Vec::new();
+      write_out(parts[&key].iter().cloned(), &mut expected);
+      assert!(streamed == expected, "part {} differs", key);
+    }
+  }
+}
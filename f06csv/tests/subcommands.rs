@@ -0,0 +1,182 @@
+//! Runs the subcommands, and the flat invocation from before them.
+
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use assert_cmd::Command;
+use common::{EXAMPLE, scratch};
+
+/// A small file whose echo asks for displacements, and then whatever's added
+/// in the marked line.
+const ECHO_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    CHECK TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     1
+      TEST MODEL
+0                                        C A S E    C O N T R O L    E C H O
+                 COMMAND
+                 COUNT
+                  1        TITLE = CHECK TEST
+                  2        DISPLACEMENT = ALL
+                  3        EXTRA
+                  4      BEGIN BULK
+1    CHECK TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     2
+      TEST MODEL
+0                                                                                SUBCASE 1
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+          1011      G      1.000000E+00   0.0            0.0            0.0            0.0            0.0
+";
+
+/// Writes the echo fixture to a scratch file, with an extra echo line.
+fn echo_file(name: &str, extra: &str) -> PathBuf {
+  let path = std::env::temp_dir()
+    .join(format!("f06csv-{}-{}.f06", name, std::process::id()));
+  fs::write(&path, ECHO_FIXTURE.replace("EXTRA", extra)).unwrap();
+  return path;
+}
+
+#[test]
+fn test_legacy_is_convert() {
+  let legacy = Command::cargo_bin("f06csv").unwrap()
+    .arg(EXAMPLE)
+    .assert()
+    .success();
+  let convert = Command::cargo_bin("f06csv").unwrap()
+    .arg("convert")
+    .arg(EXAMPLE)
+    .assert()
+    .success();
+  let legacy = legacy.get_output();
+  let convert = convert.get_output();
+  assert!(!legacy.stdout.is_empty());
+  assert_eq!(legacy.stdout, convert.stdout);
+  assert!(String::from_utf8_lossy(&legacy.stderr).contains("deprecated"));
+  assert!(!String::from_utf8_lossy(&convert.stderr).contains("deprecated"));
+}
+
+#[test]
+fn test_check() {
+  // everything asked for is there
+  let good = echo_file("check-good", "SPC = 1");
+  Command::cargo_bin("f06csv").unwrap()
+    .arg("check")
+    .arg(&good)
+    .assert()
+    .success();
+  // the spc forces aren't
+  let bad = echo_file("check-bad", "SPCFORCES = ALL");
+  let out = Command::cargo_bin("f06csv").unwrap()
+    .arg("check")
+    .arg(&bad)
+    .assert()
+    .failure();
+  let stdout = String::from_utf8_lossy(&out.get_output().stdout).to_string();
+  assert!(stdout.contains("SPCFORCES"));
+  assert!(!stdout.contains("DISPLACEMENT"));
+  let _ = fs::remove_file(good);
+  let _ = fs::remove_file(bad);
+}
+
+#[test]
+fn test_custom_layouts() {
+  let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("../examples/layouts");
+  let assert = Command::cargo_bin("f06csv").unwrap()
+    .args(["convert", "--flavour", "nx", "--layouts"])
+    .arg(dir.join("inhouse_motions.toml"))
+    .arg(dir.join("inhouse_motions.f06"))
+    .assert()
+    .success();
+  let output = assert.get_output();
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  let rows = stdout.lines()
+    .filter(|l| l.starts_with("1,"))
+    .collect::<Vec<_>>();
+  assert_eq!(rows.len(), 3);
+  assert!(rows[0].starts_with("1,101,1,+1.000000E-03,+2.000000E-03"));
+  assert!(stderr.contains("Line 10 doesn't fit the layout"));
+}
+
+#[test]
+fn test_step_column() {
+  let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("../examples/scnastran/beam_transient_steps_sol109.f06");
+  let assert = Command::cargo_bin("f06csv").unwrap()
+    .args(["convert", "--headers"])
+    .arg(path)
+    .assert()
+    .success();
+  let stdout = String::from_utf8_lossy(&assert.get_output().stdout)
+    .into_owned();
+  let header = stdout.lines()
+    .find(|l| l.starts_with("Displacements,"))
+    .unwrap();
+  assert!(header.ends_with(",Step,StepKind"));
+  let steps = stdout.lines()
+    .filter(|l| l.starts_with("1,"))
+    .map(|l| l.rsplit(',').take(2).collect::<Vec<_>>())
+    .collect::<Vec<_>>();
+  assert_eq!(steps, [
+    ["TIME", "+1.000000E-02"],
+    ["TIME", "+1.000000E-02"],
+    ["TIME", "+2.000000E-02"],
+    ["TIME", "+2.000000E-02"]
+  ]);
+}
+
+#[test]
+fn test_repeated_selections() {
+  let path = echo_file("repeated", "SPC = 1");
+  let run = |args: &[&str]| {
+    let assert = Command::cargo_bin("f06csv").unwrap()
+      .arg("convert")
+      .args(args)
+      .arg(&path)
+      .assert()
+      .success();
+    return assert.get_output().stdout.clone();
+  };
+  let repeated = run(&["-g", "5", "-g", "1000-1100", "-s", "1", "-s", "2"]);
+  let joined = run(&["-g", "5,1000-1100", "-s", "1,2"]);
+  assert!(String::from_utf8_lossy(&repeated).contains("1011"));
+  assert_eq!(repeated, joined);
+  let excluded = run(&["-g", "5", "-g", "2000-"]);
+  assert!(!String::from_utf8_lossy(&excluded).contains("1011"));
+  fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_split_parts() {
+  let dir = scratch("split");
+  Command::cargo_bin("f06csv").unwrap()
+    .args(["convert", "--split-subcases", "-s", "92", "-o"])
+    .arg(dir.join("out.csv"))
+    .arg(EXAMPLE)
+    .assert()
+    .success();
+  // the parts left empty by the selection aren't written
+  assert!(dir.join("out.csv").is_file());
+  assert!(dir.join("out_sc92.csv").is_file());
+  assert!(!dir.join("out_sc91.csv").exists());
+  let part = fs::read_to_string(dir.join("out_sc92.csv")).unwrap();
+  assert!(part.lines().skip(1).all(|l| l.split(',').nth(2) == Some("92")));
+  // and by type, each gets its natural columns
+  Command::cargo_bin("f06csv").unwrap()
+    .args(["convert", "--split-types", "-o"])
+    .arg(dir.join("types.csv"))
+    .arg(EXAMPLE)
+    .assert()
+    .success();
+  let disp = fs::read_to_string(dir.join("types_disp.csv")).unwrap();
+  let header = disp.lines().next();
+  assert_eq!(header, Some("GID,Subcase,Tx,Ty,Tz,Rx,Ry,Rz,Coord"));
+  assert_eq!(disp.lines().count(), 27);
+  fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,90 @@
+//! Runs the binary on files that aren't F06s at all.
+
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use assert_cmd::Command;
+use common::{EXAMPLE, scratch, sibling_binary};
+
+/// Runs a conversion of a file, returning the exit code and the standard
+/// error.
+fn convert(path: &PathBuf) -> (Option<i32>, String) {
+  let out = Command::cargo_bin("f06csv").unwrap()
+    .arg("convert")
+    .arg(path)
+    .output()
+    .unwrap();
+  let stderr = String::from_utf8(out.stderr).unwrap();
+  return (out.status.code(), stderr);
+}
+
+#[test]
+fn test_op2_input() {
+  let mut op2 = vec![4, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0, 28, 0, 0, 0];
+  op2.extend_from_slice(b"NASTRAN FORT TAPE ID CODE - ");
+  let dir = scratch("wrong-op2");
+  let path = dir.join("model.op2");
+  fs::write(&path, &op2).unwrap();
+  let (code, stderr) = convert(&path);
+  assert_eq!(code, Some(3));
+  assert!(stderr.contains("OP2 binary results file"));
+  assert!(stderr.contains("NASTRAN FORT TAPE ID CODE"));
+  fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_deck_input() {
+  let deck = b"SOL 101\nCEND\nBEGIN BULK\nGRID,1,,0.,0.,0.\nENDDATA\n";
+  let dir = scratch("wrong-deck");
+  let path = dir.join("model.bdf");
+  fs::write(&path, deck).unwrap();
+  let (code, stderr) = convert(&path);
+  assert_eq!(code, Some(3));
+  assert!(stderr.contains("input deck"));
+  fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_wrong_file_from_stdin() {
+  let out = Command::cargo_bin("f06csv").unwrap()
+    .arg("convert")
+    .arg("-")
+    .write_stdin(&b"\x89HDF\r\n\x1a\n\0\0\0\0"[..])
+    .output()
+    .unwrap();
+  assert_eq!(out.status.code(), Some(3));
+  assert!(String::from_utf8(out.stderr).unwrap().contains("HDF5"));
+}
+
+#[test]
+fn test_other_tools_exit_alike() {
+  let deck = b"SOL 101\nCEND\nBEGIN BULK\nGRID,1,,0.,0.,0.\nENDDATA\n";
+  let dir = scratch("wrong-other");
+  let path = dir.join("other.bdf");
+  fs::write(&path, deck).unwrap();
+  let info = std::process::Command::new(sibling_binary("f06info"))
+    .arg(&path)
+    .output()
+    .unwrap();
+  let diff = std::process::Command::new(sibling_binary("f06diff"))
+    .arg(EXAMPLE)
+    .arg(&path)
+    .output()
+    .unwrap();
+  for out in [info, diff] {
+    assert_eq!(out.status.code(), Some(3));
+    assert!(String::from_utf8(out.stderr).unwrap().contains("input deck"));
+  }
+  fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_missing_input_is_generic() {
+  let path = std::env::temp_dir().join("f06csv-wrong-nonexistent.f06");
+  let (code, _) = convert(&path);
+  assert_eq!(code, Some(1));
+}
@@ -8,6 +8,7 @@
 #![allow(clippy::needless_return)] // i'll never forgive rust for this
 
 use std::collections::BTreeSet;
+use std::fs;
 use std::io::{self, BufReader};
 use std::path::PathBuf;
 
@@ -29,6 +30,20 @@ struct Cli {
   #[clap(default_value_t = 10)]
   #[arg(short = 'p')]
   print_max_flags: isize,
+  /// The superelement ID ranges to check.
+  #[command(flatten)]
+  se_ranges: SeRangesArgs,
+  /// A TOML file with the tolerance model to compare values with, instead of
+  /// the max difference and ratio: a [default] tolerance, overridden per
+  /// block type in [blocks.<type>] and per column in [columns.<type>], each
+  /// with an absolute floor, a relative tolerance, a max ULP distance and
+  /// whether "any" or "all" of them must pass.
+  #[arg(long = "criteria", value_name = "PATH")]
+  criteria_file: Option<PathBuf>,
+  /// Just print how the blocks would be paired by content (with their
+  /// similarity scores and any ambiguities), without diffing them.
+  #[arg(long = "suggest-pairing")]
+  suggest_pairing: bool,
   /// The settings for the differ.
   #[command(flatten)]
   settings: DiffSettings,
@@ -38,6 +53,58 @@ struct Cli {
   second: PathBuf
 }
 
+/// Prints a pairing of blocks by content.
+fn print_pairing(pairing: &ContentPairing) {
+  info!("Pairing of blocks by content:");
+  if pairing.pairs.is_empty() {
+    info!("{}- No blocks could be paired.", INDENT);
+  }
+  for pair in pairing.pairs.iter() {
+    info!(
+      "{}- {}: subcase {} with {} (score {:.4}, margin {:.4});",
+      INDENT,
+      pair.block_type.desc(),
+      pair.subcase_a,
+      pair.subcase_b,
+      pair.score,
+      pair.margin
+    );
+  }
+  for amb in pairing.ambiguous.iter() {
+    warn!(
+      "{}- {}: ambiguous, {}; not paired.",
+      INDENT,
+      amb.best.block_type.desc(),
+      amb
+    );
+  }
+  let unpaired = pairing.unpaired_a.iter()
+    .map(|br| (br, "first"))
+    .chain(pairing.unpaired_b.iter().map(|br| (br, "second")));
+  for (br, which) in unpaired {
+    info!(
+      "{}- {}: subcase {} in the {} file has no counterpart.",
+      INDENT,
+      br.block_type.desc(),
+      br.subcase,
+      which
+    );
+  }
+  let not_unique = pairing.not_unique_a.iter()
+    .map(|br| (br, "first"))
+    .chain(pairing.not_unique_b.iter().map(|br| (br, "second")));
+  for (br, which) in not_unique {
+    warn!(
+      "{}- {}: subcase {} in the {} file has more than one block for a \
+      step; not paired.",
+      INDENT,
+      br.block_type.desc(),
+      br.subcase,
+      which
+    );
+  }
+}
+
 fn main() -> io::Result<()> {
   // init cli stuff
   let args = Cli::parse();
@@ -47,9 +114,22 @@ fn main() -> io::Result<()> {
     LevelFilter::Info
   };
   env_logger::builder().filter_level(log_level).init();
+  // read the tolerance model, if any
+  let mut tolerance: Option<ToleranceModel> = None;
+  if let Some(ref path) = args.criteria_file {
+    match ToleranceModel::from_toml(&fs::read_to_string(path)?) {
+      Ok(model) => tolerance = Some(model),
+      Err(e) => {
+        error!("Couldn't read the criteria: {}!", e);
+        std::process::exit(1);
+      }
+    };
+  }
   // check for no ratio and no difference
   let crit = &args.settings.criteria;
-  if crit.ratio.is_none() && crit.difference.is_none() {
+  let auto_tol = args.settings.auto_tol;
+  let model = tolerance.is_some();
+  if crit.ratio.is_none() && crit.difference.is_none() && !auto_tol && !model {
     warn!("You didn't specify a max difference nor a max ratio.");
     warn!("You'll likely get no useful results, number-wise.");
   }
@@ -62,14 +142,17 @@ fn main() -> io::Result<()> {
     } else {
       info!("Loading first file...");
     }
-    OnePassParser::parse_file(&args.first)?
+    OnePassParser::parse_file(&args.first)
+      .inspect_err(|e| exit_if_wrong_file(e))?
   } else {
     error!("Second path either does not exist or is not a file!");
     std::process::exit(1);
   };
   // parse the second file
   let mut second = if args.second.as_os_str().eq_ignore_ascii_case("-") {
-    let mut f = OnePassParser::parse_bufread(BufReader::new(io::stdin()))?;
+    let stdin = BufReader::new(io::stdin());
+    let mut f = OnePassParser::parse_bufread(stdin)
+      .inspect_err(|e| exit_if_wrong_file(e))?;
     f.filename = Some("<stdin>".to_string());
     f
   } else if args.second.is_file() {
@@ -80,7 +163,8 @@ fn main() -> io::Result<()> {
     } else {
       info!("Loading first file...");
     }
-    OnePassParser::parse_file(&args.second)?
+    OnePassParser::parse_file(&args.second)
+      .inspect_err(|e| exit_if_wrong_file(e))?
   } else {
     error!("Second path either does not exist or is not a file!");
     std::process::exit(1);
@@ -90,16 +174,61 @@ fn main() -> io::Result<()> {
     .unwrap_or("the first file").to_owned();
   let mut fn2 = second.filename.as_deref()
     .unwrap_or("the second file").to_owned();
+  // check superelement IDs, before merging, to know where the blocks were
+  for diag in args.se_ranges.check([&mut first, &mut second]) {
+    warn!("{}!", diag);
+  }
   // tidy stuff up
+  let registry = PostProcessorRegistry::builtin();
   for b in [&mut first, &mut second] {
-    b.merge_blocks(true);
-    b.merge_potential_headers();
-    b.sort_all_blocks();
+    match registry.run(DEFAULT_POST_PROCESSORS, b) {
+      Ok(diagnostics) => {
+        for diag in diagnostics {
+          warn!("{}!", diag);
+        }
+      },
+      Err(e) => {
+        error!("Couldn't tidy up the files: {}!", e);
+        std::process::exit(1);
+      }
+    }
+  }
+  // just suggest a pairing?
+  if args.suggest_pairing {
+    print_pairing(&ContentPairing::propose(&first, &second));
+    return Ok(());
   }
   // generate the diff
   info!("Generating diff...");
-  let diff = F06Diff::compare(&args.settings, &first, &second);
+  let diff = F06Diff::compare_with_tolerance(
+    &args.settings,
+    tolerance.as_ref(),
+    &first,
+    &second
+  );
   info!("Done. Report follows.");
+  if let Some(ref pairing) = diff.pairing {
+    print_pairing(pairing);
+  }
+  // subcases paired by content are told along with their counterparts
+  let subcase = |sbr: &SteppedBlockRef| {
+    let br = sbr.block_ref;
+    let pair = diff.pairing.as_ref().and_then(|p| p.counterpart(br));
+    return match pair {
+      Some(pair) if pair.subcase_b != br.subcase => {
+        format!("{} (paired with {})", br.subcase, pair.subcase_b)
+      },
+      _ => br.subcase.to_string()
+    };
+  };
+  // the blocks of dynamic analyses are compared step by step
+  let block = |sbr: &SteppedBlockRef| {
+    let desc = sbr.block_ref.block_type.desc().to_lowercase();
+    return match sbr.step {
+      Some(step) => format!("{} at {}", desc, step),
+      None => desc
+    };
+  };
   // list basic file info
   info!("Basic information:");
   // solver
@@ -178,32 +307,43 @@ fn main() -> io::Result<()> {
   let pad1 = mkpad(&fn1, &fn2);
   let pad2 = mkpad(&fn2, &fn1);
   // list not compared blocks
-  if !diff.not_compared.is_empty() {
+  let all_compared = diff.not_compared.is_empty()
+    && diff.not_compared_b.is_empty();
+  if !all_compared {
     info!("Blocks that could not be compared:");
   }
-  for (br, reason) in diff.not_compared.iter() {
+  for (sbr, reason) in diff.not_compared.iter() {
     info!(
       "{}- Subcase {}, {}: {}",
       INDENT,
-      br.subcase,
-      br.block_type.desc().to_lowercase(),
+      subcase(sbr),
+      block(sbr),
+      reason
+    );
+  }
+  for (sbr, reason) in diff.not_compared_b.iter() {
+    info!(
+      "{}- Subcase {} of the second file, {}: {}",
+      INDENT,
+      sbr.block_ref.subcase,
+      block(sbr),
       reason
     );
   }
   // list compared blocks
   if diff.compared.is_empty() {
     info!("No blocks could be compared.");
-  } else if diff.not_compared.is_empty() {
+  } else if all_compared {
     info!("All blocks were compared:");
   } else {
     info!("Blocks that could be compared:");
   }
-  for (br, flags) in diff.compared.iter() {
+  for (sbr, flags) in diff.compared.iter() {
     info!(
       "{}- Subcase {}, {}:",
       INDENT,
-      br.subcase,
-      br.block_type.desc().to_lowercase()
+      subcase(sbr),
+      block(sbr)
     );
     if flags.is_empty() {
       info!("{}{}- No values flagged.", INDENT, INDENT);
@@ -4,13 +4,15 @@
 #![allow(dead_code)] // temporary
 
 use std::collections::BTreeMap;
-use std::io::{self, BufReader};
+use std::fs;
+use std::io::{self, BufRead, BufReader};
 use std::path::PathBuf;
 
 use clap::Parser;
 use f06::prelude::*;
+use f06::testing;
 use f06::util::PotentialHeader;
-use log::{LevelFilter, info, error};
+use log::{LevelFilter, info, error, warn};
 
 #[derive(Parser)]
 #[command(author, version)]
@@ -18,9 +20,66 @@ struct Cli {
   /// Disable block merging.
   #[arg(short = 'M', long)]
   no_merge: bool,
+  /// The flavour of the file, to use instead of detecting it: a solver,
+  /// optionally followed by a slash and a solution type, e.g. "nx",
+  /// "mystran/sol101" or "simcenter/modes". The solver can be "auto".
+  ///
+  /// Evidence in the file that disagrees is reported as a conflict.
+  #[arg(long = "flavour", visible_alias = "solver", value_name = "SPEC")]
+  flavour: Option<Flavour>,
+  /// Parsing options.
+  #[command(flatten)]
+  parsing: ParsingArgs,
   /// Output extra/debug info while parsing.
   #[arg(short, long)]
   verbose: bool,
+  /// Also dump every block to standard output as a table in this style.
+  #[arg(long)]
+  table_style: Option<TableMode>,
+  /// Alignment for the values in dumped tables.
+  #[arg(long, default_value = "decimal")]
+  table_align: TableAlignment,
+  /// Decimal places for reals in dumped tables (free-form if absent).
+  #[arg(long)]
+  table_precision: Option<usize>,
+  /// Write small per-block-type seed files and a fuzzing dictionary to this
+  /// directory.
+  #[arg(long, value_name = "DIR")]
+  export_seed: Option<PathBuf>,
+  /// Also print the run statistics the solver reported (times, memory, set
+  /// and matrix sizes).
+  #[arg(long)]
+  run_stats: bool,
+  /// Also print a summary of the time steps or frequencies of each subcase
+  /// of a dynamic analysis: their span, count, median interval, and the gaps
+  /// where output was skipped or the step size grew.
+  #[arg(long)]
+  transient: bool,
+  /// The expected interval between time steps or frequencies, to find gaps
+  /// against instead of the median one (see --transient).
+  #[arg(long = "expected-dt", value_name = "DT")]
+  expected_dt: Option<f64>,
+  /// Also print the byte offsets of the lines mentioned, and of the blocks
+  /// (only known for unmerged blocks, see --no-merge).
+  #[arg(long)]
+  offsets: bool,
+  /// Don't print the parse fingerprint, a hash of what was parsed that should
+  /// be the same on every machine.
+  #[arg(long)]
+  no_fingerprint: bool,
+  /// The superelement ID ranges to check.
+  #[command(flatten)]
+  se_ranges: SeRangesArgs,
+  /// Report progress to standard error while parsing, every so many lines
+  /// (e.g. --progress=5000; 100000 if not given).
+  #[arg(
+    long,
+    value_name = "LINES",
+    num_args = 0..=1,
+    require_equals = true,
+    default_missing_value = "100000"
+  )]
+  progress: Option<usize>,
   /// File path (set to "-" to read from standard input).
   file: PathBuf
 }
@@ -37,8 +96,17 @@ fn main() -> io::Result<()> {
   };
   env_logger::builder().filter_level(log_level).init();
   // parse the file
-  let mut f06: F06File = if args.file.as_os_str().eq_ignore_ascii_case("-") {
-    OnePassParser::parse_bufread(BufReader::new(io::stdin()))?
+  let mut parser = OnePassParser::new();
+  if let Some(flavour) = args.flavour {
+    parser.hint_flavour(flavour);
+  }
+  args.parsing.configure(&mut parser);
+  let parsed = if args.file.as_os_str().eq_ignore_ascii_case("-") {
+    let stdin = BufReader::new(io::stdin());
+    match args.progress {
+      Some(lines) => parse_with_progress(parser, stdin, lines, None),
+      None => parser.read_bufread(stdin)
+    }
   } else if args.file.is_file() {
     if let Some(bn) = args.file.file_name() {
       if let Some(sbn) = bn.to_str() {
@@ -47,33 +115,140 @@ fn main() -> io::Result<()> {
     } else {
       info!("Parsing...");
     }
-    OnePassParser::parse_file(&args.file)?
+    match args.progress {
+      Some(lines) => {
+        let file = fs::File::open(&args.file)?;
+        let size = file.metadata().ok().map(|m| m.len());
+        let reader = BufReader::new(file);
+        parse_with_progress(parser, reader, lines, size).map(|mut f06| {
+          f06.filename = args.file.file_name()
+            .and_then(|s| s.to_str())
+            .map(String::from);
+          return f06;
+        })
+      },
+      None => parser.read_file(&args.file)
+    }
   } else {
     error!("Provided path either does not exist or is not a file!");
     std::process::exit(1);
   };
+  let mut f06 = parsed.inspect_err(|e| exit_if_wrong_file(e))?;
   // print block & merge info
   info!("Done parsing.");
+  // of the raw parse, before anything's checked or merged, like f06csv's
+  let fingerprint = f06.fingerprint_hex();
+  let count = args.parsing.validate(&f06);
+  if count > 0 {
+    error!("Found {} internal inconsistencies, stopping.", count);
+    std::process::exit(1);
+  }
+  // export seeds before merging, while line ranges are still accurate
+  if let Some(ref dir) = args.export_seed {
+    if args.file.as_os_str().eq_ignore_ascii_case("-") {
+      error!("Can't export seeds when reading from standard input!");
+      std::process::exit(1);
+    }
+    let source = fs::read_to_string(&args.file)?;
+    fs::create_dir_all(dir)?;
+    let seeds = testing::block_seeds(&source, &f06, 3);
+    for (bt, seed) in seeds.iter() {
+      let name = format!("{}.f06", bt.snake_case_name());
+      fs::write(dir.join(name), seed)?;
+    }
+    let dict = testing::libfuzzer_dict(&testing::fuzz_dictionary());
+    fs::write(dir.join("f06.dict"), dict)?;
+    info!("Exported {} seeds to {}.", seeds.len(), dir.display());
+  }
+  // check superelement IDs, before merging, to know where the blocks were
+  args.se_ranges.check([&mut f06]);
   let solver_name = f06.flavour.solver.map_or("unknown", |s| s.name());
   let soltype = f06.flavour.soltype.map_or("unknown", |st| st.name());
   info!("Solver is {}.", solver_name);
   info!("Analysis type is {}.", soltype);
+  if !args.no_fingerprint {
+    info!("Parse fingerprint is {}.", fingerprint);
+  }
+  if f06.decimal_comma {
+    info!("Reals were written with decimal commas.");
+  }
+  let res = &f06.flavour_resolution;
+  if args.verbose || res.has_conflicts() {
+    info!("Flavour resolution:");
+    info!("{}- Solver: {}", INDENT, res.solver);
+    info!("{}- Solution type: {}", INDENT, res.soltype);
+  }
+  // print run statistics
+  if args.run_stats {
+    if f06.run_stats.is_empty() {
+      info!("No run statistics found.");
+    } else {
+      info!("Run statistics:");
+      for (label, value) in f06.run_stats.entries() {
+        info!("{}- {}: {}", INDENT, label, value);
+      }
+    }
+  }
+  // print the checks on the solution
+  if !f06.solution_diagnostics.is_empty() {
+    info!("Solution checks:");
+    for (subcase, diag) in f06.solution_diagnostics.iter() {
+      info!("{}- Subcase {}: {}", INDENT, subcase, diag);
+    }
+  }
+  if f06.ill_conditioned() {
+    warn!("The solution might be ill-conditioned, see above!");
+  }
   // print warnings
-  if f06.warnings.is_empty() {
+  let diags = f06.diagnostics.iter()
+    .filter(|d| !d.kind.is_fatal())
+    .collect::<Vec<_>>();
+  if diags.is_empty() {
     info!("No warnings found.");
   } else {
     info!("The following warnings were found:");
-    for (line, text) in f06.warnings.iter() {
-      info!("{}- Line {}: {}", INDENT, line, text);
+    for diag in diags {
+      info!("{}- {}", INDENT, diag);
+      for sample in diag.samples.iter() {
+        info!("{}{}- e.g. \"{}\"", INDENT, INDENT, sample);
+      }
+      if args.offsets && !diag.offsets.is_empty() {
+        let offsets = diag.offsets.iter()
+          .map(|o| o.to_string())
+          .collect::<Vec<_>>();
+        info!("{}{}- at bytes {}", INDENT, INDENT, offsets.join(", "));
+      }
     }
   }
+  if f06.diagnostics.overflow > 0 {
+    info!(
+      "{}- (other {} diagnostics dropped, cap of {} kinds reached)",
+      INDENT,
+      f06.diagnostics.overflow,
+      f06.diagnostics.limits.max_entries
+    );
+  }
   // print fatals
   if f06.fatal_errors.is_empty() {
     info!("No fatal errors found.");
   } else {
     info!("The following fatal errors were found:");
     for (line, text) in f06.fatal_errors.iter() {
-      info!("{}- Line {}: {}", INDENT, line, text);
+      match f06.message_offsets.get(line).filter(|_| args.offsets) {
+        Some(o) => info!("{}- Line {} (byte {}): {}", INDENT, line, o, text),
+        None => info!("{}- Line {}: {}", INDENT, line, text)
+      };
+    }
+    let total = f06.diagnostics.iter()
+      .filter(|d| d.kind.is_fatal())
+      .map(|d| d.count)
+      .sum::<usize>();
+    if total > f06.fatal_errors.len() {
+      info!(
+        "{}- ... ({} more)",
+        INDENT,
+        total - f06.fatal_errors.len()
+      );
     }
   }
   // print merge/block info
@@ -97,17 +272,61 @@ fn main() -> io::Result<()> {
     for subcase in f06.subcases() {
       info!("{}- Subcase {}:", INDENT, subcase);
       for block in f06.block_search(None, Some(subcase), false) {
+        let bytes = match block.byte_range.filter(|_| args.offsets) {
+          Some((start, end)) => format!(", bytes {}-{}", start, end),
+          None => String::new()
+        };
+        let step = match block.step {
+          Some(step) => format!(" ({})", step),
+          None => String::new()
+        };
         info!(
-          "{}{}- {}: {} rows, {} columns",
+          "{}{}- {}{}: {} rows, {} columns{}",
           INDENT,
           INDENT,
           block.block_type,
+          step,
           block.row_indexes.len(),
-          block.col_indexes.len()
+          block.col_indexes.len(),
+          bytes
         );
       }
     }
   }
+  // print the output axes, if asked to
+  if args.transient {
+    print_transient(&f06, args.expected_dt);
+  }
+  // dump the tables, if asked to
+  if let Some(mode) = args.table_style {
+    let style = TableStyle {
+      mode,
+      index: ColumnStyle {
+        alignment: TableAlignment::Left,
+        ..Default::default()
+      },
+      columns: Vec::new(),
+      default_column: ColumnStyle {
+        alignment: args.table_align,
+        precision: args.table_precision,
+        ..Default::default()
+      }
+    };
+    for block in f06.all_blocks(false) {
+      match block.step {
+        Some(step) => println!(
+          "{}, subcase {}, {}:",
+          block.block_type,
+          block.subcase,
+          step
+        ),
+        None => println!("{}, subcase {}:", block.block_type, block.subcase)
+      };
+      println!();
+      print!("{}", style.render(block));
+      println!();
+    }
+  }
   if f06.potential_headers.is_empty() {
     info!("No potential headers for unsupported blocks were found.");
   } else {
@@ -130,7 +349,17 @@ fn main() -> io::Result<()> {
         2 => format!("ines {} and {}", ph.start, ph.lines().last().unwrap()),
         _ => format!("ines {}-{}", ph.start, ph.lines().last().unwrap()),
       };
-      info!("{}- L{}: \"{}\"", INDENT, countlines, txt);
+      if args.offsets {
+        info!(
+          "{}- L{} (byte {}): \"{}\"",
+          INDENT,
+          countlines,
+          ph.offset,
+          txt
+        );
+      } else {
+        info!("{}- L{}: \"{}\"", INDENT, countlines, txt);
+      }
       if ntimes > 1 {
         info!("{}{}- (other {} occurences omitted)", INDENT, INDENT, ntimes-1);
       }
@@ -138,3 +367,85 @@ fn main() -> io::Result<()> {
   }
   return Ok(());
 }
+
+/// Logs how far a parse has gone, as a share of the size of the input if it's
+/// known.
+fn log_progress(progress: &ProgressInfo, size: Option<u64>) {
+  let read = match (progress.bytes_read, size) {
+    (Some(read), Some(size)) if size > 0 => {
+      format!("{:.0}% of the input", read as f64 * 100.0 / size as f64)
+    },
+    (Some(read), _) => format!("{} bytes", read),
+    (None, _) => "bytes unknown".to_owned()
+  };
+  info!(
+    "Parsed {} lines ({}), {} blocks so far...",
+    progress.lines_parsed,
+    read,
+    progress.blocks_completed
+  );
+}
+
+/// Parses with progress reports every so many lines, and once at the end, as
+/// a share of the size of the input if it's known.
+fn parse_with_progress<R: BufRead>(
+  parser: OnePassParser,
+  reader: R,
+  lines: usize,
+  size: Option<u64>
+) -> io::Result<F06File> {
+  let lines = lines.max(1);
+  // the last report, if it wasn't logged
+  let mut unlogged: Option<ProgressInfo> = None;
+  let report = |progress: ProgressInfo| {
+    // the parser also reports whenever a block finishes
+    if progress.lines_parsed.is_multiple_of(lines) {
+      log_progress(&progress, size);
+      unlogged = None;
+    } else {
+      unlogged = Some(progress);
+    }
+  };
+  let parsed = parser.progress_interval(lines)
+    .parse_bufread_with_progress(reader, report);
+  if let (Ok(_), Some(progress)) = (&parsed, unlogged) {
+    log_progress(&progress, size);
+  }
+  return parsed;
+}
+
+/// Prints a summary of the output axis of each subcase with time steps or
+/// frequencies.
+fn print_transient(f06: &F06File, expected_dt: Option<f64>) {
+  let axes = f06.output_axes();
+  if axes.is_empty() {
+    info!("No time steps or frequencies found.");
+    return;
+  }
+  info!("Output axes:");
+  for axis in axes.values() {
+    let summary = TransientSummary::new(axis, expected_dt);
+    let name = axis.steps.first().map_or("STEP", |s| s.name());
+    info!("{}- Subcase {}:", INDENT, summary.subcase);
+    if let Some((first, last)) = summary.span {
+      info!("{}{}- {} from {:E} to {:E}", INDENT, INDENT, name, first, last);
+    }
+    info!("{}{}- {} steps", INDENT, INDENT, summary.count);
+    if let Some(dt) = summary.median_interval {
+      info!("{}{}- Median interval is {:E}", INDENT, INDENT, dt);
+    }
+    if summary.gaps.is_empty() {
+      info!("{}{}- No gaps found", INDENT, INDENT);
+    }
+    for gap in summary.gaps.iter() {
+      info!(
+        "{}{}- Gap between {} and {} ({:.2} times the interval)",
+        INDENT,
+        INDENT,
+        gap.after_step,
+        gap.before_step,
+        gap.ratio
+      );
+    }
+  }
+}
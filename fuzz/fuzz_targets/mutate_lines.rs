@@ -0,0 +1,78 @@
+//! Structure-aware target: takes a real F06 file and mutates it line by line
+//! using the fuzzing dictionary, so that the input stays close enough to the
+//! real thing to get deep into the decoders.
+
+#![no_main]
+
+use std::sync::OnceLock;
+
+use f06::prelude::*;
+use f06::testing::{fuzz_dictionary, NUMERIC_PATTERNS};
+use libfuzzer_sys::fuzz_target;
+
+/// The base files mutations are applied to.
+const BASES: &[&str] = &[
+  include_str!("../../examples/mystran/SB-ALL-ELEM-TEST.F06"),
+  include_str!("../../examples/mystran/SB-BUSH-01-OFFSET-2b.F06"),
+  include_str!(
+    "../../examples/scnastran/SB-ALL-ELEM-TEST_scnas_postexport.f06"
+  ),
+  include_str!(
+    "../../examples/scnastran/SB-BUSH-01-OFFSET-2b_scnas_postexport.f06"
+  ),
+];
+
+/// The dictionary, built once.
+static DICT: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Applies a single mutation to the lines. Index bytes are reduced modulo
+/// the lengths, so every input is a valid sequence of mutations.
+fn mutate(lines: &mut Vec<String>, dict: &[String], op: [u8; 4]) {
+  if lines.is_empty() {
+    return;
+  }
+  let ix = ((usize::from(op[1]) << 8) | usize::from(op[2])) % lines.len();
+  let word = &dict[usize::from(op[3]) % dict.len()];
+  match op[0] % 5 {
+    // replace the line with a dictionary entry
+    0 => lines[ix] = word.clone(),
+    // insert a dictionary entry as a new line
+    1 => lines.insert(ix, word.clone()),
+    // delete the line
+    2 => { lines.remove(ix); },
+    // duplicate the line
+    3 => {
+      let line = lines[ix].clone();
+      lines.insert(ix, line);
+    },
+    // replace one whitespace-separated field with a numeric pattern
+    _ => {
+      let pat = NUMERIC_PATTERNS[usize::from(op[3]) % NUMERIC_PATTERNS.len()];
+      let mut fields: Vec<&str> = lines[ix].split_whitespace().collect();
+      if !fields.is_empty() {
+        let fi = usize::from(op[2]) % fields.len();
+        fields[fi] = pat;
+        lines[ix] = fields.join("    ");
+      }
+    }
+  };
+}
+
+fuzz_target!(|data: &[u8]| {
+  let (first, ops) = match data.split_first() {
+    Some(x) => x,
+    None => return
+  };
+  let dict = DICT.get_or_init(fuzz_dictionary);
+  let base = BASES[usize::from(*first) % BASES.len()];
+  let mut lines: Vec<String> = base.lines().map(String::from).collect();
+  for chunk in ops.chunks_exact(4) {
+    mutate(&mut lines, dict, [chunk[0], chunk[1], chunk[2], chunk[3]]);
+  }
+  let text = lines.join("\n");
+  if let Ok(mut f06) = OnePassParser::parse_bufread(text.as_bytes()) {
+    f06.merge_blocks(true);
+    f06.merge_potential_headers();
+    f06.sort_all_blocks();
+  }
+});
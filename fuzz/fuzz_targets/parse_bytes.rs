@@ -0,0 +1,17 @@
+//! Feeds arbitrary bytes to the parser, then runs the usual post-processing
+//! on whatever comes out. Memory use is bounded by libFuzzer's `-max_len` and
+//! `-rss_limit_mb`, plus the parser's default diagnostic caps.
+
+#![no_main]
+
+use f06::prelude::*;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+  // invalid utf-8 is an error, not a panic
+  if let Ok(mut f06) = OnePassParser::parse_bufread(data) {
+    f06.merge_blocks(true);
+    f06.merge_potential_headers();
+    f06.sort_all_blocks();
+  }
+});
@@ -0,0 +1,42 @@
+//! Compares converting a many-subcase file to records on one thread with
+//! converting it on several. The file is every block of the all-elements
+//! example, copied into 150 subcases.
+//!
+//! Run with `cargo bench -p nas_csv --features parallel`.
+
+#![allow(clippy::needless_return)]
+
+/// The helpers shared with the tests.
+#[path = "../../f06csv/tests/common/mod.rs"]
+mod common;
+
+use std::fs;
+
+use common::{EXAMPLE, many_subcases};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use nas_csv::from_f06::templates::all_converters;
+use nas_csv::prelude::*;
+
+/// How many subcases the blocks of the example are copied into.
+const NUM_SUBCASES: usize = 150;
+
+/// Benchmarks converting the file with different numbers of jobs.
+fn bench_scaling(c: &mut Criterion) {
+  let converters = all_converters();
+  let example = fs::read_to_string(EXAMPLE).unwrap();
+  let file = many_subcases(&example, NUM_SUBCASES);
+  let mut group = c.benchmark_group("convert_subcases");
+  group.sample_size(10);
+  group.bench_function("sequential", |b| b.iter(|| {
+    return to_records(&file, &converters).count();
+  }));
+  for jobs in [2, 4, 8] {
+    group.bench_with_input(BenchmarkId::new("parallel", jobs), &jobs, |b, &j| {
+      b.iter(|| to_records_parallel(&file, &converters, j).count());
+    });
+  }
+  group.finish();
+}
+
+criterion_group!(benches, bench_scaling);
+criterion_main!(benches);
@@ -0,0 +1,238 @@
+//! This module implements the canonical form of the CSV output: a frozen way
+//! of writing records that doesn't change with harmless run-to-run
+//! differences (row order, formatting noise, metadata), meant for golden-file
+//! regression tests.
+//!
+//! The current definition, "canonical-v2", is as follows:
+//! - the 0-block is dropped, and replaced by a single row stating the version
+//!   of the definition;
+//! - records are sorted by CSV block ID, subcase, grid point ID, element ID
+//!   and extra row dimensions, with ties broken by the remaining fields;
+//! - reals smaller in magnitude than 1e-15 are written as zeroes, and zeroes
+//!   lose their sign;
+//! - reals are written in scientific notation with nine significant digits,
+//!   rounding half to even, e.g. `+1.23456789E+02`;
+//! - NaNs and infinities are written as `NaN`, `Inf` and `-Inf`;
+//! - blanks are written as empty fields;
+//! - the columns for all extra row dimensions are always written, the time
+//!   step or frequency of dynamic analyses and its kind among them;
+//! - a header row is written before the first record, and then every time
+//!   the header changes;
+//! - fields are separated by commas, and only quoted if they have to be;
+//! - lines end in a single line feed.
+//!
+//! Any change to the above needs a new version string. The only change from
+//! "canonical-v1" is the step columns.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use f06::prelude::RowDimension;
+
+use crate::prelude::*;
+
+/// The version of the canonical form implemented here.
+pub const CANONICAL_VERSION: &str = "canonical-v2";
+
+/// The number of significant digits reals are written with.
+pub const CANONICAL_SIGNIFICANT_DIGITS: usize = 9;
+
+/// Reals smaller than this in magnitude are written as zeroes.
+pub const CANONICAL_ZERO_THRESHOLD: f64 = 1.0e-15;
+
+/// The formatting used for fields in the canonical form.
+pub const CANONICAL_FORMATTING: CsvFormatting = CsvFormatting {
+  reals: FloatFormat {
+    dec_places: Some(CANONICAL_SIGNIFICANT_DIGITS - 1),
+    no_scientific: false,
+    no_superfluous_plus: false,
+    small_e: false
+  },
+  blanks: BlankDisplay::Empty,
+  align: Alignment::None
+};
+
+/// Normalises a real: noise and negative zeroes become zeroes.
+pub fn canonical_real(x: f64) -> f64 {
+  if x.abs() < CANONICAL_ZERO_THRESHOLD {
+    return 0.0;
+  }
+  return x;
+}
+
+/// Writes out a field in the canonical form.
+pub fn canonical_field(field: CsvField) -> String {
+  let field = match field {
+    CsvField::Real(x) => CsvField::Real(canonical_real(x)),
+    other => other
+  };
+  return CANONICAL_FORMATTING.to_string(field);
+}
+
+/// Returns the row stating the version of the canonical form, which takes the
+/// place of the 0-block.
+pub fn version_record() -> CsvRecord {
+  return CsvRecord {
+    block_id: CsvBlockId::Metadata,
+    block_type: None,
+    gid: None,
+    eid: None,
+    etype: None,
+    subcase: None,
+    fields: [
+      CsvField::String("Canonical form".to_owned()),
+      CsvField::String(CANONICAL_VERSION.to_owned()),
+      CsvField::Blank,
+      CsvField::Blank,
+      CsvField::Blank,
+      CsvField::Blank,
+      CsvField::Blank,
+      CsvField::Blank,
+      CsvField::Blank,
+      CsvField::Blank
+    ],
+    headers: &[
+      "Key", "Value", HBLANK, HBLANK, HBLANK,
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ],
+    columns: [None; NAS_CSV_COLS-1],
+    extras: BTreeMap::new(),
+    provenance: None
+  };
+}
+
+/// The sort key of a canonical row: block ID, subcase, grid point ID, element
+/// ID and the extra row dimensions.
+type CanonicalKey = (
+  usize,
+  Option<usize>,
+  Option<usize>,
+  Option<usize>,
+  Vec<String>
+);
+
+/// A record already written out in the canonical form.
+struct CanonicalRow {
+  /// What it's sorted by.
+  key: CanonicalKey,
+  /// The header fields.
+  header: Vec<String>,
+  /// The fields themselves.
+  fields: Vec<String>
+}
+
+impl CanonicalRow {
+  /// Writes out a record.
+  fn new(record: CsvRecord) -> Self {
+    let dims = RowDimension::extras();
+    let extras = dims.iter()
+      .map(|d| record.extras.get(d).cloned().unwrap_or(CsvField::Blank))
+      .map(canonical_field)
+      .collect();
+    let key = (
+      usize::from(record.block_id),
+      record.subcase,
+      record.gid,
+      record.eid,
+      extras
+    );
+    let header = record.header_with(dims).map(str::to_owned).collect();
+    let fields = record.to_fields_with(dims).map(canonical_field).collect();
+    return Self { key, header, fields };
+  }
+}
+
+/// Quotes a field, if it has to be.
+fn quoted(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    return format!("\"{}\"", field.replace('"', "\"\""));
+  }
+  return field.to_owned();
+}
+
+/// Writes a line of fields.
+fn write_line<W: Write>(out: &mut W, fields: &[String]) -> io::Result<()> {
+  let line = fields.iter()
+    .map(|f| quoted(f))
+    .collect::<Vec<_>>()
+    .join(",");
+  return writeln!(out, "{}", line);
+}
+
+/// Writes canonical rows out, with a header row whenever it changes.
+struct CanonicalWriter<'w, W: Write> {
+  /// Where it's all written to.
+  out: &'w mut W,
+  /// The last header written, if any.
+  last_header: Option<Vec<String>>
+}
+
+impl<'w, W: Write> CanonicalWriter<'w, W> {
+  /// Starts writing, with the row stating the version.
+  fn new(out: &'w mut W) -> io::Result<Self> {
+    let mut writer = Self { out, last_header: None };
+    writer.write_row(&CanonicalRow::new(version_record()))?;
+    return Ok(writer);
+  }
+
+  /// Writes a row, after its header if it changed.
+  fn write_row(&mut self, row: &CanonicalRow) -> io::Result<()> {
+    if self.last_header.as_ref() != Some(&row.header) {
+      write_line(self.out, &row.header)?;
+      self.last_header = Some(row.header.clone());
+    }
+    return write_line(self.out, &row.fields);
+  }
+
+  /// Sorts some records and writes them out. The 0-block is dropped.
+  fn write_sorted<I>(&mut self, records: I) -> io::Result<()>
+  where
+    I: IntoIterator<Item = CsvRecord>
+  {
+    let mut rows = records.into_iter()
+      .filter(|rec| rec.block_id != CsvBlockId::Metadata)
+      .map(CanonicalRow::new)
+      .collect::<Vec<_>>();
+    rows.sort_by(|a, b| {
+      return a.key.cmp(&b.key).then_with(|| a.fields.cmp(&b.fields));
+    });
+    for row in rows.iter() {
+      self.write_row(row)?;
+    }
+    return Ok(());
+  }
+}
+
+/// Writes records out in the canonical form (see the module docs). They're
+/// all held in memory to be sorted; see `write_canonical_grouped` for a way
+/// not to.
+pub fn write_canonical<I, W>(records: I, out: &mut W) -> io::Result<()>
+where
+  I: IntoIterator<Item = CsvRecord>,
+  W: Write
+{
+  let mut writer = CanonicalWriter::new(out)?;
+  writer.write_sorted(records)?;
+  return Ok(());
+}
+
+/// Writes records out in the canonical form, like `write_canonical`, but
+/// sorting them a group at a time. Each group must have all the records for
+/// some CSV block IDs and subcases, and the groups must come in the order of
+/// those, like the ones from `record_groups`. Only a group's records are held
+/// in memory at a time.
+pub fn write_canonical_grouped<G, I, W>(
+  groups: G,
+  out: &mut W
+) -> io::Result<()>
+where
+  G: IntoIterator<Item = I>,
+  I: IntoIterator<Item = CsvRecord>,
+  W: Write
+{
+  let mut writer = CanonicalWriter::new(out)?;
+  for group in groups {
+    writer.write_sorted(group)?;
+  }
+  return Ok(());
+}
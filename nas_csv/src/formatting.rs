@@ -3,7 +3,7 @@
 use std::fmt::{Display, Write};
 
 use clap::{Args, ValueEnum};
-use f06::util::fmt_f64;
+use f06::util::{fmt_f64, non_finite_name};
 use serde::{Deserialize, Serialize};
 
 use crate::prelude::*;
@@ -41,6 +41,9 @@ impl Default for FloatFormat {
 impl FloatFormat {
   /// Wrties an f64 into a formatter.
   pub fn fmt_f64<W: Write>(&self, f: &mut W, x: f64) -> std::fmt::Result {
+    if let Some(name) = non_finite_name(x) {
+      return write!(f, "{}", name);
+    }
     if self.no_scientific {
       return match (self.dec_places, self.no_superfluous_plus) {
         (None, true) => write!(f, "{}", x),
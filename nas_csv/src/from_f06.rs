@@ -1,11 +1,15 @@
 //! This module contains types and subroutines to produce CSV-ready data from
 //! parsed F06 files.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Display;
 
 use f06::prelude::*;
 use log::error;
+#[cfg(feature = "parallel")]
+use rayon::ThreadPoolBuilder;
+#[cfg(feature = "parallel")]
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use serde::{Serialize, Deserialize};
 
 use crate::layout::*;
@@ -101,10 +105,32 @@ pub enum ColumnGenerator {
   /// Output a constant string.
   ConstantString(&'static str),
   /// Runs another generator, with a default for errors.
-  WithDefault(&'static ColumnGenerator, &'static CsvField)
+  WithDefault(&'static ColumnGenerator, &'static CsvField),
+  /// Output a block metadata value by key (blank if absent).
+  BlockMetadata(&'static str),
+  /// Output a block metadata value by key as a real (blank if absent or not
+  /// a number).
+  RealMetadata(&'static str),
+  /// Output the label a column's value codes for, as an index into a list
+  /// of labels (blank if it's not one of them). Errs if absent.
+  ValueLabel(NasIndex, &'static [&'static str]),
+  /// Output a column's value as a natural number, for IDs kept among reals
+  /// (blank if it's not one). Errs if absent.
+  NaturalValue(NasIndex)
 }
 
 impl ColumnGenerator {
+  /// Returns the column of the block this generator outputs, if any.
+  pub fn column(&self) -> Option<NasIndex> {
+    return match self {
+      Self::ColumnValue(col)
+        | Self::ValueLabel(col, _)
+        | Self::NaturalValue(col) => Some(*col),
+      Self::WithDefault(g, _) => g.column(),
+      _ => None
+    };
+  }
+
   /// Calls the generator to produce a CSV field, or an error.
   pub fn convert(&self,
     block: &FinalBlock,
@@ -141,6 +167,40 @@ impl ColumnGenerator {
       Self::ConstantString(s) => s.to_string().into(),
       Self::WithDefault(g, d) => {
         g.convert(block, flavour, row).unwrap_or((*d).clone())
+      },
+      Self::BlockMetadata(key) => match block.metadata.get(*key) {
+        Some(val) => val.clone().into(),
+        None => ().into(),
+      },
+      Self::RealMetadata(key) => {
+        match block.metadata.get(*key).and_then(|s| s.parse::<f64>().ok()) {
+          Some(x) => x.into(),
+          None => ().into(),
+        }
+      },
+      Self::ValueLabel(col, labels) => match block.get(row, *col) {
+        Some(x) => {
+          let code = f64::from(x);
+          let label = (code >= 0.0 && code.fract() == 0.0)
+            .then(|| labels.get(code as usize))
+            .flatten();
+          match label {
+            Some(label) => label.to_string().into(),
+            None => ().into()
+          }
+        },
+        None => return Err(ConversionError::MissingDatum { row, col: *col }),
+      },
+      Self::NaturalValue(col) => match block.get(row, *col) {
+        Some(x) => {
+          let id = f64::from(x);
+          if id >= 0.0 && id.fract() == 0.0 {
+            (id as usize).into()
+          } else {
+            ().into()
+          }
+        },
+        None => return Err(ConversionError::MissingDatum { row, col: *col }),
       }
     });
   }
@@ -154,10 +214,14 @@ pub struct BlockConverter {
   /// The type of CSV block this produces.
   pub output_block_id: CsvBlockId,
   /// Contains row generators, because a single data block row might produce
-  /// more than one CSV row.
+  /// more than one CSV row. Blocks without any of the columns a row generator
+  /// outputs (without a default) don't produce its rows.
   pub generators: &'static [RowGenerator],
   /// The headers for the row this produces.
-  pub headers: &'static [RowHeader]
+  pub headers: &'static [RowHeader],
+  /// Generators for the extra row dimension of the block type (see its
+  /// schema), one per row generator. Empty if it has none.
+  pub extras: &'static [ColumnGenerator]
 }
 
 impl BlockConverter {
@@ -168,6 +232,27 @@ impl BlockConverter {
     &'a self,
     block: &'a FinalBlock,
     flavour: &'a Flavour
+  ) -> Result<impl Iterator<Item = CsvRecord> + 'a, ConversionError> {
+    return self.convert_rows(block, flavour, block.row_indexes.keys());
+  }
+
+  /// Returns whether a row generator produces rows for a block: if it
+  /// outputs any of its columns, or no columns without a default at all.
+  fn generates(gens: &RowGenerator, block: &FinalBlock) -> bool {
+    let mut cols = gens.iter()
+      .filter(|g| !matches!(g, ColumnGenerator::WithDefault(..)))
+      .filter_map(ColumnGenerator::column)
+      .peekable();
+    return cols.peek().is_none()
+      || cols.any(|c| block.col_indexes.contains_key(&c));
+  }
+
+  /// Begins conversion of some rows of a block, like `convert_block`.
+  fn convert_rows<'a, R: Iterator<Item = &'a NasIndex> + 'a>(
+    &'a self,
+    block: &'a FinalBlock,
+    flavour: &'a Flavour,
+    rows: R
   ) -> Result<impl Iterator<Item = CsvRecord> + 'a, ConversionError> {
     if block.block_type != self.input_block_type {
       return Err(
@@ -177,8 +262,11 @@ impl BlockConverter {
         }
       );
     }
-    return Ok(block.row_indexes.keys().flat_map(|row| {
-      self.generators.iter().enumerate().map(|(irow, gens)| {
+    return Ok(rows.flat_map(|row| {
+      let produced = self.generators.iter()
+        .enumerate()
+        .filter(|(_, gens)| Self::generates(gens, block));
+      produced.map(|(irow, gens)| {
         let headers = &self.headers[irow];
         let mut fields: [CsvField; NAS_CSV_COLS-1] = [
           CsvField::Blank,
@@ -240,6 +328,26 @@ impl BlockConverter {
           fields[i] = flderr;
         }
         etype = etype.or(self.input_block_type.elem_type());
+        let mut extras: BTreeMap<RowDimension, CsvField> = BTreeMap::new();
+        if let Some(egen) = self.extras.get(irow) {
+          let dim = self.input_block_type.schema().row_dimension;
+          let fld = egen.convert(block, *flavour, *row).unwrap_or_else(|e| {
+            error!(
+              "Error found when doing the {} of {} in the {} block: {}.",
+              dim,
+              *row,
+              block.block_type.short_name(),
+              e
+            );
+            "<ERROR>".to_owned().into()
+          });
+          extras.insert(dim, fld);
+        }
+        if let Some(step) = block.step {
+          extras.insert(RowDimension::Step, CsvField::Real(step.value()));
+          let kind = CsvField::String(step.name().to_owned());
+          extras.insert(RowDimension::StepKind, kind);
+        }
         return CsvRecord {
           block_id: self.output_block_id,
           block_type: Some(block.block_type),
@@ -248,16 +356,28 @@ impl BlockConverter {
           etype,
           subcase,
           fields,
-          headers
+          headers,
+          columns: std::array::from_fn(|i| gens[i].column()),
+          extras,
+          provenance: Some(Provenance::of_row(block, *row))
         }
       })
     }));
   }
 }
 
-/// Generates the 0-block for a file.
+/// Generates the 0-block for a file, fingerprinting it as it is now.
 pub fn zeroth_block(
   file: &F06File
+) -> impl Iterator<Item = CsvRecord> + '_ {
+  return zeroth_block_with(file, file.fingerprint_hex());
+}
+
+/// Generates the 0-block for a file, with a fingerprint taken before, like
+/// the one of the raw parse, so it matches what other tools report.
+pub fn zeroth_block_with(
+  file: &F06File,
+  fingerprint: String
 ) -> impl Iterator<Item = CsvRecord> + '_ {
   /// Name for unknown values
   const U: &str = "Unknown";
@@ -273,12 +393,21 @@ pub fn zeroth_block(
     ("#Subcases", Some(file.subcases().count().to_string())),
     ("#Warnings", Some(file.warnings.len().to_string())),
     ("#Fatals", Some(file.fatal_errors.len().to_string())),
+    ("Fingerprint", Some(fingerprint)),
     ("f06csv version", option_env!("CARGO_PKG_VERSION").map(ts)),
     ("f06csv authors", option_env!("CARGO_PKG_AUTHORS").map(ts)),
     ("Part of", Some("the MYSTRAN project".to_owned()))
   ];
+  // and the run statistics and solution checks, if any
+  let stats = file.run_stats.entries()
+    .into_iter()
+    .chain(file.solution_diagnostic_entries())
+    .map(|(k, v)| (k, Some(v)));
+  let vvk = vvk.into_iter()
+    .map(|(k, v)| (k.to_owned(), v))
+    .chain(stats);
   // make it into fields
-  return vvk.into_iter().map(|(k, v)| CsvRecord {
+  return vvk.map(|(k, v)| CsvRecord {
     block_id: CsvBlockId::Metadata,
     block_type: None,
     gid: None,
@@ -286,7 +415,7 @@ pub fn zeroth_block(
     etype: None,
     subcase: None,
     fields: [
-      CsvField::String(k.to_owned()),
+      CsvField::String(k),
       CsvField::String(v.unwrap_or(U.to_owned())),
       CsvField::Blank,
       CsvField::Blank,
@@ -300,17 +429,51 @@ pub fn zeroth_block(
     headers: &[
       "Key", "Value", HBLANK, HBLANK, HBLANK,
       HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
-    ]
+    ],
+    columns: [None; NAS_CSV_COLS-1],
+    extras: BTreeMap::new(),
+    provenance: None
   })
 }
 
-/// Generates all CSV records for a file.
-pub fn to_records<'s>(
+/// Returns the extra row dimensions to write columns for, in order. If the
+/// schema is fixed, that's all of them; otherwise, only those relevant to the
+/// block types passed. Steps aren't tied to block types, so callers add that
+/// one themselves if some block has it (see `stepped_dimensions`).
+pub fn extra_dimensions<I: IntoIterator<Item = BlockType>>(
+  block_types: I,
+  fixed_schema: bool
+) -> Vec<RowDimension> {
+  if fixed_schema {
+    return RowDimension::extras().to_vec();
+  }
+  return block_types.into_iter()
+    .map(|bt| bt.schema().row_dimension)
+    .filter(RowDimension::is_extra)
+    .collect::<BTreeSet<_>>()
+    .into_iter()
+    .collect();
+}
+
+/// Adds the step and its kind to some extra row dimensions, if they aren't
+/// there yet, keeping them in order. For when any of the blocks written has a
+/// step.
+pub fn stepped_dimensions(mut dims: Vec<RowDimension>) -> Vec<RowDimension> {
+  for dim in [RowDimension::Step, RowDimension::StepKind] {
+    if !dims.contains(&dim) {
+      dims.push(dim);
+    }
+  }
+  dims.sort();
+  return dims;
+}
+
+/// Returns the blocks of a file that have converters, in the order their
+/// records are written: by output CSV block ID, then by block reference.
+fn ordered_blocks<'s>(
   file: &'s F06File,
-  converters: &'s BTreeMap<BlockType, BlockConverter>
-) -> impl Iterator<Item = CsvRecord> + 's {
-  // zeroth block
-  let zeroth = zeroth_block(file);
+  converters: &BTreeMap<BlockType, BlockConverter>
+) -> Vec<&'s FinalBlock> {
   // sort the block refs by the output csv block id
   let mut block_refs = file.blocks.keys().collect::<Vec<_>>();
   block_refs.sort_by_key(
@@ -318,13 +481,169 @@ pub fn to_records<'s>(
       .map(|c| usize::from(c.output_block_id))
       .unwrap_or(0)
   );
-  // get the blocks in the correct order
-  let blocks = block_refs.into_iter()
-    .flat_map(|br| file.blocks.get(br).unwrap())
-    .filter_map(
-      |b| converters.get(&b.block_type).map(
-        |c| c.convert_block(b, &file.flavour)
-      )
-    ).flatten();
-  return zeroth.chain(blocks.flatten());
+  // get the blocks in the correct order, the steps of dynamic analyses in
+  // their own order
+  return block_refs.into_iter()
+    .flat_map(|br| {
+      let mut blocks = file.blocks.get(br).unwrap().iter().collect::<Vec<_>>();
+      blocks.sort_by(|a, b| {
+        return a.step.cmp(&b.step);
+      });
+      return blocks;
+    })
+    .filter(|b| converters.contains_key(&b.block_type))
+    .collect();
+}
+
+/// Generates the CSV records for the blocks of a file whose output CSV block
+/// ID and subcase pass a filter, lazily and in the order `to_records` would
+/// produce them in. The 0-block isn't included. Every template writes the
+/// subcase of its block, so this is how to get some of the records without
+/// converting the others.
+pub fn to_records_where<'s, P>(
+  file: &'s F06File,
+  converters: &'s BTreeMap<BlockType, BlockConverter>,
+  filter: P
+) -> impl Iterator<Item = CsvRecord> + 's
+where
+  P: Fn(CsvBlockId, usize) -> bool + 's
+{
+  return ordered_blocks(file, converters)
+    .into_iter()
+    .filter_map(move |b| {
+      let converter = converters.get(&b.block_type)?;
+      if !filter(converter.output_block_id, b.subcase) {
+        return None;
+      }
+      return converter.convert_block(b, &file.flavour).ok();
+    })
+    .flatten();
+}
+
+/// Returns the output CSV block IDs and subcases of the blocks of a file that
+/// have converters, in the order their records are written. Records are
+/// sorted by these first in the canonical form, for instance.
+pub fn record_groups(
+  file: &F06File,
+  converters: &BTreeMap<BlockType, BlockConverter>
+) -> BTreeSet<(CsvBlockId, usize)> {
+  return file.blocks.keys()
+    .filter_map(|br| {
+      let converter = converters.get(&br.block_type)?;
+      return Some((converter.output_block_id, br.subcase));
+    })
+    .collect();
+}
+
+/// Generates all CSV records for a file, lazily.
+pub fn to_records<'s>(
+  file: &'s F06File,
+  converters: &'s BTreeMap<BlockType, BlockConverter>
+) -> impl Iterator<Item = CsvRecord> + 's {
+  return zeroth_block(file)
+    .chain(to_records_where(file, converters, |_, _| true));
+}
+
+// the converters are all `&'static` data and function pointers, with no
+// interior mutability, so they can be shared between threads. this makes
+// sure it stays that way.
+#[cfg(feature = "parallel")]
+const _: fn() = || {
+  /// Only compiles if the type can be shared between threads.
+  fn is_sync<T: Send + Sync>() {}
+  is_sync::<ColumnGenerator>();
+  is_sync::<BlockConverter>();
+  is_sync::<F06File>();
+  is_sync::<CsvRecord>();
+};
+
+/// How many rows of a block a thread converts at a time in the parallel
+/// conversion. At most this many records per thread are held in memory.
+#[cfg(feature = "parallel")]
+pub const PARALLEL_CHUNK_ROWS: usize = 4096;
+
+/// Some consecutive rows of a block, converted by a thread at a time.
+#[cfg(feature = "parallel")]
+#[derive(Copy, Clone)]
+struct RowChunk<'s> {
+  /// The block.
+  block: &'s FinalBlock,
+  /// Its converter.
+  converter: &'s BlockConverter,
+  /// The first row in the chunk.
+  first: NasIndex
+}
+
+#[cfg(feature = "parallel")]
+impl RowChunk<'_> {
+  /// Converts the rows in the chunk.
+  fn convert(&self, flavour: &Flavour) -> Vec<CsvRecord> {
+    let rows = self.block.row_indexes.range(self.first..)
+      .map(|(row, _)| row)
+      .take(PARALLEL_CHUNK_ROWS);
+    return self.converter.convert_rows(self.block, flavour, rows)
+      .map(|it| it.collect())
+      .unwrap_or_default();
+  }
+}
+
+/// Generates the CSV records for the blocks of a file whose output CSV block
+/// ID and subcase pass a filter, like `to_records_where`, but converting
+/// them on a pool of `jobs` threads, started once per call. The blocks are
+/// cut in chunks of `PARALLEL_CHUNK_ROWS` rows, and the threads convert `jobs`
+/// chunks at a time, as the records are asked for, so that only those are
+/// ever held in memory. The records come out in the exact same order.
+#[cfg(feature = "parallel")]
+pub fn to_records_parallel_where<'s, P>(
+  file: &'s F06File,
+  converters: &'s BTreeMap<BlockType, BlockConverter>,
+  jobs: usize,
+  filter: P
+) -> impl Iterator<Item = CsvRecord> + 's
+where
+  P: Fn(CsvBlockId, usize) -> bool + 's
+{
+  let mut chunks = ordered_blocks(file, converters)
+    .into_iter()
+    .filter_map(move |block| {
+      let converter = converters.get(&block.block_type)?;
+      let wanted = block.block_type == converter.input_block_type
+        && filter(converter.output_block_id, block.subcase);
+      return wanted.then_some((block, converter));
+    })
+    .flat_map(|(block, converter)| {
+      return block.row_indexes.keys()
+        .step_by(PARALLEL_CHUNK_ROWS)
+        .map(move |first| RowChunk { block, converter, first: *first });
+    });
+  let jobs = jobs.max(1);
+  let pool = ThreadPoolBuilder::new()
+    .num_threads(jobs)
+    .build()
+    .expect("couldn't start the conversion threads");
+  let batches = std::iter::from_fn(move || {
+    let batch = chunks.by_ref().take(jobs).collect::<Vec<_>>();
+    return (!batch.is_empty()).then_some(batch);
+  });
+  let flavour = &file.flavour;
+  return batches.flat_map(move |batch| {
+    let converted = pool.install(|| {
+      return batch.par_iter()
+        .map(|chunk| chunk.convert(flavour))
+        .collect::<Vec<_>>();
+    });
+    return converted.into_iter().flatten();
+  });
+}
+
+/// Generates all CSV records for a file, like `to_records`, but converting
+/// the blocks on up to `jobs` threads (see `to_records_parallel_where`).
+#[cfg(feature = "parallel")]
+pub fn to_records_parallel<'s>(
+  file: &'s F06File,
+  converters: &'s BTreeMap<BlockType, BlockConverter>,
+  jobs: usize
+) -> impl Iterator<Item = CsvRecord> + 's {
+  return zeroth_block(file)
+    .chain(to_records_parallel_where(file, converters, jobs, |_, _| true));
 }
@@ -9,45 +9,118 @@ fn bad_col_type<T>(index: NasIndex) -> Result<T, ConversionError> {
   return Err(ConversionError::BadColIndexType(index));
 }
 
+/// The parts of an index the functions here extract, gathered by visiting it.
+/// Where an index has more than one of a part, the first one is kept.
+#[derive(Default)]
+struct IndexParts {
+  /// The grid point ID, of the grid point itself or of an element corner.
+  gid: Option<usize>,
+  /// The element, or the one applying a force.
+  element: Option<ElementRef>,
+  /// The origin of a force.
+  force_origin: Option<ForceOrigin>,
+  /// The side of an element.
+  side: Option<ElementSide>,
+  /// The station along an element, as a fraction of its length.
+  station: Option<f64>,
+  /// The layer of a composite.
+  layer: Option<CompositeLayer>,
+  /// The surface of grid point stress output.
+  surface: Option<usize>,
+  /// The solution set sequence number.
+  seq: Option<SeqRef>,
+  /// The mode.
+  mode: Option<ModeRef>,
+  /// The subcase or load set, in a summary across them.
+  case: Option<CaseRef>
+}
+
+impl NasIndexVisitor for IndexParts {
+  fn grid(&mut self, grid_point: GridPointRef) {
+    self.gid.get_or_insert(grid_point.gid);
+  }
+
+  fn element(&mut self, element: ElementRef) {
+    self.element.get_or_insert(element);
+  }
+
+  fn point(&mut self, point: ElementPoint) {
+    if let ElementPoint::Corner(g) = point {
+      self.gid.get_or_insert(g.gid);
+    }
+  }
+
+  fn force_origin(&mut self, force_origin: ForceOrigin) {
+    self.force_origin.get_or_insert(force_origin);
+  }
+
+  fn side(&mut self, side: ElementSide) {
+    self.side.get_or_insert(side);
+  }
+
+  fn station(&mut self, fraction: f64) {
+    self.station.get_or_insert(fraction);
+  }
+
+  fn layer(&mut self, layer: CompositeLayer) {
+    self.layer.get_or_insert(layer);
+  }
+
+  fn surface(&mut self, surface: usize) {
+    self.surface.get_or_insert(surface);
+  }
+
+  fn seq(&mut self, seq: SeqRef) {
+    self.seq.get_or_insert(seq);
+  }
+
+  fn mode(&mut self, mode: ModeRef) {
+    self.mode.get_or_insert(mode);
+  }
+
+  fn case(&mut self, case: CaseRef) {
+    self.case.get_or_insert(case);
+  }
+}
+
+/// Extracts a part from an index, erring if it doesn't have one.
+fn part<T>(
+  index: NasIndex,
+  f: impl FnOnce(IndexParts) -> Option<T>
+) -> Result<T, ConversionError> {
+  let mut parts = IndexParts::default();
+  index.visit(&mut parts);
+  return f(parts).map_or_else(|| bad_col_type(index), Ok);
+}
+
 /// Attempts to extract a grid point ID from an index type.
 pub fn ixfn_gid(index: NasIndex) -> Result<CsvField, ConversionError> {
-  return Ok(match index {
-    NasIndex::GridPointRef(g) => g.gid,
-    NasIndex::PointInElement(pie) => match pie.point {
-      ElementPoint::Corner(g) => g.gid,
-      _ => return bad_col_type(index)
-    },
-    NasIndex::GridPointForceOrigin(gpfo) => gpfo.grid_point.gid,
-    NasIndex::ElementSidedPoint(esp) => match esp.point {
-      ElementPoint::Corner(g) => g.gid,
-      _ => return bad_col_type(index)
-    },
-    _ => return bad_col_type(index)
-  }.into());
+  return part(index, |p| p.gid).map(Into::into);
 }
 
-/// Utility functions: extracts element references from index types.
-fn util_eref(index: NasIndex) -> Result<ElementRef, ConversionError> {
-  return Ok(match index {
-    NasIndex::ElementRef(eref) => eref,
-    NasIndex::PointInElement(pie) => pie.element,
-    NasIndex::GridPointForceOrigin(gpfo) => match gpfo.force_origin {
-      ForceOrigin::Element { elem } => elem,
-      _ => return bad_col_type(index)
-    },
-    NasIndex::ElementSidedPoint(esp) => esp.element,
-    _ => return bad_col_type(index)
-  });
+/// Attempts to extract a solution set sequence number from an index type.
+pub fn ixfn_seq(index: NasIndex) -> Result<CsvField, ConversionError> {
+  return part(index, |p| p.seq).map(|seq| seq.0.into());
+}
+
+/// Attempts to extract a mode number from an index type.
+pub fn ixfn_mode(index: NasIndex) -> Result<CsvField, ConversionError> {
+  return part(index, |p| p.mode).map(|mr| mr.mode_number.into());
+}
+
+/// Attempts to extract a subcase or load set ID from an index type.
+pub fn ixfn_case(index: NasIndex) -> Result<CsvField, ConversionError> {
+  return part(index, |p| p.case).map(|case| case.0.into());
 }
 
 /// Attempts to extract an element ID from an index type.
 pub fn ixfn_eid(index: NasIndex) -> Result<CsvField, ConversionError> {
-  return util_eref(index).map(|eref| eref.eid.into());
+  return part(index, |p| p.element).map(|eref| eref.eid.into());
 }
 
 /// Attempts to extract an element type from an index type.
 pub fn ixfn_etype(index: NasIndex) -> Result<CsvField, ConversionError> {
-  if let Some(etype) = util_eref(index).map(|eref| eref.etype)? {
+  if let Some(etype) = part(index, |p| p.element)?.etype {
     return Ok(etype.into());
   } else {
     return Ok("<UNKNOWN>".to_owned().into());
@@ -56,17 +129,54 @@ pub fn ixfn_etype(index: NasIndex) -> Result<CsvField, ConversionError> {
 
 /// Extracts a force origin into a shorter string.
 pub fn ixfn_fo(index: NasIndex) -> Result<CsvField, ConversionError> {
-  if let NasIndex::GridPointForceOrigin(gpfo) = index {
-    return Ok(match gpfo.force_origin {
-      ForceOrigin::Load => "APPLIED".to_owned(),
-      ForceOrigin::Element { elem } => match elem.etype {
-        Some(et) => et.to_string(),
-        None => "<ELEM>".to_string(),
-      },
-      ForceOrigin::SinglePointConstraint => "SPC".to_string(),
-      ForceOrigin::MultiPointConstraint => "MPC".to_string(),
-    }.into());
-  } else {
-    return Err(ConversionError::BadColIndexType(index));
-  }
+  return Ok(match part(index, |p| p.force_origin)? {
+    ForceOrigin::Load => "APPLIED".to_owned(),
+    ForceOrigin::Element { elem } => match elem.etype {
+      Some(et) => et.to_string(),
+      None => "<ELEM>".to_string(),
+    },
+    ForceOrigin::SinglePointConstraint => "SPC".to_string(),
+    ForceOrigin::MultiPointConstraint => "MPC".to_string(),
+  }.into());
+}
+
+/// Extracts the side of a plate element point.
+pub fn ixfn_side(index: NasIndex) -> Result<CsvField, ConversionError> {
+  return Ok(match part(index, |p| p.side)? {
+    ElementSide::Bottom => "Bottom",
+    ElementSide::Middle => "Middle",
+    ElementSide::Top => "Top",
+  }.to_owned().into());
+}
+
+/// Extracts the station of a beam, as a fraction of its length.
+pub fn ixfn_station(index: NasIndex) -> Result<CsvField, ConversionError> {
+  return part(index, |p| p.station).map(CsvField::Real);
+}
+
+/// Extracts the surface ID of grid point stress output.
+pub fn ixfn_surface(index: NasIndex) -> Result<CsvField, ConversionError> {
+  return part(index, |p| p.surface).map(Into::into);
+}
+
+/// Extracts the ply ID of a composite layer, blank for all plies.
+pub fn ixfn_ply_id(index: NasIndex) -> Result<CsvField, ConversionError> {
+  return Ok(match part(index, |p| p.layer)?.ply() {
+    Some(ply) => ply.into(),
+    None => ().into()
+  });
+}
+
+/// Extracts the kind of a composite layer (ply, bond or all plies).
+pub fn ixfn_layer_kind(index: NasIndex) -> Result<CsvField, ConversionError> {
+  return Ok(part(index, |p| p.layer)?.kind().to_owned().into());
+}
+
+/// Extracts a composite layer into a short string.
+pub fn ixfn_ply(index: NasIndex) -> Result<CsvField, ConversionError> {
+  return Ok(match part(index, |p| p.layer)? {
+    CompositeLayer::Ply(ply) => format!("Ply {}", ply),
+    CompositeLayer::Bond(ply) => format!("Bond {}", ply),
+    CompositeLayer::AllPlies => "All".to_owned(),
+  }.into());
 }
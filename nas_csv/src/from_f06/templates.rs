@@ -101,43 +101,183 @@ const ZERO: ColumnGenerator = ColumnGenerator::ConstantNumber(
 /// Generator for End A columns.
 const END_A: ColumnGenerator = ColumnGenerator::ConstantString("End A");
 
-/// Generator for End A columns.
+/// Generator for End B columns.
 const END_B: ColumnGenerator = ColumnGenerator::ConstantString("End B");
 
+/// Generator for the side of plate element points.
+const SIDE: ColumnGenerator = ColumnGenerator::RowIndexFn(
+  &(ixfn_side as IndexFn)
+);
+
+/// Generator for the station of beam element rows.
+const STATION: ColumnGenerator = ColumnGenerator::RowIndexFn(
+  &(ixfn_station as IndexFn)
+);
+
+/// Generator for the status of gap elements, as text.
+const GAP_STATUS: ColumnGenerator = ColumnGenerator::ValueLabel(
+  NasIndex::CgapForceField(CgapForceField::Status),
+  &GAP_STATUSES
+);
+
+/// Generator for the status of composite plies, as text.
+const PLY_STATUS: ColumnGenerator = ColumnGenerator::ValueLabel(
+  NasIndex::CompositeFailureField(CompositeFailureField::Failed),
+  &PLY_STATUSES
+);
+
+/// Generator for the layer of composite element rows.
+const PLY: ColumnGenerator = ColumnGenerator::RowIndexFn(
+  &(ixfn_ply as IndexFn)
+);
+
 /// Generator that always produces a blank.
 const BLANK: ColumnGenerator = ColumnGenerator::Blank;
 
+/// Generator for the subcase or load set of the rows of summaries.
+const CASE: ColumnGenerator = ColumnGenerator::RowIndexFn(
+  &(ixfn_case as IndexFn)
+);
+
+/// The headers of the rows of the tables of maximums per DOF.
+const MAX_SUMMARY_HEADER: RowHeader = [
+  "Case", "Subcase", "Summary", "Quantity", "T1", "T2", "T3", "R1", "R2", "R3"
+];
+
+/// Generates the conversion template for a table of maximums per DOF: a row
+/// with the maximums, then a row with the grid points where they occur.
+macro_rules! max_summary_converter {
+  ($block_type:ident, $summary:literal) => {
+    BlockConverter {
+      input_block_type: BlockType::$block_type,
+      output_block_id: CsvBlockId::MaxSummaries,
+      generators: &[
+        cols!(
+          MaxSummaryField,
+          [
+            CASE,
+            ColumnGenerator::Subcase,
+            ColumnGenerator::ConstantString($summary),
+            ColumnGenerator::ConstantString("Max"),
+          ],
+          [],
+          [Tx, Ty, Tz, Rx, Ry, Rz,],
+          [],
+        ),
+        [
+          CASE,
+          ColumnGenerator::Subcase,
+          ColumnGenerator::ConstantString($summary),
+          ColumnGenerator::ConstantString("GID"),
+          ColumnGenerator::NaturalValue(
+            NasIndex::MaxSummaryField(MaxSummaryField::GridTx)
+          ),
+          ColumnGenerator::NaturalValue(
+            NasIndex::MaxSummaryField(MaxSummaryField::GridTy)
+          ),
+          ColumnGenerator::NaturalValue(
+            NasIndex::MaxSummaryField(MaxSummaryField::GridTz)
+          ),
+          ColumnGenerator::NaturalValue(
+            NasIndex::MaxSummaryField(MaxSummaryField::GridRx)
+          ),
+          ColumnGenerator::NaturalValue(
+            NasIndex::MaxSummaryField(MaxSummaryField::GridRy)
+          ),
+          ColumnGenerator::NaturalValue(
+            NasIndex::MaxSummaryField(MaxSummaryField::GridRz)
+          ),
+        ]
+      ],
+      headers: &[MAX_SUMMARY_HEADER, MAX_SUMMARY_HEADER],
+      extras: &[]
+    }
+  };
+}
+
 /// Contains all the block converters in this source file.
 pub const ALL_CONVERTERS: &[BlockConverter] = &[
   // displacements
   CT_DISPLACEMENTS,
+  // velocities
+  CT_VELOCITIES,
+  // accelerations
+  CT_ACCELERATIONS,
   // grid point force balance
   CT_GPFORCEBALANCE,
   // element stresses
   CT_STRESSES_QUAD,
   CT_STRESSES_TRIA,
   CT_STRESSES_ROD,
+  CT_STRESSES_CONROD,
   CT_STRESSES_BAR,
   CT_STRESSES_ELAS1,
+  CT_STRESSES_ELAS2,
+  CT_STRESSES_ELAS3,
+  CT_STRESSES_ELAS4,
   CT_STRESSES_BUSH,
+  CT_STRESSES_CSHEAR,
+  CT_STRESSES_CHEXA,
+  CT_STRESSES_CPENTA,
+  CT_STRESSES_CTETRA,
   // element strains
   CT_STRAINS_QUAD,
   CT_STRAINS_TRIA,
   CT_STRAINS_ROD,
+  CT_STRAINS_CONROD,
   CT_STRAINS_BAR,
   CT_STRAINS_ELAS1,
+  CT_STRAINS_ELAS2,
+  CT_STRAINS_ELAS3,
+  CT_STRAINS_ELAS4,
   CT_STRAINS_BUSH,
+  CT_STRAINS_CHEXA,
+  CT_STRAINS_CPENTA,
+  CT_STRAINS_CTETRA,
   // element engineering forces
   CT_FORCES_QUAD,
   CT_FORCES_TRIA,
   CT_FORCES_ROD,
+  CT_FORCES_CONROD,
   CT_FORCES_BAR,
+  CT_FORCES_BEAM,
   CT_FORCES_ELAS1,
+  CT_FORCES_ELAS2,
+  CT_FORCES_ELAS3,
+  CT_FORCES_ELAS4,
+  CT_FORCES_CDAMP,
+  CT_FORCES_CVISC,
   CT_FORCES_BUSH,
+  CT_FORCES_CSHEAR,
+  CT_FORCES_CGAP,
   // applied forces
   CT_APPLIED_FORCES,
+  // equivalent thermal loads
+  CT_THERMAL_LOADS,
   // spc forces
-  CT_SPC_FORCES
+  CT_SPC_FORCES,
+  // mpc forces
+  CT_MPC_FORCES,
+  // solution set displacements
+  CT_SOLSET_DISPLACEMENTS,
+  // temperatures
+  CT_TEMPERATURES,
+  // composite failure indices
+  CT_COMPOSITE_FAILURE,
+  // composite ply stresses
+  CT_COMPOSITE_STRESSES,
+  // grid point stresses
+  CT_STRESSES_GRID,
+  // element strain energies
+  CT_STRAIN_ENERGY,
+  // real eigenvalues
+  CT_EIGENVALUES_REAL,
+  // real eigenvectors
+  CT_EIGENVECTORS_REAL,
+  // maximums per DOF
+  CT_MAX_DISPLACEMENTS,
+  CT_MAX_APPLIED_LOADS,
+  CT_MAX_SPC_FORCES
 ];
 
 /// Returns all the converters in this source file, coded per-type.
@@ -166,7 +306,76 @@ pub const CT_DISPLACEMENTS: BlockConverter = BlockConverter {
   ],
   headers: &[
     ["GID", "Subcase", "Tx", "Ty", "Tz", "Rx", "Ry", "Rz", "Coord", HBLANK]
-  ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for velocities blocks.
+pub const CT_VELOCITIES: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Velocities,
+  output_block_id: CsvBlockId::Velocities,
+  generators: &[
+    cols!(
+      Dof,
+      [
+        ColumnGenerator::GridId,
+        ColumnGenerator::Subcase,
+      ],
+      [DOF_TX, DOF_TY, DOF_TZ, DOF_RX, DOF_RY, DOF_RZ,],
+      [],
+      [BLANK, BLANK,],
+    )
+  ],
+  headers: &[
+    ["GID", "Subcase", "Vx", "Vy", "Vz", "VRx", "VRy", "VRz", HBLANK, HBLANK]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for accelerations blocks.
+pub const CT_ACCELERATIONS: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Accelerations,
+  output_block_id: CsvBlockId::Accelerations,
+  generators: &[
+    cols!(
+      Dof,
+      [
+        ColumnGenerator::GridId,
+        ColumnGenerator::Subcase,
+      ],
+      [DOF_TX, DOF_TY, DOF_TZ, DOF_RX, DOF_RY, DOF_RZ,],
+      [],
+      [BLANK, BLANK,],
+    )
+  ],
+  headers: &[
+    [
+      "GID", "Subcase", "Ax", "Ay", "Az", "ARx", "ARy", "ARz", HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for solution set displacements blocks.
+pub const CT_SOLSET_DISPLACEMENTS: BlockConverter = BlockConverter {
+  input_block_type: BlockType::SolutionSetDisplacements,
+  output_block_id: CsvBlockId::SolutionSetDisplacements,
+  generators: &[
+    cols!(
+      Dof,
+      [
+        ColumnGenerator::RowIndexFn(&(ixfn_seq as IndexFn)),
+        ColumnGenerator::Subcase,
+      ],
+      [DOF_TX, DOF_TY, DOF_TZ, DOF_RX, DOF_RY, DOF_RZ,],
+      [],
+      [BLANK, BLANK,],
+    )
+  ],
+  headers: &[
+    ["SEQ", "Subcase", "Tx", "Ty", "Tz", "Rx", "Ry", "Rz", HBLANK, HBLANK]
+  ],
+  extras: &[]
 };
 
 /// Conversion template for grid point force balance blocks.
@@ -191,7 +400,8 @@ pub const CT_GPFORCEBALANCE: BlockConverter = BlockConverter {
   ],
   headers: &[
     ["GID", "Subcase", "EID", "TYPE", "Fx", "Fy", "Fz", "Mx", "My", "Mz"]
-  ]
+  ],
+  extras: &[]
 };
 
 /// Conversion template for quad stresses.
@@ -223,7 +433,8 @@ pub const CT_STRESSES_QUAD: BlockConverter = BlockConverter {
       "EID (QUAD4)", "Subcase", "GID", "FibreDistance",
       "NormalX", "NormalY", HBLANK, "ShearXY", HBLANK, HBLANK
     ]
-  ]
+  ],
+  extras: &[SIDE]
 };
 
 /// Conversion template for tria stresses.
@@ -236,7 +447,8 @@ pub const CT_STRESSES_TRIA: BlockConverter = BlockConverter {
       "EID (TRIA3)", "Subcase", "GID", "FibreDistance",
       "NormalX", "NormalY", HBLANK, "ShearXY", HBLANK, HBLANK
     ]
-  ]
+  ],
+  extras: CT_STRESSES_QUAD.extras
 };
 
 /// Conversion template for rod stresses.
@@ -267,12 +479,27 @@ pub const CT_STRESSES_ROD: BlockConverter = BlockConverter {
       "EID (ROD)", "Subcase", HBLANK, HBLANK, "Axial",
       HBLANK, HBLANK, "Torsional", HBLANK, HBLANK
     ]
-  ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for conrod stresses.
+pub const CT_STRESSES_CONROD: BlockConverter = BlockConverter {
+  input_block_type: BlockType::ConrodStresses,
+  output_block_id: CT_STRESSES_ROD.output_block_id,
+  generators: CT_STRESSES_ROD.generators,
+  headers: &[
+    [
+      "EID (CONROD)", "Subcase", HBLANK, HBLANK, "Axial",
+      HBLANK, HBLANK, "Torsional", HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
 };
 
 /// Header for bar stresses.
 const BAR_STRESSES_HEADER: [&str; 10] = [
-  "EID (BAR)", "Subcase", "GID", "End", "Axial", "S1", "S2", "S3", "S4", HBLANK
+  "EID (BAR)", "Subcase", "GID", HBLANK, "Axial", "S1", "S2", "S3", "S4", HBLANK
 ];
 
 /// Conversion template for bar stresses.
@@ -286,7 +513,7 @@ pub const CT_STRESSES_BAR: BlockConverter = BlockConverter {
         ColumnGenerator::ElementId,
         ColumnGenerator::Subcase,
         ZERO,
-        END_A,
+        BLANK,
       ],
       [
         BarStressField::Axial,
@@ -304,7 +531,7 @@ pub const CT_STRESSES_BAR: BlockConverter = BlockConverter {
         ColumnGenerator::ElementId,
         ColumnGenerator::Subcase,
         ZERO,
-        END_B,
+        BLANK,
       ],
       [
         BarStressField::Axial,
@@ -317,7 +544,8 @@ pub const CT_STRESSES_BAR: BlockConverter = BlockConverter {
       [BLANK,],
     )
   ],
-  headers: &[BAR_STRESSES_HEADER, BAR_STRESSES_HEADER]
+  headers: &[BAR_STRESSES_HEADER, BAR_STRESSES_HEADER],
+  extras: &[END_A, END_B]
 };
 
 /// Conversion template for ELAS1 stresses.
@@ -343,12 +571,55 @@ pub const CT_STRESSES_ELAS1: BlockConverter = BlockConverter {
       "EID (ELAS1)", "Subcase", HBLANK, HBLANK, "Stress",
       HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
     ]
-  ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for ELAS2 stresses.
+pub const CT_STRESSES_ELAS2: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Elas2Stresses,
+  output_block_id: CT_STRESSES_ELAS1.output_block_id,
+  generators: CT_STRESSES_ELAS1.generators,
+  headers: &[
+    [
+      "EID (ELAS2)", "Subcase", HBLANK, HBLANK, "Stress",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for ELAS3 stresses.
+pub const CT_STRESSES_ELAS3: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Elas3Stresses,
+  output_block_id: CT_STRESSES_ELAS1.output_block_id,
+  generators: CT_STRESSES_ELAS1.generators,
+  headers: &[
+    [
+      "EID (ELAS3)", "Subcase", HBLANK, HBLANK, "Stress",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for ELAS4 stresses.
+pub const CT_STRESSES_ELAS4: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Elas4Stresses,
+  output_block_id: CT_STRESSES_ELAS1.output_block_id,
+  generators: CT_STRESSES_ELAS1.generators,
+  headers: &[
+    [
+      "EID (ELAS4)", "Subcase", HBLANK, HBLANK, "Stress",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
 };
 
 /// Header for bush stresses.
 pub const BUSH_STRESSES_HEADER: [&str; 10] = [
-  "EID (BUSH)", "Subcase", HBLANK, HBLANK, "Sx", "Sy", "Sx", "Mx", "My", "Mz"
+  "EID (BUSH)", "Subcase", HBLANK, HBLANK, "Sx", "Sy", "Sz", "Mx", "My", "Mz"
 ];
 
 /// Conversion template for BUSH stresses;
@@ -357,66 +628,36 @@ pub const CT_STRESSES_BUSH: BlockConverter = BlockConverter {
   output_block_id: CsvBlockId::Stresses,
   generators: &[
     cols!(
-      Dof,
+      BushStressField,
       [
         ColumnGenerator::ElementId,
         ColumnGenerator::Subcase,
         BLANK,
         BLANK,
       ],
-      [DOF_TX, DOF_TY, DOF_TZ, DOF_RX, DOF_RY, DOF_RZ,],
-      [],
       [],
-    )
-  ],
-  headers: &[BUSH_STRESSES_HEADER]
-};
-
-/// Conversion template for quad strains.
-pub const CT_STRAINS_QUAD: BlockConverter = BlockConverter {
-  input_block_type: BlockType::QuadStrains,
-  output_block_id: CsvBlockId::Strains,
-  generators: &[
-    cols_inner!(
-      PlateStrainField,
-      PlateStressField,
       [
-        ColumnGenerator::ElementId,
-        ColumnGenerator::Subcase,
-        ColumnGenerator::WithDefault(
-          &ColumnGenerator::GridId, &CsvField::Natural(0)
-        ),
+        TranslationX,
+        TranslationY,
+        TranslationZ,
+        RotationX,
+        RotationY,
+        RotationZ,
       ],
       [],
-      [FibreDistance, NormalX, NormalY,],
-      [BLANK,],
-      PlateStrainField,
-      PlateStressField,
-      [],
-      [],
-      [ShearXY,],
-      [BLANK, BLANK,],
     )
   ],
-  headers: CT_STRESSES_QUAD.headers
-};
-
-/// Conversion template for tria strains.
-pub const CT_STRAINS_TRIA: BlockConverter = BlockConverter {
-  input_block_type: BlockType::TriaStrains,
-  output_block_id: CsvBlockId::Strains,
-  generators: CT_STRAINS_QUAD.generators,
-  headers: CT_STRESSES_TRIA.headers
+  headers: &[BUSH_STRESSES_HEADER],
+  extras: &[]
 };
 
-/// Conversion template for rod strains.
-pub const CT_STRAINS_ROD: BlockConverter = BlockConverter {
-  input_block_type: BlockType::RodStrains,
-  output_block_id: CsvBlockId::Strains,
+/// Conversion template for CSHEAR stresses.
+pub const CT_STRESSES_CSHEAR: BlockConverter = BlockConverter {
+  input_block_type: BlockType::CshearStresses,
+  output_block_id: CsvBlockId::Stresses,
   generators: &[
-    cols_inner!(
-      RodStrainField,
-      RodStressField,
+    cols!(
+      CshearStressField,
       [
         ColumnGenerator::ElementId,
         ColumnGenerator::Subcase,
@@ -424,124 +665,760 @@ pub const CT_STRAINS_ROD: BlockConverter = BlockConverter {
         BLANK,
       ],
       [],
-      [Axial,],
-      [BLANK, BLANK,],
-      RodStrainField,
-      RodStressField,
-      [],
-      [],
-      [Torsional,],
-      [BLANK, BLANK,],
+      [MaxShearStress, AverageShear, MarginOfSafety,],
+      [BLANK, BLANK, BLANK,],
     )
   ],
-  headers: CT_STRESSES_ROD.headers
+  headers: &[
+    [
+      "EID (SHEAR)", "Subcase", HBLANK, HBLANK, "MaxShear", "AvgShear", "MS",
+      HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
 };
 
-/// Conversion template for bar strains.
-pub const CT_STRAINS_BAR: BlockConverter = BlockConverter {
-  input_block_type: BlockType::BarStrains,
-  output_block_id: CsvBlockId::Strains,
+/// Conversion template for CHEXA stresses. The octahedral shear stress
+/// doesn't fit, and the side is the face the corner is on. Each point gets a
+/// second record with the principal stresses and the mean pressure, blank
+/// where they aren't printed.
+pub const CT_STRESSES_CHEXA: BlockConverter = BlockConverter {
+  input_block_type: BlockType::ChexaStresses,
+  output_block_id: CsvBlockId::Stresses,
   generators: &[
-    cols_inner!(
-      BarStrainField,
-      BarStressField,
+    cols!(
+      ChexaStressField,
       [
         ColumnGenerator::ElementId,
         ColumnGenerator::Subcase,
-        ZERO,
-        END_A,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
       ],
+      [],
+      [NormalX, NormalY, NormalZ, ShearXY, ShearYZ, ShearZX,],
       [
-        BarStressField::Axial,
-        BarStressField::AtRecoveryPoint { end: BarEnd::EndA,  point: 1 },
-        BarStressField::AtRecoveryPoint { end: BarEnd::EndA,  point: 2 },
-        BarStressField::AtRecoveryPoint { end: BarEnd::EndA,  point: 3 },
-        BarStressField::AtRecoveryPoint { end: BarEnd::EndA,  point: 4 },
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::ChexaStressField(ChexaStressField::VonMises)
+          ),
+          &CsvField::Blank
+        ),
       ],
-      [],
-      [BLANK,],
     ),
-    cols_inner!(
-      BarStrainField,
-      BarStressField,
+    cols!(
+      ChexaStressField,
       [
         ColumnGenerator::ElementId,
         ColumnGenerator::Subcase,
-        ZERO,
-        END_B,
-      ],
-      [
-        BarStressField::Axial,
-        BarStressField::AtRecoveryPoint { end: BarEnd::EndB,  point: 1 },
-        BarStressField::AtRecoveryPoint { end: BarEnd::EndB,  point: 2 },
-        BarStressField::AtRecoveryPoint { end: BarEnd::EndB,  point: 3 },
-        BarStressField::AtRecoveryPoint { end: BarEnd::EndB,  point: 4 },
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
       ],
       [],
-      [BLANK,],
-    )
-  ],
-  headers: CT_STRESSES_BAR.headers
-};
-
-/// Conversion template for ELAS1 strains.
-pub const CT_STRAINS_ELAS1: BlockConverter = BlockConverter {
-  input_block_type: BlockType::Elas1Strains,
-  output_block_id: CsvBlockId::Strains,
-  generators: &[
-    cols!(
-      SingleStrain,
+      [],
       [
-        ColumnGenerator::ElementId,
-        ColumnGenerator::Subcase,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::ChexaStressField(ChexaStressField::PrincipalA)
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::ChexaStressField(ChexaStressField::PrincipalB)
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::ChexaStressField(ChexaStressField::PrincipalC)
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::ChexaStressField(ChexaStressField::MeanPressure)
+          ),
+          &CsvField::Blank
+        ),
+        BLANK,
         BLANK,
         BLANK,
       ],
-      [],
-      [Strain,],
-      [BLANK, BLANK, BLANK, BLANK, BLANK,],
     )
   ],
   headers: &[
     [
-      "EID (ELAS1)", "Subcase", HBLANK, HBLANK, "Strain",
-      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+      "EID (HEXA)", "Subcase", "GID", "NormalX", "NormalY", "NormalZ",
+      "ShearXY", "ShearYZ", "ShearZX", "VonMises"
+    ],
+    [
+      "EID (HEXA)", "Subcase", "GID", "PrincipalA", "PrincipalB",
+      "PrincipalC", "MeanPressure", HBLANK, HBLANK, HBLANK
     ]
-  ]
-};
-
-/// Conversion template for BUSH strains.
-pub const CT_STRAINS_BUSH: BlockConverter = BlockConverter {
-  input_block_type: BlockType::BushStrains,
-  output_block_id: CsvBlockId::Strains,
-  generators: CT_STRESSES_BUSH.generators,
-  headers: CT_STRESSES_BUSH.headers
+  ],
+  extras: &[SIDE, SIDE]
 };
 
-/// Conversion template for quad forces.
-pub const CT_FORCES_QUAD: BlockConverter = BlockConverter {
-  input_block_type: BlockType::QuadForces,
-  output_block_id: CsvBlockId::EngForces,
+/// Conversion template for CPENTA stresses.
+pub const CT_STRESSES_CPENTA: BlockConverter = BlockConverter {
+  input_block_type: BlockType::CpentaStresses,
+  output_block_id: CsvBlockId::Stresses,
   generators: &[
     cols!(
-      PlateForceField,
+      CpentaStressField,
       [
         ColumnGenerator::ElementId,
         ColumnGenerator::Subcase,
-        BLANK,
-        BLANK,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
       ],
       [],
-      [NormalX, NormalY, NormalXY, MomentX, MomentY, MomentXY,],
-      [],
-    )
-  ],
-  headers: &[
-    [
-      "EID (QUAD4)", "Subcase", HBLANK, HBLANK, "NormalX",
-      "NormalY", "NormalXY", "MomentX", "MomentY", "MomentXY"
+      [NormalX, NormalY, NormalZ, ShearXY, ShearYZ, ShearZX,],
+      [
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CpentaStressField(CpentaStressField::VonMises)
+          ),
+          &CsvField::Blank
+        ),
+      ],
+    ),
+    cols!(
+      CpentaStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
+      ],
+      [],
+      [],
+      [
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CpentaStressField(CpentaStressField::PrincipalA)
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CpentaStressField(CpentaStressField::PrincipalB)
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CpentaStressField(CpentaStressField::PrincipalC)
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CpentaStressField(CpentaStressField::MeanPressure)
+          ),
+          &CsvField::Blank
+        ),
+        BLANK,
+        BLANK,
+        BLANK,
+      ],
+    )
+  ],
+  headers: &[
+    [
+      "EID (PENTA)", "Subcase", "GID", "NormalX", "NormalY", "NormalZ",
+      "ShearXY", "ShearYZ", "ShearZX", "VonMises"
+    ],
+    [
+      "EID (PENTA)", "Subcase", "GID", "PrincipalA", "PrincipalB",
+      "PrincipalC", "MeanPressure", HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[SIDE, SIDE]
+};
+
+/// Conversion template for CTETRA stresses.
+pub const CT_STRESSES_CTETRA: BlockConverter = BlockConverter {
+  input_block_type: BlockType::CtetraStresses,
+  output_block_id: CsvBlockId::Stresses,
+  generators: &[
+    cols!(
+      CtetraStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
+      ],
+      [],
+      [NormalX, NormalY, NormalZ, ShearXY, ShearYZ, ShearZX,],
+      [
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CtetraStressField(CtetraStressField::VonMises)
+          ),
+          &CsvField::Blank
+        ),
+      ],
+    ),
+    cols!(
+      CtetraStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
+      ],
+      [],
+      [],
+      [
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CtetraStressField(CtetraStressField::PrincipalA)
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CtetraStressField(CtetraStressField::PrincipalB)
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CtetraStressField(CtetraStressField::PrincipalC)
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CtetraStressField(CtetraStressField::MeanPressure)
+          ),
+          &CsvField::Blank
+        ),
+        BLANK,
+        BLANK,
+        BLANK,
+      ],
+    )
+  ],
+  headers: &[
+    [
+      "EID (TETRA)", "Subcase", "GID", "NormalX", "NormalY", "NormalZ",
+      "ShearXY", "ShearYZ", "ShearZX", "VonMises"
+    ],
+    [
+      "EID (TETRA)", "Subcase", "GID", "PrincipalA", "PrincipalB",
+      "PrincipalC", "MeanPressure", HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[SIDE, SIDE]
+};
+
+/// Conversion template for quad strains.
+pub const CT_STRAINS_QUAD: BlockConverter = BlockConverter {
+  input_block_type: BlockType::QuadStrains,
+  output_block_id: CsvBlockId::Strains,
+  generators: &[
+    cols_inner!(
+      PlateStrainField,
+      PlateStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
+      ],
+      [],
+      [FibreDistance, NormalX, NormalY,],
+      [BLANK,],
+      PlateStrainField,
+      PlateStressField,
+      [],
+      [],
+      [ShearXY,],
+      [BLANK, BLANK,],
+    )
+  ],
+  headers: CT_STRESSES_QUAD.headers,
+  extras: &[SIDE]
+};
+
+/// Conversion template for tria strains.
+pub const CT_STRAINS_TRIA: BlockConverter = BlockConverter {
+  input_block_type: BlockType::TriaStrains,
+  output_block_id: CsvBlockId::Strains,
+  generators: CT_STRAINS_QUAD.generators,
+  headers: CT_STRESSES_TRIA.headers,
+  extras: CT_STRAINS_QUAD.extras
+};
+
+/// Conversion template for rod strains.
+pub const CT_STRAINS_ROD: BlockConverter = BlockConverter {
+  input_block_type: BlockType::RodStrains,
+  output_block_id: CsvBlockId::Strains,
+  generators: &[
+    cols_inner!(
+      RodStrainField,
+      RodStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        BLANK,
+        BLANK,
+      ],
+      [],
+      [Axial,],
+      [BLANK, BLANK,],
+      RodStrainField,
+      RodStressField,
+      [],
+      [],
+      [Torsional,],
+      [BLANK, BLANK,],
+    )
+  ],
+  headers: CT_STRESSES_ROD.headers,
+  extras: &[]
+};
+
+/// Conversion template for conrod strains.
+pub const CT_STRAINS_CONROD: BlockConverter = BlockConverter {
+  input_block_type: BlockType::ConrodStrains,
+  output_block_id: CT_STRAINS_ROD.output_block_id,
+  generators: CT_STRAINS_ROD.generators,
+  headers: CT_STRESSES_CONROD.headers,
+  extras: &[]
+};
+
+/// Conversion template for bar strains.
+pub const CT_STRAINS_BAR: BlockConverter = BlockConverter {
+  input_block_type: BlockType::BarStrains,
+  output_block_id: CsvBlockId::Strains,
+  generators: &[
+    cols_inner!(
+      BarStrainField,
+      BarStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ZERO,
+        BLANK,
+      ],
+      [
+        BarStressField::Axial,
+        BarStressField::AtRecoveryPoint { end: BarEnd::EndA,  point: 1 },
+        BarStressField::AtRecoveryPoint { end: BarEnd::EndA,  point: 2 },
+        BarStressField::AtRecoveryPoint { end: BarEnd::EndA,  point: 3 },
+        BarStressField::AtRecoveryPoint { end: BarEnd::EndA,  point: 4 },
+      ],
+      [],
+      [BLANK,],
+    ),
+    cols_inner!(
+      BarStrainField,
+      BarStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ZERO,
+        BLANK,
+      ],
+      [
+        BarStressField::Axial,
+        BarStressField::AtRecoveryPoint { end: BarEnd::EndB,  point: 1 },
+        BarStressField::AtRecoveryPoint { end: BarEnd::EndB,  point: 2 },
+        BarStressField::AtRecoveryPoint { end: BarEnd::EndB,  point: 3 },
+        BarStressField::AtRecoveryPoint { end: BarEnd::EndB,  point: 4 },
+      ],
+      [],
+      [BLANK,],
+    )
+  ],
+  headers: CT_STRESSES_BAR.headers,
+  extras: CT_STRESSES_BAR.extras
+};
+
+/// Conversion template for ELAS1 strains.
+pub const CT_STRAINS_ELAS1: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Elas1Strains,
+  output_block_id: CsvBlockId::Strains,
+  generators: &[
+    cols!(
+      SingleStrain,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        BLANK,
+        BLANK,
+      ],
+      [],
+      [Strain,],
+      [BLANK, BLANK, BLANK, BLANK, BLANK,],
+    )
+  ],
+  headers: &[
+    [
+      "EID (ELAS1)", "Subcase", HBLANK, HBLANK, "Strain",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for ELAS2 strains.
+pub const CT_STRAINS_ELAS2: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Elas2Strains,
+  output_block_id: CT_STRAINS_ELAS1.output_block_id,
+  generators: CT_STRAINS_ELAS1.generators,
+  headers: &[
+    [
+      "EID (ELAS2)", "Subcase", HBLANK, HBLANK, "Strain",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for ELAS3 strains.
+pub const CT_STRAINS_ELAS3: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Elas3Strains,
+  output_block_id: CT_STRAINS_ELAS1.output_block_id,
+  generators: CT_STRAINS_ELAS1.generators,
+  headers: &[
+    [
+      "EID (ELAS3)", "Subcase", HBLANK, HBLANK, "Strain",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for ELAS4 strains.
+pub const CT_STRAINS_ELAS4: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Elas4Strains,
+  output_block_id: CT_STRAINS_ELAS1.output_block_id,
+  generators: CT_STRAINS_ELAS1.generators,
+  headers: &[
+    [
+      "EID (ELAS4)", "Subcase", HBLANK, HBLANK, "Strain",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for BUSH strains.
+pub const CT_STRAINS_BUSH: BlockConverter = BlockConverter {
+  input_block_type: BlockType::BushStrains,
+  output_block_id: CsvBlockId::Strains,
+  generators: &[
+    cols_inner!(
+      BushStrainField,
+      BushStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        BLANK,
+        BLANK,
+      ],
+      [],
+      [
+        TranslationX,
+        TranslationY,
+        TranslationZ,
+        RotationX,
+        RotationY,
+        RotationZ,
+      ],
+      [],
+    )
+  ],
+  headers: CT_STRESSES_BUSH.headers,
+  extras: &[]
+};
+
+/// Conversion template for CHEXA strains.
+pub const CT_STRAINS_CHEXA: BlockConverter = BlockConverter {
+  input_block_type: BlockType::ChexaStrains,
+  output_block_id: CsvBlockId::Strains,
+  generators: &[
+    cols_inner!(
+      ChexaStrainField,
+      ChexaStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
+      ],
+      [],
+      [NormalX, NormalY, NormalZ, ShearXY, ShearYZ, ShearZX,],
+      [
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::ChexaStrainField(
+              ChexaStrainField(ChexaStressField::VonMises)
+            )
+          ),
+          &CsvField::Blank
+        ),
+      ],
+    ),
+    cols_inner!(
+      ChexaStrainField,
+      ChexaStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
+      ],
+      [],
+      [],
+      [
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::ChexaStrainField(
+              ChexaStrainField(ChexaStressField::PrincipalA)
+            )
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::ChexaStrainField(
+              ChexaStrainField(ChexaStressField::PrincipalB)
+            )
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::ChexaStrainField(
+              ChexaStrainField(ChexaStressField::PrincipalC)
+            )
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::ChexaStrainField(
+              ChexaStrainField(ChexaStressField::MeanPressure)
+            )
+          ),
+          &CsvField::Blank
+        ),
+        BLANK,
+        BLANK,
+        BLANK,
+      ],
+    )
+  ],
+  headers: CT_STRESSES_CHEXA.headers,
+  extras: CT_STRESSES_CHEXA.extras
+};
+
+/// Conversion template for CPENTA strains.
+pub const CT_STRAINS_CPENTA: BlockConverter = BlockConverter {
+  input_block_type: BlockType::CpentaStrains,
+  output_block_id: CsvBlockId::Strains,
+  generators: &[
+    cols_inner!(
+      CpentaStrainField,
+      CpentaStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
+      ],
+      [],
+      [NormalX, NormalY, NormalZ, ShearXY, ShearYZ, ShearZX,],
+      [
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CpentaStrainField(
+              CpentaStrainField(CpentaStressField::VonMises)
+            )
+          ),
+          &CsvField::Blank
+        ),
+      ],
+    ),
+    cols_inner!(
+      CpentaStrainField,
+      CpentaStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
+      ],
+      [],
+      [],
+      [
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CpentaStrainField(
+              CpentaStrainField(CpentaStressField::PrincipalA)
+            )
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CpentaStrainField(
+              CpentaStrainField(CpentaStressField::PrincipalB)
+            )
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CpentaStrainField(
+              CpentaStrainField(CpentaStressField::PrincipalC)
+            )
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CpentaStrainField(
+              CpentaStrainField(CpentaStressField::MeanPressure)
+            )
+          ),
+          &CsvField::Blank
+        ),
+        BLANK,
+        BLANK,
+        BLANK,
+      ],
+    )
+  ],
+  headers: CT_STRESSES_CPENTA.headers,
+  extras: CT_STRESSES_CPENTA.extras
+};
+
+/// Conversion template for CTETRA strains.
+pub const CT_STRAINS_CTETRA: BlockConverter = BlockConverter {
+  input_block_type: BlockType::CtetraStrains,
+  output_block_id: CsvBlockId::Strains,
+  generators: &[
+    cols_inner!(
+      CtetraStrainField,
+      CtetraStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
+      ],
+      [],
+      [NormalX, NormalY, NormalZ, ShearXY, ShearYZ, ShearZX,],
+      [
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CtetraStrainField(
+              CtetraStrainField(CtetraStressField::VonMises)
+            )
+          ),
+          &CsvField::Blank
+        ),
+      ],
+    ),
+    cols_inner!(
+      CtetraStrainField,
+      CtetraStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::GridId, &CsvField::Natural(0)
+        ),
+      ],
+      [],
+      [],
+      [
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CtetraStrainField(
+              CtetraStrainField(CtetraStressField::PrincipalA)
+            )
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CtetraStrainField(
+              CtetraStrainField(CtetraStressField::PrincipalB)
+            )
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CtetraStrainField(
+              CtetraStrainField(CtetraStressField::PrincipalC)
+            )
+          ),
+          &CsvField::Blank
+        ),
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CtetraStrainField(
+              CtetraStrainField(CtetraStressField::MeanPressure)
+            )
+          ),
+          &CsvField::Blank
+        ),
+        BLANK,
+        BLANK,
+        BLANK,
+      ],
+    )
+  ],
+  headers: CT_STRESSES_CTETRA.headers,
+  extras: CT_STRESSES_CTETRA.extras
+};
+
+/// Conversion template for quad forces.
+pub const CT_FORCES_QUAD: BlockConverter = BlockConverter {
+  input_block_type: BlockType::QuadForces,
+  output_block_id: CsvBlockId::EngForces,
+  generators: &[
+    cols!(
+      PlateForceField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        BLANK,
+        BLANK,
+      ],
+      [],
+      [NormalX, NormalY, NormalXY, MomentX, MomentY, MomentXY,],
+      [],
+    )
+  ],
+  headers: &[
+    [
+      "EID (QUAD4)", "Subcase", HBLANK, HBLANK, "NormalX",
+      "NormalY", "NormalXY", "MomentX", "MomentY", "MomentXY"
     ]
-  ]
+  ],
+  extras: &[]
 };
 
 /// Conversion template for tria forces.
@@ -549,7 +1426,8 @@ pub const CT_FORCES_TRIA: BlockConverter = BlockConverter {
   input_block_type: BlockType::TriaForces,
   output_block_id: CsvBlockId::EngForces,
   generators: CT_FORCES_QUAD.generators,
-  headers: CT_FORCES_QUAD.headers
+  headers: CT_FORCES_QUAD.headers,
+  extras: &[]
 };
 
 /// Conversion template for rod forces.
@@ -558,115 +1436,350 @@ pub const CT_FORCES_ROD: BlockConverter = BlockConverter {
   output_block_id: CsvBlockId::EngForces,
   generators: &[
     cols!(
-      RodForceField,
+      RodForceField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        BLANK,
+        BLANK,
+      ],
+      [],
+      [AxialForce,],
+      [BLANK, BLANK, BLANK, BLANK,],
+      RodForceField,
+      [],
+      [],
+      [Torque,],
+      [],
+    )
+  ],
+  headers: &[
+    [
+      "EID (ROD)", "Subcase", HBLANK, HBLANK, "Axial",
+      HBLANK, HBLANK, HBLANK, HBLANK, "Torque"
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for conrod forces.
+pub const CT_FORCES_CONROD: BlockConverter = BlockConverter {
+  input_block_type: BlockType::ConrodForces,
+  output_block_id: CT_FORCES_ROD.output_block_id,
+  generators: CT_FORCES_ROD.generators,
+  headers: &[
+    [
+      "EID (CONROD)", "Subcase", HBLANK, HBLANK, "Axial",
+      HBLANK, HBLANK, HBLANK, HBLANK, "Torque"
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for viscous damper forces, laid out like rod forces.
+pub const CT_FORCES_CVISC: BlockConverter = BlockConverter {
+  input_block_type: BlockType::ViscForces,
+  output_block_id: CT_FORCES_ROD.output_block_id,
+  generators: CT_FORCES_ROD.generators,
+  headers: &[
+    [
+      "EID (CVISC)", "Subcase", HBLANK, HBLANK, "Axial",
+      HBLANK, HBLANK, HBLANK, HBLANK, "Torque"
+    ]
+  ],
+  extras: &[]
+};
+
+/// Header for bar forces. It appears twice.
+const BAR_FORCES_HEADER: [&str; 10] = [
+  "EID (BAR)", "Subcase", "GID", HBLANK, "Axial", "S1", "S2", "M1", "M2",
+  "Torque"
+];
+
+/// Conversion template for bar forces.
+pub const CT_FORCES_BAR: BlockConverter = BlockConverter {
+  input_block_type: BlockType::BarForces,
+  output_block_id: CsvBlockId::EngForces,
+  generators: &[
+    cols!(
+      BarForceField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ZERO,
+        BLANK,
+      ],
+      [],
+      [
+        AxialForce,
+        ShearPlane1,
+        ShearPlane2,
+        BendMomentAPlane1,
+        BendMomentAPlane2,
+        Torque,
+      ],
+      [],
+    ),
+    cols!(
+      BarForceField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ZERO,
+        BLANK,
+      ],
+      [],
+      [
+        AxialForce,
+        ShearPlane1,
+        ShearPlane2,
+        BendMomentBPlane1,
+        BendMomentBPlane2,
+        Torque,
+      ],
+      [],
+    )
+  ],
+  headers: &[BAR_FORCES_HEADER, BAR_FORCES_HEADER],
+  extras: &[END_A, END_B]
+};
+
+/// Conversion template for beam forces. The warping torque goes in the fourth
+/// column, blank where it's not printed, and the station in its own column.
+pub const CT_FORCES_BEAM: BlockConverter = BlockConverter {
+  input_block_type: BlockType::BeamForces,
+  output_block_id: CsvBlockId::EngForces,
+  generators: &[
+    cols!(
+      BeamForceField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        BLANK,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::BeamForceField(BeamForceField::WarpingTorque)
+          ),
+          &CsvField::Blank
+        ),
+      ],
+      [],
+      [
+        AxialForce,
+        ShearPlane1,
+        ShearPlane2,
+        MomentPlane1,
+        MomentPlane2,
+        TotalTorque,
+      ],
+      [],
+    )
+  ],
+  headers: &[
+    [
+      "EID (BEAM)", "Subcase", HBLANK, "Warping", "Axial", "S1", "S2", "M1",
+      "M2", "Torque"
+    ]
+  ],
+  extras: &[STATION]
+};
+
+/// Conversion template for ELAS1 forces.
+pub const CT_FORCES_ELAS1: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Elas1Forces,
+  output_block_id: CsvBlockId::EngForces,
+  generators: &[
+    cols!(
+      SingleForce,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        BLANK,
+        BLANK,
+      ],
+      [],
+      [Force,],
+      [BLANK, BLANK, BLANK, BLANK, BLANK,],
+    )
+  ],
+  headers: &[
+    [
+      "EID (ELAS1)", "Subcase", HBLANK, HBLANK, "Force",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for ELAS2 forces.
+pub const CT_FORCES_ELAS2: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Elas2Forces,
+  output_block_id: CT_FORCES_ELAS1.output_block_id,
+  generators: CT_FORCES_ELAS1.generators,
+  headers: &[
+    [
+      "EID (ELAS2)", "Subcase", HBLANK, HBLANK, "Force",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for ELAS3 forces.
+pub const CT_FORCES_ELAS3: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Elas3Forces,
+  output_block_id: CT_FORCES_ELAS1.output_block_id,
+  generators: CT_FORCES_ELAS1.generators,
+  headers: &[
+    [
+      "EID (ELAS3)", "Subcase", HBLANK, HBLANK, "Force",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for ELAS4 forces.
+pub const CT_FORCES_ELAS4: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Elas4Forces,
+  output_block_id: CT_FORCES_ELAS1.output_block_id,
+  generators: CT_FORCES_ELAS1.generators,
+  headers: &[
+    [
+      "EID (ELAS4)", "Subcase", HBLANK, HBLANK, "Force",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for scalar damper forces, with the subtype of each
+/// damper in its own column.
+pub const CT_FORCES_CDAMP: BlockConverter = BlockConverter {
+  input_block_type: BlockType::CdampForces,
+  output_block_id: CsvBlockId::EngForces,
+  generators: &[
+    cols!(
+      SingleForce,
       [
         ColumnGenerator::ElementId,
         ColumnGenerator::Subcase,
-        BLANK,
+        ColumnGenerator::ElementType,
         BLANK,
       ],
       [],
-      [AxialForce,],
-      [BLANK, BLANK, BLANK, BLANK,],
-      RodForceField,
-      [],
-      [],
-      [Torque,],
-      [],
+      [Force,],
+      [BLANK, BLANK, BLANK, BLANK, BLANK,],
     )
   ],
   headers: &[
     [
-      "EID (ROD)", "Subcase", HBLANK, HBLANK, "Axial",
-      HBLANK, HBLANK, HBLANK, HBLANK, "Torque"
+      "EID (CDAMP)", "Subcase", "EType", HBLANK, "Force",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
     ]
-  ]
+  ],
+  extras: &[]
 };
 
-/// Header for bar forces. It appears twice.
-const BAR_FORCES_HEADER: [&str; 10] = [
-  "EID (BAR)", "Subcase", "GID", "End", "Axial", "S1", "S2", "M1", "M2", "Torque"
-];
+/// Conversion template for BUSH forces.
+pub const CT_FORCES_BUSH: BlockConverter = BlockConverter {
+  input_block_type: BlockType::BushForces,
+  output_block_id: CsvBlockId::EngForces,
+  generators: CT_STRESSES_BUSH.generators,
+  headers: &[
+    ["EID (BUSH)", "Subcase", HBLANK, HBLANK, "Fx", "Fy", "Fz", "Mx", "My", "Mz"]
+  ],
+  extras: &[]
+};
 
-/// Conversion template for bar forces.
-pub const CT_FORCES_BAR: BlockConverter = BlockConverter {
-  input_block_type: BlockType::BarForces,
+/// Conversion template for CSHEAR forces. The corner forces don't fit, so
+/// each element gets a row of kick forces and a row of shear flows.
+pub const CT_FORCES_CSHEAR: BlockConverter = BlockConverter {
+  input_block_type: BlockType::CshearForces,
   output_block_id: CsvBlockId::EngForces,
   generators: &[
     cols!(
-      BarForceField,
+      CshearForceField,
       [
         ColumnGenerator::ElementId,
         ColumnGenerator::Subcase,
-        ZERO,
-        END_A,
-      ],
-      [
-        BarForceField::AxialForce,
-        BarForceField::Shear { plane: BarPlane::Plane1 },
-        BarForceField::Shear { plane: BarPlane::Plane2 },
-        BarForceField::BendMoment {end: BarEnd::EndA, plane: BarPlane::Plane1},
-        BarForceField::BendMoment {end: BarEnd::EndA, plane: BarPlane::Plane2},
+        BLANK,
+        BLANK,
       ],
-      [Torque,],
       [],
+      [Kick1, Kick2, Kick3, Kick4,],
+      [BLANK, BLANK,],
     ),
     cols!(
-      BarForceField,
+      CshearForceField,
       [
         ColumnGenerator::ElementId,
         ColumnGenerator::Subcase,
-        ZERO,
-        END_B,
-      ],
-      [
-        BarForceField::AxialForce,
-        BarForceField::Shear { plane: BarPlane::Plane1 },
-        BarForceField::Shear { plane: BarPlane::Plane2 },
-        BarForceField::BendMoment {end: BarEnd::EndB, plane: BarPlane::Plane1},
-        BarForceField::BendMoment {end: BarEnd::EndB, plane: BarPlane::Plane2},
+        BLANK,
+        BLANK,
       ],
-      [Torque,],
       [],
+      [Shear12, Shear23, Shear34, Shear41,],
+      [BLANK, BLANK,],
     )
   ],
-  headers: &[BAR_FORCES_HEADER, BAR_FORCES_HEADER]
+  headers: &[
+    [
+      "EID (SHEAR)", "Subcase", HBLANK, HBLANK, "Kick1", "Kick2", "Kick3",
+      "Kick4", HBLANK, HBLANK
+    ],
+    [
+      "EID (SHEAR)", "Subcase", HBLANK, HBLANK, "Q12", "Q23", "Q34", "Q41",
+      HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
 };
 
-/// Conversion template for ELAS1 forces.
-pub const CT_FORCES_ELAS1: BlockConverter = BlockConverter {
-  input_block_type: BlockType::Elas1Forces,
+/// Conversion template for CGAP forces: the forces, then the displacements.
+pub const CT_FORCES_CGAP: BlockConverter = BlockConverter {
+  input_block_type: BlockType::CgapForces,
   output_block_id: CsvBlockId::EngForces,
   generators: &[
     cols!(
-      SingleForce,
+      CgapForceField,
       [
         ColumnGenerator::ElementId,
         ColumnGenerator::Subcase,
+        GAP_STATUS,
         BLANK,
+      ],
+      [],
+      [CompressiveForce, ShearPlane1, ShearPlane2,],
+      [BLANK, BLANK, BLANK,],
+    ),
+    cols!(
+      CgapForceField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        GAP_STATUS,
         BLANK,
       ],
       [],
-      [Force,],
-      [BLANK, BLANK, BLANK, BLANK, BLANK,],
+      [
+        AxialDisplacement, TotalDisplacement1, TotalDisplacement2, Slip1,
+        Slip2,
+      ],
+      [BLANK,],
     )
   ],
   headers: &[
     [
-      "EID (ELAS1)", "Subcase", HBLANK, HBLANK, "Force",
-      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+      "EID (GAP)", "Subcase", "Status", HBLANK, "CompX", "ShearY", "ShearZ",
+      HBLANK, HBLANK, HBLANK
+    ],
+    [
+      "EID (GAP)", "Subcase", "Status", HBLANK, "AxialU", "TotalV", "TotalW",
+      "SlipV", "SlipW", HBLANK
     ]
-  ]
-};
-
-/// Conversion template for BUSH forces.
-pub const CT_FORCES_BUSH: BlockConverter = BlockConverter {
-  input_block_type: BlockType::BushForces,
-  output_block_id: CsvBlockId::EngForces,
-  generators: CT_STRESSES_BUSH.generators,
-  headers: &[
-    ["EID (BUSH)", "Subcase", HBLANK, HBLANK, "Fx", "Fy", "Fz", "Mx", "My", "Mz"]
-  ]
+  ],
+  extras: &[]
 };
 
 /// Conversion template for the load vector.
@@ -687,7 +1800,30 @@ pub const CT_APPLIED_FORCES: BlockConverter = BlockConverter {
   ],
   headers: &[
     ["GID", "Subcase", "Fx", "Fy", "Fz", "Mx", "My", "Mz", HBLANK, HBLANK]
-  ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for the equivalent nodal forces of thermal loads.
+pub const CT_THERMAL_LOADS: BlockConverter = BlockConverter {
+  input_block_type: BlockType::ThermalLoads,
+  output_block_id: CsvBlockId::ThermalLoads,
+  generators: &[
+    cols!(
+      Dof,
+      [
+        ColumnGenerator::GridId,
+        ColumnGenerator::Subcase,
+      ],
+      [DOF_TX, DOF_TY, DOF_TZ, DOF_RX, DOF_RY, DOF_RZ,],
+      [],
+      [BLANK, BLANK,],
+    )
+  ],
+  headers: &[
+    ["GID", "Subcase", "Fx", "Fy", "Fz", "Mx", "My", "Mz", HBLANK, HBLANK]
+  ],
+  extras: &[]
 };
 
 /// Conversion template for SPC forces.
@@ -708,5 +1844,259 @@ pub const CT_SPC_FORCES: BlockConverter = BlockConverter {
   ],
   headers: &[
     ["GID", "Subcase", "Fx", "Fy", "Fz", "Mx", "My", "Mz", HBLANK, HBLANK]
-  ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for MPC forces.
+pub const CT_MPC_FORCES: BlockConverter = BlockConverter {
+  input_block_type: BlockType::MpcForces,
+  output_block_id: CsvBlockId::MpcForces,
+  generators: &[
+    cols!(
+      Dof,
+      [
+        ColumnGenerator::GridId,
+        ColumnGenerator::Subcase,
+      ],
+      [DOF_TX, DOF_TY, DOF_TZ, DOF_RX, DOF_RY, DOF_RZ,],
+      [],
+      [BLANK, BLANK,],
+    )
+  ],
+  headers: &[
+    ["GID", "Subcase", "Fx", "Fy", "Fz", "Mx", "My", "Mz", HBLANK, HBLANK]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for grid point temperatures.
+pub const CT_TEMPERATURES: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Temperatures,
+  output_block_id: CsvBlockId::Temperatures,
+  generators: &[
+    cols!(
+      SingleTemperature,
+      [
+        ColumnGenerator::GridId,
+        ColumnGenerator::Subcase,
+        BLANK,
+        BLANK,
+      ],
+      [],
+      [Temperature,],
+      [BLANK, BLANK, BLANK, BLANK, BLANK,],
+    )
+  ],
+  headers: &[
+    [
+      "GID", "Subcase", HBLANK, HBLANK, "Temperature",
+      HBLANK, HBLANK, HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for composite failure indices, by theory or by
+/// criterion.
+pub const CT_COMPOSITE_FAILURE: BlockConverter = BlockConverter {
+  input_block_type: BlockType::CompositeFailureIndices,
+  output_block_id: CsvBlockId::CompositeFailure,
+  generators: &[
+    cols!(
+      CompositeFailureField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::ElementType,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::RowIndexFn(&(ixfn_ply_id as IndexFn)),
+        ColumnGenerator::RowIndexFn(&(ixfn_layer_kind as IndexFn)),
+        ColumnGenerator::BlockMetadata(META_FAILURE_THEORY),
+      ],
+      [],
+      [FailureIndex, StrengthRatio,],
+      [BLANK, BLANK,],
+    ),
+    cols!(
+      CompositeFailureField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::RowIndexFn(&(ixfn_ply_id as IndexFn)),
+        SIDE,
+      ],
+      [],
+      [TsaiWu, TsaiHill, Hoffman, MaxStress, MaxStrain,],
+      [PLY_STATUS,],
+    )
+  ],
+  headers: &[
+    [
+      "EID", "EType", "Subcase", "Ply", "Layer",
+      "Theory", "FailureIndex", "StrengthRatio", HBLANK, HBLANK
+    ],
+    [
+      "EID", "Subcase", "Ply", "Side", "TsaiWu", "TsaiHill", "Hoffman",
+      "MaxStress", "MaxStrain", "Status"
+    ]
+  ],
+  extras: &[PLY, PLY]
+};
+
+/// Conversion template for the stresses in the plies of composite elements.
+/// The inter-laminar shears, the principal angle and the maximum shear don't
+/// fit.
+pub const CT_COMPOSITE_STRESSES: BlockConverter = BlockConverter {
+  input_block_type: BlockType::CompositePlystresses,
+  output_block_id: CsvBlockId::CompositeStresses,
+  generators: &[
+    cols!(
+      CompositeStressField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::RowIndexFn(&(ixfn_ply_id as IndexFn)),
+        SIDE,
+      ],
+      [],
+      [Normal1, Normal2, Shear12, Major, Minor,],
+      [
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ColumnValue(
+            NasIndex::CompositeStressField(CompositeStressField::VonMises)
+          ),
+          &CsvField::Blank
+        ),
+      ],
+    )
+  ],
+  headers: &[
+    [
+      "EID", "Subcase", "Ply", "Side", "Normal1", "Normal2", "Shear12",
+      "Major", "Minor", "VonMises"
+    ]
+  ],
+  extras: &[PLY]
+};
+
+/// Conversion template for grid point stresses.
+pub const CT_STRESSES_GRID: BlockConverter = BlockConverter {
+  input_block_type: BlockType::GridPointStresses,
+  output_block_id: CsvBlockId::Stresses,
+  generators: &[
+    cols!(
+      GridStressField,
+      [
+        ColumnGenerator::GridId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::RowIndexFn(&(ixfn_surface as IndexFn)),
+      ],
+      [],
+      [NormalX, NormalY, ShearXY, Major, Minor, MaxShear, VonMises,],
+      [],
+    )
+  ],
+  headers: &[
+    [
+      "GID", "Subcase", "Surface", "NormalX", "NormalY", "ShearXY", "Major",
+      "Minor", "MaxShear", "VonMises"
+    ]
+  ],
+  extras: &[SIDE]
+};
+
+/// Conversion template for element strain energies.
+pub const CT_STRAIN_ENERGY: BlockConverter = BlockConverter {
+  input_block_type: BlockType::StrainEnergy,
+  output_block_id: CsvBlockId::StrainEnergy,
+  generators: &[
+    cols!(
+      StrainEnergyField,
+      [
+        ColumnGenerator::ElementId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::WithDefault(
+          &ColumnGenerator::ElementType, &CsvField::Blank
+        ),
+      ],
+      [],
+      [Energy, PercentTotal, Density,],
+      [
+        ColumnGenerator::RealMetadata(META_TOTAL_ENERGY), BLANK, BLANK, BLANK,
+      ],
+    )
+  ],
+  headers: &[
+    [
+      "EID", "Subcase", "Type", "Energy", "PercentTotal", "Density", "Total",
+      HBLANK, HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for real eigenvalue tables.
+pub const CT_EIGENVALUES_REAL: BlockConverter = BlockConverter {
+  input_block_type: BlockType::RealEigenvalues,
+  output_block_id: CsvBlockId::Eigenvalues,
+  generators: &[
+    cols!(
+      EigenvalueField,
+      [
+        ColumnGenerator::RowIndexFn(&(ixfn_mode as IndexFn)),
+        ColumnGenerator::Subcase,
+      ],
+      [],
+      [
+        ExtractionOrder, Eigenvalue, Radians, Cycles, GeneralizedMass,
+        GeneralizedStiffness,
+      ],
+      [BLANK, BLANK,],
+    )
+  ],
+  headers: &[
+    [
+      "Mode", "Subcase", "Order", "Eigenvalue", "Radians", "Cycles",
+      "GenMass", "GenStiffness", HBLANK, HBLANK
+    ]
+  ],
+  extras: &[]
+};
+
+/// Conversion template for real eigenvectors.
+pub const CT_EIGENVECTORS_REAL: BlockConverter = BlockConverter {
+  input_block_type: BlockType::Eigenvectors,
+  output_block_id: CsvBlockId::Eigenvectors,
+  generators: &[
+    cols!(
+      Dof,
+      [
+        ColumnGenerator::GridId,
+        ColumnGenerator::Subcase,
+        ColumnGenerator::BlockMetadata(META_MODE),
+      ],
+      [DOF_TX, DOF_TY, DOF_TZ, DOF_RX, DOF_RY, DOF_RZ,],
+      [],
+      [BLANK,],
+    )
+  ],
+  headers: &[
+    ["GID", "Subcase", "Mode", "Tx", "Ty", "Tz", "Rx", "Ry", "Rz", HBLANK]
+  ],
+  extras: &[]
 };
+
+/// Conversion template for the maximum displacements per DOF.
+pub const CT_MAX_DISPLACEMENTS: BlockConverter = max_summary_converter!(
+  MaxDisplacements, "Displacements"
+);
+
+/// Conversion template for the maximum applied loads per DOF.
+pub const CT_MAX_APPLIED_LOADS: BlockConverter = max_summary_converter!(
+  MaxAppliedLoads, "AppliedLoads"
+);
+
+/// Conversion template for the maximum SPC forces per DOF.
+pub const CT_MAX_SPC_FORCES: BlockConverter = max_summary_converter!(
+  MaxSpcForces, "SpcForces"
+);
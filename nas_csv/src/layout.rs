@@ -2,6 +2,7 @@
 
 #![allow(clippy::needless_return)] // i'll never forgive rust for this
 
+use std::collections::BTreeMap;
 use std::fmt::Display;
 
 use clap::builder::PossibleValue;
@@ -10,6 +11,8 @@ use f06::prelude::*;
 use f06::util::fmt_f64;
 use serde::{Serialize, Deserialize};
 
+use crate::naming::{HeaderStyle, styled_template_header};
+
 /// Number of fields in a fixed-form CSV record.
 pub const NAS_CSV_COLS: usize = 11;
 
@@ -38,6 +41,31 @@ pub enum CsvBlockId {
   AppliedForces,
   /// The 7-block: forces of single-point constraint.
   SpcForces,
+  /// The 8-block: displacements in a solution set (not per grid point).
+  SolutionSetDisplacements,
+  /// The 9-block: grid point temperatures.
+  Temperatures,
+  /// The 10-block: failure indices in composite elements.
+  CompositeFailure,
+  /// The 11-block: real eigenvalues of a modal analysis.
+  Eigenvalues,
+  /// The 12-block: real eigenvectors (mode shapes) of a modal analysis.
+  Eigenvectors,
+  /// The 13-block: forces of multi-point constraint.
+  MpcForces,
+  /// The 14-block: grid point velocities.
+  Velocities,
+  /// The 15-block: grid point accelerations.
+  Accelerations,
+  /// The 16-block: stresses in the plies of composite elements.
+  CompositeStresses,
+  /// The 17-block: strain energy of each element.
+  StrainEnergy,
+  /// The 18-block: equivalent nodal forces of thermal loads.
+  ThermalLoads,
+  /// The 19-block: maximums per DOF of displacements, applied loads and SPC
+  /// forces, with the grid points where they occur.
+  MaxSummaries,
 }
 
 // this impl allow numerical shorthands
@@ -64,7 +92,19 @@ impl CsvBlockId {
       Self::EngForces,
       Self::GridPointForces,
       Self::AppliedForces,
-      Self::SpcForces
+      Self::SpcForces,
+      Self::SolutionSetDisplacements,
+      Self::Temperatures,
+      Self::CompositeFailure,
+      Self::Eigenvalues,
+      Self::Eigenvectors,
+      Self::MpcForces,
+      Self::Velocities,
+      Self::Accelerations,
+      Self::CompositeStresses,
+      Self::StrainEnergy,
+      Self::ThermalLoads,
+      Self::MaxSummaries
     ];
   }
 
@@ -78,7 +118,19 @@ impl CsvBlockId {
       Self::EngForces => "EngForces",
       Self::GridPointForces => "GridPointForces",
       Self::AppliedForces => "AppliedForces",
-      Self::SpcForces => "SpcForces"
+      Self::SpcForces => "SpcForces",
+      Self::SolutionSetDisplacements => "SolutionSetDisplacements",
+      Self::Temperatures => "Temperatures",
+      Self::CompositeFailure => "CompositeFailure",
+      Self::Eigenvalues => "Eigenvalues",
+      Self::Eigenvectors => "Eigenvectors",
+      Self::MpcForces => "MpcForces",
+      Self::Velocities => "Velocities",
+      Self::Accelerations => "Accelerations",
+      Self::CompositeStresses => "CompositeStresses",
+      Self::StrainEnergy => "StrainEnergy",
+      Self::ThermalLoads => "ThermalLoads",
+      Self::MaxSummaries => "MaxSummaries"
     };
   }
 
@@ -92,7 +144,19 @@ impl CsvBlockId {
       Self::EngForces => "engfor",
       Self::GridPointForces => "gpforce",
       Self::AppliedForces => "load",
-      Self::SpcForces => "spcfor"
+      Self::SpcForces => "spcfor",
+      Self::SolutionSetDisplacements => "soldisp",
+      Self::Temperatures => "temp",
+      Self::CompositeFailure => "compfail",
+      Self::Eigenvalues => "eigen",
+      Self::Eigenvectors => "eigvec",
+      Self::MpcForces => "mpcfor",
+      Self::Velocities => "vel",
+      Self::Accelerations => "accel",
+      Self::CompositeStresses => "compstress",
+      Self::StrainEnergy => "energy",
+      Self::ThermalLoads => "thermload",
+      Self::MaxSummaries => "max"
     }
   }
 
@@ -109,7 +173,23 @@ impl CsvBlockId {
         "grid_point_force_balance"
       ],
       Self::AppliedForces => &["6", "applied"],
-      Self::SpcForces => &["7", "spcf", "spcforces"]
+      Self::SpcForces => &["7", "spcf", "spcforces"],
+      Self::SolutionSetDisplacements => &["8", "solset", "solution_set"],
+      Self::Temperatures => &["9", "temps", "temperatures"],
+      Self::CompositeFailure => &[
+        "10", "composite", "failure", "failure_indices"
+      ],
+      Self::Eigenvalues => &["11", "eigenvalues", "modes", "frequencies"],
+      Self::Eigenvectors => &["12", "eigenvectors", "mode_shapes"],
+      Self::MpcForces => &["13", "mpcf", "mpcforces"],
+      Self::Velocities => &["14", "velo", "velocities"],
+      Self::Accelerations => &["15", "acce", "accelerations"],
+      Self::CompositeStresses => &[
+        "16", "composite_stresses", "ply_stresses"
+      ],
+      Self::StrainEnergy => &["17", "ese", "strain_energy"],
+      Self::ThermalLoads => &["18", "thermal", "thermal_loads"],
+      Self::MaxSummaries => &["19", "maximums", "max_summaries"]
     }
   }
 }
@@ -131,6 +211,18 @@ impl From<CsvBlockId> for usize {
       CsvBlockId::GridPointForces => 5,
       CsvBlockId::AppliedForces => 6,
       CsvBlockId::SpcForces => 7,
+      CsvBlockId::SolutionSetDisplacements => 8,
+      CsvBlockId::Temperatures => 9,
+      CsvBlockId::CompositeFailure => 10,
+      CsvBlockId::Eigenvalues => 11,
+      CsvBlockId::Eigenvectors => 12,
+      CsvBlockId::MpcForces => 13,
+      CsvBlockId::Velocities => 14,
+      CsvBlockId::Accelerations => 15,
+      CsvBlockId::CompositeStresses => 16,
+      CsvBlockId::StrainEnergy => 17,
+      CsvBlockId::ThermalLoads => 18,
+      CsvBlockId::MaxSummaries => 19,
     };
   }
 }
@@ -154,6 +246,18 @@ impl TryFrom<usize> for CsvBlockId {
       5 => CsvBlockId::GridPointForces,
       6 => CsvBlockId::AppliedForces,
       7 => CsvBlockId::SpcForces,
+      8 => CsvBlockId::SolutionSetDisplacements,
+      9 => CsvBlockId::Temperatures,
+      10 => CsvBlockId::CompositeFailure,
+      11 => CsvBlockId::Eigenvalues,
+      12 => CsvBlockId::Eigenvectors,
+      13 => CsvBlockId::MpcForces,
+      14 => CsvBlockId::Velocities,
+      15 => CsvBlockId::Accelerations,
+      16 => CsvBlockId::CompositeStresses,
+      17 => CsvBlockId::StrainEnergy,
+      18 => CsvBlockId::ThermalLoads,
+      19 => CsvBlockId::MaxSummaries,
       _ => return Err(())
     });
   }
@@ -202,6 +306,64 @@ impl Display for CsvField {
   }
 }
 
+/// The marker written instead of a line number for records that weren't read
+/// from a line, but derived from other data.
+pub const DERIVED_MARKER: &str = "derived";
+
+/// The headers of the provenance columns, written after everything else when
+/// asked for.
+pub const PROVENANCE_HEADERS: [&str; 3] = [
+  "SourceLine", "BlockUID", "BlockStart"
+];
+
+/// Where a record's row came from in an F06 file.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SourceLine {
+  /// It was read from this line.
+  Line(usize),
+  /// It was derived from other data, not read.
+  Derived,
+  /// It's not known (e.g. the block was merged from others).
+  Unknown
+}
+
+/// What's known about where a record came from: the line and the block.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Provenance {
+  /// The line the row was read from.
+  pub source_line: SourceLine,
+  /// The UID of the block the row is in, if it has one.
+  pub block_uid: Option<usize>,
+  /// The first line of the block, if known.
+  pub block_start: Option<usize>
+}
+
+impl Provenance {
+  /// Finds the provenance of a row of a block.
+  pub fn of_row(block: &FinalBlock, row: NasIndex) -> Self {
+    return Self {
+      source_line: block.source_line(row)
+        .map_or(SourceLine::Unknown, SourceLine::Line),
+      block_uid: block.uid,
+      block_start: block.line_range.map(|lr| lr.0)
+    };
+  }
+
+  /// Returns the provenance columns.
+  pub fn to_fields(&self) -> [CsvField; 3] {
+    let opt = |n: Option<usize>| n.map_or(CsvField::Blank, CsvField::Natural);
+    return [
+      match self.source_line {
+        SourceLine::Line(n) => CsvField::Natural(n),
+        SourceLine::Derived => CsvField::String(DERIVED_MARKER.to_owned()),
+        SourceLine::Unknown => CsvField::Blank
+      },
+      opt(self.block_uid),
+      opt(self.block_start)
+    ];
+  }
+}
+
 /// A non-header line in a CSV file.
 #[derive(Clone, Debug, Serialize)]
 pub struct CsvRecord {
@@ -220,7 +382,16 @@ pub struct CsvRecord {
   /// The remaining ten fields.
   pub fields: [CsvField; NAS_CSV_COLS-1],
   /// The headers for the ten fields.
-  pub headers: &'static RowHeader
+  pub headers: &'static RowHeader,
+  /// The columns of the originating block the ten fields came from, if any.
+  pub columns: [Option<NasIndex>; NAS_CSV_COLS-1],
+  /// Values for the extra row dimensions (side, end, etc.) this record has.
+  /// These go after the fixed-form fields, in their own columns.
+  pub extras: BTreeMap<RowDimension, CsvField>,
+  /// Where the record came from, for records converted from a block. Only
+  /// written out when provenance columns are asked for.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub provenance: Option<Provenance>
 }
 
 impl CsvRecord {
@@ -235,4 +406,281 @@ impl CsvRecord {
       self.headers.iter().copied()
     );
   }
+
+  /// Returns this as eleven fields, plus one for each extra dimension passed
+  /// (blank if this record doesn't have it).
+  pub fn to_fields_with(
+    mut self,
+    dims: &[RowDimension]
+  ) -> impl Iterator<Item = CsvField> {
+    let extras = dims.iter()
+      .map(|d| self.extras.remove(d).unwrap_or(CsvField::Blank))
+      .collect::<Vec<_>>();
+    return self.to_fields().chain(extras);
+  }
+
+  /// Returns this block's headers as eleven strings, plus the names of the
+  /// extra dimensions passed.
+  pub fn header_with<'a>(
+    &'a self,
+    dims: &'a [RowDimension]
+  ) -> impl Iterator<Item = &'a str> {
+    return self.header_as_iter().chain(dims.iter().map(|d| d.name()));
+  }
+}
+
+/// The first field of the schema row, which sets it apart from the records.
+pub const SCHEMA_MARKER: &str = "#schema";
+
+/// The version of the fixed-form layout (eleven columns, then the extra row
+/// dimensions) written in the schema row.
+pub const CSV_FORMAT_VERSION: &str = "nas-csv-v1";
+
+/// What the schema row says about headers when none are written.
+const NO_HEADERS: &str = "none";
+
+/// What the schema row says about headers when they use the template names.
+const DEFAULT_HEADERS: &str = "default";
+
+/// Separates the extra row dimensions in the schema row.
+const EXTRAS_SEPARATOR: &str = ";";
+
+/// What the schema row says when the provenance columns are written.
+const WITH_PROVENANCE: &str = "provenance";
+
+/// Describes the layout of a CSV file, so it can be read without knowing how
+/// it was written. It's written as the first row: the schema marker, the
+/// format version, the version of this crate, the header style ("none" if
+/// there are no headers, "default" for the template names), the extra row
+/// dimensions, separated by semicolons, and "provenance" if the provenance
+/// columns come last. The rest of the row is left empty.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsvSchemaDescriptor {
+  /// The version of the layout.
+  pub format: String,
+  /// The version of the crate that wrote the file.
+  pub crate_version: String,
+  /// Whether there are header rows.
+  pub headers: bool,
+  /// The style of the header names, if not the template ones.
+  pub header_style: Option<HeaderStyle>,
+  /// The extra row dimensions, in the order of their columns.
+  pub extras: Vec<RowDimension>,
+  /// Whether the provenance columns come last.
+  #[serde(default)]
+  pub provenance: bool
+}
+
+/// The ways a CSV file can disagree with its schema row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CsvSchemaError {
+  /// The first row isn't a schema row.
+  Missing,
+  /// The schema row is for a layout we don't know.
+  UnknownFormat(String),
+  /// A field of the schema row couldn't be understood.
+  BadField {
+    /// What the field describes.
+    field: &'static str,
+    /// What was in it.
+    value: String
+  },
+  /// A row doesn't have as many columns as the schema says.
+  WrongWidth {
+    /// The row number, starting at 1 for the schema row.
+    row: usize,
+    /// How many columns the schema says there are.
+    expected: usize,
+    /// How many columns there are.
+    found: usize
+  },
+  /// A row doesn't start with a CSV block ID (or its name, for headers).
+  BadBlockId {
+    /// The row number, starting at 1 for the schema row.
+    row: usize,
+    /// What was there instead.
+    value: String
+  }
+}
+
+impl Display for CsvSchemaError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::Missing => write!(f, "the first row is not a schema row"),
+      Self::UnknownFormat(s) => write!(f, "unknown CSV format \"{}\"", s),
+      Self::BadField { field, value } => {
+        write!(f, "bad {} in the schema row: \"{}\"", field, value)
+      },
+      Self::WrongWidth { row, expected, found } => write!(
+        f,
+        "row {} has {} columns, but the schema says {}",
+        row,
+        found,
+        expected
+      ),
+      Self::BadBlockId { row, value } => {
+        write!(f, "row {} starts with \"{}\", not a block ID", row, value)
+      }
+    };
+  }
+}
+
+impl std::error::Error for CsvSchemaError {}
+
+impl CsvSchemaDescriptor {
+  /// Describes the fixed-form layout as written by this version of the crate.
+  pub fn new(
+    headers: bool,
+    header_style: Option<HeaderStyle>,
+    extras: &[RowDimension]
+  ) -> Self {
+    return Self {
+      format: CSV_FORMAT_VERSION.to_owned(),
+      crate_version: env!("CARGO_PKG_VERSION").to_owned(),
+      headers,
+      header_style: header_style.filter(|_| headers),
+      extras: extras.to_vec(),
+      provenance: false
+    };
+  }
+
+  /// Sets whether the provenance columns are written.
+  pub fn with_provenance(mut self, provenance: bool) -> Self {
+    self.provenance = provenance;
+    return self;
+  }
+
+  /// Returns the number of columns in the described rows.
+  pub fn width(&self) -> usize {
+    let provenance = if self.provenance { PROVENANCE_HEADERS.len() } else { 0 };
+    return NAS_CSV_COLS + self.extras.len() + provenance;
+  }
+
+  /// Returns the schema row, with as many fields as the described rows.
+  pub fn to_fields(&self) -> Vec<String> {
+    let headers = match (self.headers, self.header_style) {
+      (false, _) => NO_HEADERS.to_owned(),
+      (true, None) => DEFAULT_HEADERS.to_owned(),
+      (true, Some(style)) => style.to_possible_value()
+        .map(|pv| pv.get_name().to_owned())
+        .unwrap_or_default()
+    };
+    let extras = self.extras.iter()
+      .map(|d| d.name())
+      .collect::<Vec<_>>()
+      .join(EXTRAS_SEPARATOR);
+    let mut fields = vec![
+      SCHEMA_MARKER.to_owned(),
+      self.format.clone(),
+      self.crate_version.clone(),
+      headers,
+      extras,
+      if self.provenance { WITH_PROVENANCE.to_owned() } else { String::new() }
+    ];
+    fields.resize(self.width().max(fields.len()), String::new());
+    return fields;
+  }
+
+  /// Reads a schema row.
+  pub fn from_fields<S: AsRef<str>>(
+    fields: &[S]
+  ) -> Result<Self, CsvSchemaError> {
+    let field = |i: usize| fields.get(i).map_or("", |f| f.as_ref().trim());
+    if field(0) != SCHEMA_MARKER {
+      return Err(CsvSchemaError::Missing);
+    }
+    if field(1) != CSV_FORMAT_VERSION {
+      return Err(CsvSchemaError::UnknownFormat(field(1).to_owned()));
+    }
+    let (headers, header_style) = match field(3) {
+      NO_HEADERS => (false, None),
+      DEFAULT_HEADERS => (true, None),
+      s => match HeaderStyle::from_str(s, true) {
+        Ok(style) => (true, Some(style)),
+        Err(_) => return Err(CsvSchemaError::BadField {
+          field: "header style",
+          value: s.to_owned()
+        })
+      }
+    };
+    let extras = field(4).split(EXTRAS_SEPARATOR)
+      .filter(|s| !s.is_empty())
+      .map(|s| {
+        return RowDimension::extras().iter()
+          .find(|d| d.name() == s)
+          .copied()
+          .ok_or_else(|| CsvSchemaError::BadField {
+            field: "extra row dimension",
+            value: s.to_owned()
+          });
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+    let provenance = match field(5) {
+      "" => false,
+      WITH_PROVENANCE => true,
+      s => return Err(CsvSchemaError::BadField {
+        field: "provenance",
+        value: s.to_owned()
+      })
+    };
+    return Ok(Self {
+      format: field(1).to_owned(),
+      crate_version: field(2).to_owned(),
+      headers,
+      header_style,
+      extras,
+      provenance
+    });
+  }
+
+  /// Checks a row (other than the schema one) against this schema: it must
+  /// have the declared number of columns and start with a CSV block ID, or
+  /// with its name if it's a header row. The row number is for errors.
+  pub fn check_row<S: AsRef<str>>(
+    &self,
+    row: usize,
+    fields: &[S]
+  ) -> Result<(), CsvSchemaError> {
+    if fields.len() != self.width() {
+      return Err(CsvSchemaError::WrongWidth {
+        row,
+        expected: self.width(),
+        found: fields.len()
+      });
+    }
+    let first = fields[0].as_ref().trim();
+    let is_id = first.parse::<usize>().is_ok_and(|n| {
+      return CsvBlockId::try_from(n).is_ok();
+    });
+    let is_name = self.headers && CsvBlockId::all().iter().any(|bid| {
+      return match self.header_style {
+        None => bid.name() == first,
+        Some(style) => styled_template_header(bid.name(), style) == first
+      };
+    });
+    if !is_id && !is_name {
+      return Err(CsvSchemaError::BadBlockId { row, value: first.to_owned() });
+    }
+    return Ok(());
+  }
+
+  /// Reads the schema row off the first of some rows, then checks all the
+  /// others against it. Returns the schema.
+  pub fn validate<I, R, S>(rows: I) -> Result<Self, CsvSchemaError>
+  where
+    I: IntoIterator<Item = R>,
+    R: AsRef<[S]>,
+    S: AsRef<str>
+  {
+    let mut rows = rows.into_iter();
+    let schema = match rows.next() {
+      Some(first) => Self::from_fields(first.as_ref())?,
+      None => return Err(CsvSchemaError::Missing)
+    };
+    for (i, row) in rows.enumerate() {
+      schema.check_row(i + 2, row.as_ref())?;
+    }
+    return Ok(schema);
+  }
 }
@@ -6,13 +6,24 @@
 #![warn(missing_docs)]
 #![warn(clippy::missing_docs_in_private_items)]
 
+pub mod canonical;
 pub mod formatting;
 pub mod from_f06;
 pub mod layout;
+pub mod naming;
+pub mod report;
+pub mod split;
 
 /// Imports the most relevant exports from the library.
 pub mod prelude {
+  pub use super::canonical::*;
   pub use super::formatting::*;
   pub use super::from_f06::*;
   pub use super::layout::*;
+  pub use super::naming::*;
+  pub use super::report::*;
+  pub use super::split::*;
 }
+
+#[cfg(test)]
+mod tests;
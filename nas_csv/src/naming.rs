@@ -0,0 +1,1099 @@
+//! This module implements names for the columns of the supported block types,
+//! for CSV consumers that want something other than the usual headers. Each
+//! column gets a machine name (snake case, fit for a dataframe), a short name
+//! (like the usual headers) and a long, descriptive name with placeholders for
+//! its units, like "Normal stress X [{stress}]".
+//!
+//! Names depend on the block type as well as the column: a translational DOF
+//! is a translation in a displacements block, but a force in an applied
+//! forces block. The units come from the physical dimension of each column
+//! (see `NasIndex::dimension`), the same one unit scaling goes by.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use f06::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// The quantities that can be given units, as they appear in placeholders.
+pub const QUANTITIES: &[&str] = &[
+  "length", "angle", "force", "moment", "stress", "strain", "temperature"
+];
+
+/// Placeholder for lengths.
+const LENGTH: &str = "{length}";
+
+/// Placeholder for angles.
+const ANGLE: &str = "{angle}";
+
+/// Placeholder for forces.
+const FORCE: &str = "{force}";
+
+/// Placeholder for moments.
+const MOMENT: &str = "{moment}";
+
+/// Placeholder for forces per unit length.
+const FORCE_PER_LENGTH: &str = "{force}/{length}";
+
+/// Placeholder for moments per unit length.
+const MOMENT_PER_LENGTH: &str = "{moment}/{length}";
+
+/// Placeholder for stresses.
+const STRESS: &str = "{stress}";
+
+/// Placeholder for strains.
+const STRAIN: &str = "{strain}";
+
+/// Placeholder for energies.
+const ENERGY: &str = "{force}*{length}";
+
+/// Placeholder for energies per unit volume.
+const ENERGY_DENSITY: &str = "{force}/{length}^2";
+
+/// Placeholder for temperatures.
+const TEMPERATURE: &str = "{temperature}";
+
+/// Which of the names to use for CSV headers.
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, ValueEnum, PartialEq, Eq
+)]
+#[clap(rename_all = "snake_case")]
+pub enum HeaderStyle {
+  /// Snake case identifiers, like "normal_x".
+  Machine,
+  /// Short names, like "NormalX".
+  Short,
+  /// Long names with units, like "Normal stress X [MPa]".
+  Long
+}
+
+/// An error parsing a unit map.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnitMapError {
+  /// A term isn't of the form "quantity=unit".
+  BadTerm(String),
+  /// A quantity isn't one of the known ones.
+  UnknownQuantity(String)
+}
+
+impl Display for UnitMapError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return match self {
+      Self::BadTerm(t) => {
+        write!(f, "\"{}\" is not of the form \"quantity=unit\"", t)
+      },
+      Self::UnknownQuantity(q) => write!(
+        f,
+        "unknown quantity \"{}\" (known: {})",
+        q,
+        QUANTITIES.join(", ")
+      ),
+    };
+  }
+}
+
+impl std::error::Error for UnitMapError {}
+
+/// The units of some quantities, to substitute into long names. Parses from
+/// comma-separated pairs, like "stress=MPa,length=mm".
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UnitMap(BTreeMap<String, String>);
+
+impl Default for UnitMap {
+  fn default() -> Self {
+    return Self::new();
+  }
+}
+
+impl UnitMap {
+  /// Makes an empty unit map.
+  pub fn new() -> Self {
+    return Self(BTreeMap::new());
+  }
+
+  /// Returns the unit of a quantity, if given.
+  pub fn get(&self, quantity: &str) -> Option<&str> {
+    return self.0.get(quantity).map(String::as_str);
+  }
+
+  /// Sets the unit of a quantity.
+  pub fn insert(&mut self, quantity: &str, unit: &str) {
+    self.0.insert(quantity.to_owned(), unit.to_owned());
+  }
+}
+
+impl FromStr for UnitMap {
+  type Err = UnitMapError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut units = Self::new();
+    for term in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+      let (quantity, unit) = match term.split_once('=') {
+        Some((q, u)) if !q.trim().is_empty() && !u.trim().is_empty() => {
+          (q.trim().to_lowercase(), u.trim())
+        },
+        _ => return Err(UnitMapError::BadTerm(term.to_owned()))
+      };
+      if !QUANTITIES.contains(&quantity.as_str()) {
+        return Err(UnitMapError::UnknownQuantity(quantity));
+      }
+      units.insert(&quantity, unit);
+    }
+    return Ok(units);
+  }
+}
+
+/// The names of a column.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ColumnNames {
+  /// A snake case identifier, unique within the block type.
+  pub machine: String,
+  /// A short name, like the usual headers.
+  pub short: String,
+  /// A long, descriptive name, with placeholders for units (e.g. "{stress}").
+  pub long: String
+}
+
+impl ColumnNames {
+  /// Returns the long name with the units substituted. Quantities without a
+  /// unit are left as their names, e.g. "[stress]".
+  pub fn long_with(&self, units: &UnitMap) -> String {
+    let mut long = self.long.clone();
+    for quantity in QUANTITIES.iter().copied() {
+      let unit = units.get(quantity).unwrap_or(quantity);
+      long = long.replace(&format!("{{{}}}", quantity), unit);
+    }
+    return long;
+  }
+
+  /// Returns the name in a style.
+  pub fn styled(&self, style: HeaderStyle, units: &UnitMap) -> String {
+    return match style {
+      HeaderStyle::Machine => self.machine.clone(),
+      HeaderStyle::Short => self.short.clone(),
+      HeaderStyle::Long => self.long_with(units),
+    };
+  }
+}
+
+/// What the values in a block are, as far as naming goes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Kind {
+  /// Displacements (translations and rotations).
+  Displacement,
+  /// Velocities (translational and rotational).
+  Velocity,
+  /// Accelerations (translational and rotational).
+  Acceleration,
+  /// Forces and moments.
+  Force,
+  /// Stresses.
+  Stress,
+  /// Strains.
+  Strain,
+  /// Anything else (the columns say it all).
+  Other
+}
+
+impl Kind {
+  /// Returns the kind of values in a block type.
+  fn of(block_type: BlockType) -> Self {
+    return match block_type {
+      BlockType::Displacements
+        | BlockType::SolutionSetDisplacements
+        | BlockType::Eigenvectors
+        | BlockType::MaxDisplacements => Self::Displacement,
+      BlockType::Velocities => Self::Velocity,
+      BlockType::Accelerations => Self::Acceleration,
+      BlockType::GridPointForceBalance
+        | BlockType::SpcForces
+        | BlockType::MpcForces
+        | BlockType::AppliedForces
+        | BlockType::ThermalLoads
+        | BlockType::MaxAppliedLoads
+        | BlockType::MaxSpcForces
+        | BlockType::Elas1Forces
+        | BlockType::Elas2Forces
+        | BlockType::Elas3Forces
+        | BlockType::Elas4Forces
+        | BlockType::CdampForces
+        | BlockType::ViscForces
+        | BlockType::RodForces
+        | BlockType::ConrodForces
+        | BlockType::BarForces
+        | BlockType::BeamForces
+        | BlockType::TriaForces
+        | BlockType::QuadForces
+        | BlockType::CshearForces
+        | BlockType::CgapForces
+        | BlockType::BushForces => Self::Force,
+      BlockType::Elas1Stresses
+        | BlockType::Elas2Stresses
+        | BlockType::Elas3Stresses
+        | BlockType::Elas4Stresses
+        | BlockType::RodStresses
+        | BlockType::ConrodStresses
+        | BlockType::BarStresses
+        | BlockType::BeamStresses
+        | BlockType::TriaStresses
+        | BlockType::QuadStresses
+        | BlockType::ChexaStresses
+        | BlockType::CpentaStresses
+        | BlockType::CtetraStresses
+        | BlockType::CshearStresses
+        | BlockType::BushStresses
+        | BlockType::CompositePlystresses
+        | BlockType::GridPointStresses => Self::Stress,
+      BlockType::Elas1Strains
+        | BlockType::Elas2Strains
+        | BlockType::Elas3Strains
+        | BlockType::Elas4Strains
+        | BlockType::RodStrains
+        | BlockType::ConrodStrains
+        | BlockType::BarStrains
+        | BlockType::BeamStrains
+        | BlockType::TriaStrains
+        | BlockType::QuadStrains
+        | BlockType::ChexaStrains
+        | BlockType::CpentaStrains
+        | BlockType::CtetraStrains
+        | BlockType::BushStrains => Self::Strain,
+      _ => Self::Other
+    };
+  }
+
+  /// For stresses and strains: the word and the letter.
+  fn stress_words(&self) -> Option<(&'static str, char)> {
+    return match self {
+      Self::Stress => Some(("stress", 's')),
+      Self::Strain => Some(("strain", 'e')),
+      _ => None
+    };
+  }
+}
+
+/// The names of a column before qualifying them with a bar end.
+struct BaseNames {
+  /// The machine name.
+  machine: String,
+  /// The short name.
+  short: String,
+  /// The long name, without units.
+  long: String,
+  /// The bar end, for columns that have one.
+  end: Option<BarEnd>
+}
+
+impl BaseNames {
+  /// Makes base names without a bar end.
+  fn new(machine: &str, short: &str, long: &str) -> Self {
+    return Self {
+      machine: machine.to_owned(),
+      short: short.to_owned(),
+      long: long.to_owned(),
+      end: None
+    };
+  }
+
+  /// Sets the bar end.
+  fn at(mut self, end: BarEnd) -> Self {
+    self.end = Some(end);
+    return self;
+  }
+
+  /// Makes the full names, qualifying them with the bar end if asked to, and
+  /// appending the placeholder for the units, if any.
+  fn finish(self, qualify: bool, unit: Option<&str>) -> ColumnNames {
+    let mut names = ColumnNames {
+      machine: self.machine,
+      short: self.short,
+      long: self.long
+    };
+    if let Some(end) = self.end.filter(|_| qualify) {
+      let letter = match end {
+        BarEnd::EndA => 'A',
+        BarEnd::EndB => 'B',
+      };
+      names.machine.push_str(&format!("_{}", letter.to_ascii_lowercase()));
+      names.short.push_str(&format!(" ({})", letter));
+      names.long.push_str(&format!(", end {}", letter));
+    }
+    if let Some(unit) = unit {
+      names.long.push_str(&format!(" [{}]", unit));
+    }
+    return names;
+  }
+}
+
+/// Returns the placeholder for the units of a physical dimension, if it can
+/// be put in terms of the known quantities. Velocities, accelerations and
+/// frequencies get none, since there are no units for time; masses get none
+/// either.
+fn placeholder(dimension: PhysicalDimension) -> Option<&'static str> {
+  return match dimension {
+    PhysicalDimension::Length => Some(LENGTH),
+    PhysicalDimension::Angle => Some(ANGLE),
+    PhysicalDimension::Force => Some(FORCE),
+    PhysicalDimension::Moment => Some(MOMENT),
+    PhysicalDimension::ForcePerLength => Some(FORCE_PER_LENGTH),
+    PhysicalDimension::MomentPerLength => Some(MOMENT_PER_LENGTH),
+    PhysicalDimension::Stress => Some(STRESS),
+    PhysicalDimension::Strain => Some(STRAIN),
+    PhysicalDimension::Energy => Some(ENERGY),
+    PhysicalDimension::EnergyDensity => Some(ENERGY_DENSITY),
+    PhysicalDimension::Temperature => Some(TEMPERATURE),
+    PhysicalDimension::Dimensionless
+      | PhysicalDimension::Velocity
+      | PhysicalDimension::AngularVelocity
+      | PhysicalDimension::Acceleration
+      | PhysicalDimension::AngularAcceleration
+      | PhysicalDimension::Mass
+      | PhysicalDimension::MomentOfInertia
+      | PhysicalDimension::Frequency
+      | PhysicalDimension::AngularFrequency
+      | PhysicalDimension::AngularFrequencySquared
+      | PhysicalDimension::Unknown => None,
+  };
+}
+
+/// Names for DOF columns.
+fn dof_names(kind: Kind, dof: Dof) -> Option<BaseNames> {
+  let (machine, short, long) = match (kind, dof.dof_type) {
+    (Kind::Displacement, DofType::Translational) => ("t", "T", "Translation"),
+    (Kind::Displacement, DofType::Rotational) => ("r", "R", "Rotation"),
+    (Kind::Velocity, DofType::Translational) => ("v", "V", "Velocity"),
+    (Kind::Velocity, DofType::Rotational) => {
+      ("vr", "VR", "Rotational velocity")
+    },
+    (Kind::Acceleration, DofType::Translational) => {
+      ("a", "A", "Acceleration")
+    },
+    (Kind::Acceleration, DofType::Rotational) => {
+      ("ar", "AR", "Rotational acceleration")
+    },
+    (Kind::Force, DofType::Translational) => ("f", "F", "Force"),
+    (Kind::Force, DofType::Rotational) => ("m", "M", "Moment"),
+    (Kind::Stress, DofType::Translational) => ("s", "S", "Stress"),
+    (Kind::Stress, DofType::Rotational) => ("sr", "SR", "Rotational stress"),
+    (Kind::Strain, DofType::Translational) => ("e", "E", "Strain"),
+    (Kind::Strain, DofType::Rotational) => ("er", "ER", "Rotational strain"),
+  _ => return None
+  };
+  let axis = dof.axis.letter();
+  return Some(BaseNames::new(
+    &format!("{}{}", machine, axis),
+    &format!("{}{}", short, axis),
+    &format!("{} {}", long, axis.to_ascii_uppercase())
+  ));
+}
+
+/// Names for plate stress and strain columns.
+fn plate_stress_names(
+  kind: Kind,
+  field: PlateStressField
+) -> Option<BaseNames> {
+  let (word, _) = kind.stress_words()?;
+  let (machine, short, long) = match field {
+    PlateStressField::FibreDistance => {
+      ("fibre_distance", "FibreDistance", "Fibre distance".to_owned())
+    },
+    PlateStressField::NormalX => {
+      ("normal_x", "NormalX", format!("Normal {} X", word))
+    },
+    PlateStressField::NormalY => {
+      ("normal_y", "NormalY", format!("Normal {} Y", word))
+    },
+    PlateStressField::ShearXY => {
+      ("shear_xy", "ShearXY", format!("Shear {} XY", word))
+    },
+    PlateStressField::Angle => {
+      ("angle", "Angle", "Principal angle".to_owned())
+    },
+    PlateStressField::Major => {
+      ("major", "Major", format!("Major principal {}", word))
+    },
+    PlateStressField::Minor => {
+      ("minor", "Minor", format!("Minor principal {}", word))
+    },
+    PlateStressField::VonMises => {
+      ("von_mises", "VonMises", format!("Von Mises {}", word))
+    },
+  };
+  return Some(BaseNames::new(machine, short, &long));
+}
+
+/// Names for solid stress columns.
+fn solid_stress_names(
+  kind: Kind,
+  field: ChexaStressField
+) -> Option<BaseNames> {
+  let (word, _) = kind.stress_words()?;
+  let (machine, short, long) = match field {
+    ChexaStressField::NormalX => ("normal_x", "NormalX", "Normal {} X"),
+    ChexaStressField::NormalY => ("normal_y", "NormalY", "Normal {} Y"),
+    ChexaStressField::NormalZ => ("normal_z", "NormalZ", "Normal {} Z"),
+    ChexaStressField::ShearXY => ("shear_xy", "ShearXY", "Shear {} XY"),
+    ChexaStressField::ShearYZ => ("shear_yz", "ShearYZ", "Shear {} YZ"),
+    ChexaStressField::ShearZX => ("shear_zx", "ShearZX", "Shear {} ZX"),
+    ChexaStressField::VonMises => ("von_mises", "VonMises", "Von Mises {}"),
+    ChexaStressField::Octahedral => {
+      ("octahedral", "Octahedral", "Octahedral shear {}")
+    },
+    ChexaStressField::PrincipalA => {
+      ("principal_a", "PrincipalA", "Principal {} A")
+    },
+    ChexaStressField::PrincipalB => {
+      ("principal_b", "PrincipalB", "Principal {} B")
+    },
+    ChexaStressField::PrincipalC => {
+      ("principal_c", "PrincipalC", "Principal {} C")
+    },
+    ChexaStressField::MeanPressure => {
+      ("mean_pressure", "MeanPressure", "Mean pressure")
+    },
+  };
+  let long = long.replace("{}", word);
+  return Some(BaseNames::new(machine, short, &long));
+}
+
+/// Names for plate engineering force columns.
+fn plate_force_names(field: PlateForceField) -> BaseNames {
+  let (machine, short, long) = match field {
+    PlateForceField::NormalX => {
+      ("normal_x", "NormalX", "Membrane force X")
+    },
+    PlateForceField::NormalY => {
+      ("normal_y", "NormalY", "Membrane force Y")
+    },
+    PlateForceField::NormalXY => {
+      ("normal_xy", "NormalXY", "Membrane shear force XY")
+    },
+    PlateForceField::MomentX => {
+      ("moment_x", "MomentX", "Bending moment X")
+    },
+    PlateForceField::MomentY => {
+      ("moment_y", "MomentY", "Bending moment Y")
+    },
+    PlateForceField::MomentXY => {
+      ("moment_xy", "MomentXY", "Twisting moment XY")
+    },
+    PlateForceField::TransverseShearX => (
+      "transverse_shear_x",
+      "TransverseShearX",
+      "Transverse shear force X"
+    ),
+    PlateForceField::TransverseShearY => (
+      "transverse_shear_y",
+      "TransverseShearY",
+      "Transverse shear force Y"
+    ),
+  };
+  return BaseNames::new(machine, short, long);
+}
+
+/// Names for rod engineering force columns.
+fn rod_force_names(field: RodForceField) -> BaseNames {
+  return match field {
+    RodForceField::AxialForce => {
+      BaseNames::new("axial", "Axial", "Axial force")
+    },
+    RodForceField::Torque => {
+      BaseNames::new("torque", "Torque", "Torque")
+    },
+  };
+}
+
+/// Names for rod stress and strain columns.
+fn rod_stress_names(kind: Kind, field: RodStressField) -> Option<BaseNames> {
+  let (word, _) = kind.stress_words()?;
+  return Some(match field {
+    RodStressField::Axial => BaseNames::new(
+      "axial",
+      "Axial",
+      &format!("Axial {}", word)
+    ),
+    RodStressField::AxialSafetyMargin => BaseNames::new(
+      "axial_ms",
+      "AxialMS",
+      "Axial margin of safety"
+    ),
+    RodStressField::Torsional => BaseNames::new(
+      "torsional",
+      "Torsional",
+      &format!("Torsional {}", word)
+    ),
+    RodStressField::TorsionalSafetyMargin => BaseNames::new(
+      "torsional_ms",
+      "TorsionalMS",
+      "Torsional margin of safety"
+    ),
+  });
+}
+
+/// Names for shear panel force columns.
+fn cshear_force_names(field: CshearForceField) -> BaseNames {
+  let name = field.name();
+  return match name.split_once(" TO ") {
+    Some((from, to)) => BaseNames::new(
+      &format!("{}_to_{}", from, to).to_ascii_lowercase(),
+      &format!("{}to{}", from, to),
+      &format!("Force from corner {} to corner {}", &from[1..], &to[1..])
+    ),
+    None => {
+      let (kind, at) = name.split_once('-').unwrap_or((name, ""));
+      let long = match kind {
+        "KICK" => format!("Kick force at corner {}", at),
+        _ => format!("Shear flow along edge {}", at)
+      };
+      BaseNames::new(
+        &format!("{}{}", kind, at).to_ascii_lowercase(),
+        &format!("{}{}", capitalise(&kind.to_ascii_lowercase()), at),
+        &long
+      )
+    }
+  };
+}
+
+/// Names for gap force columns.
+fn cgap_force_names(field: CgapForceField) -> BaseNames {
+  return match field {
+    CgapForceField::CompressiveForce => BaseNames::new(
+      "comp_x",
+      "CompX",
+      "Compressive force"
+    ),
+    CgapForceField::ShearPlane1 => BaseNames::new(
+      "shear_y",
+      "ShearY",
+      "Shear force in plane 1"
+    ),
+    CgapForceField::ShearPlane2 => BaseNames::new(
+      "shear_z",
+      "ShearZ",
+      "Shear force in plane 2"
+    ),
+    CgapForceField::AxialDisplacement => BaseNames::new(
+      "axial_u",
+      "AxialU",
+      "Axial displacement"
+    ),
+    CgapForceField::TotalDisplacement1 => BaseNames::new(
+      "total_v",
+      "TotalV",
+      "Total displacement in plane 1"
+    ),
+    CgapForceField::TotalDisplacement2 => BaseNames::new(
+      "total_w",
+      "TotalW",
+      "Total displacement in plane 2"
+    ),
+    CgapForceField::Slip1 => BaseNames::new(
+      "slip_v",
+      "SlipV",
+      "Slip in plane 1"
+    ),
+    CgapForceField::Slip2 => BaseNames::new(
+      "slip_w",
+      "SlipW",
+      "Slip in plane 2"
+    ),
+    CgapForceField::Status => BaseNames::new(
+      "status",
+      "Status",
+      "Status (0 if open, 1 if closed, 2 if sliding)"
+    ),
+  };
+}
+
+/// Names for shear panel stress columns.
+fn cshear_stress_names(
+  kind: Kind,
+  field: CshearStressField
+) -> Option<BaseNames> {
+  let (word, _) = kind.stress_words()?;
+  return Some(match field {
+    CshearStressField::AverageShear => BaseNames::new(
+      "avg_shear",
+      "AvgShear",
+      &format!("Average shear {}", word)
+    ),
+    CshearStressField::MaxShearStress => BaseNames::new(
+      "max_shear",
+      "MaxShear",
+      &format!("Maximum shear {}", word)
+    ),
+    CshearStressField::MarginOfSafety => BaseNames::new(
+      "shear_ms",
+      "ShearMS",
+      "Shear margin of safety"
+    ),
+  });
+}
+
+/// Returns the number of a bar plane.
+fn plane_number(plane: BarPlane) -> usize {
+  return match plane {
+    BarPlane::Plane1 => 1,
+    BarPlane::Plane2 => 2,
+  };
+}
+
+/// Names for bar engineering force columns.
+fn bar_force_names(field: BarForceField) -> BaseNames {
+  let moment = |end: BarEnd, plane: BarPlane| {
+    let n = plane_number(plane);
+    return BaseNames::new(
+      &format!("m{}", n),
+      &format!("M{}", n),
+      &format!("Bending moment, plane {}", n)
+    ).at(end);
+  };
+  let shear = |plane: BarPlane| {
+    let n = plane_number(plane);
+    return BaseNames::new(
+      &format!("s{}", n),
+      &format!("S{}", n),
+      &format!("Shear force, plane {}", n)
+    );
+  };
+  return match field {
+    BarForceField::BendMomentAPlane1 => moment(BarEnd::EndA, BarPlane::Plane1),
+    BarForceField::BendMomentAPlane2 => moment(BarEnd::EndA, BarPlane::Plane2),
+    BarForceField::BendMomentBPlane1 => moment(BarEnd::EndB, BarPlane::Plane1),
+    BarForceField::BendMomentBPlane2 => moment(BarEnd::EndB, BarPlane::Plane2),
+    BarForceField::ShearPlane1 => shear(BarPlane::Plane1),
+    BarForceField::ShearPlane2 => shear(BarPlane::Plane2),
+    BarForceField::AxialForce => {
+      BaseNames::new("axial", "Axial", "Axial force")
+    },
+    BarForceField::Torque => BaseNames::new("torque", "Torque", "Torque")
+  };
+}
+
+/// Names for beam engineering force columns.
+fn beam_force_names(field: BeamForceField) -> BaseNames {
+  return match field {
+    BeamForceField::MomentPlane1 | BeamForceField::MomentPlane2 => {
+      let n = if field == BeamForceField::MomentPlane1 { 1 } else { 2 };
+      BaseNames::new(
+        &format!("m{}", n),
+        &format!("M{}", n),
+        &format!("Bending moment, plane {}", n)
+      )
+    },
+    BeamForceField::ShearPlane1 | BeamForceField::ShearPlane2 => {
+      let n = if field == BeamForceField::ShearPlane1 { 1 } else { 2 };
+      BaseNames::new(
+        &format!("s{}", n),
+        &format!("S{}", n),
+        &format!("Web shear force, plane {}", n)
+      )
+    },
+    BeamForceField::AxialForce => {
+      BaseNames::new("axial", "Axial", "Axial force")
+    },
+    BeamForceField::TotalTorque => {
+      BaseNames::new("torque", "Torque", "Total torque")
+    },
+    BeamForceField::WarpingTorque => BaseNames::new(
+      "warping_torque",
+      "WarpingTorque",
+      "Warping torque"
+    ),
+  };
+}
+
+/// Names for bar stress and strain columns.
+fn bar_stress_names(kind: Kind, field: BarStressField) -> Option<BaseNames> {
+  let (word, letter) = kind.stress_words()?;
+  return Some(match field {
+    BarStressField::AtRecoveryPoint { end, point } => BaseNames::new(
+      &format!("{}{}", letter, point),
+      &format!("{}{}", letter.to_ascii_uppercase(), point),
+      &format!("{} at recovery point {}", capitalise(word), point)
+    ).at(end),
+    BarStressField::Axial => BaseNames::new(
+      "axial",
+      "Axial",
+      &format!("Axial {}", word)
+    ),
+    BarStressField::MaxAt(end) => BaseNames::new(
+      "max",
+      "Max",
+      &format!("Maximum {}", word)
+    ).at(end),
+    BarStressField::MinAt(end) => BaseNames::new(
+      "min",
+      "Min",
+      &format!("Minimum {}", word)
+    ).at(end),
+    BarStressField::SafetyMargin(NormalStressDirection::Tension) => {
+      BaseNames::new("ms_tension", "MS-T", "Margin of safety in tension")
+    },
+    BarStressField::SafetyMargin(NormalStressDirection::Compression) => {
+      BaseNames::new(
+        "ms_compression",
+        "MS-C",
+        "Margin of safety in compression"
+      )
+    },
+  });
+}
+
+/// Names for beam stress and strain columns.
+fn beam_stress_names(kind: Kind, field: BeamStressField) -> Option<BaseNames> {
+  let (word, letter) = kind.stress_words()?;
+  let at_point = |point: char| BaseNames::new(
+    &format!("{}{}", letter, point.to_ascii_lowercase()),
+    &format!("{}{}", letter.to_ascii_uppercase(), point),
+    &format!("Longitudinal {} at point {}", word, point)
+  );
+  return Some(match field {
+    BeamStressField::LongStressC => at_point('C'),
+    BeamStressField::LongStressD => at_point('D'),
+    BeamStressField::LongStressE => at_point('E'),
+    BeamStressField::LongStressF => at_point('F'),
+    BeamStressField::MaxStress => BaseNames::new(
+      "max",
+      "Max",
+      &format!("Maximum {}", word)
+    ),
+    BeamStressField::MinStress => BaseNames::new(
+      "min",
+      "Min",
+      &format!("Minimum {}", word)
+    ),
+    BeamStressField::MarginTension => {
+      BaseNames::new("ms_tension", "MS-T", "Margin of safety in tension")
+    },
+    BeamStressField::MarginCompression => BaseNames::new(
+      "ms_compression",
+      "MS-C",
+      "Margin of safety in compression"
+    ),
+  });
+}
+
+/// Names for composite ply stress columns.
+fn composite_stress_names(
+  kind: Kind,
+  field: CompositeStressField
+) -> Option<BaseNames> {
+  let (word, _) = kind.stress_words()?;
+  let (machine, short, long) = match field {
+    CompositeStressField::Normal1 => {
+      ("normal_1", "Normal1", format!("Normal {} 1 (fibre)", word))
+    },
+    CompositeStressField::Normal2 => {
+      ("normal_2", "Normal2", format!("Normal {} 2 (matrix)", word))
+    },
+    CompositeStressField::Shear12 => {
+      ("shear_12", "Shear12", format!("Shear {} 12", word))
+    },
+    CompositeStressField::Shear1Z => (
+      "shear_1z", "Shear1Z", format!("Inter-laminar shear {} 1Z", word)
+    ),
+    CompositeStressField::Shear2Z => (
+      "shear_2z", "Shear2Z", format!("Inter-laminar shear {} 2Z", word)
+    ),
+    CompositeStressField::Angle => {
+      ("angle", "Angle", "Principal angle".to_owned())
+    },
+    CompositeStressField::Major => {
+      ("major", "Major", format!("Major principal {}", word))
+    },
+    CompositeStressField::Minor => {
+      ("minor", "Minor", format!("Minor principal {}", word))
+    },
+    CompositeStressField::MaxShear => {
+      ("max_shear", "MaxShear", format!("Maximum shear {}", word))
+    },
+    CompositeStressField::VonMises => {
+      ("von_mises", "VonMises", format!("Von Mises {}", word))
+    },
+  };
+  return Some(BaseNames::new(machine, short, &long));
+}
+
+/// Names for grid point stress columns.
+fn grid_stress_names(kind: Kind, field: GridStressField) -> Option<BaseNames> {
+  let (word, _) = kind.stress_words()?;
+  let (machine, short, long) = match field {
+    GridStressField::NormalX => {
+      ("normal_x", "NormalX", format!("Normal {} X", word))
+    },
+    GridStressField::NormalY => {
+      ("normal_y", "NormalY", format!("Normal {} Y", word))
+    },
+    GridStressField::ShearXY => {
+      ("shear_xy", "ShearXY", format!("Shear {} XY", word))
+    },
+    GridStressField::Angle => {
+      ("angle", "Angle", "Principal angle".to_owned())
+    },
+    GridStressField::Major => {
+      ("major", "Major", format!("Major principal {}", word))
+    },
+    GridStressField::Minor => {
+      ("minor", "Minor", format!("Minor principal {}", word))
+    },
+    GridStressField::MaxShear => {
+      ("max_shear", "MaxShear", format!("Maximum shear {}", word))
+    },
+    GridStressField::VonMises => {
+      ("von_mises", "VonMises", format!("Von Mises {}", word))
+    },
+  };
+  return Some(BaseNames::new(machine, short, &long));
+}
+
+/// Names for composite failure columns.
+fn composite_failure_names(field: CompositeFailureField) -> BaseNames {
+  return match field {
+    CompositeFailureField::FailureIndex => {
+      BaseNames::new("failure_index", "FailureIndex", "Failure index")
+    },
+    CompositeFailureField::StrengthRatio => {
+      BaseNames::new("strength_ratio", "StrengthRatio", "Strength ratio")
+    },
+    CompositeFailureField::TsaiWu => {
+      BaseNames::new("tsai_wu", "TsaiWu", "Tsai-Wu failure index")
+    },
+    CompositeFailureField::TsaiHill => BaseNames::new(
+      "tsai_hill", "TsaiHill", "Tsai-Hill failure index"
+    ),
+    CompositeFailureField::Hoffman => {
+      BaseNames::new("hoffman", "Hoffman", "Hoffman failure index")
+    },
+    CompositeFailureField::MaxStress => BaseNames::new(
+      "max_stress", "MaxStress", "Maximum stress failure index"
+    ),
+    CompositeFailureField::MaxStrain => BaseNames::new(
+      "max_strain", "MaxStrain", "Maximum strain failure index"
+    ),
+    CompositeFailureField::Failed => {
+      BaseNames::new("failed", "Failed", "Ply fails (1) or not (0)")
+    },
+  };
+}
+
+/// Names for the columns of the strain energy of elements.
+fn strain_energy_names(field: StrainEnergyField) -> BaseNames {
+  return match field {
+    StrainEnergyField::Energy => {
+      BaseNames::new("energy", "Energy", "Strain energy")
+    },
+    StrainEnergyField::PercentTotal => BaseNames::new(
+      "percent_total", "PercentTotal", "Percentage of the total energy"
+    ),
+    StrainEnergyField::Density => BaseNames::new(
+      "density", "Density", "Strain energy density"
+    ),
+  };
+}
+
+/// Names for real eigenvalue columns.
+fn eigenvalue_names(field: EigenvalueField) -> BaseNames {
+  return match field {
+    EigenvalueField::ExtractionOrder => BaseNames::new(
+      "extraction_order", "Order", "Extraction order"
+    ),
+    EigenvalueField::Eigenvalue => {
+      BaseNames::new("eigenvalue", "Eigenvalue", "Eigenvalue")
+    },
+    EigenvalueField::Radians => BaseNames::new(
+      "radians", "Radians", "Circular frequency [rad/s]"
+    ),
+    EigenvalueField::Cycles => {
+      BaseNames::new("cycles", "Cycles", "Frequency [Hz]")
+    },
+    EigenvalueField::GeneralizedMass => BaseNames::new(
+      "generalized_mass", "GenMass", "Generalized mass"
+    ),
+    EigenvalueField::GeneralizedStiffness => BaseNames::new(
+      "generalized_stiffness", "GenStiffness", "Generalized stiffness"
+    ),
+  };
+}
+
+/// Names for complex eigenvalue columns.
+fn complex_eigenvalue_names(field: ComplexEigenvalueField) -> BaseNames {
+  return match field {
+    ComplexEigenvalueField::ExtractionOrder => BaseNames::new(
+      "extraction_order", "Order", "Extraction order"
+    ),
+    ComplexEigenvalueField::RealPart => BaseNames::new(
+      "eigenvalue_real", "EigenReal", "Eigenvalue, real part"
+    ),
+    ComplexEigenvalueField::ImaginaryPart => BaseNames::new(
+      "eigenvalue_imag", "EigenImag", "Eigenvalue, imaginary part"
+    ),
+    ComplexEigenvalueField::Frequency => {
+      BaseNames::new("frequency", "Frequency", "Frequency [Hz]")
+    },
+    ComplexEigenvalueField::Damping => BaseNames::new(
+      "damping", "Damping", "Damping coefficient"
+    ),
+  };
+}
+
+/// Names for modal effective mass columns.
+fn modal_mass_names(field: ModalMassField) -> BaseNames {
+  let (dir, cumulative) = match field {
+    ModalMassField::Tx => ("T1", false),
+    ModalMassField::Ty => ("T2", false),
+    ModalMassField::Tz => ("T3", false),
+    ModalMassField::Rx => ("R1", false),
+    ModalMassField::Ry => ("R2", false),
+    ModalMassField::Rz => ("R3", false),
+    ModalMassField::CumTx => ("T1", true),
+    ModalMassField::CumTy => ("T2", true),
+    ModalMassField::CumTz => ("T3", true),
+    ModalMassField::CumRx => ("R1", true),
+    ModalMassField::CumRy => ("R2", true),
+    ModalMassField::CumRz => ("R3", true),
+  };
+  let lower = dir.to_ascii_lowercase();
+  return if cumulative {
+    BaseNames::new(
+      &format!("cum_mass_{}", lower),
+      &format!("CumMass{}", dir),
+      &format!("Cumulative effective mass {} [%]", dir)
+    )
+  } else {
+    BaseNames::new(
+      &format!("mass_{}", lower),
+      &format!("Mass{}", dir),
+      &format!("Effective mass {}", dir)
+    )
+  };
+}
+
+/// Names for the columns of the tables of maximums per DOF: the maximum
+/// itself, or the grid point where it occurs.
+fn max_summary_names(kind: Kind, field: MaxSummaryField) -> Option<BaseNames> {
+  let mut names = dof_names(kind, field.dof())?;
+  names.long[..1].make_ascii_lowercase();
+  let long = names.long;
+  return Some(if field.is_grid() {
+    BaseNames::new(
+      &format!("max_{}_gid", names.machine),
+      &format!("Max{}GID", names.short),
+      &format!("Grid point of the maximum {}", long)
+    )
+  } else {
+    BaseNames::new(
+      &format!("max_{}", names.machine),
+      &format!("Max{}", names.short),
+      &format!("Maximum {}", long)
+    )
+  });
+}
+
+/// Capitalises the first letter of a word.
+fn capitalise(word: &str) -> String {
+  let mut chars = word.chars();
+  return match chars.next() {
+    Some(c) => c.to_uppercase().chain(chars).collect(),
+    None => String::new()
+  };
+}
+
+/// Finds the base names of a column of a block type.
+fn base_names(block_type: BlockType, col: NasIndex) -> Option<BaseNames> {
+  let kind = Kind::of(block_type);
+  return match col {
+    NasIndex::Dof(dof) => dof_names(kind, dof),
+    NasIndex::PlateStressField(f) => plate_stress_names(kind, f),
+    NasIndex::PlateStrainField(f) => plate_stress_names(kind, f.0),
+    NasIndex::ChexaStressField(f) => solid_stress_names(kind, f),
+    NasIndex::ChexaStrainField(f) => solid_stress_names(kind, f.0),
+    NasIndex::CpentaStressField(f) => solid_stress_names(kind, f.into()),
+    NasIndex::CpentaStrainField(f) => solid_stress_names(kind, f.0.into()),
+    NasIndex::CtetraStressField(f) => solid_stress_names(kind, f.into()),
+    NasIndex::CtetraStrainField(f) => solid_stress_names(kind, f.0.into()),
+    NasIndex::CshearForceField(f) => Some(cshear_force_names(f)),
+    NasIndex::CshearStressField(f) => cshear_stress_names(kind, f),
+    NasIndex::CgapForceField(f) => Some(cgap_force_names(f)),
+    NasIndex::BushStressField(f) => dof_names(kind, f.dof()),
+    NasIndex::BushStrainField(f) => dof_names(kind, f.0.dof()),
+    NasIndex::PlateForceField(f) => Some(plate_force_names(f)),
+    NasIndex::RodForceField(f) => Some(rod_force_names(f)),
+    NasIndex::RodStressField(f) => rod_stress_names(kind, f),
+    NasIndex::RodStrainField(f) => rod_stress_names(kind, f.0),
+    NasIndex::BarForceField(f) => Some(bar_force_names(f)),
+    NasIndex::BeamForceField(f) => Some(beam_force_names(f)),
+    NasIndex::BeamStressField(f) => beam_stress_names(kind, f),
+    NasIndex::BeamStrainField(f) => beam_stress_names(kind, f.0),
+    NasIndex::BarStressField(f) => bar_stress_names(kind, f),
+    NasIndex::BarStrainField(f) => bar_stress_names(kind, f.0),
+    NasIndex::SingleForce(_) => {
+      Some(BaseNames::new("force", "Force", "Force"))
+    },
+    NasIndex::SingleStress(_) => {
+      Some(BaseNames::new("stress", "Stress", "Stress"))
+    },
+    NasIndex::SingleStrain(_) => {
+      Some(BaseNames::new("strain", "Strain", "Strain"))
+    },
+    NasIndex::SingleTemperature(_) => Some(BaseNames::new(
+      "temperature",
+      "Temperature",
+      "Temperature"
+    )),
+    NasIndex::CompositeFailureField(f) => Some(composite_failure_names(f)),
+    NasIndex::CompositeStressField(f) => composite_stress_names(kind, f),
+    NasIndex::GridStressField(f) => grid_stress_names(kind, f),
+    NasIndex::StrainEnergyField(f) => Some(strain_energy_names(f)),
+    NasIndex::EigenvalueField(f) => Some(eigenvalue_names(f)),
+    NasIndex::ComplexEigenvalueField(f) => {
+      Some(complex_eigenvalue_names(f))
+    },
+    NasIndex::ModalMassField(f) => Some(modal_mass_names(f)),
+    NasIndex::GpwgField(_) => {
+      Some(BaseNames::new("value", "Value", "Value"))
+    },
+    NasIndex::MaxSummaryField(f) => max_summary_names(kind, f),
+    _ => None
+  };
+}
+
+/// Returns the names of a column of a block type, unique within it (so bar
+/// columns say which end they're at). None if it's not a column of that
+/// block type.
+pub fn column_names(
+  block_type: BlockType,
+  col: NasIndex
+) -> Option<ColumnNames> {
+  let unit = placeholder(col.dimension(block_type));
+  return base_names(block_type, col).map(|b| b.finish(true, unit));
+}
+
+/// Returns the names of a column of a block type as written in CSV records,
+/// i.e. without the bar end, which goes in its own column.
+pub fn record_column_names(
+  block_type: BlockType,
+  col: NasIndex
+) -> Option<ColumnNames> {
+  let unit = placeholder(col.dimension(block_type));
+  return base_names(block_type, col).map(|b| b.finish(false, unit));
+}
+
+/// Returns a template header (one that doesn't come from a column) in a
+/// style. Those are short names already, so only the machine name differs:
+/// lowercase, without remarks in parentheses, e.g. "EID (QUAD4)" is "eid".
+pub fn styled_template_header(header: &str, style: HeaderStyle) -> String {
+  if style != HeaderStyle::Machine || header == HBLANK {
+    return header.to_owned();
+  }
+  let bare = header.split(" (").next().unwrap_or(header).trim();
+  return bare.chars()
+    .map(|c| c.to_ascii_lowercase())
+    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+    .collect();
+}
@@ -0,0 +1,380 @@
+//! This module implements a one-page summary of a run, meant for humans: its
+//! subcases with their titles, the largest displacement and von Mises stress
+//! in each, reaction totals, message counts and what the solver said about
+//! the run. The summary is assembled into a `RunReport`, which can then be
+//! rendered as a self-contained HTML page.
+
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use f06::prelude::*;
+use serde::{Serialize, Deserialize};
+
+/// The translational and rotational DOFs, in the order they're shown.
+const ALL_DOFS: [Dof; SIXDOF] = [
+  DOF_TX, DOF_TY, DOF_TZ, DOF_RX, DOF_RY, DOF_RZ
+];
+
+/// The names of the reaction components, in the order of `ALL_DOFS`.
+const REACTION_NAMES: [&str; SIXDOF] = ["Fx", "Fy", "Fz", "Mx", "My", "Mz"];
+
+/// The stylesheet embedded in the HTML page.
+const REPORT_CSS: &str = concat!(
+  "body { font-family: sans-serif; margin: 2em; color: #222; }\n",
+  "table { border-collapse: collapse; margin-bottom: 1.5em; }\n",
+  "th, td { border: 1px solid #aaa; padding: 0.25em 0.75em; }\n",
+  "th { background: #eee; text-align: left; }\n",
+  "td.num { text-align: right; font-family: monospace; }\n",
+  "td.bad { color: #b00; font-weight: bold; }\n",
+  "footer { color: #666; font-size: 0.9em; }\n"
+);
+
+/// Details about how a report was made, which go in its footer. Kept apart
+/// from the report itself so renderings can be reproduced.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReportMeta {
+  /// The tool that made the report.
+  pub tool: String,
+  /// The tool's version.
+  pub version: String,
+  /// When the report was made, if it's to be shown.
+  pub generated: Option<String>
+}
+
+impl ReportMeta {
+  /// Makes the details for a report made right now by some tool, with the
+  /// time in UTC.
+  pub fn now(tool: &str, version: &str) -> Self {
+    let secs = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map_or(0, |d| d.as_secs());
+    return Self {
+      tool: tool.to_owned(),
+      version: version.to_owned(),
+      generated: Some(utc_timestamp(secs))
+    };
+  }
+}
+
+/// Formats seconds since the Unix epoch as a date and time in UTC.
+pub(crate) fn utc_timestamp(secs: u64) -> String {
+  let (days, rem) = ((secs / 86400) as i64, secs % 86400);
+  // days to a civil date, after Howard Hinnant's algorithm
+  let z = days + 719468;
+  let era = z.div_euclid(146097);
+  let doe = z.rem_euclid(146097);
+  let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + i64::from(month <= 2);
+  return format!(
+    "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+    year,
+    month,
+    day,
+    rem / 3600,
+    rem % 3600 / 60,
+    rem % 60
+  );
+}
+
+/// The largest value of something in a subcase, and where it is.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Extremum {
+  /// The value.
+  pub value: f64,
+  /// The type of the block it's in.
+  pub block_type: BlockType,
+  /// The row it's in (a grid point, an element, a point in an element...).
+  pub location: NasIndex
+}
+
+impl Extremum {
+  /// Keeps whichever is largest of this and a candidate.
+  fn keep_max(this: &mut Option<Self>, candidate: Self) {
+    if !candidate.value.is_finite() {
+      return;
+    }
+    if this.as_ref().is_none_or(|e| candidate.value > e.value) {
+      *this = Some(candidate);
+    }
+  }
+}
+
+/// The summary of a subcase.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SubcaseReport {
+  /// The subcase ID.
+  pub subcase: usize,
+  /// Its title from the case control echo, if any.
+  pub title: Option<String>,
+  /// The largest translational displacement magnitude, if there are any
+  /// displacements.
+  pub max_displacement: Option<Extremum>,
+  /// The largest von Mises stress, if there are any stresses with it.
+  pub max_von_mises: Option<Extremum>,
+  /// The SPC forces summed over all grid points, component-wise, if there
+  /// are any. Moments are summed as printed, not taken about a point.
+  pub reactions: Option<[f64; SIXDOF]>
+}
+
+impl SubcaseReport {
+  /// Summarises a subcase of a file.
+  fn of(file: &F06File, subcase: usize) -> Self {
+    let mut report = Self {
+      subcase,
+      title: file.subcase_titles.get(&subcase).cloned(),
+      max_displacement: None,
+      max_von_mises: None,
+      reactions: None
+    };
+    let stress_types = OutputKeyword::Stress.block_types();
+    for block in file.block_search(None, Some(subcase), false) {
+      let bt = block.block_type;
+      if bt == BlockType::Displacements {
+        for row in block.row_indexes.keys() {
+          let [x, y, z] = [DOF_TX, DOF_TY, DOF_TZ]
+            .map(|dof| real(block, *row, dof));
+          let value = (x * x + y * y + z * z).sqrt();
+          let candidate = Extremum { value, block_type: bt, location: *row };
+          Extremum::keep_max(&mut report.max_displacement, candidate);
+        }
+      } else if bt == BlockType::SpcForces {
+        let sums = report.reactions.get_or_insert([0.0; SIXDOF]);
+        for row in block.row_indexes.keys() {
+          for (sum, dof) in sums.iter_mut().zip(ALL_DOFS) {
+            *sum += real(block, *row, dof);
+          }
+        }
+      } else if stress_types.contains(&bt) {
+        let von_mises = block.col_indexes.keys()
+          .filter(|col| is_von_mises(col))
+          .copied()
+          .collect::<Vec<_>>();
+        for row in block.row_indexes.keys() {
+          for col in von_mises.iter() {
+            let value = real(block, *row, *col);
+            let candidate = Extremum { value, block_type: bt, location: *row };
+            Extremum::keep_max(&mut report.max_von_mises, candidate);
+          }
+        }
+      }
+    }
+    return report;
+  }
+}
+
+/// Returns whether a column holds von Mises stresses.
+fn is_von_mises(col: &NasIndex) -> bool {
+  return matches!(
+    col,
+    NasIndex::PlateStressField(PlateStressField::VonMises)
+      | NasIndex::ChexaStressField(ChexaStressField::VonMises)
+      | NasIndex::CpentaStressField(CpentaStressField::VonMises)
+      | NasIndex::CtetraStressField(CtetraStressField::VonMises)
+      | NasIndex::CompositeStressField(CompositeStressField::VonMises)
+      | NasIndex::GridStressField(GridStressField::VonMises)
+  );
+}
+
+/// Returns a value in a block as a real, or NaN if it's not there.
+fn real<R: Into<NasIndex>, C: Into<NasIndex>>(
+  block: &FinalBlock,
+  row: R,
+  col: C
+) -> f64 {
+  return block.get(row, col).map_or(f64::NAN, f64::from);
+}
+
+/// A one-page summary of a run.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RunReport {
+  /// The name of the file, if known.
+  pub filename: Option<String>,
+  /// The solver, if known.
+  pub solver: Option<Solver>,
+  /// The solution type, if known.
+  pub soltype: Option<SolType>,
+  /// How many warnings the solver printed.
+  pub warnings: usize,
+  /// How many fatal errors the solver printed.
+  pub fatal_errors: usize,
+  /// How many diagnostics came up while parsing.
+  pub diagnostics: usize,
+  /// What the solver said about the run, as labelled values.
+  pub run_stats: Vec<(String, String)>,
+  /// The summaries of the subcases, in order.
+  pub subcases: Vec<SubcaseReport>
+}
+
+impl RunReport {
+  /// Summarises a parsed file.
+  pub fn from_file(file: &F06File) -> Self {
+    return Self {
+      filename: file.filename.clone(),
+      solver: file.flavour.solver,
+      soltype: file.flavour.soltype,
+      warnings: file.warnings.len(),
+      fatal_errors: file.fatal_errors.len(),
+      diagnostics: file.diagnostics.total(),
+      run_stats: file.run_stats.entries(),
+      subcases: file.subcases()
+        .map(|sc| SubcaseReport::of(file, sc))
+        .collect()
+    };
+  }
+
+  /// Renders the report as a self-contained HTML page.
+  pub fn to_html(&self, meta: &ReportMeta) -> String {
+    let mut html = String::new();
+    self.write_html(&mut html, meta)
+      .expect("writing to a string can't fail");
+    return html;
+  }
+
+  /// Writes the report out as a self-contained HTML page.
+  fn write_html(
+    &self,
+    out: &mut String,
+    meta: &ReportMeta
+  ) -> std::fmt::Result {
+    let name = self.filename.as_deref().unwrap_or("(unnamed)");
+    let known = |x: Option<String>| x.unwrap_or_else(|| "unknown".to_owned());
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"en\">")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>Run report: {}</title>", escape(name))?;
+    write!(out, "<style>\n{}</style>\n", REPORT_CSS)?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    writeln!(out, "<h1>Run report: {}</h1>", escape(name))?;
+    // the run
+    writeln!(out, "<h2>Run</h2>")?;
+    writeln!(out, "<table>")?;
+    let solver = known(self.solver.map(|s| s.to_string()));
+    let soltype = known(self.soltype.map(|s| s.to_string()));
+    row_th(out, "Solver", &escape(&solver), false)?;
+    row_th(out, "Solution", &escape(&soltype), false)?;
+    row_th(out, "Warnings", &self.warnings.to_string(), false)?;
+    let fatal = self.fatal_errors > 0;
+    row_th(out, "Fatal errors", &self.fatal_errors.to_string(), fatal)?;
+    row_th(out, "Diagnostics", &self.diagnostics.to_string(), false)?;
+    for (label, value) in self.run_stats.iter() {
+      row_th(out, &escape(label), &escape(value), false)?;
+    }
+    writeln!(out, "</table>")?;
+    // the subcases
+    writeln!(out, "<h2>Subcases</h2>")?;
+    if self.subcases.is_empty() {
+      writeln!(out, "<p>No results were found.</p>")?;
+    } else {
+      writeln!(out, "<table>")?;
+      write!(out, "<tr><th>Subcase</th><th>Title</th>")?;
+      write!(out, "<th>Max displacement</th><th>At</th>")?;
+      writeln!(out, "<th>Max von Mises</th><th>At</th></tr>")?;
+      for sc in self.subcases.iter() {
+        let title = sc.title.as_deref().unwrap_or("");
+        write!(out, "<tr><td class=\"num\">{}</td>", sc.subcase)?;
+        write!(out, "<td>{}</td>", escape(title))?;
+        extremum_cells(out, sc.max_displacement.as_ref())?;
+        extremum_cells(out, sc.max_von_mises.as_ref())?;
+        writeln!(out, "</tr>")?;
+      }
+      writeln!(out, "</table>")?;
+    }
+    // the reactions
+    let reactions = self.subcases.iter()
+      .filter_map(|sc| sc.reactions.map(|r| (sc.subcase, r)))
+      .collect::<Vec<_>>();
+    if !reactions.is_empty() {
+      writeln!(out, "<h2>Reaction totals</h2>")?;
+      writeln!(out, "<table>")?;
+      write!(out, "<tr><th>Subcase</th>")?;
+      for name in REACTION_NAMES {
+        write!(out, "<th>{}</th>", name)?;
+      }
+      writeln!(out, "</tr>")?;
+      for (subcase, sums) in reactions {
+        write!(out, "<tr><td class=\"num\">{}</td>", subcase)?;
+        for sum in sums {
+          write!(out, "<td class=\"num\">{}</td>", number(sum))?;
+        }
+        writeln!(out, "</tr>")?;
+      }
+      writeln!(out, "</table>")?;
+    }
+    // the footer
+    write!(
+      out,
+      "<footer>Generated by {} {}",
+      escape(&meta.tool),
+      escape(&meta.version)
+    )?;
+    if let Some(ref when) = meta.generated {
+      write!(out, " on {}", escape(when))?;
+    }
+    writeln!(out, ".</footer>")?;
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")?;
+    return Ok(());
+  }
+}
+
+/// Writes a table row with a header cell and a value, maybe flagged as bad.
+fn row_th(
+  out: &mut String,
+  header: &str,
+  value: &str,
+  bad: bool
+) -> std::fmt::Result {
+  let class = if bad { " class=\"bad\"" } else { "" };
+  return writeln!(
+    out,
+    "<tr><th>{}</th><td{}>{}</td></tr>",
+    header,
+    class,
+    value
+  );
+}
+
+/// Writes the cells for an extremum (its value and where it is), which are
+/// left blank if there's none.
+fn extremum_cells(
+  out: &mut String,
+  extremum: Option<&Extremum>
+) -> std::fmt::Result {
+  return match extremum {
+    Some(e) => write!(
+      out,
+      "<td class=\"num\">{}</td><td>{}, {}</td>",
+      number(e.value),
+      escape(&e.location.to_string()),
+      escape(e.block_type.desc())
+    ),
+    None => write!(out, "<td></td><td></td>")
+  };
+}
+
+/// Formats a number for the report.
+fn number(x: f64) -> String {
+  return format!("{:.6E}", x);
+}
+
+/// Escapes text to go in HTML.
+fn escape(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      c => escaped.push(c)
+    };
+  }
+  return escaped;
+}
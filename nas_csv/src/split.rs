@@ -0,0 +1,222 @@
+//! This module implements splitting the CSV output into several files, by CSV
+//! block ID and/or subcase, and the natural per-type layout each of those
+//! files gets: only the columns that mean something for the block ID, instead
+//! of the fixed eleven.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+
+use f06::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::*;
+
+/// Which part of the output a split file gets. Fields that aren't split by
+/// are `None`, and so are subcases for records without one (the 0-block).
+#[derive(
+  Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord
+)]
+pub struct SplitKey {
+  /// The CSV block ID, if splitting by type.
+  pub block_id: Option<CsvBlockId>,
+  /// The subcase, if splitting by subcase.
+  pub subcase: Option<usize>
+}
+
+impl Display for SplitKey {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    return write!(f, "{}", self.suffix());
+  }
+}
+
+impl SplitKey {
+  /// Returns the key of the part that records with a CSV block ID and
+  /// subcase go in.
+  pub fn of(
+    block_id: CsvBlockId,
+    subcase: Option<usize>,
+    by_type: bool,
+    by_subcase: bool
+  ) -> Self {
+    return Self {
+      block_id: Some(block_id).filter(|_| by_type),
+      subcase: subcase.filter(|_| by_subcase)
+    };
+  }
+
+  /// Returns the suffix that goes after the stem in file names, like
+  /// "_disp", "_sc2" or "_disp_sc2". Empty if not split at all.
+  pub fn suffix(&self) -> String {
+    let mut suffix = String::new();
+    if let Some(bid) = self.block_id {
+      suffix.push('_');
+      suffix.push_str(bid.shorthand());
+    }
+    if let Some(sc) = self.subcase {
+      suffix.push_str(&format!("_sc{}", sc));
+    }
+    return suffix;
+  }
+
+  /// Returns the name of the file for this part: `{stem}{suffix}.{ext}`.
+  pub fn file_name(&self, stem: &str, ext: &str) -> String {
+    return format!("{}{}.{}", stem, self.suffix(), ext);
+  }
+}
+
+/// Splits records by CSV block ID and/or subcase, keeping their order within
+/// each part. They're all held in memory; to write huge files out, get the
+/// keys from `split_keys` and the records for each of them instead.
+pub fn split_records<I: IntoIterator<Item = CsvRecord>>(
+  records: I,
+  by_type: bool,
+  by_subcase: bool
+) -> BTreeMap<SplitKey, Vec<CsvRecord>> {
+  let mut parts: BTreeMap<SplitKey, Vec<CsvRecord>> = BTreeMap::new();
+  for rec in records {
+    let key = SplitKey::of(rec.block_id, rec.subcase, by_type, by_subcase);
+    parts.entry(key).or_default().push(rec);
+  }
+  return parts;
+}
+
+/// Returns the keys of the parts a file's records are split into, without
+/// converting them: the 0-block's, and those of the blocks with converters.
+/// Some parts may turn out empty if records are filtered out. The records
+/// of a part are those of the 0-block if its key is the 0-block's, and then
+/// those from `to_records_where` with the blocks whose key it is.
+pub fn split_keys(
+  file: &F06File,
+  converters: &BTreeMap<BlockType, BlockConverter>,
+  by_type: bool,
+  by_subcase: bool
+) -> BTreeSet<SplitKey> {
+  let zeroth = SplitKey::of(CsvBlockId::Metadata, None, by_type, by_subcase);
+  return record_groups(file, converters)
+    .into_iter()
+    .map(|(bid, sc)| SplitKey::of(bid, Some(sc), by_type, by_subcase))
+    .chain(std::iter::once(zeroth))
+    .collect();
+}
+
+/// The columns of records that get written: the block ID (or not), some of
+/// the ten fixed-form fields, the extra row dimensions and, optionally, the
+/// provenance columns.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ColumnSelection {
+  /// Whether the CSV block ID goes first.
+  pub block_id: bool,
+  /// The fixed-form fields written, by index into `CsvRecord::fields`.
+  pub fields: Vec<usize>,
+  /// The extra row dimensions written, after the fields.
+  pub dims: Vec<RowDimension>,
+  /// Whether the provenance columns are written, last. They're blank for
+  /// records without a provenance (the 0-block).
+  #[serde(default)]
+  pub provenance: bool
+}
+
+impl ColumnSelection {
+  /// Selects the full fixed-form layout: all eleven columns, then the extra
+  /// row dimensions passed.
+  pub fn full(dims: Vec<RowDimension>) -> Self {
+    return Self {
+      block_id: true,
+      fields: (0..NAS_CSV_COLS-1).collect(),
+      dims,
+      provenance: false
+    };
+  }
+
+  /// Selects the natural layout for records with some headers (i.e. coming
+  /// from some templates): no block ID, since it's the same for all, and only
+  /// the fields that have a header in any of them. Then the extra row
+  /// dimensions passed.
+  pub fn natural<'a, I: IntoIterator<Item = &'a RowHeader>>(
+    headers: I,
+    dims: Vec<RowDimension>
+  ) -> Self {
+    let mut used = [false; NAS_CSV_COLS-1];
+    for header in headers {
+      for (u, h) in used.iter_mut().zip(header.iter()) {
+        *u |= *h != HBLANK;
+      }
+    }
+    let fields = used.iter()
+      .enumerate()
+      .filter(|(_, u)| **u)
+      .map(|(i, _)| i)
+      .collect();
+    return Self { block_id: false, fields, dims, provenance: false };
+  }
+
+  /// Sets whether the provenance columns are written.
+  pub fn with_provenance(mut self, provenance: bool) -> Self {
+    self.provenance = provenance;
+    return self;
+  }
+
+  /// Returns the headers of the provenance columns, if they're written.
+  fn provenance_headers(&self) -> &'static [&'static str] {
+    return if self.provenance { &PROVENANCE_HEADERS } else { &[] };
+  }
+
+  /// Returns the header of a record, in the selected columns.
+  pub fn header<'a>(&'a self, rec: &'a CsvRecord) -> Vec<&'a str> {
+    let block_id = Some(rec.block_id.name()).filter(|_| self.block_id);
+    return block_id.into_iter()
+      .chain(self.fields.iter().map(|i| rec.headers[*i]))
+      .chain(self.dims.iter().map(|d| d.name()))
+      .chain(self.provenance_headers().iter().copied())
+      .collect();
+  }
+
+  /// Returns the header of a record, in the selected columns, with names in a
+  /// style. Fields that come from a block column get its names (see the
+  /// naming module); the others keep their template headers.
+  pub fn styled_header(
+    &self,
+    rec: &CsvRecord,
+    style: HeaderStyle,
+    units: &UnitMap
+  ) -> Vec<String> {
+    let block_id = Some(rec.block_id.name()).filter(|_| self.block_id);
+    let fields = self.fields.iter().map(|i| {
+      let names = rec.block_type.zip(rec.columns[*i])
+        .and_then(|(bt, col)| record_column_names(bt, col));
+      return match names {
+        Some(n) => n.styled(style, units),
+        None => styled_template_header(rec.headers[*i], style)
+      };
+    });
+    return block_id.into_iter()
+      .map(|h| styled_template_header(h, style))
+      .chain(fields)
+      .chain(self.dims.iter().map(|d| styled_template_header(d.name(), style)))
+      .chain(
+        self.provenance_headers()
+          .iter()
+          .map(|h| styled_template_header(h, style))
+      )
+      .collect();
+  }
+
+  /// Returns the fields of a record, in the selected columns.
+  pub fn fields(&self, mut rec: CsvRecord) -> Vec<CsvField> {
+    let block_id = Some(CsvField::from(rec.block_id))
+      .filter(|_| self.block_id);
+    let extras = self.dims.iter()
+      .map(|d| rec.extras.remove(d).unwrap_or(CsvField::Blank))
+      .collect::<Vec<_>>();
+    let provenance = match (self.provenance, rec.provenance) {
+      (false, _) => vec![],
+      (true, Some(p)) => p.to_fields().to_vec(),
+      (true, None) => vec![CsvField::Blank; PROVENANCE_HEADERS.len()]
+    };
+    return block_id.into_iter()
+      .chain(self.fields.iter().map(|i| rec.fields[*i].clone()))
+      .chain(extras)
+      .chain(provenance)
+      .collect();
+  }
+}
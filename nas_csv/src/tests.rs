@@ -0,0 +1,1427 @@
+use std::collections::BTreeSet;
+use std::fs;
+
+use f06::prelude::*;
+
+use crate::prelude::*;
+use crate::from_f06::templates::all_converters;
+use common::EXAMPLE;
+#[cfg(feature = "parallel")]
+use common::many_subcases;
+use fixtures::{
+  MYSTRAN_PLY_STRESSES_FIXTURE,
+  NON_FINITE_FIXTURE,
+  THERMAL_LOAD_FIXTURE
+};
+
+/// The API and MSRV checks, shared with f06's tests without being part of
+/// its API.
+#[allow(dead_code)]
+#[path = "../../f06/src/stability.rs"]
+mod stability;
+
+use stability::{api_surface, check_api_snapshot};
+
+/// The fixtures shared with f06's tests.
+#[path = "../../f06/src/fixtures.rs"]
+mod fixtures;
+
+/// The helpers shared with f06csv's tests and the benchmarks.
+#[path = "../../f06csv/tests/common/mod.rs"]
+mod common;
+
+/// Reads the example file with lots of different block types.
+fn read_example() -> String {
+  return fs::read_to_string(EXAMPLE).unwrap();
+}
+
+/// Parses the example file, keeping only some block types.
+fn parse_keeping(keep: &[BlockType]) -> F06File {
+  let mut file = OnePassParser::parse_bufread(read_example().as_bytes())
+    .unwrap();
+  file.blocks.retain(|br, _| keep.contains(&br.block_type));
+  file.merge_blocks(true);
+  return file;
+}
+
+/// Returns the extra fields of each written record, by CSV block ID.
+fn extra_fields(
+  file: &F06File,
+  dims: &[RowDimension]
+) -> Vec<(CsvBlockId, Vec<CsvField>)> {
+  return to_records(file, &all_converters())
+    .map(|rec| {
+      let bid = rec.block_id;
+      let fields = rec.to_fields_with(dims).collect::<Vec<_>>();
+      assert_eq!(fields.len(), NAS_CSV_COLS + dims.len());
+      return (bid, fields[NAS_CSV_COLS..].to_vec());
+    })
+    .collect();
+}
+
+#[test]
+fn test_adaptive_schema() {
+  assert_eq!(
+    BlockType::QuadStresses.schema().row_dimension,
+    RowDimension::Side
+  );
+  assert_eq!(BlockType::BarForces.schema().row_dimension, RowDimension::End);
+  assert_eq!(
+    BlockType::Displacements.schema().row_dimension,
+    RowDimension::None
+  );
+  // displacements alone have no extra columns
+  let disp_only = parse_keeping(&[BlockType::Displacements]);
+  let dims = extra_dimensions(disp_only.block_types(), false);
+  assert!(dims.is_empty());
+  assert!(extra_fields(&disp_only, &dims).iter().all(|(_, e)| e.is_empty()));
+  // mixed, only side and end
+  let file = parse_keeping(&[
+    BlockType::Displacements,
+    BlockType::QuadStresses,
+    BlockType::BarForces
+  ]);
+  let dims = extra_dimensions(file.block_types(), false);
+  assert_eq!(dims, vec![RowDimension::Side, RowDimension::End]);
+  let top = CsvField::String("Top".to_owned());
+  let bottom = CsvField::String("Bottom".to_owned());
+  let end_a = CsvField::String("End A".to_owned());
+  let end_b = CsvField::String("End B".to_owned());
+  let mut seen = Vec::new();
+  for (bid, extras) in extra_fields(&file, &dims) {
+    match bid {
+      CsvBlockId::Stresses => {
+        assert!(extras[0] == top || extras[0] == bottom);
+        assert_eq!(extras[1], CsvField::Blank);
+      },
+      CsvBlockId::EngForces => {
+        assert_eq!(extras[0], CsvField::Blank);
+        assert!(extras[1] == end_a || extras[1] == end_b);
+      },
+      _ => assert_eq!(extras, vec![CsvField::Blank; 2])
+    };
+    seen.push(bid);
+  }
+  assert!(seen.contains(&CsvBlockId::Displacements));
+  assert!(seen.contains(&CsvBlockId::Stresses));
+  assert!(seen.contains(&CsvBlockId::EngForces));
+  // and the headers follow
+  let rec = to_records(&file, &all_converters()).last().unwrap();
+  let header = rec.header_with(&dims).collect::<Vec<_>>();
+  assert_eq!(header[NAS_CSV_COLS..], ["Side", "End"]);
+}
+
+#[test]
+fn test_fixed_schema() {
+  let file = parse_keeping(&[
+    BlockType::Displacements,
+    BlockType::QuadStresses,
+    BlockType::BarForces
+  ]);
+  let dims = extra_dimensions(file.block_types(), true);
+  assert_eq!(dims, RowDimension::extras());
+  let ix = |d: RowDimension| dims.iter().position(|x| *x == d).unwrap();
+  for (bid, extras) in extra_fields(&file, &dims) {
+    let filled = extras.iter()
+      .enumerate()
+      .filter(|(_, f)| **f != CsvField::Blank)
+      .map(|(i, _)| i)
+      .collect::<Vec<_>>();
+    match bid {
+      CsvBlockId::Stresses => assert_eq!(filled, vec![ix(RowDimension::Side)]),
+      CsvBlockId::EngForces => assert_eq!(filled, vec![ix(RowDimension::End)]),
+      _ => assert!(filled.is_empty())
+    };
+  }
+  // even with no extra dimensions in sight
+  let disp_only = parse_keeping(&[BlockType::Displacements]);
+  let dims = extra_dimensions(disp_only.block_types(), true);
+  assert_eq!(dims.len(), RowDimension::extras().len());
+}
+
+/// Renders records like f06csv would, one string per line.
+#[cfg(feature = "parallel")]
+fn render<I: Iterator<Item = CsvRecord>>(records: I) -> Vec<String> {
+  let fmtr = CsvFormatting {
+    reals: FloatFormat::default(),
+    blanks: BlankDisplay::default(),
+    align: Alignment::None
+  };
+  let dims = RowDimension::extras();
+  return records
+    .map(|rec| {
+      return rec.to_fields_with(dims)
+        .map(|f| fmtr.to_string(f))
+        .collect::<Vec<_>>()
+        .join(",");
+    })
+    .collect();
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_matches_sequential() {
+  let converters = all_converters();
+  let file = many_subcases(&read_example(), 7);
+  let sequential = render(to_records(&file, &converters));
+  assert!(sequential.len() > 7);
+  for jobs in [1, 2, 3, 8, 100] {
+    let parallel = to_records_parallel(&file, &converters, jobs);
+    assert_eq!(render(parallel), sequential);
+  }
+}
+
+/// A Simcenter-like output with displacements in two subcases.
+const CANONICAL_FIXTURE_A: &str = "
+                      Welcome to Simcenter Nastran
+1    CANONICAL TEST                                      SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1.000000E+00   2.000000E+00   0.0            0.0            0.0            0.0
+             2      G     -5.000000E-01   0.0            0.0            0.0            0.0            1.000000E-20
+1    CANONICAL TEST                                      SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 2
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      3.000000E+00   0.0            0.0            0.0            0.0            0.0
+             2      G      0.0            0.0           -1.234568E-03   0.0            0.0            0.0
+1    CANONICAL TEST                                      SIMCENTER NASTRAN  2/11/21   PAGE     3
+";
+
+/// The same results as `CANONICAL_FIXTURE_A`, but from a different run: other
+/// title and date, subcases and rows in another order, other number formats,
+/// negative zeroes and noise.
+const CANONICAL_FIXTURE_B: &str = "
+                      Welcome to Simcenter Nastran
+1    CANONICAL TEST, SECOND RUN                          SIMCENTER NASTRAN  3/12/22   PAGE     1
+0                                                                                SUBCASE 2
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             2      G     -0.0            0.0           -1.2345680E-03  0.0            0.0            0.0
+             1      G      3.0000000E+00  0.0            0.0           -0.0            0.0            0.0
+1    CANONICAL TEST, SECOND RUN                          SIMCENTER NASTRAN  3/12/22   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             2      G     -5.0E-01        0.0            0.0            0.0            0.0           -3.0E-21
+             1      G      1.0E+00        2.0E+00        0.0            0.0            0.0            0.0
+1    CANONICAL TEST, SECOND RUN                          SIMCENTER NASTRAN  3/12/22   PAGE     3
+";
+
+/// Parses a file like f06csv would and writes it out in canonical form.
+fn canonical_csv(f06: &str) -> String {
+  let mut file = OnePassParser::parse_bufread(f06.as_bytes()).unwrap();
+  file.merge_blocks(true);
+  file.sort_all_blocks();
+  let mut out: Vec<u8> = Vec::new();
+  write_canonical(to_records(&file, &all_converters()), &mut out).unwrap();
+  return String::from_utf8(out).unwrap();
+}
+
+#[test]
+fn test_canonical_form() {
+  let a = canonical_csv(CANONICAL_FIXTURE_A);
+  let b = canonical_csv(CANONICAL_FIXTURE_B);
+  assert_eq!(a, b);
+  let lines = a.lines().collect::<Vec<_>>();
+  // version header and row, displacements header and four rows
+  assert_eq!(lines.len(), 7);
+  assert!(lines[1].starts_with("0,Canonical form,canonical-v2,"));
+  assert!(lines[3].starts_with("1,1,1,+1.00000000E+00,+2.00000000E+00,"));
+  assert!(lines[6].contains(",-1.23456800E-03,"));
+  assert!(!a.contains("-0.00000000E+00"));
+  assert!(!a.contains("Filename"));
+  // every line has the fixed schema
+  let width = NAS_CSV_COLS + RowDimension::extras().len();
+  assert!(lines.iter().all(|l| l.split(',').count() == width));
+}
+
+#[test]
+fn test_non_finite_fields() {
+  // written as words with any formatting, never as a formatted number
+  let formats = [
+    CANONICAL_FORMATTING,
+    CsvFormatting { reals: FloatFormat::default(), ..CANONICAL_FORMATTING },
+    CsvFormatting {
+      reals: FloatFormat { no_scientific: true, ..Default::default() },
+      ..CANONICAL_FORMATTING
+    }
+  ];
+  for fmtr in formats {
+    assert_eq!(fmtr.to_string(CsvField::Real(f64::NAN)), "NaN");
+    assert_eq!(fmtr.to_string(CsvField::Real(f64::INFINITY)), "Inf");
+    assert_eq!(fmtr.to_string(CsvField::Real(f64::NEG_INFINITY)), "-Inf");
+  }
+  assert_eq!(CsvField::Real(f64::NAN).to_string(), "NaN");
+  // and through a whole conversion
+  let csv = canonical_csv(NON_FINITE_FIXTURE);
+  let lines = csv.lines().collect::<Vec<_>>();
+  assert_eq!(lines.len(), 6);
+  assert!(lines[3].starts_with("1,1,1,+1.00000000E+00,NaN,"));
+  assert!(lines[4].starts_with("1,2,1,Inf,-Inf,"));
+  assert!(lines[4].contains(",NaN"));
+}
+
+/// A packed temperature vector under its longer "grid point" title.
+const TEMPERATURES_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    THERMAL TEST                                        SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 3
+ 
+                                   G R I D   P O I N T   T E M P E R A T U R E   V E C T O R
+ 
+      POINT ID.   VALUE       POINT ID.   VALUE       POINT ID.   VALUE
+            11   2.000000E+01       12   2.500000E+01       13   3.000000E+01
+";
+
+#[test]
+fn test_temperature_records() {
+  let file = OnePassParser::parse_bufread(TEMPERATURES_FIXTURE.as_bytes())
+    .unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::Temperatures)
+    .collect::<Vec<_>>();
+  assert_eq!(records.len(), 3);
+  assert_eq!(records[1].gid, Some(12));
+  assert_eq!(records[1].fields[1], CsvField::Natural(3));
+  assert_eq!(records[1].fields[4], CsvField::Real(25.0));
+  assert_eq!(records[1].fields[5], CsvField::Blank);
+}
+
+/// MPC forces on two grid points, after the SPC forces.
+const MPC_FORCES_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    MPC TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                               F O R C E S   O F   S I N G L E - P O I N T   C O N S T R A I N T
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1.000000E+02   0.0            0.0            0.0            0.0            0.0
+1    MPC TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                                F O R C E S   O F   M U L T I - P O I N T   C O N S T R A I N T
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             2      G     -5.000000E+01   0.0            2.500000E+00   0.0            0.0            1.000000E+00
+             3      G     -5.000000E+01   0.0           -2.500000E+00   0.0            0.0           -1.000000E+00
+";
+
+#[test]
+fn test_mpc_force_records() {
+  let file = OnePassParser::parse_bufread(MPC_FORCES_FIXTURE.as_bytes())
+    .unwrap();
+  let records = to_records(&file, &all_converters()).collect::<Vec<_>>();
+  let of = |bid: CsvBlockId| {
+    return records.iter().filter(|r| r.block_id == bid).collect::<Vec<_>>();
+  };
+  assert_eq!(of(CsvBlockId::SpcForces).len(), 1);
+  let mpcs = of(CsvBlockId::MpcForces);
+  assert_eq!(mpcs.len(), 2);
+  assert_eq!(mpcs[1].gid, Some(3));
+  assert_eq!(mpcs[1].fields[0], CsvField::Natural(3));
+  assert_eq!(mpcs[1].fields[4], CsvField::Real(-2.5));
+  assert_eq!(CsvBlockId::try_from(13), Ok(CsvBlockId::MpcForces));
+}
+
+/// A transient step with displacements, velocities and accelerations.
+const TRANSIENT_F06: &str = include_str!(
+  "../../examples/scnastran/beam_transient_sol109.f06"
+);
+
+#[test]
+fn test_transient_records() {
+  let file = OnePassParser::parse_bufread(TRANSIENT_F06.as_bytes()).unwrap();
+  let records = to_records(&file, &all_converters()).collect::<Vec<_>>();
+  let of = |bid: CsvBlockId| {
+    return records.iter().filter(|r| r.block_id == bid).collect::<Vec<_>>();
+  };
+  for bid in [
+    CsvBlockId::Displacements,
+    CsvBlockId::Velocities,
+    CsvBlockId::Accelerations
+  ] {
+    assert_eq!(of(bid).len(), 2);
+  }
+  let accels = of(CsvBlockId::Accelerations);
+  assert_eq!(accels[1].fields[4], CsvField::Real(5.0));
+  assert_eq!(CsvBlockId::try_from(14), Ok(CsvBlockId::Velocities));
+  assert_eq!(CsvBlockId::try_from(15), Ok(CsvBlockId::Accelerations));
+}
+
+/// Displacements at two time steps, the first one split across two pages.
+const TRANSIENT_STEPS_F06: &str = include_str!(
+  "../../examples/scnastran/beam_transient_steps_sol109.f06"
+);
+
+#[test]
+fn test_step_records() {
+  let mut file = OnePassParser::parse_bufread(TRANSIENT_STEPS_F06.as_bytes())
+    .unwrap();
+  file.merge_blocks(true);
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::Displacements)
+    .collect::<Vec<_>>();
+  let steps = records.iter()
+    .map(|rec| (rec.gid, rec.extras.get(&RowDimension::Step).cloned()))
+    .collect::<Vec<_>>();
+  assert_eq!(steps, [
+    (Some(1), Some(CsvField::Real(1e-2))),
+    (Some(2), Some(CsvField::Real(1e-2))),
+    (Some(1), Some(CsvField::Real(2e-2))),
+    (Some(2), Some(CsvField::Real(2e-2)))
+  ]);
+  assert_eq!(records[3].fields[4], CsvField::Real(6e-3));
+  // what the step is comes along
+  let kind = CsvField::String("TIME".to_owned());
+  assert!(records.iter().all(|rec| {
+    return rec.extras.get(&RowDimension::StepKind) == Some(&kind);
+  }));
+  // steps aren't tied to block types
+  let dims = extra_dimensions(file.block_types(), false);
+  assert!(dims.is_empty());
+  assert_eq!(
+    stepped_dimensions(dims),
+    [RowDimension::Step, RowDimension::StepKind]
+  );
+  let dims = extra_dimensions(file.block_types(), true);
+  assert_eq!(stepped_dimensions(dims.clone()), dims);
+}
+
+/// A real eigenvalue table with two modes.
+const EIGENVALUES_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     1
+ 
+                                              R E A L   E I G E N V A L U E S
+   MODE    EXTRACTION      EIGENVALUE            RADIANS             CYCLES            GENERALIZED         GENERALIZED
+    NO.       ORDER                                                                       MASS              STIFFNESS
+        1         1        3.947842E+04        1.986918E+02        3.162278E+01        1.000000E+00        3.947842E+04
+        2         2        1.579137E+05        3.973835E+02        6.324555E+01        1.000000E+00        1.579137E+05
+";
+
+#[test]
+fn test_eigenvalue_records() {
+  let file = OnePassParser::parse_bufread(EIGENVALUES_FIXTURE.as_bytes())
+    .unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::Eigenvalues)
+    .collect::<Vec<_>>();
+  assert_eq!(records.len(), 2);
+  assert_eq!(records[1].fields[0], CsvField::Natural(2));
+  assert_eq!(records[1].fields[5], CsvField::Real(63.24555));
+  assert_eq!(records[1].fields[7], CsvField::Real(1.579137E5));
+  assert_eq!(records[1].fields[8], CsvField::Blank);
+  assert_eq!(CsvBlockId::try_from(11), Ok(CsvBlockId::Eigenvalues));
+}
+
+/// Eigenvectors for two modes of a two-point model.
+const EIGENVECTORS_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+      EIGENVALUE =  3.947842E+04
+          CYCLES =  3.162278E+01         R E A L   E I G E N V E C T O R   N O .          1
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      1.000000E+00   0.0            0.0            0.0            0.0            0.0
+             2      G      5.000000E-01   0.0            0.0            0.0            0.0            0.0
+1    MODES TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+      EIGENVALUE =  1.579137E+05
+          CYCLES =  6.324555E+01         R E A L   E I G E N V E C T O R   N O .          2
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G      0.0            1.000000E+00   0.0            0.0            0.0            0.0
+             2      G      0.0           -1.000000E+00   0.0            0.0            0.0            0.0
+";
+
+#[test]
+fn test_eigenvector_records() {
+  let file = OnePassParser::parse_bufread(EIGENVECTORS_FIXTURE.as_bytes())
+    .unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::Eigenvectors)
+    .collect::<Vec<_>>();
+  assert_eq!(records.len(), 4);
+  let modes = records.iter()
+    .map(|rec| (rec.gid, rec.fields[2].to_string()))
+    .collect::<BTreeSet<_>>();
+  let expected = [(1, "1"), (2, "1"), (1, "2"), (2, "2")]
+    .map(|(gid, mode)| (Some(gid), mode.to_owned()));
+  assert_eq!(modes, BTreeSet::from(expected));
+  let mode_2_gid_2 = records.iter()
+    .find(|rec| rec.gid == Some(2) && rec.fields[2].to_string() == "2")
+    .unwrap();
+  assert_eq!(mode_2_gid_2.fields[4], CsvField::Real(-1.0));
+}
+
+/// A composite failure indices table for a single element with two plies.
+const COMPOSITE_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    COMPOSITE TEST                                      SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                   F A I L U R E   I N D I C E S   F O R   L A Y E R E D   C O M P O S I T E   E L E M E N T S   ( Q U A D 4 )
+   ELEMENT  FAILURE    PLY   FP=FAILURE INDEX FOR PLY    FB=FAILURE INDEX FOR BONDING   MAX OF FP,FB FOR ALL PLIES
+     ID      THEORY     ID  (DIRECT STRESSES/STRAINS)     (INTER-LAMINAR STRESSES)
+       101   HOFFMAN     1      0.2104   4.7529
+                                                              0.0312   32.051
+                         2      0.1870   5.3476
+                                                              0.0281   35.587
+                                                                                                0.2104   4.7529
+1    COMPOSITE TEST                                      SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+#[test]
+fn test_composite_failure_records() {
+  let file = OnePassParser::parse_bufread(COMPOSITE_FIXTURE.as_bytes())
+    .unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::CompositeFailure)
+    .collect::<Vec<_>>();
+  // two plies, two bonds and the maximum
+  assert_eq!(records.len(), 5);
+  for rec in records.iter() {
+    assert_eq!(rec.eid, Some(101));
+    assert_eq!(rec.fields[5], CsvField::String("HOFFMAN".to_owned()));
+  }
+  let layers = records.iter()
+    .map(|rec| rec.extras.get(&RowDimension::Ply).cloned().unwrap())
+    .collect::<Vec<_>>();
+  let expected = ["Ply 1", "Ply 2", "Bond 1", "Bond 2", "All"];
+  assert_eq!(
+    layers,
+    expected.map(|s| CsvField::String(s.to_owned())).to_vec()
+  );
+  assert_eq!(records[4].fields[3], CsvField::Blank);
+  assert_eq!(records[4].fields[6], CsvField::Real(0.2104));
+}
+
+/// A MYSTRAN-like table of the failure indices by criterion of a 2-ply
+/// laminate, with a side printed for the second ply.
+const PLY_FAILURE_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+                                                                                                                  OUTPUT FOR SUBCASE          1
+ 
+                F A I L U R E   I N D I C E S   I N   L A Y E R E D   C O M P O S I T E   E L E M E N T S   ( Q U A D 4 )
+ 
+   Element  Ply     Tsai-Wu      Tsai-Hill      Hoffman     Max Stress    Max Strain    Status
+     ID      ID
+       101    1  1.23456E-01  1.10000E-01  1.20000E-01  9.00000E-02  8.50000E-02    NO FAIL
+       101    2 Z2 1.05000E+00  9.80000E-01  1.02000E+00  1.10000E+00  9.70000E-01    FAIL
+ ------------------------------------------------------------------------------------------------------------------------------------------------------------
+";
+
+#[test]
+fn test_composite_failure_criterion_records() {
+  let file = OnePassParser::parse_bufread(PLY_FAILURE_FIXTURE.as_bytes())
+    .unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::CompositeFailure)
+    .collect::<Vec<_>>();
+  // one record per ply, without the ones by theory
+  assert_eq!(records.len(), 2);
+  let sides = ["Middle", "Top"];
+  let statuses = ["NO FAIL", "FAIL"];
+  for (ply, rec) in records.iter().enumerate() {
+    assert_eq!(rec.eid, Some(101));
+    assert_eq!(rec.headers[4], "TsaiWu");
+    assert_eq!(rec.fields[2], CsvField::Natural(ply + 1));
+    assert_eq!(rec.fields[3], CsvField::String(sides[ply].to_owned()));
+    assert_eq!(rec.fields[9], CsvField::String(statuses[ply].to_owned()));
+  }
+  assert_eq!(records[0].fields[4], CsvField::Real(0.123456));
+  assert_eq!(records[1].fields[8], CsvField::Real(0.97));
+}
+
+#[test]
+fn test_composite_stress_records() {
+  let fixture = MYSTRAN_PLY_STRESSES_FIXTURE.as_bytes();
+  let file = OnePassParser::parse_bufread(fixture).unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::CompositeStresses)
+    .collect::<Vec<_>>();
+  assert_eq!(records.len(), 4);
+  for (ply, rec) in records.iter().enumerate() {
+    assert_eq!(rec.eid, Some(101));
+    assert_eq!(rec.fields[2], CsvField::Natural(ply + 1));
+    assert_eq!(rec.fields[3], CsvField::String("Middle".to_owned()));
+    assert_eq!(
+      rec.extras.get(&RowDimension::Ply),
+      Some(&CsvField::String(format!("Ply {}", ply + 1)))
+    );
+  }
+  assert_eq!(records[0].headers[9], "VonMises");
+  assert_eq!(records[0].fields[4], CsvField::Real(-38.1691));
+  assert_eq!(records[1].fields[6], CsvField::Real(3.0));
+  assert_eq!(records[3].fields[8], CsvField::Real(-37.2111));
+  assert_eq!(records[3].fields[9], CsvField::Real(32.8634));
+  // without von Mises stresses, theirs is left blank
+  let without = ["3.49372E+01", "1.17898E+01", "1.04403E+01", "3.28634E+01"]
+    .iter()
+    .fold(MYSTRAN_PLY_STRESSES_FIXTURE.to_owned(), |f, vm| f.replace(vm, ""));
+  let file = OnePassParser::parse_bufread(without.as_bytes()).unwrap();
+  let first = to_records(&file, &all_converters())
+    .find(|rec| rec.block_id == CsvBlockId::CompositeStresses)
+    .unwrap();
+  assert_eq!(first.fields[4], CsvField::Real(-38.1691));
+  assert_eq!(first.fields[9], CsvField::Blank);
+  let aliases = CsvBlockId::CompositeStresses.aliases();
+  assert!(aliases.contains(&"ply_stresses"));
+}
+
+#[test]
+fn test_thermal_load_records() {
+  let file = OnePassParser::parse_bufread(THERMAL_LOAD_FIXTURE.as_bytes())
+    .unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id != CsvBlockId::Metadata)
+    .collect::<Vec<_>>();
+  let ids = records.iter().map(|rec| rec.block_id).collect::<Vec<_>>();
+  assert_eq!(ids, [
+    CsvBlockId::AppliedForces,
+    CsvBlockId::AppliedForces,
+    CsvBlockId::ThermalLoads,
+    CsvBlockId::ThermalLoads
+  ]);
+  assert_eq!(records[0].fields[4], CsvField::Real(-468.75));
+  assert_eq!(records[2].fields[2], CsvField::Real(125.0));
+  assert_eq!(records[2].gid, Some(1011));
+  assert_eq!(CsvBlockId::try_from(18), Ok(CsvBlockId::ThermalLoads));
+}
+
+/// A Simcenter-like table of element strain energies.
+const STRAIN_ENERGY_FIXTURE: &str = "
+                                                                                                                  SUBCASE 1
+                                           E L E M E N T   S T R A I N   E N E R G I E S
+ 
+                ELEMENT-TYPE = QUAD4               * TOTAL ENERGY OF ALL ELEMENTS IN PROBLEM     =   9.157987E-01
+                SUBCASE               1            * TOTAL ENERGY OF ALL ELEMENTS IN SET      -1 =   9.157987E-01
+0
+                                    ELEMENT-ID          STRAIN-ENERGY           PERCENT OF TOTAL    STRAIN-ENERGY-DENSITY
+                                             1          1.538959E-03                 0.1680              1.575894E-01
+                                             2          1.323719E-03                 0.1445              1.355489E-01
+";
+
+#[test]
+fn test_strain_energy_records() {
+  let file = OnePassParser::parse_bufread(STRAIN_ENERGY_FIXTURE.as_bytes())
+    .unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::StrainEnergy)
+    .collect::<Vec<_>>();
+  assert_eq!(records.len(), 2);
+  assert_eq!(records[0].eid, Some(1));
+  assert_eq!(records[1].eid, Some(2));
+  assert_eq!(records[0].fields[2], CsvField::ElementType(ElementType::Quad4));
+  assert_eq!(records[0].fields[3], CsvField::Real(1.538959e-3));
+  assert_eq!(records[1].fields[4], CsvField::Real(0.1445));
+  assert_eq!(records[0].fields[5], CsvField::Real(0.1575894));
+  assert_eq!(records[0].fields[6], CsvField::Real(9.157987e-1));
+  assert_eq!(records[0].fields[7], CsvField::Blank);
+  assert_eq!(records[0].headers[4], "PercentTotal");
+  assert_eq!(usize::from(CsvBlockId::StrainEnergy), 17);
+  assert_eq!(CsvBlockId::try_from(17), Ok(CsvBlockId::StrainEnergy));
+  assert!(CsvBlockId::StrainEnergy.aliases().contains(&"ese"));
+}
+
+/// A Simcenter-like table of the stresses at a grid point on surface 3.
+const GRID_STRESS_FIXTURE: &str = "
+                                                                                                                  SUBCASE 1
+              S T R E S S E S   A T   G R I D   P O I N T S   - -     S U R F A C E       3
+     GRID      ELEMENT            STRESSES IN SURFACE SYSTEM           PRINCIPAL STRESSES            MAX
+     ID          ID    FIBRE   NORMAL-X   NORMAL-Y    SHEAR-XY     ANGLE      MAJOR      MINOR      SHEAR     VON MISES
+0       12           0     Z1 -8.934E+03 -2.112E+03  1.250E+02   -88.95 -2.110E+03 -8.936E+03  3.413E+03  8.010E+03
+                           Z2  8.934E+03  2.112E+03 -1.250E+02   -1.05  8.936E+03  2.110E+03  3.413E+03  8.010E+03
+";
+
+#[test]
+fn test_grid_stress_records() {
+  let file = OnePassParser::parse_bufread(GRID_STRESS_FIXTURE.as_bytes())
+    .unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::Stresses)
+    .collect::<Vec<_>>();
+  assert_eq!(records.len(), 2);
+  for rec in records.iter() {
+    assert_eq!(rec.gid, Some(12));
+    assert_eq!(rec.eid, None);
+    assert_eq!(rec.fields[2], CsvField::Natural(3));
+    assert_eq!(rec.headers[2], "Surface");
+  }
+  let side = |s: &str| records.iter()
+    .find(|rec| {
+      rec.extras.get(&RowDimension::Side)
+        == Some(&CsvField::String(s.to_owned()))
+    })
+    .unwrap();
+  assert_eq!(side("Bottom").fields[3], CsvField::Real(-8934.0));
+  assert_eq!(side("Top").fields[7], CsvField::Real(2110.0));
+  assert_eq!(side("Top").fields[9], CsvField::Real(8010.0));
+}
+
+/// A MYSTRAN-like output with the stresses at the centroid and two corners of
+/// a CHEXA. The normal X stress at corner N is 10*N, and von Mises is 100+N.
+const CHEXA_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+
+ OUTPUT FOR SUBCASE        1
+
+                        E L E M E N T   S T R E S S E S   I N   L O C A L   E L E M E N T   C O O R D I N A T E   S Y S T E M
+                                                   F O R   E L E M E N T   T Y P E   H E X A 8
+  Element  Location      Sigma-xx      Sigma-yy      Sigma-zz       Tau-xy        Tau-yz        Tau-zx      von Mises
+     ID
+      101   CENTER   0.0           5.000000E+01 -2.500000E+01  1.000000E+01           0.0 -5.000000E+00  1.000000E+02
+              1001   1.000000E+01  5.000000E+01 -2.500000E+01  1.000000E+01           0.0 -5.000000E+00  1.010000E+02
+              1002   2.000000E+01  5.000000E+01 -2.500000E+01  1.000000E+01           0.0 -5.000000E+00  1.020000E+02
+";
+
+/// Splits the records of solid stresses or strains into the main ones and
+/// those with the principal values, which come right after each.
+fn solid_records(
+  file: &F06File,
+  bid: CsvBlockId
+) -> (Vec<CsvRecord>, Vec<CsvRecord>) {
+  let records = to_records(file, &all_converters())
+    .filter(|rec| rec.block_id == bid)
+    .collect::<Vec<_>>();
+  assert_eq!(records.len() % 2, 0);
+  return records.chunks(2)
+    .map(|pair| (pair[0].clone(), pair[1].clone()))
+    .unzip();
+}
+
+#[test]
+fn test_solid_stress_records() {
+  let file = OnePassParser::parse_bufread(CHEXA_FIXTURE.as_bytes()).unwrap();
+  let (records, principals) = solid_records(&file, CsvBlockId::Stresses);
+  assert_eq!(records.len(), 3);
+  assert_eq!(records[0].headers[0], "EID (HEXA)");
+  assert_eq!(records[0].headers[9], "VonMises");
+  // the centroid has no grid ID
+  let gids = records.iter()
+    .map(|rec| rec.fields[2].clone())
+    .collect::<Vec<_>>();
+  assert_eq!(gids, [0, 1001, 1002].map(CsvField::Natural).to_vec());
+  for (n, rec) in records.iter().enumerate() {
+    assert_eq!(rec.eid, Some(101));
+    assert_eq!(rec.fields[3], CsvField::Real(10.0 * n as f64));
+    assert_eq!(rec.fields[4], CsvField::Real(50.0));
+    assert_eq!(rec.fields[9], CsvField::Real(100.0 + n as f64));
+  }
+  // mystran prints no principal stresses
+  for (rec, main) in principals.iter().zip(records.iter()) {
+    assert_eq!(rec.headers[3], "PrincipalA");
+    assert_eq!(rec.fields[..3], main.fields[..3]);
+    assert_eq!(rec.fields[3..], vec![CsvField::Blank; 7]);
+  }
+  // where the octahedral shear is printed, von Mises is left blank
+  let mut file = file;
+  let vm = ChexaStressField::VonMises.into();
+  for block in file.blocks.values_mut().flatten() {
+    block.col_indexes.remove(&vm);
+  }
+  let (records, _) = solid_records(&file, CsvBlockId::Stresses);
+  assert_eq!(records.len(), 3);
+  for rec in records {
+    assert_eq!(rec.fields[8], CsvField::Real(-5.0));
+    assert_eq!(rec.fields[9], CsvField::Blank);
+  }
+}
+
+/// A Simcenter-like output with the stresses at the centroid of a CHEXA under
+/// a known state of stress: 30, -10 and 20 normal to X, Y and Z, and 15 of
+/// shear in XY. The principal stresses are 35, 20 and -15, the mean pressure
+/// is -40/3, and von Mises is the square root of 1975.
+const SIMCENTER_CHEXA_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    CHEXA TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                   S T R E S S E S   I N   H E X A H E D R O N   S O L I D   E L E M E N T S   ( H E X A )
+               CORNER        ------CENTER AND CORNER POINT STRESSES---------       DIR.  COSINES       MEAN
+  ELEMENT-ID    GRID-ID        NORMAL              SHEAR             PRINCIPAL       -A-  -B-  -C-     PRESSURE       VON MISES
+0       101           0GRID CS  8 GP
+0          CENTER  X   3.000000E+01  XY   1.500000E+01   A   3.500000E+01  LX 0.95 0.00-0.32  -1.333333E+01   4.444097E+01
+                          Y  -1.000000E+01  YZ            0.0   B   2.000000E+01  LY 0.32 0.00 0.95
+                          Z   2.000000E+01  ZX            0.0   C  -1.500000E+01  LZ 0.00 1.00 0.00
+1    CHEXA TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+#[test]
+fn test_solid_principal_records() {
+  let file = OnePassParser::parse_bufread(SIMCENTER_CHEXA_FIXTURE.as_bytes())
+    .unwrap();
+  let (records, principals) = solid_records(&file, CsvBlockId::Stresses);
+  assert_eq!((records.len(), principals.len()), (1, 1));
+  let real = |field: &CsvField| match field {
+    CsvField::Real(x) => *x,
+    _ => panic!("not a real: {:?}", field)
+  };
+  let stress = records[0].fields[3..9].iter().map(real).collect::<Vec<_>>();
+  assert_eq!(stress, [30.0, -10.0, 20.0, 15.0, 0.0, 0.0]);
+  assert_eq!(records[0].fields[9], CsvField::Real(44.44097));
+  let rec = &principals[0];
+  assert_eq!(rec.eid, Some(101));
+  assert_eq!(
+    rec.headers[..7],
+    [
+      "EID (HEXA)", "Subcase", "GID", "PrincipalA", "PrincipalB",
+      "PrincipalC", "MeanPressure"
+    ]
+  );
+  let principal = rec.fields[3..6].iter().map(real).collect::<Vec<_>>();
+  assert_eq!(principal, [35.0, 20.0, -15.0]);
+  // the invariants agree with the components
+  let trace = stress[..3].iter().sum::<f64>();
+  assert_eq!(principal.iter().sum::<f64>(), trace);
+  let pressure = real(&rec.fields[6]);
+  assert!((pressure + trace / 3.0).abs() < 1e-5);
+  assert_eq!(rec.fields[7..], vec![CsvField::Blank; 3]);
+}
+
+#[test]
+fn test_solid_strain_records() {
+  // the stresses, printed as strains
+  let text = CHEXA_FIXTURE
+    .replace("S T R E S S E S", "S T R A I N S")
+    .replace("Sigma", "Epsilon")
+    .replace("Tau", "Gamma");
+  let file = OnePassParser::parse_bufread(text.as_bytes()).unwrap();
+  let block = file.block_search(Some(BlockType::ChexaStrains), None, false)
+    .next()
+    .unwrap();
+  let (records, _) = solid_records(&file, CsvBlockId::Strains);
+  assert_eq!(records.len(), 3);
+  // every value makes it through, in the same columns as the stresses
+  let fields = ChexaStressField::all()
+    .iter()
+    .take(7)
+    .map(|f| ChexaStrainField(*f))
+    .collect::<Vec<_>>();
+  for (rec, row) in records.iter().zip(block.row_indexes.keys()) {
+    assert_eq!(rec.eid, Some(101));
+    assert_eq!(rec.headers[0], "EID (HEXA)");
+    for (n, field) in fields.iter().enumerate() {
+      let value = block.get(*row, *field).unwrap();
+      assert_eq!(rec.fields[3 + n], CsvField::from(value));
+    }
+  }
+  assert_eq!(records[2].fields[3], CsvField::Real(20.0));
+}
+
+/// A MYSTRAN-like output with the engineering forces of two CBARs.
+const MYSTRAN_BAR_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+
+ OUTPUT FOR SUBCASE        1
+
+                                                       E L E M E N T   E N G I N E E R I N G   F O R C E S
+                                                             F O R   E L E M E N T   T Y P E   B A R
+                 Element       Bend-Moment End A           Bend-Moment End B              - Shear -              Axial         Torque
+                    ID       Plane 1       Plane 2       Plane 1       Plane 2      Plane 1       Plane 2        Force
+                      11  1.000000E+01  2.000000E+01 -3.000000E+01  4.000000E+01  5.000000E+00 -6.000000E+00  7.000000E+02  8.000000E+00
+                      12 -1.500000E+02  0.000000E+00  2.500000E+02 -3.500000E+00  4.500000E+01  0.000000E+00 -5.500000E+02  6.500000E-01
+";
+
+/// The same results as `MYSTRAN_BAR_FIXTURE`, as Simcenter prints them.
+const SIMCENTER_BAR_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    BAR TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                 F O R C E S   I N   B A R   E L E M E N T S         ( C B A R )
+0    ELEMENT         BEND-MOMENT END-A            BEND-MOMENT END-B                - SHEAR -               AXIAL
+       ID.         PLANE 1       PLANE 2        PLANE 1       PLANE 2        PLANE 1       PLANE 2         FORCE         TORQUE
+           11     1.000000E+01  2.000000E+01  -3.000000E+01  4.000000E+01   5.000000E+00 -6.000000E+00   7.000000E+02   8.000000E+00
+           12    -1.500000E+02  0.0            2.500000E+02 -3.500000E+00   4.500000E+01  0.0           -5.500000E+02   6.500000E-01
+1    BAR TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+#[test]
+fn test_bar_forces_across_solvers() {
+  let mystran = canonical_csv(MYSTRAN_BAR_FIXTURE);
+  let simcenter = canonical_csv(SIMCENTER_BAR_FIXTURE);
+  assert_eq!(mystran, simcenter);
+  // both ends of both bars, under the same header
+  let lines = mystran.lines().collect::<Vec<_>>();
+  assert_eq!(lines.len(), 7);
+  assert!(lines[2].starts_with(
+    "EngForces,EID (BAR),Subcase,GID,<UNUSED>,Axial,S1,S2,M1,M2,Torque,"
+  ));
+  assert!(lines[3].starts_with(
+    "4,11,1,0,,+7.00000000E+02,+5.00000000E+00,-6.00000000E+00,"
+  ));
+  assert!(lines[3].ends_with(",End A,,,,"));
+  assert!(lines[4].contains(",-3.00000000E+01,+4.00000000E+01,"));
+  assert!(lines[4].ends_with(",End B,,,,"));
+}
+
+/// A Simcenter-like output with the engineering forces of a CBEAM, at its ends
+/// and at its middle.
+const BEAM_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    BEAM TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                 F O R C E S   I N   B E A M   E L E M E N T S        ( C B E A M )
+                    STAT DIST/   - BENDING MOMENTS -            - WEB  SHEARS -           AXIAL          TOTAL          WARPING
+   ELEMENT-ID  GRID   LENGTH    PLANE 1       PLANE 2        PLANE 1       PLANE 2        FORCE          TORQUE         TORQUE
+0        5
+0                1   0.000   1.000000E+02            0.0  -3.000000E+01   4.000000E+01   5.000000E+02   6.000000E+00   5.000000E-01
+0                0   0.500            0.0  -5.000000E+01  -3.000000E+01   4.000000E+01   5.000000E+02   6.000000E+00   5.000000E-01
+0                2   1.000  -1.000000E+02  -1.000000E+02  -3.000000E+01   4.000000E+01   5.000000E+02   6.000000E+00   5.000000E-01
+1    BEAM TEST                                           SIMCENTER NASTRAN  2/11/21   PAGE     2
+";
+
+/// A MYSTRAN-like output with the engineering forces of a CBEAM, at its ends.
+const MYSTRAN_BEAM_FIXTURE: &str = "
+ MYSTRAN Version 15.1.3
+
+ OUTPUT FOR SUBCASE        1
+
+                                                       E L E M E N T   E N G I N E E R I N G   F O R C E S
+                                                             F O R   E L E M E N T   T Y P E   B E A M
+                 Element       Bend-Moment End A           Bend-Moment End B              - Shear -              Axial         Torque
+                    ID       Plane 1       Plane 2       Plane 1       Plane 2      Plane 1       Plane 2        Force
+                       7  1.000000E+01  2.000000E+01 -3.000000E+01  4.000000E+01  5.000000E+00 -6.000000E+00  7.000000E+02  8.000000E+00
+";
+
+#[test]
+fn test_beam_force_records() {
+  let file = OnePassParser::parse_bufread(BEAM_FIXTURE.as_bytes()).unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::EngForces)
+    .collect::<Vec<_>>();
+  assert_eq!(records.len(), 3);
+  let stations = records.iter()
+    .map(|rec| rec.extras.get(&RowDimension::Station).cloned().unwrap())
+    .collect::<Vec<_>>();
+  assert_eq!(stations, [0.0, 0.5, 1.0].map(CsvField::Real).to_vec());
+  for rec in records.iter() {
+    assert_eq!(rec.eid, Some(5));
+    assert_eq!(rec.etype, Some(ElementType::Beam));
+    assert_eq!(rec.headers[0], "EID (BEAM)");
+    assert_eq!(rec.fields[4], CsvField::Real(500.0));
+    assert_eq!(rec.fields[9], CsvField::Real(6.0));
+    assert_eq!(rec.headers[3], "Warping");
+    assert_eq!(rec.fields[3], CsvField::Real(0.5));
+  }
+  assert_eq!(records[1].fields[8], CsvField::Real(-50.0));
+  // mystran doesn't print the warping torque, so it's left blank
+  let file = OnePassParser::parse_bufread(MYSTRAN_BEAM_FIXTURE.as_bytes())
+    .unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::EngForces)
+    .collect::<Vec<_>>();
+  assert_eq!(records.len(), 2);
+  for rec in records.iter() {
+    assert_eq!(rec.fields[3], CsvField::Blank);
+    assert_eq!(rec.fields[9], CsvField::Real(8.0));
+  }
+}
+
+/// A Simcenter-like output with the forces and the stresses of one shear
+/// panel.
+const CSHEAR_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    CSHEAR TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                   F O R C E S   I N   S H E A R   P A N E L S      ( C S H E A R )
+  ELEMENT        ====== POINT  1 ======      ====== POINT  2 ======      ====== POINT  3 ======      ====== POINT  4 ======
+     ID          F-FROM-4      F-FROM-2      F-FROM-1      F-FROM-3      F-FROM-2      F-FROM-4      F-FROM-3      F-FROM-1
+                  KICK-1       SHEAR-12       KICK-2       SHEAR-23       KICK-3       SHEAR-34       KICK-4       SHEAR-41
+0       501   1.000000E+00  2.000000E+00  3.000000E+00  4.000000E+00  5.000000E+00  6.000000E+00  7.000000E+00  8.000000E+00
+              1.100000E+01  1.200000E+01  1.300000E+01  1.400000E+01  1.500000E+01  1.600000E+01  1.700000E+01  1.800000E+01
+1    CSHEAR TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                                   S T R E S S E S   I N   S H E A R   P A N E L S      ( C S H E A R )
+      ELEMENT            MAX            AVG        SAFETY         ELEMENT            MAX            AVG        SAFETY
+        ID.             SHEAR          SHEAR       MARGIN           ID.             SHEAR          SHEAR       MARGIN
+0       501         1.200000E+02  -1.100000E+02      N/A
+1    CSHEAR TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     3
+";
+
+#[test]
+fn test_cshear_records() {
+  let file = OnePassParser::parse_bufread(CSHEAR_FIXTURE.as_bytes()).unwrap();
+  let records = to_records(&file, &all_converters()).collect::<Vec<_>>();
+  let forces = records.iter()
+    .filter(|rec| rec.block_id == CsvBlockId::EngForces)
+    .collect::<Vec<_>>();
+  assert_eq!(forces.len(), 2);
+  assert_eq!(forces[0].headers[4], "Kick1");
+  assert_eq!(forces[0].fields[4], CsvField::Real(11.0));
+  assert_eq!(forces[0].fields[7], CsvField::Real(17.0));
+  assert_eq!(forces[1].headers[4], "Q12");
+  assert_eq!(forces[1].fields[4], CsvField::Real(12.0));
+  assert_eq!(forces[1].fields[7], CsvField::Real(18.0));
+  let stresses = records.iter()
+    .filter(|rec| rec.block_id == CsvBlockId::Stresses)
+    .collect::<Vec<_>>();
+  assert_eq!(stresses.len(), 1);
+  assert_eq!(stresses[0].eid, Some(501));
+  assert_eq!(stresses[0].headers[0], "EID (SHEAR)");
+  assert_eq!(stresses[0].fields[4], CsvField::Real(120.0));
+  assert_eq!(stresses[0].fields[5], CsvField::Real(-110.0));
+  assert_eq!(stresses[0].fields[7], CsvField::Blank);
+}
+
+/// A Simcenter-like table of gap forces, with a gap that sticks, an open one
+/// and one that slides.
+const CGAP_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    GAP TEST                                            SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                     F O R C E S   I N   G A P   E L E M E N T S   ( C G A P )
+  ELEMENT   - F O R C E S  I N  E L E M  S Y S T -     - D I S P L A C E M E N T S  I N  E L E M  S Y S T -
+    ID       COMP-X       SHEAR-Y      SHEAR-Z       AXIAL-U      TOTAL-V      TOTAL-W      SLIP-V       SLIP-W     STATUS
+        7   1.500000E+02  2.000000E+00 -3.000000E+00 -1.000000E-03  0.0          0.0          0.0          0.0         STICK
+        8   0.0           0.0          0.0           2.000000E-03  0.0          0.0          0.0          0.0         OPEN
+        9   2.000000E+02  5.000000E+01  0.0          -2.000000E-03  4.000000E-03  0.0          3.000000E-03  0.0         SLIDE
+";
+
+#[test]
+fn test_cgap_records() {
+  let file = OnePassParser::parse_bufread(CGAP_FIXTURE.as_bytes()).unwrap();
+  let records = to_records(&file, &all_converters()).collect::<Vec<_>>();
+  let forces = records.iter()
+    .filter(|rec| rec.block_id == CsvBlockId::EngForces)
+    .collect::<Vec<_>>();
+  // the forces, then the displacements, of each element
+  assert_eq!(forces.len(), 6);
+  assert_eq!(forces[0].headers[2], "Status");
+  assert_eq!(forces[1].headers[5], "TotalV");
+  let statuses = forces.iter()
+    .step_by(2)
+    .map(|rec| rec.fields[2].clone())
+    .collect::<Vec<_>>();
+  let expected = ["CLOSED", "OPEN", "SLIDE"].map(|s| s.to_owned().into());
+  assert_eq!(statuses, expected);
+  assert_eq!(forces[0].fields[4], CsvField::Real(150.0));
+  assert_eq!(forces[3].fields[4], CsvField::Real(0.002));
+  assert_eq!(forces[5].fields[5], CsvField::Real(0.004));
+  assert_eq!(forces[5].fields[7], CsvField::Real(0.003));
+}
+
+/// Simcenter-like forces in a scalar and a viscous damper.
+const DAMPER_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    DAMPER TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                              F O R C E S   I N   S C A L A R   D A M P E R S        ( C D A M P 2 )
+      ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE            ELEMENT         FORCE
+        ID.                              ID.                              ID.                              ID.
+           11      1.000000E+01
+1    DAMPER TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                                 F O R C E S   I N   V I S C   E L E M E N T S   ( C V I S C )
+                 ELEMENT           AXIAL                                       ELEMENT           AXIAL
+                   ID.             FORCE           TORQUE                        ID.             FORCE           TORQUE
+                    21         1.500000E+01     -2.000000E-01
+";
+
+#[test]
+fn test_damper_records() {
+  let file = OnePassParser::parse_bufread(DAMPER_FIXTURE.as_bytes()).unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::EngForces)
+    .collect::<Vec<_>>();
+  assert_eq!(records.len(), 2);
+  // the damper force goes in the first value column either way
+  let by_eid = |eid| records.iter().find(|r| r.eid == Some(eid)).unwrap();
+  let cdamp = by_eid(11);
+  assert_eq!(cdamp.headers[0], "EID (CDAMP)");
+  assert_eq!(cdamp.fields[4], CsvField::Real(10.0));
+  let cvisc = by_eid(21);
+  assert_eq!(cvisc.headers[0], "EID (CVISC)");
+  assert_eq!(cvisc.fields[4], CsvField::Real(15.0));
+  assert_eq!(cvisc.fields[9], CsvField::Real(-0.2));
+}
+
+/// Simcenter-like summaries of maximum displacements, with the grid points
+/// where they occur, and of maximum SPC forces, without them.
+const MAX_SUMMARY_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    MAXIMA TEST                                         SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+0                                                  MAXIMUM  DISPLACEMENTS
+  SUBCASE/
+  DAREA ID        T1             T2             T3             R1             R2             R3
+0        1   3.0000000E-03  1.0000000E-03  2.5000000E-02  2.0000000E-04  4.0000000E-04  0.0
+                      102            102            101            102            101              0
+0                                                  MAXIMUM  SPCFORCES
+  SUBCASE/
+  DAREA ID        T1             T2             T3             R1             R2             R3
+0        1   1.2000000E+01  0.0            5.0000000E+02  0.0            7.5000000E+01  0.0
+";
+
+#[test]
+fn test_max_summary_records() {
+  let file = OnePassParser::parse_bufread(MAX_SUMMARY_FIXTURE.as_bytes())
+    .unwrap();
+  let records = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::MaxSummaries)
+    .collect::<Vec<_>>();
+  assert_eq!(records.len(), 3);
+  // the maximums, then where they occur, if printed, for each summary
+  let quantities = records.iter()
+    .map(|rec| (rec.fields[2].to_string(), rec.fields[3].to_string()))
+    .collect::<Vec<_>>();
+  let pair = |s: &str, q: &str| (s.to_owned(), q.to_owned());
+  assert_eq!(quantities, [
+    pair("Displacements", "Max"),
+    pair("Displacements", "GID"),
+    pair("SpcForces", "Max"),
+  ]);
+  assert_eq!(records[0].fields[0], CsvField::Natural(1));
+  assert_eq!(records[0].fields[6], CsvField::Real(2.5e-2));
+  assert_eq!(records[1].fields[4], CsvField::Natural(102));
+  assert_eq!(records[1].fields[6], CsvField::Natural(101));
+  assert_eq!(records[1].fields[9], CsvField::Natural(0));
+  assert_eq!(records[2].fields[8], CsvField::Real(75.0));
+  assert_eq!(records[0].headers[3], "Quantity");
+  assert_eq!(CsvBlockId::try_from(19), Ok(CsvBlockId::MaxSummaries));
+  assert!(CsvBlockId::MaxSummaries.aliases().contains(&"maximums"));
+  // the columns are named after what they summarise
+  let bt = BlockType::MaxDisplacements;
+  let names = column_names(bt, MaxSummaryField::Tz.into()).unwrap();
+  assert_eq!(names.machine, "max_tz");
+  let names = column_names(bt, MaxSummaryField::GridTz.into()).unwrap();
+  assert_eq!(names.machine, "max_tz_gid");
+}
+
+/// A Simcenter-like output with displacements, applied forces and SPC forces
+/// in two subcases.
+const SPLIT_FIXTURE: &str = "
+                      Welcome to Simcenter Nastran
+1    SPLIT TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     1
+0                                                                                SUBCASE 1
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G     1.000000E+00   0.0            0.0            0.0            0.0            0.0
+             2      G     2.000000E+00   0.0            0.0            0.0            0.0            0.0
+             3      G     3.000000E+00   0.0            0.0            0.0            0.0            0.0
+1    SPLIT TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     2
+0                                                                                SUBCASE 1
+ 
+                                                       L O A D   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             3      G     1.000000E+02   0.0            0.0            0.0            0.0            0.0
+1    SPLIT TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     3
+0                                                                                SUBCASE 1
+ 
+                               F O R C E S   O F   S I N G L E - P O I N T   C O N S T R A I N T
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G     -5.000000E+01  0.0            0.0            0.0            0.0            0.0
+             2      G     -5.000000E+01  0.0            0.0            0.0            0.0            0.0
+1    SPLIT TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     4
+0                                                                                SUBCASE 2
+ 
+                                             D I S P L A C E M E N T   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G     1.000000E+00   0.0            0.0            0.0            0.0            0.0
+             2      G     2.000000E+00   0.0            0.0            0.0            0.0            0.0
+             3      G     3.000000E+00   0.0            0.0            0.0            0.0            0.0
+1    SPLIT TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     5
+0                                                                                SUBCASE 2
+ 
+                                                       L O A D   V E C T O R
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             3      G     1.000000E+02   0.0            0.0            0.0            0.0            0.0
+1    SPLIT TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     6
+0                                                                                SUBCASE 2
+ 
+                               F O R C E S   O F   S I N G L E - P O I N T   C O N S T R A I N T
+ 
+      POINT ID.   TYPE          T1             T2             T3             R1             R2             R3
+             1      G     -5.000000E+01  0.0            0.0            0.0            0.0            0.0
+             2      G     -5.000000E+01  0.0            0.0            0.0            0.0            0.0
+1    SPLIT TEST                                          SIMCENTER NASTRAN  2/11/21   PAGE     7
+";
+
+#[test]
+fn test_split_by_type_and_subcase() {
+  let file = OnePassParser::parse_bufread(SPLIT_FIXTURE.as_bytes()).unwrap();
+  let converters = all_converters();
+  // the cross product, plus the 0-block on its own
+  let parts = split_records(to_records(&file, &converters), true, true);
+  let names = parts.keys()
+    .map(|k| k.file_name("out", "csv"))
+    .collect::<Vec<_>>();
+  assert_eq!(names, [
+    "out_meta.csv",
+    "out_disp_sc1.csv", "out_disp_sc2.csv",
+    "out_load_sc1.csv", "out_load_sc2.csv",
+    "out_spcfor_sc1.csv", "out_spcfor_sc2.csv"
+  ]);
+  let rows = parts.values().skip(1).map(Vec::len).collect::<Vec<_>>();
+  assert_eq!(rows, [3, 3, 1, 1, 2, 2]);
+  // each type gets its own columns
+  let natural = |bid: CsvBlockId| {
+    let key = SplitKey { block_id: Some(bid), subcase: Some(2) };
+    let part = parts.get(&key).unwrap();
+    let sel = ColumnSelection::natural(part.iter().map(|r| r.headers), vec![]);
+    let header = sel.header(&part[0]).join(",");
+    let fields = sel.fields(part[0].clone());
+    assert_eq!(fields.len(), header.split(',').count());
+    return (header, fields);
+  };
+  let (header, fields) = natural(CsvBlockId::Displacements);
+  assert_eq!(header, "GID,Subcase,Tx,Ty,Tz,Rx,Ry,Rz,Coord");
+  assert_eq!(fields[0], CsvField::Natural(1));
+  assert_eq!(fields[1], CsvField::Natural(2));
+  let (header, _) = natural(CsvBlockId::SpcForces);
+  assert_eq!(header, "GID,Subcase,Fx,Fy,Fz,Mx,My,Mz");
+  let meta = parts.values().next().unwrap();
+  let sel = ColumnSelection::natural(meta.iter().map(|r| r.headers), vec![]);
+  assert_eq!(sel.header(&meta[0]), ["Key", "Value"]);
+  // by type alone, subcases go together
+  let parts = split_records(to_records(&file, &converters), true, false);
+  let rows = parts.values().skip(1).map(Vec::len).collect::<Vec<_>>();
+  assert_eq!(rows, [6, 2, 4]);
+  // the full layout is still the fixed one
+  let full = ColumnSelection::full(vec![RowDimension::Side]);
+  let rec = parts.values().nth(1).unwrap()[0].clone();
+  assert_eq!(full.header(&rec).len(), NAS_CSV_COLS + 1);
+  assert_eq!(
+    full.fields(rec.clone()),
+    rec.to_fields_with(&[RowDimension::Side]).collect::<Vec<_>>()
+  );
+}
+
+#[test]
+fn test_column_names() {
+  // every column of every block type has names, unique machine ones
+  for bt in BlockType::all() {
+    let mut machine = BTreeSet::new();
+    for col in bt.columns() {
+      let names = column_names(*bt, col)
+        .unwrap_or_else(|| panic!("no names for {} in {}", col, bt));
+      assert!(
+        names.machine.chars()
+          .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+        "bad machine name {} for {} in {}", names.machine, col, bt
+      );
+      assert!(!names.short.is_empty() && !names.long.is_empty());
+      assert!(
+        machine.insert(names.machine.clone()),
+        "duplicate machine name {} in {}", names.machine, bt
+      );
+    }
+  }
+  assert!(!BlockType::BarStresses.columns().is_empty());
+  // units go into the long names
+  let units: UnitMap = "stress=MPa, moment=N.mm".parse().unwrap();
+  let col = NasIndex::from(PlateStressField::NormalX);
+  let names = column_names(BlockType::QuadStresses, col).unwrap();
+  assert_eq!(names.long_with(&units), "Normal stress X [MPa]");
+  let names = column_names(BlockType::QuadStrains, PlateStrainField(
+    PlateStressField::NormalX
+  ).into()).unwrap();
+  assert_eq!(names.machine, "normal_x");
+  assert_eq!(names.long_with(&units), "Normal strain X [strain]");
+  // the units follow the dimension of the column
+  let col = NasIndex::from(CshearForceField::Shear12);
+  let names = column_names(BlockType::CshearForces, col).unwrap();
+  assert_eq!(names.long, "Shear flow along edge 12 [{force}/{length}]");
+  let names = column_names(BlockType::BushForces, DOF_RX.into()).unwrap();
+  assert_eq!(names.long, "Moment X [{moment}]");
+  let col = NasIndex::from(RodStressField::AxialSafetyMargin);
+  let names = column_names(BlockType::RodStresses, col).unwrap();
+  assert_eq!(names.long, "Axial margin of safety");
+  assert!("stress".parse::<UnitMap>().is_err());
+  assert!("speed=m/s".parse::<UnitMap>().is_err());
+  // records get them without the bar end, so both ends share a header
+  let file = parse_keeping(&[BlockType::BarForces]);
+  let recs = to_records(&file, &all_converters())
+    .filter(|rec| rec.block_id == CsvBlockId::EngForces)
+    .collect::<Vec<_>>();
+  let dims = vec![RowDimension::End];
+  let sel = ColumnSelection::natural(recs.iter().map(|r| r.headers), dims);
+  let style = HeaderStyle::Machine;
+  let headers = recs.iter()
+    .map(|rec| sel.styled_header(rec, style, &units).join(","))
+    .collect::<BTreeSet<_>>();
+  assert_eq!(
+    headers.into_iter().collect::<Vec<_>>(),
+    ["eid,subcase,gid,axial,s1,s2,m1,m2,torque,end"]
+  );
+  let long = sel.styled_header(&recs[0], HeaderStyle::Long, &units);
+  assert_eq!(long[0], "EID (BAR)");
+  assert_eq!(long[6], "Bending moment, plane 1 [N.mm]");
+  assert_eq!(long[3], "Axial force [force]");
+}
+
+#[test]
+fn test_schema_row() {
+  let keep = [BlockType::Displacements, BlockType::QuadStresses];
+  let file = parse_keeping(&keep);
+  let dims = extra_dimensions(file.block_types(), false);
+  assert_eq!(dims, vec![RowDimension::Side]);
+  // written out like f06csv does, with headers
+  let schema = CsvSchemaDescriptor::new(true, None, &dims);
+  let selection = ColumnSelection::full(dims.clone());
+  let mut rows = vec![schema.to_fields()];
+  for rec in to_records(&file, &all_converters()) {
+    let header = selection.header(&rec).into_iter().map(str::to_owned);
+    rows.push(header.collect());
+    let fields = selection.fields(rec).into_iter();
+    rows.push(fields.map(|f| CANONICAL_FORMATTING.to_string(f)).collect());
+  }
+  let version = env!("CARGO_PKG_VERSION");
+  assert_eq!(
+    rows[0][..5],
+    ["#schema", "nas-csv-v1", version, "default", "Side"]
+  );
+  assert_eq!(CsvSchemaDescriptor::validate(&rows), Ok(schema.clone()));
+  // a schema that disagrees with the columns
+  let narrow = CsvSchemaDescriptor::new(true, None, &[]);
+  rows[0] = narrow.to_fields();
+  assert_eq!(
+    CsvSchemaDescriptor::validate(&rows),
+    Err(CsvSchemaError::WrongWidth { row: 2, expected: 11, found: 12 })
+  );
+  // or says there are no headers
+  let headless = CsvSchemaDescriptor::new(false, None, &dims);
+  rows[0] = headless.to_fields();
+  let res = CsvSchemaDescriptor::validate(&rows);
+  assert!(matches!(res, Err(CsvSchemaError::BadBlockId { row: 2, .. })));
+  // and no schema row at all
+  let res = CsvSchemaDescriptor::validate(&rows[1..]);
+  assert_eq!(res, Err(CsvSchemaError::Missing));
+}
+
+#[test]
+fn test_provenance_columns() {
+  let example = read_example();
+  let file = OnePassParser::parse_bufread(example.as_bytes()).unwrap();
+  let lines = example.lines().collect::<Vec<_>>();
+  let converters = all_converters();
+  let selection = ColumnSelection::full(vec![]).with_provenance(true);
+  let mut checked = 0;
+  for block in file.all_blocks(false) {
+    let conv = match converters.get(&block.block_type) {
+      Some(conv) => conv,
+      None => continue
+    };
+    let recs = conv.convert_block(block, &file.flavour)
+      .unwrap()
+      .collect::<Vec<_>>();
+    // each row gets a record per generator, in order
+    let per_row = recs.chunks(conv.generators.len());
+    for (row, recs) in block.row_indexes.keys().zip(per_row).step_by(5) {
+      let line = block.source_line(*row).unwrap();
+      let fields = selection.fields(recs[0].clone());
+      assert_eq!(fields.len(), NAS_CSV_COLS + PROVENANCE_HEADERS.len());
+      assert_eq!(fields[NAS_CSV_COLS], CsvField::Natural(line));
+      assert_eq!(
+        fields[NAS_CSV_COLS+1],
+        CsvField::Natural(block.uid.unwrap())
+      );
+      let start = block.line_range.unwrap().0;
+      assert_eq!(fields[NAS_CSV_COLS+2], CsvField::Natural(start));
+      assert!(start <= line && line < block.line_range.unwrap().1);
+      // and the line really is the row's
+      if let NasIndex::GridPointRef(gp) = row {
+        let gid = gp.gid.to_string();
+        assert!(lines[line-1].split_whitespace().any(|t| t == gid));
+      }
+      checked += 1;
+    }
+  }
+  assert!(checked > 20);
+  // the 0-block has none
+  let meta = to_records(&file, &converters).next().unwrap();
+  let fields = selection.fields(meta);
+  assert!(fields[NAS_CSV_COLS..].iter().all(|f| *f == CsvField::Blank));
+  let rec = to_records(&file, &converters).nth(1).unwrap();
+  let header = selection.header(&rec);
+  assert_eq!(header[NAS_CSV_COLS..], PROVENANCE_HEADERS);
+  // derived records are marked as such
+  let derived = Provenance {
+    source_line: SourceLine::Derived,
+    block_uid: None,
+    block_start: None
+  };
+  let fields = derived.to_fields();
+  assert_eq!(fields[0], CsvField::String(DERIVED_MARKER.to_owned()));
+  // and the schema row says they're there
+  let schema = CsvSchemaDescriptor::new(true, None, &[]).with_provenance(true);
+  assert_eq!(schema.width(), NAS_CSV_COLS + PROVENANCE_HEADERS.len());
+  assert_eq!(CsvSchemaDescriptor::from_fields(&schema.to_fields()), Ok(schema));
+}
+
+/// The expected HTML report for the example file. Rewritten by running the
+/// test with `UPDATE_GOLDEN` set.
+const ALL_ELEM_REPORT_HTML: &str = include_str!(
+  "SB-ALL-ELEM-TEST.report.html"
+);
+
+/// Fixed details for reports, so they render the same every time.
+fn fixed_report_meta() -> ReportMeta {
+  return ReportMeta {
+    tool: "f06csv".to_owned(),
+    version: "0.0.0".to_owned(),
+    generated: Some("2000-01-01 00:00:00 UTC".to_owned())
+  };
+}
+
+#[test]
+fn test_run_report_html() {
+  let mut file = OnePassParser::parse_bufread(read_example().as_bytes())
+    .unwrap();
+  file.filename = Some("SB-ALL-ELEM-TEST.F06".to_owned());
+  let report = RunReport::from_file(&file);
+  let html = report.to_html(&fixed_report_meta());
+  if std::env::var_os("UPDATE_GOLDEN").is_some() {
+    let path = concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/src/SB-ALL-ELEM-TEST.report.html"
+    );
+    std::fs::write(path, &html).unwrap();
+  }
+  assert_eq!(html, ALL_ELEM_REPORT_HTML);
+}
+
+#[test]
+fn test_report_timestamps() {
+  use crate::report::utc_timestamp;
+  assert_eq!(utc_timestamp(0), "1970-01-01 00:00:00 UTC");
+  assert_eq!(utc_timestamp(951_827_696), "2000-02-29 12:34:56 UTC");
+  assert_eq!(utc_timestamp(1_791_072_000), "2026-10-04 00:00:00 UTC");
+}
+
+#[test]
+fn test_api_snapshot() {
+  let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+  let lines = api_surface("nas_csv", &root.join("src")).unwrap()
+    .iter()
+    .map(ToString::to_string)
+    .collect::<Vec<_>>();
+  if let Some(message) = check_api_snapshot(&root.join("api.txt"), &lines) {
+    panic!("{}", message);
+  }
+}